@@ -0,0 +1,183 @@
+//! Animating values from one state to another over time.
+//!
+//! # Example
+//! ```
+//! use agape::animation::{Easing, Transition};
+//! use std::time::{Duration, Instant};
+//!
+//! let start = Instant::now();
+//! let transition = Transition::new(0.0_f32, 100.0, Duration::from_millis(100), start)
+//!     .easing(Easing::Linear);
+//!
+//! assert_eq!(transition.value_at(start + Duration::from_millis(25)), 25.0);
+//! assert_eq!(transition.value_at(start + Duration::from_secs(1)), 100.0);
+//! ```
+use agape_core::{Color, Position, Rgba};
+use std::time::{Duration, Instant};
+
+/// Values that can be interpolated between.
+pub trait Lerp: Clone {
+    /// Get the value a fraction `t` of the way from `self` to `to`, where
+    /// `t` is between `0.0` and `1.0`.
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Position {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Position::new(self.x.lerp(&to.x, t), self.y.lerp(&to.y, t))
+    }
+}
+
+impl Lerp for Color<Rgba> {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self.mix(to, t)
+    }
+}
+
+/// Values that are only set on one side snap to the other side straight
+/// away.
+impl<T: Lerp> Lerp for Option<T> {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        match (self, to) {
+            (Some(from), Some(to)) => Some(from.lerp(to, t)),
+            _ => to.clone(),
+        }
+    }
+}
+
+/// How the speed of a [`Transition`] changes over its duration.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    /// Starts fast and slows down at the end, which feels the most
+    /// responsive for changes caused by input.
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map the linear `progress` of a transition, from `0.0` to `1.0`, to
+    /// the eased progress.
+    pub fn apply(&self, progress: f32) -> f32 {
+        let t = progress.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => match t < 0.5 {
+                true => 4.0 * t * t * t,
+                false => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            },
+        }
+    }
+}
+
+/// A value that moves from one value to another over a duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition<T> {
+    from: T,
+    to: T,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T: Lerp> Transition<T> {
+    /// Create a transition that starts at the time `start`.
+    pub fn new(from: T, to: T, duration: Duration, start: Instant) -> Self {
+        Self {
+            from,
+            to,
+            start,
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Set the [`Easing`] of the transition.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Get the value that the transition ends at.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Get how far along the transition is at the time `now`, from `0.0`
+    /// to `1.0`.
+    pub fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.start);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Returns `true` if the transition has reached its target at the time
+    /// `now`.
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+
+    /// Get the value of the transition at the time `now`.
+    pub fn value_at(&self, now: Instant) -> T {
+        let progress = self.progress(now);
+        if progress >= 1.0 {
+            return self.to.clone();
+        }
+        self.from.lerp(&self.to, self.easing.apply(progress))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn easing_starts_and_ends_in_place() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+    }
+
+    #[test]
+    fn transition_colors() {
+        let start = Instant::now();
+        let transition = Transition::new(
+            Color::BLACK,
+            Color::WHITE,
+            Duration::from_millis(100),
+            start,
+        )
+        .easing(Easing::Linear);
+        let halfway = transition.value_at(start + Duration::from_millis(50));
+        assert_eq!(halfway, Color::rgb(128, 128, 128));
+        assert!(!transition.is_finished(start + Duration::from_millis(50)));
+        assert!(transition.is_finished(start + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn zero_duration_snaps() {
+        let start = Instant::now();
+        let transition = Transition::new(0.0, 10.0, Duration::ZERO, start);
+        assert_eq!(transition.value_at(start), 10.0);
+    }
+}
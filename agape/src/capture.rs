@@ -0,0 +1,100 @@
+//! Capture the frames drawn by the app as images, for saving screenshots
+//! and comparing against golden images in tests.
+//!
+//! Running apps capture frames through the [`FrameCapture`] resource, the
+//! image is handed over once the next frame has been drawn.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//! use agape::capture::FrameCapture;
+//! use agape::resources::Resources;
+//!
+//! let mut saved = false;
+//! let app = App::new(hstack! {}).add_system(move |resources: &mut Resources| {
+//!     if saved {
+//!         return;
+//!     }
+//!     saved = true;
+//!     let capture = resources.get_mut::<FrameCapture>().unwrap();
+//!     capture.request(|image| {
+//!         image.save("screenshot.png").unwrap();
+//!     });
+//! });
+//! ```
+use image::RgbaImage;
+use tiny_skia::Pixmap;
+
+type CaptureFn = Box<dyn FnOnce(RgbaImage)>;
+
+/// Requests for a copy of the next frame.
+#[derive(Default)]
+pub struct FrameCapture {
+    requests: Vec<CaptureFn>,
+}
+
+impl FrameCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture the next frame, `f` is called with the image once the frame
+    /// is drawn.
+    pub fn request(&mut self, f: impl FnOnce(RgbaImage) + 'static) {
+        self.requests.push(Box::new(f));
+    }
+
+    /// Returns `true` if a capture is waiting for the next frame.
+    pub fn is_pending(&self) -> bool {
+        !self.requests.is_empty()
+    }
+
+    /// Hand a frame to all the pending requests.
+    pub(crate) fn capture(&mut self, pixmap: &Pixmap) {
+        if self.requests.is_empty() {
+            return;
+        }
+        let image = to_image(pixmap);
+        for request in self.requests.drain(..) {
+            request(image.clone());
+        }
+    }
+}
+
+/// Copy a premultiplied pixmap into an image.
+pub(crate) fn to_image(pixmap: &Pixmap) -> RgbaImage {
+    let data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(pixmap.width(), pixmap.height(), data).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn capture_next_frame() {
+        let mut pixmap = Pixmap::new(4, 2).unwrap();
+        pixmap.fill(tiny_skia::Color::from_rgba8(255, 0, 0, 128));
+
+        let captured = Rc::new(RefCell::new(None));
+        let mut capture = FrameCapture::new();
+        let target = Rc::clone(&captured);
+        capture.request(move |image| *target.borrow_mut() = Some(image));
+        assert!(capture.is_pending());
+
+        capture.capture(&pixmap);
+        assert!(!capture.is_pending());
+        let image = captured.borrow_mut().take().unwrap();
+        assert_eq!(image.dimensions(), (4, 2));
+        assert_eq!(image.get_pixel(3, 1).0, [255, 0, 0, 128]);
+    }
+}
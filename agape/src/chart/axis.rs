@@ -0,0 +1,181 @@
+use std::fmt;
+use std::rc::Rc;
+
+type Formatter = Rc<dyn Fn(f32) -> String>;
+
+/// An axis of a chart, it maps a range of values onto the screen and
+/// chooses the ticks and their labels.
+///
+/// # Example
+/// ```
+/// use agape::chart::Axis;
+///
+/// let axis = Axis::new(0.0, 100.0).tick_count(5);
+/// assert_eq!(axis.ticks(), vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+/// ```
+#[derive(Clone)]
+pub struct Axis {
+    pub range: [f32; 2],
+    pub tick_count: usize,
+    pub label: Option<String>,
+    formatter: Option<Formatter>,
+}
+
+impl fmt::Debug for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Axis")
+            .field("range", &self.range)
+            .field("tick_count", &self.tick_count)
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Axis {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            range: [min, max],
+            tick_count: 5,
+            label: None,
+            formatter: None,
+        }
+    }
+
+    /// Set the preferred number of ticks, the actual number may differ
+    /// slightly so that the ticks land on round numbers.
+    pub fn tick_count(mut self, count: usize) -> Self {
+        self.tick_count = count;
+        self
+    }
+
+    /// Set the title of the axis.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
+    /// Set how tick values are turned into labels.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::chart::Axis;
+    ///
+    /// let axis = Axis::new(0.0, 1.0).formatter(|value| format!("{}%", value * 100.0));
+    /// assert_eq!(axis.format(0.5), "50%");
+    /// ```
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'static) -> Self {
+        self.formatter = Some(Rc::new(formatter));
+        self
+    }
+
+    /// Get the distance between ticks.
+    pub fn tick_step(&self) -> f32 {
+        let [min, max] = self.range;
+        let span = (max - min).abs();
+        if span == 0.0 || !span.is_finite() {
+            return 1.0;
+        }
+
+        let rough = span / self.tick_count.max(1) as f32;
+        let magnitude = 10f32.powf(rough.log10().floor());
+        let nice = match rough / magnitude {
+            n if n < 1.5 => 1.0,
+            n if n < 3.0 => 2.0,
+            n if n < 7.0 => 5.0,
+            _ => 10.0,
+        };
+        nice * magnitude
+    }
+
+    /// Get the tick values that fall within the range, there are never more
+    /// than ten times the tick count. Ranges that aren't finite have no
+    /// ticks.
+    pub fn ticks(&self) -> Vec<f32> {
+        let [min, max] = self.range;
+        if !min.is_finite() || !max.is_finite() || !(max - min).is_finite() {
+            return vec![];
+        }
+        let (min, max) = (min.min(max), min.max(max));
+        let step = self.tick_step();
+        if step <= 0.0 || !step.is_finite() {
+            return vec![];
+        }
+
+        let first = (min / step).ceil() as i64;
+        let last = (max / step + 1e-4).floor() as i64;
+        (first..=last)
+            .take(self.tick_count.max(1) * 10)
+            // Avoid -0.0
+            .map(|i| if i == 0 { 0.0 } else { i as f32 * step })
+            .collect()
+    }
+
+    /// Format a value as a tick label.
+    pub fn format(&self, value: f32) -> String {
+        if let Some(formatter) = &self.formatter {
+            return formatter(value);
+        }
+
+        // Show enough decimals to tell the ticks apart
+        let decimals = (-self.tick_step().log10().floor()).max(0.0) as usize;
+        format!("{value:.decimals$}")
+    }
+
+    /// Map a value to a fraction of the axis, `0.0` is the start of the
+    /// range and `1.0` is the end.
+    pub fn normalize(&self, value: f32) -> f32 {
+        let [min, max] = self.range;
+        if max == min {
+            return 0.0;
+        }
+        (value - min) / (max - min)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_ticks() {
+        let axis = Axis::new(0.3, 9.7).tick_count(4);
+        assert_eq!(axis.tick_step(), 2.0);
+        assert_eq!(axis.ticks(), vec![2.0, 4.0, 6.0, 8.0]);
+    }
+
+    #[test]
+    fn small_ticks() {
+        let axis = Axis::new(0.0, 0.5);
+        assert_eq!(axis.tick_step(), 0.1);
+        assert_eq!(axis.format(0.2), "0.2");
+        assert_eq!(axis.ticks().len(), 6);
+    }
+
+    #[test]
+    fn negative_ticks() {
+        let axis = Axis::new(-10.0, 10.0).tick_count(2);
+        assert_eq!(axis.ticks(), vec![-10.0, 0.0, 10.0]);
+        assert_eq!(axis.format(-10.0), "-10");
+    }
+
+    #[test]
+    fn empty_range() {
+        let axis = Axis::new(4.0, 4.0);
+        assert_eq!(axis.ticks(), vec![4.0]);
+        assert_eq!(axis.normalize(4.0), 0.0);
+    }
+
+    #[test]
+    fn infinite_range() {
+        assert!(Axis::new(f32::MIN, f32::MAX).ticks().is_empty());
+        assert!(Axis::new(0.0, f32::INFINITY).ticks().is_empty());
+        assert!(Axis::new(f32::NAN, 1.0).ticks().is_empty());
+    }
+
+    #[test]
+    fn ticks_are_capped() {
+        // The step is lost in the precision of the range
+        let axis = Axis::new(1e20, 1e20 + 1e16).tick_count(2);
+        assert!(axis.ticks().len() <= 20);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::style::Styled;
+use crate::view::View;
+use crate::widgets::{HStack, LayoutExt, Rect, Text, Widget};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{AxisAlignment, Layout};
+
+/// A row of labelled color swatches describing the series in a chart.
+///
+/// # Example
+/// ```
+/// use agape::chart::Legend;
+/// use agape::Color;
+///
+/// let legend = Legend::new()
+///     .entry("Revenue", Color::BLUE)
+///     .entry("Costs", Color::RED);
+/// ```
+pub struct Legend {
+    entries: Vec<(String, Color<Rgba>)>,
+    child: HStack,
+}
+
+impl Default for Legend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Legend {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            child: HStack::new().spacing(12),
+        }
+    }
+
+    /// Add a series to the legend.
+    pub fn entry(mut self, label: &str, color: impl IntoColor<Rgba>) -> Self {
+        let color = color.into_color();
+        let swatch = Rect::new(10.0, 10.0).background_color(color.clone());
+        let entry = HStack::new()
+            .spacing(4)
+            .cross_axis_alignment(AxisAlignment::Center)
+            .add_child(swatch)
+            .add_child(Text::new(label));
+
+        self.child = self.child.add_child(entry);
+        self.entries.push((label.to_owned(), color));
+        self
+    }
+
+    /// Get the label and color of each series.
+    pub fn entries(&self) -> &[(String, Color<Rgba>)] {
+        &self.entries
+    }
+}
+
+impl Widget for Legend {
+    fn id(&self) -> GlobalId {
+        self.child.id()
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        self.child.view()
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        self.child.layout()
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        self.child.children()
+    }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        self.child.children_mut()
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        self.child.traverse_mut(f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_entries() {
+        let legend = Legend::new()
+            .entry("Revenue", Color::BLUE)
+            .entry("Costs", Color::RED);
+
+        assert_eq!(legend.entries().len(), 2);
+        assert_eq!(legend.children().len(), 2);
+        assert_eq!(legend.entries()[1], ("Costs".to_owned(), Color::RED));
+    }
+}
@@ -0,0 +1,33 @@
+//! Building blocks for charts.
+//!
+//! A [`PlotArea`] draws the axes, crosshair and tooltip, and can be panned
+//! and zoomed with [`PlotArea::scroll`]. The contents of the chart are drawn onto a
+//! [`PlotCanvas`] in data units, so new chart types only need to describe
+//! what to draw.
+//!
+//! ```
+//! use agape::chart::{Axis, Legend, PlotArea};
+//! use agape::{Color, vstack};
+//!
+//! let sales = vec![(0.0, 4.0), (1.0, 7.0), (2.0, 5.0), (3.0, 9.0)];
+//! let chart = vstack! {
+//!     PlotArea::new(Axis::new(0.0, 3.0).label("Quarter"), Axis::new(0.0, 10.0))
+//!         .draw(move |canvas| {
+//!             for &(x, y) in &sales {
+//!                 canvas.rect((x - 0.3, 0.0), (x + 0.3, y), &Color::BLUE);
+//!             }
+//!         }),
+//!     Legend::new().entry("Sales", Color::BLUE),
+//! };
+//! ```
+mod axis;
+mod legend;
+mod plot;
+mod tooltip;
+mod viewport;
+
+pub use axis::Axis;
+pub use legend::Legend;
+pub use plot::{PlotArea, PlotCanvas, PlotView};
+pub(crate) use tooltip::draw_tooltip;
+pub use viewport::Viewport;
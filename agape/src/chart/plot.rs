@@ -0,0 +1,547 @@
+use super::{Axis, Viewport, draw_tooltip};
+use crate::Resources;
+use crate::resources::CursorPosition;
+use crate::style::{StrokeCap, StrokeJoin, StrokeStyle};
+use crate::view::new_paint;
+use crate::view::{TextView, View, skia_color, skia_stroke};
+use crate::widgets::Widget;
+use agape_core::{Bounds, Color, GlobalId, Position, Rgba, Size};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use std::fmt;
+use std::rc::Rc;
+use tiny_skia::{FillRule, Mask, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+type Painter = Rc<dyn Fn(&mut PlotCanvas)>;
+type TooltipFn = Rc<dyn Fn(f32, f32) -> Option<String>>;
+
+/// The space on the left of the plot for the y axis labels.
+const LEFT_MARGIN: f32 = 40.0;
+/// The space below the plot for the x axis labels.
+const BOTTOM_MARGIN: f32 = 24.0;
+/// The space taken up by an axis title.
+const TITLE_MARGIN: f32 = 20.0;
+const TICK_LENGTH: f32 = 4.0;
+const AXIS_COLOR: Color<Rgba> = Color::rgb(160, 160, 160);
+
+/// A region of the screen that a chart is drawn onto, in data units.
+///
+/// Anything drawn outside of the plot area is clipped.
+pub struct PlotCanvas<'a> {
+    pixmap: &'a mut Pixmap,
+    mask: Option<Mask>,
+    viewport: Viewport,
+    bounds: Bounds,
+}
+
+impl<'a> PlotCanvas<'a> {
+    pub(crate) fn new(pixmap: &'a mut Pixmap, viewport: Viewport, bounds: Bounds) -> Self {
+        let mut mask = Mask::new(pixmap.width(), pixmap.height());
+        let rect = Rect::from_ltrb(bounds.x[0], bounds.y[0], bounds.x[1], bounds.y[1]);
+        if let (Some(mask), Some(rect)) = (&mut mask, rect) {
+            let path = PathBuilder::from_rect(rect);
+            mask.fill_path(&path, FillRule::Winding, false, Transform::identity());
+        }
+
+        Self {
+            pixmap,
+            mask,
+            viewport,
+            bounds,
+        }
+    }
+
+    /// Get the visible region of the data.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Get the bounds of the plot area on the screen.
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    /// Convert a point in data units to a position on the screen.
+    pub fn to_screen(&self, x: f32, y: f32) -> Position {
+        self.viewport.to_screen(x, y, self.bounds)
+    }
+
+    /// Draw a line through the points.
+    pub fn line(&mut self, points: &[(f32, f32)], color: &Color<Rgba>, width: f32) {
+        let stroke = StrokeStyle::new(width, color.clone())
+            .cap(StrokeCap::Round)
+            .join(StrokeJoin::Round);
+        self.polyline(points, &stroke);
+    }
+
+    /// Draw a line through the points with a [`StrokeStyle`], such as a
+    /// dashed line.
+    pub fn polyline(&mut self, points: &[(f32, f32)], stroke: &StrokeStyle) {
+        let mut builder = PathBuilder::new();
+        for (index, &(x, y)) in points.iter().enumerate() {
+            let position = self.to_screen(x, y);
+            if index == 0 {
+                builder.move_to(position.x, position.y);
+            } else {
+                builder.line_to(position.x, position.y);
+            }
+        }
+        let Some(path) = builder.finish() else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&stroke.color));
+        self.pixmap.stroke_path(
+            &path,
+            &paint,
+            &skia_stroke(stroke),
+            Transform::identity(),
+            self.mask.as_ref(),
+        );
+    }
+
+    /// Fill the rectangle between two corners.
+    pub fn rect(&mut self, from: (f32, f32), to: (f32, f32), color: &Color<Rgba>) {
+        let from = self.to_screen(from.0, from.1);
+        let to = self.to_screen(to.0, to.1);
+        let Some(rect) = Rect::from_ltrb(
+            from.x.min(to.x),
+            from.y.min(to.y),
+            from.x.max(to.x),
+            from.y.max(to.y),
+        ) else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        let path = PathBuilder::from_rect(rect);
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            self.mask.as_ref(),
+        );
+    }
+
+    /// Draw a circle at the point, the `radius` is in pixels.
+    pub fn point(&mut self, x: f32, y: f32, radius: f32, color: &Color<Rgba>) {
+        let center = self.to_screen(x, y);
+        let Some(path) = PathBuilder::from_circle(center.x, center.y, radius) else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            self.mask.as_ref(),
+        );
+    }
+}
+
+/// A chart area with axes, that can be panned and zoomed with [`PlotArea::scroll`].
+///
+/// The contents are drawn by a painter, so custom chart types can be
+/// built on top of the axes, crosshair and tooltip.
+///
+/// # Example
+/// ```
+/// use agape::chart::{Axis, PlotArea};
+/// use agape::Color;
+///
+/// let points = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 2.0)];
+/// let plot = PlotArea::new(Axis::new(0.0, 2.0), Axis::new(0.0, 4.0))
+///     .draw(move |canvas| canvas.line(&points, &Color::BLUE, 2.0))
+///     .tooltip(|x, y| Some(format!("{x:.1}: {y:.1}")));
+/// ```
+pub struct PlotArea {
+    id: GlobalId,
+    x_axis: Axis,
+    y_axis: Axis,
+    viewport: Viewport,
+    painter: Option<Painter>,
+    tooltip: Option<TooltipFn>,
+    crosshair: bool,
+    size: Size,
+}
+
+impl fmt::Debug for PlotArea {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlotArea")
+            .field("id", &self.id)
+            .field("x_axis", &self.x_axis)
+            .field("y_axis", &self.y_axis)
+            .field("viewport", &self.viewport)
+            .field("crosshair", &self.crosshair)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PlotArea {
+    pub fn new(x_axis: Axis, y_axis: Axis) -> Self {
+        Self {
+            id: GlobalId::new(),
+            viewport: Viewport::new(x_axis.range, y_axis.range),
+            x_axis,
+            y_axis,
+            painter: None,
+            tooltip: None,
+            crosshair: true,
+            size: Size::new(400.0, 300.0),
+        }
+    }
+
+    /// Set the function that draws the chart's contents.
+    pub fn draw(mut self, painter: impl Fn(&mut PlotCanvas) + 'static) -> Self {
+        self.painter = Some(Rc::new(painter));
+        self
+    }
+
+    /// Set the text of the tooltip shown at the cursor, the function is
+    /// called with the cursor's position in data units. By default the
+    /// position is shown.
+    pub fn tooltip(mut self, tooltip: impl Fn(f32, f32) -> Option<String> + 'static) -> Self {
+        self.tooltip = Some(Rc::new(tooltip));
+        self
+    }
+
+    /// Show or hide the crosshair and tooltip.
+    pub fn crosshair(mut self, crosshair: bool) -> Self {
+        self.crosshair = crosshair;
+        self
+    }
+
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = Size::new(width, height);
+        self
+    }
+
+    /// Get the visible region of the data.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Reset the pan and zoom.
+    pub fn reset_viewport(&mut self) {
+        self.viewport = Viewport::new(self.x_axis.range, self.y_axis.range);
+    }
+
+    /// Vertical scrolling zooms and horizontal scrolling pans.
+    pub fn scroll(&mut self, delta: Position) {
+        let factor = 1.1f32.powf(delta.y);
+        self.viewport.zoom(factor, self.viewport.center());
+
+        let span = self.viewport.x[1] - self.viewport.x[0];
+        self.viewport.pan(-delta.x * span * 0.1, 0.0);
+    }
+}
+
+impl Widget for PlotArea {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.size.width, self.size.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut x_axis = self.x_axis.clone();
+        let mut y_axis = self.y_axis.clone();
+        x_axis.range = self.viewport.x;
+        y_axis.range = self.viewport.y;
+
+        Box::new(PlotView {
+            id: self.id,
+            position: Position::default(),
+            size: Size::default(),
+            x_axis,
+            y_axis,
+            viewport: self.viewport,
+            painter: self.painter.clone(),
+            tooltip: self.tooltip.clone(),
+            crosshair: self.crosshair,
+        })
+    }
+    fn scroll_by(&mut self, delta: Position) -> bool {
+        self.scroll(delta);
+        true
+    }
+}
+
+/// Draws a [`PlotArea`].
+pub struct PlotView {
+    id: GlobalId,
+    position: Position,
+    size: Size,
+    x_axis: Axis,
+    y_axis: Axis,
+    viewport: Viewport,
+    painter: Option<Painter>,
+    tooltip: Option<TooltipFn>,
+    crosshair: bool,
+}
+
+impl PlotView {
+    /// Get the bounds of the plot, without the axes.
+    pub fn plot_bounds(&self) -> Bounds {
+        let top = if self.y_axis.label.is_some() {
+            TITLE_MARGIN
+        } else {
+            0.0
+        };
+        let bottom = if self.x_axis.label.is_some() {
+            BOTTOM_MARGIN + TITLE_MARGIN
+        } else {
+            BOTTOM_MARGIN
+        };
+
+        Bounds {
+            x: [
+                self.position.x + LEFT_MARGIN,
+                self.position.x + self.size.width,
+            ],
+            y: [
+                self.position.y + top,
+                self.position.y + self.size.height - bottom,
+            ],
+        }
+    }
+
+    fn draw_axes(&self, pixmap: &mut Pixmap, resources: &Resources, bounds: Bounds) {
+        let mut builder = PathBuilder::new();
+        builder.move_to(bounds.x[0], bounds.y[0]);
+        builder.line_to(bounds.x[0], bounds.y[1]);
+        builder.line_to(bounds.x[1], bounds.y[1]);
+
+        for tick in self.x_axis.ticks() {
+            let x = self.viewport.to_screen(tick, 0.0, bounds).x;
+            builder.move_to(x, bounds.y[1]);
+            builder.line_to(x, bounds.y[1] + TICK_LENGTH);
+
+            let label = self.x_axis.format(tick);
+            draw_label(pixmap, resources, &label, |size| {
+                Position::new(x - size.width / 2.0, bounds.y[1] + TICK_LENGTH * 2.0)
+            });
+        }
+
+        for tick in self.y_axis.ticks() {
+            let y = self.viewport.to_screen(0.0, tick, bounds).y;
+            builder.move_to(bounds.x[0], y);
+            builder.line_to(bounds.x[0] - TICK_LENGTH, y);
+
+            let label = self.y_axis.format(tick);
+            draw_label(pixmap, resources, &label, |size| {
+                Position::new(
+                    bounds.x[0] - TICK_LENGTH * 2.0 - size.width,
+                    y - size.height / 2.0,
+                )
+            });
+        }
+
+        if let Some(label) = &self.x_axis.label {
+            draw_label(pixmap, resources, label, |size| {
+                Position::new(
+                    (bounds.x[0] + bounds.x[1]) / 2.0 - size.width / 2.0,
+                    bounds.y[1] + BOTTOM_MARGIN,
+                )
+            });
+        }
+
+        if let Some(label) = &self.y_axis.label {
+            draw_label(pixmap, resources, label, |_| {
+                Position::new(self.position.x, self.position.y)
+            });
+        }
+
+        let Some(path) = builder.finish() else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&AXIS_COLOR));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn draw_crosshair(&self, pixmap: &mut Pixmap, resources: &Resources, bounds: Bounds) {
+        let Some(CursorPosition(cursor)) = resources.get::<CursorPosition>() else {
+            return;
+        };
+        if !bounds.within(cursor) {
+            return;
+        }
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(cursor.x, bounds.y[0]);
+        builder.line_to(cursor.x, bounds.y[1]);
+        builder.move_to(bounds.x[0], cursor.y);
+        builder.line_to(bounds.x[1], cursor.y);
+        if let Some(path) = builder.finish() {
+            let mut paint = new_paint();
+            paint.set_color(skia_color(&AXIS_COLOR));
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &Stroke::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+
+        let (x, y) = self.viewport.to_data(*cursor, bounds);
+        let text = match &self.tooltip {
+            Some(tooltip) => tooltip(x, y),
+            None => Some(format!(
+                "{}, {}",
+                self.x_axis.format(x),
+                self.y_axis.format(y)
+            )),
+        };
+        if let Some(text) = text {
+            draw_tooltip(pixmap, resources, *cursor, &text);
+        }
+    }
+}
+
+/// Draw a text label, the position is computed from the size of the text.
+fn draw_label(
+    pixmap: &mut Pixmap,
+    resources: &Resources,
+    text: &str,
+    position: impl FnOnce(Size) -> Position,
+) {
+    let mut view = TextView::new(text);
+    let size = view.text_size();
+    view.set_size(size);
+    view.set_position(position(size));
+    view.render(pixmap, resources);
+}
+
+impl View for PlotView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &AXIS_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let bounds = self.plot_bounds();
+        if let Some(painter) = &self.painter {
+            let mut canvas = PlotCanvas::new(pixmap, self.viewport, bounds);
+            painter(&mut canvas);
+        }
+
+        self.draw_axes(pixmap, resources, bounds);
+        if self.crosshair {
+            self.draw_crosshair(pixmap, resources, bounds);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn canvas_bounds() -> Bounds {
+        Bounds::new(Position::new(10.0, 10.0), Size::new(80.0, 80.0))
+    }
+
+    #[test]
+    fn scroll_to_zoom() {
+        let mut plot = PlotArea::new(Axis::new(0.0, 10.0), Axis::new(0.0, 10.0));
+        plot.scroll(Position::new(0.0, 1.0));
+        let span = plot.viewport().x[1] - plot.viewport().x[0];
+        assert!(span < 10.0);
+        assert_eq!(plot.viewport().center(), (5.0, 5.0));
+
+        plot.reset_viewport();
+        assert_eq!(plot.viewport().x, [0.0, 10.0]);
+    }
+
+    #[test]
+    fn scroll_to_pan() {
+        let mut plot = PlotArea::new(Axis::new(0.0, 10.0), Axis::new(0.0, 10.0));
+        plot.scroll(Position::new(-1.0, 0.0));
+        assert_eq!(plot.viewport().x, [1.0, 11.0]);
+        assert_eq!(plot.viewport().y, [0.0, 10.0]);
+    }
+
+    #[test]
+    fn clip_to_plot() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let viewport = Viewport::new([0.0, 1.0], [0.0, 1.0]);
+        let mut canvas = PlotCanvas::new(&mut pixmap, viewport, canvas_bounds());
+        canvas.rect((-1.0, -1.0), (2.0, 2.0), &Color::BLACK);
+
+        assert_ne!(pixmap.pixel(50, 50).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(95, 95).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn draw_in_data_units() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let viewport = Viewport::new([0.0, 1.0], [0.0, 1.0]);
+        let mut canvas = PlotCanvas::new(&mut pixmap, viewport, canvas_bounds());
+        // The top right corner of the plot
+        canvas.point(1.0, 1.0, 4.0, &Color::BLACK);
+
+        assert_ne!(pixmap.pixel(88, 12).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(12, 88).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn plot_bounds_leave_room_for_labels() {
+        let plot = PlotArea::new(Axis::new(0.0, 1.0).label("Time"), Axis::new(0.0, 1.0));
+        let bounds = PlotView {
+            size: Size::new(200.0, 100.0),
+            ..plot_view(&plot)
+        }
+        .plot_bounds();
+
+        assert_eq!(bounds.x, [LEFT_MARGIN, 200.0]);
+        assert_eq!(bounds.y, [0.0, 100.0 - BOTTOM_MARGIN - TITLE_MARGIN]);
+    }
+
+    fn plot_view(plot: &PlotArea) -> PlotView {
+        PlotView {
+            id: plot.id,
+            position: Position::default(),
+            size: Size::default(),
+            x_axis: plot.x_axis.clone(),
+            y_axis: plot.y_axis.clone(),
+            viewport: plot.viewport,
+            painter: None,
+            tooltip: None,
+            crosshair: true,
+        }
+    }
+}
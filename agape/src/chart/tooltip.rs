@@ -0,0 +1,49 @@
+use crate::Resources;
+use crate::view::new_paint;
+use crate::view::{TextView, View, skia_color};
+use agape_core::{Color, Position};
+use tiny_skia::{FillRule, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// The space between the tooltip's border and its text.
+const PADDING: f32 = 4.0;
+
+/// Draw a tooltip centered above the `anchor`.
+pub(crate) fn draw_tooltip(
+    pixmap: &mut Pixmap,
+    resources: &Resources,
+    anchor: Position,
+    text: &str,
+) {
+    let mut text = TextView::new(text);
+    let text_size = text.text_size();
+
+    let width = text_size.width + PADDING * 2.0;
+    let height = text_size.height + PADDING * 2.0;
+    let x = anchor.x - width / 2.0;
+    let y = anchor.y - height - 2.0;
+
+    if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+        let path = PathBuilder::from_rect(rect);
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&Color::WHITE));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+        paint.set_color(skia_color(&Color::rgb(200, 200, 200)));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    text.set_position(Position::new(x + PADDING, y + PADDING));
+    text.set_size(text_size);
+    text.render(pixmap, resources);
+}
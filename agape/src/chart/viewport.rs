@@ -0,0 +1,120 @@
+use agape_core::{Bounds, Position};
+
+/// The visible region of a chart's data, which can be panned and zoomed.
+///
+/// # Example
+/// ```
+/// use agape::chart::Viewport;
+///
+/// let mut viewport = Viewport::new([0.0, 10.0], [0.0, 10.0]);
+/// viewport.zoom(2.0, (5.0, 5.0));
+/// assert_eq!(viewport.x, [2.5, 7.5]);
+///
+/// viewport.pan(1.0, 0.0);
+/// assert_eq!(viewport.x, [3.5, 8.5]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Viewport {
+    pub x: [f32; 2],
+    pub y: [f32; 2],
+}
+
+impl Viewport {
+    pub fn new(x: [f32; 2], y: [f32; 2]) -> Self {
+        Self { x, y }
+    }
+
+    /// Move the viewport, in data units.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.x = [self.x[0] + dx, self.x[1] + dx];
+        self.y = [self.y[0] + dy, self.y[1] + dy];
+    }
+
+    /// Zoom around the `anchor`, which stays in the same place on the
+    /// screen. A `factor` above `1.0` zooms in.
+    pub fn zoom(&mut self, factor: f32, anchor: (f32, f32)) {
+        if factor <= 0.0 || !factor.is_finite() {
+            return;
+        }
+
+        let scale = |[min, max]: [f32; 2], anchor: f32| {
+            [
+                anchor + (min - anchor) / factor,
+                anchor + (max - anchor) / factor,
+            ]
+        };
+        self.x = scale(self.x, anchor.0);
+        self.y = scale(self.y, anchor.1);
+    }
+
+    /// Get the center of the viewport.
+    pub fn center(&self) -> (f32, f32) {
+        ((self.x[0] + self.x[1]) / 2.0, (self.y[0] + self.y[1]) / 2.0)
+    }
+
+    /// Convert a point in data units to a position on the screen, within the
+    /// `bounds`. The y axis points up.
+    pub fn to_screen(&self, x: f32, y: f32, bounds: Bounds) -> Position {
+        let fraction = |value: f32, [min, max]: [f32; 2]| {
+            if max == min {
+                0.0
+            } else {
+                (value - min) / (max - min)
+            }
+        };
+
+        Position::new(
+            bounds.x[0] + fraction(x, self.x) * (bounds.x[1] - bounds.x[0]),
+            bounds.y[1] - fraction(y, self.y) * (bounds.y[1] - bounds.y[0]),
+        )
+    }
+
+    /// Convert a position on the screen to a point in data units.
+    pub fn to_data(&self, position: Position, bounds: Bounds) -> (f32, f32) {
+        let fraction = |value: f32, [start, end]: [f32; 2]| {
+            if end == start {
+                0.0
+            } else {
+                (value - start) / (end - start)
+            }
+        };
+
+        let x = fraction(position.x, bounds.x);
+        let y = 1.0 - fraction(position.y, bounds.y);
+        (
+            self.x[0] + x * (self.x[1] - self.x[0]),
+            self.y[0] + y * (self.y[1] - self.y[0]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_core::Size;
+
+    #[test]
+    fn screen_round_trip() {
+        let viewport = Viewport::new([0.0, 10.0], [-5.0, 5.0]);
+        let bounds = Bounds::new(Position::new(20.0, 10.0), Size::new(100.0, 50.0));
+
+        let position = viewport.to_screen(2.5, 0.0, bounds);
+        assert_eq!(position, Position::new(45.0, 35.0));
+        assert_eq!(viewport.to_data(position, bounds), (2.5, 0.0));
+    }
+
+    #[test]
+    fn zoom_keeps_anchor() {
+        let mut viewport = Viewport::new([0.0, 10.0], [0.0, 10.0]);
+        viewport.zoom(4.0, (8.0, 2.0));
+        assert_eq!(viewport.x, [6.0, 8.5]);
+        assert_eq!(viewport.y, [1.5, 4.0]);
+    }
+
+    #[test]
+    fn ignore_invalid_zoom() {
+        let mut viewport = Viewport::new([0.0, 10.0], [0.0, 10.0]);
+        viewport.zoom(0.0, (0.0, 0.0));
+        assert_eq!(viewport, Viewport::new([0.0, 10.0], [0.0, 10.0]));
+    }
+}
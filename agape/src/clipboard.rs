@@ -0,0 +1,219 @@
+//! Reading and writing the system clipboard.
+//!
+//! The functions can be called from anywhere on the ui thread, like event
+//! handlers and systems, for features such as "copy link" buttons. Text and
+//! RGBA images are supported on every platform.
+//!
+//! The clipboard is opened the first time it's used and kept open while
+//! the app is running, since on some platforms, like X11, the copied
+//! contents are lost when it's closed.
+//!
+//! Tests can replace the system clipboard with a [`MemoryClipboard`] using
+//! [`set_backend`], so that they don't overwrite the user's clipboard or
+//! fail on machines without one.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::clipboard;
+//!
+//! let copy = Button::new(Text::new("Copy link")).on_click(|_| {
+//!     if let Err(err) = clipboard::set_text("https://example.com/share/42") {
+//!         eprintln!("Failed to copy the link: {err}");
+//!     }
+//! });
+//! ```
+use crate::{Error, Result};
+use image::RgbaImage;
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+thread_local! {
+    static CLIPBOARD: RefCell<Option<Box<dyn Backend>>> = const { RefCell::new(None) };
+}
+
+/// A clipboard that the functions in this module read and write.
+pub trait Backend {
+    fn get_text(&mut self) -> Result<String>;
+
+    fn set_text(&mut self, text: String) -> Result<()>;
+
+    fn get_image(&mut self) -> Result<RgbaImage>;
+
+    fn set_image(&mut self, image: &RgbaImage) -> Result<()>;
+
+    fn clear(&mut self) -> Result<()>;
+}
+
+impl Backend for arboard::Clipboard {
+    fn get_text(&mut self) -> Result<String> {
+        Ok(arboard::Clipboard::get_text(self)?)
+    }
+
+    fn set_text(&mut self, text: String) -> Result<()> {
+        Ok(arboard::Clipboard::set_text(self, text)?)
+    }
+
+    fn get_image(&mut self) -> Result<RgbaImage> {
+        to_image(arboard::Clipboard::get_image(self)?)
+    }
+
+    fn set_image(&mut self, image: &RgbaImage) -> Result<()> {
+        let data = arboard::ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: Cow::Borrowed(image.as_raw()),
+        };
+        Ok(arboard::Clipboard::set_image(self, data)?)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        Ok(arboard::Clipboard::clear(self)?)
+    }
+}
+
+/// A clipboard that only exists in memory, for tests.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MemoryClipboard {
+    pub text: Option<String>,
+    pub image: Option<RgbaImage>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Backend for MemoryClipboard {
+    fn get_text(&mut self) -> Result<String> {
+        self.text
+            .clone()
+            .ok_or(Error::ClipboardError(arboard::Error::ContentNotAvailable))
+    }
+
+    fn set_text(&mut self, text: String) -> Result<()> {
+        self.text = Some(text);
+        self.image = None;
+        Ok(())
+    }
+
+    fn get_image(&mut self) -> Result<RgbaImage> {
+        self.image
+            .clone()
+            .ok_or(Error::ClipboardError(arboard::Error::ContentNotAvailable))
+    }
+
+    fn set_image(&mut self, image: &RgbaImage) -> Result<()> {
+        self.image = Some(image.clone());
+        self.text = None;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        *self = Self::default();
+        Ok(())
+    }
+}
+
+/// Use another clipboard instead of the system clipboard, on the current
+/// thread.
+pub fn set_backend(backend: impl Backend + 'static) {
+    CLIPBOARD.set(Some(Box::new(backend)));
+}
+
+/// Call `f` with the clipboard, opening the system clipboard if there
+/// isn't one yet.
+fn with_clipboard<T>(f: impl FnOnce(&mut dyn Backend) -> Result<T>) -> Result<T> {
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        let clipboard = match clipboard {
+            Some(clipboard) => clipboard,
+            None => clipboard.insert(Box::new(arboard::Clipboard::new()?)),
+        };
+        f(clipboard.as_mut())
+    })
+}
+
+/// Get the text on the clipboard.
+///
+/// Returns an error if the clipboard is empty, or has contents that can't
+/// be converted to text.
+pub fn get_text() -> Result<String> {
+    with_clipboard(|clipboard| clipboard.get_text())
+}
+
+/// Replace the contents of the clipboard with `text`.
+pub fn set_text(text: impl Into<String>) -> Result<()> {
+    let text = text.into();
+    with_clipboard(|clipboard| clipboard.set_text(text))
+}
+
+/// Get the image on the clipboard.
+pub fn get_image() -> Result<RgbaImage> {
+    with_clipboard(|clipboard| clipboard.get_image())
+}
+
+/// Replace the contents of the clipboard with an image.
+pub fn set_image(image: &RgbaImage) -> Result<()> {
+    with_clipboard(|clipboard| clipboard.set_image(image))
+}
+
+/// Remove the contents of the clipboard.
+pub fn clear() -> Result<()> {
+    with_clipboard(|clipboard| clipboard.clear())
+}
+
+fn to_image(data: arboard::ImageData) -> Result<RgbaImage> {
+    let expected = data.width * data.height * 4;
+    let actual = data.bytes.len();
+    RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or(Error::FrameSizeError { expected, actual })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn convert_clipboard_images() {
+        let data = arboard::ImageData {
+            width: 2,
+            height: 1,
+            bytes: Cow::Owned(vec![255, 0, 0, 255, 0, 0, 255, 128]),
+        };
+        let image = to_image(data).unwrap();
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 255, 128]);
+
+        let data = arboard::ImageData {
+            width: 2,
+            height: 2,
+            bytes: Cow::Owned(vec![0; 8]),
+        };
+        assert!(matches!(
+            to_image(data),
+            Err(Error::FrameSizeError {
+                expected: 16,
+                actual: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn replace_system_clipboard() {
+        set_backend(MemoryClipboard::new());
+        assert!(get_text().is_err());
+
+        set_text("Copied").unwrap();
+        assert_eq!(get_text().unwrap(), "Copied");
+        let image = RgbaImage::new(2, 2);
+        set_image(&image).unwrap();
+        assert_eq!(get_image().unwrap(), image);
+        assert!(get_text().is_err());
+        clear().unwrap();
+        assert!(get_image().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Sharing state with the widgets in a subtree.
+//!
+//! A widget provides a value to its children with
+//! [`Widget::provide_context`], and any of its descendants gets it with
+//! [`use_context`], no matter how deeply it's nested. This is meant for
+//! state that most of a subtree needs, like a theme, the user's session or
+//! a router, without passing it through every constructor.
+//!
+//! Values are looked up by their type, the closest provider above a widget
+//! wins. They're available while the widget's layout and view are built,
+//! and while it handles events.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::context::use_context;
+//!
+//! struct Session {
+//!     user: String,
+//! }
+//!
+//! let logout = Button::new(Text::new("Log out")).on_click(|_| {
+//!     let session = use_context::<Session>().unwrap();
+//!     println!("Logging out {}", session.user);
+//! });
+//! let app = App::new(vstack! {logout}.provide_context(Session {
+//!     user: String::from("alice"),
+//! }));
+//! ```
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+thread_local! {
+    /// The values provided by the widgets that are being visited, from the
+    /// outermost to the innermost.
+    static CONTEXT: RefCell<Vec<Rc<dyn Any>>> = const { RefCell::new(vec![]) };
+}
+
+/// Get the value of type `T` provided by the closest widget above the one
+/// that's being built or handling an event.
+///
+/// Returns `None` if no widget above it provides a `T`, or if it's called
+/// outside of a widget.
+pub fn use_context<T: 'static>() -> Option<Rc<T>> {
+    CONTEXT.with_borrow(|context| {
+        context
+            .iter()
+            .rev()
+            .find_map(|value| value.clone().downcast::<T>().ok())
+    })
+}
+
+/// Removes the value from the context when it's dropped, including when a
+/// widget panics.
+struct Scope;
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        CONTEXT.with_borrow_mut(|context| context.pop());
+    }
+}
+
+/// Call `f` with a value added to the context.
+pub(crate) fn provide<R>(value: &Rc<dyn Any>, f: impl FnOnce() -> R) -> R {
+    CONTEXT.with_borrow_mut(|context| context.push(value.clone()));
+    let _scope = Scope;
+    f()
+}
@@ -0,0 +1,327 @@
+//! Diagnostic information about the running app, useful for attaching to
+//! bug reports.
+//!
+//! ```
+//! use agape::diagnostics::Diagnostics;
+//!
+//! let diagnostics = Diagnostics::new()
+//!     .app("Notes", "1.2.0")
+//!     .scale_factor(2.0)
+//!     .error("Failed to load image");
+//!
+//! let report = diagnostics.report();
+//! assert!(report.contains("app: Notes 1.2.0"));
+//! ```
+//!
+//! [`dump_tree`] writes the widget tree, with its solved layout and styles,
+//! as JSON.
+use crate::widgets::Widget;
+use agape_core::{Color, GlobalId, Rgba};
+use agape_layout::Layout;
+use std::fmt::Write;
+
+/// The name of the renderer backend.
+pub const RENDERER: &str = "tiny-skia";
+
+/// Where the renderer draws, widgets are drawn on the cpu and the frames
+/// are copied to the window.
+pub const RENDERER_KIND: &str = "cpu";
+
+/// The maximum number of errors kept in a [`Diagnostics`] report, older
+/// errors are dropped first.
+pub const MAX_ERRORS: usize = 20;
+
+/// Metadata about the app and the environment it's running in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub app_name: String,
+    pub app_version: String,
+    pub agape_version: &'static str,
+    pub renderer: &'static str,
+    /// Whether the renderer draws on the cpu or the gpu.
+    pub renderer_kind: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub scale_factor: f64,
+    pub errors: Vec<String>,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            app_name: String::new(),
+            app_version: String::new(),
+            agape_version: env!("CARGO_PKG_VERSION"),
+            renderer: RENDERER,
+            renderer_kind: RENDERER_KIND,
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            scale_factor: 1.0,
+            errors: vec![],
+        }
+    }
+}
+
+impl Diagnostics {
+    /// Create new [`Diagnostics`] for the current environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the app's name and version.
+    pub fn app(mut self, name: &str, version: &str) -> Self {
+        self.app_name = name.to_owned();
+        self.app_version = version.to_owned();
+        self
+    }
+
+    /// Set the window's scale factor.
+    pub fn scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Add an error to the error log.
+    pub fn error(mut self, error: impl ToString) -> Self {
+        self.push_error(error);
+        self
+    }
+
+    /// Push an error to the error log, only the most recent [`MAX_ERRORS`]
+    /// errors are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::diagnostics::{Diagnostics,MAX_ERRORS};
+    ///
+    /// let mut diagnostics = Diagnostics::new();
+    /// for i in 0..50 {
+    ///     diagnostics.push_error(i);
+    /// }
+    ///
+    /// assert_eq!(diagnostics.errors.len(),MAX_ERRORS);
+    /// assert_eq!(diagnostics.errors.last().unwrap(),"49");
+    /// ```
+    pub fn push_error(&mut self, error: impl ToString) {
+        self.errors.push(error.to_string());
+        if self.errors.len() > MAX_ERRORS {
+            let overflow = self.errors.len() - MAX_ERRORS;
+            self.errors.drain(..overflow);
+        }
+    }
+
+    /// Export the diagnostics as plain text.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        // Writing to a string never fails
+        let _ = writeln!(report, "app: {} {}", self.app_name, self.app_version);
+        let _ = writeln!(report, "agape: {}", self.agape_version);
+        let _ = writeln!(
+            report,
+            "renderer: {} ({})",
+            self.renderer, self.renderer_kind
+        );
+        let _ = writeln!(report, "os: {} {}", self.os, self.arch);
+        let _ = writeln!(report, "scale factor: {}", self.scale_factor);
+        let _ = writeln!(report, "errors: {}", self.errors.len());
+        for error in &self.errors {
+            let _ = writeln!(report, "  - {error}");
+        }
+
+        report
+    }
+}
+
+/// Dump the widget tree as JSON, with the solved layout, colors and group
+/// effects of each widget. Widgets are written in tree order, so dumps can
+/// be diffed between frames or test runs.
+///
+/// Use [`App::dump_tree`](crate::App::dump_tree) to dump the tree of a
+/// running app.
+pub fn dump_tree(widget: &dyn Widget, layout: &dyn Layout) -> String {
+    dump_tree_with_modal(widget, layout, None)
+}
+
+/// Dump the widget tree like [`dump_tree`], the widgets outside of an open
+/// [`modal`](crate::modal) are marked as inert and their roles and labels
+/// are left out.
+pub fn dump_tree_with_modal(
+    widget: &dyn Widget,
+    layout: &dyn Layout,
+    modal: Option<GlobalId>,
+) -> String {
+    let mut output = String::new();
+    let mut ctx = DumpCtx {
+        layout,
+        modal,
+        output: &mut output,
+    };
+    dump_widget(widget, modal.is_some(), 0, &mut ctx);
+    output.push('\n');
+    output
+}
+
+struct DumpCtx<'a> {
+    layout: &'a dyn Layout,
+    modal: Option<GlobalId>,
+    output: &'a mut String,
+}
+
+fn dump_widget(widget: &dyn Widget, inert: bool, depth: usize, ctx: &mut DumpCtx) {
+    let inert = inert && ctx.modal != Some(widget.id());
+    let layout = ctx.layout;
+    let indent = "  ".repeat(depth + 1);
+    let view = widget.view();
+    let mut fields = vec![
+        format!("\"type\": \"{}\"", widget.name()),
+        format!("\"id\": {}", widget.id().as_u32()),
+    ];
+    if let Some(name) = widget.id().name() {
+        fields.push(format!("\"name\": {name:?}"));
+    }
+    if inert {
+        fields.push("\"inert\": true".to_owned());
+    } else if let Some(semantics) = widget.as_semantics() {
+        fields.push(format!("\"role\": \"{}\"", semantics.role().name()));
+        if let Some(label) = semantics.label() {
+            fields.push(format!("\"label\": {label:?}"));
+        }
+    }
+    if let Some(layout) = layout.get(widget.id()) {
+        let (position, size) = (layout.position(), layout.size());
+        let intrinsic_size = layout.intrinsic_size();
+        fields.push(format!(
+            "\"position\": [{}, {}]",
+            number(position.x),
+            number(position.y)
+        ));
+        fields.push(format!(
+            "\"size\": [{}, {}]",
+            number(size.width),
+            number(size.height)
+        ));
+        fields.push(format!(
+            "\"intrinsic_size\": [\"{:?}\", \"{:?}\"]",
+            intrinsic_size.width, intrinsic_size.height
+        ));
+    }
+    fields.push(format!("\"color\": {}", color(view.color())));
+    if let Some(foreground) = view.foreground_color() {
+        fields.push(format!("\"foreground_color\": {}", color(foreground)));
+    }
+    if let Some(opacity) = widget.group_opacity() {
+        fields.push(format!("\"opacity\": {}", number(opacity)));
+    }
+    if let Some(blur) = widget.group_blur() {
+        fields.push(format!("\"blur\": {}", number(blur)));
+    }
+    if let Some(transform) = widget.group_transform() {
+        fields.push(format!(
+            "\"transform\": {{\"rotation\": {}, \"scale\": [{}, {}], \"translation\": [{}, {}]}}",
+            number(transform.rotation),
+            number(transform.scale_x),
+            number(transform.scale_y),
+            number(transform.translation.x),
+            number(transform.translation.y)
+        ));
+    }
+
+    ctx.output.push_str("{\n");
+    for field in fields {
+        let _ = writeln!(ctx.output, "{indent}{field},");
+    }
+    let _ = write!(ctx.output, "{indent}\"children\": [");
+    let children = widget.children();
+    for (i, child) in children.iter().enumerate() {
+        ctx.output.push_str(if i == 0 { "\n" } else { ",\n" });
+        ctx.output.push_str(&"  ".repeat(depth + 2));
+        dump_widget(*child, inert, depth + 2, ctx);
+    }
+    if !children.is_empty() {
+        let _ = write!(ctx.output, "\n{indent}");
+    }
+    ctx.output.push_str("]\n");
+    ctx.output.push_str(&"  ".repeat(depth));
+    ctx.output.push('}');
+}
+
+/// Format a number for JSON, which doesn't have infinities or NaN.
+fn number(value: f32) -> String {
+    match value.is_finite() {
+        true => value.to_string(),
+        false => "null".to_owned(),
+    }
+}
+
+/// Format a color as `[r, g, b, a]`, with the alpha from 0 to 100.
+fn color(color: &Color<Rgba>) -> String {
+    let (r, g, b, a) = color.inner();
+    format!("[{r}, {g}, {b}, {a}]")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report_includes_errors() {
+        let report = Diagnostics::new()
+            .error("Failed to load font")
+            .error("Surface lost")
+            .report();
+
+        assert!(report.contains("errors: 2"));
+        assert!(report.contains("  - Failed to load font"));
+        assert!(report.contains("  - Surface lost"));
+    }
+
+    #[test]
+    fn report_includes_renderer() {
+        let report = Diagnostics::new().report();
+        assert!(report.contains("renderer: tiny-skia (cpu)"));
+        assert!(report.contains(env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn dump_widget_tree() {
+        use crate::hstack;
+        use crate::style::Styled;
+        use crate::widgets::{LayoutExt, Rect};
+        use agape_core::Size;
+        use agape_layout::LayoutSolver;
+
+        let rect = Rect::new(20.0, 10.0).background_color(Color::rgb(255, 0, 0));
+        let rect_id = rect.id().debug_name("swatch");
+        let widget = hstack! {rect}.padding(4).opacity(0.5);
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+
+        let dump = dump_tree(&widget, layout.as_ref());
+        assert!(dump.starts_with("{\n  \"type\": \"Opacity\""));
+        assert!(dump.contains("\"opacity\": 0.5"));
+        assert!(dump.contains("\"type\": \"HStack\""));
+        assert!(dump.contains(&format!("\"id\": {},", rect_id.as_u32())));
+        assert!(dump.contains("\"name\": \"swatch\""));
+        assert_eq!(widget.get_by_name("swatch").unwrap().id(), rect_id);
+        assert!(dump.contains("\"position\": [4, 4],\n"));
+        assert!(dump.contains("\"size\": [20, 10]"));
+        assert!(dump.contains("\"color\": [255, 0, 0, 100]"));
+    }
+
+    #[test]
+    fn dump_semantics() {
+        use crate::widgets::{Button, Text};
+        use agape_core::Size;
+        use agape_layout::LayoutSolver;
+
+        let _ = crate::FONT.set(crate::init_font());
+        let button = Button::new(Text::new("Save"));
+        let mut layout = button.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+
+        let dump = dump_tree(&button, layout.as_ref());
+        assert!(dump.starts_with("{\n  \"type\": \"Button\""));
+        assert!(dump.contains("\"role\": \"button\",\n  \"label\": \"Save\""));
+        assert!(dump.contains("\"role\": \"text\""));
+    }
+}
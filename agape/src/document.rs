@@ -0,0 +1,1214 @@
+//! The document model used by the [`RichTextEditor`](crate::widgets::RichTextEditor).
+//!
+//! A [`Document`] is a list of [`Block`]s, such as paragraphs, headings and
+//! list items. Each block is made of [`Run`]s of text that share the same
+//! [`Marks`]. Images and files can be embedded in the text as
+//! [`InlineObject`]s. Documents can be exported to Markdown and HTML, and read back
+//! from the Markdown they export.
+//!
+//! # Example
+//! ```
+//! use agape::document::{Block, BlockKind, Document, Marks};
+//!
+//! let mut document = Document::new();
+//! document.blocks[0].kind = BlockKind::Heading(1);
+//! document.blocks[0].insert(0, "Notes", &Marks::default());
+//!
+//! assert_eq!(document.to_markdown(), "# Notes");
+//! assert_eq!(Document::from_markdown(&document.to_markdown()), document);
+//! ```
+use std::fmt::Write;
+use std::ops::Range;
+
+/// The formatting of a [`Run`] of text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Marks {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// The url the text links to.
+    pub link: Option<String>,
+}
+
+/// The character that takes the place of an [`InlineObject`] in the text.
+pub const OBJECT_REPLACEMENT: char = '\u{FFFC}';
+
+/// An object embedded in the text of a [`Block`], it takes the place of one
+/// character so it's selected and deleted like one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InlineObject {
+    /// An image, drawn at its size in pixels.
+    Image {
+        source: String,
+        width: u32,
+        height: u32,
+    },
+    /// A file, drawn as a chip with its name.
+    File { name: String, source: String },
+}
+
+/// Text that shares the same [`Marks`], or an [`InlineObject`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Run {
+    pub text: String,
+    pub marks: Marks,
+    /// The object of the run, its text is a single [`OBJECT_REPLACEMENT`].
+    pub object: Option<InlineObject>,
+}
+
+impl Run {
+    pub fn new(text: &str, marks: Marks) -> Self {
+        Self {
+            text: text.to_owned(),
+            marks,
+            object: None,
+        }
+    }
+
+    pub fn object(object: InlineObject) -> Self {
+        Self {
+            text: OBJECT_REPLACEMENT.to_string(),
+            marks: Marks::default(),
+            object: Some(object),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+/// The kind of a [`Block`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BlockKind {
+    #[default]
+    Paragraph,
+    /// A heading from level `1` to `3`.
+    Heading(u8),
+    BulletItem,
+    NumberedItem,
+}
+
+/// A paragraph, heading or list item.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub runs: Vec<Run>,
+}
+
+/// Get the byte index of a character in a string.
+fn byte_index(text: &str, offset: usize) -> usize {
+    text.char_indices()
+        .nth(offset)
+        .map(|(index, _)| index)
+        .unwrap_or(text.len())
+}
+
+impl Block {
+    pub fn new(kind: BlockKind) -> Self {
+        Self { kind, runs: vec![] }
+    }
+
+    /// Get the plain text of the block.
+    pub fn text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    /// Get the number of characters in the block.
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(Run::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.iter().all(|run| run.text.is_empty())
+    }
+
+    /// Split the run at a character offset, so that a run starts there, and
+    /// return the index of that run.
+    fn split_at(&mut self, offset: usize) -> usize {
+        let mut start = 0;
+        for index in 0..self.runs.len() {
+            let len = self.runs[index].len();
+            if offset == start {
+                return index;
+            }
+            if offset < start + len {
+                let run = &mut self.runs[index];
+                let tail = run.text.split_off(byte_index(&run.text, offset - start));
+                let marks = run.marks.clone();
+                self.runs.insert(index + 1, Run::new(&tail, marks));
+                return index + 1;
+            }
+            start += len;
+        }
+        self.runs.len()
+    }
+
+    /// Merge neighbouring runs with the same marks and remove empty runs.
+    fn normalize(&mut self) {
+        let mut runs: Vec<Run> = vec![];
+        for run in self.runs.drain(..) {
+            if run.text.is_empty() {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(last)
+                    if last.marks == run.marks && last.object.is_none() && run.object.is_none() =>
+                {
+                    last.text.push_str(&run.text)
+                }
+                _ => runs.push(run),
+            }
+        }
+        self.runs = runs;
+    }
+
+    /// Insert text at a character offset.
+    pub fn insert(&mut self, offset: usize, text: &str, marks: &Marks) {
+        // Objects can only be added with `insert_object`
+        let text: String = text.chars().filter(|c| *c != OBJECT_REPLACEMENT).collect();
+        let index = self.split_at(offset);
+        self.runs.insert(index, Run::new(&text, marks.clone()));
+        self.normalize();
+    }
+
+    /// Insert an object at a character offset.
+    pub fn insert_object(&mut self, offset: usize, object: InlineObject) {
+        let index = self.split_at(offset);
+        self.runs.insert(index, Run::object(object));
+        self.normalize();
+    }
+
+    /// Get the object at a character offset.
+    pub fn object_at(&self, offset: usize) -> Option<&InlineObject> {
+        let mut start = 0;
+        for run in &self.runs {
+            let len = run.len();
+            if offset < start + len {
+                return run.object.as_ref();
+            }
+            start += len;
+        }
+        None
+    }
+
+    /// Delete a range of characters.
+    pub fn delete(&mut self, range: Range<usize>) {
+        let start = self.split_at(range.start);
+        let end = self.split_at(range.end);
+        self.runs.drain(start..end);
+        self.normalize();
+    }
+
+    /// Change the marks of a range of characters.
+    pub fn apply(&mut self, range: Range<usize>, f: &impl Fn(&mut Marks)) {
+        let start = self.split_at(range.start);
+        let end = self.split_at(range.end);
+        // Objects aren't formatted
+        for run in self.runs[start..end]
+            .iter_mut()
+            .filter(|run| run.object.is_none())
+        {
+            f(&mut run.marks);
+        }
+        self.normalize();
+    }
+
+    /// Get the marks of the character before an offset, which is the
+    /// formatting that typed text continues with.
+    pub fn marks_at(&self, offset: usize) -> Marks {
+        let mut start = 0;
+        let mut marks = self.runs.first().map(|run| run.marks.clone());
+        for run in &self.runs {
+            if start >= offset {
+                break;
+            }
+            marks = Some(run.marks.clone());
+            start += run.len();
+        }
+        marks.unwrap_or_default()
+    }
+
+    /// Split the block at an offset, returning the part after it.
+    pub fn split_off(&mut self, offset: usize) -> Block {
+        let index = self.split_at(offset);
+        let runs = self.runs.split_off(index);
+        Block {
+            kind: self.kind,
+            runs,
+        }
+    }
+
+    /// Add the runs of another block to the end of this one.
+    pub fn append(&mut self, other: Block) {
+        self.runs.extend(other.runs);
+        self.normalize();
+    }
+}
+
+/// A position in a [`Document`], the `offset` is in characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextPosition {
+    pub block: usize,
+    pub offset: usize,
+}
+
+impl TextPosition {
+    pub fn new(block: usize, offset: usize) -> Self {
+        Self { block, offset }
+    }
+}
+
+/// A rich text document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// The blocks of the document, there is always at least one.
+    pub blocks: Vec<Block>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Document {
+    /// Create a document with one empty paragraph.
+    pub fn new() -> Self {
+        Self {
+            blocks: vec![Block::default()],
+        }
+    }
+
+    /// Get the plain text of the document, with the blocks on separate lines.
+    pub fn text(&self) -> String {
+        let lines: Vec<String> = self.blocks.iter().map(Block::text).collect();
+        lines.join("\n")
+    }
+
+    /// Insert text, which may contain line breaks, returning the position
+    /// after it.
+    pub fn insert(&mut self, position: TextPosition, text: &str, marks: &Marks) -> TextPosition {
+        let mut position = position;
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                position = self.split_block(position);
+            }
+            self.blocks[position.block].insert(position.offset, line, marks);
+            position.offset += line.chars().count();
+        }
+        position
+    }
+
+    /// Insert an object, returning the position after it.
+    pub fn insert_object(&mut self, position: TextPosition, object: InlineObject) -> TextPosition {
+        self.blocks[position.block].insert_object(position.offset, object);
+        TextPosition::new(position.block, position.offset + 1)
+    }
+
+    /// Split a block in two, returning the start of the new block.
+    pub fn split_block(&mut self, position: TextPosition) -> TextPosition {
+        let block = &mut self.blocks[position.block];
+        let mut tail = block.split_off(position.offset);
+        // Headings are followed by paragraphs
+        if matches!(tail.kind, BlockKind::Heading(_)) {
+            tail.kind = BlockKind::Paragraph;
+        }
+        self.blocks.insert(position.block + 1, tail);
+        TextPosition::new(position.block + 1, 0)
+    }
+
+    /// Delete the text between two positions, merging the blocks they are in.
+    pub fn delete(&mut self, start: TextPosition, end: TextPosition) {
+        let (start, end) = (start.min(end), start.max(end));
+        if start.block == end.block {
+            self.blocks[start.block].delete(start.offset..end.offset);
+            return;
+        }
+
+        let mut last = self.blocks[end.block].split_off(end.offset);
+        std::mem::swap(&mut last, &mut self.blocks[end.block]);
+        let tail = self.blocks.remove(end.block);
+        self.blocks.drain(start.block + 1..end.block);
+        let first = &mut self.blocks[start.block];
+        let len = first.len();
+        first.delete(start.offset..len);
+        first.append(tail);
+    }
+
+    /// Change the marks of the text between two positions.
+    pub fn apply(&mut self, start: TextPosition, end: TextPosition, f: impl Fn(&mut Marks)) {
+        let (start, end) = (start.min(end), start.max(end));
+        for index in start.block..=end.block {
+            let block = &mut self.blocks[index];
+            let from = if index == start.block {
+                start.offset
+            } else {
+                0
+            };
+            let to = if index == end.block {
+                end.offset
+            } else {
+                block.len()
+            };
+            block.apply(from..to, &f);
+        }
+    }
+
+    /// Returns `true` if all the text between two positions has a mark.
+    pub fn all(&self, start: TextPosition, end: TextPosition, f: impl Fn(&Marks) -> bool) -> bool {
+        let (start, end) = (start.min(end), start.max(end));
+        (start.block..=end.block).all(|index| {
+            let from = if index == start.block {
+                start.offset
+            } else {
+                0
+            };
+            let to = if index == end.block {
+                end.offset
+            } else {
+                usize::MAX
+            };
+            let mut offset = 0;
+            self.blocks[index].runs.iter().all(|run| {
+                let run_start = offset;
+                offset += run.len();
+                run_start >= to || offset <= from || f(&run.marks)
+            })
+        })
+    }
+
+    /// Export the document as Markdown, underlined text uses `<u>` tags
+    /// since Markdown doesn't have underlines.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let mut number = 0;
+        for (index, block) in self.blocks.iter().enumerate() {
+            if index > 0 {
+                markdown.push('\n');
+            }
+            number = match block.kind {
+                BlockKind::NumberedItem => number + 1,
+                _ => 0,
+            };
+            match block.kind {
+                BlockKind::Paragraph => {}
+                BlockKind::Heading(level) => {
+                    let _ = write!(markdown, "{} ", "#".repeat(level.max(1) as usize));
+                }
+                BlockKind::BulletItem => markdown.push_str("- "),
+                BlockKind::NumberedItem => {
+                    let _ = write!(markdown, "{number}. ");
+                }
+            }
+            for run in &block.runs {
+                markdown.push_str(&markdown_run(run));
+            }
+        }
+        markdown
+    }
+
+    /// Export the document as HTML.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let mut list: Option<&str> = None;
+        for block in &self.blocks {
+            let tag = match block.kind {
+                BlockKind::BulletItem => Some("ul"),
+                BlockKind::NumberedItem => Some("ol"),
+                _ => None,
+            };
+            if list != tag {
+                if let Some(list) = list {
+                    let _ = write!(html, "</{list}>");
+                }
+                if let Some(tag) = tag {
+                    let _ = write!(html, "<{tag}>");
+                }
+                list = tag;
+            }
+
+            let element = match block.kind {
+                BlockKind::Paragraph => String::from("p"),
+                BlockKind::Heading(level) => format!("h{}", level.clamp(1, 6)),
+                BlockKind::BulletItem | BlockKind::NumberedItem => String::from("li"),
+            };
+            let _ = write!(html, "<{element}>");
+            for run in &block.runs {
+                html.push_str(&html_run(run));
+            }
+            let _ = write!(html, "</{element}>");
+        }
+        if let Some(list) = list {
+            let _ = write!(html, "</{list}>");
+        }
+        html
+    }
+
+    /// Read a document from the Markdown written by [`Document::to_markdown`].
+    pub fn from_markdown(markdown: &str) -> Self {
+        let blocks: Vec<Block> = markdown.split('\n').map(parse_block).collect();
+        if blocks.is_empty() {
+            return Self::new();
+        }
+        Self { blocks }
+    }
+
+    /// Get the number of characters in the document, counting the line
+    /// break between each block as one character.
+    pub fn len(&self) -> usize {
+        let breaks = self.blocks.len() - 1;
+        self.blocks.iter().map(Block::len).sum::<usize>() + breaks
+    }
+
+    /// Returns `true` if the document has no text.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the offset of a position in the text of the document, as returned
+    /// by [`Document::text`].
+    pub fn offset_of(&self, position: TextPosition) -> usize {
+        let before: usize = self.blocks[..position.block]
+            .iter()
+            .map(|block| block.len() + 1)
+            .sum();
+        before + position.offset
+    }
+
+    /// Get the position of an offset in the text of the document, offsets
+    /// past the end are moved to the end.
+    pub fn position_at(&self, offset: usize) -> TextPosition {
+        let mut offset = offset;
+        for (index, block) in self.blocks.iter().enumerate() {
+            if offset <= block.len() {
+                return TextPosition::new(index, offset);
+            }
+            offset -= block.len() + 1;
+        }
+        let last = self.blocks.len() - 1;
+        TextPosition::new(last, self.blocks[last].len())
+    }
+
+    /// Apply an [`Operation`] to the document.
+    pub fn apply_operation(&mut self, operation: &Operation) {
+        match operation {
+            Operation::Insert {
+                offset,
+                text,
+                marks,
+            } => {
+                self.insert(self.position_at(*offset), text, marks);
+            }
+            Operation::InsertObject { offset, object } => {
+                self.insert_object(self.position_at(*offset), object.clone());
+            }
+            Operation::Delete { offset, len } => {
+                let start = self.position_at(*offset);
+                let end = self.position_at(offset + len);
+                self.delete(start, end);
+            }
+            Operation::Format {
+                offset,
+                len,
+                change,
+            } => {
+                let start = self.position_at(*offset);
+                let end = self.position_at(offset + len);
+                self.apply(start, end, |marks| change.apply(marks));
+            }
+            Operation::SetBlockKind { offset, kind } => {
+                let block = self.position_at(*offset).block;
+                self.blocks[block].kind = *kind;
+            }
+        }
+    }
+}
+
+/// A change to one of the [`Marks`] of some text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MarkChange {
+    Bold(bool),
+    Italic(bool),
+    Underline(bool),
+    /// Link the text to a url, or remove the link if `None`.
+    Link(Option<String>),
+}
+
+impl MarkChange {
+    pub fn apply(&self, marks: &mut Marks) {
+        match self {
+            MarkChange::Bold(value) => marks.bold = *value,
+            MarkChange::Italic(value) => marks.italic = *value,
+            MarkChange::Underline(value) => marks.underline = *value,
+            MarkChange::Link(url) => marks.link = url.clone(),
+        }
+    }
+
+    /// Returns `true` if both changes are to the same mark.
+    fn same_mark(&self, other: &MarkChange) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// An edit to a [`Document`], positioned by offsets in the text of the
+/// document, where the line break between blocks counts as one character.
+///
+/// Operations are plain data, so they can be sent to other copies of a
+/// document. Operations that were made at the same time, on the same
+/// version of a document, are transformed against each other with
+/// [`Operation::transform`] before they're applied, so that every copy ends
+/// up with the same document.
+///
+/// # Example
+/// ```
+/// use agape::document::{Document, Marks, Operation};
+///
+/// let mut alice = Document::from_markdown("Hello");
+/// let mut bob = alice.clone();
+///
+/// let greeting = Operation::Insert { offset: 5, text: " world".into(), marks: Marks::default() };
+/// let name = Operation::Insert { offset: 0, text: "Bob: ".into(), marks: Marks::default() };
+///
+/// alice.apply_operation(&greeting);
+/// for operation in name.transform(&greeting, false) {
+///     alice.apply_operation(&operation);
+/// }
+/// bob.apply_operation(&name);
+/// for operation in greeting.transform(&name, true) {
+///     bob.apply_operation(&operation);
+/// }
+///
+/// assert_eq!(alice, bob);
+/// assert_eq!(alice.text(), "Bob: Hello world");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// Insert text, line breaks in the text split the block.
+    Insert {
+        offset: usize,
+        text: String,
+        marks: Marks,
+    },
+    InsertObject {
+        offset: usize,
+        object: InlineObject,
+    },
+    /// Delete characters, blocks are merged when the line break between
+    /// them is deleted.
+    Delete {
+        offset: usize,
+        len: usize,
+    },
+    /// Change the marks of some text.
+    Format {
+        offset: usize,
+        len: usize,
+        change: MarkChange,
+    },
+    /// Change the kind of the block that contains `offset`.
+    SetBlockKind {
+        offset: usize,
+        kind: BlockKind,
+    },
+}
+
+impl Operation {
+    /// Get the number of characters that the operation inserts.
+    fn inserted_len(&self) -> usize {
+        match self {
+            Operation::Insert { text, .. } => text.chars().count(),
+            Operation::InsertObject { .. } => 1,
+            _ => 0,
+        }
+    }
+
+    /// Move an offset to where the same character is after the operation is
+    /// applied. Text inserted at the offset is placed before it, and
+    /// offsets in deleted text are moved to the start of the deletion.
+    pub fn transform_offset(&self, offset: usize) -> usize {
+        match self {
+            Operation::Insert { offset: at, .. } | Operation::InsertObject { offset: at, .. }
+                if *at <= offset =>
+            {
+                offset + self.inserted_len()
+            }
+            Operation::Delete { offset: at, len } if offset > *at => {
+                (*at).max(offset.saturating_sub(*len))
+            }
+            _ => offset,
+        }
+    }
+
+    /// Transform the operation so that it can be applied after `other`,
+    /// when both were made on the same version of the document.
+    ///
+    /// `first` breaks ties and should be the opposite on each side: when
+    /// `true`, text inserted at the same offset as `other` goes before it,
+    /// and where both change the same mark or block, `other` wins.
+    ///
+    /// An operation can be split in two, when text is inserted inside the
+    /// range it changes, or removed, if what it changes was deleted.
+    pub fn transform(&self, other: &Operation, first: bool) -> Vec<Operation> {
+        let mut operation = self.clone();
+        match other {
+            Operation::Insert { offset: at, .. } | Operation::InsertObject { offset: at, .. } => {
+                let (at, inserted) = (*at, other.inserted_len());
+                match &mut operation {
+                    Operation::Insert { offset, .. } | Operation::InsertObject { offset, .. } => {
+                        if at < *offset || (at == *offset && !first) {
+                            *offset += inserted;
+                        }
+                    }
+                    Operation::SetBlockKind { offset, .. } => {
+                        if at < *offset {
+                            *offset += inserted;
+                        }
+                    }
+                    Operation::Delete { offset, len } | Operation::Format { offset, len, .. } => {
+                        if at <= *offset {
+                            *offset += inserted;
+                        } else if at < *offset + *len {
+                            // Text inserted inside the range is left alone
+                            let before = at - *offset;
+                            let after_len = *len - before;
+                            // The start of a deleted range is gone by the
+                            // time the rest is deleted
+                            let after_start = match self {
+                                Operation::Delete { .. } => *offset + inserted,
+                                _ => at + inserted,
+                            };
+                            *len = before;
+                            let mut after = operation.clone();
+                            after.set_range(after_start, after_len);
+                            return vec![operation, after];
+                        }
+                    }
+                }
+            }
+            Operation::Delete {
+                offset: at,
+                len: deleted,
+            } => {
+                let map = |offset: usize| {
+                    if offset <= *at {
+                        offset
+                    } else {
+                        (*at).max(offset.saturating_sub(*deleted))
+                    }
+                };
+                match &mut operation {
+                    Operation::Insert { offset, .. }
+                    | Operation::InsertObject { offset, .. }
+                    | Operation::SetBlockKind { offset, .. } => *offset = map(*offset),
+                    Operation::Delete { offset, len } | Operation::Format { offset, len, .. } => {
+                        let end = map(*offset + *len);
+                        *offset = map(*offset);
+                        *len = end - *offset;
+                        if *len == 0 {
+                            return vec![];
+                        }
+                    }
+                }
+            }
+            Operation::Format {
+                offset: at,
+                len: formatted,
+                change: other_change,
+            } => {
+                if let Operation::Format {
+                    offset,
+                    len,
+                    change,
+                } = &operation
+                    && first
+                    && change.same_mark(other_change)
+                {
+                    // Only the parts that `other` didn't change are kept
+                    let (start, end) = (*offset, *offset + *len);
+                    let (other_start, other_end) = (*at, at + formatted);
+                    let ranges = [(start, end.min(other_start)), (start.max(other_end), end)];
+                    return ranges
+                        .into_iter()
+                        .filter(|(start, end)| start < end)
+                        .map(|(start, end)| Operation::Format {
+                            offset: start,
+                            len: end - start,
+                            change: change.clone(),
+                        })
+                        .collect();
+                }
+            }
+            Operation::SetBlockKind { offset: at, .. } => {
+                if let Operation::SetBlockKind { offset, .. } = &operation
+                    && first
+                    && offset == at
+                {
+                    return vec![];
+                }
+            }
+        }
+        vec![operation]
+    }
+
+    /// Change the range of a delete or format operation.
+    fn set_range(&mut self, start: usize, length: usize) {
+        if let Operation::Delete { offset, len } | Operation::Format { offset, len, .. } = self {
+            *offset = start;
+            *len = length;
+        }
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '[' | ']' | '<' | '`' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Write an object as an HTML tag, which is also used in Markdown.
+fn object_tag(object: &InlineObject) -> String {
+    match object {
+        InlineObject::Image {
+            source,
+            width,
+            height,
+        } => format!(
+            "<img src=\"{}\" width=\"{width}\" height=\"{height}\">",
+            escape_html(source)
+        ),
+        InlineObject::File { name, source } => format!(
+            "<a href=\"{}\" download>{}</a>",
+            escape_html(source),
+            escape_html(name)
+        ),
+    }
+}
+
+fn markdown_run(run: &Run) -> String {
+    if let Some(object) = &run.object {
+        return object_tag(object);
+    }
+    let mut text = escape_markdown(&run.text);
+    if run.marks.underline {
+        text = format!("<u>{text}</u>");
+    }
+    if run.marks.italic {
+        text = format!("_{text}_");
+    }
+    if run.marks.bold {
+        text = format!("**{text}**");
+    }
+    if let Some(link) = &run.marks.link {
+        text = format!("[{text}]({link})");
+    }
+    text
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Get the value of an attribute in an HTML tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = tag[start..].find('"')?;
+    Some(unescape_html(&tag[start..start + end]))
+}
+
+/// Parse an object written by [`object_tag`], returning it with the text
+/// after it.
+fn parse_object(text: &str) -> Option<(InlineObject, &str)> {
+    if text.starts_with("<img ") {
+        let end = text.find('>')?;
+        let tag = &text[..end];
+        let object = InlineObject::Image {
+            source: attribute(tag, "src")?,
+            width: attribute(tag, "width")?.parse().ok()?,
+            height: attribute(tag, "height")?.parse().ok()?,
+        };
+        return Some((object, &text[end + 1..]));
+    }
+
+    let rest = text.strip_prefix("<a href=\"")?;
+    let start = rest.find("\" download>")?;
+    let name_start = start + "\" download>".len();
+    let name_end = name_start + rest[name_start..].find("</a>")?;
+    let object = InlineObject::File {
+        name: unescape_html(&rest[name_start..name_end]),
+        source: unescape_html(&rest[..start]),
+    };
+    Some((object, &rest[name_end + "</a>".len()..]))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_run(run: &Run) -> String {
+    if let Some(object) = &run.object {
+        return object_tag(object);
+    }
+    let mut text = escape_html(&run.text);
+    if run.marks.underline {
+        text = format!("<u>{text}</u>");
+    }
+    if run.marks.italic {
+        text = format!("<em>{text}</em>");
+    }
+    if run.marks.bold {
+        text = format!("<strong>{text}</strong>");
+    }
+    if let Some(link) = &run.marks.link {
+        text = format!("<a href=\"{}\">{text}</a>", escape_html(link));
+    }
+    text
+}
+
+fn parse_block(line: &str) -> Block {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    let (kind, rest) = if (1..=3).contains(&hashes) && line[hashes..].starts_with(' ') {
+        (BlockKind::Heading(hashes as u8), &line[hashes + 1..])
+    } else if let Some(rest) = line.strip_prefix("- ") {
+        (BlockKind::BulletItem, rest)
+    } else {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        match line[digits..].strip_prefix(". ") {
+            Some(rest) if digits > 0 => (BlockKind::NumberedItem, rest),
+            _ => (BlockKind::Paragraph, line),
+        }
+    };
+
+    let mut block = Block::new(kind);
+    parse_inline(rest, &Marks::default(), &mut block.runs);
+    block.normalize();
+    block
+}
+
+/// Parse the inline formatting written by [`markdown_run`].
+fn parse_inline(text: &str, marks: &Marks, runs: &mut Vec<Run>) {
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((object, after)) = parse_object(rest) {
+            runs.push(Run::new(&plain, marks.clone()));
+            plain.clear();
+            runs.push(Run::object(object));
+            rest = after;
+            continue;
+        }
+
+        let nested = if let Some(inner) = rest.strip_prefix("**") {
+            inner.find("**").map(|end| {
+                let marks = Marks {
+                    bold: true,
+                    ..marks.clone()
+                };
+                (&inner[..end], marks, &inner[end + 2..])
+            })
+        } else if let Some(inner) = rest.strip_prefix('_') {
+            find_unescaped(inner, "_").map(|end| {
+                let marks = Marks {
+                    italic: true,
+                    ..marks.clone()
+                };
+                (&inner[..end], marks, &inner[end + 1..])
+            })
+        } else if let Some(inner) = rest.strip_prefix("<u>") {
+            inner.find("</u>").map(|end| {
+                let marks = Marks {
+                    underline: true,
+                    ..marks.clone()
+                };
+                (&inner[..end], marks, &inner[end + 4..])
+            })
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            find_unescaped(inner, "](").and_then(|end| {
+                let after = &inner[end + 2..];
+                after.find(')').map(|close| {
+                    let marks = Marks {
+                        link: Some(after[..close].to_owned()),
+                        ..marks.clone()
+                    };
+                    (&inner[..end], marks, &after[close + 1..])
+                })
+            })
+        } else {
+            None
+        };
+
+        if let Some((inner, inner_marks, after)) = nested {
+            runs.push(Run::new(&plain, marks.clone()));
+            plain.clear();
+            parse_inline(inner, &inner_marks, runs);
+            rest = after;
+            continue;
+        }
+
+        if c == '\\' {
+            if let Some(escaped) = rest[1..].chars().next() {
+                plain.push(escaped);
+                rest = &rest[1 + escaped.len_utf8()..];
+                continue;
+            }
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    runs.push(Run::new(&plain, marks.clone()));
+}
+
+/// Find a pattern that isn't escaped with a backslash.
+fn find_unescaped(text: &str, pattern: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if c == '\\' {
+            escaped = true;
+        } else if text[index..].starts_with(pattern) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bold() -> Marks {
+        Marks {
+            bold: true,
+            ..Default::default()
+        }
+    }
+
+    /// Apply two operations made at the same time in both orders, and
+    /// check that both copies end up the same.
+    fn converge(markdown: &str, a: Operation, b: Operation) -> Document {
+        let mut left = Document::from_markdown(markdown);
+        let mut right = left.clone();
+        left.apply_operation(&a);
+        b.transform(&a, false)
+            .iter()
+            .for_each(|operation| left.apply_operation(operation));
+        right.apply_operation(&b);
+        a.transform(&b, true)
+            .iter()
+            .for_each(|operation| right.apply_operation(operation));
+        assert_eq!(left, right);
+        left
+    }
+
+    fn insert(offset: usize, text: &str) -> Operation {
+        Operation::Insert {
+            offset,
+            text: text.to_owned(),
+            marks: Marks::default(),
+        }
+    }
+
+    #[test]
+    fn offsets_across_blocks() {
+        let document = Document::from_markdown("One\n# Two");
+        assert_eq!(document.len(), 7);
+        assert_eq!(document.offset_of(TextPosition::new(1, 2)), 6);
+        assert_eq!(document.position_at(4), TextPosition::new(1, 0));
+        assert_eq!(document.position_at(3), TextPosition::new(0, 3));
+        assert_eq!(document.position_at(99), TextPosition::new(1, 3));
+    }
+
+    #[test]
+    fn concurrent_operations_converge() {
+        let both = converge("Hello", insert(5, "!"), insert(5, "?"));
+        assert_eq!(both.text(), "Hello!?");
+
+        let delete = Operation::Delete { offset: 1, len: 3 };
+        let inside = converge("Hello", delete.clone(), insert(2, "XY"));
+        assert_eq!(inside.text(), "HXYo");
+
+        let overlap = Operation::Delete { offset: 3, len: 2 };
+        assert_eq!(converge("Hello", delete, overlap).text(), "H");
+
+        let split = converge(
+            "One Two",
+            insert(3, "\n"),
+            Operation::Delete { offset: 2, len: 3 },
+        );
+        assert_eq!(split.text(), "On\nwo");
+    }
+
+    #[test]
+    fn concurrent_formatting_converges() {
+        let bold = Operation::Format {
+            offset: 0,
+            len: 5,
+            change: MarkChange::Bold(true),
+        };
+        let document = converge("Hello", bold.clone(), insert(2, "ww"));
+        assert_eq!(document.to_markdown(), "**He**ww**llo**");
+
+        let not_bold = Operation::Format {
+            offset: 3,
+            len: 2,
+            change: MarkChange::Bold(false),
+        };
+        let document = converge("Hello", bold, not_bold);
+        assert_eq!(document.to_markdown(), "**Hel**lo");
+
+        let heading = Operation::SetBlockKind {
+            offset: 0,
+            kind: BlockKind::Heading(1),
+        };
+        let list = Operation::SetBlockKind {
+            offset: 0,
+            kind: BlockKind::BulletItem,
+        };
+        assert_eq!(converge("Hi", heading, list).to_markdown(), "- Hi");
+    }
+
+    #[test]
+    fn insert_and_merge_runs() {
+        let mut block = Block::default();
+        block.insert(0, "Hello", &Marks::default());
+        block.insert(5, " world", &Marks::default());
+        block.insert(6, "big ", &bold());
+        assert_eq!(block.text(), "Hello big world");
+        assert_eq!(block.runs.len(), 3);
+        assert_eq!(block.marks_at(7), bold());
+        assert_eq!(block.marks_at(0), Marks::default());
+    }
+
+    #[test]
+    fn apply_marks_across_blocks() {
+        let mut document = Document::new();
+        let end = document.insert(TextPosition::default(), "one\ntwo", &Marks::default());
+        document.apply(TextPosition::new(0, 1), end, |marks| marks.bold = true);
+
+        assert_eq!(document.blocks.len(), 2);
+        assert_eq!(document.blocks[0].runs[1], Run::new("ne", bold()));
+        assert_eq!(document.blocks[1].runs, vec![Run::new("two", bold())]);
+        assert!(document.all(TextPosition::new(1, 0), end, |marks| marks.bold));
+    }
+
+    #[test]
+    fn delete_across_blocks() {
+        let mut document = Document::new();
+        document.insert(
+            TextPosition::default(),
+            "first\nsecond\nthird",
+            &Marks::default(),
+        );
+        document.delete(TextPosition::new(0, 2), TextPosition::new(2, 1));
+        assert_eq!(document.text(), "fihird");
+    }
+
+    #[test]
+    fn export_markdown() {
+        let mut document = Document::new();
+        document.insert(TextPosition::default(), "Title\na\nb\nc", &Marks::default());
+        document.blocks[0].kind = BlockKind::Heading(2);
+        document.blocks[1].kind = BlockKind::NumberedItem;
+        document.blocks[2].kind = BlockKind::NumberedItem;
+        document.blocks[3].apply(0..1, &|marks| {
+            marks.bold = true;
+            marks.link = Some(String::from("https://example.com"));
+        });
+
+        assert_eq!(
+            document.to_markdown(),
+            "## Title\n1. a\n2. b\n[**c**](https://example.com)"
+        );
+        assert_eq!(Document::from_markdown(&document.to_markdown()), document);
+    }
+
+    #[test]
+    fn export_html() {
+        let mut document = Document::new();
+        document.insert(TextPosition::default(), "<a>\none\ntwo", &Marks::default());
+        document.blocks[1].kind = BlockKind::BulletItem;
+        document.blocks[2].kind = BlockKind::BulletItem;
+        document.blocks[2].apply(0..3, &|marks| marks.italic = true);
+
+        assert_eq!(
+            document.to_html(),
+            "<p>&lt;a&gt;</p><ul><li>one</li><li><em>two</em></li></ul>"
+        );
+    }
+
+    #[test]
+    fn objects_act_like_characters() {
+        let image = InlineObject::Image {
+            source: String::from("cat.png"),
+            width: 64,
+            height: 48,
+        };
+        let mut document = Document::new();
+        document.insert(TextPosition::default(), "ab", &bold());
+        let end = document.insert_object(TextPosition::new(0, 1), image.clone());
+
+        assert_eq!(end, TextPosition::new(0, 2));
+        assert_eq!(document.blocks[0].len(), 3);
+        assert_eq!(document.blocks[0].object_at(1), Some(&image));
+        // Formatting skips objects, and text is never merged into them
+        document.apply(TextPosition::new(0, 0), TextPosition::new(0, 3), |marks| {
+            marks.bold = false
+        });
+        document.insert(TextPosition::new(0, 2), "c", &Marks::default());
+        assert_eq!(document.blocks[0].runs.len(), 3);
+
+        document.delete(TextPosition::new(0, 1), TextPosition::new(0, 2));
+        assert_eq!(document.text(), "acb");
+    }
+
+    #[test]
+    fn export_objects() {
+        let mut document = Document::new();
+        document.insert(TextPosition::default(), "See ", &Marks::default());
+        let file = InlineObject::File {
+            name: String::from("notes <draft>.pdf"),
+            source: String::from("files/notes.pdf"),
+        };
+        document.insert_object(TextPosition::new(0, 4), file);
+        let image = InlineObject::Image {
+            source: String::from("a\"b.png"),
+            width: 10,
+            height: 20,
+        };
+        document.insert_object(TextPosition::new(0, 5), image);
+
+        assert_eq!(
+            document.to_html(),
+            "<p>See <a href=\"files/notes.pdf\" download>notes &lt;draft&gt;.pdf</a>\
+            <img src=\"a&quot;b.png\" width=\"10\" height=\"20\"></p>"
+        );
+        assert_eq!(Document::from_markdown(&document.to_markdown()), document);
+    }
+
+    #[test]
+    fn markdown_round_trip_escapes() {
+        let mut document = Document::new();
+        let marks = Marks {
+            italic: true,
+            underline: true,
+            ..Default::default()
+        };
+        document.insert(TextPosition::default(), "2 * 3 = [6]_", &Marks::default());
+        document.insert(TextPosition::new(0, 12), "# done", &marks);
+        assert_eq!(Document::from_markdown(&document.to_markdown()), document);
+    }
+}
@@ -0,0 +1,233 @@
+//! Dragging typed payloads from one widget to another.
+//!
+//! A widget is made draggable with [`Widget::draggable`], with the payload
+//! that it carries, and a widget accepts drops with
+//! [`Widget::drop_target`]. Payloads are matched by type, so a drop target
+//! only accepts payloads of the type that its callback takes.
+//!
+//! A drag starts once the cursor moves a few pixels with the primary mouse
+//! button held down over a draggable widget. While dragging, a translucent
+//! copy of the widget follows the cursor and the drop target under the
+//! cursor is outlined if it accepts the payload.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::hstack;
+//!
+//! #[derive(Debug, Clone)]
+//! struct Card(u32);
+//!
+//! let board = hstack! {
+//!     Rect::new(50.0, 50.0).draggable(Card(1)),
+//!     Rect::new(200.0, 200.0).drop_target(|card: Card| println!("Dropped {card:?}")),
+//! };
+//! ```
+//!
+//! Widgets that aren't built with the wrappers can implement
+//! [`DragSource`](crate::widgets::DragSource) and
+//! [`DropHandler`](crate::widgets::DropHandler) directly.
+use crate::Resources;
+use crate::view::{DisplayList, DrawCommand, Layer, new_paint, rounded_rect_path, skia_color};
+use crate::widgets::Widget;
+use agape_core::{Color, GlobalId, Position, Rgba};
+use agape_layout::Layout;
+use std::any::Any;
+use tiny_skia::{Pixmap, Stroke, Transform};
+
+const OUTLINE_OFFSET: f32 = 2.0;
+const OUTLINE_RADIUS: f32 = 4.0;
+
+/// Tracks the payload that is being dragged.
+#[derive(Debug)]
+pub struct DragAndDrop {
+    /// How far the cursor has to move, with the button held down, before
+    /// the drag starts.
+    pub threshold: f32,
+    /// The opacity of the copy of the dragged widget.
+    pub preview_opacity: f32,
+    pub outline_color: Color<Rgba>,
+    pub outline_width: f32,
+    /// The draggable widget that the button was pressed on.
+    source: Option<GlobalId>,
+    start: Position,
+    cursor: Position,
+    payload: Option<Box<dyn Any>>,
+    /// The drop target under the cursor that accepts the payload.
+    target: Option<GlobalId>,
+}
+
+impl Default for DragAndDrop {
+    fn default() -> Self {
+        Self {
+            threshold: 4.0,
+            preview_opacity: 0.6,
+            outline_color: Color::rgb(37, 99, 235),
+            outline_width: 2.0,
+            source: None,
+            start: Position::default(),
+            cursor: Position::default(),
+            payload: None,
+            target: None,
+        }
+    }
+}
+
+impl DragAndDrop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if a payload is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.payload.is_some()
+    }
+
+    /// Get the widget that is being dragged.
+    pub fn source(&self) -> Option<GlobalId> {
+        self.source.filter(|_| self.is_dragging())
+    }
+
+    /// Get the drop target that the payload would be dropped on.
+    pub fn target(&self) -> Option<GlobalId> {
+        self.target
+    }
+
+    /// Get the payload that is being dragged.
+    pub fn payload(&self) -> Option<&dyn Any> {
+        self.payload.as_deref()
+    }
+
+    /// Remember the draggable widget that the button was pressed on.
+    pub(crate) fn press(&mut self, source: Option<GlobalId>, position: Position) {
+        self.cancel();
+        self.source = source;
+        self.start = position;
+        self.cursor = position;
+    }
+
+    /// Move the cursor, returns the widget to start dragging once the
+    /// cursor is far enough from where the button was pressed.
+    pub(crate) fn move_to(&mut self, position: Position) -> Option<GlobalId> {
+        self.cursor = position;
+        if self.is_dragging() {
+            return None;
+        }
+        let (dx, dy) = (position.x - self.start.x, position.y - self.start.y);
+        self.source
+            .filter(|_| (dx * dx + dy * dy).sqrt() > self.threshold)
+    }
+
+    pub(crate) fn begin(&mut self, payload: Box<dyn Any>) {
+        self.payload = Some(payload);
+    }
+
+    /// Set the drop target under the cursor, returns the previous target
+    /// if it changed.
+    pub(crate) fn set_target(&mut self, target: Option<GlobalId>) -> Option<Option<GlobalId>> {
+        if self.target == target {
+            return None;
+        }
+        Some(std::mem::replace(&mut self.target, target))
+    }
+
+    /// End the drag, returns the target and the payload that is dropped on
+    /// it.
+    pub(crate) fn finish(&mut self) -> Option<(GlobalId, Box<dyn Any>)> {
+        let target = self.target.take();
+        let payload = self.payload.take();
+        self.source = None;
+        target.zip(payload)
+    }
+
+    /// Stop dragging without dropping the payload.
+    pub(crate) fn cancel(&mut self) {
+        self.source = None;
+        self.payload = None;
+        self.target = None;
+    }
+
+    /// Draw the outline around the drop target and the copy of the dragged
+    /// widget.
+    pub(crate) fn render(
+        &self,
+        pixmap: &mut Pixmap,
+        widget: &dyn Widget,
+        layout: &dyn Layout,
+        resources: &Resources,
+    ) {
+        if let Some(target) = self.target.and_then(|id| layout.get(id)) {
+            self.render_outline(pixmap, target);
+        }
+
+        let Some(source) = self
+            .source()
+            .and_then(|id| widget.iter().find(|w| w.id() == id))
+        else {
+            return;
+        };
+        let mut list = DisplayList::new();
+        list.push(DrawCommand::PushLayer(Layer {
+            opacity: self.preview_opacity,
+            transform: Transform::from_translate(
+                self.cursor.x - self.start.x,
+                self.cursor.y - self.start.y,
+            ),
+            ..Default::default()
+        }));
+        list.append(DisplayList::record(source, layout));
+        list.push(DrawCommand::PopLayer);
+        list.render(pixmap, resources);
+    }
+
+    fn render_outline(&self, pixmap: &mut Pixmap, layout: &dyn Layout) {
+        let (position, size) = (layout.position(), layout.size());
+        let rect = tiny_skia::Rect::from_xywh(position.x, position.y, size.width, size.height)
+            .and_then(|rect| rect.outset(OUTLINE_OFFSET, OUTLINE_OFFSET));
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, OUTLINE_RADIUS)) else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.outline_color));
+        let stroke = Stroke {
+            width: self.outline_width,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drag_starts_past_threshold() {
+        let source = GlobalId::new();
+        let mut drag = DragAndDrop::new();
+        assert_eq!(drag.move_to(Position::unit(20.0)), None);
+
+        drag.press(Some(source), Position::unit(10.0));
+        assert_eq!(drag.move_to(Position::new(12.0, 12.0)), None);
+        assert_eq!(drag.source(), None);
+        assert_eq!(drag.move_to(Position::new(20.0, 10.0)), Some(source));
+        drag.begin(Box::new(5_u32));
+        assert_eq!(drag.source(), Some(source));
+        assert_eq!(drag.move_to(Position::new(30.0, 10.0)), None);
+    }
+
+    #[test]
+    fn finish_without_target() {
+        let target = GlobalId::new();
+        let mut drag = DragAndDrop::new();
+        drag.press(Some(GlobalId::new()), Position::default());
+        drag.begin(Box::new("card"));
+        assert_eq!(drag.set_target(Some(target)), Some(None));
+        assert_eq!(drag.set_target(Some(target)), None);
+        assert_eq!(drag.set_target(None), Some(Some(target)));
+
+        assert!(drag.finish().is_none());
+        assert!(!drag.is_dragging());
+    }
+}
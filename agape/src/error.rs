@@ -11,4 +11,22 @@ pub enum Error {
     ImageError(#[from] image::ImageError),
     #[error(transparent)]
     EventLoopError(#[from] winit::error::EventLoopError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    ClipboardError(#[from] arboard::Error),
+    #[error("Failed to load font: {0}")]
+    FontError(&'static str),
+    #[error("Expected a frame of {expected} bytes but got {actual} bytes")]
+    FrameSizeError { expected: usize, actual: usize },
+    #[error("{0:?} is not a valid shortcut")]
+    InvalidShortcut(String),
+    #[error("The shortcut {0} is already registered")]
+    ShortcutConflict(crate::shortcut::Shortcut),
+    #[cfg(feature = "global-hotkeys")]
+    #[error("The hotkey {0:?} is already registered")]
+    HotkeyConflict(crate::hotkey::Hotkey),
+    #[cfg(feature = "global-hotkeys")]
+    #[error("Failed to register a global hotkey: {0}")]
+    HotkeyError(String),
 }
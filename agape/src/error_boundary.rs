@@ -0,0 +1,112 @@
+//! Keeping a panic in one part of the ui from taking down the whole app.
+//!
+//! An [`ErrorBoundary`](crate::widgets::ErrorBoundary) catches panics that
+//! are raised while its children are laid out, recorded or drawn. The
+//! children are replaced by a fallback widget, which can offer to retry,
+//! and the error is reported to the app as a [`BoundaryError`] event.
+//! Panics raised by event handlers aren't caught.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::error_boundary::BoundaryError;
+//!
+//! let panel = Text::new("Chart").error_boundary(|error: &BoundaryError| {
+//!     let error = error.clone();
+//!     vstack! {
+//!         Text::new(&format!("Failed to draw the chart: {}", error.message)),
+//!         Button::new(Text::new("Retry")).on_click(move |_| error.retry()),
+//!     }
+//! });
+//!
+//! let app = App::new(panel).add_system(|_: &mut Resources, error: &BoundaryError| {
+//!     eprintln!("A panel failed: {}", error.message);
+//! });
+//! ```
+use crate::Resources;
+use crate::event::Event;
+use crate::view::{DisplayList, View};
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use tiny_skia::Pixmap;
+
+/// A panic caught by an error boundary, emitted as an app event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryError {
+    /// The error boundary that caught the panic.
+    pub boundary: GlobalId,
+    pub message: String,
+}
+
+impl BoundaryError {
+    /// Show the children of the error boundary again, in place of the
+    /// fallback.
+    pub fn retry(&self) {
+        Event::new().emit(RetryBoundary(self.boundary));
+    }
+}
+
+/// Shows the children of an error boundary again when it's emitted as an
+/// app event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryBoundary(pub GlobalId);
+
+/// Call `f`, catching a panic and reporting it for the `boundary`.
+pub(crate) fn catch<R>(boundary: GlobalId, f: impl FnOnce() -> R) -> Option<R> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            log::error!("Widget(id:{boundary}) caught a panic: {message}");
+            Event::new().emit(BoundaryError { boundary, message });
+            None
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    match payload.downcast_ref::<String>() {
+        Some(message) => message.clone(),
+        None => String::from("unknown panic"),
+    }
+}
+
+/// Draws the children of an error boundary, catching panics while they're
+/// drawn.
+pub(crate) struct BoundaryView {
+    id: GlobalId,
+    list: DisplayList,
+}
+
+impl BoundaryView {
+    pub fn new(id: GlobalId, list: DisplayList) -> Self {
+        Self { id, list }
+    }
+}
+
+impl View for BoundaryView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &Color::TRANSPARENT
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id;
+    }
+
+    // The children are already placed
+    fn set_size(&mut self, _: Size) {}
+
+    fn set_position(&mut self, _: Position) {}
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        catch(self.id, || self.list.render(pixmap, resources));
+    }
+}
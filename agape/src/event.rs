@@ -0,0 +1,407 @@
+//! Delivering [`WidgetEvent`]s to the widget tree.
+//!
+//! Clicks and mouse presses target the innermost widget under the cursor
+//! and then bubble up through its ancestors, to the root. Each widget
+//! handles the event with an [`EventCtx`], which can stop the event from
+//! reaching the rest of the ancestors.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//!
+//! let card = Button::new(
+//!     // The card isn't opened when the delete button is clicked
+//!     Button::new(Text::new("Delete")).on_click(|ctx| ctx.stop_propagation()),
+//! )
+//! .on_click(|_| println!("Open card"));
+//! ```
+//!
+//! # App events
+//! An [`Event`] emits values of any type, from any thread, which are
+//! delivered on the ui thread on the next frame. Systems that take the type
+//! as their event run for each value, and every widget gets it in
+//! [`Reactive::app_event`](crate::widgets::Reactive::app_event).
+//!
+//! ```
+//! use agape::prelude::*;
+//! use agape::event::Event;
+//!
+//! struct DownloadFinished(String);
+//!
+//! let finished = Event::<DownloadFinished>::new();
+//! std::thread::spawn(move || finished.emit(DownloadFinished("report.pdf".into())));
+//!
+//! let app = App::new(hstack! {}).add_system(
+//!     |_: &mut Resources, event: &DownloadFinished| println!("Downloaded {}", event.0),
+//! );
+//! ```
+use crate::Resources;
+use crate::resources::EventQueue;
+use crate::widgets::{Widget, WidgetEvent};
+use agape_core::{GlobalId, Position};
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Mutex, OnceLock};
+use winit::event::WindowEvent;
+
+type AppEvent = Box<dyn Any + Send>;
+
+/// The channel that app events are sent through, shared by every thread.
+fn app_channel() -> &'static (Sender<AppEvent>, Mutex<Receiver<AppEvent>>) {
+    static CHANNEL: OnceLock<(Sender<AppEvent>, Mutex<Receiver<AppEvent>>)> = OnceLock::new();
+    CHANNEL.get_or_init(|| {
+        let (sender, receiver) = channel();
+        (sender, Mutex::new(receiver))
+    })
+}
+
+/// Emits app events of type `T`, see the [module docs](self#app-events).
+pub struct Event<T> {
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T: Send + 'static> Event<T> {
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Send an event to the app, it's delivered on the next frame.
+    pub fn emit(&self, event: T) {
+        // The receiver lives as long as the program, so this can't fail
+        let _ = app_channel().0.send(Box::new(event));
+    }
+}
+
+impl<T: Send + 'static> Default for Event<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Event<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Event<T> {}
+
+impl<T> fmt::Debug for Event<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Event<{}>", std::any::type_name::<T>())
+    }
+}
+
+/// Deliver the app events emitted since the last call to the widget tree
+/// in the `resources`, and add them to the `queue` for the systems.
+pub(crate) fn deliver_app_events(resources: &mut Resources, queue: &mut EventQueue) {
+    let receiver = app_channel().1.lock().unwrap();
+    for event in receiver.try_iter() {
+        if let Some(root) = resources.get_mut::<Box<dyn Widget>>() {
+            let mut deliver = |widget: &mut dyn Widget| {
+                if let Some(widget) = widget.as_reactive_mut() {
+                    widget.app_event(event.as_ref());
+                }
+            };
+            deliver(root.as_mut());
+            root.traverse_mut(&mut deliver);
+        }
+        queue.push_boxed(event);
+    }
+}
+
+/// The context that a bubbling event is handled with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventCtx<'a> {
+    target: GlobalId,
+    window_event: Option<&'a WindowEvent>,
+    stopped: bool,
+}
+
+impl<'a> EventCtx<'a> {
+    pub fn new(target: GlobalId, window_event: Option<&'a WindowEvent>) -> Self {
+        Self {
+            target,
+            window_event,
+            stopped: false,
+        }
+    }
+
+    /// Get the widget that the event was sent to, before it bubbled up.
+    pub fn target(&self) -> GlobalId {
+        self.target
+    }
+
+    /// Get the window event that caused the event, if there is one.
+    pub fn window_event(&self) -> Option<&'a WindowEvent> {
+        self.window_event
+    }
+
+    /// Stop the event from bubbling up to the rest of the ancestors.
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// Deliver an event to the widgets in the tree under `root`, the
+/// `window_event` is the event that caused it.
+pub fn dispatch_event(
+    root: &mut dyn Widget,
+    event: &WidgetEvent,
+    window_event: Option<&WindowEvent>,
+) {
+    match event {
+        WidgetEvent::Clicked(id) => {
+            bubble(root, *id, window_event, |widget, ctx| widget.click(ctx));
+        }
+        WidgetEvent::Pressed(id) => {
+            bubble(root, *id, window_event, |widget, ctx| widget.press(ctx));
+        }
+        WidgetEvent::Released(id) => {
+            bubble(root, *id, window_event, |widget, ctx| widget.release(ctx));
+        }
+        WidgetEvent::MousePressed(id, button) => {
+            bubble(root, *id, window_event, |widget, ctx| {
+                widget.mouse_press(*button, ctx)
+            });
+        }
+        WidgetEvent::DoubleClicked(id) => {
+            bubble(root, *id, window_event, |widget, ctx| {
+                widget.double_click(ctx)
+            });
+        }
+        WidgetEvent::TripleClicked(id) => {
+            bubble(root, *id, window_event, |widget, ctx| {
+                widget.triple_click(ctx)
+            });
+        }
+        WidgetEvent::Scrolled(ids, delta) => {
+            bubble_event(root, ids, |widget| widget.scroll_by(*delta));
+        }
+        WidgetEvent::FilesDropped(ids, paths) => {
+            bubble_event(root, ids, |widget| {
+                widget
+                    .as_drop_handler_mut()
+                    .is_some_and(|handler| handler.drop_files(paths))
+            });
+        }
+        WidgetEvent::Tapped(id) => {
+            bubble(root, *id, window_event, |widget, ctx| {
+                let tapped = widget
+                    .as_gesture_handler_mut()
+                    .is_some_and(|handler| handler.tap(ctx));
+                if !tapped {
+                    widget.click(ctx);
+                }
+            });
+        }
+        WidgetEvent::LongPressed(id) => {
+            bubble(root, *id, window_event, |widget, ctx| {
+                let long_pressed = widget
+                    .as_gesture_handler_mut()
+                    .is_some_and(|handler| handler.long_press(ctx));
+                // Long presses usually open the same menu as right clicks
+                if !long_pressed {
+                    widget.right_click(ctx);
+                }
+            });
+        }
+        WidgetEvent::Panned(ids, delta) => {
+            bubble_event(root, ids, |widget| {
+                let panned = widget
+                    .as_gesture_handler_mut()
+                    .is_some_and(|handler| handler.pan(*delta));
+                // Scroll so that the content follows the touch
+                let lines = Position::new(
+                    delta.x / crate::PIXELS_PER_LINE,
+                    delta.y / crate::PIXELS_PER_LINE,
+                );
+                panned || widget.scroll_by(lines)
+            });
+        }
+        WidgetEvent::Pinched(ids, scale, center) => {
+            bubble_event(root, ids, |widget| {
+                widget
+                    .as_gesture_handler_mut()
+                    .is_some_and(|handler| handler.pinch(*scale, *center))
+            });
+        }
+        _ => root.handle_event(event),
+    }
+}
+
+/// Get the ids of a widget and its ancestors, from the widget up to the
+/// `root`, or an empty list if the widget isn't in the tree.
+pub fn widget_path(root: &dyn Widget, id: GlobalId) -> Vec<GlobalId> {
+    if root.id() == id {
+        return vec![id];
+    }
+    for child in root.children() {
+        let mut path = widget_path(child, id);
+        if !path.is_empty() {
+            path.push(root.id());
+            return path;
+        }
+    }
+    vec![]
+}
+
+/// Handle an event on the `target` widget and its ancestors, until one of
+/// them stops the propagation.
+fn bubble(
+    root: &mut dyn Widget,
+    target: GlobalId,
+    window_event: Option<&WindowEvent>,
+    mut handle: impl FnMut(&mut dyn Widget, &mut EventCtx),
+) {
+    let path = widget_path(root, target);
+    let mut ctx = EventCtx::new(target, window_event);
+    bubble_event(root, &path, |widget| {
+        handle(widget, &mut ctx);
+        ctx.is_propagation_stopped()
+    });
+}
+
+/// Give an event to the widgets in order, until one of them handles it.
+pub(crate) fn bubble_event(
+    root: &mut dyn Widget,
+    ids: &[GlobalId],
+    mut handle: impl FnMut(&mut dyn Widget) -> bool,
+) {
+    for id in ids {
+        let mut handled = root.id() == *id && handle(root);
+        root.traverse_mut(&mut |widget| {
+            if !handled && widget.id() == *id {
+                handled = handle(widget);
+            }
+        });
+        if handled {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::{Button, Rect, Text};
+    use crate::{hstack, vstack};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn path_to_root() {
+        let rect = Rect::new(10.0, 10.0);
+        let rect_id = rect.id();
+        let inner = hstack! {rect};
+        let inner_id = inner.id();
+        let root = vstack! {Text::new("Title"), inner};
+
+        assert_eq!(widget_path(&root, rect_id), [rect_id, inner_id, root.id()]);
+        assert!(widget_path(&root, GlobalId::new()).is_empty());
+    }
+
+    #[test]
+    fn clicks_bubble_up() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let (inner_calls, outer_calls) = (calls.clone(), calls.clone());
+        let rect = Rect::new(10.0, 10.0);
+        let rect_id = rect.id();
+        let inner = Button::new(rect).on_click(move |ctx| {
+            assert_eq!(ctx.target(), rect_id);
+            inner_calls.borrow_mut().push("inner");
+        });
+        let mut outer =
+            Button::new(inner).on_click(move |_| outer_calls.borrow_mut().push("outer"));
+
+        dispatch_event(&mut outer, &WidgetEvent::Clicked(rect_id), None);
+        assert_eq!(*calls.borrow(), ["inner", "outer"]);
+    }
+
+    #[test]
+    fn stop_propagation() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let (inner_calls, outer_calls) = (calls.clone(), calls.clone());
+        let inner = Button::new(Rect::new(10.0, 10.0)).on_click(move |ctx| {
+            ctx.stop_propagation();
+            inner_calls.borrow_mut().push("inner");
+        });
+        let inner_id = inner.id();
+        let mut outer =
+            Button::new(inner).on_click(move |_| outer_calls.borrow_mut().push("outer"));
+
+        dispatch_event(&mut outer, &WidgetEvent::Clicked(inner_id), None);
+        assert_eq!(*calls.borrow(), ["inner"]);
+    }
+
+    #[test]
+    fn deliver_app_events_from_threads() {
+        use crate::layout::{EmptyLayout, Layout};
+        use crate::view::{RectView, View};
+        use crate::widgets::Reactive;
+
+        #[derive(Debug, PartialEq)]
+        struct Finished(u32);
+
+        struct Downloads {
+            id: GlobalId,
+            finished: Rc<RefCell<Vec<u32>>>,
+        }
+
+        impl Widget for Downloads {
+            fn id(&self) -> GlobalId {
+                self.id
+            }
+
+            fn view(&self) -> Box<dyn View> {
+                Box::new(RectView::new(self.id))
+            }
+
+            fn layout(&self) -> Box<dyn Layout> {
+                let mut layout = EmptyLayout::new();
+                layout.id = self.id;
+                Box::new(layout)
+            }
+
+            fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+                Some(self)
+            }
+        }
+
+        impl Reactive for Downloads {
+            fn app_event(&mut self, event: &dyn Any) {
+                if let Some(Finished(id)) = event.downcast_ref() {
+                    self.finished.borrow_mut().push(*id);
+                }
+            }
+        }
+
+        let finished = Event::<Finished>::new();
+        std::thread::spawn(move || finished.emit(Finished(1)))
+            .join()
+            .unwrap();
+        finished.emit(Finished(2));
+
+        let downloads = Downloads {
+            id: GlobalId::new(),
+            finished: Rc::default(),
+        };
+        let delivered = downloads.finished.clone();
+        let widget: Box<dyn Widget> = Box::new(hstack! {downloads});
+        let mut resources = Resources::new();
+        resources.insert(widget);
+        let mut queue = EventQueue::new();
+        deliver_app_events(&mut resources, &mut queue);
+
+        assert_eq!(*delivered.borrow(), [1, 2]);
+        assert_eq!(queue.get_all::<Finished>(), [&Finished(1), &Finished(2)]);
+    }
+}
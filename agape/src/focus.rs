@@ -0,0 +1,206 @@
+//! Keyboard focus, the focused widget is the one that receives key input.
+//!
+//! Widgets that can be focused return `true` from
+//! [`Widget::focusable`]. Clicking a focusable widget focuses it, and Tab
+//! and Shift+Tab move the focus to the next and previous focusable widget.
+//! Widgets are visited in the order of the widget tree, except for widgets
+//! with a [`Widget::tab_index`], which come first from the lowest index.
+//!
+//! A focus ring is drawn around the focused widget when the focus was moved
+//! with the keyboard.
+use crate::view::{new_paint, rounded_rect_path, skia_color};
+use crate::widgets::Widget;
+use agape_core::{Color, GlobalId, Rgba};
+use agape_layout::Layout;
+use tiny_skia::{Pixmap, Stroke, Transform};
+use winit::keyboard::ModifiersState;
+
+/// The space between a widget and its focus ring.
+const RING_OFFSET: f32 = 2.0;
+const RING_RADIUS: f32 = 4.0;
+
+/// Tracks the focused widget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Focus {
+    focused: Option<GlobalId>,
+    /// The widget that was last sent a focus event.
+    notified: Option<GlobalId>,
+    /// Whether the focus was last moved with the keyboard.
+    keyboard: bool,
+    /// The modifiers that are held, Shift+Tab moves the focus backwards.
+    pub(crate) modifiers: ModifiersState,
+    pub ring_color: Color<Rgba>,
+    pub ring_width: f32,
+}
+
+impl Default for Focus {
+    fn default() -> Self {
+        Self {
+            focused: None,
+            notified: None,
+            keyboard: false,
+            modifiers: ModifiersState::empty(),
+            ring_color: Color::rgb(37, 99, 235),
+            ring_width: 2.0,
+        }
+    }
+}
+
+impl Focus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the focused widget.
+    pub fn focused(&self) -> Option<GlobalId> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, id: GlobalId) -> bool {
+        self.focused == Some(id)
+    }
+
+    /// Focus a widget, it isn't checked whether the widget is focusable.
+    pub fn focus(&mut self, id: GlobalId) {
+        self.focused = Some(id);
+    }
+
+    /// Remove the focus, so that no widget receives key input.
+    pub fn clear(&mut self) {
+        self.focused = None;
+    }
+
+    /// Returns `true` if the focus ring is drawn.
+    pub fn is_ring_visible(&self) -> bool {
+        self.keyboard && self.focused.is_some()
+    }
+
+    /// Move the focus to the next widget in the tab `order`, or the previous
+    /// one when going `backwards`, wrapping around at the ends.
+    pub(crate) fn tab(&mut self, order: &[GlobalId], backwards: bool) {
+        if order.is_empty() {
+            return;
+        }
+        let current = self
+            .focused
+            .and_then(|id| order.iter().position(|&other| other == id));
+        let next = match (current, backwards) {
+            (Some(index), false) => (index + 1) % order.len(),
+            (Some(index), true) => (index + order.len() - 1) % order.len(),
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+        };
+        self.focused = Some(order[next]);
+        self.keyboard = true;
+    }
+
+    /// Focus the widget that was clicked, clicking a widget that can't be
+    /// focused removes the focus.
+    pub(crate) fn click(&mut self, id: Option<GlobalId>) {
+        self.focused = id;
+        self.keyboard = false;
+    }
+
+    /// Get the widget that lost focus and the widget that gained it, since
+    /// the last time this was called.
+    pub(crate) fn take_change(&mut self) -> Option<(Option<GlobalId>, Option<GlobalId>)> {
+        if self.notified == self.focused {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.notified, self.focused);
+        Some((previous, self.focused))
+    }
+
+    /// Draw the focus ring around the focused widget.
+    pub(crate) fn render(&self, pixmap: &mut Pixmap, layout: &dyn Layout) {
+        if !self.is_ring_visible() {
+            return;
+        }
+        let Some(layout) = self.focused.and_then(|id| layout.get(id)) else {
+            return;
+        };
+        let (position, size) = (layout.position(), layout.size());
+        let rect = tiny_skia::Rect::from_xywh(position.x, position.y, size.width, size.height)
+            .and_then(|rect| rect.outset(RING_OFFSET, RING_OFFSET));
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, RING_RADIUS)) else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.ring_color));
+        let stroke = Stroke {
+            width: self.ring_width,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+/// Get the focusable widgets in the order that Tab visits them.
+pub fn tab_order(widget: &dyn Widget) -> Vec<GlobalId> {
+    let mut widgets = vec![];
+    focusable_widgets(widget, &mut widgets);
+    // The sort is stable, so widgets keep their tree order within an index
+    widgets.sort_by_key(|(index, _)| (index.is_none(), *index));
+    widgets.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Collect the focusable widgets in tree order, [`Widget::iter`] visits
+/// siblings in reverse.
+fn focusable_widgets(widget: &dyn Widget, widgets: &mut Vec<(Option<u32>, GlobalId)>) {
+    if let Some(focusable) = widget.as_focusable() {
+        widgets.push((focusable.tab_index(), widget.id()));
+    }
+    for child in widget.children() {
+        focusable_widgets(child, widgets);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hstack;
+    use crate::widgets::{Button, Rect, Text, TextField};
+
+    #[test]
+    fn tab_index_comes_first() {
+        let first = Button::new(Text::new("First"));
+        let second = TextField::new();
+        let third = Button::new(Text::new("Third")).tab_index(1);
+        let ids = [third.id(), first.id(), second.id()];
+        let widget = hstack! {first, Rect::new(10.0, 10.0), second, third};
+
+        assert_eq!(tab_order(&widget), ids);
+    }
+
+    #[test]
+    fn tab_wraps_around() {
+        let order = [GlobalId::new(), GlobalId::new(), GlobalId::new()];
+        let mut focus = Focus::new();
+        focus.tab(&order, true);
+        assert_eq!(focus.focused(), Some(order[2]));
+        focus.tab(&order, false);
+        assert_eq!(focus.focused(), Some(order[0]));
+        focus.tab(&order, false);
+        assert_eq!(focus.focused(), Some(order[1]));
+        assert!(focus.is_ring_visible());
+
+        focus.click(Some(order[2]));
+        assert!(!focus.is_ring_visible());
+    }
+
+    #[test]
+    fn focus_changes() {
+        let (a, b) = (GlobalId::new(), GlobalId::new());
+        let mut focus = Focus::new();
+        assert_eq!(focus.take_change(), None);
+
+        focus.focus(a);
+        focus.focus(b);
+        assert_eq!(focus.take_change(), Some((None, Some(b))));
+        assert_eq!(focus.take_change(), None);
+
+        focus.clear();
+        assert_eq!(focus.take_change(), Some((Some(b), None)));
+    }
+}
@@ -0,0 +1,184 @@
+//! Format currencies, percentages, sizes and times for display, shown with
+//! the [`FormattedText`](crate::widgets::FormattedText) widget.
+//!
+//! # Example
+//! ```
+//! use agape::format;
+//! use agape::locale::Locale;
+//! use std::time::Duration;
+//!
+//! let locale = Locale::default();
+//! assert_eq!(format::currency(1234.5, "$", &locale), "$1,234.50");
+//! assert_eq!(format::percent(0.256, 1, &locale), "25.6%");
+//! assert_eq!(format::bytes(1_500_000, &locale), "1.5 MB");
+//! assert_eq!(format::duration(Duration::from_secs(3900)), "1 h 5 min");
+//! ```
+use crate::locale::Locale;
+use std::time::{Duration, SystemTime};
+
+/// Format an amount of money with two decimals. The symbol comes before the
+/// amount, or after it in locales that use a decimal comma.
+pub fn currency(amount: f64, symbol: &str, locale: &Locale) -> String {
+    let number = locale.format_number(amount.abs(), 2);
+    let sign = if amount < 0.0 && number.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+    match locale.decimal_separator {
+        ',' => format!("{sign}{number}\u{a0}{symbol}"),
+        _ => format!("{sign}{symbol}{number}"),
+    }
+}
+
+/// Format a fraction as a percentage, `0.5` is `50%`.
+pub fn percent(value: f64, decimals: usize, locale: &Locale) -> String {
+    format!("{}%", locale.format_number(value * 100.0, decimals))
+}
+
+/// Format a number of bytes in the largest unit that keeps the value above
+/// one, with powers of 1000.
+pub fn bytes(bytes: u64, locale: &Locale) -> String {
+    const UNITS: [&str; 6] = ["kB", "MB", "GB", "TB", "PB", "EB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    // Whole values don't need a decimal, `2 MB` instead of `2.0 MB`
+    let decimals = if (value * 10.0).round() % 10.0 == 0.0 {
+        0
+    } else {
+        1
+    };
+    format!("{} {}", locale.format_number(value, decimals), UNITS[unit])
+}
+
+/// Format a duration with its two largest units, like `2 h 5 min`.
+pub fn duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let units = [
+        (seconds / 86_400, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "min"),
+        (seconds % 60, "s"),
+    ];
+    let Some(first) = units.iter().position(|(value, _)| *value > 0) else {
+        return "0 s".to_owned();
+    };
+    units[first..]
+        .iter()
+        .take(2)
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value} {unit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Get how far `time` is from `now`, and whether it's in the past.
+fn time_from_now(time: SystemTime, now: SystemTime) -> (Duration, bool) {
+    match now.duration_since(time) {
+        Ok(elapsed) => (elapsed, true),
+        Err(err) => (err.duration(), false),
+    }
+}
+
+/// Format how long ago a `time` was, or how long until it is, compared to
+/// `now`. Times less than a minute away are `just now`, and times a day away
+/// are `yesterday` or `tomorrow`.
+pub fn relative_time(time: SystemTime, now: SystemTime) -> String {
+    let (distance, past) = time_from_now(time, now);
+    let seconds = distance.as_secs();
+    let (value, unit) = match seconds {
+        0..60 => return "just now".to_owned(),
+        60..3600 => (seconds / 60, "min"),
+        3600..86_400 => (seconds / 3600, "h"),
+        86_400..172_800 if past => return "yesterday".to_owned(),
+        86_400..172_800 => return "tomorrow".to_owned(),
+        _ => (seconds / 86_400, "days"),
+    };
+    match past {
+        true => format!("{value} {unit} ago"),
+        false => format!("in {value} {unit}"),
+    }
+}
+
+/// Get how long until [`relative_time`] changes, so that it only needs to be
+/// formatted again then. This is a minute for times less than an hour away,
+/// an hour for times less than a day away and a day after that.
+pub fn relative_time_refresh(time: SystemTime, now: SystemTime) -> Duration {
+    let (distance, past) = time_from_now(time, now);
+    let unit: u128 = match distance.as_secs() {
+        0..3600 => 60,
+        3600..86_400 => 3600,
+        _ => 86_400,
+    } * 1_000_000_000;
+    let into_unit = distance.as_nanos() % unit;
+    match past {
+        true => Duration::from_nanos((unit - into_unit) as u64),
+        // Times in the future get closer, changing as soon as the current
+        // unit is used up
+        false => Duration::from_nanos(into_unit as u64 + 1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_currency() {
+        let us = Locale::default();
+        assert_eq!(currency(-3.5, "$", &us), "-$3.50");
+        assert_eq!(currency(-0.001, "$", &us), "$0.00");
+
+        let german = Locale::from_tag("de_DE");
+        assert_eq!(currency(1234.5, "€", &german), "1.234,50\u{a0}€");
+    }
+
+    #[test]
+    fn format_bytes() {
+        let us = Locale::default();
+        assert_eq!(bytes(999, &us), "999 B");
+        assert_eq!(bytes(2_000_000, &us), "2 MB");
+        assert_eq!(bytes(1_260_000_000, &us), "1.3 GB");
+    }
+
+    #[test]
+    fn format_duration() {
+        assert_eq!(duration(Duration::ZERO), "0 s");
+        assert_eq!(duration(Duration::from_secs(45)), "45 s");
+        assert_eq!(duration(Duration::from_secs(7200)), "2 h");
+        assert_eq!(duration(Duration::from_secs(90_061)), "1 d 1 h");
+    }
+
+    #[test]
+    fn format_relative_time() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let ago = |seconds| relative_time(now - Duration::from_secs(seconds), now);
+        assert_eq!(ago(30), "just now");
+        assert_eq!(ago(180), "3 min ago");
+        assert_eq!(ago(7200), "2 h ago");
+        assert_eq!(ago(86_400), "yesterday");
+        assert_eq!(ago(200_000), "2 days ago");
+        assert_eq!(
+            relative_time(now + Duration::from_secs(600), now),
+            "in 10 min"
+        );
+    }
+
+    #[test]
+    fn relative_time_schedule() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let refresh = |seconds| relative_time_refresh(now - Duration::from_secs(seconds), now);
+        assert_eq!(refresh(20), Duration::from_secs(40));
+        assert_eq!(refresh(150), Duration::from_secs(30));
+        assert_eq!(refresh(3700), Duration::from_secs(3500));
+        assert_eq!(refresh(86_400), Duration::from_secs(86_400));
+    }
+}
@@ -0,0 +1,48 @@
+//! Draw custom content on the gpu, like 3D scenes or shaders, with your
+//! own [`wgpu`] pipelines.
+//!
+//! Widgets are drawn on the cpu and uploaded to the window's surface as a
+//! single texture, render hooks are run after that, so they draw on top of
+//! the widgets. To draw inside a widget, use its layout bounds as the
+//! viewport of the render pass.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//! use agape::gpu::{GpuContext, wgpu};
+//! use agape::resources::Resources;
+//!
+//! fn draw(gpu: &mut GpuContext, _: &Resources) {
+//!     let pass = gpu.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+//!         label: Some("custom_render_pass"),
+//!         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+//!             view: gpu.target,
+//!             resolve_target: None,
+//!             ops: wgpu::Operations::default(),
+//!         })],
+//!         ..Default::default()
+//!     });
+//!     // Set your pipeline and draw
+//!     drop(pass);
+//! }
+//!
+//! let app = App::new(hstack! {}).add_render_hook(draw);
+//! ```
+use crate::resources::Resources;
+use agape_core::Size;
+pub use pixels::wgpu;
+
+pub(crate) type RenderHook = Box<dyn FnMut(&mut GpuContext, &Resources)>;
+
+/// The gpu state that render hooks draw with.
+pub struct GpuContext<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    /// The format of the target, which pipelines should be created with.
+    pub format: wgpu::TextureFormat,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    /// The window's surface, with the widgets already drawn onto it.
+    pub target: &'a wgpu::TextureView,
+    /// The size of the target in physical pixels.
+    pub size: Size,
+}
@@ -0,0 +1,494 @@
+//! System-wide hotkeys, which are delivered to the app even when its window
+//! isn't focused, for launchers and other utility apps.
+//!
+//! Hotkeys are registered with the [`GlobalHotkeys`] resource and are
+//! unregistered when the app exits. Global hotkeys are only supported on
+//! X11 for now, registering a hotkey on other platforms returns an error.
+//!
+//! # Example
+//! ```no_run
+//! use agape::{App, hstack};
+//! use agape::hotkey::{GlobalHotkeys, Hotkey};
+//! use agape::resources::Resources;
+//! use winit::keyboard::{KeyCode, ModifiersState};
+//!
+//! let mut registered = false;
+//! let app = App::new(hstack! {}).add_system(move |resources: &mut Resources| {
+//!     if registered {
+//!         return;
+//!     }
+//!     registered = true;
+//!     let hotkeys = resources.get_mut::<GlobalHotkeys>().unwrap();
+//!     let hotkey = Hotkey::new(ModifiersState::SUPER, KeyCode::Space);
+//!     hotkeys.register(hotkey, || println!("Opened the launcher")).unwrap();
+//! });
+//! ```
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use winit::keyboard::{KeyCode, ModifiersState};
+
+type HotkeyFn = Box<dyn FnMut()>;
+
+/// A key pressed together with modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub modifiers: ModifiersState,
+    pub key: KeyCode,
+}
+
+impl Hotkey {
+    pub fn new(modifiers: ModifiersState, key: KeyCode) -> Self {
+        Self { modifiers, key }
+    }
+}
+
+/// Registers hotkeys with the system.
+trait Backend {
+    /// Start listening for a hotkey, pressed hotkeys are sent to `sender`.
+    fn grab(&mut self, hotkey: Hotkey, sender: Sender<Hotkey>) -> Result<()>;
+
+    fn ungrab(&mut self, hotkey: Hotkey);
+}
+
+/// The hotkeys registered by the app.
+pub struct GlobalHotkeys {
+    backend: Option<Box<dyn Backend>>,
+    hotkeys: HashMap<Hotkey, HotkeyFn>,
+    sender: Sender<Hotkey>,
+    receiver: Receiver<Hotkey>,
+}
+
+impl Default for GlobalHotkeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlobalHotkeys {
+    pub fn new() -> Self {
+        Self::with_backend(system_backend())
+    }
+
+    fn with_backend(backend: Option<Box<dyn Backend>>) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            backend,
+            hotkeys: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+
+    /// Register a hotkey, `f` is called every time it's pressed.
+    ///
+    /// # Errors
+    /// Returns [`Error::HotkeyConflict`] if the hotkey is already registered,
+    /// by this app or another one, and an error if global hotkeys aren't
+    /// supported on this platform.
+    pub fn register(&mut self, hotkey: Hotkey, f: impl FnMut() + 'static) -> Result<()> {
+        if self.hotkeys.contains_key(&hotkey) {
+            return Err(Error::HotkeyConflict(hotkey));
+        }
+        let Some(backend) = &mut self.backend else {
+            return Err(Error::HotkeyError(
+                "Global hotkeys aren't supported on this platform".to_owned(),
+            ));
+        };
+        backend.grab(hotkey, self.sender.clone())?;
+        self.hotkeys.insert(hotkey, Box::new(f));
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, hotkey: Hotkey) {
+        if self.hotkeys.remove(&hotkey).is_none() {
+            return;
+        }
+        if let Some(backend) = &mut self.backend {
+            backend.ungrab(hotkey);
+        }
+    }
+
+    pub fn unregister_all(&mut self) {
+        let hotkeys: Vec<Hotkey> = self.hotkeys.keys().copied().collect();
+        for hotkey in hotkeys {
+            self.unregister(hotkey);
+        }
+    }
+
+    pub fn is_registered(&self, hotkey: Hotkey) -> bool {
+        self.hotkeys.contains_key(&hotkey)
+    }
+
+    /// Call the callbacks of the hotkeys pressed since the last dispatch.
+    pub(crate) fn dispatch(&mut self) {
+        while let Ok(hotkey) = self.receiver.try_recv() {
+            if let Some(f) = self.hotkeys.get_mut(&hotkey) {
+                f();
+            }
+        }
+    }
+}
+
+impl Drop for GlobalHotkeys {
+    fn drop(&mut self) {
+        self.unregister_all();
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn system_backend() -> Option<Box<dyn Backend>> {
+    match x11::X11Backend::connect() {
+        Ok(backend) => Some(Box::new(backend)),
+        Err(err) => {
+            log::warn!("Global hotkeys are unavailable: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn system_backend() -> Option<Box<dyn Backend>> {
+    None
+}
+
+/// Get the X11 keysym of a key.
+#[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+fn keysym(key: KeyCode) -> Option<u32> {
+    const LETTERS: [KeyCode; 26] = [
+        KeyCode::KeyA,
+        KeyCode::KeyB,
+        KeyCode::KeyC,
+        KeyCode::KeyD,
+        KeyCode::KeyE,
+        KeyCode::KeyF,
+        KeyCode::KeyG,
+        KeyCode::KeyH,
+        KeyCode::KeyI,
+        KeyCode::KeyJ,
+        KeyCode::KeyK,
+        KeyCode::KeyL,
+        KeyCode::KeyM,
+        KeyCode::KeyN,
+        KeyCode::KeyO,
+        KeyCode::KeyP,
+        KeyCode::KeyQ,
+        KeyCode::KeyR,
+        KeyCode::KeyS,
+        KeyCode::KeyT,
+        KeyCode::KeyU,
+        KeyCode::KeyV,
+        KeyCode::KeyW,
+        KeyCode::KeyX,
+        KeyCode::KeyY,
+        KeyCode::KeyZ,
+    ];
+    const DIGITS: [KeyCode; 10] = [
+        KeyCode::Digit0,
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    const FUNCTION_KEYS: [KeyCode; 12] = [
+        KeyCode::F1,
+        KeyCode::F2,
+        KeyCode::F3,
+        KeyCode::F4,
+        KeyCode::F5,
+        KeyCode::F6,
+        KeyCode::F7,
+        KeyCode::F8,
+        KeyCode::F9,
+        KeyCode::F10,
+        KeyCode::F11,
+        KeyCode::F12,
+    ];
+
+    let index = |keys: &[KeyCode]| keys.iter().position(|k| *k == key).map(|i| i as u32);
+    if let Some(i) = index(&LETTERS) {
+        return Some(0x61 + i);
+    }
+    if let Some(i) = index(&DIGITS) {
+        return Some(0x30 + i);
+    }
+    if let Some(i) = index(&FUNCTION_KEYS) {
+        return Some(0xffbe + i);
+    }
+
+    let keysym = match key {
+        KeyCode::Space => 0x20,
+        KeyCode::Backspace => 0xff08,
+        KeyCode::Tab => 0xff09,
+        KeyCode::Enter => 0xff0d,
+        KeyCode::Escape => 0xff1b,
+        KeyCode::Home => 0xff50,
+        KeyCode::ArrowLeft => 0xff51,
+        KeyCode::ArrowUp => 0xff52,
+        KeyCode::ArrowRight => 0xff53,
+        KeyCode::ArrowDown => 0xff54,
+        KeyCode::PageUp => 0xff55,
+        KeyCode::PageDown => 0xff56,
+        KeyCode::End => 0xff57,
+        KeyCode::PrintScreen => 0xff61,
+        KeyCode::Insert => 0xff63,
+        KeyCode::Delete => 0xffff,
+        _ => return None,
+    };
+    Some(keysym)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod x11 {
+    use super::{Backend, Hotkey, keysym};
+    use crate::{Error, Result};
+    use std::collections::HashMap;
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Mutex};
+    use winit::keyboard::ModifiersState;
+    use x11rb::connection::Connection;
+    use x11rb::errors::ReplyError;
+    use x11rb::protocol::xproto::{ConnectionExt, GrabMode, Keycode, ModMask, Window};
+    use x11rb::protocol::{ErrorKind, Event};
+    use x11rb::rust_connection::RustConnection;
+
+    /// Lock keys are ignored, so hotkeys are grabbed with every combination
+    /// of them.
+    const LOCK_MASKS: [u16; 4] = [0, 0x2, 0x10, 0x12];
+
+    type Grabs = Arc<Mutex<HashMap<(Keycode, u16), (Hotkey, Sender<Hotkey>)>>>;
+
+    /// Grabs hotkeys on the root window, a thread waits for the key presses.
+    pub(super) struct X11Backend {
+        connection: Arc<RustConnection>,
+        root: Window,
+        grabs: Grabs,
+    }
+
+    impl X11Backend {
+        pub fn connect() -> Result<Self> {
+            let (connection, screen) = x11rb::connect(None).map_err(hotkey_error)?;
+            let root = connection.setup().roots[screen].root;
+            let connection = Arc::new(connection);
+            let grabs: Grabs = Arc::default();
+
+            let thread_connection = Arc::clone(&connection);
+            let thread_grabs = Arc::clone(&grabs);
+            std::thread::spawn(move || {
+                while let Ok(event) = thread_connection.wait_for_event() {
+                    let Event::KeyPress(event) = event else {
+                        continue;
+                    };
+                    let state = u16::from(event.state) & !LOCK_MASKS[3];
+                    let grabs = thread_grabs.lock().unwrap();
+                    if let Some((hotkey, sender)) = grabs.get(&(event.detail, state)) {
+                        let _ = sender.send(*hotkey);
+                    }
+                }
+            });
+
+            Ok(Self {
+                connection,
+                root,
+                grabs,
+            })
+        }
+
+        fn keycode(&self, keysym: u32) -> Result<Keycode> {
+            let setup = self.connection.setup();
+            let (min, max) = (setup.min_keycode, setup.max_keycode);
+            let mapping = self
+                .connection
+                .get_keyboard_mapping(min, max - min + 1)
+                .map_err(hotkey_error)?
+                .reply()
+                .map_err(hotkey_error)?;
+
+            let per_keycode = mapping.keysyms_per_keycode.max(1) as usize;
+            mapping
+                .keysyms
+                .chunks(per_keycode)
+                .position(|keysyms| keysyms.contains(&keysym))
+                .map(|i| min + i as u8)
+                .ok_or_else(|| Error::HotkeyError("The key isn't on the keyboard".to_owned()))
+        }
+    }
+
+    impl Backend for X11Backend {
+        fn grab(&mut self, hotkey: Hotkey, sender: Sender<Hotkey>) -> Result<()> {
+            let keysym = keysym(hotkey.key)
+                .ok_or_else(|| Error::HotkeyError(format!("{:?} can't be a hotkey", hotkey.key)))?;
+            let keycode = self.keycode(keysym)?;
+            let modifiers = modifier_mask(hotkey.modifiers);
+
+            for (i, lock) in LOCK_MASKS.iter().enumerate() {
+                let result = self
+                    .connection
+                    .grab_key(
+                        false,
+                        self.root,
+                        ModMask::from(modifiers | lock),
+                        keycode,
+                        GrabMode::ASYNC,
+                        GrabMode::ASYNC,
+                    )
+                    .map_err(hotkey_error)?
+                    .check();
+
+                if let Err(err) = result {
+                    for lock in &LOCK_MASKS[..i] {
+                        let _ = self.connection.ungrab_key(
+                            keycode,
+                            self.root,
+                            ModMask::from(modifiers | lock),
+                        );
+                    }
+                    let _ = self.connection.flush();
+                    return match err {
+                        ReplyError::X11Error(err) if err.error_kind == ErrorKind::Access => {
+                            Err(Error::HotkeyConflict(hotkey))
+                        }
+                        err => Err(hotkey_error(err)),
+                    };
+                }
+            }
+
+            self.grabs
+                .lock()
+                .unwrap()
+                .insert((keycode, modifiers), (hotkey, sender));
+            Ok(())
+        }
+
+        fn ungrab(&mut self, hotkey: Hotkey) {
+            let mut grabs = self.grabs.lock().unwrap();
+            grabs.retain(|&(keycode, mask), (grabbed, _)| {
+                if *grabbed != hotkey {
+                    return true;
+                }
+                for lock in LOCK_MASKS {
+                    let _ =
+                        self.connection
+                            .ungrab_key(keycode, self.root, ModMask::from(mask | lock));
+                }
+                false
+            });
+            let _ = self.connection.flush();
+        }
+    }
+
+    fn modifier_mask(modifiers: ModifiersState) -> u16 {
+        let mut mask = ModMask::from(0u16);
+        if modifiers.shift_key() {
+            mask |= ModMask::SHIFT;
+        }
+        if modifiers.control_key() {
+            mask |= ModMask::CONTROL;
+        }
+        if modifiers.alt_key() {
+            mask |= ModMask::M1;
+        }
+        if modifiers.super_key() {
+            mask |= ModMask::M4;
+        }
+        u16::from(mask)
+    }
+
+    fn hotkey_error(err: impl std::fmt::Display) -> Error {
+        Error::HotkeyError(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    type Grabbed = Rc<RefCell<Vec<(Hotkey, Sender<Hotkey>)>>>;
+
+    /// Pretends to be the system, hotkeys that are taken can't be grabbed.
+    #[derive(Default)]
+    struct TestBackend {
+        taken: Vec<Hotkey>,
+        senders: Grabbed,
+    }
+
+    impl Backend for TestBackend {
+        fn grab(&mut self, hotkey: Hotkey, sender: Sender<Hotkey>) -> Result<()> {
+            if self.taken.contains(&hotkey) {
+                return Err(Error::HotkeyConflict(hotkey));
+            }
+            self.senders.borrow_mut().push((hotkey, sender));
+            Ok(())
+        }
+
+        fn ungrab(&mut self, hotkey: Hotkey) {
+            self.senders
+                .borrow_mut()
+                .retain(|(grabbed, _)| *grabbed != hotkey);
+        }
+    }
+
+    #[test]
+    fn deliver_pressed_hotkeys() {
+        let backend = TestBackend::default();
+        let senders = Rc::clone(&backend.senders);
+        let mut hotkeys = GlobalHotkeys::with_backend(Some(Box::new(backend)));
+
+        let pressed = Rc::new(Cell::new(0));
+        let count = Rc::clone(&pressed);
+        let hotkey = Hotkey::new(ModifiersState::CONTROL, KeyCode::Space);
+        hotkeys
+            .register(hotkey, move || count.set(count.get() + 1))
+            .unwrap();
+
+        let (_, sender) = senders.borrow()[0].clone();
+        sender.send(hotkey).unwrap();
+        sender.send(hotkey).unwrap();
+        hotkeys.dispatch();
+        assert_eq!(pressed.get(), 2);
+
+        hotkeys.unregister(hotkey);
+        assert!(!hotkeys.is_registered(hotkey));
+        assert!(senders.borrow().is_empty());
+    }
+
+    #[test]
+    fn detect_conflicts() {
+        let taken = Hotkey::new(ModifiersState::SUPER, KeyCode::KeyL);
+        let backend = TestBackend {
+            taken: vec![taken],
+            ..Default::default()
+        };
+        let mut hotkeys = GlobalHotkeys::with_backend(Some(Box::new(backend)));
+
+        let hotkey = Hotkey::new(ModifiersState::ALT, KeyCode::F4);
+        hotkeys.register(hotkey, || {}).unwrap();
+        let err = hotkeys.register(hotkey, || {}).unwrap_err();
+        assert!(matches!(err, Error::HotkeyConflict(_)));
+        let err = hotkeys.register(taken, || {}).unwrap_err();
+        assert!(matches!(err, Error::HotkeyConflict(_)));
+        assert!(!hotkeys.is_registered(taken));
+    }
+
+    #[test]
+    fn unsupported_platform() {
+        let mut hotkeys = GlobalHotkeys::with_backend(None);
+        let hotkey = Hotkey::new(ModifiersState::CONTROL, KeyCode::KeyK);
+        assert!(hotkeys.register(hotkey, || {}).is_err());
+    }
+
+    #[test]
+    fn keysyms() {
+        assert_eq!(keysym(KeyCode::KeyA), Some(0x61));
+        assert_eq!(keysym(KeyCode::Digit5), Some(0x35));
+        assert_eq!(keysym(KeyCode::F12), Some(0xffc9));
+        assert_eq!(keysym(KeyCode::Fn), None);
+    }
+}
@@ -0,0 +1,332 @@
+//! Decoded images are kept in an [`ImageCache`] so that they are only
+//! decoded once and reused across frames.
+//!
+//! Images are keyed by a hash of their encoded bytes, so the same image used
+//! by different widgets is only stored once. When the cache grows past its
+//! capacity the least recently used images are dropped. Images rendered by
+//! the app, like [textures](crate::texture), are kept until they're removed.
+//!
+//! ```
+//! use agape::image_cache::ImageCache;
+//!
+//! let cache = ImageCache::new(1024 * 1024);
+//! assert!(cache.is_empty());
+//! ```
+use crate::Result;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tiny_skia::{IntSize, Pixmap};
+
+/// The default capacity of the [`ImageCache`], in bytes.
+pub const DEFAULT_CAPACITY: usize = 64 * 1024 * 1024;
+
+/// A key to an image in the [`ImageCache`], created from the hash of the
+/// image's encoded bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImageHandle(u64);
+
+impl ImageHandle {
+    /// Create the handle for the image's encoded bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Self(hasher.finish())
+    }
+
+    /// Create a handle for an image that doesn't have encoded bytes.
+    fn unique() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let mut hasher = DefaultHasher::new();
+        ("rendered", NEXT.fetch_add(1, Ordering::Relaxed)).hash(&mut hasher);
+        Self(hasher.finish())
+    }
+}
+
+#[derive(Debug)]
+struct CachedImage {
+    pixmap: Rc<Pixmap>,
+    last_used: u64,
+    /// Rendered images can't be loaded again, so they aren't evicted.
+    pinned: bool,
+}
+
+/// A cache of decoded images, with least recently used eviction.
+#[derive(Debug)]
+pub struct ImageCache {
+    images: RefCell<HashMap<ImageHandle, CachedImage>>,
+    /// Images that could not be decoded, so that they aren't decoded again.
+    failed: RefCell<HashSet<ImageHandle>>,
+    /// The maximum size of the decoded images, in bytes.
+    capacity: usize,
+    /// The size of the decoded images, in bytes.
+    usage: Cell<usize>,
+    clock: Cell<u64>,
+}
+
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ImageCache {
+    /// Create an [`ImageCache`] that holds `capacity` bytes of decoded images.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            images: RefCell::new(HashMap::new()),
+            failed: RefCell::new(HashSet::new()),
+            capacity,
+            usage: Cell::new(0),
+            clock: Cell::new(0),
+        }
+    }
+
+    /// Decode an image and add it to the cache, images that are already
+    /// cached are not decoded again.
+    ///
+    /// # Errors
+    /// Returns an error if the image could not be decoded.
+    pub fn load(&self, bytes: &[u8]) -> Result<ImageHandle> {
+        let handle = ImageHandle::from_bytes(bytes);
+        if self.get(handle).is_some() {
+            return Ok(handle);
+        }
+
+        let pixmap = decode(bytes).inspect_err(|_| {
+            self.failed.borrow_mut().insert(handle);
+        })?;
+        self.usage.set(self.usage.get() + pixmap.data().len());
+        let image = CachedImage {
+            pixmap: Rc::new(pixmap),
+            last_used: self.tick(),
+            pinned: false,
+        };
+        self.images.borrow_mut().insert(handle, image);
+        self.evict(handle);
+        Ok(handle)
+    }
+
+    /// Add an image that was rendered instead of decoded. Since it can't be
+    /// loaded again, it's never evicted and stays in the cache until it's
+    /// removed.
+    pub fn insert(&self, pixmap: Pixmap) -> ImageHandle {
+        let handle = ImageHandle::unique();
+        self.usage.set(self.usage.get() + pixmap.data().len());
+        let image = CachedImage {
+            pixmap: Rc::new(pixmap),
+            last_used: self.tick(),
+            pinned: true,
+        };
+        self.images.borrow_mut().insert(handle, image);
+        self.evict(handle);
+        handle
+    }
+
+    /// Overwrite the pixels of an image, the pixmap is only reallocated if
+    /// its size changes or it's still being drawn. Returns `false` if the
+    /// image isn't in the cache.
+    pub(crate) fn update(
+        &self,
+        handle: ImageHandle,
+        width: u32,
+        height: u32,
+        write: impl FnOnce(&mut [u8]),
+    ) -> bool {
+        let mut images = self.images.borrow_mut();
+        let Some(image) = images.get_mut(&handle) else {
+            return false;
+        };
+        if image.pixmap.width() != width || image.pixmap.height() != height {
+            let Some(pixmap) = Pixmap::new(width, height) else {
+                return false;
+            };
+            let usage = self.usage.get() - image.pixmap.data().len() + pixmap.data().len();
+            self.usage.set(usage);
+            image.pixmap = Rc::new(pixmap);
+        }
+        write(Rc::make_mut(&mut image.pixmap).data_mut());
+        true
+    }
+
+    /// Returns `true` if the image failed to decode the last time it was
+    /// loaded.
+    pub fn has_failed(&self, handle: ImageHandle) -> bool {
+        self.failed.borrow().contains(&handle)
+    }
+
+    /// Get a decoded image, marking it as recently used.
+    pub fn get(&self, handle: ImageHandle) -> Option<Rc<Pixmap>> {
+        let tick = self.tick();
+        let mut images = self.images.borrow_mut();
+        let image = images.get_mut(&handle)?;
+        image.last_used = tick;
+        Some(Rc::clone(&image.pixmap))
+    }
+
+    /// Returns `true` if the image is in the cache.
+    pub fn contains(&self, handle: ImageHandle) -> bool {
+        self.images.borrow().contains_key(&handle)
+    }
+
+    /// Remove an image from the cache.
+    pub fn remove(&self, handle: ImageHandle) {
+        let image = self.images.borrow_mut().remove(&handle);
+        if let Some(image) = image {
+            self.usage.set(self.usage.get() - image.pixmap.data().len());
+        }
+    }
+
+    /// Get the size of all the decoded images, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.usage.get()
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.borrow().is_empty()
+    }
+
+    fn tick(&self) -> u64 {
+        let tick = self.clock.get() + 1;
+        self.clock.set(tick);
+        tick
+    }
+
+    /// Drop the least recently used images until the cache fits in its
+    /// capacity, the `keep` image and rendered images are never dropped.
+    fn evict(&self, keep: ImageHandle) {
+        while self.memory_usage() > self.capacity {
+            let oldest = self
+                .images
+                .borrow()
+                .iter()
+                .filter(|(handle, image)| **handle != keep && !image.pinned)
+                .min_by_key(|(_, image)| image.last_used)
+                .map(|(handle, _)| *handle);
+
+            let Some(oldest) = oldest else {
+                break;
+            };
+            log::trace!("Evicting image {oldest:?} from the image cache");
+            self.remove(oldest);
+        }
+    }
+}
+
+/// Decode an image into a premultiplied pixmap.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Pixmap> {
+    let image = image::load_from_memory(bytes)?.into_rgba8();
+    let (width, height) = image.dimensions();
+
+    let mut data = image.into_raw();
+    premultiply(&mut data);
+
+    // Empty images can't be turned into a pixmap
+    let size = IntSize::from_wh(width.max(1), height.max(1)).unwrap();
+    Ok(Pixmap::from_vec(data, size).unwrap_or_else(|| Pixmap::new(1, 1).unwrap()))
+}
+
+/// Premultiply RGBA pixels by their alpha in place.
+pub(crate) fn premultiply(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use std::io::Cursor;
+
+    fn png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, Rgba(color));
+        let mut bytes = Cursor::new(vec![]);
+        image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn decode_once() {
+        let cache = ImageCache::default();
+        let bytes = png(10, 10, [255, 0, 0, 255]);
+
+        let first = cache.load(&bytes).unwrap();
+        let second = cache.load(&bytes).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn premultiply_pixels() {
+        let cache = ImageCache::default();
+        let handle = cache.load(&png(1, 1, [255, 255, 255, 128])).unwrap();
+        let pixmap = cache.get(handle).unwrap();
+        let pixel = pixmap.pixel(0, 0).unwrap();
+
+        assert_eq!(pixel.alpha(), 128);
+        assert_eq!(pixel.red(), 128);
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        // Each 10x10 image is 400 bytes
+        let cache = ImageCache::new(800);
+        let first = cache.load(&png(10, 10, [255, 0, 0, 255])).unwrap();
+        let second = cache.load(&png(10, 10, [0, 255, 0, 255])).unwrap();
+
+        cache.get(first);
+        let third = cache.load(&png(10, 10, [0, 0, 255, 255])).unwrap();
+
+        assert!(cache.contains(first));
+        assert!(!cache.contains(second));
+        assert!(cache.contains(third));
+        assert_eq!(cache.memory_usage(), 800);
+    }
+
+    #[test]
+    fn keep_images_larger_than_capacity() {
+        let cache = ImageCache::new(10);
+        let handle = cache.load(&png(10, 10, [0, 0, 0, 255])).unwrap();
+        assert!(cache.contains(handle));
+    }
+
+    #[test]
+    fn invalid_image() {
+        let cache = ImageCache::default();
+        assert!(cache.load(&[1, 2, 3]).is_err());
+        assert!(cache.is_empty());
+        assert!(cache.has_failed(ImageHandle::from_bytes(&[1, 2, 3])));
+    }
+
+    #[test]
+    fn keep_rendered_images() {
+        let cache = ImageCache::new(800);
+        let rendered = cache.insert(Pixmap::new(10, 10).unwrap());
+        cache.load(&png(10, 10, [255, 0, 0, 255])).unwrap();
+        cache.load(&png(10, 10, [0, 255, 0, 255])).unwrap();
+
+        assert!(cache.contains(rendered));
+        assert_ne!(rendered, cache.insert(Pixmap::new(10, 10).unwrap()));
+    }
+
+    #[test]
+    fn track_memory_usage() {
+        let cache = ImageCache::default();
+        let first = cache.load(&png(10, 10, [255, 0, 0, 255])).unwrap();
+        cache.load(&png(5, 5, [0, 255, 0, 255])).unwrap();
+        assert_eq!(cache.memory_usage(), 500);
+
+        cache.remove(first);
+        assert_eq!(cache.memory_usage(), 100);
+    }
+}
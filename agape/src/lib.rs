@@ -2,7 +2,7 @@
 //!
 //! ## Getting started
 //! ```no_run
-//! use agape::{App,hstack,widgets::Text};
+//! use agape::prelude::*;
 //!
 //! let hstack = hstack! {
 //!     Text::new("Hello"),
@@ -28,17 +28,74 @@
 //!
 //! ## Rendering
 //! `agape` uses [`tiny_skia`](https://github.com/linebender/tiny-skia) for rendering.
+pub mod animation;
+pub mod capture;
+pub mod chart;
+pub mod clipboard;
+pub mod context;
+pub mod diagnostics;
+pub mod document;
+pub mod drag_drop;
 pub mod error;
+pub mod error_boundary;
+pub mod event;
+pub mod focus;
+pub mod format;
+pub mod gpu;
+#[cfg(feature = "global-hotkeys")]
+pub mod hotkey;
+pub mod image_cache;
+pub mod locale;
 mod macros;
+pub mod magnifier;
+pub mod modal;
+pub mod prelude;
+mod present;
+pub mod process;
 pub mod resources;
+pub mod scale;
+pub mod scheduler;
+pub mod shortcut;
+pub mod signal;
+pub mod status;
 pub mod style;
 pub mod system;
+pub mod texture;
+pub mod touch;
+#[cfg(feature = "tray")]
+pub mod tray;
 pub mod view;
+pub mod vision;
+pub mod watch;
 pub mod widgets;
+pub mod window;
 
-use crate::resources::{CursorPosition, EventQueue, WindowSize};
-use crate::view::{View, init_font};
-use crate::widgets::{StateTracker, WidgetEvent, WidgetState};
+use crate::capture::FrameCapture;
+use crate::drag_drop::DragAndDrop;
+use crate::focus::Focus;
+use crate::gpu::{GpuContext, RenderHook};
+use crate::image_cache::{ImageCache, ImageHandle};
+use crate::magnifier::Magnifier;
+use crate::modal::Modal;
+use crate::present::Presenter;
+use crate::process::{Processes, update_processes};
+use crate::resources::{
+    ClickCounter, CursorPosition, EventQueue, FileDrop, KeyboardInset, LongPress, Redraw,
+    WindowSize,
+};
+use crate::scale::UiScale;
+use crate::scheduler::{Priority, ScheduledSystem, Scheduler};
+use crate::shortcut::{Shortcut, Shortcuts};
+use crate::signal::Reader;
+use crate::texture::{ExternalTextures, update_external_textures};
+use crate::touch::{Gesture, Gestures};
+use crate::view::{
+    DisplayList, DrawCommand, FontFallback, GlyphCache, GradientCache, HitShape, Layer, LayerPool,
+    ShadowCache, init_font, set_anti_aliasing, set_font_fallback,
+};
+use crate::vision::{ColorBlindness, VisionFilter};
+use crate::watch::{FileWatcher, update_file_watcher};
+use crate::widgets::{Lifecycle, StateTracker, WidgetEvent, WidgetState};
 pub use agape_core::*;
 pub use agape_layout as layout;
 use agape_layout::{Layout, LayoutSolver};
@@ -47,37 +104,52 @@ pub use error::{Error, Result};
 pub use resources::Resources;
 use system::{IntoSystem, System};
 use widgets::Widget;
+use window::{WindowConfig, WindowFrame};
 
 use fontdue::Font;
-use pixels::{Pixels, SurfaceTexture};
+use pixels::{Pixels, PixelsBuilder, SurfaceTexture};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use tiny_skia::Pixmap;
 use winit::event_loop::ActiveEventLoop;
 use winit::{
     application::ApplicationHandler,
-    event::{ElementState, MouseButton, WindowEvent},
+    event::{ElementState, MouseButton, MouseScrollDelta, StartCause, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
+    keyboard::{Key, NamedKey, PhysicalKey},
     window::Window,
-    window::WindowId,
+    window::{CursorIcon, WindowId},
 };
 
 static FONT: OnceLock<Font> = OnceLock::new();
 
+/// How long to wait before checking again, when a frame didn't have to
+/// be drawn.
+const IDLE_INTERVAL: Duration = Duration::from_millis(16);
+
 /// An `App` is a single program.
 pub struct App<'app> {
     window: Option<Arc<Window>>,
+    /// The window's surface, when frames are presented on the ui thread.
     pixels: Option<Pixels<'app>>,
+    /// The render thread, when frames are presented on it.
+    presenter: Option<Presenter>,
     pixmap: Option<Pixmap>,
     resources: Resources,
     event_queue: EventQueue,
-    systems: Vec<Box<dyn System>>,
+    systems: Vec<ScheduledSystem>,
+    window_config: WindowConfig,
+    render_hooks: Vec<RenderHook>,
 }
 
 impl ApplicationHandler for App<'_> {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         log::info!("Initializing resources");
-        let window = event_loop.create_window(Default::default()).unwrap();
+        let window = event_loop
+            .create_window(self.window_config.attributes())
+            .unwrap();
         let window = Arc::new(window);
 
         let size = Size::from(window.inner_size());
@@ -85,42 +157,91 @@ impl ApplicationHandler for App<'_> {
         let height = size.height as u32;
 
         let surface = SurfaceTexture::new(width, height, Arc::clone(&window));
-        let pixels = Pixels::new(width, height, surface).unwrap();
+        let mut builder = PixelsBuilder::new(width, height, surface);
+        if self.window_config.is_transparent() {
+            builder = builder.clear_color(pixels::wgpu::Color::TRANSPARENT);
+        }
+        let pixels = builder.build().unwrap();
         let pixmap = Pixmap::new(width, height).unwrap();
 
-        self.pixels = Some(pixels);
+        // Render hooks use the resources, which stay on the ui thread
+        match self.render_hooks.is_empty() {
+            true => self.presenter = Some(Presenter::new(pixels, Arc::clone(&window))),
+            false => self.pixels = Some(pixels),
+        }
+        self.resources.insert(Arc::clone(&window));
+        window::set_current(Arc::clone(&window));
         self.window = Some(Arc::clone(&window));
         self.pixmap = Some(pixmap);
     }
 
+    /// Check whether a frame has to be drawn, when the wait after a frame
+    /// that wasn't drawn is over.
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            event_loop.set_control_flow(ControlFlow::Poll);
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        // Anything other than a redraw could change what's drawn
+        if event != WindowEvent::RedrawRequested {
+            self.resources.get_mut::<Redraw>().unwrap().request();
+        }
         self.event_queue.push(event.clone());
+        event::deliver_app_events(&mut self.resources, &mut self.event_queue);
 
+        self.resources.get_mut::<Scheduler>().unwrap().start_pass();
         for system in self.systems.iter_mut() {
             system.run(&mut self.resources, &self.event_queue);
         }
+        scheduler::run_tasks(&mut self.resources);
 
         match event {
-            WindowEvent::CloseRequested => {
-                log::info!("Exiting app");
-                event_loop.exit();
+            #[cfg(feature = "tray")]
+            WindowEvent::CloseRequested if tray::closes_to_tray(&self.resources) => {
+                if let Some(window) = &self.window {
+                    window.set_visible(false);
+                }
             }
+            WindowEvent::CloseRequested => self.exit(event_loop),
             WindowEvent::RedrawRequested => {
+                if !self.resources.get::<Redraw>().unwrap().is_needed() {
+                    // Nothing changed, wait for about a frame before
+                    // checking again
+                    let time = Instant::now() + IDLE_INTERVAL;
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(time));
+                    let scheduler = self.resources.get_mut::<Scheduler>().unwrap();
+                    scheduler.finish_frame(Duration::ZERO);
+                    self.event_queue.clear();
+                    return;
+                }
+                let start = Instant::now();
                 self.render();
-                self.window.as_mut().unwrap().request_redraw();
+                self.resources.get_mut::<Redraw>().unwrap().finish_frame();
+                let scheduler = self.resources.get_mut::<Scheduler>().unwrap();
+                scheduler.finish_frame(start.elapsed());
+                // The render thread requests a redraw once the frame is
+                // presented
+                if self.presenter.is_none() {
+                    self.window.as_mut().unwrap().request_redraw();
+                }
             }
             WindowEvent::Resized(size) => {
-                self.pixels
-                    .as_mut()
-                    .unwrap()
-                    .resize_surface(size.width, size.height)
-                    .expect("Failed to resize the pixel buffer");
-
-                self.pixels
-                    .as_mut()
-                    .unwrap()
-                    .resize_buffer(size.width, size.height)
-                    .expect("Failed to resize the pixel buffer");
+                if let Some(presenter) = &self.presenter {
+                    presenter.resize(size.width, size.height);
+                }
+                if let Some(pixels) = &mut self.pixels {
+                    pixels
+                        .resize_surface(size.width, size.height)
+                        .expect("Failed to resize the pixel buffer");
+                    pixels
+                        .resize_buffer(size.width, size.height)
+                        .expect("Failed to resize the pixel buffer");
+                }
 
                 let pixmap = Pixmap::new(size.width, size.height).unwrap();
                 self.pixmap = Some(pixmap);
@@ -132,16 +253,32 @@ impl ApplicationHandler for App<'_> {
 
         self.event_queue.clear();
     }
+
+    /// Handle the events of the tray icon, which wakes up the event loop
+    /// since there aren't any window events while the window is hidden.
+    #[cfg(feature = "tray")]
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, _: ()) {
+        tray::dispatch(&mut self.resources);
+        if tray::quit_requested(&self.resources) {
+            self.exit(event_loop);
+        }
+        self.resources.get_mut::<Redraw>().unwrap().request();
+    }
 }
 
 impl App<'_> {
     /// Create a new app.
     pub fn new(widget: impl Widget + 'static) -> Self {
-        FONT.set(init_font()).unwrap();
+        FONT.get_or_init(init_font);
         let len = widget.iter().count();
         log::info!("Creating widget tree with {len} widgets");
 
-        let layout = widget.layout();
+        let layout = signal::track(Reader::Layout, || widget.layout());
+        if cfg!(debug_assertions) {
+            for id in low_contrast_widgets(&widget, ContrastLevel::AA) {
+                log::warn!("Widget(id:{id}) has insufficient contrast with its background");
+            }
+        }
         let state_tracker = StateTracker::new(&widget);
         let widget: Box<dyn Widget> = Box::new(widget);
 
@@ -149,20 +286,54 @@ impl App<'_> {
         resources.insert(state_tracker);
         resources.insert(CursorPosition::default());
         resources.insert(WindowSize::default());
+        resources.insert(KeyboardInset::default());
+        resources.insert(Redraw::new());
+        resources.insert(Scheduler::new());
         resources.insert(layout);
         resources.insert(EventQueue::new());
         resources.insert(widget);
         resources.insert::<Vec<WidgetEvent>>(Vec::new());
+        resources.insert(ImageCache::default());
+        resources.insert(DragState::default());
+        resources.insert(DragAndDrop::new());
+        resources.insert(PressState::default());
+        resources.insert(CursorState::default());
+        resources.insert(GlyphCache::new());
+        resources.insert(ShadowCache::new());
+        resources.insert(GradientCache::new());
+        resources.insert(LayerPool::new());
+        resources.insert(FrameCapture::new());
+        resources.insert(FileWatcher::new());
+        resources.insert(Processes::new());
+        resources.insert(UiScale::default());
+        resources.insert(ExternalTextures::new());
+        resources.insert(ClickCounter::new());
+        resources.insert(LongPress::new());
+        resources.insert(FileDrop::new());
+        resources.insert(Shortcuts::new());
+        resources.insert(Focus::new());
+        resources.insert(Modal::new());
+        resources.insert(Magnifier::new());
+        resources.insert(VisionFilter::new());
+        resources.insert(Gestures::new());
+        #[cfg(feature = "global-hotkeys")]
+        resources.insert(hotkey::GlobalHotkeys::new());
 
-        let systems = vec![Box::new(layout_system.into_system()) as Box<dyn System>];
+        let systems = vec![
+            ScheduledSystem::new(Priority::Input, Box::new(sync_widget_tree.into_system())),
+            ScheduledSystem::new(Priority::Input, Box::new(layout_system.into_system())),
+        ];
 
         Self {
             event_queue: EventQueue::new(),
             window: None,
             pixmap: None,
             pixels: None,
+            presenter: None,
             resources,
             systems,
+            window_config: WindowConfig::default(),
+            render_hooks: Vec::new(),
         }
     }
 
@@ -182,29 +353,355 @@ impl App<'_> {
     ///     .add_system(cursor_position);
     /// ```
     pub fn add_system<Input: 'static>(mut self, f: impl IntoSystem<Input> + 'static) -> Self {
-        self.systems.push(Box::new(f.into_system()));
+        self.push_system(Priority::Input, Box::new(f.into_system()));
         self
     }
 
-    fn render(&mut self) {
+    /// Add a [`System`] that runs with a [`Priority`], systems with a low
+    /// priority are deferred when the frame is over budget. See the
+    /// [`scheduler`] module.
+    ///
+    /// Only systems without an event can be deferred, since events aren't
+    /// kept for the next frame.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::{hstack, App};
+    /// use agape::resources::Resources;
+    /// use agape::scheduler::Priority;
+    ///
+    /// fn prefetch_thumbnails(resources: &mut Resources) {}
+    ///
+    /// let app = App::new(hstack! {})
+    ///     .add_system_with_priority(Priority::Prefetch, prefetch_thumbnails);
+    /// ```
+    pub fn add_system_with_priority(
+        mut self,
+        priority: Priority,
+        f: impl FnMut(&mut Resources) + 'static,
+    ) -> Self {
+        self.push_system(priority, Box::new(f.into_system()));
+        self
+    }
+
+    /// Add a system after the ones with the same or a higher priority.
+    fn push_system(&mut self, priority: Priority, system: Box<dyn System>) {
+        let index = self
+            .systems
+            .partition_point(|system| system.priority <= priority);
+        self.systems
+            .insert(index, ScheduledSystem::new(priority, system));
+    }
+
+    /// Set the time that drawing a frame and running the systems should
+    /// take, see [`Scheduler::set_budget`].
+    pub fn frame_budget(mut self, budget: Duration) -> Self {
+        self.resources
+            .get_mut::<Scheduler>()
+            .unwrap()
+            .set_budget(budget);
+        self
+    }
+
+    /// Decode an image and add it to the [`ImageCache`] ahead of time, so
+    /// that it's not decoded when it is first drawn.
+    ///
+    /// # Errors
+    /// Returns an error if the image could not be decoded.
+    pub fn preload_image(&self, bytes: &[u8]) -> Result<ImageHandle> {
+        self.resources.get::<ImageCache>().unwrap().load(bytes)
+    }
+
+    /// Set the fonts used for characters that the bundled font doesn't have,
+    /// by default the fonts installed on the system are used.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::{App, hstack, view::FontFallback};
+    ///
+    /// let app = App::new(hstack! {})
+    ///     .font_fallback(FontFallback::new(["fonts/NotoColorEmoji.ttf"]));
+    /// ```
+    pub fn font_fallback(self, fallback: FontFallback) -> Self {
+        if !set_font_fallback(fallback) {
+            log::warn!("The font fallback was already set");
+        }
+        self
+    }
+
+    /// Draw shapes with smooth edges, which is on by default. Without
+    /// anti-aliasing, edges snap to whole pixels, which is faster to draw
+    /// and keeps straight lines crisp, but curves look jagged.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::{App, hstack};
+    ///
+    /// let app = App::new(hstack! {}).anti_aliasing(false);
+    /// ```
+    pub fn anti_aliasing(self, enabled: bool) -> Self {
+        set_anti_aliasing(enabled);
+        self
+    }
+
+    /// Add a hook that draws on the window's surface with custom
+    /// [`wgpu`](gpu::wgpu) pipelines, after the widgets are drawn. Hooks
+    /// run in the order they're added.
+    pub fn add_render_hook(mut self, f: impl FnMut(&mut GpuContext, &Resources) + 'static) -> Self {
+        self.render_hooks.push(Box::new(f));
+        self
+    }
+
+    /// Scale the whole ui, independently of the system's DPI.
+    pub fn ui_scale(mut self, scale: f32) -> Self {
+        self.resources.get_mut::<UiScale>().unwrap().set(scale);
+        self
+    }
+
+    /// Save the ui scale for the current user, so that it's restored the
+    /// next time the app is opened. The `app_id` names the directory the
+    /// scale is saved in.
+    pub fn persist_ui_scale(mut self, app_id: &str) -> Self {
+        self.resources.get_mut::<UiScale>().unwrap().persist(app_id);
+        self
+    }
+
+    /// Dump the widget tree as JSON, with the layout of the last frame, see
+    /// [`diagnostics::dump_tree`]. Widgets behind an open modal are marked
+    /// as inert.
+    pub fn dump_tree(&self) -> String {
         let widget = self.resources.get::<Box<dyn Widget>>().unwrap();
-        let mut views: Vec<Box<dyn View>> = widget.iter().map(|w| w.view()).collect();
         let layout = self.resources.get::<Box<dyn Layout>>().unwrap();
+        let modal = self.resources.get::<Modal>().and_then(Modal::current);
+        diagnostics::dump_tree_with_modal(widget.as_ref(), layout.as_ref(), modal)
+    }
 
-        let pixels = self.pixels.as_mut().unwrap();
+    /// Call a function when a keyboard shortcut, like `Mod+S`, is pressed.
+    /// See the [`shortcut`] module for how shortcuts are written.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidShortcut`] if the shortcut can't be parsed,
+    /// and [`Error::ShortcutConflict`] if it's already registered.
+    pub fn shortcut(mut self, shortcut: &str, f: impl FnMut() + 'static) -> Result<Self> {
+        let shortcut = Shortcut::parse(shortcut)?;
+        let shortcuts = self.resources.get_mut::<Shortcuts>().unwrap();
+        shortcuts.register(shortcut, f)?;
+        Ok(self)
+    }
+
+    /// Set how quickly, and how close together, clicks have to be to count
+    /// as double and triple clicks. The default is 500ms and 4 pixels.
+    pub fn click_threshold(mut self, interval: Duration, distance: f32) -> Self {
+        let counter = self.resources.get_mut::<ClickCounter>().unwrap();
+        counter.interval = interval;
+        counter.distance = distance;
+        self
+    }
+
+    /// Set how long the primary mouse button has to be held down, and how
+    /// far the cursor can move while it is, for a long press. The default
+    /// is 500ms and 8 pixels.
+    pub fn long_press_threshold(mut self, duration: Duration, distance: f32) -> Self {
+        let long_press = self.resources.get_mut::<LongPress>().unwrap();
+        long_press.duration = duration;
+        long_press.distance = distance;
+        self
+    }
+
+    /// Turn on the [`Magnifier`], which magnifies the area under the cursor
+    /// by `zoom`.
+    pub fn magnifier(mut self, zoom: f32) -> Self {
+        let magnifier = self.resources.get_mut::<Magnifier>().unwrap();
+        magnifier.set_zoom(zoom);
+        magnifier.set_enabled(true);
+        self
+    }
+
+    /// Filter the frame to simulate a [`ColorBlindness`].
+    pub fn simulate_color_blindness(mut self, simulation: ColorBlindness) -> Self {
+        let filter = self.resources.get_mut::<VisionFilter>().unwrap();
+        filter.set(Some(simulation));
+        self
+    }
+
+    /// Keep the [`SystemStatus`](status::SystemStatus) resource up to date,
+    /// with the power and network status of the system.
+    pub fn track_system_status(mut self) -> Self {
+        self.resources.insert(status::SystemStatus::read());
+        self.resources
+            .insert(status::StatusUpdated(std::time::Instant::now()));
+        self.add_system(status::update_system_status)
+    }
+
+    /// Only draw frames when something changed, instead of continuously.
+    /// Frames are drawn after window events, when a signal that a widget
+    /// read changes, or when a [`Redraw`] is requested.
+    ///
+    /// Animations that don't use signals have to request a redraw for
+    /// every frame.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::{App, hstack};
+    ///
+    /// let app = App::new(hstack! {}).redraw_on_demand();
+    /// ```
+    pub fn redraw_on_demand(mut self) -> Self {
+        self.resources
+            .get_mut::<Redraw>()
+            .unwrap()
+            .set_on_demand(true);
+        self
+    }
+
+    /// Show an icon in the system tray, see the [`tray`] module.
+    #[cfg(feature = "tray")]
+    pub fn tray(mut self, tray: tray::Tray) -> Self {
+        self.resources.insert(tray);
+        self
+    }
+
+    /// Set the settings of the window.
+    pub fn window(mut self, config: WindowConfig) -> Self {
+        self.resources.insert(config.frame());
+        if let Some(size) = config.get_size() {
+            self.resources.get_mut::<WindowSize>().unwrap().0 = size;
+        }
+        self.window_config = config;
+        self
+    }
+
+    /// Draw the current frame into an image, with the same size as the
+    /// window. Before the window is opened, the size from the
+    /// [`WindowConfig`] is used, or 800x600 if there isn't one.
+    ///
+    /// Running apps can capture frames with the
+    /// [`FrameCapture`](capture::FrameCapture) resource instead.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::prelude::*;
+    ///
+    /// let rect = Rect::new(40.0, 40.0).background_color(Color::BLACK);
+    /// let mut app = App::new(rect).window(WindowConfig::new().size(100.0, 50.0));
+    ///
+    /// let image = app.capture_frame();
+    /// assert_eq!(image.dimensions(), (100, 50));
+    /// assert_eq!(image.get_pixel(20, 20).0, [0, 0, 0, 255]);
+    /// ```
+    pub fn capture_frame(&mut self) -> image::RgbaImage {
+        let (width, height) = self.layout_capture();
+        let mut pixmap = Pixmap::new(width, height).unwrap();
+        draw_frame(&self.resources, &self.window_config, &mut pixmap);
+        capture::to_image(&pixmap)
+    }
+
+    /// Export the widgets as an svg document, laid out the same way as
+    /// [`App::capture_frame`]. Shapes are exported as svg elements, and
+    /// the rest of the widgets, like text, as embedded images.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::prelude::*;
+    ///
+    /// let rect = Rect::new(40.0, 40.0).background_color(Color::BLACK);
+    /// let mut app = App::new(rect).window(WindowConfig::new().size(100.0, 50.0));
+    ///
+    /// let svg = app.capture_svg();
+    /// assert!(svg.starts_with("<svg"));
+    /// ```
+    pub fn capture_svg(&mut self) -> String {
+        let (width, height) = self.layout_capture();
+        let size = Size::new(width as f32, height as f32);
+        let widget = self.resources.get::<Box<dyn Widget>>().unwrap();
+        let layout = self.resources.get::<Box<dyn Layout>>().unwrap();
+
+        let mut list = DisplayList::new();
+        if !self.window_config.is_transparent() {
+            list.push(DrawCommand::FillRect {
+                position: Position::default(),
+                size,
+                corner_radius: Default::default(),
+                color: Color::WHITE,
+            });
+        }
+        // Widgets are laid out in the scaled down space and drawn scaled up
+        let scale = ui_scale(&self.resources);
+        list.push(DrawCommand::PushLayer(Layer {
+            transform: tiny_skia::Transform::from_scale(scale, scale),
+            ..Default::default()
+        }));
+        list.append(DisplayList::record(widget.as_ref(), layout.as_ref()));
+        list.push(DrawCommand::PopLayer);
+        list.to_svg(size, &self.resources)
+    }
+
+    /// Lay out the widgets for a capture, returns the size of the frame.
+    fn layout_capture(&mut self) -> (u32, u32) {
+        let WindowSize(size) = self.resources.get_owned::<WindowSize>().unwrap();
+        let (width, height) = match size.width >= 1.0 && size.height >= 1.0 {
+            true => (size.width as u32, size.height as u32),
+            false => (800, 600),
+        };
+        self.resources.get_mut::<WindowSize>().unwrap().0 = Size::new(width as f32, height as f32);
+        layout_system(&mut self.resources);
+        (width, height)
+    }
+
+    fn render(&mut self) {
         let pixmap = self.pixmap.as_mut().unwrap();
-        pixmap.fill(tiny_skia::Color::WHITE);
+        draw_frame(&self.resources, &self.window_config, pixmap);
+        if let Some(capture) = self.resources.get_mut::<FrameCapture>() {
+            capture.capture(pixmap);
+        }
 
-        // Draw each view(widget) to the pixmap
-        for view in &mut views {
-            let layout = layout.get(view.id()).unwrap();
-            view.set_size(layout.size());
-            view.set_position(layout.position());
-            view.render(pixmap, &self.resources);
+        if let Some(presenter) = &self.presenter {
+            let frame = self.pixmap.take().unwrap();
+            let (width, height) = (frame.width(), frame.height());
+            let next = presenter
+                .present(frame)
+                .or_else(|| Pixmap::new(width, height));
+            self.pixmap = next;
+            return;
         }
 
+        let pixels = self.pixels.as_mut().unwrap();
         pixels.frame_mut().copy_from_slice(pixmap.data());
-        pixels.render().unwrap();
+        if self.render_hooks.is_empty() {
+            pixels.render().unwrap();
+            return;
+        }
+
+        let format = pixels.render_texture_format();
+        let size = Size::new(pixmap.width() as f32, pixmap.height() as f32);
+        let hooks = &mut self.render_hooks;
+        let resources = &self.resources;
+        pixels
+            .render_with(|encoder, target, context| {
+                context.scaling_renderer.render(encoder, target);
+                let mut gpu = GpuContext {
+                    device: &context.device,
+                    queue: &context.queue,
+                    format,
+                    encoder,
+                    target,
+                    size,
+                };
+                for hook in hooks.iter_mut() {
+                    hook(&mut gpu, resources);
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    fn exit(&mut self, event_loop: &ActiveEventLoop) {
+        log::info!("Exiting app");
+        #[cfg(feature = "global-hotkeys")]
+        if let Some(hotkeys) = self.resources.get_mut::<hotkey::GlobalHotkeys>() {
+            hotkeys.unregister_all();
+        }
+        event_loop.exit();
     }
 
     /// Run the app.
@@ -216,160 +713,2194 @@ impl App<'_> {
     pub fn run(mut self) -> Result<()> {
         self = self
             .add_system(update_cursor_position)
+            .add_system(modal::handle_open_modal)
+            .add_system(modal::handle_close_modal)
+            .add_system(handle_ui_scale)
+            .add_system(handle_magnifier)
+            .add_system(handle_vision_filter)
+            .add_system(handle_shortcuts)
+            .add_system(handle_window_resize)
+            .add_system(update_cursor_icon)
+            .add_system(handle_keyboard_inset)
             .add_system(handle_mouse_button)
+            .add_system(handle_long_press)
             .add_system(intersection_observer)
+            .add_system(handle_mouse_move)
             .add_system(handle_key_input)
-            .add_system(handle_widget_event);
+            .add_system(handle_focus)
+            .add_system(handle_modifiers)
+            .add_system(handle_drag)
+            .add_system(handle_drag_and_drop)
+            .add_system(handle_mouse_wheel)
+            .add_system(handle_touch)
+            .add_system(handle_file_drop)
+            .add_system(handle_widget_event)
+            .add_system_with_priority(Priority::Async, update_file_watcher)
+            .add_system_with_priority(Priority::Async, update_processes)
+            .add_system_with_priority(Priority::Async, signal::update_resources)
+            .add_system_with_priority(Priority::Animation, update_external_textures);
+        #[cfg(feature = "global-hotkeys")]
+        {
+            self = self.add_system(|resources: &mut Resources| {
+                if let Some(hotkeys) = resources.get_mut::<hotkey::GlobalHotkeys>() {
+                    hotkeys.dispatch();
+                }
+            });
+        }
 
         let event_loop = EventLoop::new()?;
         event_loop.set_control_flow(ControlFlow::Poll);
-        event_loop.run_app(&mut self)?;
+        #[cfg(feature = "tray")]
+        if let Some(tray) = self.resources.get_mut::<tray::Tray>() {
+            tray.set_proxy(event_loop.create_proxy());
+        }
+        for_each_lifecycle(&mut self.resources, |widget| widget.mount());
+        let result = event_loop.run_app(&mut self);
+        for_each_lifecycle(&mut self.resources, |widget| widget.unmount());
+        result?;
         Ok(())
     }
 }
 
-fn layout_system(resources: &mut Resources) {
-    // TODO update layout every frame
-    let WindowSize(size) = resources.get_owned::<WindowSize>().unwrap();
-
-    let layout: &mut Box<dyn Layout> = resources.get_mut().unwrap();
-    LayoutSolver::solve(&mut **layout, size);
-}
-
-fn update_cursor_position(resources: &mut Resources, event: &WindowEvent) {
-    if let WindowEvent::CursorMoved { position, .. } = event {
-        let cursor_position = resources.get_mut::<CursorPosition>().unwrap();
-        cursor_position.0 = Position::from(*position);
+/// Call `f` on every widget in the tree that has a [`Lifecycle`].
+fn for_each_lifecycle(resources: &mut Resources, f: impl FnMut(&mut dyn Lifecycle)) {
+    if let Some(root) = resources.get_mut::<Box<dyn Widget>>() {
+        widgets::for_each_lifecycle(root.as_mut(), f);
     }
 }
 
-fn handle_mouse_button(resources: &mut Resources, event: &WindowEvent) {
-    match event {
-        &WindowEvent::MouseInput { state, button, .. } => {
-            if state != ElementState::Pressed || button != MouseButton::Left {
-                return;
-            }
+/// Let the widgets whose signals changed update themselves, before the
+/// layout is synced.
+fn notify_signals_changed(root: &mut dyn Widget, ids: &HashSet<GlobalId>) {
+    let mut notify = |widget: &mut dyn Widget| {
+        if !ids.contains(&widget.id()) {
+            return;
         }
-        _ => return,
-    }
+        if let Some(widget) = widget.as_reactive_mut() {
+            widget.signals_changed();
+        }
+    };
+    notify(root);
+    root.traverse_mut(&mut notify);
+}
 
+/// Clear the pixmap and draw the window frame and widgets onto it.
+fn draw_frame(resources: &Resources, config: &WindowConfig, pixmap: &mut Pixmap) {
+    let widget = resources.get::<Box<dyn Widget>>().unwrap();
     let layout = resources.get::<Box<dyn Layout>>().unwrap();
-    let cursor_position: &CursorPosition = resources.get().unwrap();
 
-    let ids: Vec<GlobalId> = layout
-        .iter()
-        .filter(|l| l.bounds().within(&cursor_position.0))
-        .map(|l| l.id())
-        .collect();
+    match config.is_transparent() {
+        true => pixmap.fill(tiny_skia::Color::TRANSPARENT),
+        false => pixmap.fill(tiny_skia::Color::WHITE),
+    }
+    if let Some(frame) = resources.get::<WindowFrame>() {
+        frame.render(pixmap, resources.get::<ShadowCache>());
+    }
 
-    let state_tracker = resources.get_mut::<StateTracker>().unwrap();
-    for id in &ids {
-        state_tracker.update_state(*id, WidgetState::Clicked);
+    let draw = |pixmap: &mut Pixmap| {
+        render_widget(widget.as_ref(), layout.as_ref(), pixmap, resources);
+        if let Some(modal) = resources.get::<Modal>() {
+            modal.render(pixmap, widget.as_ref(), layout.as_ref(), resources);
+        }
+        if let Some(focus) = resources.get::<Focus>() {
+            focus.render(pixmap, layout.as_ref());
+        }
+        if let Some(drag) = resources.get::<DragAndDrop>() {
+            drag.render(pixmap, widget.as_ref(), layout.as_ref(), resources);
+        }
+    };
+    let scale = ui_scale(resources);
+    if scale == 1.0 {
+        draw(pixmap);
+    } else {
+        // Draw the widgets at their layout size, then scale them up
+        let width = (pixmap.width() as f32 / scale).ceil() as u32;
+        let height = (pixmap.height() as f32 / scale).ceil() as u32;
+        let Some(mut scaled) = Pixmap::new(width, height) else {
+            return;
+        };
+        draw(&mut scaled);
+        let paint = tiny_skia::PixmapPaint {
+            quality: tiny_skia::FilterQuality::Bilinear,
+            ..Default::default()
+        };
+        let transform = tiny_skia::Transform::from_scale(scale, scale);
+        pixmap.draw_pixmap(0, 0, scaled.as_ref(), &paint, transform, None);
     }
 
-    let event_queue = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
-    for id in ids {
-        event_queue.push(WidgetEvent::Clicked(id));
+    if let Some(filter) = resources.get::<VisionFilter>() {
+        filter.apply(pixmap);
+    }
+    // The loupe magnifies the finished frame
+    if let Some(magnifier) = resources.get::<Magnifier>() {
+        let CursorPosition(cursor) = resources.get_owned().unwrap();
+        let cursor = Position::new(cursor.x * scale, cursor.y * scale);
+        magnifier.render(pixmap, cursor);
     }
 }
 
-fn handle_key_input(resources: &mut Resources, event: &WindowEvent) {
-    if let WindowEvent::KeyboardInput { event, .. } = event {
-        let events = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
-        events.push(WidgetEvent::KeyInput(event.clone()));
+/// Draw a widget and its children, by recording them to a [`DisplayList`].
+pub(crate) fn render_widget(
+    widget: &dyn Widget,
+    layout: &dyn Layout,
+    pixmap: &mut Pixmap,
+    resources: &Resources,
+) {
+    DisplayList::record(widget, layout).render(pixmap, resources);
+}
+
+/// Find the widgets whose foreground doesn't have enough contrast with the
+/// background behind them.
+fn low_contrast_widgets(widget: &dyn Widget, level: ContrastLevel) -> Vec<GlobalId> {
+    let mut ids = vec![];
+    // The window is cleared to white before drawing
+    let background = opaque_background(widget, Color::WHITE);
+    check_contrast(widget, background, level, &mut ids);
+    ids
+}
+
+/// Get the background a widget's children are drawn on, transparent
+/// backgrounds show whatever is behind them.
+fn opaque_background(widget: &dyn Widget, behind: Color<Rgba>) -> Color<Rgba> {
+    let view = widget.view();
+    if view.foreground_color().is_none() && view.color().a() == 100 {
+        view.color().clone()
+    } else {
+        behind
     }
 }
 
-fn intersection_observer(resources: &mut Resources) {
-    let cursor_pos = resources.get::<CursorPosition>().unwrap();
-    let layout = resources.get::<Box<dyn Layout>>().unwrap();
+fn check_contrast(
+    parent: &dyn Widget,
+    background: Color<Rgba>,
+    level: ContrastLevel,
+    ids: &mut Vec<GlobalId>,
+) {
+    for child in parent.children() {
+        let view = child.view();
+        if let Some(foreground) = view.foreground_color() {
+            if !foreground.meets_contrast(&background, level) {
+                ids.push(child.id());
+            }
+        }
 
-    // TODO combine both iters and just use a for loop
-    let hovered_ids: Vec<GlobalId> = layout
-        .iter()
-        .filter(|l| l.bounds().within(&cursor_pos.0))
-        .map(|l| l.id())
-        .collect();
+        let background = opaque_background(child, background.clone());
+        check_contrast(child, background, level, ids);
+    }
+}
 
-    let not_hovered: Vec<GlobalId> = layout
-        .iter()
-        .filter(|l| !hovered_ids.contains(&l.id()))
-        .map(|l| l.id())
+/// Keep the layout and the tracked widget states in sync with the widget
+/// tree, when widgets are added or removed while the app is running.
+fn sync_widget_tree(resources: &mut Resources) {
+    // Only the widgets that read a signal that changed are drawn again, the
+    // layout is built again if it read one
+    let dirty = signal::take_dirty();
+    let layout_dirty = dirty.contains(&Reader::Layout);
+    let views: HashSet<GlobalId> = dirty
+        .into_iter()
+        .filter_map(|reader| match reader {
+            Reader::View(id) => Some(id),
+            Reader::Layout => None,
+        })
         .collect();
+    if !views.is_empty() {
+        if let Some(root) = resources.get_mut::<Box<dyn Widget>>() {
+            notify_signals_changed(root.as_mut(), &views);
+        }
+    }
+    if let Some(redraw) = resources.get_mut::<Redraw>() {
+        redraw.mark_dirty(views);
+    }
 
-    let state = resources.get_mut::<StateTracker>().unwrap();
-    for id in &hovered_ids {
-        state.update_state(*id, WidgetState::Hovered);
+    let Some(widget) = resources.get::<Box<dyn Widget>>() else {
+        return;
+    };
+    let Some(state_tracker) = resources.get::<StateTracker>() else {
+        return;
+    };
+    let changed = layout_dirty
+        || widget.iter().count() != state_tracker.len()
+        || widget.iter().any(|w| !state_tracker.contains(w.id()));
+    if !changed {
+        return;
     }
 
-    for id in &not_hovered {
-        state.update_state(*id, WidgetState::Resting);
+    let layout = signal::track(Reader::Layout, || widget.layout());
+    let mut state_tracker = resources.get_owned::<StateTracker>().unwrap();
+    let removed = state_tracker.sync(widget.as_ref());
+    *resources.get_mut::<StateTracker>().unwrap() = state_tracker;
+    *resources.get_mut::<Box<dyn Layout>>().unwrap() = layout;
+    if let Some(redraw) = resources.get_mut::<Redraw>() {
+        redraw.request();
+    }
+    if removed.is_empty() {
+        return;
     }
 
-    let state = resources.get::<StateTracker>().unwrap();
-    let mut events = vec![];
-    for id in &hovered_ids {
-        if state.previous_state(*id).unwrap() == &WidgetState::Resting {
-            events.push(WidgetEvent::Hovered(*id));
+    // Forget the removed widgets, so events aren't sent to them
+    if let Some(focus) = resources.get_mut::<Focus>() {
+        if focus.focused().is_some_and(|id| removed.contains(&id)) {
+            focus.clear();
         }
     }
-
-    let widget_events: &mut Vec<WidgetEvent> = resources.get_mut().unwrap();
-    widget_events.extend(events);
-}
-
-fn handle_widget_event(resources: &mut Resources) {
-    let events: Vec<WidgetEvent> = resources.get_owned().unwrap();
-    let widget: &mut Box<dyn Widget> = resources.get_mut().unwrap();
-
-    for event in events {
-        widget.handle_event(&event);
+    if let Some(press) = resources.get_mut::<PressState>() {
+        press.ids.retain(|id| !removed.contains(id));
+    }
+    if let Some(drag) = resources.get_mut::<DragState>() {
+        drag.ids.retain(|id| !removed.contains(id));
+    }
+    if let Some(file_drop) = resources.get_mut::<FileDrop>() {
+        file_drop.targets.retain(|id| !removed.contains(id));
+    }
+    if let Some(drag) = resources.get_mut::<DragAndDrop>() {
+        let ids = [drag.source(), drag.target()];
+        if ids.iter().flatten().any(|id| removed.contains(id)) {
+            drag.cancel();
+        }
     }
-
-    resources.get_mut::<Vec<WidgetEvent>>().unwrap().clear();
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::hstack;
-    use crate::widgets::Rect;
+fn layout_system(resources: &mut Resources) {
+    // TODO update layout every frame
+    let WindowSize(size) = resources.get_owned::<WindowSize>().unwrap();
+    // Undecorated windows leave space around the content for their shadow
+    let (position, size) = match resources.get::<WindowFrame>() {
+        Some(frame) => frame.content(size),
+        None => (Position::default(), size),
+    };
+    // Widgets are laid out in the scaled down space and drawn scaled up
+    let scale = ui_scale(resources);
+    let position = Position::new(position.x / scale, position.y / scale);
+    let size = Size::new(size.width / scale, size.height / scale);
 
-    #[test]
-    fn widget_hover_system() {
-        let rect = Rect::new(100.0, 100.0);
-        let mut layout = rect.layout();
-        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+    let inset = resources.get_owned::<KeyboardInset>().unwrap_or_default().0 / scale;
+    let focused = resources.get::<Focus>().and_then(Focus::focused);
 
-        let state_tracker = StateTracker::new(&rect);
-        let mut resources = Resources::new();
-        resources.insert(layout);
-        resources.insert(state_tracker);
-        resources.insert(CursorPosition(Position::unit(50.0)));
-        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+    let layout: &mut Box<dyn Layout> = resources.get_mut().unwrap();
+    layout.set_position(position);
+    LayoutSolver::solve(&mut **layout, size);
 
-        intersection_observer(&mut resources);
+    // Move the content up so that the focused widget isn't under the
+    // keyboard, but not so far that its top goes off the screen
+    if inset <= 0.0 {
+        return;
+    }
+    let Some(bounds) = focused.and_then(|id| layout.get(id)).map(|l| l.bounds()) else {
+        return;
+    };
+    let visible_bottom = position.y + size.height - inset;
+    let offset = (bounds.y[1] - visible_bottom).min(bounds.y[0] - position.y);
+    if offset > 0.0 {
+        layout.set_position(Position::new(position.x, position.y - offset));
+        LayoutSolver::solve(&mut **layout, size);
+    }
+}
 
-        let events: &Vec<WidgetEvent> = resources.get().unwrap();
-        assert!(events.contains(&WidgetEvent::Hovered(rect.id())));
+/// Update the [`KeyboardInset`] when it's emitted as an app event.
+fn handle_keyboard_inset(resources: &mut Resources, inset: &KeyboardInset) {
+    if let Some(current) = resources.get_mut::<KeyboardInset>() {
+        *current = *inset;
     }
+}
 
-    #[test]
-    fn layout_system_works() {
-        let hstack = hstack! {}.fill();
-        let layout = hstack.layout();
+/// The cursor icon that was last shown by [`update_cursor_icon`].
+#[derive(Debug, Default)]
+struct CursorState {
+    icon: Option<CursorIcon>,
+}
 
-        let mut resources = Resources::new();
-        resources.insert(layout);
-        resources.insert(WindowSize(Size::unit(500.0)));
+/// Show the cursor icon of the widget under the cursor.
+fn update_cursor_icon(resources: &mut Resources) {
+    // The edges of undecorated windows show resize arrows instead
+    let resizing = resources
+        .get::<WindowFrame>()
+        .is_some_and(|frame| frame.hovered.is_some());
+    let icon = match resizing {
+        true => None,
+        false => Some(hovered_cursor_icon(resources)),
+    };
+    let state = resources.get_mut::<CursorState>().unwrap();
+    if state.icon == icon {
+        return;
+    }
+    state.icon = icon;
+    if let (Some(icon), Some(window)) = (icon, resources.get::<Arc<Window>>()) {
+        window.set_cursor(icon);
+    }
+}
 
-        layout_system(&mut resources);
+/// Get the icon of the innermost widget under the cursor that has one.
+/// When widgets cover the same area, like a widget wrapped with
+/// [`Widget::cursor`], the outer icon wins because it was set explicitly.
+fn hovered_cursor_icon(resources: &Resources) -> CursorIcon {
+    if resources
+        .get::<DragAndDrop>()
+        .is_some_and(DragAndDrop::is_dragging)
+    {
+        return CursorIcon::Grabbing;
+    }
+    let ids = hovered_widgets(resources);
+    let (Some(root), Some(layout)) = (
+        resources.get::<Box<dyn Widget>>(),
+        resources.get::<Box<dyn Layout>>(),
+    ) else {
+        return CursorIcon::Default;
+    };
 
-        let layout = resources.get::<Box<dyn Layout>>().unwrap();
-        assert_eq!(layout.size(), Size::unit(500.0));
+    let mut icon = None;
+    let mut icon_bounds = None;
+    for id in ids {
+        let bounds = layout.get(id).map(|layout| layout.bounds());
+        if icon.is_some() && bounds != icon_bounds {
+            break;
+        }
+        if let Some(widget_icon) = root
+            .get(id)
+            .and_then(|w| w.as_pointer_target())
+            .and_then(|w| w.cursor_icon())
+        {
+            icon = Some(widget_icon);
+            icon_bounds = bounds;
+        }
     }
+    icon.unwrap_or_default()
+}
 
-    #[test]
+/// Resize undecorated windows when their edges are dragged.
+fn handle_window_resize(resources: &mut Resources, event: &WindowEvent) {
+    let WindowSize(size) = resources.get_owned::<WindowSize>().unwrap();
+    let Some(frame) = resources.get_mut::<WindowFrame>() else {
+        return;
+    };
+
+    match event {
+        WindowEvent::CursorMoved { position, .. } => {
+            let direction = frame.resize_direction(Position::from(*position), size);
+            if direction == frame.hovered {
+                return;
+            }
+            frame.hovered = direction;
+            if let Some(window) = resources.get::<Arc<Window>>() {
+                let icon = direction.map_or(CursorIcon::Default, CursorIcon::from);
+                window.set_cursor(icon);
+            }
+        }
+        &WindowEvent::MouseInput {
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+            ..
+        } => {
+            let Some(direction) = frame.hovered else {
+                return;
+            };
+            let Some(window) = resources.get::<Arc<Window>>() else {
+                return;
+            };
+            if let Err(err) = window.drag_resize_window(direction) {
+                log::warn!("Failed to resize the window: {err}");
+            }
+        }
+        _ => {}
+    }
+}
+
+fn update_cursor_position(resources: &mut Resources, event: &WindowEvent) {
+    if let WindowEvent::CursorMoved { position, .. } = event {
+        let scale = ui_scale(resources);
+        let cursor_position = resources.get_mut::<CursorPosition>().unwrap();
+        cursor_position.0 = Position::new(position.x as f32 / scale, position.y as f32 / scale);
+    }
+}
+
+fn ui_scale(resources: &Resources) -> f32 {
+    resources.get::<UiScale>().map_or(1.0, UiScale::get)
+}
+
+fn handle_ui_scale(resources: &mut Resources, event: &WindowEvent) {
+    let Some(scale) = resources.get_mut::<UiScale>() else {
+        return;
+    };
+    match event {
+        WindowEvent::ModifiersChanged(modifiers) => scale.modifiers_changed(modifiers.state()),
+        WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+            scale.handle_key(&event.logical_key)
+        }
+        _ => {}
+    }
+}
+
+fn handle_magnifier(resources: &mut Resources, event: &WindowEvent) {
+    let Some(magnifier) = resources.get_mut::<Magnifier>() else {
+        return;
+    };
+    match event {
+        WindowEvent::ModifiersChanged(modifiers) => magnifier.modifiers_changed(modifiers.state()),
+        WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+            if let PhysicalKey::Code(key) = event.physical_key {
+                magnifier.handle_key(key);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_vision_filter(resources: &mut Resources, event: &WindowEvent) {
+    let Some(filter) = resources.get_mut::<VisionFilter>() else {
+        return;
+    };
+    match event {
+        WindowEvent::ModifiersChanged(modifiers) => filter.modifiers_changed(modifiers.state()),
+        WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+            if let PhysicalKey::Code(key) = event.physical_key {
+                filter.handle_key(key);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_shortcuts(resources: &mut Resources, event: &WindowEvent) {
+    let Some(shortcuts) = resources.get_mut::<Shortcuts>() else {
+        return;
+    };
+    let key = match event {
+        WindowEvent::ModifiersChanged(modifiers) => {
+            shortcuts.modifiers_changed(modifiers.state());
+            return;
+        }
+        WindowEvent::KeyboardInput { event, .. }
+            if event.state == ElementState::Pressed && !event.repeat =>
+        {
+            match event.physical_key {
+                PhysicalKey::Code(key) => key,
+                PhysicalKey::Unidentified(_) => return,
+            }
+        }
+        _ => return,
+    };
+    let shortcut = shortcuts.handle_key(key);
+
+    // Widgets with the shortcut are clicked
+    let Some(widget) = resources.get::<Box<dyn Widget>>() else {
+        return;
+    };
+    let modal = resources.get::<Modal>();
+    let ids: Vec<GlobalId> = widget
+        .iter()
+        .filter(|child| {
+            let keyboard = child.as_keyboard_input();
+            keyboard.and_then(|child| child.keyboard_shortcut()) == Some(shortcut)
+        })
+        .map(|child| child.id())
+        .filter(|&id| !modal.is_some_and(|modal| modal.is_inert(widget.as_ref(), id)))
+        .collect();
+    resources
+        .get_mut::<Vec<WidgetEvent>>()
+        .unwrap()
+        .extend(ids.into_iter().map(WidgetEvent::Clicked));
+}
+
+/// Returns `true` if the focused widget uses the Tab key.
+fn focused_accepts_tab(resources: &Resources) -> bool {
+    let Some(id) = resources.get::<Focus>().and_then(Focus::focused) else {
+        return false;
+    };
+    resources
+        .get::<Box<dyn Widget>>()
+        .and_then(|widget| widget.get(id))
+        .and_then(|widget| widget.as_focusable())
+        .is_some_and(|widget| widget.accepts_tab())
+}
+
+fn handle_focus(resources: &mut Resources, event: &WindowEvent) {
+    match event {
+        WindowEvent::ModifiersChanged(modifiers) => {
+            resources.get_mut::<Focus>().unwrap().modifiers = modifiers.state();
+        }
+        WindowEvent::MouseInput {
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+            ..
+        } => {
+            let hovered = hovered_widgets(resources);
+            let widget = resources.get::<Box<dyn Widget>>().unwrap();
+            let target = hovered.into_iter().find(|&id| {
+                widget
+                    .get(id)
+                    .is_some_and(|widget| widget.as_focusable().is_some())
+            });
+            resources.get_mut::<Focus>().unwrap().click(target);
+        }
+        WindowEvent::KeyboardInput { event, .. }
+            if event.state == ElementState::Pressed
+                && event.logical_key == Key::Named(NamedKey::Tab)
+                && !focused_accepts_tab(resources) =>
+        {
+            let widget = resources.get::<Box<dyn Widget>>().unwrap();
+            // Tab stays within an open modal
+            let root = resources
+                .get::<Modal>()
+                .and_then(Modal::current)
+                .and_then(|id| widget.get(id))
+                .unwrap_or(widget.as_ref());
+            let order = focus::tab_order(root);
+            let focus = resources.get_mut::<Focus>().unwrap();
+            let backwards = focus.modifiers.shift_key();
+            focus.tab(&order, backwards);
+        }
+        _ => {}
+    }
+
+    let change = resources.get_mut::<Focus>().unwrap().take_change();
+    if let Some((previous, focused)) = change {
+        let events = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+        events.extend(previous.map(WidgetEvent::Unfocused));
+        events.extend(focused.map(WidgetEvent::Focused));
+    }
+}
+
+/// The widgets under the cursor when the primary mouse button was pressed,
+/// from the innermost outwards.
+#[derive(Debug, Default)]
+struct PressState {
+    ids: Vec<GlobalId>,
+}
+
+fn handle_mouse_button(resources: &mut Resources, event: &WindowEvent) {
+    let (state, button) = match event {
+        &WindowEvent::MouseInput { state, button, .. } => (state, button),
+        _ => return,
+    };
+
+    // The events go to the innermost widget and bubble up from there,
+    // layouts without a widget are skipped
+    let widget = resources.get::<Box<dyn Widget>>();
+    let ids: Vec<GlobalId> = hovered_widgets(resources)
+        .into_iter()
+        .filter(|&id| widget.is_none_or(|widget| widget.get(id).is_some()))
+        .collect();
+
+    if state == ElementState::Released {
+        // Only the primary button clicks
+        if button == MouseButton::Left {
+            release(resources, &ids);
+        }
+        return;
+    }
+
+    let Some(&target) = ids.first() else {
+        return;
+    };
+    let event_queue = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+    event_queue.push(WidgetEvent::MousePressed(target, button));
+    if button != MouseButton::Left {
+        return;
+    }
+    event_queue.push(WidgetEvent::Pressed(target));
+    let CursorPosition(cursor_position) = *resources.get().unwrap();
+    if let Some(long_press) = resources.get_mut::<LongPress>() {
+        long_press.press(Instant::now(), cursor_position);
+    }
+
+    let state_tracker = resources.get_mut::<StateTracker>().unwrap();
+    for id in &ids {
+        state_tracker.update_state(*id, WidgetState::Clicked);
+    }
+    resources.get_mut::<PressState>().unwrap().ids = ids;
+}
+
+/// Release the pressed widget and click the innermost widget that the
+/// cursor was over both when the button was pressed and released, if there
+/// is one. Moving off a button before releasing cancels its click.
+fn release(resources: &mut Resources, ids: &[GlobalId]) {
+    let pressed = std::mem::take(&mut resources.get_mut::<PressState>().unwrap().ids);
+    let Some(&pressed_target) = pressed.first() else {
+        return;
+    };
+    let event_queue = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+    event_queue.push(WidgetEvent::Released(pressed_target));
+    // Long presses replace the click
+    let long_pressed = resources
+        .get_mut::<LongPress>()
+        .is_some_and(LongPress::release);
+    if long_pressed {
+        return;
+    }
+    let Some(target) = pressed.into_iter().find(|id| ids.contains(id)) else {
+        return;
+    };
+
+    let CursorPosition(cursor_position) = *resources.get().unwrap();
+    let count = resources
+        .get_mut::<ClickCounter>()
+        .map_or(1, |counter| counter.click(Instant::now(), cursor_position));
+
+    let event_queue = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+    event_queue.push(WidgetEvent::Clicked(target));
+    match count {
+        2 => event_queue.push(WidgetEvent::DoubleClicked(target)),
+        3 => event_queue.push(WidgetEvent::TripleClicked(target)),
+        _ => {}
+    }
+}
+
+/// Send a long press to the pressed widget once the primary mouse button
+/// has been held down long enough.
+fn handle_long_press(resources: &mut Resources) {
+    let CursorPosition(cursor_position) = *resources.get().unwrap();
+    let long_pressed = resources
+        .get_mut::<LongPress>()
+        .is_some_and(|long_press| long_press.update(Instant::now(), cursor_position));
+    if !long_pressed {
+        return;
+    }
+    let Some(&target) = resources.get::<PressState>().unwrap().ids.first() else {
+        return;
+    };
+    let event_queue = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+    event_queue.push(WidgetEvent::LongPressed(target));
+}
+
+fn handle_key_input(resources: &mut Resources, event: &WindowEvent) {
+    let WindowEvent::KeyboardInput { event, .. } = event else {
+        return;
+    };
+    let Some(id) = resources.get::<Focus>().and_then(Focus::focused) else {
+        return;
+    };
+    // Tab moves the focus, unless the focused widget uses it
+    if event.logical_key == Key::Named(NamedKey::Tab) && !focused_accepts_tab(resources) {
+        return;
+    }
+    let events = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+    events.push(WidgetEvent::KeyInput(id, event.clone()));
+}
+
+fn handle_modifiers(resources: &mut Resources, event: &WindowEvent) {
+    if let WindowEvent::ModifiersChanged(modifiers) = event {
+        let events = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+        events.push(WidgetEvent::ModifiersChanged(modifiers.state()));
+    }
+}
+
+/// The widgets that are being dragged.
+#[derive(Debug, Default)]
+struct DragState {
+    ids: Vec<GlobalId>,
+    last_position: Position,
+    /// The transforms of the dragged widgets, their deltas are mapped into
+    /// the untransformed space.
+    transforms: HitTransforms,
+}
+
+fn handle_drag(resources: &mut Resources, event: &WindowEvent) {
+    match event {
+        &WindowEvent::MouseInput {
+            state,
+            button: MouseButton::Left,
+            ..
+        } => {
+            if state == ElementState::Released {
+                let ids = std::mem::take(&mut resources.get_mut::<DragState>().unwrap().ids);
+                let events = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+                events.extend(ids.into_iter().map(WidgetEvent::DragEnded));
+                return;
+            }
+
+            let mut transforms = hit_transforms(resources);
+            let ids = hovered_widgets(resources);
+            let layout = resources.get::<Box<dyn Layout>>().unwrap();
+            let CursorPosition(cursor_position) = *resources.get().unwrap();
+            let events: Vec<WidgetEvent> = ids
+                .iter()
+                .filter_map(|&id| {
+                    let position =
+                        local_position(&transforms, layout.as_ref(), id, cursor_position)?;
+                    Some(WidgetEvent::DragStarted(id, position))
+                })
+                .collect();
+            transforms.retain(|id, _| ids.contains(id));
+
+            let drag_state = resources.get_mut::<DragState>().unwrap();
+            drag_state.ids = ids;
+            drag_state.last_position = cursor_position;
+            drag_state.transforms = transforms;
+            resources
+                .get_mut::<Vec<WidgetEvent>>()
+                .unwrap()
+                .extend(events);
+        }
+        WindowEvent::CursorMoved { position, .. } => {
+            // The same space as the cursor position the drag started at
+            let scale = ui_scale(resources);
+            let position = Position::new(position.x as f32 / scale, position.y as f32 / scale);
+            let drag_state = resources.get_mut::<DragState>().unwrap();
+            if drag_state.ids.is_empty() {
+                return;
+            }
+
+            let mut delta = position;
+            delta -= drag_state.last_position;
+            drag_state.last_position = position;
+
+            let events: Vec<WidgetEvent> = drag_state
+                .ids
+                .iter()
+                .map(|id| {
+                    let delta = match drag_state.transforms.get(id) {
+                        Some(Some(transform)) => {
+                            // Only the direction and length of the delta change
+                            let mut point = tiny_skia::Point::from_xy(delta.x, delta.y);
+                            let linear = tiny_skia::Transform {
+                                tx: 0.0,
+                                ty: 0.0,
+                                ..*transform
+                            };
+                            linear.map_point(&mut point);
+                            Position::new(point.x, point.y)
+                        }
+                        _ => delta,
+                    };
+                    WidgetEvent::Dragged(*id, delta)
+                })
+                .collect();
+            resources
+                .get_mut::<Vec<WidgetEvent>>()
+                .unwrap()
+                .extend(events);
+        }
+        _ => {}
+    }
+}
+
+/// Drag payloads from draggable widgets and drop them on the drop targets
+/// that accept them, see [`drag_drop`].
+fn handle_drag_and_drop(resources: &mut Resources, event: &WindowEvent) {
+    let CursorPosition(cursor) = resources.get_owned().unwrap();
+    match event {
+        &WindowEvent::MouseInput {
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+            ..
+        } => {
+            let ids = hovered_widgets(resources);
+            let root = resources.get::<Box<dyn Widget>>().unwrap();
+            let source = ids
+                .into_iter()
+                .find(|&id| root.get(id).is_some_and(|w| w.as_drag_source().is_some()));
+            resources
+                .get_mut::<DragAndDrop>()
+                .unwrap()
+                .press(source, cursor);
+        }
+        WindowEvent::CursorMoved { .. } => {
+            let drag = resources.get_mut::<DragAndDrop>().unwrap();
+            if let Some(source) = drag.move_to(cursor) {
+                let root = resources.get::<Box<dyn Widget>>().unwrap();
+                let payload = root
+                    .get(source)
+                    .and_then(|widget| widget.as_drag_source())
+                    .map(|source| source.drag_payload());
+                let drag = resources.get_mut::<DragAndDrop>().unwrap();
+                match payload {
+                    Some(payload) => drag.begin(payload),
+                    None => drag.cancel(),
+                }
+            }
+
+            let ids = hovered_widgets(resources);
+            let root = resources.get::<Box<dyn Widget>>().unwrap();
+            let drag = resources.get::<DragAndDrop>().unwrap();
+            let Some(payload) = drag.payload() else {
+                return;
+            };
+            let target = ids.into_iter().find(|&id| {
+                root.get(id)
+                    .and_then(|widget| widget.as_drop_handler())
+                    .is_some_and(|handler| handler.accepts(payload))
+            });
+            let drag = resources.get_mut::<DragAndDrop>().unwrap();
+            if let Some(previous) = drag.set_target(target) {
+                with_drop_handler(resources, previous, |handler| handler.drag_hover(false));
+                with_drop_handler(resources, target, |handler| handler.drag_hover(true));
+            }
+        }
+        &WindowEvent::MouseInput {
+            state: ElementState::Released,
+            button: MouseButton::Left,
+            ..
+        } => {
+            let drag = resources.get_mut::<DragAndDrop>().unwrap();
+            let target = drag.target();
+            let Some((target, payload)) = drag.finish() else {
+                with_drop_handler(resources, target, |handler| handler.drag_hover(false));
+                return;
+            };
+            let mut payload = Some(payload);
+            with_drop_handler(resources, Some(target), |handler| {
+                handler.drag_hover(false);
+                if let Some(payload) = payload.take() {
+                    handler.drop_payload(payload);
+                }
+            });
+        }
+        WindowEvent::KeyboardInput { event, .. }
+            if event.state.is_pressed() && event.logical_key == Key::Named(NamedKey::Escape) =>
+        {
+            let drag = resources.get_mut::<DragAndDrop>().unwrap();
+            let target = drag.target();
+            drag.cancel();
+            with_drop_handler(resources, target, |handler| handler.drag_hover(false));
+        }
+        _ => {}
+    }
+}
+
+/// Call `f` with the [`DropHandler`](widgets::DropHandler) of a widget.
+fn with_drop_handler(
+    resources: &mut Resources,
+    id: Option<GlobalId>,
+    mut f: impl FnMut(&mut dyn widgets::DropHandler),
+) {
+    let (Some(id), Some(root)) = (id, resources.get_mut::<Box<dyn Widget>>()) else {
+        return;
+    };
+    event::bubble_event(root.as_mut(), &[id], |widget| {
+        if let Some(handler) = widget.as_drop_handler_mut() {
+            f(handler);
+        }
+        true
+    });
+}
+
+fn intersection_observer(resources: &mut Resources) {
+    let hovered_ids = hovered_widgets(resources);
+    let layout = resources.get::<Box<dyn Layout>>().unwrap();
+
+    let not_hovered: Vec<GlobalId> = layout
+        .iter()
+        .filter(|l| !hovered_ids.contains(&l.id()))
+        .map(|l| l.id())
+        .collect();
+
+    let state = resources.get_mut::<StateTracker>().unwrap();
+    for id in &hovered_ids {
+        state.update_state(*id, WidgetState::Hovered);
+    }
+
+    for id in &not_hovered {
+        state.update_state(*id, WidgetState::Resting);
+    }
+
+    let state = resources.get::<StateTracker>().unwrap();
+    let mut events = vec![];
+    for id in &hovered_ids {
+        if state.previous_state(*id).unwrap() == &WidgetState::Resting {
+            events.push(WidgetEvent::Hovered(*id));
+        }
+    }
+    for id in &not_hovered {
+        if state.previous_state(*id).unwrap() != &WidgetState::Resting {
+            events.push(WidgetEvent::Unhovered(*id));
+        }
+    }
+
+    let widget_events: &mut Vec<WidgetEvent> = resources.get_mut().unwrap();
+    widget_events.extend(events);
+}
+
+fn handle_mouse_move(resources: &mut Resources, event: &WindowEvent) {
+    if !matches!(event, WindowEvent::CursorMoved { .. }) {
+        return;
+    }
+
+    let transforms = hit_transforms(resources);
+    let ids = hovered_widgets(resources);
+    let CursorPosition(cursor_position) = *resources.get().unwrap();
+    let layout = resources.get::<Box<dyn Layout>>().unwrap();
+    let events: Vec<WidgetEvent> = ids
+        .into_iter()
+        .filter_map(|id| {
+            let position = local_position(&transforms, layout.as_ref(), id, cursor_position)?;
+            Some(WidgetEvent::MouseMoved(id, position))
+        })
+        .collect();
+    resources
+        .get_mut::<Vec<WidgetEvent>>()
+        .unwrap()
+        .extend(events);
+}
+
+/// The inverse transforms of the widgets that are drawn transformed, which
+/// map the cursor back into their layout. Widgets that are scaled down to
+/// nothing map to `None`.
+type HitTransforms = HashMap<GlobalId, Option<tiny_skia::Transform>>;
+
+/// Get the [`HitTransforms`] of the widget tree.
+fn hit_transforms(resources: &Resources) -> HitTransforms {
+    let mut transforms = HitTransforms::new();
+    let Some(widget) = resources.get::<Box<dyn Widget>>() else {
+        return transforms;
+    };
+    let layout = resources.get::<Box<dyn Layout>>().unwrap();
+
+    let mut matrices = HashMap::new();
+    let mut collect = |widget: &dyn Widget| {
+        let Some(transform) = widget.group_transform() else {
+            return;
+        };
+        if let Some(layout) = layout.get(widget.id()) {
+            let matrix = transform.matrix(layout.position(), layout.size());
+            matrices.insert(widget.id(), matrix);
+        }
+    };
+    collect(widget.as_ref());
+    widget.traverse(&mut collect);
+
+    if !matrices.is_empty() {
+        let identity = tiny_skia::Transform::identity();
+        collect_hit_transforms(layout.as_ref(), identity, &matrices, &mut transforms);
+    }
+    transforms
+}
+
+/// Combine the transforms of each layout with the transforms of its parents.
+fn collect_hit_transforms(
+    layout: &dyn Layout,
+    parent: tiny_skia::Transform,
+    matrices: &HashMap<GlobalId, tiny_skia::Transform>,
+    transforms: &mut HitTransforms,
+) {
+    let transform = match matrices.get(&layout.id()) {
+        Some(matrix) => parent.pre_concat(*matrix),
+        None => parent,
+    };
+    if !transform.is_identity() {
+        transforms.insert(layout.id(), transform.invert());
+    }
+    for child in layout.children() {
+        collect_hit_transforms(child.as_ref(), transform, matrices, transforms);
+    }
+}
+
+/// Map a position in the window into the untransformed space of a widget.
+fn untransform(transforms: &HitTransforms, id: GlobalId, position: Position) -> Option<Position> {
+    match transforms.get(&id) {
+        Some(Some(transform)) => {
+            let mut point = tiny_skia::Point::from_xy(position.x, position.y);
+            transform.map_point(&mut point);
+            Some(Position::new(point.x, point.y))
+        }
+        Some(None) => None,
+        None => Some(position),
+    }
+}
+
+/// Get the cursor's position relative to the top left corner of a widget,
+/// after its transform.
+fn local_position(
+    transforms: &HitTransforms,
+    layout: &dyn Layout,
+    id: GlobalId,
+    cursor: Position,
+) -> Option<Position> {
+    let layout = layout.get(id)?;
+    let mut position = untransform(transforms, id, cursor)?;
+    position -= layout.position();
+    Some(position)
+}
+
+/// Returns `true` if the cursor is over a widget's shape, after its
+/// transform.
+fn is_hit(
+    transforms: &HitTransforms,
+    layout: &dyn Layout,
+    widget: Option<&dyn Widget>,
+    cursor: Position,
+) -> bool {
+    let Some(position) = untransform(transforms, layout.id(), cursor) else {
+        return false;
+    };
+    if !layout.bounds().within(&position) {
+        return false;
+    }
+    // Clicks in the transparent corners of rounded widgets miss them
+    let shape =
+        widget
+            .and_then(|widget| widget.get(layout.id()))
+            .map_or(HitShape::Rect, |widget| {
+                widget
+                    .as_pointer_target()
+                    .and_then(|target| target.hit_area())
+                    .unwrap_or_else(|| widget.view().hit_shape())
+            });
+    shape.contains(layout.position(), layout.size(), position)
+}
+
+/// Returns `true` if the cursor is outside of a widget's
+/// [`hit_area`](widgets::PointerTarget::hit_area), which its children are clipped to.
+fn is_outside_hit_area(
+    transforms: &HitTransforms,
+    layout: &dyn Layout,
+    widget: Option<&dyn Widget>,
+    cursor: Position,
+) -> bool {
+    let Some(shape) = widget
+        .and_then(|widget| widget.get(layout.id()))
+        .and_then(|widget| widget.as_pointer_target())
+        .and_then(|target| target.hit_area())
+    else {
+        return false;
+    };
+    untransform(transforms, layout.id(), cursor)
+        .is_none_or(|position| !shape.contains(layout.position(), layout.size(), position))
+}
+
+/// Find the topmost layout under the cursor, returns it and its ancestors
+/// with the innermost first.
+fn hit_path(
+    transforms: &HitTransforms,
+    layout: &dyn Layout,
+    widget: Option<&dyn Widget>,
+    cursor: Position,
+) -> Vec<GlobalId> {
+    if is_outside_hit_area(transforms, layout, widget, cursor) {
+        return vec![];
+    }
+    // Earlier children are drawn over the later ones, so they're hit first
+    for child in layout.children() {
+        let mut path = hit_path(transforms, child.as_ref(), widget, cursor);
+        if !path.is_empty() {
+            path.push(layout.id());
+            return path;
+        }
+    }
+    match is_hit(transforms, layout, widget, cursor) {
+        true => vec![layout.id()],
+        false => vec![],
+    }
+}
+
+/// The number of pixels in a line of scrolling, for trackpads that scroll
+/// by pixels.
+const PIXELS_PER_LINE: f32 = 20.0;
+
+fn handle_mouse_wheel(resources: &mut Resources, event: &WindowEvent) {
+    let WindowEvent::MouseWheel { delta, .. } = event else {
+        return;
+    };
+    let delta = match *delta {
+        MouseScrollDelta::LineDelta(x, y) => Position::new(x, y),
+        MouseScrollDelta::PixelDelta(position) => {
+            let pixels = PIXELS_PER_LINE * ui_scale(resources);
+            Position::new(position.x as f32 / pixels, position.y as f32 / pixels)
+        }
+    };
+
+    let ids = hovered_widgets(resources);
+    if ids.is_empty() {
+        return;
+    }
+    resources
+        .get_mut::<Vec<WidgetEvent>>()
+        .unwrap()
+        .push(WidgetEvent::Scrolled(ids, delta));
+}
+
+/// Turn touches into gestures, which are sent to the widgets under the
+/// touch. Long presses are detected on any event, since they happen while
+/// the touch doesn't move.
+fn handle_touch(resources: &mut Resources, event: &WindowEvent) {
+    let now = Instant::now();
+    let scale = ui_scale(resources);
+    let gestures = resources.get_mut::<Gestures>().unwrap();
+    let gesture = match event {
+        WindowEvent::Touch(touch) => {
+            let position = Position::new(
+                touch.location.x as f32 / scale,
+                touch.location.y as f32 / scale,
+            );
+            gestures.touch(touch.id, touch.phase, position, now)
+        }
+        _ => gestures.update(now),
+    };
+
+    // Taps and long presses target the innermost widget, layouts without a
+    // widget are skipped
+    let target = |position| {
+        let widget = resources.get::<Box<dyn Widget>>();
+        widgets_at(resources, position)
+            .into_iter()
+            .find(|&id| widget.is_none_or(|widget| widget.get(id).is_some()))
+    };
+    let widget_event = match gesture {
+        None => return,
+        Some(Gesture::Tap(position)) => target(position).map(WidgetEvent::Tapped),
+        Some(Gesture::LongPress(position)) => target(position).map(WidgetEvent::LongPressed),
+        Some(Gesture::Pan { start, delta }) => {
+            Some(WidgetEvent::Panned(widgets_at(resources, start), delta))
+        }
+        Some(Gesture::Pinch { scale, center }) => Some(WidgetEvent::Pinched(
+            widgets_at(resources, center),
+            scale,
+            center,
+        )),
+    };
+    if let Some(widget_event) = widget_event {
+        let events = resources.get_mut::<Vec<WidgetEvent>>().unwrap();
+        events.push(widget_event);
+    }
+}
+
+/// Get the topmost widget under the cursor and its ancestors, from the
+/// innermost to the outermost.
+fn hovered_widgets(resources: &Resources) -> Vec<GlobalId> {
+    let CursorPosition(cursor_position) = *resources.get().unwrap();
+    widgets_at(resources, cursor_position)
+}
+
+/// Get the topmost widget at a position and its ancestors, from the
+/// innermost to the outermost.
+fn widgets_at(resources: &Resources, position: Position) -> Vec<GlobalId> {
+    let transforms = hit_transforms(resources);
+    let layout = resources.get::<Box<dyn Layout>>().unwrap();
+    let widget = resources.get::<Box<dyn Widget>>();
+    let path = hit_path(
+        &transforms,
+        layout.as_ref(),
+        widget.map(|widget| widget.as_ref()),
+        position,
+    );
+    // The widgets outside of an open modal are inert
+    match resources.get::<Modal>() {
+        Some(modal) => modal.clip_path(path),
+        None => path,
+    }
+}
+
+fn handle_file_drop(resources: &mut Resources, event: &WindowEvent) {
+    let file_drop = resources.get_mut::<FileDrop>().unwrap();
+    match event {
+        WindowEvent::HoveredFile(path) => file_drop.hovered.push(path.clone()),
+        WindowEvent::HoveredFileCancelled => file_drop.hovered.clear(),
+        WindowEvent::DroppedFile(path) => {
+            file_drop.dropped.push(path.clone());
+            return;
+        }
+        WindowEvent::CursorMoved { .. } => {}
+        _ if !file_drop.dropped.is_empty() => file_drop.hovered.clear(),
+        _ => return,
+    }
+
+    let dropped = std::mem::take(&mut file_drop.dropped);
+    let previous = std::mem::take(&mut file_drop.targets);
+    let is_hovered = file_drop.is_hovered();
+    let hovered = hovered_widgets(resources);
+
+    let mut events = vec![];
+    if !dropped.is_empty() && !hovered.is_empty() {
+        events.push(WidgetEvent::FilesDropped(hovered.clone(), dropped));
+    }
+    let targets = if is_hovered { hovered } else { vec![] };
+    for id in &previous {
+        if !targets.contains(id) {
+            events.push(WidgetEvent::FileUnhovered(*id));
+        }
+    }
+    for id in &targets {
+        if !previous.contains(id) {
+            events.push(WidgetEvent::FileHovered(*id));
+        }
+    }
+
+    resources.get_mut::<FileDrop>().unwrap().targets = targets;
+    resources
+        .get_mut::<Vec<WidgetEvent>>()
+        .unwrap()
+        .extend(events);
+}
+
+fn handle_widget_event(resources: &mut Resources, event: &WindowEvent) {
+    let events: Vec<WidgetEvent> = resources.get_owned().unwrap();
+    let widget: &mut Box<dyn Widget> = resources.get_mut().unwrap();
+
+    for widget_event in &events {
+        event::dispatch_event(widget.as_mut(), widget_event, Some(event));
+    }
+
+    resources.get_mut::<Vec<WidgetEvent>>().unwrap().clear();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signal::Signal;
+    use crate::style::{State, Styled};
+    use crate::view::{RectView, View};
+    use crate::widgets::{InteractionState, Rect};
+    use crate::{hstack, vstack};
+    use agape_layout::{EmptyLayout, IntrinsicSize};
+
+    #[test]
+    fn widget_hover_system() {
+        let rect = Rect::new(100.0, 100.0);
+        let mut layout = rect.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let state_tracker = StateTracker::new(&rect);
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(state_tracker);
+        resources.insert(CursorPosition(Position::unit(50.0)));
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        intersection_observer(&mut resources);
+
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert!(events.contains(&WidgetEvent::Hovered(rect.id())));
+    }
+
+    #[test]
+    fn interaction_state_of_widgets() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let other = Rect::new(100.0, 100.0);
+        let other_id = other.id();
+        let widget = hstack! {rect, other};
+        let state_tracker = StateTracker::new(&widget);
+        let mut resources = hit_resources(widget, Position::unit(50.0));
+        resources.insert(state_tracker);
+        resources.insert(PressState::default());
+        resources.insert(Focus::new());
+        resources.insert(Modal::new());
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+        assert_eq!(resources.interaction_state(id), InteractionState::default());
+
+        intersection_observer(&mut resources);
+        let (press, _) = left_button();
+        handle_mouse_button(&mut resources, &press);
+        let state = resources.interaction_state(id);
+        assert!(state.hovered && state.pressed && !state.focused);
+        assert!(state.matches(State::Pressed));
+        assert!(!resources.interaction_state(other_id).hovered);
+
+        // The focus is only visible when it's moved with the keyboard
+        let focus = resources.get_mut::<Focus>().unwrap();
+        focus.tab(&[other_id], false);
+        let state = resources.interaction_state(other_id);
+        assert!(state.focused && state.focus_visible);
+        resources.get_mut::<Focus>().unwrap().click(Some(other_id));
+        assert!(!resources.interaction_state(other_id).focus_visible);
+
+        resources.get_mut::<Modal>().unwrap().open(other_id, None);
+        assert!(resources.interaction_state(id).disabled);
+        assert!(!resources.interaction_state(other_id).disabled);
+    }
+
+    #[test]
+    fn double_click() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let mut layout = rect.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(StateTracker::new(&rect));
+        resources.insert(CursorPosition(Position::unit(50.0)));
+        resources.insert(ClickCounter::new());
+        resources.insert(PressState::default());
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let (press, release) = left_button();
+        for _ in 0..2 {
+            handle_mouse_button(&mut resources, &press);
+            handle_mouse_button(&mut resources, &release);
+        }
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(
+            events,
+            &[
+                WidgetEvent::MousePressed(id, MouseButton::Left),
+                WidgetEvent::Pressed(id),
+                WidgetEvent::Released(id),
+                WidgetEvent::Clicked(id),
+                WidgetEvent::MousePressed(id, MouseButton::Left),
+                WidgetEvent::Pressed(id),
+                WidgetEvent::Released(id),
+                WidgetEvent::Clicked(id),
+                WidgetEvent::DoubleClicked(id)
+            ]
+        );
+    }
+
+    /// Get a press and a release of the primary mouse button.
+    fn left_button() -> (WindowEvent, WindowEvent) {
+        let press = WindowEvent::MouseInput {
+            device_id: winit::event::DeviceId::dummy(),
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        };
+        let release = WindowEvent::MouseInput {
+            device_id: winit::event::DeviceId::dummy(),
+            state: ElementState::Released,
+            button: MouseButton::Left,
+        };
+        (press, release)
+    }
+
+    #[test]
+    fn long_press_replaces_click() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let mut resources = hit_resources(rect, Position::unit(50.0));
+        resources.insert(StateTracker::new(
+            resources.get::<Box<dyn Widget>>().unwrap().as_ref(),
+        ));
+        resources.insert(PressState::default());
+        let mut long_press = LongPress::new();
+        long_press.duration = Duration::ZERO;
+        resources.insert(long_press);
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let (press, release) = left_button();
+        handle_mouse_button(&mut resources, &press);
+        handle_long_press(&mut resources);
+        handle_mouse_button(&mut resources, &release);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert!(events.contains(&WidgetEvent::LongPressed(id)));
+        assert!(events.contains(&WidgetEvent::Released(id)));
+        assert!(!events.contains(&WidgetEvent::Clicked(id)));
+    }
+
+    #[test]
+    fn drop_payloads_on_matching_targets() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let dropped = Rc::new(RefCell::new(vec![]));
+        let sink = dropped.clone();
+        let card = Rect::new(100.0, 100.0).draggable(7_u32);
+        let column = Rect::new(100.0, 100.0).drop_target(move |n: u32| sink.borrow_mut().push(n));
+        let column_id = column.id();
+        let trash = Rect::new(100.0, 100.0).drop_target(|_: String| panic!("wrong type"));
+        let widget = hstack! {card, column, trash};
+        let mut resources = hit_resources(widget, Position::unit(50.0));
+        resources.insert(DragAndDrop::new());
+
+        let moved = WindowEvent::CursorMoved {
+            device_id: winit::event::DeviceId::dummy(),
+            position: winit::dpi::PhysicalPosition::new(0.0, 0.0),
+        };
+        let move_to = |resources: &mut Resources, x: f32| {
+            resources.get_mut::<CursorPosition>().unwrap().0 = Position::new(x, 50.0);
+            handle_drag_and_drop(resources, &moved);
+        };
+        let (press, release) = left_button();
+        handle_drag_and_drop(&mut resources, &press);
+        move_to(&mut resources, 150.0);
+        let drag = resources.get::<DragAndDrop>().unwrap();
+        assert!(drag.is_dragging());
+        assert_eq!(drag.target(), Some(column_id));
+
+        // The trash doesn't accept numbers
+        move_to(&mut resources, 250.0);
+        assert_eq!(resources.get::<DragAndDrop>().unwrap().target(), None);
+
+        move_to(&mut resources, 150.0);
+        handle_drag_and_drop(&mut resources, &release);
+        assert_eq!(*dropped.borrow(), [7]);
+        assert!(!resources.get::<DragAndDrop>().unwrap().is_dragging());
+    }
+
+    #[test]
+    fn releasing_elsewhere_cancels_click() {
+        let button = Rect::new(100.0, 100.0);
+        let button_id = button.id();
+        let other = Rect::new(100.0, 100.0);
+        let other_id = other.id();
+        let widget = hstack! {button, other};
+        let root_id = widget.id();
+        let mut resources = hit_resources(widget, Position::unit(50.0));
+        resources.insert(StateTracker::new(
+            resources.get::<Box<dyn Widget>>().unwrap().as_ref(),
+        ));
+        resources.insert(PressState::default());
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let (press, release) = left_button();
+        handle_mouse_button(&mut resources, &press);
+        *resources.get_mut::<CursorPosition>().unwrap() =
+            CursorPosition(Position::new(150.0, 50.0));
+        handle_mouse_button(&mut resources, &release);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert!(events.contains(&WidgetEvent::Pressed(button_id)));
+        assert!(events.contains(&WidgetEvent::Released(button_id)));
+        assert!(!events.contains(&WidgetEvent::Clicked(button_id)));
+        assert!(!events.contains(&WidgetEvent::Clicked(other_id)));
+        // The stack is under the cursor both times, so it's still clicked
+        assert!(events.contains(&WidgetEvent::Clicked(root_id)));
+    }
+
+    #[test]
+    fn touch_gestures() {
+        use winit::event::TouchPhase;
+
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let mut layout = rect.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(Gestures::new());
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let touch = |phase, x, y| {
+            WindowEvent::Touch(winit::event::Touch {
+                device_id: winit::event::DeviceId::dummy(),
+                phase,
+                location: winit::dpi::PhysicalPosition::new(x, y),
+                force: None,
+                id: 0,
+            })
+        };
+        for event in [
+            touch(TouchPhase::Started, 50.0, 50.0),
+            touch(TouchPhase::Ended, 52.0, 50.0),
+            touch(TouchPhase::Started, 50.0, 50.0),
+            touch(TouchPhase::Moved, 50.0, 80.0),
+            touch(TouchPhase::Ended, 50.0, 80.0),
+            // Taps outside of the widgets are ignored
+            touch(TouchPhase::Started, 300.0, 300.0),
+            touch(TouchPhase::Ended, 300.0, 300.0),
+        ] {
+            handle_touch(&mut resources, &event);
+        }
+
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(
+            events,
+            &[
+                WidgetEvent::Tapped(id),
+                WidgetEvent::Panned(vec![id], Position::new(0.0, 30.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn right_click_does_not_click() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let mut layout = rect.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(StateTracker::new(&rect));
+        resources.insert(CursorPosition(Position::unit(50.0)));
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let press = WindowEvent::MouseInput {
+            device_id: winit::event::DeviceId::dummy(),
+            state: ElementState::Pressed,
+            button: MouseButton::Right,
+        };
+        handle_mouse_button(&mut resources, &press);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(events, &[WidgetEvent::MousePressed(id, MouseButton::Right)]);
+    }
+
+    #[test]
+    fn scroll_innermost_widget_first() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let value = Rc::new(Cell::new(0.0));
+        let dial_value = value.clone();
+        let dial = widgets::Dial::new(0.0, 10.0)
+            .detents(11)
+            .on_change(move |value| dial_value.set(value));
+        let dial_id = dial.id();
+        let widget: Box<dyn Widget> = Box::new(hstack! {dial, Rect::new(100.0, 100.0)});
+        let hstack_id = widget.id();
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(widget);
+        resources.insert(CursorPosition(Position::unit(10.0)));
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let wheel = WindowEvent::MouseWheel {
+            device_id: winit::event::DeviceId::dummy(),
+            delta: MouseScrollDelta::PixelDelta(winit::dpi::PhysicalPosition::new(0.0, 40.0)),
+            phase: winit::event::TouchPhase::Moved,
+        };
+        handle_mouse_wheel(&mut resources, &wheel);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(
+            events[0],
+            WidgetEvent::Scrolled(vec![dial_id, hstack_id], Position::new(0.0, 2.0))
+        );
+
+        handle_widget_event(&mut resources, &WindowEvent::RedrawRequested);
+        assert_eq!(value.get(), 2.0);
+    }
+
+    #[test]
+    fn drop_files_on_innermost_widget() {
+        use std::cell::RefCell;
+        use std::path::PathBuf;
+        use std::rc::Rc;
+
+        let calls = Rc::new(RefCell::new(vec![]));
+        let (hover_calls, leave_calls, drop_calls) = (calls.clone(), calls.clone(), calls.clone());
+        let rect = Rect::new(100.0, 100.0);
+        let rect_id = rect.id();
+        let button = widgets::Button::new(rect)
+            .on_file_hover(move || hover_calls.borrow_mut().push(String::from("hover")))
+            .on_file_leave(move || leave_calls.borrow_mut().push(String::from("leave")))
+            .on_file_drop(move |paths| drop_calls.borrow_mut().push(format!("{paths:?}")));
+        let button_id = button.id();
+        let widget: Box<dyn Widget> = Box::new(hstack! {button});
+        let hstack_id = widget.id();
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(widget);
+        resources.insert(CursorPosition(Position::unit(10.0)));
+        resources.insert(FileDrop::new());
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let (a, b) = (PathBuf::from("a.png"), PathBuf::from("b.png"));
+        handle_file_drop(&mut resources, &WindowEvent::HoveredFile(a.clone()));
+        handle_file_drop(&mut resources, &WindowEvent::HoveredFile(b.clone()));
+        assert_eq!(resources.get::<FileDrop>().unwrap().hovered().len(), 2);
+        handle_widget_event(&mut resources, &WindowEvent::RedrawRequested);
+
+        // The drop is only delivered once all the files are in
+        handle_file_drop(&mut resources, &WindowEvent::DroppedFile(a.clone()));
+        handle_file_drop(&mut resources, &WindowEvent::DroppedFile(b.clone()));
+        assert!(resources.get::<Vec<WidgetEvent>>().unwrap().is_empty());
+        handle_file_drop(&mut resources, &WindowEvent::RedrawRequested);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(
+            events[0],
+            WidgetEvent::FilesDropped(vec![rect_id, button_id, hstack_id], vec![a, b])
+        );
+        handle_widget_event(&mut resources, &WindowEvent::RedrawRequested);
+
+        assert!(!resources.get::<FileDrop>().unwrap().is_hovered());
+        assert_eq!(
+            *calls.borrow(),
+            ["hover", "[\"a.png\", \"b.png\"]", "leave"]
+        );
+    }
+
+    #[test]
+    fn mouse_leave_and_move() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let mut layout = rect.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(StateTracker::new(&rect));
+        resources.insert(CursorPosition(Position::unit(50.0)));
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let moved = WindowEvent::CursorMoved {
+            device_id: winit::event::DeviceId::dummy(),
+            position: winit::dpi::PhysicalPosition::new(50.0, 50.0),
+        };
+        intersection_observer(&mut resources);
+        handle_mouse_move(&mut resources, &moved);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert!(events.contains(&WidgetEvent::MouseMoved(id, Position::unit(50.0))));
+
+        resources.get_mut::<Vec<WidgetEvent>>().unwrap().clear();
+        resources.get_mut::<CursorPosition>().unwrap().0 = Position::unit(200.0);
+        intersection_observer(&mut resources);
+        handle_mouse_move(&mut resources, &moved);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(events, &[WidgetEvent::Unhovered(id)]);
+
+        // Leaving only fires once
+        resources.get_mut::<Vec<WidgetEvent>>().unwrap().clear();
+        intersection_observer(&mut resources);
+        assert!(resources.get::<Vec<WidgetEvent>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn drag_system() {
+        let rect = Rect::new(100.0, 100.0);
+        let mut layout = rect.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(CursorPosition(Position::unit(50.0)));
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+        resources.insert(DragState::default());
+
+        let device_id = winit::event::DeviceId::dummy();
+        let press = WindowEvent::MouseInput {
+            device_id,
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        };
+        let moved = WindowEvent::CursorMoved {
+            device_id,
+            position: winit::dpi::PhysicalPosition::new(60.0, 45.0),
+        };
+        let release = WindowEvent::MouseInput {
+            device_id,
+            state: ElementState::Released,
+            button: MouseButton::Left,
+        };
+        handle_drag(&mut resources, &press);
+        handle_drag(&mut resources, &moved);
+        handle_drag(&mut resources, &release);
+        handle_drag(&mut resources, &moved);
+
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        let delta = Position::new(10.0, -5.0);
+        assert_eq!(
+            events,
+            &vec![
+                WidgetEvent::DragStarted(rect.id(), Position::unit(50.0)),
+                WidgetEvent::Dragged(rect.id(), delta),
+                WidgetEvent::DragEnded(rect.id()),
+            ]
+        );
+    }
+
+    #[test]
+    fn drag_with_ui_scale() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let mut app = App::new(rect).ui_scale(2.0);
+        app.capture_frame();
+
+        let device_id = winit::event::DeviceId::dummy();
+        let cursor_moved = |x, y| WindowEvent::CursorMoved {
+            device_id,
+            position: winit::dpi::PhysicalPosition::new(x, y),
+        };
+        let press = WindowEvent::MouseInput {
+            device_id,
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        };
+        // The window's pixels are half the size of the ui's
+        for event in [cursor_moved(100.0, 100.0), press, cursor_moved(120.0, 90.0)] {
+            update_cursor_position(&mut app.resources, &event);
+            handle_drag(&mut app.resources, &event);
+        }
+
+        let events: &Vec<WidgetEvent> = app.resources.get().unwrap();
+        assert!(events.contains(&WidgetEvent::DragStarted(id, Position::unit(50.0))));
+        assert!(events.contains(&WidgetEvent::Dragged(id, Position::new(10.0, -5.0))));
+    }
+
+    #[test]
+    fn click_focuses_widget() {
+        let button = widgets::Button::new(Rect::new(100.0, 100.0));
+        let button_id = button.id();
+        let widget: Box<dyn Widget> = Box::new(vstack! {button, Rect::new(100.0, 100.0)});
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(widget);
+        resources.insert(CursorPosition(Position::unit(5.0)));
+        resources.insert(Focus::new());
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let press = WindowEvent::MouseInput {
+            device_id: winit::event::DeviceId::dummy(),
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        };
+        handle_focus(&mut resources, &press);
+        assert!(resources.get::<Focus>().unwrap().is_focused(button_id));
+        assert!(!resources.get::<Focus>().unwrap().is_ring_visible());
+
+        // Clicking a widget that can't be focused removes the focus
+        resources.get_mut::<CursorPosition>().unwrap().0 = Position::new(5.0, 200.0);
+        handle_focus(&mut resources, &press);
+        assert_eq!(resources.get::<Focus>().unwrap().focused(), None);
+
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert_eq!(
+            events,
+            &vec![
+                WidgetEvent::Focused(button_id),
+                WidgetEvent::Unfocused(button_id)
+            ]
+        );
+    }
+
+    fn hit_resources(widget: impl Widget + 'static, cursor: Position) -> Resources {
+        let widget: Box<dyn Widget> = Box::new(widget);
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(widget);
+        resources.insert(CursorPosition(cursor));
+        resources
+    }
+
+    #[test]
+    fn modal_makes_background_inert() {
+        use crate::modal::{CloseModal, OpenModal, handle_close_modal, handle_open_modal};
+        use crate::widgets::{Button, Text};
+
+        let _ = FONT.set(init_font());
+        let ok = Button::new(Text::new("Ok"));
+        let ok_id = ok.id();
+        let dialog = vstack! {ok};
+        let dialog_id = dialog.id();
+        let background = Button::new(Text::new("Delete"));
+        let background_id = background.id();
+        let widget = hstack! {dialog, background};
+        let mut resources = hit_resources(widget, Position::default());
+        resources.insert(Focus::new());
+        resources.insert(Modal::new());
+        resources.get_mut::<Focus>().unwrap().focus(background_id);
+
+        handle_open_modal(&mut resources, &OpenModal(dialog_id));
+        assert_eq!(resources.get::<Focus>().unwrap().focused(), Some(ok_id));
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        let bounds = layout.get(background_id).unwrap().bounds();
+        resources.get_mut::<CursorPosition>().unwrap().0 =
+            Position::new(bounds.x[0] + 1.0, bounds.y[0] + 1.0);
+        assert!(hovered_widgets(&resources).is_empty());
+
+        let root = resources.get::<Box<dyn Widget>>().unwrap();
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        let dump =
+            diagnostics::dump_tree_with_modal(root.as_ref(), layout.as_ref(), Some(dialog_id));
+        assert_eq!(dump.matches("\"inert\": true").count(), 3);
+
+        handle_close_modal(&mut resources, &CloseModal);
+        assert_eq!(
+            resources.get::<Focus>().unwrap().focused(),
+            Some(background_id)
+        );
+        assert!(hovered_widgets(&resources).contains(&background_id));
+    }
+
+    #[test]
+    fn hit_shape_clips_children() {
+        let rect = Rect::new(100.0, 100.0);
+        let rect_id = rect.id();
+        let round = rect.hit_shape(HitShape::Ellipse);
+        let round_id = round.id();
+        let stack = hstack! {round};
+        let stack_id = stack.id();
+        let mut resources = hit_resources(stack, Position::new(4.0, 4.0));
+
+        // The corner is outside of the circle, even though the rect is
+        // drawn there
+        assert_eq!(hovered_widgets(&resources), vec![stack_id]);
+        resources.get_mut::<CursorPosition>().unwrap().0 = Position::unit(50.0);
+        assert_eq!(
+            hovered_widgets(&resources),
+            vec![rect_id, round_id, stack_id]
+        );
+    }
+
+    #[test]
+    fn rounded_corners_are_not_hit() {
+        let rect = Rect::new(100.0, 100.0).corner_radius(50.0);
+        let rect_id = rect.id();
+        let stack = hstack! {rect};
+        let stack_id = stack.id();
+
+        let mut resources = hit_resources(stack, Position::unit(4.0));
+        assert_eq!(hovered_widgets(&resources), [stack_id]);
+        resources.get_mut::<CursorPosition>().unwrap().0 = Position::unit(50.0);
+        assert_eq!(hovered_widgets(&resources), [rect_id, stack_id]);
+    }
+
+    #[test]
+    fn only_topmost_widget_is_hit() {
+        let top = Rect::new(100.0, 100.0);
+        let top_id = top.id();
+        // Scaled up around its center, so it reaches under the first rect
+        let below = Rect::new(100.0, 100.0).scale(2.0);
+        let below_id = below.id();
+        let stack = hstack! {top, below};
+        let stack_id = stack.id();
+
+        let mut resources = hit_resources(stack, Position::new(75.0, 50.0));
+        assert_eq!(hovered_widgets(&resources), [top_id, stack_id]);
+        resources.get_mut::<CursorPosition>().unwrap().0 = Position::new(150.0, 50.0);
+        let hovered = hovered_widgets(&resources);
+        assert!(hovered.contains(&below_id));
+        assert!(!hovered.contains(&top_id));
+    }
+
+    #[test]
+    fn group_opacity() {
+        let panel = vstack! {
+            Rect::new(50.0, 50.0).background_color(Color::rgb(0, 0, 255))
+        }
+        .background_color(Color::rgb(255, 0, 0))
+        .opacity(0.5);
+        let mut layout = panel.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.fill(tiny_skia::Color::WHITE);
+        render_widget(&panel, layout.as_ref(), &mut pixmap, &Resources::new());
+
+        // The red background doesn't show through the blue rect
+        let pixel = pixmap.pixel(25, 25).unwrap();
+        assert!(pixel.red().abs_diff(128) <= 1);
+        assert_eq!(pixel.red(), pixel.green());
+        assert_eq!(pixel.blue(), 255);
+        // Nothing is drawn outside the group
+        assert_eq!(pixmap.pixel(75, 75).unwrap().red(), 255);
+    }
+
+    #[test]
+    fn group_blur() {
+        let panel = vstack! {
+            Rect::new(50.0, 50.0).background_color(Color::BLACK)
+        }
+        .blur(8.0);
+        let mut layout = panel.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.fill(tiny_skia::Color::WHITE);
+        render_widget(&panel, layout.as_ref(), &mut pixmap, &Resources::new());
+
+        // The rect bleeds past its edges
+        let edge = pixmap.pixel(52, 25).unwrap().red();
+        assert!(edge > 0 && edge < 255);
+        assert_eq!(pixmap.pixel(25, 25).unwrap().red(), 0);
+        assert_eq!(pixmap.pixel(90, 90).unwrap().red(), 255);
+    }
+
+    #[test]
+    fn detect_low_contrast() {
+        use crate::widgets::{Button, Text};
+
+        let dark = Button::new(Text::new("Dark")).background_color(Color::rgb(20, 20, 20));
+        let light = Button::new(Text::new("Light")).background_color(Color::WHITE);
+        let dark_id = dark.children()[0].id();
+        let widget = hstack! {dark,light};
+
+        let ids = low_contrast_widgets(&widget, ContrastLevel::AA);
+        assert_eq!(ids, vec![dark_id]);
+    }
+
+    #[test]
+    fn contrast_through_transparent_parents() {
+        use crate::widgets::{Button, Text};
+
+        let text = Text::new("Nested");
+        let text_id = text.id();
+        let stack = vstack! {text}.background_color(Color::TRANSPARENT);
+        let button = Button::new(stack).background_color(Color::rgb(20, 20, 20));
+        let widget = hstack! {button};
+
+        let ids = low_contrast_widgets(&widget, ContrastLevel::AA);
+        assert_eq!(ids, vec![text_id]);
+    }
+
+    #[test]
+    fn layout_system_works() {
+        let hstack = hstack! {}.fill();
+        let layout = hstack.layout();
+
+        let mut resources = Resources::new();
+        resources.insert(layout);
+        resources.insert(WindowSize(Size::unit(500.0)));
+
+        layout_system(&mut resources);
+
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        assert_eq!(layout.size(), Size::unit(500.0));
+    }
+
+    #[test]
+    fn undecorated_window_insets_layout() {
+        let config = WindowConfig::new().decorations(false);
+        let app = App::new(hstack! {}.fill()).window(config);
+        let mut resources = app.resources;
+        resources.get_mut::<WindowSize>().unwrap().0 = Size::unit(556.0);
+
+        layout_system(&mut resources);
+
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        assert_eq!(layout.position(), Position::unit(28.0));
+        assert_eq!(layout.size(), Size::unit(500.0));
+    }
+
+    #[test]
+    fn cursor_icon_of_hovered_widget() {
+        use crate::widgets::{Button, RichTextEditor, Text};
+
+        let _ = FONT.set(init_font());
+        let widget = vstack! {
+            Button::new(Text::new("Save")),
+            RichTextEditor::new(),
+            Button::new(Text::new("Help")).cursor(CursorIcon::Help),
+            Rect::new(100.0, 100.0),
+        };
+        let mut resources = hit_resources(widget, Position::default());
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        let centers: Vec<Position> = layout
+            .children()
+            .iter()
+            .map(|child| {
+                let bounds = child.bounds();
+                Position::new(
+                    (bounds.x[0] + bounds.x[1]) / 2.0,
+                    (bounds.y[0] + bounds.y[1]) / 2.0,
+                )
+            })
+            .collect();
+
+        let mut icons = vec![];
+        for center in centers {
+            resources.get_mut::<CursorPosition>().unwrap().0 = center;
+            icons.push(hovered_cursor_icon(&resources));
+        }
+        assert_eq!(
+            icons,
+            [
+                CursorIcon::Pointer,
+                CursorIcon::Text,
+                CursorIcon::Help,
+                CursorIcon::Default
+            ]
+        );
+    }
+
+    #[test]
+    fn sync_added_and_removed_widgets() {
+        let old = Rect::new(100.0, 100.0);
+        let old_id = old.id();
+        let widget = hstack! {old};
+        let mut resources = hit_resources(widget, Position::unit(50.0));
+        resources.insert(StateTracker::new(
+            resources.get::<Box<dyn Widget>>().unwrap().as_ref(),
+        ));
+        resources.insert(Focus::new());
+        resources.get_mut::<Focus>().unwrap().focus(old_id);
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        let new = Rect::new(100.0, 100.0);
+        let new_id = new.id();
+        *resources.get_mut::<Box<dyn Widget>>().unwrap() = Box::new(hstack! {new});
+        sync_widget_tree(&mut resources);
+        resources.insert(WindowSize(Size::unit(500.0)));
+        layout_system(&mut resources);
+        intersection_observer(&mut resources);
+
+        let state_tracker = resources.get::<StateTracker>().unwrap();
+        assert!(!state_tracker.contains(old_id));
+        assert_eq!(
+            state_tracker.current_state(new_id),
+            Some(&WidgetState::Hovered)
+        );
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        assert!(layout.get(new_id).is_some());
+        assert_eq!(resources.get::<Focus>().unwrap().focused(), None);
+    }
+
+    /// A rect that reads its width and color from signals.
+    struct SignalRect {
+        id: GlobalId,
+        width: Signal<f32>,
+        color: Signal<Color<Rgba>>,
+    }
+
+    impl Widget for SignalRect {
+        fn id(&self) -> GlobalId {
+            self.id
+        }
+
+        fn view(&self) -> Box<dyn View> {
+            let mut view = RectView::new(self.id);
+            view.color = self.color.get();
+            Box::new(view)
+        }
+
+        fn layout(&self) -> Box<dyn Layout> {
+            let mut layout = EmptyLayout::new();
+            layout.intrinsic_size = IntrinsicSize::fixed(self.width.get(), 10.0);
+            layout.id = self.id;
+            Box::new(layout)
+        }
+    }
+
+    #[test]
+    fn signals_mark_readers_dirty() {
+        let (width, color) = (Signal::new(10.0), Signal::new(Color::BLACK));
+        let rect = SignalRect {
+            id: GlobalId::new(),
+            width: width.clone(),
+            color: color.clone(),
+        };
+        let id = rect.id;
+        let mut resources = Resources::new();
+        resources.insert(StateTracker::new(&rect));
+        resources.insert(signal::track(Reader::Layout, || rect.layout()));
+        resources.insert::<Box<dyn Widget>>(Box::new(rect));
+        resources.insert(WindowSize(Size::unit(500.0)));
+        let mut redraw = Redraw::new();
+        redraw.set_on_demand(true);
+        redraw.finish_frame();
+        resources.insert(redraw);
+
+        layout_system(&mut resources);
+        let widget = resources.get::<Box<dyn Widget>>().unwrap();
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        DisplayList::record(widget.as_ref(), layout.as_ref());
+        sync_widget_tree(&mut resources);
+        assert!(!resources.get::<Redraw>().unwrap().is_needed());
+
+        color.set(Color::WHITE);
+        sync_widget_tree(&mut resources);
+        let redraw = resources.get::<Redraw>().unwrap();
+        assert!(redraw.is_needed());
+        assert!(redraw.dirty_widgets().contains(&id));
+
+        width.set(40.0);
+        sync_widget_tree(&mut resources);
+        layout_system(&mut resources);
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        assert_eq!(layout.get(id).unwrap().size().width, 40.0);
+    }
+
+    #[test]
+    fn bound_widgets_follow_signals() {
+        use crate::view::init_font;
+        use crate::widgets::TextField;
+
+        let _ = FONT.set(init_font());
+        let name = Signal::new(String::from("Alice"));
+        let field = TextField::new().bind(name.clone());
+        let text = field.text.id();
+        let root = hstack! {field};
+        let mut resources = Resources::new();
+        resources.insert(StateTracker::new(&root));
+        resources.insert(root.layout());
+        resources.insert::<Box<dyn Widget>>(Box::new(root));
+        resources.insert(WindowSize(Size::unit(500.0)));
+
+        layout_system(&mut resources);
+        let widget = resources.get::<Box<dyn Widget>>().unwrap();
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        DisplayList::record(widget.as_ref(), layout.as_ref());
+
+        name.set(String::from("Bob"));
+        sync_widget_tree(&mut resources);
+        let widget = resources.get::<Box<dyn Widget>>().unwrap();
+        let label = widget.get(text).unwrap().as_semantics().unwrap().label();
+        assert_eq!(label.as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn keyboard_inset_moves_focused_widget_up() {
+        use crate::widgets::Rect;
+
+        let field = Rect::new(100.0, 100.0);
+        let id = field.id();
+        let widget = vstack! {Rect::new(100.0, 300.0), field};
+        let mut resources = Resources::new();
+        resources.insert(widget.layout());
+        resources.insert(WindowSize(Size::unit(500.0)));
+        resources.insert(KeyboardInset::default());
+        resources.insert(Focus::new());
+        resources.get_mut::<Focus>().unwrap().focus(id);
+
+        handle_keyboard_inset(&mut resources, &KeyboardInset(200.0));
+        layout_system(&mut resources);
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        assert_eq!(layout.get(id).unwrap().bounds().y[1], 300.0);
+
+        // The content goes back down when the keyboard is hidden
+        handle_keyboard_inset(&mut resources, &KeyboardInset(0.0));
+        layout_system(&mut resources);
+        let layout = resources.get::<Box<dyn Layout>>().unwrap();
+        assert_eq!(layout.position(), Position::default());
+    }
+
+    #[test]
+    fn track_system_status() {
+        let app = App::new(hstack! {}).track_system_status();
+        app.resources.get::<status::SystemStatus>().unwrap();
+        assert_eq!(app.systems.len(), 3);
+    }
+
+    #[test]
+    fn scale_ui() {
+        let rect = widgets::Rect::new(10.0, 10.0).background_color(Color::BLACK);
+        let config = WindowConfig::new().size(40.0, 40.0);
+        let mut app = App::new(rect).window(config).ui_scale(2.0);
+
+        let image = app.capture_frame();
+        assert_eq!(image.get_pixel(15, 15).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(25, 25).0, [255, 255, 255, 255]);
+        let layout = app.resources.get::<Box<dyn Layout>>().unwrap();
+        assert_eq!(layout.size(), Size::unit(10.0));
+    }
+
+    #[test]
+    fn hover_transformed_widget() {
+        let rect = Rect::new(100.0, 100.0);
+        let id = rect.id();
+        let widget = rect.scale(0.5);
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(500.0));
+
+        let mut resources = Resources::new();
+        resources.insert(StateTracker::new(&widget));
+        resources.insert(layout);
+        resources.insert(Box::new(widget) as Box<dyn Widget>);
+        resources.insert(CursorPosition(Position::unit(10.0)));
+        resources.insert::<Vec<WidgetEvent>>(Vec::new());
+
+        // The corner of the layout is outside the scaled down rect
+        intersection_observer(&mut resources);
+        assert!(resources.get::<Vec<WidgetEvent>>().unwrap().is_empty());
+
+        resources.get_mut::<CursorPosition>().unwrap().0 = Position::unit(30.0);
+        intersection_observer(&mut resources);
+        let events: &Vec<WidgetEvent> = resources.get().unwrap();
+        assert!(events.contains(&WidgetEvent::Hovered(id)));
+    }
+
+    #[test]
+    fn draw_rotated_widget() {
+        let rect = widgets::Rect::new(40.0, 10.0)
+            .background_color(Color::BLACK)
+            .rotate(90.0);
+        let config = WindowConfig::new().size(40.0, 40.0);
+        let mut app = App::new(rect).window(config);
+
+        // The rect is drawn upright around its center
+        let image = app.capture_frame();
+        assert_eq!(image.get_pixel(20, 15).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 5).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
     fn initial_resources() {
         let app = App::new(hstack! {});
         app.resources.get::<CursorPosition>().unwrap();
@@ -379,13 +2910,110 @@ mod test {
         app.resources.get::<Box<dyn Widget>>().unwrap();
         app.resources.get::<Vec<WidgetEvent>>().unwrap();
         app.resources.get::<StateTracker>().unwrap();
+        app.resources.get::<ImageCache>().unwrap();
+        app.resources.get::<DragState>().unwrap();
+        app.resources.get::<PressState>().unwrap();
+        app.resources.get::<LongPress>().unwrap();
+        app.resources.get::<GlyphCache>().unwrap();
+        app.resources.get::<FrameCapture>().unwrap();
+        app.resources.get::<FileWatcher>().unwrap();
+        app.resources.get::<LayerPool>().unwrap();
+        app.resources.get::<Processes>().unwrap();
+        app.resources.get::<UiScale>().unwrap();
+        app.resources.get::<Magnifier>().unwrap();
+        app.resources.get::<VisionFilter>().unwrap();
+        app.resources.get::<FileDrop>().unwrap();
+        app.resources.get::<Shortcuts>().unwrap();
+        app.resources.get::<Focus>().unwrap();
+        app.resources.get::<Gestures>().unwrap();
+        app.resources.get::<KeyboardInset>().unwrap();
+        app.resources.get::<DragAndDrop>().unwrap();
+        app.resources.get::<CursorState>().unwrap();
+        app.resources.get::<Modal>().unwrap();
+        app.resources.get::<Scheduler>().unwrap();
 
-        assert_eq!(app.resources.len(), 7);
+        let hotkeys = cfg!(feature = "global-hotkeys") as usize;
+        assert_eq!(app.resources.len(), 33 + hotkeys);
     }
 
     #[test]
     fn init_systems() {
         let app = App::new(hstack! {});
-        assert_eq!(app.systems.len(), 1);
+        assert_eq!(app.systems.len(), 2);
+    }
+
+    #[test]
+    fn systems_are_ordered_by_priority() {
+        let app = App::new(hstack! {})
+            .add_system_with_priority(Priority::Prefetch, |_| {})
+            .add_system_with_priority(Priority::Async, |_| {})
+            .add_system(|_: &mut Resources| {});
+        let priorities: Vec<_> = app.systems.iter().map(|system| system.priority).collect();
+        assert_eq!(
+            priorities,
+            [
+                Priority::Input,
+                Priority::Input,
+                Priority::Input,
+                Priority::Async,
+                Priority::Prefetch
+            ]
+        );
+    }
+
+    #[test]
+    fn mount_and_unmount_widgets() {
+        use crate::widgets::Lifecycle;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct Tracked {
+            id: GlobalId,
+            mounted: Rc<Cell<bool>>,
+        }
+
+        impl Lifecycle for Tracked {
+            fn mount(&mut self) {
+                self.mounted.set(true);
+            }
+
+            fn unmount(&mut self) {
+                self.mounted.set(false);
+            }
+        }
+
+        impl Widget for Tracked {
+            fn id(&self) -> GlobalId {
+                self.id
+            }
+
+            fn view(&self) -> Box<dyn view::View> {
+                Box::new(view::RectView::new(self.id))
+            }
+
+            fn layout(&self) -> Box<dyn Layout> {
+                let mut layout = layout::EmptyLayout::new();
+                layout.id = self.id;
+                Box::new(layout)
+            }
+
+            fn as_lifecycle_mut(&mut self) -> Option<&mut dyn Lifecycle> {
+                Some(self)
+            }
+        }
+
+        let mounted = Rc::new(Cell::new(false));
+        let widget = Tracked {
+            id: GlobalId::new(),
+            mounted: mounted.clone(),
+        };
+        let root: Box<dyn Widget> = Box::new(hstack! {widget});
+        let mut resources = Resources::new();
+        resources.insert(root);
+
+        for_each_lifecycle(&mut resources, |widget| widget.mount());
+        assert!(mounted.get());
+        for_each_lifecycle(&mut resources, |widget| widget.unmount());
+        assert!(!mounted.get());
     }
 }
@@ -0,0 +1,313 @@
+//! Format and parse numbers and dates the way the user's region writes
+//! them, for inputs that shouldn't assume `1,234.5` and month-first dates.
+//!
+//! # Example
+//! ```
+//! use agape::locale::{Date, Locale};
+//!
+//! let locale = Locale::from_tag("de_DE");
+//! assert_eq!(locale.format_number(1234.5, 2), "1.234,50");
+//! assert_eq!(locale.parse_number("1.234,5"), Some(1234.5));
+//!
+//! let date = Date::new(2024, 3, 9).unwrap();
+//! assert_eq!(locale.format_date(date), "09.03.2024");
+//! assert_eq!(locale.parse_date("9.3.2024"), Some(date));
+//! ```
+
+/// The order that the parts of a date are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// A day in the gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Create a date, returns `None` if the day doesn't exist.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Get the month, from 1 to 12.
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // Sakamoto's method, where 0 is a Sunday
+        const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let year = if self.month < 3 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let day = (year + year.div_euclid(4) - year.div_euclid(100)
+            + year.div_euclid(400)
+            + OFFSETS[self.month as usize - 1]
+            + self.day as i32)
+            .rem_euclid(7);
+        match day {
+            0 => Weekday::Sunday,
+            1 => Weekday::Monday,
+            2 => Weekday::Tuesday,
+            3 => Weekday::Wednesday,
+            4 => Weekday::Thursday,
+            5 => Weekday::Friday,
+            _ => Weekday::Saturday,
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// How numbers and dates are written in a region.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale {
+    pub decimal_separator: char,
+    /// Separates groups of thousands, if numbers are grouped.
+    pub group_separator: Option<char>,
+    pub date_order: DateOrder,
+    pub date_separator: char,
+    pub first_day_of_week: Weekday,
+}
+
+impl Default for Locale {
+    /// The `en_US` locale.
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: Some(','),
+            date_order: DateOrder::MonthDayYear,
+            date_separator: '/',
+            first_day_of_week: Weekday::Sunday,
+        }
+    }
+}
+
+impl Locale {
+    /// Get the locale of the user, from the `LC_ALL`, `LC_NUMERIC` and
+    /// `LANG` environment variables.
+    pub fn current() -> Self {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|tag| !tag.is_empty())
+            .map_or_else(Self::default, |tag| Self::from_tag(&tag))
+    }
+
+    /// Get the locale for a language tag like `fr_FR`, `de-CH` or
+    /// `en_GB.UTF-8`. Regions that aren't known fall back to the conventions
+    /// of the language, and then to `en_US`.
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default();
+        let mut parts = tag.split(['_', '-']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        let us = Self::default();
+        let european = |group, separator| Self {
+            decimal_separator: ',',
+            group_separator: Some(group),
+            date_order: DateOrder::DayMonthYear,
+            date_separator: separator,
+            first_day_of_week: Weekday::Monday,
+        };
+        match (language.as_str(), region.as_str()) {
+            ("en", "US" | "") => us,
+            ("en", "CA") => Self {
+                date_order: DateOrder::YearMonthDay,
+                date_separator: '-',
+                ..us
+            },
+            ("en", _) => Self {
+                date_order: DateOrder::DayMonthYear,
+                first_day_of_week: Weekday::Monday,
+                ..us
+            },
+            ("de" | "fr" | "it", "CH") => Self {
+                decimal_separator: '.',
+                ..european('\'', '.')
+            },
+            ("de", _) | ("nl", _) | ("da", _) | ("tr", _) => european('.', '.'),
+            ("fr", _) | ("ru", _) | ("pl", _) | ("fi", _) | ("cs", _) => european('\u{a0}', '.'),
+            ("sv", _) | ("nb", _) | ("no", _) => european('\u{a0}', '-'),
+            ("es", _) | ("pt", _) | ("it", _) => european('.', '/'),
+            ("ja", _) | ("zh", _) | ("ko", _) => Self {
+                date_order: DateOrder::YearMonthDay,
+                date_separator: '-',
+                ..us
+            },
+            _ => us,
+        }
+    }
+
+    /// Format a number with a fixed number of `decimals`.
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = match formatted.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut output = String::new();
+        if value.is_sign_negative() && formatted.chars().any(|c| c != '0' && c != '.') {
+            output.push('-');
+        }
+        for (i, digit) in whole.chars().enumerate() {
+            let remaining = whole.len() - i;
+            if i > 0 && remaining % 3 == 0 {
+                if let Some(separator) = self.group_separator {
+                    output.push(separator);
+                }
+            }
+            output.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            output.push(self.decimal_separator);
+            output.push_str(fraction);
+        }
+        output
+    }
+
+    /// Parse a number written in this locale, group separators are
+    /// optional. Returns `None` if the text isn't a number.
+    pub fn parse_number(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let mut normalized = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                _ if c == self.decimal_separator => normalized.push('.'),
+                _ if Some(c) == self.group_separator => {}
+                // Spaces are often typed in place of a non-breaking space
+                ' ' | '\u{202f}' if self.group_separator == Some('\u{a0}') => {}
+                '0'..='9' | '-' | '+' => normalized.push(c),
+                _ => return None,
+            }
+        }
+        normalized.parse().ok()
+    }
+
+    /// Format a date with zero padded days and months.
+    pub fn format_date(&self, date: Date) -> String {
+        let separator = self.date_separator;
+        let (year, month, day) = (date.year, date.month, date.day);
+        match self.date_order {
+            DateOrder::DayMonthYear => format!("{day:02}{separator}{month:02}{separator}{year}"),
+            DateOrder::MonthDayYear => format!("{month:02}{separator}{day:02}{separator}{year}"),
+            DateOrder::YearMonthDay => format!("{year}{separator}{month:02}{separator}{day:02}"),
+        }
+    }
+
+    /// Parse a date written in this locale's order, any of `/`, `.` and `-`
+    /// are accepted between the parts. Returns `None` if the date doesn't
+    /// exist.
+    pub fn parse_date(&self, text: &str) -> Option<Date> {
+        let parts: Vec<&str> = text
+            .trim()
+            .split(['/', '.', '-', self.date_separator])
+            .filter(|part| !part.is_empty())
+            .collect();
+        let [a, b, c] = parts.as_slice() else {
+            return None;
+        };
+        let (year, month, day) = match self.date_order {
+            DateOrder::DayMonthYear => (c, b, a),
+            DateOrder::MonthDayYear => (c, a, b),
+            DateOrder::YearMonthDay => (a, b, c),
+        };
+        Date::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    }
+
+    /// Get the days of the week in the order a calendar shows them.
+    pub fn weekdays(&self) -> [Weekday; 7] {
+        use Weekday::*;
+        let days = [
+            Monday, Tuesday, Wednesday, Thursday, Friday, Saturday, Sunday,
+        ];
+        let first = self.first_day_of_week as usize;
+        std::array::from_fn(|i| days[(first + i) % 7])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_numbers() {
+        let us = Locale::default();
+        assert_eq!(us.format_number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(us.format_number(-12.0, 0), "-12");
+        assert_eq!(us.format_number(-0.001, 1), "0.0");
+
+        let swiss = Locale::from_tag("de_CH.UTF-8");
+        assert_eq!(swiss.format_number(1234.5, 1), "1'234.5");
+    }
+
+    #[test]
+    fn parse_numbers() {
+        let french = Locale::from_tag("fr_FR");
+        assert_eq!(french.parse_number("1 234,5"), Some(1234.5));
+        assert_eq!(french.parse_number("-3,25"), Some(-3.25));
+        assert_eq!(french.parse_number("1.5"), None);
+        assert_eq!(Locale::default().parse_number("1,000.25"), Some(1000.25));
+    }
+
+    #[test]
+    fn dates_in_order() {
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(Locale::default().format_date(date), "02/29/2024");
+        assert_eq!(Locale::from_tag("ja_JP").format_date(date), "2024-02-29");
+
+        let british = Locale::from_tag("en_GB");
+        assert_eq!(british.parse_date("29/02/2024"), Some(date));
+        assert_eq!(british.parse_date("02/29/2024"), None);
+        assert_eq!(Date::new(2023, 2, 29), None);
+    }
+
+    #[test]
+    fn first_day_of_week() {
+        assert_eq!(Locale::default().weekdays()[0], Weekday::Sunday);
+        let german = Locale::from_tag("de");
+        assert_eq!(german.weekdays()[0], Weekday::Monday);
+        assert_eq!(german.weekdays()[6], Weekday::Sunday);
+        assert_eq!(Date::new(2024, 3, 9).unwrap().weekday(), Weekday::Saturday);
+    }
+}
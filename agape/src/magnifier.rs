@@ -0,0 +1,207 @@
+//! A loupe that magnifies the area under the cursor, for low-vision users and
+//! for checking designs pixel by pixel.
+//!
+//! The [`Magnifier`] resource is drawn on top of the finished frame. It's
+//! turned on and off with Ctrl+Alt+M by default.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//!
+//! let app = App::new(hstack! {}).magnifier(4.0);
+//! ```
+use agape_core::Position;
+use tiny_skia::{
+    FillRule, FilterQuality, IntRect, Paint, PathBuilder, Pattern, Pixmap, SpreadMode, Stroke,
+    Transform,
+};
+use winit::keyboard::{KeyCode, ModifiersState};
+
+/// The smallest magnifier zoom.
+pub const MIN_ZOOM: f32 = 1.0;
+/// The largest magnifier zoom.
+pub const MAX_ZOOM: f32 = 16.0;
+
+/// Magnifies a circle around the cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Magnifier {
+    enabled: bool,
+    zoom: f32,
+    /// The diameter of the loupe in pixels.
+    size: f32,
+    /// Whether the keyboard shortcut toggles the magnifier.
+    shortcut: bool,
+    modifiers: ModifiersState,
+}
+
+impl Default for Magnifier {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            zoom: 2.0,
+            size: 160.0,
+            shortcut: true,
+            modifiers: ModifiersState::empty(),
+        }
+    }
+}
+
+impl Magnifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Set how much the loupe magnifies, which is clamped between
+    /// [`MIN_ZOOM`] and [`MAX_ZOOM`].
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    /// Get the diameter of the loupe.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size.max(0.0);
+    }
+
+    /// Turn the keyboard shortcut on or off.
+    pub fn set_shortcut(&mut self, enabled: bool) {
+        self.shortcut = enabled;
+    }
+
+    pub(crate) fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    /// Handle the toggle shortcut.
+    pub(crate) fn handle_key(&mut self, key: KeyCode) {
+        if !self.shortcut || !self.modifiers.control_key() || !self.modifiers.alt_key() {
+            return;
+        }
+        if key == KeyCode::KeyM {
+            self.toggle();
+        }
+    }
+
+    /// Draw the loupe centered on the cursor, magnifying what's already
+    /// been drawn to the `pixmap`.
+    pub(crate) fn render(&self, pixmap: &mut Pixmap, cursor: Position) {
+        if !self.enabled || self.size <= 0.0 {
+            return;
+        }
+
+        // Copy the area that fills the loupe once it's magnified
+        let radius = self.size / 2.0;
+        let sample_radius = radius / self.zoom;
+        let sample = IntRect::from_ltrb(
+            (cursor.x - sample_radius).floor().max(0.0) as i32,
+            (cursor.y - sample_radius).floor().max(0.0) as i32,
+            ((cursor.x + sample_radius).ceil() as i32).min(pixmap.width() as i32),
+            ((cursor.y + sample_radius).ceil() as i32).min(pixmap.height() as i32),
+        );
+        let Some(sample) = sample else {
+            return;
+        };
+        let Some(source) = pixmap.clone_rect(sample) else {
+            return;
+        };
+
+        let Some(circle) = PathBuilder::from_circle(cursor.x, cursor.y, radius) else {
+            return;
+        };
+        // Nearest filtering keeps the edges of magnified pixels sharp
+        let transform = Transform::from_translate(sample.x() as f32, sample.y() as f32)
+            .post_translate(-cursor.x, -cursor.y)
+            .post_scale(self.zoom, self.zoom)
+            .post_translate(cursor.x, cursor.y);
+        let paint = Paint {
+            shader: Pattern::new(
+                source.as_ref(),
+                SpreadMode::Pad,
+                FilterQuality::Nearest,
+                1.0,
+                transform,
+            ),
+            ..Default::default()
+        };
+        pixmap.fill_path(
+            &circle,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+
+        let mut border = Paint::default();
+        border.set_color_rgba8(60, 60, 60, 255);
+        border.anti_alias = true;
+        let stroke = Stroke {
+            width: 2.0,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&circle, &border, &stroke, Transform::identity(), None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toggle_shortcut() {
+        let mut magnifier = Magnifier::new();
+        magnifier.handle_key(KeyCode::KeyM);
+        assert!(!magnifier.is_enabled());
+
+        magnifier.modifiers_changed(ModifiersState::CONTROL | ModifiersState::ALT);
+        magnifier.handle_key(KeyCode::KeyM);
+        assert!(magnifier.is_enabled());
+        magnifier.handle_key(KeyCode::KeyM);
+        assert!(!magnifier.is_enabled());
+
+        magnifier.set_shortcut(false);
+        magnifier.handle_key(KeyCode::KeyM);
+        assert!(!magnifier.is_enabled());
+    }
+
+    #[test]
+    fn magnify_under_cursor() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.fill(tiny_skia::Color::WHITE);
+        // A 4x4 black square just right of the cursor
+        let square = tiny_skia::Rect::from_xywh(50.0, 48.0, 4.0, 4.0).unwrap();
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(0, 0, 0, 255);
+        pixmap.fill_rect(square, &paint, Transform::identity(), None);
+
+        let mut magnifier = Magnifier::new();
+        magnifier.set_enabled(true);
+        magnifier.set_zoom(4.0);
+        magnifier.set_size(60.0);
+        magnifier.render(&mut pixmap, Position::unit(50.0));
+
+        // The square now reaches 16 pixels from the cursor
+        let pixel = |x, y| pixmap.pixel(x, y).unwrap().red();
+        assert_eq!(pixel(64, 50), 0);
+        assert_eq!(pixel(68, 50), 255);
+        assert_eq!(pixel(45, 50), 255);
+    }
+}
@@ -0,0 +1,203 @@
+//! Modals block the rest of the app while they're open.
+//!
+//! A modal is any widget in the tree, opening it makes every widget outside
+//! of it inert: the cursor doesn't hover or click them, Tab doesn't focus
+//! them, their keyboard shortcuts don't fire and they're marked as inert in
+//! [diagnostics](crate::diagnostics) dumps. The background is dimmed while
+//! the modal is open, and the focus is restored when it's closed.
+//!
+//! Modals are opened and closed with the [`Modal`] resource, or from
+//! widgets and other threads by emitting [`OpenModal`] and [`CloseModal`]
+//! as app events.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::event::Event;
+//! use agape::modal::{CloseModal, OpenModal};
+//!
+//! let dialog = Button::new(Text::new("Close")).on_click(|_| Event::new().emit(CloseModal));
+//! let id = dialog.id();
+//! let open = Button::new(Text::new("Delete")).on_click(move |_| Event::new().emit(OpenModal(id)));
+//! let app = App::new(vstack! {dialog, open});
+//! ```
+use crate::focus::{self, Focus};
+use crate::view::{new_paint, skia_color};
+use crate::widgets::Widget;
+use crate::{Resources, render_widget};
+use agape_core::{Color, GlobalId, Rgba};
+use agape_layout::Layout;
+use tiny_skia::{Pixmap, Transform};
+
+/// Opens a modal when it's emitted as an app event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenModal(pub GlobalId);
+
+/// Closes the topmost modal when it's emitted as an app event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloseModal;
+
+/// The modals that are open, each one blocks the ones below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modal {
+    /// The open modals and the widget that was focused before each one
+    /// was opened.
+    stack: Vec<(GlobalId, Option<GlobalId>)>,
+    /// The color drawn over the background, or `None` to leave it as is.
+    pub dim_color: Option<Color<Rgba>>,
+}
+
+impl Default for Modal {
+    fn default() -> Self {
+        Self {
+            stack: vec![],
+            dim_color: Some(Color::rgba(0, 0, 0, 40)),
+        }
+    }
+}
+
+impl Modal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the topmost modal.
+    pub fn current(&self) -> Option<GlobalId> {
+        self.stack.last().map(|(id, _)| *id)
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    /// Open a modal over the ones that are already open, `focused` is
+    /// restored when it's closed.
+    pub fn open(&mut self, id: GlobalId, focused: Option<GlobalId>) {
+        self.stack.push((id, focused));
+    }
+
+    /// Close the topmost modal, returns the widget that was focused before
+    /// it was opened.
+    pub fn close(&mut self) -> Option<GlobalId> {
+        self.stack.pop().and_then(|(_, focused)| focused)
+    }
+
+    /// Returns `true` if a widget is outside of the topmost modal.
+    pub fn is_inert(&self, root: &dyn Widget, id: GlobalId) -> bool {
+        let Some(modal) = self.current().and_then(|modal| root.get(modal)) else {
+            return false;
+        };
+        modal.get(id).is_none()
+    }
+
+    /// Cut a hit path, from the innermost widget outwards, at the topmost
+    /// modal. Paths outside of the modal don't hit anything.
+    pub(crate) fn clip_path(&self, mut path: Vec<GlobalId>) -> Vec<GlobalId> {
+        let Some(modal) = self.current() else {
+            return path;
+        };
+        match path.iter().position(|id| *id == modal) {
+            Some(index) => {
+                path.truncate(index + 1);
+                path
+            }
+            None => vec![],
+        }
+    }
+
+    /// Dim the background and draw the modal over it again.
+    pub(crate) fn render(
+        &self,
+        pixmap: &mut Pixmap,
+        root: &dyn Widget,
+        layout: &dyn Layout,
+        resources: &Resources,
+    ) {
+        let (Some(color), Some(modal)) = (&self.dim_color, self.current()) else {
+            return;
+        };
+        let Some(modal) = root.get(modal) else {
+            return;
+        };
+        let rect =
+            tiny_skia::Rect::from_xywh(0.0, 0.0, pixmap.width() as f32, pixmap.height() as f32);
+        if let Some(rect) = rect {
+            let mut paint = new_paint();
+            paint.set_color(skia_color(color));
+            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+        render_widget(modal, layout, pixmap, resources);
+    }
+}
+
+/// Open a modal and move the focus into it.
+pub(crate) fn handle_open_modal(resources: &mut Resources, &OpenModal(id): &OpenModal) {
+    let focused = resources.get::<Focus>().and_then(Focus::focused);
+    let first = resources
+        .get::<Box<dyn Widget>>()
+        .and_then(|root| root.get(id))
+        .and_then(|modal| focus::tab_order(modal).first().copied());
+    if let Some(modal) = resources.get_mut::<Modal>() {
+        modal.open(id, focused);
+    }
+    if let Some(focus) = resources.get_mut::<Focus>() {
+        match first {
+            Some(first) => focus.focus(first),
+            None => focus.clear(),
+        }
+    }
+}
+
+/// Close the topmost modal and restore the focus from before it opened.
+pub(crate) fn handle_close_modal(resources: &mut Resources, _: &CloseModal) {
+    let Some(modal) = resources.get_mut::<Modal>() else {
+        return;
+    };
+    if !modal.is_open() {
+        return;
+    }
+    let focused = modal.close();
+    if let Some(focus) = resources.get_mut::<Focus>() {
+        match focused {
+            Some(id) => focus.focus(id),
+            None => focus.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hstack;
+    use crate::widgets::Rect;
+
+    #[test]
+    fn clip_paths_at_modal() {
+        let (inner, modal, root) = (GlobalId::new(), GlobalId::new(), GlobalId::new());
+        let mut state = Modal::new();
+        state.open(modal, None);
+
+        assert_eq!(state.clip_path(vec![inner, modal, root]), [inner, modal]);
+        assert!(state.clip_path(vec![GlobalId::new(), root]).is_empty());
+        state.close();
+        assert_eq!(state.clip_path(vec![inner, root]), [inner, root]);
+    }
+
+    #[test]
+    fn widgets_outside_modal_are_inert() {
+        let inside = Rect::new(10.0, 10.0);
+        let inside_id = inside.id();
+        let dialog = hstack! {inside};
+        let dialog_id = dialog.id();
+        let background = Rect::new(10.0, 10.0);
+        let background_id = background.id();
+        let root = hstack! {dialog, background};
+
+        let mut modal = Modal::new();
+        assert!(!modal.is_inert(&root, background_id));
+        modal.open(dialog_id, None);
+        assert!(modal.is_inert(&root, background_id));
+        assert!(modal.is_inert(&root, root.id()));
+        assert!(!modal.is_inert(&root, inside_id));
+    }
+}
@@ -0,0 +1,33 @@
+//! The items that most apps use, import them all with
+//! `use agape::prelude::*`.
+//!
+//! Builders are named the same way across widgets: boxes are styled with
+//! the [`Styled`] builders like `background_color`, and restyled while
+//! they're hovered, pressed or focused with [`StateStyled::style_when`].
+//! Shapes are filled with `fill`, and lines, arcs and text are colored
+//! with `color`. Widgets with children share the [`LayoutExt`] builders
+//! like `padding`.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//!
+//! let app = App::new(
+//!     vstack! {
+//!         Text::new("Hello"),
+//!         Rect::new(100.0, 50.0).background_color(Color::BLUE),
+//!     }
+//!     .padding(12)
+//!     .spacing(8),
+//! );
+//! ```
+pub use crate::event::EventCtx;
+pub use crate::signal::Signal;
+pub use crate::style::{State, StateStyled, Styled};
+pub use crate::view::HitShape;
+pub use crate::widgets::*;
+pub use crate::window::{WindowConfig, WindowControls};
+pub use crate::{App, Resources, hstack, vstack};
+pub use agape_core::{Color, IntoColor, Position, Rgba, Size};
+pub use agape_layout::AxisAlignment;
+pub use winit::window::CursorIcon;
@@ -0,0 +1,184 @@
+//! Presenting frames on a separate thread.
+//!
+//! Widgets are drawn on the cpu, into a pixmap on the ui thread. Finished
+//! frames are handed to a render thread, which uploads them to the window's
+//! surface and presents them. Waiting for vsync happens on the render
+//! thread, so it doesn't hold up input, and slow event handlers don't hold
+//! up presenting the frame that was already drawn.
+//!
+//! There are two pixmaps: the ui thread draws into one while the render
+//! thread presents the other. The render thread requests a redraw after
+//! each frame it presents, and if the ui thread draws faster than that, the
+//! frame that is waiting is replaced by the new one.
+use pixels::Pixels;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use tiny_skia::Pixmap;
+use winit::window::Window;
+
+#[derive(Default)]
+struct State {
+    /// The latest frame, which hasn't been presented yet.
+    frame: Option<Pixmap>,
+    /// A frame that was presented, which can be drawn into again.
+    spare: Option<Pixmap>,
+    /// The size that the surface should be resized to.
+    resize: Option<(u32, u32)>,
+    exit: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state stays valid even if the other thread panicked
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Owns the render thread, which presents the frames drawn on the ui
+/// thread.
+pub(crate) struct Presenter {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Presenter {
+    /// Move the `pixels` of the `window` to a new render thread.
+    pub fn new(pixels: Pixels<'static>, window: Arc<Window>) -> Self {
+        let shared = Arc::new(Shared::default());
+        let thread = std::thread::Builder::new()
+            .name(String::from("agape-render"))
+            .spawn({
+                let shared = Arc::clone(&shared);
+                move || render_loop(pixels, &window, &shared)
+            })
+            .expect("Failed to spawn the render thread");
+
+        Self {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Send a finished frame to be presented, replacing the frame that is
+    /// waiting if there is one. Returns a pixmap with the same size that
+    /// the next frame can be drawn into, if one is free.
+    pub fn present(&self, frame: Pixmap) -> Option<Pixmap> {
+        let (width, height) = (frame.width(), frame.height());
+        let mut state = self.shared.lock();
+        let replaced = state.frame.replace(frame);
+        let free = replaced.or_else(|| state.spare.take());
+        drop(state);
+        self.shared.changed.notify_one();
+        free.filter(|pixmap| pixmap.width() == width && pixmap.height() == height)
+    }
+
+    /// Resize the window's surface, before the next frame is presented.
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.shared.lock();
+        state.resize = Some((width, height));
+        // Frames with the old size are dropped
+        state.frame = None;
+        state.spare = None;
+        drop(state);
+        self.shared.changed.notify_one();
+    }
+}
+
+impl Drop for Presenter {
+    fn drop(&mut self) {
+        self.shared.lock().exit = true;
+        self.shared.changed.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn render_loop(mut pixels: Pixels<'static>, window: &Window, shared: &Shared) {
+    loop {
+        let mut state = shared.lock();
+        while state.frame.is_none() && state.resize.is_none() && !state.exit {
+            state = shared
+                .changed
+                .wait(state)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+        if state.exit {
+            return;
+        }
+        let resize = state.resize.take();
+        let frame = state.frame.take();
+        drop(state);
+
+        if let Some((width, height)) = resize {
+            let resized = pixels
+                .resize_surface(width, height)
+                .and_then(|_| pixels.resize_buffer(width, height));
+            if let Err(error) = resized {
+                log::error!("Failed to resize the surface: {error}");
+            }
+        }
+
+        // A frame drawn before a resize doesn't fit the buffer
+        if let Some(frame) = frame {
+            if pixels.frame().len() == frame.data().len() {
+                pixels.frame_mut().copy_from_slice(frame.data());
+                if let Err(error) = pixels.render() {
+                    log::error!("Failed to present a frame: {error}");
+                }
+            }
+            shared.lock().spare = Some(frame);
+        }
+        window.request_redraw();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Take the frame that is waiting, as the render thread would.
+    fn take_frame(shared: &Shared) -> Option<Pixmap> {
+        shared.lock().frame.take()
+    }
+
+    #[test]
+    fn replace_waiting_frame() {
+        let presenter = Presenter {
+            shared: Arc::default(),
+            thread: None,
+        };
+        let first = Pixmap::new(10, 10).unwrap();
+        assert!(presenter.present(first).is_none());
+        // The first frame wasn't presented, so it's drawn into again
+        let mut second = Pixmap::new(10, 10).unwrap();
+        second.fill(tiny_skia::Color::BLACK);
+        assert!(presenter.present(second).is_some());
+
+        let frame = take_frame(&presenter.shared).unwrap();
+        assert_eq!(frame.pixel(0, 0).unwrap().alpha(), 255);
+        presenter.shared.lock().spare = Some(frame);
+        assert!(presenter.present(Pixmap::new(10, 10).unwrap()).is_some());
+    }
+
+    #[test]
+    fn drop_frames_on_resize() {
+        let presenter = Presenter {
+            shared: Arc::default(),
+            thread: None,
+        };
+        presenter.present(Pixmap::new(10, 10).unwrap());
+        presenter.resize(20, 20);
+        assert!(take_frame(&presenter.shared).is_none());
+
+        // Pixmaps with the old size aren't reused
+        presenter.shared.lock().spare = Some(Pixmap::new(10, 10).unwrap());
+        assert!(presenter.present(Pixmap::new(20, 20).unwrap()).is_none());
+    }
+}
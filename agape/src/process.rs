@@ -0,0 +1,288 @@
+//! Run processes in the background and stream their output to the app, for
+//! developer tools that show live command output.
+//!
+//! The output is read on background threads and delivered on the ui thread,
+//! one line at a time, followed by the exit status.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//! use agape::process::{ProcessEvent, Processes};
+//! use agape::resources::Resources;
+//! use std::process::Command;
+//!
+//! let mut started = false;
+//! let app = App::new(hstack! {}).add_system(move |resources: &mut Resources| {
+//!     if started {
+//!         return;
+//!     }
+//!     started = true;
+//!     let processes = resources.get_mut::<Processes>().unwrap();
+//!     let mut command = Command::new("cargo");
+//!     command.arg("build");
+//!     processes
+//!         .spawn(command, |event| match event {
+//!             ProcessEvent::Stdout(line) | ProcessEvent::Stderr(line) => println!("{line}"),
+//!             ProcessEvent::Exited(status) => println!("Finished with {status}"),
+//!         })
+//!         .unwrap();
+//! });
+//! ```
+use crate::resources::Resources;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+type ProcessFn = Box<dyn FnMut(ProcessEvent)>;
+
+/// Identifies a process started by [`Processes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProcessId(u64);
+
+/// The output of a process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessEvent {
+    /// A line written to stdout, without the line ending.
+    Stdout(String),
+    /// A line written to stderr, without the line ending.
+    Stderr(String),
+    /// The process exited, this is always the last event.
+    Exited(ExitStatus),
+}
+
+enum Message {
+    Line(ProcessEvent),
+    /// One of the output streams was closed.
+    Closed,
+}
+
+struct Process {
+    id: ProcessId,
+    child: Child,
+    f: ProcessFn,
+    /// The number of output streams that are still open.
+    open_streams: u8,
+}
+
+/// The processes started by the app.
+pub struct Processes {
+    processes: Vec<Process>,
+    next_id: u64,
+    sender: Sender<(ProcessId, Message)>,
+    receiver: Receiver<(ProcessId, Message)>,
+}
+
+impl Default for Processes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Processes {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            processes: vec![],
+            next_id: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Start a process, `f` is called with each line of its output and
+    /// then with its exit status.
+    ///
+    /// # Errors
+    /// Returns an error if the process could not be started.
+    pub fn spawn(
+        &mut self,
+        mut command: Command,
+        f: impl FnMut(ProcessEvent) + 'static,
+    ) -> io::Result<ProcessId> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let id = ProcessId(self.next_id);
+        self.next_id += 1;
+        if let Some(stdout) = child.stdout.take() {
+            read_lines(id, stdout, ProcessEvent::Stdout, self.sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            read_lines(id, stderr, ProcessEvent::Stderr, self.sender.clone());
+        }
+
+        self.processes.push(Process {
+            id,
+            child,
+            f: Box::new(f),
+            open_streams: 2,
+        });
+        Ok(id)
+    }
+
+    /// Kill a running process, it's still reported as exited.
+    ///
+    /// # Errors
+    /// Returns an error if the process could not be killed.
+    pub fn kill(&mut self, id: ProcessId) -> io::Result<()> {
+        match self.processes.iter_mut().find(|process| process.id == id) {
+            Some(process) => process.child.kill(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_running(&self, id: ProcessId) -> bool {
+        self.processes.iter().any(|process| process.id == id)
+    }
+
+    /// Get the number of running processes.
+    pub fn len(&self) -> usize {
+        self.processes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processes.is_empty()
+    }
+
+    /// Deliver the output read since the last poll, and the exit status of
+    /// processes whose output has all been read.
+    fn poll(&mut self) {
+        while let Ok((id, message)) = self.receiver.try_recv() {
+            let Some(process) = self.processes.iter_mut().find(|process| process.id == id) else {
+                continue;
+            };
+            match message {
+                Message::Line(event) => (process.f)(event),
+                Message::Closed => process.open_streams -= 1,
+            }
+        }
+
+        self.processes.retain_mut(|process| {
+            if process.open_streams > 0 {
+                return true;
+            }
+            match process.child.try_wait() {
+                Ok(Some(status)) => {
+                    (process.f)(ProcessEvent::Exited(status));
+                    false
+                }
+                Ok(None) => true,
+                Err(err) => {
+                    log::warn!("Failed to get the status of a process: {err}");
+                    false
+                }
+            }
+        });
+    }
+}
+
+impl Drop for Processes {
+    fn drop(&mut self) {
+        for process in &mut self.processes {
+            let _ = process.child.kill();
+        }
+    }
+}
+
+/// Send each line of a stream from a background thread.
+fn read_lines(
+    id: ProcessId,
+    stream: impl Read + Send + 'static,
+    event: fn(String) -> ProcessEvent,
+    sender: Sender<(ProcessId, Message)>,
+) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut line = vec![];
+        while let Ok(len) = reader.read_until(b'\n', &mut line) {
+            if len == 0 {
+                break;
+            }
+            let text = String::from_utf8_lossy(&line);
+            let text = text.trim_end_matches(['\n', '\r']).to_owned();
+            if sender.send((id, Message::Line(event(text)))).is_err() {
+                return;
+            }
+            line.clear();
+        }
+        let _ = sender.send((id, Message::Closed));
+    });
+}
+
+/// Delivers the output of the [`Processes`].
+pub(crate) fn update_processes(resources: &mut Resources) {
+    if let Some(processes) = resources.get_mut::<Processes>() {
+        if !processes.is_empty() {
+            processes.poll();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    fn run(processes: &mut Processes, id: ProcessId) {
+        let start = Instant::now();
+        while processes.is_running(id) {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            processes.poll();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn stream_output() {
+        let events = Rc::new(RefCell::new(vec![]));
+        let received = Rc::clone(&events);
+        let mut processes = Processes::new();
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo one; echo two; echo error >&2; exit 3"]);
+        let id = processes
+            .spawn(command, move |event| received.borrow_mut().push(event))
+            .unwrap();
+        run(&mut processes, id);
+
+        let events = events.borrow();
+        let stdout: Vec<&ProcessEvent> = events
+            .iter()
+            .filter(|event| matches!(event, ProcessEvent::Stdout(_)))
+            .collect();
+        assert_eq!(
+            stdout,
+            [
+                &ProcessEvent::Stdout("one".to_owned()),
+                &ProcessEvent::Stdout("two".to_owned())
+            ]
+        );
+        assert!(events.contains(&ProcessEvent::Stderr("error".to_owned())));
+        let Some(ProcessEvent::Exited(status)) = events.last() else {
+            panic!("The process didn't exit last");
+        };
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[test]
+    fn kill_process() {
+        let exited = Rc::new(RefCell::new(false));
+        let received = Rc::clone(&exited);
+        let mut processes = Processes::new();
+        let mut command = Command::new("sleep");
+        command.arg("10");
+        let id = processes
+            .spawn(command, move |event| {
+                *received.borrow_mut() = matches!(event, ProcessEvent::Exited(_));
+            })
+            .unwrap();
+
+        processes.kill(id).unwrap();
+        run(&mut processes, id);
+        assert!(*exited.borrow());
+    }
+}
@@ -4,9 +4,22 @@
 //! ### Resources
 //! - [`CursorPosition`]
 //! - [`WindowSize`]
+//! - [`ClickCounter`]
+//! - [`LongPress`]
+//! - [`KeyboardInset`]
+//! - [`Redraw`]
+//! - [`ImageCache`](crate::image_cache::ImageCache)
 //!
-use agape_core::{Position, Size};
+use crate::focus::Focus;
+use crate::modal::Modal;
+use crate::view::register_font;
+use crate::widgets::{InteractionState, StateTracker, Widget, WidgetState};
+use crate::{PressState, Result};
+use agape_core::{GlobalId, Position, Size};
 use std::any::Any;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Global resources
 pub struct Resources {
@@ -80,6 +93,61 @@ impl Resources {
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
+
+    /// Load a font and return its family name, which can be used with the
+    /// `font_family` modifier of text widgets.
+    ///
+    /// Fonts should be loaded before the app is created, so that text is
+    /// measured with them.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes aren't a valid font.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::Resources;
+    ///
+    /// let bytes = std::fs::read("fonts/Inter/static/Inter-Bold.ttf").unwrap();
+    /// let family = Resources::load_font(bytes).unwrap();
+    /// assert_eq!(family, "Inter");
+    /// ```
+    pub fn load_font(bytes: impl Into<Vec<u8>>) -> Result<String> {
+        register_font(bytes.into())
+    }
+
+    /// Load a font from a file and return its family name.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a valid font.
+    pub fn load_font_file(path: impl AsRef<Path>) -> Result<String> {
+        register_font(std::fs::read(path)?)
+    }
+
+    /// Get whether a widget is hovered, pressed, focused or disabled, so
+    /// that its view can be drawn differently in each state.
+    pub fn interaction_state(&self, id: GlobalId) -> InteractionState {
+        let hovered = self
+            .get::<StateTracker>()
+            .and_then(|tracker| tracker.current_state(id))
+            .is_some_and(|state| *state != WidgetState::Resting);
+        let pressed = self
+            .get::<PressState>()
+            .is_some_and(|press| press.ids.contains(&id));
+        let focus = self.get::<Focus>();
+        let focused = focus.is_some_and(|focus| focus.is_focused(id));
+        let disabled = match (self.get::<Modal>(), self.get::<Box<dyn Widget>>()) {
+            (Some(modal), Some(root)) => modal.is_inert(root.as_ref(), id),
+            _ => false,
+        };
+
+        InteractionState {
+            hovered,
+            pressed,
+            focused,
+            focus_visible: focused && focus.is_some_and(Focus::is_ring_visible),
+            disabled,
+        }
+    }
 }
 
 /// The current cursor position.
@@ -90,6 +158,218 @@ pub struct CursorPosition(pub Position);
 #[derive(Debug, Default, Copy, Clone)]
 pub struct WindowSize(pub Size);
 
+/// The height of the bottom of the window that an on-screen keyboard
+/// covers, `0.0` when there's no keyboard.
+///
+/// Platforms with a soft keyboard, or a virtual keyboard widget, report the
+/// inset by setting this resource or emitting it as an
+/// [`Event`](crate::event::Event). While it's set the content is moved up
+/// so that the focused widget stays above the keyboard.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct KeyboardInset(pub f32);
+
+/// Counts clicks that happen quickly one after another in the same place,
+/// for double and triple clicks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClickCounter {
+    /// The longest time between two clicks that are counted together.
+    pub interval: Duration,
+    /// How far the cursor can move between two clicks that are counted
+    /// together.
+    pub distance: f32,
+    count: u32,
+    last_click: Option<(Instant, Position)>,
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(500),
+            distance: 4.0,
+            count: 0,
+            last_click: None,
+        }
+    }
+}
+
+impl ClickCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a click and return how many clicks are in the current series,
+    /// which goes from one up to three and then starts over.
+    pub fn click(&mut self, time: Instant, position: Position) -> u32 {
+        let continues = self.last_click.is_some_and(|(last_time, last_position)| {
+            let moved = (position.x - last_position.x).hypot(position.y - last_position.y);
+            time.saturating_duration_since(last_time) <= self.interval && moved <= self.distance
+        });
+        self.count = match continues {
+            true => self.count % 3 + 1,
+            false => 1,
+        };
+        self.last_click = Some((time, position));
+        self.count
+    }
+}
+
+/// Detects presses of the primary mouse button that are held down without
+/// the cursor moving, for long presses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongPress {
+    /// How long the button has to be held down.
+    pub duration: Duration,
+    /// How far the cursor can move before the press stops counting.
+    pub distance: f32,
+    press: Option<(Instant, Position)>,
+    fired: bool,
+}
+
+impl Default for LongPress {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(500),
+            distance: 8.0,
+            press: None,
+            fired: false,
+        }
+    }
+}
+
+impl LongPress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start timing a press at a `position`.
+    pub fn press(&mut self, time: Instant, position: Position) {
+        self.press = Some((time, position));
+        self.fired = false;
+    }
+
+    /// Stop timing the press, returns `true` if it was a long press.
+    pub fn release(&mut self) -> bool {
+        self.press = None;
+        std::mem::take(&mut self.fired)
+    }
+
+    /// Check the press with the cursor at a `position`, returns `true` once
+    /// the press has been held long enough. Moving too far cancels the
+    /// press.
+    pub fn update(&mut self, time: Instant, position: Position) -> bool {
+        let Some((start, start_position)) = self.press else {
+            return false;
+        };
+        let moved = (position.x - start_position.x).hypot(position.y - start_position.y);
+        if moved > self.distance {
+            self.press = None;
+            return false;
+        }
+        if time.saturating_duration_since(start) < self.duration {
+            return false;
+        }
+        self.press = None;
+        self.fired = true;
+        true
+    }
+}
+
+/// Decides when the window is drawn again.
+///
+/// Frames are drawn continuously by default. When frames are drawn on
+/// demand, a frame is only drawn after a window event, when a signal that a
+/// widget read has changed, or when a redraw is requested. The systems keep
+/// running either way, only drawing is skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redraw {
+    on_demand: bool,
+    requested: bool,
+    /// The widgets that read a signal that changed since the last frame.
+    dirty: HashSet<GlobalId>,
+}
+
+impl Default for Redraw {
+    fn default() -> Self {
+        Self {
+            on_demand: false,
+            // The first frame is always drawn
+            requested: true,
+            dirty: HashSet::new(),
+        }
+    }
+}
+
+impl Redraw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_on_demand(&self) -> bool {
+        self.on_demand
+    }
+
+    /// Only draw frames when something changed, instead of continuously.
+    pub fn set_on_demand(&mut self, on_demand: bool) {
+        self.on_demand = on_demand;
+    }
+
+    /// Draw the next frame, for changes that signals don't track, like
+    /// animations.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Get the widgets that have to be drawn again, because a signal that
+    /// they read has changed.
+    pub fn dirty_widgets(&self) -> &HashSet<GlobalId> {
+        &self.dirty
+    }
+
+    pub(crate) fn mark_dirty(&mut self, ids: impl IntoIterator<Item = GlobalId>) {
+        self.dirty.extend(ids);
+    }
+
+    /// Returns `true` if the next frame has to be drawn.
+    pub fn is_needed(&self) -> bool {
+        !self.on_demand || self.requested || !self.dirty.is_empty()
+    }
+
+    /// Forget the changes once they're drawn.
+    pub(crate) fn finish_frame(&mut self) {
+        self.requested = false;
+        self.dirty.clear();
+    }
+}
+
+/// The files from other apps that are dragged over, or dropped on, the
+/// window.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileDrop {
+    pub(crate) hovered: Vec<PathBuf>,
+    /// Files that were dropped, each file is a separate window event so
+    /// they're only delivered once the events for all of them are in.
+    pub(crate) dropped: Vec<PathBuf>,
+    /// The widgets that the files are dragged over.
+    pub(crate) targets: Vec<GlobalId>,
+}
+
+impl FileDrop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the files that are dragged over the window, this is empty when
+    /// nothing is being dragged.
+    pub fn hovered(&self) -> &[PathBuf] {
+        &self.hovered
+    }
+
+    /// Returns `true` if files are dragged over the window.
+    pub fn is_hovered(&self) -> bool {
+        !self.hovered.is_empty()
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct EventQueue {
     events: Vec<Box<dyn Any>>,
@@ -105,6 +385,11 @@ impl EventQueue {
         self.events.push(Box::new(item));
     }
 
+    /// Push an event that is already boxed, without boxing it again.
+    pub(crate) fn push_boxed(&mut self, event: Box<dyn Any>) {
+        self.events.push(event);
+    }
+
     /// Get an event from the queue.
     pub fn get<T: 'static>(&self) -> Option<&T> {
         for event in &self.events {
@@ -153,6 +438,40 @@ mod test {
         assert_eq!(events.len(), 3);
     }
 
+    #[test]
+    fn detect_long_press() {
+        let mut long_press = LongPress::new();
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        long_press.press(at(0), Position::unit(10.0));
+        assert!(!long_press.update(at(300), Position::unit(12.0)));
+        assert!(long_press.update(at(500), Position::unit(12.0)));
+        // Only once per press
+        assert!(!long_press.update(at(600), Position::unit(12.0)));
+        assert!(long_press.release());
+
+        // Moving too far cancels the press
+        long_press.press(at(1000), Position::unit(10.0));
+        assert!(!long_press.update(at(1100), Position::unit(30.0)));
+        assert!(!long_press.update(at(1600), Position::unit(10.0)));
+        assert!(!long_press.release());
+    }
+
+    #[test]
+    fn count_clicks() {
+        let mut counter = ClickCounter::new();
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        assert_eq!(counter.click(at(0), Position::unit(10.0)), 1);
+        assert_eq!(counter.click(at(200), Position::unit(12.0)), 2);
+        assert_eq!(counter.click(at(400), Position::unit(12.0)), 3);
+        assert_eq!(counter.click(at(600), Position::unit(12.0)), 1);
+        // Too slow
+        assert_eq!(counter.click(at(1200), Position::unit(12.0)), 1);
+        // Too far
+        assert_eq!(counter.click(at(1300), Position::unit(30.0)), 1);
+    }
+
     #[test]
     fn clear_events() {
         let mut event_queue = EventQueue::new();
@@ -0,0 +1,202 @@
+//! Scale the whole ui, independently of the system's DPI, for accessibility
+//! and user preference.
+//!
+//! The scale is kept in the [`UiScale`] resource. Ctrl+= and Ctrl+- zoom in
+//! and out by default, and Ctrl+0 resets the scale.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//!
+//! let app = App::new(hstack! {})
+//!     .ui_scale(1.25)
+//!     .persist_ui_scale("com.example.notes");
+//! ```
+use std::path::PathBuf;
+use winit::keyboard::{Key, ModifiersState};
+
+/// The smallest ui scale.
+pub const MIN_UI_SCALE: f32 = 0.5;
+/// The largest ui scale.
+pub const MAX_UI_SCALE: f32 = 3.0;
+/// How much the shortcuts change the ui scale.
+pub const UI_SCALE_STEP: f32 = 0.1;
+
+/// The scale of the ui, a scale of `2.0` draws everything twice as large.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UiScale {
+    scale: f32,
+    /// Whether the keyboard shortcuts change the scale.
+    shortcuts: bool,
+    modifiers: ModifiersState,
+    /// The file the scale is saved to.
+    path: Option<PathBuf>,
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl UiScale {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            scale: scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE),
+            shortcuts: true,
+            modifiers: ModifiersState::empty(),
+            path: None,
+        }
+    }
+
+    pub fn get(&self) -> f32 {
+        self.scale
+    }
+
+    /// Set the ui scale, which is clamped between [`MIN_UI_SCALE`] and
+    /// [`MAX_UI_SCALE`]. The scale is saved if it's persisted.
+    pub fn set(&mut self, scale: f32) {
+        let scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        if scale == self.scale {
+            return;
+        }
+        self.scale = scale;
+        self.save();
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.set(round_step(self.scale + UI_SCALE_STEP));
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.set(round_step(self.scale - UI_SCALE_STEP));
+    }
+
+    pub fn reset(&mut self) {
+        self.set(1.0);
+    }
+
+    /// Turn the keyboard shortcuts on or off, apps with their own zoom
+    /// shortcuts, like a [`PrintPreview`](crate::widgets::PrintPreview),
+    /// should turn them off.
+    pub fn set_shortcuts(&mut self, enabled: bool) {
+        self.shortcuts = enabled;
+    }
+
+    /// Save the scale to a file for this user, and load the scale that
+    /// was saved before.
+    pub(crate) fn persist(&mut self, app_id: &str) {
+        let Some(path) = config_dir().map(|dir| dir.join(app_id).join("ui-scale")) else {
+            log::warn!("Failed to find the config directory, the ui scale won't be saved");
+            return;
+        };
+        let saved = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|scale| scale.trim().parse::<f32>().ok());
+        if let Some(scale) = saved {
+            self.scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        }
+        self.path = Some(path);
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, self.scale.to_string()));
+        if let Err(err) = result {
+            log::warn!("Failed to save the ui scale: {err}");
+        }
+    }
+
+    pub(crate) fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    /// Handle the zoom shortcuts.
+    pub(crate) fn handle_key(&mut self, key: &Key) {
+        if !self.shortcuts || !self.modifiers.control_key() {
+            return;
+        }
+        if let Key::Character(key) = key {
+            match key.as_str() {
+                "=" | "+" => self.zoom_in(),
+                "-" => self.zoom_out(),
+                "0" => self.reset(),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Round a scale to the nearest step, so that zooming in and out again
+/// gets back to the same scale.
+fn round_step(scale: f32) -> f32 {
+    (scale / UI_SCALE_STEP).round() * UI_SCALE_STEP
+}
+
+/// Get the directory where the user's settings are stored.
+fn config_dir() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    if cfg!(target_os = "windows") {
+        return var("APPDATA").map(PathBuf::from);
+    }
+    if cfg!(target_os = "macos") {
+        return var("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"));
+    }
+    var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zoom_shortcuts() {
+        let mut scale = UiScale::default();
+        let key = |s: &str| Key::Character(s.into());
+
+        scale.handle_key(&key("="));
+        assert_eq!(scale.get(), 1.0);
+
+        scale.modifiers_changed(ModifiersState::CONTROL);
+        scale.handle_key(&key("="));
+        scale.handle_key(&key("+"));
+        assert!((scale.get() - 1.2).abs() < 0.001);
+        scale.handle_key(&key("-"));
+        assert!((scale.get() - 1.1).abs() < 0.001);
+        scale.handle_key(&key("0"));
+        assert_eq!(scale.get(), 1.0);
+
+        scale.set_shortcuts(false);
+        scale.handle_key(&key("="));
+        assert_eq!(scale.get(), 1.0);
+    }
+
+    #[test]
+    fn clamp_scale() {
+        let mut scale = UiScale::new(10.0);
+        assert_eq!(scale.get(), MAX_UI_SCALE);
+        scale.set(0.0);
+        assert_eq!(scale.get(), MIN_UI_SCALE);
+        scale.zoom_out();
+        assert_eq!(scale.get(), MIN_UI_SCALE);
+    }
+
+    #[test]
+    fn save_scale() {
+        let path = std::env::temp_dir().join("agape-ui-scale/ui-scale");
+        let _ = std::fs::remove_file(&path);
+        let mut scale = UiScale {
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+        scale.set(1.5);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1.5");
+    }
+}
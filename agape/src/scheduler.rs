@@ -0,0 +1,285 @@
+//! Scheduling the work done each frame by priority.
+//!
+//! Systems and tasks are classified with a [`Priority`]. Input handling and
+//! animations always run, while applying the results of background work and
+//! prefetching are deferred to a later frame when the [`Scheduler`] is over
+//! its frame budget. This keeps the app responsive to input while a large
+//! app is under load. Work that's been deferred for too many frames in a
+//! row runs anyway, so it's never starved.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::scheduler::{Priority, Scheduler};
+//! use std::time::Duration;
+//!
+//! fn sync_inbox(resources: &mut Resources) {}
+//!
+//! let app = App::new(hstack! {})
+//!     .frame_budget(Duration::from_millis(8))
+//!     .add_system_with_priority(Priority::Async, sync_inbox)
+//!     .add_system(|resources: &mut Resources| {
+//!         let scheduler = resources.get_mut::<Scheduler>().unwrap();
+//!         scheduler.spawn(Priority::Prefetch, |_| println!("Warming up the cache"));
+//!     });
+//! ```
+use crate::Resources;
+use crate::resources::EventQueue;
+use crate::system::System;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+type Task = Box<dyn FnOnce(&mut Resources)>;
+
+/// How urgent a piece of work is, from the most to the least urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Handling input and laying out the widgets, which always runs.
+    #[default]
+    Input,
+    /// Advancing animations, which always runs.
+    Animation,
+    /// Applying the results of background work, which is deferred when the
+    /// frame is over budget.
+    Async,
+    /// Work that's only needed later, like prefetching, which is deferred
+    /// when less than half of the frame budget is left.
+    Prefetch,
+}
+
+/// Decides which work runs each frame, see the [module docs](self).
+pub struct Scheduler {
+    budget: Duration,
+    max_deferrals: u32,
+    /// When the systems started running for the current event.
+    pass_start: Instant,
+    /// The number of times the systems have run.
+    pass: u64,
+    /// How long the last frame took to draw.
+    render_time: Duration,
+    /// The tasks of each priority and the pass they were spawned in.
+    tasks: [VecDeque<(u64, Task)>; 4],
+    deferred: usize,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_millis(16),
+            max_deferrals: 30,
+            pass_start: Instant::now(),
+            pass: 0,
+            render_time: Duration::ZERO,
+            tasks: Default::default(),
+            deferred: 0,
+        }
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the time that drawing a frame and running the systems should
+    /// take.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    /// Set the number of times in a row that work can be deferred before
+    /// it runs anyway.
+    pub fn set_max_deferrals(&mut self, max_deferrals: u32) {
+        self.max_deferrals = max_deferrals;
+    }
+
+    /// Run a task once, as soon as there's time for its priority.
+    pub fn spawn(&mut self, priority: Priority, task: impl FnOnce(&mut Resources) + 'static) {
+        self.tasks[priority as usize].push_back((self.pass, Box::new(task)));
+    }
+
+    /// Get the number of tasks that haven't run yet.
+    pub fn pending_tasks(&self) -> usize {
+        self.tasks.iter().map(VecDeque::len).sum()
+    }
+
+    /// Get the number of systems and tasks that were deferred the last time
+    /// the systems ran.
+    pub fn deferred(&self) -> usize {
+        self.deferred
+    }
+
+    /// Get the time used from the budget, by the last frame and the systems
+    /// that have run since.
+    pub fn used(&self) -> Duration {
+        self.render_time + self.pass_start.elapsed()
+    }
+
+    /// Returns `true` if work of a `priority` can run now.
+    pub fn has_time_for(&self, priority: Priority) -> bool {
+        match priority {
+            Priority::Input | Priority::Animation => true,
+            Priority::Async => self.used() < self.budget,
+            Priority::Prefetch => self.used() < self.budget / 2,
+        }
+    }
+
+    /// Start running the systems for an event.
+    pub(crate) fn start_pass(&mut self) {
+        self.pass_start = Instant::now();
+        self.pass += 1;
+        self.deferred = 0;
+    }
+
+    /// Remember how long the last frame took to draw.
+    pub(crate) fn finish_frame(&mut self, render_time: Duration) {
+        self.render_time = render_time;
+    }
+
+    /// Returns `true` if work that has been deferred `deferrals` times in a
+    /// row should run, or counts it as deferred.
+    fn should_run(&mut self, priority: Priority, deferrals: u32) -> bool {
+        if deferrals >= self.max_deferrals || self.has_time_for(priority) {
+            return true;
+        }
+        self.deferred += 1;
+        false
+    }
+
+    /// Take the next task that should run, the most urgent ones first.
+    pub(crate) fn next_task(&mut self) -> Option<Task> {
+        let mut skipped = 0;
+        for priority in [
+            Priority::Input,
+            Priority::Animation,
+            Priority::Async,
+            Priority::Prefetch,
+        ] {
+            let queue = &self.tasks[priority as usize];
+            let Some(&(spawned, _)) = queue.front() else {
+                continue;
+            };
+            let deferrals = (self.pass - spawned).min(u32::MAX as u64) as u32;
+            if deferrals >= self.max_deferrals || self.has_time_for(priority) {
+                return self.tasks[priority as usize]
+                    .pop_front()
+                    .map(|(_, task)| task);
+            }
+            skipped += queue.len();
+        }
+        self.deferred += skipped;
+        None
+    }
+}
+
+/// A system and the priority it runs with.
+pub(crate) struct ScheduledSystem {
+    pub priority: Priority,
+    system: Box<dyn System>,
+    /// The number of times in a row the system was deferred.
+    deferrals: u32,
+}
+
+impl ScheduledSystem {
+    pub fn new(priority: Priority, system: Box<dyn System>) -> Self {
+        Self {
+            priority,
+            system,
+            deferrals: 0,
+        }
+    }
+
+    /// Run the system if the scheduler has time for it.
+    pub fn run(&mut self, resources: &mut Resources, event_queue: &EventQueue) {
+        let run = resources
+            .get_mut::<Scheduler>()
+            .is_none_or(|scheduler| scheduler.should_run(self.priority, self.deferrals));
+        if !run {
+            self.deferrals += 1;
+            return;
+        }
+        self.deferrals = 0;
+        self.system.run(resources, event_queue);
+    }
+}
+
+/// Run the tasks that there's time for.
+pub(crate) fn run_tasks(resources: &mut Resources) {
+    while let Some(task) = resources
+        .get_mut::<Scheduler>()
+        .and_then(Scheduler::next_task)
+    {
+        task(resources);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::system::IntoSystem;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn counter(runs: &Rc<Cell<u32>>) -> Box<dyn System> {
+        let runs = runs.clone();
+        Box::new((move |_: &mut Resources| runs.set(runs.get() + 1)).into_system())
+    }
+
+    #[test]
+    fn defer_low_priorities_over_budget() {
+        let mut resources = Resources::new();
+        let mut scheduler = Scheduler::new();
+        scheduler.set_max_deferrals(2);
+        // The last frame used up the whole budget
+        scheduler.finish_frame(Duration::from_millis(20));
+        resources.insert(scheduler);
+        let (input, prefetch) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let mut systems = [
+            ScheduledSystem::new(Priority::Input, counter(&input)),
+            ScheduledSystem::new(Priority::Prefetch, counter(&prefetch)),
+        ];
+
+        for _ in 0..3 {
+            resources.get_mut::<Scheduler>().unwrap().start_pass();
+            for system in &mut systems {
+                system.run(&mut resources, &EventQueue::new());
+            }
+        }
+        assert_eq!(input.get(), 3);
+        // Deferred twice, then run so it doesn't starve
+        assert_eq!(prefetch.get(), 1);
+        assert_eq!(resources.get::<Scheduler>().unwrap().deferred(), 0);
+    }
+
+    #[test]
+    fn run_urgent_tasks_first() {
+        let mut resources = Resources::new();
+        let order = Rc::new(std::cell::RefCell::new(vec![]));
+        let mut scheduler = Scheduler::new();
+        for priority in [Priority::Prefetch, Priority::Async, Priority::Input] {
+            let order = order.clone();
+            scheduler.spawn(priority, move |_| order.borrow_mut().push(priority));
+        }
+        resources.insert(scheduler);
+
+        resources.get_mut::<Scheduler>().unwrap().start_pass();
+        run_tasks(&mut resources);
+        assert_eq!(
+            *order.borrow(),
+            [Priority::Input, Priority::Async, Priority::Prefetch]
+        );
+
+        let scheduler = resources.get_mut::<Scheduler>().unwrap();
+        scheduler.finish_frame(Duration::from_millis(20));
+        scheduler.start_pass();
+        scheduler.spawn(Priority::Async, |_| {});
+        run_tasks(&mut resources);
+        let scheduler = resources.get::<Scheduler>().unwrap();
+        assert_eq!(scheduler.pending_tasks(), 1);
+        assert_eq!(scheduler.deferred(), 1);
+    }
+}
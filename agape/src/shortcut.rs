@@ -0,0 +1,363 @@
+//! Keyboard shortcuts, which call a function when a key is pressed together
+//! with modifiers while the window is focused.
+//!
+//! Shortcuts are written like `Ctrl+Shift+S`. `Mod` (or `CmdOrCtrl`) is Cmd
+//! on macOS and Ctrl everywhere else, so the same shortcut feels native on
+//! every platform. Shortcuts only match when exactly their modifiers are
+//! held, `Ctrl+S` isn't pressed by Ctrl+Shift+S.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//!
+//! fn main() -> agape::Result<()> {
+//!     let app = App::new(hstack! {})
+//!         .shortcut("Mod+S", || println!("Saved"))?
+//!         .shortcut("Mod+Shift+S", || println!("Saved as"))?;
+//!     Ok(())
+//! }
+//! ```
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use winit::keyboard::{KeyCode, ModifiersState};
+
+type ShortcutFn = Box<dyn FnMut()>;
+
+/// Keys that have a name instead of a character.
+const NAMED_KEYS: [(&str, KeyCode); 25] = [
+    ("Enter", KeyCode::Enter),
+    ("Escape", KeyCode::Escape),
+    ("Tab", KeyCode::Tab),
+    ("Space", KeyCode::Space),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::ArrowUp),
+    ("Down", KeyCode::ArrowDown),
+    ("Left", KeyCode::ArrowLeft),
+    ("Right", KeyCode::ArrowRight),
+    ("=", KeyCode::Equal),
+    ("-", KeyCode::Minus),
+    (",", KeyCode::Comma),
+    (".", KeyCode::Period),
+    ("/", KeyCode::Slash),
+    ("\\", KeyCode::Backslash),
+    (";", KeyCode::Semicolon),
+    ("'", KeyCode::Quote),
+    ("[", KeyCode::BracketLeft),
+    ("]", KeyCode::BracketRight),
+];
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+];
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+const FUNCTION_KEYS: [KeyCode; 12] = [
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+];
+
+/// The modifier that most shortcuts use, Cmd on macOS and Ctrl everywhere
+/// else.
+pub const PRIMARY_MODIFIER: ModifiersState = if cfg!(target_os = "macos") {
+    ModifiersState::SUPER
+} else {
+    ModifiersState::CONTROL
+};
+
+/// The name of the super key on this platform.
+const SUPER_NAME: &str = if cfg!(target_os = "macos") {
+    "Cmd"
+} else {
+    "Super"
+};
+
+/// A key pressed together with modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub modifiers: ModifiersState,
+    pub key: KeyCode,
+}
+
+impl Shortcut {
+    pub fn new(modifiers: ModifiersState, key: KeyCode) -> Self {
+        Self { modifiers, key }
+    }
+
+    /// Parse a shortcut like `Ctrl+Shift+S`, the key comes last and the
+    /// names aren't case sensitive.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidShortcut`] if a modifier or key isn't known,
+    /// or if there isn't exactly one key.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::shortcut::Shortcut;
+    /// use winit::keyboard::{KeyCode, ModifiersState};
+    ///
+    /// let shortcut = Shortcut::parse("ctrl+shift+s").unwrap();
+    /// assert_eq!(shortcut.key, KeyCode::KeyS);
+    /// assert_eq!(shortcut.modifiers, ModifiersState::CONTROL | ModifiersState::SHIFT);
+    /// assert_eq!(shortcut.to_string(), "Ctrl+Shift+S");
+    /// ```
+    pub fn parse(shortcut: &str) -> Result<Self> {
+        let invalid = || Error::InvalidShortcut(shortcut.to_owned());
+        // Split from the end, so that `Ctrl++` is Ctrl and the plus key
+        let (modifiers, key) = match shortcut.rsplit_once('+') {
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').ok_or_else(invalid)?, "="),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", shortcut),
+        };
+
+        let mut state = ModifiersState::empty();
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            state |= match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "control" => ModifiersState::CONTROL,
+                "shift" => ModifiersState::SHIFT,
+                "alt" | "option" => ModifiersState::ALT,
+                "cmd" | "command" | "super" | "meta" | "win" => ModifiersState::SUPER,
+                "mod" | "cmdorctrl" | "primary" => PRIMARY_MODIFIER,
+                _ => return Err(invalid()),
+            };
+        }
+        let key = parse_key(key.trim()).ok_or_else(invalid)?;
+        Ok(Self::new(state, key))
+    }
+
+    /// Returns `true` if the shortcut is pressed by the `key` while the
+    /// `modifiers` are held.
+    pub fn matches(&self, modifiers: ModifiersState, key: KeyCode) -> bool {
+        self.key == key && self.modifiers == modifiers
+    }
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.clone().next()) {
+        if c.is_ascii_alphabetic() {
+            return Some(LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize]);
+        }
+        if let Some(digit) = c.to_digit(10) {
+            return Some(DIGITS[digit as usize]);
+        }
+    }
+    if let Some(number) = key
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        return FUNCTION_KEYS.get(number.checked_sub(1)?).copied();
+    }
+    match key.to_lowercase().as_str() {
+        "esc" => return Some(KeyCode::Escape),
+        "return" => return Some(KeyCode::Enter),
+        "plus" => return Some(KeyCode::Equal),
+        _ => {}
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, code)| *code)
+}
+
+fn key_name(key: KeyCode) -> String {
+    if let Some(index) = LETTERS.iter().position(|&k| k == key) {
+        return char::from(b'A' + index as u8).to_string();
+    }
+    if let Some(index) = DIGITS.iter().position(|&k| k == key) {
+        return index.to_string();
+    }
+    if let Some(index) = FUNCTION_KEYS.iter().position(|&k| k == key) {
+        return format!("F{}", index + 1);
+    }
+    match NAMED_KEYS.iter().find(|(_, code)| *code == key) {
+        Some((name, _)) => name.to_string(),
+        None => format!("{key:?}"),
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let modifiers = [
+            (ModifiersState::CONTROL, "Ctrl"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::SUPER, SUPER_NAME),
+        ];
+        for (modifier, name) in modifiers {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+/// The keyboard shortcuts registered by the app.
+#[derive(Default)]
+pub struct Shortcuts {
+    shortcuts: HashMap<Shortcut, ShortcutFn>,
+    modifiers: ModifiersState,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a shortcut, `f` is called every time it's pressed.
+    ///
+    /// # Errors
+    /// Returns [`Error::ShortcutConflict`] if the shortcut is already
+    /// registered.
+    pub fn register(&mut self, shortcut: Shortcut, f: impl FnMut() + 'static) -> Result<()> {
+        if self.shortcuts.contains_key(&shortcut) {
+            return Err(Error::ShortcutConflict(shortcut));
+        }
+        self.shortcuts.insert(shortcut, Box::new(f));
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, shortcut: Shortcut) {
+        self.shortcuts.remove(&shortcut);
+    }
+
+    pub fn is_registered(&self, shortcut: Shortcut) -> bool {
+        self.shortcuts.contains_key(&shortcut)
+    }
+
+    pub(crate) fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    /// Call the shortcut pressed by the `key`, if there is one, and return
+    /// the pressed shortcut.
+    pub(crate) fn handle_key(&mut self, key: KeyCode) -> Shortcut {
+        let shortcut = Shortcut::new(self.modifiers, key);
+        if let Some(f) = self.shortcuts.get_mut(&shortcut) {
+            f();
+        }
+        shortcut
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn parse_shortcuts() {
+        let parse = |s| Shortcut::parse(s).unwrap();
+        assert_eq!(
+            parse("Alt+F4"),
+            Shortcut::new(ModifiersState::ALT, KeyCode::F4)
+        );
+        assert_eq!(
+            parse("Ctrl++"),
+            Shortcut::new(ModifiersState::CONTROL, KeyCode::Equal)
+        );
+        assert_eq!(parse("Mod+1").modifiers, PRIMARY_MODIFIER);
+        assert_eq!(
+            parse("esc"),
+            Shortcut::new(ModifiersState::empty(), KeyCode::Escape)
+        );
+        assert_eq!(parse("shift+alt+up").to_string(), "Alt+Shift+Up");
+
+        assert!(Shortcut::parse("Ctrl+").is_err());
+        assert!(Shortcut::parse("Hyper+S").is_err());
+        assert!(Shortcut::parse("Ctrl+S+T").is_err());
+        assert!(Shortcut::parse("F13").is_err());
+    }
+
+    #[test]
+    fn exact_modifiers() {
+        let saved = Rc::new(Cell::new(0));
+        let count = saved.clone();
+        let mut shortcuts = Shortcuts::new();
+        let save = Shortcut::parse("Ctrl+S").unwrap();
+        shortcuts
+            .register(save, move || count.set(count.get() + 1))
+            .unwrap();
+
+        shortcuts.modifiers_changed(ModifiersState::CONTROL | ModifiersState::SHIFT);
+        shortcuts.handle_key(KeyCode::KeyS);
+        assert_eq!(saved.get(), 0);
+
+        shortcuts.modifiers_changed(ModifiersState::CONTROL);
+        shortcuts.handle_key(KeyCode::KeyS);
+        assert_eq!(saved.get(), 1);
+    }
+
+    #[test]
+    fn shortcut_conflict() {
+        let mut shortcuts = Shortcuts::new();
+        let save = Shortcut::parse("Mod+S").unwrap();
+        shortcuts.register(save, || {}).unwrap();
+        let result = shortcuts.register(Shortcut::parse("mod+s").unwrap(), || {});
+        assert!(matches!(result, Err(Error::ShortcutConflict(shortcut)) if shortcut == save));
+    }
+}
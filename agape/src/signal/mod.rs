@@ -0,0 +1,565 @@
+//! Signals hold state that widgets read when they're drawn.
+//!
+//! A [`Signal`] is a shared value, clones of a signal point to the same
+//! value. A derived signal computes its value from other signals, it's
+//! only recomputed when it's read after one of the signals it read last
+//! time has changed.
+//!
+//! The signals that a widget reads while its view is built are tracked, so
+//! when one of them changes only that widget is marked as dirty, see
+//! [`Redraw`](crate::resources::Redraw). Signals read while the layout is
+//! built rebuild the layout when they change.
+//!
+//! Input widgets like [`TextField`](crate::widgets::TextField) and
+//! [`Dial`](crate::widgets::Dial) can be bound to a signal with `bind`, they
+//! show its value and set it when they're edited.
+//!
+//! Signals that are set together can be [batched](batch), so their readers
+//! are only notified once, when the batch ends.
+//!
+//! Code outside of the widget tree can [subscribe](Signal::subscribe) to a
+//! signal, to be called with the new and old value when it changes.
+//!
+//! A [`Resource`] runs a future in the background, like an http request,
+//! and exposes whether it's loading, ready or failed as a signal.
+//!
+//! # Example
+//! ```
+//! use agape::signal::Signal;
+//!
+//! let items = Signal::new(vec![3, 1, 2]);
+//! let sorted = Signal::derive({
+//!     let items = items.clone();
+//!     move |deps| {
+//!         let mut items = deps.get(&items);
+//!         items.sort();
+//!         items
+//!     }
+//! });
+//! let label = sorted.map(|items| format!("{} items", items.len()));
+//!
+//! assert_eq!(sorted.get(), [1, 2, 3]);
+//! items.update(|items| items.push(0));
+//! assert_eq!(sorted.get(), [0, 1, 2, 3]);
+//! assert_eq!(label.get(), "4 items");
+//! ```
+mod resource;
+
+pub(crate) use resource::update_resources;
+pub use resource::{Resource, ResourceState};
+
+use agape_core::GlobalId;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+type Compute<T> = Box<dyn Fn(&mut Deps) -> T>;
+type WatchFn<T> = Box<dyn FnMut(&T, &T)>;
+
+/// A signal that another signal was computed from, and its version at the
+/// time.
+type Dependency = (Rc<dyn Source>, u64);
+
+/// Something that reads signals, and has to be updated when they change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Reader {
+    /// The view of a widget, which is drawn again.
+    View(GlobalId),
+    /// The layout of the widget tree, which is built again.
+    Layout,
+}
+
+thread_local! {
+    /// The reader that signals are currently read for.
+    static READER: Cell<Option<Reader>> = const { Cell::new(None) };
+    /// The readers of the signals that changed since they were last taken.
+    static DIRTY: RefCell<HashSet<Reader>> = RefCell::new(HashSet::new());
+    /// The number of batches that are running.
+    static BATCH_DEPTH: Cell<u32> = const { Cell::new(0) };
+    /// The signals that changed in the current batch.
+    static BATCHED: RefCell<Vec<Rc<dyn Source>>> = const { RefCell::new(vec![]) };
+}
+
+/// Call `f`, recording the signals it reads for the `reader`.
+pub(crate) fn track<R>(reader: Reader, f: impl FnOnce() -> R) -> R {
+    let previous = READER.replace(Some(reader));
+    let result = f();
+    READER.set(previous);
+    result
+}
+
+/// Take the readers of the signals that changed since this was last
+/// called.
+pub(crate) fn take_dirty() -> HashSet<Reader> {
+    DIRTY.take()
+}
+
+/// Set several signals at once, their readers are notified once when the
+/// outermost batch ends, instead of after each change. The values change
+/// right away, so they can be read inside of the batch.
+///
+/// # Example
+/// ```
+/// use agape::signal::{Signal, batch};
+///
+/// let (first, last) = (Signal::new("Ada"), Signal::new("Lovelace"));
+/// let name = Signal::derive({
+///     let (first, last) = (first.clone(), last.clone());
+///     move |deps| format!("{} {}", deps.get(&first), deps.get(&last))
+/// });
+///
+/// batch(|| {
+///     first.set("Grace");
+///     last.set("Hopper");
+/// });
+/// assert_eq!(name.get(), "Grace Hopper");
+/// ```
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    BATCH_DEPTH.set(BATCH_DEPTH.get() + 1);
+    let _batch = Batch;
+    f()
+}
+
+/// Ends a batch when it's dropped, including when the batch panics.
+struct Batch;
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        let depth = BATCH_DEPTH.get() - 1;
+        BATCH_DEPTH.set(depth);
+        if depth > 0 {
+            return;
+        }
+        for source in BATCHED.take() {
+            source.notify();
+        }
+    }
+}
+
+/// Notify the readers of a signal that changed, or wait for the end of the
+/// batch.
+fn notify(source: Rc<dyn Source>) {
+    if BATCH_DEPTH.get() == 0 {
+        source.notify();
+        return;
+    }
+    BATCHED.with_borrow_mut(|batched| {
+        if !batched.iter().any(|other| Rc::ptr_eq(other, &source)) {
+            batched.push(source);
+        }
+    });
+}
+
+/// Anything that a derived signal can depend on.
+trait Source {
+    /// Get the version of the value, which changes whenever the value
+    /// does. Derived signals are brought up to date first.
+    fn version(&self) -> u64;
+
+    /// Mark the readers of the value, and of the signals derived from it,
+    /// as dirty.
+    fn notify(&self);
+}
+
+struct Inner<T> {
+    value: RefCell<T>,
+    version: Cell<u64>,
+    compute: Option<Compute<T>>,
+    dependencies: RefCell<Vec<Dependency>>,
+    /// The signal itself, which derived signals subscribe with.
+    this: Weak<Inner<T>>,
+    /// The derived signals that read this one.
+    subscribers: RefCell<Vec<Weak<dyn Source>>>,
+    readers: RefCell<HashSet<Reader>>,
+}
+
+impl<T: 'static> Inner<T> {
+    fn new(
+        this: Weak<Inner<T>>,
+        value: T,
+        compute: Option<Compute<T>>,
+        dependencies: Vec<Dependency>,
+    ) -> Self {
+        Self {
+            value: RefCell::new(value),
+            version: Cell::new(0),
+            compute,
+            dependencies: RefCell::new(dependencies),
+            this,
+            subscribers: RefCell::new(vec![]),
+            readers: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Remember the reader that the value is being read for.
+    fn track_reader(&self) {
+        if let Some(reader) = READER.get() {
+            self.readers.borrow_mut().insert(reader);
+        }
+    }
+
+    /// Recompute a derived value if any of its dependencies have changed.
+    fn refresh(&self) {
+        let Some(compute) = &self.compute else {
+            return;
+        };
+        let stale = self
+            .dependencies
+            .borrow()
+            .iter()
+            .any(|(source, version)| source.version() != *version);
+        if !stale {
+            return;
+        }
+
+        let mut deps = Deps::new(self.this.clone());
+        let value = compute(&mut deps);
+        *self.value.borrow_mut() = value;
+        *self.dependencies.borrow_mut() = deps.dependencies;
+        self.version.set(self.version.get() + 1);
+    }
+}
+
+impl<T: 'static> Source for Inner<T> {
+    fn version(&self) -> u64 {
+        self.refresh();
+        self.version.get()
+    }
+
+    fn notify(&self) {
+        // Readers are tracked again the next time they read the value
+        let readers = std::mem::take(&mut *self.readers.borrow_mut());
+        DIRTY.with_borrow_mut(|dirty| dirty.extend(readers));
+        // Collected first, since subscribers can read this signal again
+        let subscribers: Vec<Rc<dyn Source>> = {
+            let mut subscribers = self.subscribers.borrow_mut();
+            subscribers.retain(|subscriber| subscriber.strong_count() > 0);
+            subscribers.iter().filter_map(Weak::upgrade).collect()
+        };
+        for subscriber in subscribers {
+            subscriber.notify();
+        }
+    }
+}
+
+/// Records the signals that a derived signal reads.
+pub struct Deps {
+    /// The derived signal that the dependencies are recorded for.
+    subscriber: Weak<dyn Source>,
+    dependencies: Vec<Dependency>,
+}
+
+impl Deps {
+    fn new<T: 'static>(subscriber: Weak<Inner<T>>) -> Self {
+        Self {
+            subscriber,
+            dependencies: vec![],
+        }
+    }
+
+    /// Get the value of a signal and depend on it.
+    pub fn get<T: Clone + 'static>(&mut self, signal: &Signal<T>) -> T {
+        self.with(signal, T::clone)
+    }
+
+    /// Call `f` with a reference to the value of a signal and depend on it.
+    pub fn with<T: 'static, R>(&mut self, signal: &Signal<T>, f: impl FnOnce(&T) -> R) -> R {
+        signal.inner.refresh();
+        let mut subscribers = signal.inner.subscribers.borrow_mut();
+        if !subscribers
+            .iter()
+            .any(|subscriber| Weak::ptr_eq(subscriber, &self.subscriber))
+        {
+            subscribers.push(self.subscriber.clone());
+        }
+        drop(subscribers);
+
+        let source: Rc<dyn Source> = signal.inner.clone();
+        self.dependencies.push((source, signal.inner.version.get()));
+        f(&signal.inner.value.borrow())
+    }
+}
+
+/// A shared value that derived signals can depend on.
+pub struct Signal<T> {
+    inner: Rc<Inner<T>>,
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Rc::new_cyclic(|this| Inner::new(this.clone(), value, None, vec![])),
+        }
+    }
+
+    /// Create a signal that's computed from other signals, the signals
+    /// that are read through the [`Deps`] are tracked and the value is
+    /// recomputed when one of them changes.
+    pub fn derive(compute: impl Fn(&mut Deps) -> T + 'static) -> Self {
+        Self {
+            inner: Rc::new_cyclic(|this: &Weak<Inner<T>>| {
+                let mut deps = Deps::new(this.clone());
+                let value = compute(&mut deps);
+                Inner::new(
+                    this.clone(),
+                    value,
+                    Some(Box::new(compute)),
+                    deps.dependencies,
+                )
+            }),
+        }
+    }
+
+    /// Create a signal that's computed from this one.
+    pub fn map<U: 'static>(&self, f: impl Fn(&T) -> U + 'static) -> Signal<U> {
+        let signal = self.clone();
+        Signal::derive(move |deps| deps.with(&signal, &f))
+    }
+
+    /// Call `f` with a reference to the value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.refresh();
+        self.inner.track_reader();
+        f(&self.inner.value.borrow())
+    }
+
+    /// Set the value. The value of a derived signal is replaced until one
+    /// of the signals that it depends on changes.
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Change the value in place.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.inner.refresh();
+        f(&mut self.inner.value.borrow_mut());
+        self.inner.version.set(self.inner.version.get() + 1);
+        notify(self.inner.clone());
+    }
+
+    /// Get the number of times the value has changed, derived signals
+    /// count each time they're recomputed.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+}
+
+impl<T: Clone + 'static> Signal<T> {
+    /// Get a copy of the value.
+    pub fn get(&self) -> T {
+        self.with(T::clone)
+    }
+
+    /// Call `f` with the new and the old value whenever the value changes,
+    /// until the returned [`Subscription`] is dropped. This is meant for
+    /// code outside of the widget tree, like saving the value to disk.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::signal::Signal;
+    ///
+    /// let volume = Signal::new(5);
+    /// let subscription = volume.subscribe(|new, old| println!("Volume: {old} -> {new}"));
+    /// volume.set(6);
+    /// drop(subscription);
+    /// ```
+    #[must_use = "the subscription is cancelled when it's dropped"]
+    pub fn subscribe(&self, f: impl FnMut(&T, &T) + 'static) -> Subscription {
+        let watcher: Rc<dyn Source> = Rc::new(Watcher {
+            signal: self.clone(),
+            old: RefCell::new(self.get_untracked()),
+            f: RefCell::new(Box::new(f)),
+        });
+        self.inner
+            .subscribers
+            .borrow_mut()
+            .push(Rc::downgrade(&watcher));
+        Subscription { _watcher: watcher }
+    }
+
+    /// Get a copy of the value without tracking the reader.
+    fn get_untracked(&self) -> T {
+        self.inner.refresh();
+        self.inner.value.borrow().clone()
+    }
+}
+
+/// Calls a function when a signal changes, until it's dropped. Created
+/// with [`Signal::subscribe`].
+pub struct Subscription {
+    _watcher: Rc<dyn Source>,
+}
+
+impl fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Subscription")
+    }
+}
+
+/// Watches a signal for a [`Subscription`].
+struct Watcher<T> {
+    signal: Signal<T>,
+    old: RefCell<T>,
+    f: RefCell<WatchFn<T>>,
+}
+
+impl<T: Clone + 'static> Source for Watcher<T> {
+    // Nothing depends on a watcher
+    fn version(&self) -> u64 {
+        0
+    }
+
+    fn notify(&self) {
+        let new = self.signal.get_untracked();
+        let old = self.old.replace(new.clone());
+        // A subscriber that changes the signal again isn't called again
+        // until it returns
+        if let Ok(mut f) = self.f.try_borrow_mut() {
+            f(&new, &old);
+        }
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Default + 'static> Default for Signal<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for Signal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with(|value| f.debug_tuple("Signal").field(value).finish())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn derived_values_are_memoized() {
+        let runs = Rc::new(Cell::new(0));
+        let (width, height) = (Signal::new(2), Signal::new(3));
+        let area = Signal::derive({
+            let (width, height, runs) = (width.clone(), height.clone(), runs.clone());
+            move |deps| {
+                runs.set(runs.get() + 1);
+                deps.get(&width) * deps.get(&height)
+            }
+        });
+
+        assert_eq!(area.get(), 6);
+        assert_eq!(area.get(), 6);
+        assert_eq!(runs.get(), 1);
+
+        width.set(4);
+        assert_eq!(runs.get(), 1);
+        assert_eq!(area.get(), 12);
+        assert_eq!(runs.get(), 2);
+    }
+
+    #[test]
+    fn chains_of_derived_signals() {
+        let celsius = Signal::new(100.0_f32);
+        let fahrenheit = celsius.map(|c| c * 9.0 / 5.0 + 32.0);
+        let label = fahrenheit.map(|f| format!("{f}°F"));
+
+        assert_eq!(label.get(), "212°F");
+        celsius.set(0.0);
+        assert_eq!(label.get(), "32°F");
+        assert_eq!(fahrenheit.version(), 1);
+    }
+
+    #[test]
+    fn dependencies_change_with_branches() {
+        let use_metric = Signal::new(true);
+        let metres = Signal::new(1.0_f32);
+        let feet = Signal::new(3.0_f32);
+        let distance = Signal::derive({
+            let (use_metric, metres, feet) = (use_metric.clone(), metres.clone(), feet.clone());
+            move |deps| match deps.get(&use_metric) {
+                true => deps.get(&metres),
+                false => deps.get(&feet),
+            }
+        });
+
+        // Feet aren't read, so changing them doesn't recompute the value
+        feet.set(6.0);
+        assert_eq!(distance.version(), 0);
+        use_metric.set(false);
+        assert_eq!(distance.get(), 6.0);
+    }
+
+    #[test]
+    fn batched_changes_notify_once() {
+        let id = GlobalId::new();
+        let (width, height) = (Signal::new(1), Signal::new(1));
+        let area = Signal::derive({
+            let (width, height) = (width.clone(), height.clone());
+            move |deps| deps.get(&width) * deps.get(&height)
+        });
+        track(Reader::View(id), || area.get());
+        take_dirty();
+
+        batch(|| {
+            width.set(2);
+            batch(|| height.set(3));
+            assert!(take_dirty().is_empty());
+            assert_eq!(area.get(), 6);
+        });
+        assert_eq!(take_dirty(), HashSet::from([Reader::View(id)]));
+    }
+
+    #[test]
+    fn subscribe_to_changes() {
+        let changes = Rc::new(RefCell::new(vec![]));
+        let count = Signal::new(1);
+        let doubled = count.map(|count| count * 2);
+        let subscription = doubled.subscribe({
+            let changes = changes.clone();
+            move |new, old| changes.borrow_mut().push((*new, *old))
+        });
+
+        count.set(2);
+        batch(|| {
+            count.set(3);
+            count.set(4);
+        });
+        assert_eq!(*changes.borrow(), [(4, 2), (8, 4)]);
+
+        drop(subscription);
+        count.set(5);
+        assert_eq!(changes.borrow().len(), 2);
+    }
+
+    #[test]
+    fn readers_of_changed_signals_are_dirty() {
+        let (label, other) = (GlobalId::new(), GlobalId::new());
+        let count = Signal::new(1);
+        let doubled = count.map(|count| count * 2);
+        take_dirty();
+
+        let unrelated = Signal::new("unrelated");
+        track(Reader::View(label), || doubled.get());
+        track(Reader::View(other), || unrelated.get());
+        track(Reader::Layout, || count.get());
+        count.set(2);
+        assert_eq!(
+            take_dirty(),
+            HashSet::from([Reader::View(label), Reader::Layout])
+        );
+
+        // Readers are forgotten until they read the value again
+        count.set(3);
+        assert!(take_dirty().is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+use super::Signal;
+use crate::Resources;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, TryRecvError, channel};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Moves the result of a finished future into its signal, returns `true`
+/// once it's done.
+type PollFn = Box<dyn FnMut() -> bool>;
+
+thread_local! {
+    /// The resources whose futures are still running.
+    static PENDING: RefCell<Vec<PollFn>> = const { RefCell::new(vec![]) };
+}
+
+/// The state of a [`Resource`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceState<T, E> {
+    Loading,
+    Ready(T),
+    Error(E),
+}
+
+impl<T, E> ResourceState<T, E> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    /// Get the value if it's ready.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            Self::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A value that's loaded in the background, like the response to an http
+/// request, and exposed as a signal of its [`ResourceState`].
+///
+/// The future runs on its own thread and its result is moved into the
+/// signal on the ui thread, where the widgets that read it are drawn again.
+/// Futures that need a specific runtime, like tokio, have to start it
+/// themselves.
+///
+/// # Example
+/// ```no_run
+/// use agape::signal::{Resource, ResourceState};
+///
+/// let user = Resource::new(async {
+///     let response = reqwest::blocking::get("https://example.com/user")?;
+///     response.text()
+/// });
+/// let label = user.signal().map(|state| match state {
+///     ResourceState::Loading => String::from("Loading..."),
+///     ResourceState::Ready(name) => name.clone(),
+///     ResourceState::Error(error) => format!("Failed to load the user: {error}"),
+/// });
+/// ```
+pub struct Resource<T, E> {
+    state: Signal<ResourceState<T, E>>,
+}
+
+impl<T: Send + 'static, E: Send + 'static> Resource<T, E> {
+    /// Start running a future in the background.
+    pub fn new(future: impl Future<Output = Result<T, E>> + Send + 'static) -> Self {
+        let state = Signal::new(ResourceState::Loading);
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            // The resource might have been dropped
+            let _ = sender.send(block_on(future));
+        });
+
+        let signal = state.clone();
+        PENDING.with_borrow_mut(|pending| {
+            pending.push(Box::new(move || finish(&signal, &receiver)));
+        });
+        Self { state }
+    }
+}
+
+impl<T: 'static, E: 'static> Resource<T, E> {
+    /// Get the signal of the state, which changes once when the future
+    /// finishes.
+    pub fn signal(&self) -> &Signal<ResourceState<T, E>> {
+        &self.state
+    }
+
+    /// Call `f` with a reference to the state.
+    pub fn with<R>(&self, f: impl FnOnce(&ResourceState<T, E>) -> R) -> R {
+        self.state.with(f)
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.with(ResourceState::is_loading)
+    }
+}
+
+impl<T, E> Clone for Resource<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Set the state once the result has arrived, returns `true` if the
+/// resource doesn't have to be polled anymore.
+fn finish<T: 'static, E: 'static>(
+    state: &Signal<ResourceState<T, E>>,
+    receiver: &Receiver<Result<T, E>>,
+) -> bool {
+    match receiver.try_recv() {
+        Ok(Ok(value)) => state.set(ResourceState::Ready(value)),
+        Ok(Err(error)) => state.set(ResourceState::Error(error)),
+        Err(TryRecvError::Empty) => return false,
+        // The future panicked
+        Err(TryRecvError::Disconnected) => log::error!("A resource's future panicked"),
+    }
+    true
+}
+
+/// Move the results of the futures that have finished into their signals.
+pub(crate) fn update_resources(_: &mut Resources) {
+    // Taken out first, since setting a signal can create new resources
+    let mut pending = PENDING.take();
+    pending.retain_mut(|poll| !poll());
+    PENDING.with_borrow_mut(|new| pending.append(new));
+    PENDING.set(pending);
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run a future on the current thread, parking it while the future is
+/// waiting.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signal::{Reader, take_dirty, track};
+    use agape_core::GlobalId;
+    use std::time::{Duration, Instant};
+
+    fn wait(resource: &Resource<u32, String>) {
+        let start = Instant::now();
+        while resource.is_loading() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            update_resources(&mut Resources::new());
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// A future that's woken from another thread before it's ready.
+    struct Delayed(bool);
+
+    impl Future for Delayed {
+        type Output = Result<u32, String>;
+
+        fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0 {
+                return Poll::Ready(Ok(2));
+            }
+            self.0 = true;
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn resource_becomes_ready() {
+        let id = GlobalId::new();
+        let resource = Resource::new(Delayed(false));
+        track(Reader::View(id), || resource.is_loading());
+        take_dirty();
+
+        wait(&resource);
+        assert_eq!(resource.with(|state| state.value().copied()), Some(2));
+        assert!(take_dirty().contains(&Reader::View(id)));
+    }
+
+    #[test]
+    fn resource_error() {
+        let resource = Resource::new(async { Err(String::from("not found")) });
+        wait(&resource);
+        assert_eq!(
+            resource.signal().get(),
+            ResourceState::Error(String::from("not found"))
+        );
+    }
+}
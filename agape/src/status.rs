@@ -0,0 +1,186 @@
+//! The power and network status of the system, so that apps can throttle
+//! animations on battery or hold back network requests while offline.
+//!
+//! Tracking is opt-in with [`App::track_system_status`](crate::App::track_system_status),
+//! the [`SystemStatus`] resource is then updated every few seconds. The
+//! status is read from sysfs on Linux, on other platforms it's unknown.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//! use agape::resources::Resources;
+//! use agape::status::SystemStatus;
+//!
+//! let app = App::new(hstack! {})
+//!     .track_system_status()
+//!     .add_system(|resources: &mut Resources| {
+//!         let status = resources.get::<SystemStatus>().unwrap();
+//!         if status.is_offline() {
+//!             // Queue network requests until the system is online
+//!         }
+//!     });
+//! ```
+use crate::resources::Resources;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often the status is read.
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The power and network status of the system, each field is `None` if it
+/// isn't known.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SystemStatus {
+    /// Whether the system is running on battery power.
+    pub on_battery: Option<bool>,
+    /// Whether the system is in a power saving mode.
+    pub power_saving: Option<bool>,
+    /// Whether any network interface is connected.
+    pub online: Option<bool>,
+}
+
+impl SystemStatus {
+    /// Read the current status of the system.
+    pub fn read() -> Self {
+        if cfg!(target_os = "linux") {
+            Self::read_sysfs(Path::new("/sys"))
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns `true` if the app should save power, because the system is on
+    /// battery or in a power saving mode.
+    pub fn should_save_power(&self) -> bool {
+        self.on_battery == Some(true) || self.power_saving == Some(true)
+    }
+
+    /// Returns `true` if the system is known to be offline.
+    pub fn is_offline(&self) -> bool {
+        self.online == Some(false)
+    }
+
+    fn read_sysfs(root: &Path) -> Self {
+        Self {
+            on_battery: on_battery(&root.join("class/power_supply")),
+            power_saving: read(&root.join("firmware/acpi/platform_profile"))
+                .map(|profile| profile == "low-power"),
+            online: online(&root.join("class/net")),
+        }
+    }
+}
+
+/// Read a sysfs attribute.
+fn read(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_owned())
+}
+
+/// The system is on battery if it has a battery and none of its mains
+/// supplies are online.
+fn on_battery(power_supply: &Path) -> Option<bool> {
+    let mut has_battery = false;
+    for entry in fs::read_dir(power_supply).ok()?.flatten() {
+        let path = entry.path();
+        match read(&path.join("type")).as_deref() {
+            Some("Mains") if read(&path.join("online")).as_deref() == Some("1") => {
+                return Some(false);
+            }
+            Some("Battery") => has_battery = true,
+            _ => {}
+        }
+    }
+    has_battery.then_some(true)
+}
+
+/// The system is online if a network interface, other than loopback, is
+/// up.
+fn online(net: &Path) -> Option<bool> {
+    let interfaces = fs::read_dir(net).ok()?;
+    let online = interfaces
+        .flatten()
+        .filter(|entry| entry.file_name() != "lo")
+        .any(|entry| read(&entry.path().join("operstate")).as_deref() == Some("up"));
+    Some(online)
+}
+
+/// When the [`SystemStatus`] was last read.
+pub(crate) struct StatusUpdated(pub Instant);
+
+/// Keeps the [`SystemStatus`] resource up to date.
+pub(crate) fn update_system_status(resources: &mut Resources) {
+    let Some(updated) = resources.get_mut::<StatusUpdated>() else {
+        return;
+    };
+    if updated.0.elapsed() < STATUS_INTERVAL {
+        return;
+    }
+    updated.0 = Instant::now();
+
+    let status = SystemStatus::read();
+    if let Some(current) = resources.get_mut::<SystemStatus>() {
+        if *current != status {
+            log::info!("System status changed: {status:?}");
+        }
+        *current = status;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn sysfs(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("agape-sysfs-{name}"));
+        let _ = fs::remove_dir_all(&root);
+        for (path, value) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, value).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn laptop_on_battery() {
+        let root = sysfs(
+            "battery",
+            &[
+                ("class/power_supply/AC/type", "Mains\n"),
+                ("class/power_supply/AC/online", "0\n"),
+                ("class/power_supply/BAT0/type", "Battery\n"),
+                ("firmware/acpi/platform_profile", "low-power\n"),
+                ("class/net/lo/operstate", "unknown\n"),
+                ("class/net/wlan0/operstate", "up\n"),
+            ],
+        );
+
+        let status = SystemStatus::read_sysfs(&root);
+        assert_eq!(status.on_battery, Some(true));
+        assert_eq!(status.power_saving, Some(true));
+        assert_eq!(status.online, Some(true));
+        assert!(status.should_save_power());
+    }
+
+    #[test]
+    fn desktop_offline() {
+        let root = sysfs(
+            "desktop",
+            &[
+                ("class/power_supply/AC/type", "Mains\n"),
+                ("class/power_supply/AC/online", "1\n"),
+                ("class/net/lo/operstate", "up\n"),
+                ("class/net/eth0/operstate", "down\n"),
+            ],
+        );
+
+        let status = SystemStatus::read_sysfs(&root);
+        assert_eq!(status.on_battery, Some(false));
+        assert_eq!(status.power_saving, None);
+        assert!(status.is_offline());
+        assert!(!status.should_save_power());
+    }
+}
@@ -1,11 +1,21 @@
-use agape_core::{Color, IntoColor, Rgba};
+use crate::animation::{Easing, Lerp, Transition};
+use agape_core::{Color, IntoColor, Position, Rgba};
 use agape_layout::{BoxSizing, IntrinsicSize};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 #[derive(Default, Debug, PartialOrd, PartialEq, Clone)]
 pub struct BoxStyle {
     pub intrinsic_size: IntrinsicSize,
     pub background_color: Color<Rgba>,
+    /// Drawn instead of the background color when set.
+    pub gradient: Option<Gradient>,
     pub border: Option<Border>,
+    pub corner_radius: CornerRadius,
+    pub shadow: Option<BoxShadow>,
+    /// Blur what's behind the widget by this radius, so that a translucent
+    /// background looks like frosted glass.
+    pub backdrop_blur: Option<f32>,
 }
 
 impl BoxStyle {
@@ -158,11 +168,24 @@ impl BoxStyle {
         self.intrinsic_height(BoxSizing::Shrink);
     }
 
-    /// Set the border width.
+    /// Set the width of every side of the border, this replaces any widths
+    /// set with [`BoxStyle::border_sides`].
+    ///
+    /// # Example
+    /// ```
+    /// use agape::style::{BoxStyle,BorderSides};
+    ///
+    /// let mut style = BoxStyle::new();
+    /// style.border_sides(BorderSides::new(0.0,0.0,1.0,0.0));
+    /// style.border_width(2.0);
+    ///
+    /// assert_eq!(style.border.unwrap().side_widths(),BorderSides::all(2.0));
+    /// ```
     pub fn border_width(&mut self, width: f32) {
         match &mut self.border {
             Some(border) => {
                 border.width = width;
+                border.sides = None;
             }
             None => {
                 let border = Border {
@@ -174,6 +197,49 @@ impl BoxStyle {
         }
     }
 
+    /// Set the width of each side of the border, this replaces the width set
+    /// with [`BoxStyle::border_width`].
+    ///
+    /// # Example
+    /// ```
+    /// use agape::style::{BoxStyle,BorderSides};
+    ///
+    /// let mut style = BoxStyle::new();
+    /// style.border_sides(BorderSides::new(0.0,0.0,1.0,0.0));
+    ///
+    /// assert_eq!(style.border.unwrap().side_widths().bottom,1.0);
+    /// ```
+    pub fn border_sides(&mut self, sides: BorderSides) {
+        match &mut self.border {
+            Some(border) => {
+                border.sides = Some(sides);
+            }
+            None => {
+                let border = Border {
+                    sides: Some(sides),
+                    ..Default::default()
+                };
+                self.border = Some(border);
+            }
+        }
+    }
+
+    /// Set the border [`Gradient`].
+    pub fn border_gradient(&mut self, gradient: Gradient) {
+        match &mut self.border {
+            Some(border) => {
+                border.gradient = Some(gradient);
+            }
+            None => {
+                let border = Border {
+                    gradient: Some(gradient),
+                    ..Default::default()
+                };
+                self.border = Some(border);
+            }
+        }
+    }
+
     /// Set the border color.
     pub fn border_color(&mut self, color: impl IntoColor<Rgba>) {
         match &mut self.border {
@@ -191,11 +257,365 @@ impl BoxStyle {
     }
 }
 
+/// The builders shared by widgets that are drawn as a box, with a
+/// [`BoxStyle`].
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+///
+/// let card = Rect::new(200.0, 100.0)
+///     .background_color(Color::WHITE)
+///     .corner_radius(12.0)
+///     .border_width(1.0);
+/// ```
+pub trait Styled: Sized {
+    /// Get the style of the widget.
+    fn style_mut(&mut self) -> &mut BoxStyle;
+
+    /// Set the background [`Color`] of the widget.
+    fn background_color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.style_mut().background_color = color.into_color();
+        self
+    }
+
+    /// Fill the widget with a [`Gradient`] instead of
+    /// a solid color.
+    fn gradient(mut self, gradient: Gradient) -> Self {
+        self.style_mut().gradient = Some(gradient);
+        self
+    }
+
+    /// Add a drop shadow behind the widget.
+    fn shadow(
+        mut self,
+        offset: Position,
+        blur: f32,
+        spread: f32,
+        color: impl IntoColor<Rgba>,
+    ) -> Self {
+        self.style_mut().shadow = Some(BoxShadow::new(offset, blur, spread, color));
+        self
+    }
+
+    /// Blur what's behind the widget, the background color should be
+    /// translucent for the blur to show.
+    fn backdrop_blur(mut self, radius: f32) -> Self {
+        self.style_mut().backdrop_blur = Some(radius);
+        self
+    }
+
+    /// Round every corner of the widget by the same radius.
+    fn corner_radius(mut self, radius: f32) -> Self {
+        self.style_mut().corner_radius = CornerRadius::all(radius);
+        self
+    }
+
+    /// Round each corner of the widget separately, clockwise from the
+    /// top left.
+    fn corner_radii(
+        mut self,
+        top_left: f32,
+        top_right: f32,
+        bottom_right: f32,
+        bottom_left: f32,
+    ) -> Self {
+        self.style_mut().corner_radius =
+            CornerRadius::new(top_left, top_right, bottom_right, bottom_left);
+        self
+    }
+
+    /// Set the width of every side of the widgets border, the last of
+    /// `border_width` and `border_sides` to be called is used.
+    fn border_width(mut self, width: f32) -> Self {
+        self.style_mut().border_width(width);
+        self
+    }
+
+    /// Set the width of each side of the widgets border, the last of
+    /// `border_width` and `border_sides` to be called is used.
+    fn border_sides(mut self, top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        self.style_mut()
+            .border_sides(BorderSides::new(top, right, bottom, left));
+        self
+    }
+
+    /// Set the widgets border [`Color`].
+    fn border_color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.style_mut().border_color(color);
+        self
+    }
+
+    /// Draw the widgets border with a [`Gradient`].
+    fn border_gradient(mut self, gradient: Gradient) -> Self {
+        self.style_mut().border_gradient(gradient);
+        self
+    }
+
+    /// Add a border.
+    fn border(mut self, border: Border) -> Self {
+        self.style_mut().border = Some(border);
+        self
+    }
+
+    /// Make the widget fit its children.
+    fn fit(mut self) -> Self {
+        self.style_mut().fit();
+        self
+    }
+
+    fn fit_height(mut self) -> Self {
+        self.style_mut().fit_height();
+        self
+    }
+
+    fn fit_width(mut self) -> Self {
+        self.style_mut().fit_width();
+        self
+    }
+
+    /// Make the widget fill its parent.
+    fn fill(mut self) -> Self {
+        self.style_mut().fill();
+        self
+    }
+
+    fn fill_width(mut self) -> Self {
+        self.style_mut().fill_width();
+        self
+    }
+
+    fn fill_height(mut self) -> Self {
+        self.style_mut().fill_height();
+        self
+    }
+
+    /// Give the widget a fixed size.
+    fn fixed(mut self, width: f32, height: f32) -> Self {
+        self.style_mut().fixed(width, height);
+        self
+    }
+}
+
+/// An interaction state that a widget can be styled for, with
+/// [`StateStyled::style_when`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum State {
+    Hovered,
+    Focused,
+    Pressed,
+}
+
+impl State {
+    /// Every state, in the order that their styles are applied, so a
+    /// pressed style wins over a hovered one.
+    pub const ALL: [State; 3] = [State::Hovered, State::Focused, State::Pressed];
+}
+
+type StyleFn = Rc<dyn Fn(&mut BoxStyle)>;
+
+/// The changes to a widget's style in each [`State`], and the transition
+/// between them.
+///
+/// Widgets keep their default style and tell the [`StateStyles`] when a
+/// state starts or ends, the style that they're drawn with animates from
+/// the current style to the new one.
+#[derive(Clone, Default)]
+pub struct StateStyles {
+    styles: Vec<(State, StyleFn)>,
+    active: Vec<State>,
+    duration: Duration,
+    easing: Easing,
+    transition: Option<Transition<BoxStyle>>,
+}
+
+impl StateStyles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Change the style with `f` while the widget is in a `state`.
+    pub fn add(&mut self, state: State, f: impl Fn(&mut BoxStyle) + 'static) {
+        self.styles.push((state, Rc::new(f)));
+    }
+
+    /// Set how long it takes to move between styles, the styles snap when
+    /// the duration is zero.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    /// Returns `true` if the widget is in a `state`.
+    pub fn is_active(&self, state: State) -> bool {
+        self.active.contains(&state)
+    }
+
+    /// Start or end a `state`, transitioning from the style of the widget
+    /// with the `base` style.
+    pub fn update(&mut self, state: State, active: bool, base: &BoxStyle) {
+        self.update_at(state, active, base, Instant::now());
+    }
+
+    /// Get the style that the widget with the `base` style is drawn with.
+    pub fn resolve(&self, base: &BoxStyle) -> BoxStyle {
+        self.resolve_at(base, Instant::now())
+    }
+
+    pub(crate) fn update_at(&mut self, state: State, active: bool, base: &BoxStyle, now: Instant) {
+        if self.is_active(state) == active {
+            return;
+        }
+        let from = self.resolve_at(base, now);
+        match active {
+            true => self.active.push(state),
+            false => self.active.retain(|s| *s != state),
+        }
+        // States without a style don't change how the widget looks
+        if !self.styles.iter().any(|(s, _)| *s == state) {
+            return;
+        }
+        let to = self.target(base);
+        self.transition = Some(Transition::new(from, to, self.duration, now).easing(self.easing));
+    }
+
+    pub(crate) fn resolve_at(&self, base: &BoxStyle, now: Instant) -> BoxStyle {
+        match &self.transition {
+            Some(transition) if !transition.is_finished(now) => {
+                // Only the appearance is animated, the layout of the base
+                // style is always used
+                let mut style = transition.value_at(now);
+                style.intrinsic_size = base.intrinsic_size;
+                style
+            }
+            _ => self.target(base),
+        }
+    }
+
+    /// Get the style of the widget in its active states.
+    fn target(&self, base: &BoxStyle) -> BoxStyle {
+        let mut style = base.clone();
+        for state in State::ALL.iter().filter(|state| self.is_active(**state)) {
+            for (_, f) in self.styles.iter().filter(|(s, _)| s == state) {
+                f(&mut style);
+            }
+        }
+        style
+    }
+}
+
+/// Widgets whose style changes when they're hovered, pressed or focused.
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+/// use std::time::Duration;
+///
+/// let button = Button::new(Text::new("Save"))
+///     .background_color(Color::rgb(230, 230, 230))
+///     .style_when(State::Hovered, |style| {
+///         style.background_color = Color::rgb(210, 210, 210);
+///     })
+///     .style_when(State::Pressed, |style| {
+///         style.background_color = Color::rgb(180, 180, 180);
+///     })
+///     .transition(Duration::from_millis(150));
+/// ```
+pub trait StateStyled: Sized {
+    fn state_styles_mut(&mut self) -> &mut StateStyles;
+
+    /// Change the widget's style with `f` while it's in a [`State`].
+    fn style_when(mut self, state: State, f: impl Fn(&mut BoxStyle) + 'static) -> Self {
+        self.state_styles_mut().add(state, f);
+        self
+    }
+
+    /// Animate between the styles of each state over a `duration`, instead
+    /// of snapping to them.
+    fn transition(mut self, duration: Duration) -> Self {
+        self.state_styles_mut().set_duration(duration);
+        self
+    }
+
+    /// Set the [`Easing`] of the transitions between states.
+    fn transition_easing(mut self, easing: Easing) -> Self {
+        self.state_styles_mut().set_easing(easing);
+        self
+    }
+}
+
+impl Lerp for BoxStyle {
+    /// Gradients and the layout snap to the target, everything else is
+    /// interpolated.
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Self {
+            intrinsic_size: to.intrinsic_size,
+            background_color: self.background_color.lerp(&to.background_color, t),
+            gradient: to.gradient.clone(),
+            border: self.border.lerp(&to.border, t),
+            corner_radius: self.corner_radius.lerp(&to.corner_radius, t),
+            shadow: self.shadow.lerp(&to.shadow, t),
+            backdrop_blur: self.backdrop_blur.lerp(&to.backdrop_blur, t),
+        }
+    }
+}
+
+impl Lerp for Border {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Self {
+            width: self.width.lerp(&to.width, t),
+            color: self.color.lerp(&to.color, t),
+            sides: self.sides.lerp(&to.sides, t),
+            gradient: to.gradient.clone(),
+        }
+    }
+}
+
+impl Lerp for BorderSides {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Self::new(
+            self.top.lerp(&to.top, t),
+            self.right.lerp(&to.right, t),
+            self.bottom.lerp(&to.bottom, t),
+            self.left.lerp(&to.left, t),
+        )
+    }
+}
+
+impl Lerp for CornerRadius {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Self::new(
+            self.top_left.lerp(&to.top_left, t),
+            self.top_right.lerp(&to.top_right, t),
+            self.bottom_right.lerp(&to.bottom_right, t),
+            self.bottom_left.lerp(&to.bottom_left, t),
+        )
+    }
+}
+
+impl Lerp for BoxShadow {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Self {
+            offset: self.offset.lerp(&to.offset, t),
+            blur: self.blur.lerp(&to.blur, t),
+            spread: self.spread.lerp(&to.spread, t),
+            color: self.color.lerp(&to.color, t),
+        }
+    }
+}
+
 /// Border style for [`View`]s;
 #[derive(Clone, PartialEq, Debug, Default, PartialOrd)]
 pub struct Border {
     pub width: f32,
     pub color: Color<Rgba>,
+    /// Per-side widths, these take priority over the `width`.
+    pub sides: Option<BorderSides>,
+    /// Drawn instead of the color when set.
+    pub gradient: Option<Gradient>,
 }
 
 impl Border {
@@ -203,12 +623,459 @@ impl Border {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Get the width of each side of the border.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::style::{Border,BorderSides};
+    ///
+    /// let mut border = Border::new();
+    /// border.width = 2.0;
+    /// assert_eq!(border.side_widths(),BorderSides::all(2.0));
+    ///
+    /// border.sides = Some(BorderSides::new(0.0,0.0,4.0,0.0));
+    /// assert_eq!(border.side_widths().bottom,4.0);
+    /// ```
+    pub fn side_widths(&self) -> BorderSides {
+        self.sides.unwrap_or(BorderSides::all(self.width))
+    }
+}
+
+/// The width of each side of a [`Border`].
+#[derive(Clone, Copy, PartialEq, Debug, Default, PartialOrd)]
+pub struct BorderSides {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl BorderSides {
+    /// Create new [`BorderSides`], in the same order as css.
+    pub fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Set all the sides to the same width.
+    pub fn all(width: f32) -> Self {
+        Self::new(width, width, width, width)
+    }
+
+    /// Get the widest side.
+    pub fn max(&self) -> f32 {
+        self.top.max(self.right).max(self.bottom).max(self.left)
+    }
+}
+
+/// The radius of each corner of a box.
+///
+/// # Example
+/// ```
+/// use agape::style::CornerRadius;
+///
+/// // Round the top of a card that sits on a flat footer
+/// let radius = CornerRadius::top(12.0);
+/// assert_eq!(radius, CornerRadius::new(12.0, 12.0, 0.0, 0.0));
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug, Default, PartialOrd)]
+pub struct CornerRadius {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl CornerRadius {
+    /// Create a new [`CornerRadius`], clockwise from the top left like css.
+    pub fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Self {
+        Self {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    /// Round every corner by the same radius.
+    pub fn all(radius: f32) -> Self {
+        Self::new(radius, radius, radius, radius)
+    }
+
+    /// Round only the top corners.
+    pub fn top(radius: f32) -> Self {
+        Self::new(radius, radius, 0.0, 0.0)
+    }
+
+    /// Round only the bottom corners.
+    pub fn bottom(radius: f32) -> Self {
+        Self::new(0.0, 0.0, radius, radius)
+    }
+
+    /// Get the largest radius.
+    pub fn max(&self) -> f32 {
+        self.top_left
+            .max(self.top_right)
+            .max(self.bottom_right)
+            .max(self.bottom_left)
+    }
+
+    /// Returns `true` if none of the corners are rounded.
+    pub fn is_zero(&self) -> bool {
+        self.max() <= 0.0
+    }
+
+    /// Grow every corner by `amount`, or shrink it if the amount is negative,
+    /// corners don't shrink below zero.
+    pub fn offset(&self, amount: f32) -> Self {
+        let offset = |radius: f32| (radius + amount).max(0.0);
+        Self::new(
+            offset(self.top_left),
+            offset(self.top_right),
+            offset(self.bottom_right),
+            offset(self.bottom_left),
+        )
+    }
+
+    /// Scale the corners down so that neighbouring corners don't overlap in
+    /// a box of `width` by `height`, negative radii are treated as zero.
+    pub(crate) fn fit(&self, width: f32, height: f32) -> Self {
+        let radius = Self::new(
+            self.top_left.max(0.0),
+            self.top_right.max(0.0),
+            self.bottom_right.max(0.0),
+            self.bottom_left.max(0.0),
+        );
+        let scale = |side: f32, a: f32, b: f32| match a + b > side {
+            true => side / (a + b),
+            false => 1.0,
+        };
+        let scale = scale(width, radius.top_left, radius.top_right)
+            .min(scale(width, radius.bottom_left, radius.bottom_right))
+            .min(scale(height, radius.top_left, radius.bottom_left))
+            .min(scale(height, radius.top_right, radius.bottom_right))
+            .max(0.0);
+        Self::new(
+            radius.top_left * scale,
+            radius.top_right * scale,
+            radius.bottom_right * scale,
+            radius.bottom_left * scale,
+        )
+    }
+}
+
+impl From<f32> for CornerRadius {
+    fn from(radius: f32) -> Self {
+        Self::all(radius)
+    }
+}
+
+/// A drop shadow drawn behind a box.
+///
+/// # Example
+/// ```
+/// use agape::{Color,Position,style::BoxShadow};
+///
+/// let shadow = BoxShadow::new(Position::new(0.0,4.0),12.0,0.0,Color::rgba(0,0,0,25));
+/// assert_eq!(shadow.blur,12.0);
+/// ```
+#[derive(Clone, PartialEq, Debug, Default, PartialOrd)]
+pub struct BoxShadow {
+    /// How far the shadow is moved from the box.
+    pub offset: Position,
+    /// The blur radius, larger values make softer shadows.
+    pub blur: f32,
+    /// How far the shadow extends past the box before blurring.
+    pub spread: f32,
+    pub color: Color<Rgba>,
+}
+
+impl BoxShadow {
+    /// Create a new [`BoxShadow`], negative blur values are treated as `0.0`.
+    pub fn new(offset: Position, blur: f32, spread: f32, color: impl IntoColor<Rgba>) -> Self {
+        Self {
+            offset,
+            blur: blur.max(0.0),
+            spread,
+            color: color.into_color(),
+        }
+    }
+}
+
+/// A point along a [`Gradient`], the offset is from `0.0` to `1.0`.
+#[derive(Clone, PartialEq, Debug, Default, PartialOrd)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color<Rgba>,
+}
+
+/// The shape of a [`Gradient`].
+#[derive(Clone, Copy, PartialEq, Debug, PartialOrd)]
+pub enum GradientKind {
+    /// A gradient along a line, the angle is in degrees with `0.0` pointing
+    /// upwards and `90.0` pointing to the right.
+    Linear { angle: f32 },
+    /// A gradient radiating outwards from the center.
+    ///
+    /// The center is relative to the size of the shape, so `(0.5,0.5)` is
+    /// the middle, and the radius is relative to the longest side.
+    Radial { center: Position, radius: f32 },
+    /// A gradient that sweeps around the center, starting at the angle, in
+    /// degrees, and going clockwise. The center is relative to the size
+    /// of the shape.
+    Conic { center: Position, angle: f32 },
+}
+
+/// A multi-stop color gradient.
+///
+/// # Example
+/// ```
+/// use agape::{Color,style::Gradient};
+///
+/// let gradient = Gradient::linear(90.0)
+///     .stop(0.0,Color::RED)
+///     .stop(1.0,Color::BLUE);
+///
+/// assert_eq!(gradient.stops.len(),2);
+/// ```
+#[derive(Clone, PartialEq, Debug, PartialOrd)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    /// Create a linear gradient at an `angle` in degrees.
+    pub fn linear(angle: f32) -> Self {
+        Self {
+            kind: GradientKind::Linear { angle },
+            stops: vec![],
+        }
+    }
+
+    /// Create a radial gradient.
+    pub fn radial(center: Position, radius: f32) -> Self {
+        Self {
+            kind: GradientKind::Radial { center, radius },
+            stops: vec![],
+        }
+    }
+
+    /// Create a conic gradient that starts at `angle` degrees.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::{Color,Position,style::Gradient};
+    ///
+    /// // A rainbow ring
+    /// let gradient = Gradient::conic(Position::unit(0.5),0.0)
+    ///     .stop(0.0,Color::RED)
+    ///     .stop(0.5,Color::GREEN)
+    ///     .stop(1.0,Color::RED);
+    /// ```
+    pub fn conic(center: Position, angle: f32) -> Self {
+        Self {
+            kind: GradientKind::Conic { center, angle },
+            stops: vec![],
+        }
+    }
+
+    /// Add a color stop, the `offset` is clamped between `0.0` and `1.0`.
+    ///
+    /// The stops are kept in order of their offset, stops with the same
+    /// offset stay in the order they were added, which makes a hard edge.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::{Color,style::Gradient};
+    ///
+    /// let gradient = Gradient::linear(0.0)
+    ///     .stop(1.0,Color::WHITE)
+    ///     .stop(0.0,Color::BLACK);
+    ///
+    /// assert_eq!(gradient.stops[0].color,Color::BLACK);
+    /// ```
+    pub fn stop(mut self, offset: f32, color: impl IntoColor<Rgba>) -> Self {
+        let offset = if offset.is_nan() {
+            0.0
+        } else {
+            offset.clamp(0.0, 1.0)
+        };
+        let index = self.stops.partition_point(|stop| stop.offset <= offset);
+        self.stops.insert(
+            index,
+            GradientStop {
+                offset,
+                color: color.into_color(),
+            },
+        );
+        self
+    }
+}
+
+/// The shape at the ends of a stroke.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, PartialOrd, Ord, Hash)]
+pub enum StrokeCap {
+    /// The stroke ends exactly at its end points.
+    #[default]
+    Butt,
+    /// The stroke ends with a semi-circle.
+    Round,
+    /// The stroke ends with a square that extends past the end points by
+    /// half of the stroke width.
+    Square,
+}
+
+/// The shape of the corners where two segments of a stroke meet.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, PartialOrd, Ord, Hash)]
+pub enum StrokeJoin {
+    /// A sharp corner.
+    #[default]
+    Miter,
+    /// A rounded corner.
+    Round,
+    /// A corner with the tip cut off.
+    Bevel,
+}
+
+/// Describes how a line is drawn.
+///
+/// # Example
+/// ```
+/// use agape::style::{StrokeCap, StrokeStyle};
+/// use agape::Color;
+///
+/// // A dotted line
+/// let stroke = StrokeStyle::new(2.0, Color::BLACK)
+///     .cap(StrokeCap::Round)
+///     .dash(vec![0.0, 6.0], 0.0);
+/// ```
+#[derive(Clone, PartialEq, Debug, PartialOrd)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub color: Color<Rgba>,
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
+    /// Alternating lengths of dashes and gaps, an empty pattern draws a
+    /// solid line.
+    pub dash: Vec<f32>,
+    /// How far into the dash pattern the line starts.
+    pub dash_offset: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::new(1.0, Color::BLACK)
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32, color: Color<Rgba>) -> Self {
+        Self {
+            width,
+            color,
+            cap: StrokeCap::default(),
+            join: StrokeJoin::default(),
+            dash: vec![],
+            dash_offset: 0.0,
+        }
+    }
+
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: StrokeJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Set the dash pattern, patterns with an odd number of lengths are
+    /// repeated to make them even.
+    pub fn dash(mut self, pattern: Vec<f32>, offset: f32) -> Self {
+        self.dash = pattern;
+        self.dash_offset = offset;
+        self
+    }
+}
+
+/// Decides which parts of a shape are inside it, when the shape overlaps
+/// itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, PartialOrd, Ord, Hash)]
+pub enum FillRule {
+    /// Areas that are enclosed by the shape are filled, unless the
+    /// overlapping parts go in opposite directions.
+    #[default]
+    NonZero,
+    /// Areas enclosed an odd number of times are filled, so overlapping
+    /// parts make holes.
+    EvenOdd,
+}
+
+/// The thickness of a font.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, PartialOrd, Ord, Hash)]
+pub enum FontWeight {
+    Light,
+    #[default]
+    Regular,
+    Medium,
+    SemiBold,
+    Bold,
+}
+
+/// Whether text is upright or slanted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, PartialOrd, Ord, Hash)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    /// Slanted text, the bundled font doesn't have an italic face so the
+    /// glyphs are skewed.
+    Italic,
+}
+
+/// How text is broken into lines when it's wider than the space it's given.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub enum TextWrap {
+    /// Keep the text on one line, apart from explicit line breaks.
+    #[default]
+    None,
+    /// Break lines between words, words that don't fit on a line of their
+    /// own are broken between characters.
+    Word,
+    /// Break lines between any two characters.
+    Char,
+}
+
+/// What happens to text that doesn't fit in the space it's given.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub enum TextOverflow {
+    /// Cut the text off at the edge.
+    #[default]
+    Clip,
+    /// End the last line with `…`.
+    Ellipsis,
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn gradient_stops_are_clamped() {
+        let gradient = Gradient::linear(0.0)
+            .stop(-1.0, Color::RED)
+            .stop(2.0, Color::BLUE);
+
+        assert_eq!(gradient.stops[0].offset, 0.0);
+        assert_eq!(gradient.stops[1].offset, 1.0);
+    }
+
     #[test]
     fn set_border_width() {
         let mut style = BoxStyle::new();
@@ -216,4 +1083,51 @@ mod test {
 
         assert_eq!(style.border.unwrap().width, 12.0);
     }
+
+    #[test]
+    fn fit_corner_radius() {
+        let radius = CornerRadius::new(80.0, 20.0, 0.0, 0.0).fit(50.0, 200.0);
+        assert_eq!(radius, CornerRadius::new(40.0, 10.0, 0.0, 0.0));
+
+        let radius = CornerRadius::new(-5.0, 10.0, 10.0, 10.0).fit(100.0, 100.0);
+        assert_eq!(radius, CornerRadius::new(0.0, 10.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn transition_between_states() {
+        let base = BoxStyle {
+            background_color: Color::BLACK,
+            ..Default::default()
+        };
+        let mut styles = StateStyles::new();
+        styles.add(State::Hovered, |style| {
+            style.background_color = Color::WHITE
+        });
+        styles.set_duration(Duration::from_millis(100));
+        styles.set_easing(Easing::Linear);
+
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        styles.update_at(State::Hovered, true, &base, start);
+        let color = |styles: &StateStyles, ms| styles.resolve_at(&base, at(ms)).background_color;
+        assert_eq!(color(&styles, 50), Color::rgb(128, 128, 128));
+        assert_eq!(color(&styles, 100), Color::WHITE);
+
+        // Leaving halfway back starts from the current color
+        styles.update_at(State::Hovered, false, &base, at(150));
+        styles.update_at(State::Hovered, true, &base, at(200));
+        assert_eq!(color(&styles, 200), Color::rgb(128, 128, 128));
+    }
+
+    #[test]
+    fn pressed_style_wins() {
+        let mut styles = StateStyles::new();
+        styles.add(State::Pressed, |style| style.background_color = Color::BLUE);
+        styles.add(State::Hovered, |style| style.background_color = Color::RED);
+
+        let base = BoxStyle::new();
+        styles.update(State::Pressed, true, &base);
+        styles.update(State::Hovered, true, &base);
+        assert_eq!(styles.resolve(&base).background_color, Color::BLUE);
+    }
 }
@@ -0,0 +1,268 @@
+//! Textures are drawn offscreen once and then displayed like any other
+//! image, which is useful for content that is expensive to draw but rarely
+//! changes, like minimaps and charts with lots of points.
+//!
+//! Textures are stored in the [`ImageCache`], and stay there until they're
+//! removed.
+//!
+//! An [`ExternalTexture`] shows frames that are produced outside of the app,
+//! like video and camera frames, and can be written to from any thread.
+//!
+//! # Example
+//! ```
+//! use agape::{Color, Size};
+//! use agape::image_cache::ImageCache;
+//! use agape::resources::Resources;
+//! use agape::texture::render_to_texture;
+//! use agape::widgets::Image;
+//!
+//! let mut resources = Resources::new();
+//! resources.insert(ImageCache::default());
+//!
+//! let size = Size::new(64.0, 64.0);
+//! let handle = render_to_texture(&resources, size, |pixmap, _| {
+//!     pixmap.fill(tiny_skia::Color::from_rgba8(255, 0, 0, 255));
+//! })
+//! .unwrap();
+//! let image = Image::texture(handle, 64.0, 64.0);
+//! ```
+use crate::image_cache::{ImageCache, ImageHandle, premultiply};
+use crate::render_widget;
+use crate::resources::Resources;
+use crate::widgets::Widget;
+use crate::{Error, Result};
+use agape_core::Size;
+use agape_layout::LayoutSolver;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+use tiny_skia::Pixmap;
+
+/// Draw onto a transparent pixmap and add it to the [`ImageCache`] as a
+/// texture.
+///
+/// Returns `None` if the size is empty or the resources don't have an
+/// [`ImageCache`].
+pub fn render_to_texture(
+    resources: &Resources,
+    size: Size,
+    draw: impl FnOnce(&mut Pixmap, &Resources),
+) -> Option<ImageHandle> {
+    let cache = resources.get::<ImageCache>()?;
+    let mut pixmap = Pixmap::new(size.width.ceil() as u32, size.height.ceil() as u32)?;
+    draw(&mut pixmap, resources);
+    Some(cache.insert(pixmap))
+}
+
+/// Lay out a widget in an area of `size` and draw it to a texture.
+///
+/// Returns `None` if the size is empty or the resources don't have an
+/// [`ImageCache`].
+pub fn render_widget_to_texture(
+    resources: &Resources,
+    widget: &dyn Widget,
+    size: Size,
+) -> Option<ImageHandle> {
+    let mut layout = widget.layout();
+    LayoutSolver::solve(&mut *layout, size);
+    render_to_texture(resources, size, |pixmap, resources| {
+        render_widget(widget, layout.as_ref(), pixmap, resources);
+    })
+}
+
+/// The latest frame written to an [`ExternalTexture`].
+#[derive(Debug, Default)]
+struct Frame {
+    width: u32,
+    height: u32,
+    /// RGBA pixels that aren't premultiplied.
+    pixels: Vec<u8>,
+    dirty: bool,
+}
+
+/// A texture that's updated from outside of the app, like frames of a video
+/// or a camera. Display it with [`Image::texture`](crate::widgets::Image::texture).
+///
+/// The texture is removed from the [`ImageCache`] once every clone of it has
+/// been dropped.
+#[derive(Debug, Clone)]
+pub struct ExternalTexture {
+    handle: ImageHandle,
+    frame: Arc<Mutex<Frame>>,
+}
+
+impl ExternalTexture {
+    pub fn handle(&self) -> ImageHandle {
+        self.handle
+    }
+
+    /// Write a frame of RGBA pixels, which aren't premultiplied. Only the
+    /// latest frame is shown, frames written before the next redraw are
+    /// skipped.
+    ///
+    /// # Errors
+    /// Returns an error if there aren't `width * height * 4` bytes.
+    pub fn write_rgba(&self, width: u32, height: u32, rgba: &[u8]) -> Result<()> {
+        let expected = width as usize * height as usize * 4;
+        if rgba.len() != expected {
+            return Err(Error::FrameSizeError {
+                expected,
+                actual: rgba.len(),
+            });
+        }
+
+        let mut frame = self.frame.lock().unwrap();
+        // Reuses the buffer of the last frame
+        frame.pixels.clear();
+        frame.pixels.extend_from_slice(rgba);
+        frame.width = width;
+        frame.height = height;
+        frame.dirty = true;
+        Ok(())
+    }
+}
+
+/// The [`ExternalTexture`]s of an app.
+#[derive(Debug, Default)]
+pub struct ExternalTextures {
+    textures: RefCell<Vec<ExternalTexture>>,
+}
+
+impl ExternalTextures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textures.borrow().is_empty()
+    }
+}
+
+/// Add a transparent texture to the [`ImageCache`] that's updated with
+/// [`ExternalTexture::write_rgba`].
+///
+/// Returns `None` if the size is empty or the resources don't have an
+/// [`ImageCache`] and [`ExternalTextures`].
+pub fn external_texture(resources: &Resources, width: u32, height: u32) -> Option<ExternalTexture> {
+    let textures = resources.get::<ExternalTextures>()?;
+    let cache = resources.get::<ImageCache>()?;
+    let pixmap = Pixmap::new(width, height)?;
+    let texture = ExternalTexture {
+        handle: cache.insert(pixmap),
+        frame: Arc::default(),
+    };
+    textures.textures.borrow_mut().push(texture.clone());
+    Some(texture)
+}
+
+/// Copy the latest frames into their textures.
+pub(crate) fn update_external_textures(resources: &mut Resources) {
+    let Some(textures) = resources.get::<ExternalTextures>() else {
+        return;
+    };
+    let Some(cache) = resources.get::<ImageCache>() else {
+        return;
+    };
+
+    textures.textures.borrow_mut().retain(|texture| {
+        // Only the registry is holding on to the texture
+        if Arc::strong_count(&texture.frame) == 1 {
+            cache.remove(texture.handle);
+            return false;
+        }
+
+        let mut frame = texture.frame.lock().unwrap();
+        if frame.dirty {
+            frame.dirty = false;
+            cache.update(texture.handle, frame.width, frame.height, |data| {
+                data.copy_from_slice(&frame.pixels);
+                premultiply(data);
+            });
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+    use crate::style::Styled;
+    use crate::widgets::Rect;
+
+    #[test]
+    fn render_widget_offscreen() {
+        let mut resources = Resources::new();
+        resources.insert(ImageCache::default());
+        let rect = Rect::new(20.0, 20.0).background_color(Color::rgb(0, 0, 255));
+        let handle = render_widget_to_texture(&resources, &rect, Size::new(40.0, 30.0)).unwrap();
+
+        let texture = resources.get::<ImageCache>().unwrap().get(handle).unwrap();
+        assert_eq!((texture.width(), texture.height()), (40, 30));
+        assert_eq!(texture.pixel(10, 10).unwrap().blue(), 255);
+        // The rest of the texture is transparent
+        assert_eq!(texture.pixel(30, 25).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn empty_texture() {
+        let mut resources = Resources::new();
+        resources.insert(ImageCache::default());
+        let handle = render_to_texture(&resources, Size::new(0.0, 10.0), |_, _| {});
+        assert!(handle.is_none());
+    }
+
+    fn texture_resources() -> Resources {
+        let mut resources = Resources::new();
+        resources.insert(ImageCache::default());
+        resources.insert(ExternalTextures::new());
+        resources
+    }
+
+    #[test]
+    fn update_external_texture() {
+        let mut resources = texture_resources();
+        let texture = external_texture(&resources, 2, 1).unwrap();
+        let handle = texture.handle();
+        let thread_texture = texture.clone();
+        std::thread::spawn(move || {
+            thread_texture
+                .write_rgba(2, 1, &[255, 0, 0, 255, 0, 0, 255, 128])
+                .unwrap();
+        })
+        .join()
+        .unwrap();
+
+        update_external_textures(&mut resources);
+        let cache = resources.get::<ImageCache>().unwrap();
+        let pixmap = cache.get(handle).unwrap();
+        assert_eq!(pixmap.pixel(0, 0).unwrap().red(), 255);
+        // Pixels are premultiplied when they're copied
+        assert_eq!(pixmap.pixel(1, 0).unwrap().blue(), 128);
+
+        assert!(texture.write_rgba(2, 2, &[0; 8]).is_err());
+    }
+
+    #[test]
+    fn resize_external_texture() {
+        let mut resources = texture_resources();
+        let texture = external_texture(&resources, 2, 2).unwrap();
+        texture.write_rgba(1, 3, &[255; 12]).unwrap();
+        update_external_textures(&mut resources);
+        let cache = resources.get::<ImageCache>().unwrap();
+        let pixmap = cache.get(texture.handle()).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (1, 3));
+    }
+
+    #[test]
+    fn remove_dropped_texture() {
+        let mut resources = texture_resources();
+        let handle = external_texture(&resources, 4, 4).unwrap().handle();
+        update_external_textures(&mut resources);
+        assert!(resources.get::<ExternalTextures>().unwrap().is_empty());
+        assert!(!resources.get::<ImageCache>().unwrap().contains(handle));
+    }
+}
@@ -0,0 +1,281 @@
+//! Recognizing gestures on touchscreens.
+//!
+//! Touches are turned into gestures by the [`Gestures`] resource, which are
+//! delivered to the widgets under the touch like mouse events. Widgets
+//! handle them with a [`GestureHandler`], or get mouse events instead:
+//!
+//! - A tap calls [`GestureHandler::tap`], or clicks the widget.
+//! - Holding a touch still calls [`GestureHandler::long_press`], or right
+//!   clicks the widget.
+//! - Dragging one touch calls [`GestureHandler::pan`], or scrolls.
+//! - Moving two touches apart or together calls [`GestureHandler::pinch`].
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//!
+//! let photo = Button::new(Rect::new(200.0, 200.0))
+//!     .on_pinch(|scale, _| println!("Zoomed by {scale}"))
+//!     .on_long_press(|_| println!("Show the photo's menu"));
+//! ```
+//!
+//! [`GestureHandler`]: crate::widgets::GestureHandler
+//! [`GestureHandler::tap`]: crate::widgets::GestureHandler::tap
+//! [`GestureHandler::long_press`]: crate::widgets::GestureHandler::long_press
+//! [`GestureHandler::pan`]: crate::widgets::GestureHandler::pan
+//! [`GestureHandler::pinch`]: crate::widgets::GestureHandler::pinch
+use agape_core::Position;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use winit::event::TouchPhase;
+
+/// A gesture made on a touchscreen, positions are in the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A touch was lifted quickly without moving.
+    Tap(Position),
+    /// A touch was held without moving.
+    LongPress(Position),
+    /// A touch that started at `start` moved, by a number of pixels.
+    Pan { start: Position, delta: Position },
+    /// Two touches moved apart or together. The `scale` is the change in
+    /// the distance between them since the last pinch, around their
+    /// `center`.
+    Pinch { scale: f32, center: Position },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// No touches, or only the ones that are left after a gesture.
+    Idle,
+    /// One touch that could still be a tap, a long press or a pan.
+    Pressed(Instant),
+    LongPressed,
+    Panning,
+    /// Two touches, the distance between them at the last pinch.
+    Pinching(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Touch {
+    start: Position,
+    position: Position,
+}
+
+/// Turns touches into [`Gesture`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gestures {
+    /// How far a touch can move and still be a tap or a long press.
+    pub tap_slop: f32,
+    /// How long a touch has to be held for a long press.
+    pub long_press_duration: Duration,
+    touches: HashMap<u64, Touch>,
+    /// The order the touches started in.
+    order: Vec<u64>,
+    state: State,
+}
+
+impl Default for Gestures {
+    fn default() -> Self {
+        Self {
+            tap_slop: 10.0,
+            long_press_duration: Duration::from_millis(500),
+            touches: HashMap::new(),
+            order: vec![],
+            state: State::Idle,
+        }
+    }
+}
+
+impl Gestures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if a touch is on the screen.
+    pub fn is_touching(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    /// Handle a touch event, returns the gesture it finished or continued.
+    pub fn touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: Position,
+        time: Instant,
+    ) -> Option<Gesture> {
+        match phase {
+            TouchPhase::Started => {
+                self.touches.insert(
+                    id,
+                    Touch {
+                        start: position,
+                        position,
+                    },
+                );
+                self.order.push(id);
+                self.state = match (self.order.len(), self.state) {
+                    (1, _) => State::Pressed(time),
+                    (2, _) => State::Pinching(self.pinch_distance()?),
+                    // Other touches are ignored
+                    (_, state) => state,
+                };
+                None
+            }
+            TouchPhase::Moved => {
+                let touch = self.touches.get_mut(&id)?;
+                let previous = touch.position;
+                touch.position = position;
+                let touch = *touch;
+                match self.state {
+                    State::Pressed(_) if distance(touch.start, position) > self.tap_slop => {
+                        self.state = State::Panning;
+                        Some(Gesture::Pan {
+                            start: touch.start,
+                            delta: offset(position, touch.start),
+                        })
+                    }
+                    State::Panning => Some(Gesture::Pan {
+                        start: touch.start,
+                        delta: offset(position, previous),
+                    }),
+                    State::Pinching(last_distance) => {
+                        let distance = self.pinch_distance()?;
+                        self.state = State::Pinching(distance);
+                        if last_distance <= 0.0 || distance == last_distance {
+                            return None;
+                        }
+                        Some(Gesture::Pinch {
+                            scale: distance / last_distance,
+                            center: self.pinch_center()?,
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let touch = self.touches.remove(&id)?;
+                self.order.retain(|touch| *touch != id);
+                let tapped = phase == TouchPhase::Ended
+                    && matches!(self.state, State::Pressed(_))
+                    && distance(touch.start, position) <= self.tap_slop;
+                // The touches that are left don't start a new gesture
+                self.state = State::Idle;
+                tapped.then_some(Gesture::Tap(position))
+            }
+        }
+    }
+
+    /// Check whether a touch has been held long enough to be a long press.
+    pub fn update(&mut self, time: Instant) -> Option<Gesture> {
+        let State::Pressed(pressed) = self.state else {
+            return None;
+        };
+        if time.saturating_duration_since(pressed) < self.long_press_duration {
+            return None;
+        }
+        self.state = State::LongPressed;
+        let touch = self.touches.get(self.order.first()?)?;
+        Some(Gesture::LongPress(touch.position))
+    }
+
+    /// Get the first two touches.
+    fn pinch_touches(&self) -> Option<(Position, Position)> {
+        let [first, second, ..] = self.order[..] else {
+            return None;
+        };
+        Some((
+            self.touches.get(&first)?.position,
+            self.touches.get(&second)?.position,
+        ))
+    }
+
+    fn pinch_distance(&self) -> Option<f32> {
+        let (first, second) = self.pinch_touches()?;
+        Some(distance(first, second))
+    }
+
+    fn pinch_center(&self) -> Option<Position> {
+        let (first, second) = self.pinch_touches()?;
+        Some(Position::new(
+            (first.x + second.x) / 2.0,
+            (first.y + second.y) / 2.0,
+        ))
+    }
+}
+
+/// Get the offset from one position to another.
+fn offset(to: Position, from: Position) -> Position {
+    Position::new(to.x - from.x, to.y - from.y)
+}
+
+fn distance(a: Position, b: Position) -> f32 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tap_and_long_press() {
+        let mut gestures = Gestures::new();
+        let now = Instant::now();
+        let position = Position::unit(50.0);
+
+        gestures.touch(0, TouchPhase::Started, position, now);
+        let lifted = Position::unit(54.0);
+        let gesture = gestures.touch(0, TouchPhase::Ended, lifted, now);
+        assert_eq!(gesture, Some(Gesture::Tap(lifted)));
+
+        gestures.touch(1, TouchPhase::Started, position, now);
+        assert_eq!(gestures.update(now), None);
+        let later = now + gestures.long_press_duration;
+        assert_eq!(gestures.update(later), Some(Gesture::LongPress(position)));
+        assert_eq!(gestures.update(later), None);
+        // Lifting the touch after a long press isn't a tap
+        assert_eq!(gestures.touch(1, TouchPhase::Ended, position, later), None);
+        assert!(!gestures.is_touching());
+    }
+
+    #[test]
+    fn pan() {
+        let mut gestures = Gestures::new();
+        let now = Instant::now();
+        let start = Position::unit(10.0);
+
+        gestures.touch(0, TouchPhase::Started, start, now);
+        assert_eq!(
+            gestures.touch(0, TouchPhase::Moved, Position::new(15.0, 10.0), now),
+            None
+        );
+        let gesture = gestures.touch(0, TouchPhase::Moved, Position::new(40.0, 10.0), now);
+        let delta = Position::new(30.0, 0.0);
+        assert_eq!(gesture, Some(Gesture::Pan { start, delta }));
+        let gesture = gestures.touch(0, TouchPhase::Moved, Position::new(40.0, 30.0), now);
+        let delta = Position::new(0.0, 20.0);
+        assert_eq!(gesture, Some(Gesture::Pan { start, delta }));
+
+        assert_eq!(gestures.update(now + Duration::from_secs(1)), None);
+        assert_eq!(gestures.touch(0, TouchPhase::Ended, start, now), None);
+    }
+
+    #[test]
+    fn pinch() {
+        let mut gestures = Gestures::new();
+        let now = Instant::now();
+
+        gestures.touch(0, TouchPhase::Started, Position::new(40.0, 50.0), now);
+        gestures.touch(1, TouchPhase::Started, Position::new(60.0, 50.0), now);
+        let gesture = gestures.touch(1, TouchPhase::Moved, Position::new(80.0, 50.0), now);
+        let center = Position::new(60.0, 50.0);
+        assert_eq!(gesture, Some(Gesture::Pinch { scale: 2.0, center }));
+
+        // The touch that's left doesn't tap or pan
+        gestures.touch(1, TouchPhase::Ended, Position::new(80.0, 50.0), now);
+        let moved = Position::new(100.0, 100.0);
+        assert_eq!(gestures.touch(0, TouchPhase::Moved, moved, now), None);
+        assert_eq!(gestures.touch(0, TouchPhase::Ended, moved, now), None);
+    }
+}
@@ -0,0 +1,844 @@
+//! An icon in the system tray, for utility and background apps.
+//!
+//! The [`Tray`] shows an icon with a menu, which is opened by right
+//! clicking it. Clicking the icon shows the window, and apps that keep
+//! running in the background can hide the window when it's closed instead
+//! of exiting, with [`Tray::close_to_tray`].
+//!
+//! The tray is added with [`App::tray`](crate::App::tray), and changed while
+//! the app is running through the `Tray` resource. It's only supported on
+//! X11 for now, through the system tray protocol that most desktops
+//! implement. On other platforms, or when no tray is running, the app runs
+//! without an icon and closing the window exits as usual.
+//!
+//! # Example
+//! ```no_run
+//! use agape::prelude::*;
+//! use agape::tray::Tray;
+//!
+//! let icon = image::open("icon.png").unwrap().into_rgba8();
+//! let tray = Tray::new(icon)
+//!     .tooltip("Clipboard history")
+//!     .item("Clear history", |_| println!("Cleared"))
+//!     .separator()
+//!     .item("Quit", |resources: &mut Resources| {
+//!         resources.get_mut::<Tray>().unwrap().quit();
+//!     })
+//!     .close_to_tray(true);
+//!
+//! App::new(hstack! {}).tray(tray).run().unwrap();
+//! ```
+use crate::capture;
+use crate::render_widget;
+use crate::resources::Resources;
+use crate::style::Styled;
+use crate::widgets::{HStack, LayoutExt, Rect, Text, VStack, Widget};
+use agape_core::{Color, GlobalId, Position, Size};
+use agape_layout::LayoutSolver;
+use image::RgbaImage;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex};
+use tiny_skia::Pixmap;
+use winit::event_loop::EventLoopProxy;
+use winit::window::Window;
+
+type TrayFn = Rc<RefCell<dyn FnMut(&mut Resources)>>;
+/// The vertical extent of each item in a menu, and the index of the item.
+type MenuRows = Vec<([f32; 2], usize)>;
+
+/// The largest size a menu can be drawn at.
+const MAX_MENU_SIZE: f32 = 2048.0;
+
+/// Something that happened to the icon, sent by the backend.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TrayEvent {
+    /// The icon was clicked with the primary button.
+    Click,
+    /// The icon was clicked with the secondary button, at a position on the
+    /// screen.
+    OpenMenu(Position),
+    /// The open menu was clicked at a position in the menu, or `None` if
+    /// the click was outside of it.
+    MenuClicked(Option<Position>),
+}
+
+/// Sends the backend's events to the ui thread, and wakes up the event loop
+/// since there aren't any window events while the window is hidden.
+#[derive(Clone)]
+struct Notifier {
+    sender: Sender<TrayEvent>,
+    proxy: Arc<Mutex<Option<EventLoopProxy<()>>>>,
+}
+
+impl Notifier {
+    fn send(&self, event: TrayEvent) {
+        let _ = self.sender.send(event);
+        if let Some(proxy) = self.proxy.lock().unwrap().as_ref() {
+            let _ = proxy.send_event(());
+        }
+    }
+}
+
+/// Shows the icon and its menu with the system.
+trait Backend {
+    fn set_icon(&mut self, icon: &RgbaImage);
+
+    fn set_tooltip(&mut self, tooltip: &str);
+
+    /// Show the menu drawn in `image` at a position on the screen.
+    fn show_menu(&mut self, image: &RgbaImage, position: Position);
+
+    fn hide_menu(&mut self);
+}
+
+enum MenuEntry {
+    Item(String, TrayFn),
+    Separator,
+}
+
+/// An icon in the system tray with a menu, see the [module docs](self).
+pub struct Tray {
+    icon: RgbaImage,
+    tooltip: String,
+    menu: Vec<MenuEntry>,
+    click_fn: Option<TrayFn>,
+    close_to_tray: bool,
+    quit: bool,
+    backend: Option<Box<dyn Backend>>,
+    notifier: Notifier,
+    receiver: Receiver<TrayEvent>,
+    open_menu: Option<MenuRows>,
+}
+
+impl Tray {
+    /// Show an icon in the system tray, it's scaled to the size chosen by
+    /// the tray.
+    pub fn new(icon: RgbaImage) -> Self {
+        Self::with_backend(icon, system_backend)
+    }
+
+    fn with_backend(
+        icon: RgbaImage,
+        connect: impl FnOnce(Notifier) -> Option<Box<dyn Backend>>,
+    ) -> Self {
+        let (sender, receiver) = channel();
+        let notifier = Notifier {
+            sender,
+            proxy: Arc::default(),
+        };
+        let mut backend = connect(notifier.clone());
+        if let Some(backend) = &mut backend {
+            backend.set_icon(&icon);
+        }
+        Self {
+            icon,
+            tooltip: String::new(),
+            menu: vec![],
+            click_fn: None,
+            close_to_tray: false,
+            quit: false,
+            backend,
+            notifier,
+            receiver,
+            open_menu: None,
+        }
+    }
+
+    /// Set the text shown when the icon is hovered, trays that don't show
+    /// tooltips ignore it.
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.set_tooltip(tooltip);
+        self
+    }
+
+    /// Add an item to the menu, `f` is called when it's clicked.
+    pub fn item(mut self, label: &str, f: impl FnMut(&mut Resources) + 'static) -> Self {
+        self.menu
+            .push(MenuEntry::Item(label.to_owned(), Rc::new(RefCell::new(f))));
+        self
+    }
+
+    /// Add a line between the items before and after it.
+    pub fn separator(mut self) -> Self {
+        self.menu.push(MenuEntry::Separator);
+        self
+    }
+
+    /// Called when the icon is clicked, instead of showing the window.
+    pub fn on_click(mut self, f: impl FnMut(&mut Resources) + 'static) -> Self {
+        self.click_fn = Some(Rc::new(RefCell::new(f)));
+        self
+    }
+
+    /// Hide the window when it's closed instead of exiting, the window is
+    /// shown again by clicking the icon. Apps that close to the tray should
+    /// have an item that calls [`Tray::quit`].
+    pub fn close_to_tray(mut self, close_to_tray: bool) -> Self {
+        self.close_to_tray = close_to_tray;
+        self
+    }
+
+    /// Change the icon, like to show that there's something new.
+    pub fn set_icon(&mut self, icon: RgbaImage) {
+        if let Some(backend) = &mut self.backend {
+            backend.set_icon(&icon);
+        }
+        self.icon = icon;
+    }
+
+    pub fn icon(&self) -> &RgbaImage {
+        &self.icon
+    }
+
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        if let Some(backend) = &mut self.backend {
+            backend.set_tooltip(tooltip);
+        }
+        self.tooltip = tooltip.to_owned();
+    }
+
+    pub fn get_tooltip(&self) -> &str {
+        &self.tooltip
+    }
+
+    /// Exit the app, even if it closes to the tray.
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    /// Returns `true` if the icon is shown, which is only the case on
+    /// supported platforms while a tray is running.
+    pub fn is_shown(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    pub(crate) fn set_proxy(&mut self, proxy: EventLoopProxy<()>) {
+        *self.notifier.proxy.lock().unwrap() = Some(proxy);
+    }
+
+    /// Close the open menu, returns the callback of the item at `position`
+    /// if there's one.
+    fn close_menu(&mut self, position: Option<Position>) -> Option<TrayFn> {
+        let rows = self.open_menu.take()?;
+        if let Some(backend) = &mut self.backend {
+            backend.hide_menu();
+        }
+        let position = position?;
+        let (_, index) = rows
+            .iter()
+            .find(|([top, bottom], _)| position.y >= *top && position.y < *bottom)?;
+        match self.menu.get(*index)? {
+            MenuEntry::Item(_, f) => Some(Rc::clone(f)),
+            MenuEntry::Separator => None,
+        }
+    }
+}
+
+/// Returns `true` if closing the window should hide it rather than exit,
+/// which needs the icon to be shown, so that the window can be opened again.
+pub(crate) fn closes_to_tray(resources: &Resources) -> bool {
+    resources
+        .get::<Tray>()
+        .is_some_and(|tray| tray.close_to_tray && !tray.quit && tray.is_shown())
+}
+
+pub(crate) fn quit_requested(resources: &Resources) -> bool {
+    resources.get::<Tray>().is_some_and(|tray| tray.quit)
+}
+
+/// Handle the events sent by the backend since the last dispatch.
+pub(crate) fn dispatch(resources: &mut Resources) {
+    while let Some(event) = resources
+        .get_mut::<Tray>()
+        .and_then(|tray| tray.receiver.try_recv().ok())
+    {
+        let tray = resources.get_mut::<Tray>().unwrap();
+        match event {
+            TrayEvent::Click => {
+                tray.close_menu(None);
+                match tray.click_fn.clone() {
+                    Some(f) => (*f.borrow_mut())(resources),
+                    None => show_window(resources),
+                }
+            }
+            TrayEvent::OpenMenu(position) => open_menu(resources, position),
+            TrayEvent::MenuClicked(position) => {
+                if let Some(f) = tray.close_menu(position) {
+                    (*f.borrow_mut())(resources);
+                }
+            }
+        }
+    }
+}
+
+/// Show the window and bring it to the front.
+fn show_window(resources: &Resources) {
+    if let Some(window) = resources.get::<Arc<Window>>() {
+        window.set_visible(true);
+        window.focus_window();
+    }
+}
+
+fn open_menu(resources: &mut Resources, position: Position) {
+    let Some(tray) = resources.get::<Tray>() else {
+        return;
+    };
+    if tray.backend.is_none() || tray.menu.is_empty() {
+        return;
+    }
+    let Some((image, rows)) = render_menu(&tray.menu, resources) else {
+        return;
+    };
+    let tray = resources.get_mut::<Tray>().unwrap();
+    if let Some(backend) = &mut tray.backend {
+        backend.show_menu(&image, position);
+    }
+    tray.open_menu = Some(rows);
+}
+
+/// Draw the menu, returns the image and the vertical extent of each item.
+fn render_menu(menu: &[MenuEntry], resources: &Resources) -> Option<(RgbaImage, MenuRows)> {
+    let mut rows: Vec<(GlobalId, usize)> = vec![];
+    let mut stack = VStack::new().padding(4);
+    for (index, entry) in menu.iter().enumerate() {
+        stack = match entry {
+            MenuEntry::Item(label, _) => {
+                let row = HStack::new()
+                    .add_child(Text::new(label))
+                    .padding(6)
+                    .fill_width();
+                rows.push((row.id(), index));
+                stack.add_child(row)
+            }
+            MenuEntry::Separator => stack.add_child(
+                Rect::new(0.0, 1.0)
+                    .fill_width()
+                    .background_color(Color::rgb(220, 220, 220)),
+            ),
+        };
+    }
+
+    let mut layout = stack.layout();
+    LayoutSolver::solve(&mut *layout, Size::unit(MAX_MENU_SIZE));
+    let size = layout.size();
+    let mut pixmap = Pixmap::new(size.width.ceil() as u32, size.height.ceil() as u32)?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    render_widget(&stack, layout.as_ref(), &mut pixmap, resources);
+
+    let rows = rows
+        .into_iter()
+        .filter_map(|(id, index)| Some((layout.get(id)?.bounds().y, index)))
+        .collect();
+    Some((capture::to_image(&pixmap), rows))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn system_backend(notifier: Notifier) -> Option<Box<dyn Backend>> {
+    match x11::X11Backend::connect(notifier) {
+        Ok(backend) => Some(Box::new(backend)),
+        Err(err) => {
+            log::warn!("The tray icon is unavailable: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn system_backend(_: Notifier) -> Option<Box<dyn Backend>> {
+    None
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod x11 {
+    use super::{Backend, Notifier, TrayEvent};
+    use agape_core::Position;
+    use image::RgbaImage;
+    use image::imageops::FilterType;
+    use std::fmt::Display;
+    use std::sync::{Arc, Mutex};
+    use x11rb::connection::Connection;
+    use x11rb::protocol::Event;
+    use x11rb::protocol::xproto::{
+        AtomEnum, ClientMessageEvent, ColormapAlloc, ConfigureWindowAux, ConnectionExt,
+        CreateGCAux, CreateWindowAux, EventMask, Gcontext, GrabMode, ImageFormat, ImageOrder,
+        PropMode, Screen, StackMode, Visualid, Window, WindowClass,
+    };
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::wrapper::ConnectionExt as _;
+
+    const SYSTEM_TRAY_REQUEST_DOCK: u32 = 0;
+    /// The `_XEMBED_INFO` flag that asks the tray to show the window.
+    const XEMBED_MAPPED: u32 = 1;
+    /// The size of the icon until the tray resizes it.
+    const DEFAULT_ICON_SIZE: u16 = 22;
+    /// The most bytes of an image sent in one request, larger images are
+    /// sent a few rows at a time.
+    const MAX_IMAGE_BYTES: usize = 64 * 1024;
+
+    /// The images drawn in the windows, which are drawn again when they're
+    /// exposed.
+    struct Images {
+        icon: RgbaImage,
+        /// The size of the icon window, which is chosen by the tray.
+        icon_size: (u16, u16),
+        menu: Option<RgbaImage>,
+    }
+
+    /// The state shared with the thread that waits for events.
+    struct Shared {
+        connection: RustConnection,
+        icon_window: Window,
+        menu_window: Window,
+        gc: Gcontext,
+        depth: u8,
+        images: Mutex<Images>,
+    }
+
+    impl Shared {
+        fn draw_icon(&self) {
+            let images = self.images.lock().unwrap();
+            let (width, height) = images.icon_size;
+            if width == 0 || height == 0 {
+                return;
+            }
+            let icon = image::imageops::resize(
+                &images.icon,
+                width as u32,
+                height as u32,
+                FilterType::Triangle,
+            );
+            self.put_image(self.icon_window, &icon);
+        }
+
+        fn draw_menu(&self) {
+            if let Some(menu) = &self.images.lock().unwrap().menu {
+                self.put_image(self.menu_window, menu);
+            }
+        }
+
+        /// Draw an image in the top left of a window, alpha is only kept by
+        /// trays with a transparent visual, the others show it over black.
+        fn put_image(&self, window: Window, image: &RgbaImage) {
+            let lsb_first = self.connection.setup().image_byte_order == ImageOrder::LSB_FIRST;
+            let data: Vec<u8> = image
+                .pixels()
+                .flat_map(|pixel| {
+                    let [r, g, b, a] = pixel.0;
+                    let premultiply = |c: u8| (c as u32 * a as u32 / 255) as u8;
+                    let pixel =
+                        u32::from_be_bytes([a, premultiply(r), premultiply(g), premultiply(b)]);
+                    match lsb_first {
+                        true => pixel.to_le_bytes(),
+                        false => pixel.to_be_bytes(),
+                    }
+                })
+                .collect();
+
+            let row_bytes = image.width() as usize * 4;
+            let rows_per_request = (MAX_IMAGE_BYTES / row_bytes.max(1)).max(1);
+            for (i, chunk) in data.chunks(row_bytes * rows_per_request).enumerate() {
+                let _ = self.connection.put_image(
+                    ImageFormat::Z_PIXMAP,
+                    window,
+                    self.gc,
+                    image.width() as u16,
+                    (chunk.len() / row_bytes) as u16,
+                    0,
+                    (i * rows_per_request) as i16,
+                    0,
+                    self.depth,
+                    chunk,
+                );
+            }
+            let _ = self.connection.flush();
+        }
+
+        fn handle_event(&self, event: Event, notifier: &Notifier) {
+            match event {
+                Event::Expose(event) if event.count == 0 => {
+                    if event.window == self.icon_window {
+                        self.draw_icon();
+                    } else if event.window == self.menu_window {
+                        self.draw_menu();
+                    }
+                }
+                Event::ConfigureNotify(event) if event.window == self.icon_window => {
+                    self.images.lock().unwrap().icon_size = (event.width, event.height);
+                    self.draw_icon();
+                }
+                Event::ButtonPress(event) if event.event == self.icon_window => {
+                    let position = Position::new(event.root_x as f32, event.root_y as f32);
+                    match event.detail {
+                        1 => notifier.send(TrayEvent::Click),
+                        3 => notifier.send(TrayEvent::OpenMenu(position)),
+                        _ => {}
+                    }
+                }
+                // The pointer is grabbed while the menu is open, so every
+                // click is reported to it
+                Event::ButtonPress(event) if event.event == self.menu_window => {
+                    let size = self
+                        .images
+                        .lock()
+                        .unwrap()
+                        .menu
+                        .as_ref()
+                        .map(|menu| (menu.width() as i16, menu.height() as i16));
+                    let (x, y) = (event.event_x, event.event_y);
+                    let inside = size.is_some_and(|(w, h)| x >= 0 && y >= 0 && x < w && y < h);
+                    let position = inside.then(|| Position::new(x as f32, y as f32));
+                    notifier.send(TrayEvent::MenuClicked(position));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Docks a window in the tray with the system tray protocol, a thread
+    /// waits for its events.
+    pub(super) struct X11Backend {
+        shared: Arc<Shared>,
+        screen_size: (i16, i16),
+        net_wm_name: u32,
+        utf8_string: u32,
+    }
+
+    impl X11Backend {
+        pub fn connect(notifier: Notifier) -> Result<Self, String> {
+            let (connection, screen_num) = x11rb::connect(None).map_err(tray_error)?;
+            let atom = |name: &str| -> Result<u32, String> {
+                Ok(connection
+                    .intern_atom(false, name.as_bytes())
+                    .map_err(tray_error)?
+                    .reply()
+                    .map_err(tray_error)?
+                    .atom)
+            };
+            let selection = atom(&format!("_NET_SYSTEM_TRAY_S{screen_num}"))?;
+            let opcode = atom("_NET_SYSTEM_TRAY_OPCODE")?;
+            let tray_visual = atom("_NET_SYSTEM_TRAY_VISUAL")?;
+            let xembed_info = atom("_XEMBED_INFO")?;
+            let net_wm_name = atom("_NET_WM_NAME")?;
+            let utf8_string = atom("UTF8_STRING")?;
+
+            let owner = connection
+                .get_selection_owner(selection)
+                .map_err(tray_error)?
+                .reply()
+                .map_err(tray_error)?
+                .owner;
+            if owner == x11rb::NONE {
+                return Err("no system tray is running".to_owned());
+            }
+
+            let screen = &connection.setup().roots[screen_num];
+            let root = screen.root;
+            let screen_size = (
+                screen.width_in_pixels as i16,
+                screen.height_in_pixels as i16,
+            );
+            // Trays that support transparent icons advertise a visual with
+            // an alpha channel
+            let visual = connection
+                .get_property(false, owner, tray_visual, AtomEnum::VISUALID, 0, 1)
+                .map_err(tray_error)?
+                .reply()
+                .map_err(tray_error)?
+                .value32()
+                .and_then(|mut visuals| visuals.next());
+            let (depth, visual) = visual
+                .and_then(|visual| Some((visual_depth(screen, visual)?, visual)))
+                .unwrap_or((screen.root_depth, screen.root_visual));
+
+            let colormap = connection.generate_id().map_err(tray_error)?;
+            let icon_window = connection.generate_id().map_err(tray_error)?;
+            let menu_window = connection.generate_id().map_err(tray_error)?;
+            let gc = connection.generate_id().map_err(tray_error)?;
+            connection
+                .create_colormap(ColormapAlloc::NONE, colormap, root, visual)
+                .map_err(tray_error)?;
+            let attributes = CreateWindowAux::new()
+                .background_pixel(0)
+                .border_pixel(0)
+                .colormap(colormap);
+            connection
+                .create_window(
+                    depth,
+                    icon_window,
+                    root,
+                    0,
+                    0,
+                    DEFAULT_ICON_SIZE,
+                    DEFAULT_ICON_SIZE,
+                    0,
+                    WindowClass::INPUT_OUTPUT,
+                    visual,
+                    &attributes.event_mask(
+                        EventMask::EXPOSURE | EventMask::BUTTON_PRESS | EventMask::STRUCTURE_NOTIFY,
+                    ),
+                )
+                .map_err(tray_error)?;
+            connection
+                .create_window(
+                    depth,
+                    menu_window,
+                    root,
+                    0,
+                    0,
+                    1,
+                    1,
+                    0,
+                    WindowClass::INPUT_OUTPUT,
+                    visual,
+                    &attributes
+                        .override_redirect(1)
+                        .event_mask(EventMask::EXPOSURE | EventMask::BUTTON_PRESS),
+                )
+                .map_err(tray_error)?;
+            connection
+                .create_gc(gc, icon_window, &CreateGCAux::new())
+                .map_err(tray_error)?;
+            connection
+                .change_property32(
+                    PropMode::REPLACE,
+                    icon_window,
+                    xembed_info,
+                    xembed_info,
+                    &[0, XEMBED_MAPPED],
+                )
+                .map_err(tray_error)?;
+
+            let dock = ClientMessageEvent::new(
+                32,
+                owner,
+                opcode,
+                [
+                    x11rb::CURRENT_TIME,
+                    SYSTEM_TRAY_REQUEST_DOCK,
+                    icon_window,
+                    0,
+                    0,
+                ],
+            );
+            connection
+                .send_event(false, owner, EventMask::NO_EVENT, dock)
+                .map_err(tray_error)?;
+            connection.flush().map_err(tray_error)?;
+
+            let shared = Arc::new(Shared {
+                connection,
+                icon_window,
+                menu_window,
+                gc,
+                depth,
+                images: Mutex::new(Images {
+                    icon: RgbaImage::new(0, 0),
+                    icon_size: (DEFAULT_ICON_SIZE, DEFAULT_ICON_SIZE),
+                    menu: None,
+                }),
+            });
+            let thread_shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                while let Ok(event) = thread_shared.connection.wait_for_event() {
+                    thread_shared.handle_event(event, &notifier);
+                }
+            });
+
+            Ok(Self {
+                shared,
+                screen_size,
+                net_wm_name,
+                utf8_string,
+            })
+        }
+    }
+
+    impl Backend for X11Backend {
+        fn set_icon(&mut self, icon: &RgbaImage) {
+            self.shared.images.lock().unwrap().icon = icon.clone();
+            self.shared.draw_icon();
+        }
+
+        fn set_tooltip(&mut self, tooltip: &str) {
+            let connection = &self.shared.connection;
+            let _ = connection.change_property8(
+                PropMode::REPLACE,
+                self.shared.icon_window,
+                self.net_wm_name,
+                self.utf8_string,
+                tooltip.as_bytes(),
+            );
+            let _ = connection.flush();
+        }
+
+        fn show_menu(&mut self, image: &RgbaImage, position: Position) {
+            let (width, height) = (image.width() as i16, image.height() as i16);
+            let (screen_width, screen_height) = self.screen_size;
+            // Keep the menu on the screen, trays at the bottom open it
+            // above the cursor
+            let x = (position.x as i16).min(screen_width - width).max(0);
+            let mut y = position.y as i16;
+            if y + height > screen_height {
+                y = (y - height).max(0);
+            }
+
+            let shared = &self.shared;
+            shared.images.lock().unwrap().menu = Some(image.clone());
+            let connection = &shared.connection;
+            let _ = connection.configure_window(
+                shared.menu_window,
+                &ConfigureWindowAux::new()
+                    .x(x as i32)
+                    .y(y as i32)
+                    .width(image.width())
+                    .height(image.height())
+                    .stack_mode(StackMode::ABOVE),
+            );
+            let _ = connection.map_window(shared.menu_window);
+            let _ = connection.grab_pointer(
+                false,
+                shared.menu_window,
+                EventMask::BUTTON_PRESS,
+                GrabMode::ASYNC,
+                GrabMode::ASYNC,
+                x11rb::NONE,
+                x11rb::NONE,
+                x11rb::CURRENT_TIME,
+            );
+            shared.draw_menu();
+        }
+
+        fn hide_menu(&mut self) {
+            let shared = &self.shared;
+            shared.images.lock().unwrap().menu = None;
+            let _ = shared.connection.ungrab_pointer(x11rb::CURRENT_TIME);
+            let _ = shared.connection.unmap_window(shared.menu_window);
+            let _ = shared.connection.flush();
+        }
+    }
+
+    impl Drop for X11Backend {
+        fn drop(&mut self) {
+            let shared = &self.shared;
+            let _ = shared.connection.destroy_window(shared.menu_window);
+            let _ = shared.connection.destroy_window(shared.icon_window);
+            let _ = shared.connection.flush();
+        }
+    }
+
+    fn visual_depth(screen: &Screen, visual: Visualid) -> Option<u8> {
+        screen.allowed_depths.iter().find_map(|depth| {
+            depth
+                .visuals
+                .iter()
+                .any(|v| v.visual_id == visual)
+                .then_some(depth.depth)
+        })
+    }
+
+    fn tray_error(err: impl Display) -> String {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FONT;
+    use crate::view::{GlyphCache, init_font};
+
+    /// Records what the tray asks the system to do.
+    #[derive(Default)]
+    struct TestBackend {
+        calls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Backend for TestBackend {
+        fn set_icon(&mut self, icon: &RgbaImage) {
+            let (width, height) = icon.dimensions();
+            self.calls
+                .borrow_mut()
+                .push(format!("icon {width}x{height}"));
+        }
+
+        fn set_tooltip(&mut self, tooltip: &str) {
+            self.calls.borrow_mut().push(format!("tooltip {tooltip}"));
+        }
+
+        fn show_menu(&mut self, _: &RgbaImage, _: Position) {
+            self.calls.borrow_mut().push("show menu".to_owned());
+        }
+
+        fn hide_menu(&mut self) {
+            self.calls.borrow_mut().push("hide menu".to_owned());
+        }
+    }
+
+    fn test_tray() -> (Tray, Rc<RefCell<Vec<String>>>) {
+        let backend = TestBackend::default();
+        let calls = Rc::clone(&backend.calls);
+        let tray = Tray::with_backend(RgbaImage::new(16, 16), |_| Some(Box::new(backend)));
+        (tray, calls)
+    }
+
+    #[test]
+    fn click_menu_items() {
+        let _ = FONT.set(init_font());
+        let (tray, calls) = test_tray();
+        let tray =
+            tray.item("Open", |_| {})
+                .separator()
+                .item("Quit", |resources: &mut Resources| {
+                    resources.get_mut::<Tray>().unwrap().quit();
+                });
+        let notifier = tray.notifier.clone();
+        let mut resources = Resources::new();
+        resources.insert(GlyphCache::new());
+        resources.insert(tray);
+
+        notifier.send(TrayEvent::OpenMenu(Position::unit(100.0)));
+        dispatch(&mut resources);
+        let rows = resources.get::<Tray>().unwrap().open_menu.clone().unwrap();
+        assert_eq!(rows.iter().map(|(_, i)| *i).collect::<Vec<_>>(), [0, 2]);
+
+        let ([top, _], _) = rows[1];
+        notifier.send(TrayEvent::MenuClicked(Some(Position::new(10.0, top + 1.0))));
+        dispatch(&mut resources);
+        assert!(quit_requested(&resources));
+        assert_eq!(*calls.borrow(), ["icon 16x16", "show menu", "hide menu"]);
+    }
+
+    #[test]
+    fn click_icon() {
+        let (tray, _) = test_tray();
+        let clicks = Rc::new(RefCell::new(0));
+        let tray = tray.on_click({
+            let clicks = Rc::clone(&clicks);
+            move |_| *clicks.borrow_mut() += 1
+        });
+        let notifier = tray.notifier.clone();
+        let mut resources = Resources::new();
+        resources.insert(tray);
+
+        notifier.send(TrayEvent::Click);
+        notifier.send(TrayEvent::MenuClicked(None));
+        dispatch(&mut resources);
+        assert_eq!(*clicks.borrow(), 1);
+    }
+
+    #[test]
+    fn close_to_tray_needs_an_icon() {
+        let mut resources = Resources::new();
+        let tray = Tray::with_backend(RgbaImage::new(16, 16), |_| None).close_to_tray(true);
+        resources.insert(tray);
+        assert!(!closes_to_tray(&resources));
+
+        let mut resources = Resources::new();
+        resources.insert(test_tray().0.close_to_tray(true));
+        assert!(closes_to_tray(&resources));
+        resources.get_mut::<Tray>().unwrap().quit();
+        assert!(!closes_to_tray(&resources));
+    }
+}
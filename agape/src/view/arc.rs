@@ -0,0 +1,244 @@
+use super::new_paint;
+use super::stroke::line_cap;
+use super::{View, skia_color};
+use crate::Resources;
+use crate::style::StrokeCap;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use std::f32::consts::FRAC_PI_2;
+use tiny_skia::{Path, PathBuilder, Pixmap, Stroke, Transform};
+
+/// Draws a circular arc on top of a full circle track.
+///
+/// Angles are in degrees, zero is at the top and angles increase clockwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub color: Color<Rgba>,
+    pub track_color: Color<Rgba>,
+    pub stroke_width: f32,
+    pub cap: StrokeCap,
+    /// The angle the arc starts at.
+    pub start: f32,
+    /// How far the arc goes around the circle, a sweep of `360.0` is a
+    /// full circle.
+    pub sweep: f32,
+}
+
+impl Default for ArcView {
+    fn default() -> Self {
+        Self {
+            id: GlobalId::default(),
+            position: Position::default(),
+            size: Size::default(),
+            color: Color::BLACK,
+            track_color: Color::TRANSPARENT,
+            stroke_width: 4.0,
+            cap: StrokeCap::default(),
+            start: 0.0,
+            sweep: 0.0,
+        }
+    }
+}
+
+impl ArcView {
+    /// Create a new arc view.
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
+}
+
+impl View for ArcView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.color
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        let center = Position::new(
+            self.position.x + self.size.width / 2.0,
+            self.position.y + self.size.height / 2.0,
+        );
+        // Keep the stroke inside the bounds
+        let radius = (self.size.width.min(self.size.height) - self.stroke_width) / 2.0;
+        if radius <= 0.0 {
+            return;
+        }
+
+        let stroke = Stroke {
+            width: self.stroke_width,
+            line_cap: line_cap(self.cap),
+            ..Default::default()
+        };
+
+        let mut paint = new_paint();
+        if let Some(track) = arc_path(center, radius, 0.0, 360.0) {
+            paint.set_color(skia_color(&self.track_color));
+            pixmap.stroke_path(&track, &paint, &stroke, Transform::identity(), None);
+        }
+
+        if let Some(arc) = arc_path(center, radius, self.start, self.sweep) {
+            paint.set_color(skia_color(&self.color));
+            pixmap.stroke_path(&arc, &paint, &stroke, Transform::identity(), None);
+        }
+    }
+}
+
+/// Create the path of a circular arc, the `start` and `sweep` are in
+/// degrees with zero at the top, going clockwise.
+///
+/// The arc is approximated with one cubic curve per quarter circle at most.
+pub(crate) fn arc_path(center: Position, radius: f32, start: f32, sweep: f32) -> Option<Path> {
+    let sweep = sweep.clamp(-360.0, 360.0);
+    if sweep == 0.0 || radius <= 0.0 {
+        return None;
+    }
+
+    let mut builder = PathBuilder::new();
+    push_arc(&mut builder, center, radius, start, sweep);
+    builder.finish()
+}
+
+/// Create the path of a pie slice, or a segment of a donut if the
+/// `inner_radius` isn't zero. The angles are the same as [`arc_path`].
+pub(crate) fn pie_path(
+    center: Position,
+    radius: f32,
+    inner_radius: f32,
+    start: f32,
+    sweep: f32,
+) -> Option<Path> {
+    let sweep = sweep.clamp(-360.0, 360.0);
+    let inner_radius = inner_radius.clamp(0.0, radius);
+    if sweep == 0.0 || radius <= 0.0 || inner_radius == radius {
+        return None;
+    }
+
+    let mut builder = PathBuilder::new();
+    push_arc(&mut builder, center, radius, start, sweep);
+    if inner_radius > 0.0 {
+        // Go back along the inner edge, in the opposite direction
+        push_arc(&mut builder, center, inner_radius, start + sweep, -sweep);
+    } else {
+        builder.line_to(center.x, center.y);
+    }
+    builder.close();
+    builder.finish()
+}
+
+/// Add an arc to the `builder`, it's connected to the previous point with a
+/// line if there is one.
+fn push_arc(builder: &mut PathBuilder, center: Position, radius: f32, start: f32, sweep: f32) {
+    // Convert to radians, measured from the positive x axis
+    let start = start.to_radians() - FRAC_PI_2;
+    let sweep = sweep.to_radians();
+    let segments = (sweep.abs() / FRAC_PI_2).ceil();
+    let step = sweep / segments;
+    // The distance of the control points along the tangent
+    let kappa = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+
+    let point = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        (center.x + radius * cos, center.y + radius * sin, sin, cos)
+    };
+
+    let (x, y, _, _) = point(start);
+    match builder.is_empty() {
+        true => builder.move_to(x, y),
+        false => builder.line_to(x, y),
+    }
+
+    for i in 0..segments as usize {
+        let from = start + step * i as f32;
+        let to = from + step;
+        let (x0, y0, sin0, cos0) = point(from);
+        let (x1, y1, sin1, cos1) = point(to);
+        builder.cubic_to(
+            x0 - kappa * sin0,
+            y0 + kappa * cos0,
+            x1 + kappa * sin1,
+            y1 - kappa * cos1,
+            x1,
+            y1,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty_arc() {
+        assert!(arc_path(Position::default(), 10.0, 0.0, 0.0).is_none());
+        assert!(arc_path(Position::default(), 0.0, 0.0, 90.0).is_none());
+    }
+
+    #[test]
+    fn quarter_arc_bounds() {
+        let path = arc_path(Position::new(50.0, 50.0), 50.0, 0.0, 90.0).unwrap();
+        let bounds = path.bounds();
+
+        // Starts at the top and ends on the right
+        assert!((bounds.left() - 50.0).abs() < 0.01);
+        assert!((bounds.top() - 0.0).abs() < 0.01);
+        assert!((bounds.right() - 100.0).abs() < 0.01);
+        assert!((bounds.bottom() - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn full_circle_bounds() {
+        let path = arc_path(Position::new(50.0, 50.0), 50.0, 0.0, 360.0).unwrap();
+        let bounds = path.bounds();
+
+        assert!((bounds.width() - 100.0).abs() < 0.01);
+        assert!((bounds.height() - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn donut_segment() {
+        let center = Position::unit(50.0);
+        let path = pie_path(center, 50.0, 30.0, 0.0, 180.0).unwrap();
+        let bounds = path.bounds();
+
+        // The right half of the donut
+        assert!((bounds.left() - 50.0).abs() < 0.01);
+        assert!((bounds.right() - 100.0).abs() < 0.01);
+        assert!((bounds.height() - 100.0).abs() < 0.01);
+        assert!(pie_path(center, 50.0, 50.0, 0.0, 90.0).is_none());
+    }
+
+    #[test]
+    fn draw_arc() {
+        let mut view = ArcView::new(GlobalId::new());
+        view.size = Size::unit(100.0);
+        view.stroke_width = 10.0;
+        view.sweep = 90.0;
+
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+
+        // The top right quarter is drawn but not the bottom left
+        assert_ne!(pixmap.pixel(85, 15).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(15, 85).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,135 @@
+//! A separable gaussian blur, the rows of a region are blurred first and
+//! then its columns, which is much cheaper than a two dimensional kernel.
+use tiny_skia::{Pixmap, PremultipliedColorU8};
+
+/// Get the normalized weights of a gaussian kernel, from the center outwards.
+fn kernel(radius: f32) -> Vec<f32> {
+    // Blur radii are two standard deviations, the same as css and shadows
+    let sigma = radius / 2.0;
+    // The gaussian is practically zero three standard deviations away
+    let size = (sigma * 3.0).ceil() as usize;
+    let mut weights: Vec<f32> = (0..=size)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total = weights[0] + weights[1..].iter().sum::<f32>() * 2.0;
+    weights.iter_mut().for_each(|weight| *weight /= total);
+    weights
+}
+
+/// Blur the pixels of a `rect` in place, pixels outside of the rect aren't
+/// sampled, the edges are repeated instead.
+pub(crate) fn blur_region(pixmap: &mut Pixmap, rect: tiny_skia::Rect, radius: f32) {
+    if radius <= 0.0 {
+        return;
+    }
+    let left = rect.left().floor().max(0.0) as usize;
+    let top = rect.top().floor().max(0.0) as usize;
+    let right = (rect.right().ceil() as usize).min(pixmap.width() as usize);
+    let bottom = (rect.bottom().ceil() as usize).min(pixmap.height() as usize);
+    if left >= right || top >= bottom {
+        return;
+    }
+
+    let (width, height) = (right - left, bottom - top);
+    let stride = pixmap.width() as usize;
+    let weights = kernel(radius);
+
+    let mut buffer: Vec<[f32; 4]> = Vec::with_capacity(width * height);
+    for y in top..bottom {
+        for pixel in &pixmap.pixels()[y * stride + left..y * stride + right] {
+            buffer.push([
+                pixel.red() as f32,
+                pixel.green() as f32,
+                pixel.blue() as f32,
+                pixel.alpha() as f32,
+            ]);
+        }
+    }
+
+    let buffer = blur_pass(&buffer, width, height, &weights, true);
+    let buffer = blur_pass(&buffer, width, height, &weights, false);
+
+    let pixels = pixmap.pixels_mut();
+    for (index, [r, g, b, a]) in buffer.into_iter().enumerate() {
+        let (x, y) = (left + index % width, top + index / width);
+        let a = a.round().clamp(0.0, 255.0) as u8;
+        let channel = |value: f32| (value.round().clamp(0.0, 255.0) as u8).min(a);
+        pixels[y * stride + x] =
+            PremultipliedColorU8::from_rgba(channel(r), channel(g), channel(b), a).unwrap();
+    }
+}
+
+/// Blur each row, or each column, of a buffer.
+fn blur_pass(
+    buffer: &[[f32; 4]],
+    width: usize,
+    height: usize,
+    weights: &[f32],
+    horizontal: bool,
+) -> Vec<[f32; 4]> {
+    let (length, count) = if horizontal {
+        (width, height)
+    } else {
+        (height, width)
+    };
+    let index = |line: usize, i: usize| {
+        if horizontal {
+            line * width + i
+        } else {
+            i * width + line
+        }
+    };
+
+    let mut output = vec![[0.0; 4]; buffer.len()];
+    for line in 0..count {
+        for i in 0..length {
+            let center = buffer[index(line, i)];
+            let mut sum = center.map(|channel| channel * weights[0]);
+            for (offset, weight) in weights.iter().enumerate().skip(1) {
+                let before = buffer[index(line, i.saturating_sub(offset))];
+                let after = buffer[index(line, (i + offset).min(length - 1))];
+                for channel in 0..4 {
+                    sum[channel] += (before[channel] + after[channel]) * weight;
+                }
+            }
+            output[index(line, i)] = sum;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn kernel_is_normalized() {
+        let weights = kernel(8.0);
+        let total = weights[0] + weights[1..].iter().sum::<f32>() * 2.0;
+        assert!((total - 1.0).abs() < 0.001);
+        assert!(weights.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn blur_hard_edge() {
+        let mut pixmap = Pixmap::new(100, 20).unwrap();
+        pixmap.fill(tiny_skia::Color::WHITE);
+        let black = tiny_skia::Rect::from_xywh(0.0, 0.0, 50.0, 20.0).unwrap();
+        pixmap.fill_rect(
+            black,
+            &tiny_skia::Paint::default(),
+            Default::default(),
+            None,
+        );
+
+        let region = tiny_skia::Rect::from_xywh(20.0, 0.0, 60.0, 20.0).unwrap();
+        blur_region(&mut pixmap, region, 10.0);
+
+        let red = |x| pixmap.pixel(x, 10).unwrap().red();
+        assert!(red(48) > 0 && red(48) < red(52) && red(52) < 255);
+        // Far from the edge, and outside the region, nothing changes
+        assert_eq!(red(25), 0);
+        assert_eq!(red(75), 255);
+        assert_eq!(red(10), 0);
+    }
+}
@@ -0,0 +1,85 @@
+use super::View;
+use super::gauge::{START_ANGLE, SWEEP_ANGLE, fill_circle, point_at, stroke_line, value_angle};
+use crate::Resources;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::Pixmap;
+
+/// Draws a [`Dial`](crate::widgets::Dial).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    pub detents: usize,
+    pub color: Color<Rgba>,
+    pub indicator_color: Color<Rgba>,
+}
+
+impl DialView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            min: 0.0,
+            max: 1.0,
+            value: 0.0,
+            detents: 0,
+            color: Color::rgb(60, 60, 60),
+            indicator_color: Color::WHITE,
+        }
+    }
+}
+
+impl View for DialView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.color
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        let center = Position::new(
+            self.position.x + self.size.width / 2.0,
+            self.position.y + self.size.height / 2.0,
+        );
+        let radius = self.size.width.min(self.size.height) / 2.0;
+        // Leave room for the detent marks around the knob
+        let knob_radius = radius * 0.8;
+        if knob_radius <= 0.0 {
+            return;
+        }
+
+        if self.detents > 1 {
+            for i in 0..self.detents {
+                let angle = START_ANGLE + SWEEP_ANGLE * i as f32 / (self.detents - 1) as f32;
+                let from = point_at(center, radius * 0.9, angle);
+                let to = point_at(center, radius, angle);
+                stroke_line(pixmap, from, to, 1.0, &self.color);
+            }
+        }
+
+        fill_circle(pixmap, center, knob_radius, &self.color);
+        let angle = value_angle(self.value, self.min, self.max);
+        let from = point_at(center, knob_radius * 0.3, angle);
+        let to = point_at(center, knob_radius * 0.85, angle);
+        stroke_line(pixmap, from, to, 2.0, &self.indicator_color);
+    }
+}
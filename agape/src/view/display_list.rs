@@ -0,0 +1,352 @@
+//! A [`DisplayList`] is a list of the commands that draw a frame, recorded
+//! from the widget tree. The same list is drawn to the window, captured as
+//! an image or exported as an svg, so every backend draws the same frame.
+//!
+//! # Example
+//! ```
+//! use agape::layout::{Layout, LayoutSolver};
+//! use agape::prelude::*;
+//! use agape::view::DisplayList;
+//!
+//! let rect = Rect::new(40.0, 20.0).background_color(Color::BLUE);
+//! let mut layout = rect.layout();
+//! LayoutSolver::solve(&mut *layout, Size::new(100.0, 100.0));
+//!
+//! let list = DisplayList::record(&rect, layout.as_ref());
+//! let svg = list.to_svg(Size::new(100.0, 100.0), &Resources::new());
+//! assert!(svg.contains("<rect"));
+//! ```
+use super::{LayerPool, RectView, View, blur_region};
+use crate::Resources;
+use crate::error_boundary::{self, BoundaryView};
+use crate::signal::{self, Reader};
+use crate::style::CornerRadius;
+use crate::widgets::Widget;
+use agape_core::{Color, Position, Rgba, Size};
+use agape_layout::Layout;
+use std::fmt;
+use tiny_skia::{Pixmap, Transform};
+
+/// A single drawing operation in a [`DisplayList`].
+pub enum DrawCommand {
+    /// Fill a rect with a solid color.
+    FillRect {
+        position: Position,
+        size: Size,
+        corner_radius: CornerRadius,
+        color: Color<Rgba>,
+    },
+    /// Draw a view that can't be described by the other commands.
+    View(Box<dyn View>),
+    /// Draw the following commands to a separate layer, until the matching
+    /// [`DrawCommand::PopLayer`].
+    PushLayer(Layer),
+    /// Blend the current layer into the one below it.
+    PopLayer,
+}
+
+impl fmt::Debug for DrawCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FillRect {
+                position,
+                size,
+                corner_radius,
+                color,
+            } => f
+                .debug_struct("FillRect")
+                .field("position", position)
+                .field("size", size)
+                .field("corner_radius", corner_radius)
+                .field("color", color)
+                .finish(),
+            Self::View(view) => f.debug_tuple("View").field(&view.id()).finish(),
+            Self::PushLayer(layer) => f.debug_tuple("PushLayer").field(layer).finish(),
+            Self::PopLayer => f.write_str("PopLayer"),
+        }
+    }
+}
+
+/// A group of commands that is blended as a whole, for widgets with a
+/// group opacity, blur or transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub opacity: f32,
+    /// The radius that the layer is blurred by.
+    pub blur: f32,
+    /// The area that the blur is applied to, before it spreads out.
+    pub bounds: Option<tiny_skia::Rect>,
+    pub transform: Transform,
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            blur: 0.0,
+            bounds: None,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+/// The commands that draw a frame, in the order they're drawn.
+#[derive(Debug, Default)]
+pub struct DisplayList {
+    commands: Vec<DrawCommand>,
+}
+
+impl DisplayList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the commands that draw a widget and its children, the widgets
+    /// must already be laid out.
+    pub fn record(widget: &dyn Widget, layout: &dyn Layout) -> Self {
+        let mut list = Self::new();
+        list.record_widget(widget, layout);
+        list
+    }
+
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    /// Move the commands of another list to the end of this one.
+    pub fn append(&mut self, list: DisplayList) {
+        self.commands.extend(list.commands);
+    }
+
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn record_widget(&mut self, widget: &dyn Widget, layout: &dyn Layout) {
+        let opacity = widget.group_opacity().unwrap_or(1.0);
+        let blur = widget.group_blur().unwrap_or(0.0);
+        let widget_layout = layout.get(widget.id());
+        let transform = widget.group_transform().and_then(|transform| {
+            let layout = widget_layout?;
+            Some(transform.matrix(layout.position(), layout.size()))
+        });
+        if opacity <= 0.0 {
+            return;
+        }
+
+        let layered = opacity < 1.0 || blur > 0.0 || transform.is_some();
+        if layered {
+            let bounds = widget_layout.and_then(|layout| {
+                let Position { x, y } = layout.position();
+                let Size { width, height } = layout.size();
+                tiny_skia::Rect::from_xywh(x, y, width, height)
+            });
+            self.push(DrawCommand::PushLayer(Layer {
+                opacity,
+                blur,
+                bounds,
+                transform: transform.unwrap_or_default(),
+            }));
+        }
+
+        let mut view = signal::track(Reader::View(widget.id()), || widget.view());
+        let widget_layout = layout.get(view.id()).unwrap();
+        view.set_size(widget_layout.size());
+        view.set_position(widget_layout.position());
+        match view.commands() {
+            Some(commands) => self.commands.extend(commands),
+            None => self.push(DrawCommand::View(view)),
+        }
+
+        let scope = widget.as_scope();
+        let mut record = || {
+            if !scope.is_some_and(|scope| scope.catches_panics()) {
+                self.record_children(widget, layout);
+                return;
+            }
+            let id = widget.id();
+            let children = error_boundary::catch(id, || {
+                let mut list = DisplayList::new();
+                list.record_children(widget, layout);
+                list
+            });
+            if let Some(list) = children {
+                self.push(DrawCommand::View(Box::new(BoundaryView::new(id, list))));
+            }
+        };
+        match scope {
+            Some(scope) => scope.with_context(&mut record),
+            None => record(),
+        }
+
+        if layered {
+            self.push(DrawCommand::PopLayer);
+        }
+    }
+
+    fn record_children(&mut self, widget: &dyn Widget, layout: &dyn Layout) {
+        // Children are drawn in the same order as `Widget::iter`
+        for child in widget.children().into_iter().rev() {
+            self.record_widget(child, layout);
+        }
+    }
+
+    /// Draw the commands onto a pixmap.
+    pub fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        render_commands(&mut self.commands.iter(), pixmap, resources);
+    }
+}
+
+/// Draw commands until the end of the current layer.
+fn render_commands<'a>(
+    commands: &mut impl Iterator<Item = &'a DrawCommand>,
+    pixmap: &mut Pixmap,
+    resources: &Resources,
+) {
+    while let Some(command) = commands.next() {
+        match command {
+            DrawCommand::FillRect {
+                position,
+                size,
+                corner_radius,
+                color,
+            } => {
+                let view = RectView {
+                    position: *position,
+                    size: *size,
+                    corner_radius: *corner_radius,
+                    color: color.clone(),
+                    ..Default::default()
+                };
+                view.render(pixmap, resources);
+            }
+            DrawCommand::View(view) => view.render(pixmap, resources),
+            DrawCommand::PushLayer(layer) => render_layer(layer, commands, pixmap, resources),
+            DrawCommand::PopLayer => return,
+        }
+    }
+}
+
+/// Draw the commands of a layer to a separate pixmap, which is then
+/// blended into the `pixmap` as a whole.
+fn render_layer<'a>(
+    layer: &Layer,
+    commands: &mut impl Iterator<Item = &'a DrawCommand>,
+    pixmap: &mut Pixmap,
+    resources: &Resources,
+) {
+    let pool = resources.get::<LayerPool>();
+    let pixels = match pool {
+        Some(pool) => pool.take(pixmap.width(), pixmap.height()),
+        None => Pixmap::new(pixmap.width(), pixmap.height()),
+    };
+    let Some(mut pixels) = pixels else {
+        skip_layer(commands);
+        return;
+    };
+    render_commands(commands, &mut pixels, resources);
+    if layer.blur > 0.0 {
+        let bounds = layer.bounds.and_then(|bounds| {
+            // The content spreads out as far as the blur reaches
+            let margin = blur_margin(layer.blur);
+            bounds.outset(margin, margin)
+        });
+        if let Some(bounds) = bounds {
+            blur_region(&mut pixels, bounds, layer.blur);
+        }
+    }
+    let paint = tiny_skia::PixmapPaint {
+        opacity: layer.opacity,
+        quality: tiny_skia::FilterQuality::Bilinear,
+        ..Default::default()
+    };
+    pixmap.draw_pixmap(0, 0, pixels.as_ref(), &paint, layer.transform, None);
+    if let Some(pool) = pool {
+        pool.give_back(pixels);
+    }
+}
+
+/// Skip the commands of a layer that can't be drawn.
+fn skip_layer<'a>(commands: &mut impl Iterator<Item = &'a DrawCommand>) {
+    let mut depth = 0;
+    for command in commands {
+        match command {
+            DrawCommand::PushLayer(_) => depth += 1,
+            DrawCommand::PopLayer if depth == 0 => return,
+            DrawCommand::PopLayer => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// Get how far the content of a layer spreads out when it's blurred.
+pub(crate) fn blur_margin(blur: f32) -> f32 {
+    (blur * 1.5).ceil()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hstack;
+    use crate::style::Styled;
+    use crate::widgets::Rect;
+    use agape_layout::LayoutSolver;
+
+    fn record(widget: &dyn Widget) -> DisplayList {
+        let mut layout = widget.layout();
+        LayoutSolver::solve(&mut *layout, Size::new(100.0, 100.0));
+        DisplayList::record(widget, layout.as_ref())
+    }
+
+    #[test]
+    fn record_plain_rects() {
+        let rect = Rect::new(20.0, 20.0).background_color(Color::BLACK);
+        let list = record(&rect);
+        assert!(matches!(
+            list.commands(),
+            [DrawCommand::FillRect { size, .. }] if *size == Size::unit(20.0)
+        ));
+
+        // Layout only widgets have nothing to draw
+        let list = record(&hstack! {});
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn record_layers() {
+        let widget = hstack! {
+            Rect::new(20.0, 20.0).background_color(Color::BLACK)
+        }
+        .opacity(0.5);
+        let list = record(&widget);
+        assert!(matches!(
+            list.commands(),
+            [
+                DrawCommand::PushLayer(Layer { opacity: 0.5, .. }),
+                DrawCommand::FillRect { .. },
+                DrawCommand::PopLayer
+            ]
+        ));
+    }
+
+    #[test]
+    fn skip_nested_layers() {
+        let commands = [
+            DrawCommand::PushLayer(Layer::default()),
+            DrawCommand::PopLayer,
+            DrawCommand::PopLayer,
+            DrawCommand::PopLayer,
+        ];
+        let mut iter = commands.iter();
+        skip_layer(&mut iter);
+        assert_eq!(iter.count(), 1);
+    }
+}
@@ -0,0 +1,101 @@
+use super::new_paint;
+use super::{HitShape, View, skia_color, skia_stroke};
+use crate::Resources;
+use crate::style::StrokeStyle;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, PathBuilder, Pixmap, Transform};
+
+/// Draws an ellipse that fills the view's bounds.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EllipseView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub fill: Color<Rgba>,
+    pub stroke: Option<StrokeStyle>,
+}
+
+impl EllipseView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
+}
+
+impl View for EllipseView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.fill
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn hit_shape(&self) -> HitShape {
+        HitShape::Ellipse
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        let Position { x, y } = self.position;
+        let Size { width, height } = self.size;
+        let path = tiny_skia::Rect::from_xywh(x, y, width, height).and_then(PathBuilder::from_oval);
+        let Some(path) = path else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.fill));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+
+        if let Some(stroke) = &self.stroke {
+            paint.set_color(skia_color(&stroke.color));
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &skia_stroke(stroke),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fill_bounds() {
+        let mut view = EllipseView::new(GlobalId::new());
+        view.size = Size::new(100.0, 50.0);
+        view.fill = Color::BLACK;
+
+        let mut pixmap = Pixmap::new(100, 50).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+
+        assert_eq!(pixmap.pixel(50, 25).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(5, 25).unwrap().alpha(), 255);
+        // The corners of the bounds are outside the ellipse
+        assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,213 @@
+use super::new_paint;
+use super::rect::rounded_rect_path;
+use super::{TextView, View, skia_color};
+use crate::Resources;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// The size of each emoji in the grid.
+pub(crate) const EMOJI_CELL: f32 = 36.0;
+pub(crate) const EMOJI_COLUMNS: usize = 8;
+pub(crate) const EMOJI_ROWS: usize = 6;
+/// The height of the tab, search and skin tone bars.
+pub(crate) const PICKER_BAR_HEIGHT: f32 = 32.0;
+const BACKGROUND_COLOR: Color<Rgba> = Color::WHITE;
+const BORDER_COLOR: Color<Rgba> = Color::rgb(210, 210, 210);
+const SELECTED_COLOR: Color<Rgba> = Color::rgb(229, 231, 235);
+const PLACEHOLDER_COLOR: Color<Rgba> = Color::rgb(150, 150, 150);
+
+/// Draws an [`EmojiPicker`](crate::widgets::EmojiPicker).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmojiPickerView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub open: bool,
+    /// The icon of each tab, and whether it's selected.
+    pub tabs: Vec<(String, bool)>,
+    pub query: String,
+    /// The emoji in the grid, from left to right and top to bottom.
+    pub cells: Vec<String>,
+    /// The color of each skin tone swatch, and whether it's selected.
+    pub skin_tones: Vec<(Color<Rgba>, bool)>,
+}
+
+impl EmojiPickerView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            open: true,
+            tabs: vec![],
+            query: String::new(),
+            cells: vec![],
+            skin_tones: vec![],
+        }
+    }
+
+    fn fill_rect(&self, pixmap: &mut Pixmap, rect: Option<Rect>, radius: f32, color: &Color<Rgba>) {
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, radius)) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    /// Draw text centered in a rect.
+    fn draw_text(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        text: &str,
+        center: Position,
+        color: Color<Rgba>,
+    ) {
+        let mut view = TextView::new(text);
+        view.set_foreground_color(color);
+        let size = view.text_size();
+        view.set_size(size);
+        view.set_position(Position::new(
+            center.x - size.width / 2.0,
+            center.y - size.height / 2.0,
+        ));
+        view.render(pixmap, resources);
+    }
+
+    fn draw_divider(&self, pixmap: &mut Pixmap, y: f32) {
+        let mut builder = PathBuilder::new();
+        builder.move_to(self.position.x, self.position.y + y);
+        builder.line_to(self.position.x + self.size.width, self.position.y + y);
+        let Some(path) = builder.finish() else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&BORDER_COLOR));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+}
+
+impl View for EmojiPickerView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &BACKGROUND_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        if !self.open {
+            return;
+        }
+
+        let Position { x, y } = self.position;
+        let background = Rect::from_xywh(x, y, self.size.width, self.size.height);
+        self.fill_rect(pixmap, background, 8.0, &BACKGROUND_COLOR);
+
+        // Tabs
+        let tab_width = self.size.width / self.tabs.len().max(1) as f32;
+        for (index, (icon, selected)) in self.tabs.iter().enumerate() {
+            let left = x + index as f32 * tab_width;
+            if *selected {
+                let rect = Rect::from_xywh(left + 2.0, y + 2.0, tab_width - 4.0, 28.0);
+                self.fill_rect(pixmap, rect, 4.0, &SELECTED_COLOR);
+            }
+            let center = Position::new(left + tab_width / 2.0, y + PICKER_BAR_HEIGHT / 2.0);
+            self.draw_text(pixmap, resources, icon, center, Color::BLACK);
+        }
+        self.draw_divider(pixmap, PICKER_BAR_HEIGHT);
+
+        // Search
+        let (query, color) = match self.query.is_empty() {
+            true => ("Search", PLACEHOLDER_COLOR),
+            false => (self.query.as_str(), Color::BLACK),
+        };
+        let mut text = TextView::new(query);
+        text.font_size = 14;
+        text.set_foreground_color(color);
+        let size = text.text_size();
+        text.set_size(size);
+        text.set_position(Position::new(
+            x + 8.0,
+            y + PICKER_BAR_HEIGHT * 1.5 - size.height / 2.0,
+        ));
+        text.render(pixmap, resources);
+        self.draw_divider(pixmap, PICKER_BAR_HEIGHT * 2.0);
+
+        // Emoji grid
+        let grid_top = y + PICKER_BAR_HEIGHT * 2.0;
+        for (index, emoji) in self.cells.iter().enumerate() {
+            let column = (index % EMOJI_COLUMNS) as f32;
+            let row = (index / EMOJI_COLUMNS) as f32;
+            let center = Position::new(
+                x + (column + 0.5) * EMOJI_CELL,
+                grid_top + (row + 0.5) * EMOJI_CELL,
+            );
+            self.draw_text(pixmap, resources, emoji, center, Color::BLACK);
+        }
+
+        // Skin tones
+        let tones_top = grid_top + EMOJI_ROWS as f32 * EMOJI_CELL;
+        self.draw_divider(pixmap, tones_top - y);
+        for (index, (color, selected)) in self.skin_tones.iter().enumerate() {
+            let center_x = x + (index as f32 + 0.5) * EMOJI_CELL;
+            let center_y = tones_top + PICKER_BAR_HEIGHT / 2.0;
+            if *selected {
+                let rect = Rect::from_xywh(center_x - 12.0, center_y - 12.0, 24.0, 24.0);
+                self.fill_rect(pixmap, rect, 12.0, &SELECTED_COLOR);
+            }
+            let rect = Rect::from_xywh(center_x - 8.0, center_y - 8.0, 16.0, 16.0);
+            self.fill_rect(pixmap, rect, 8.0, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FONT;
+    use crate::view::init_font;
+
+    #[test]
+    fn closed_picker_is_empty() {
+        let _ = FONT.set(init_font());
+        let mut view = EmojiPickerView::new(GlobalId::new());
+        view.size = Size::new(288.0, 312.0);
+        view.skin_tones = vec![(Color::RED, true)];
+
+        let mut pixmap = Pixmap::new(288, 312).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+        assert_ne!(pixmap.pixel(18, 296).unwrap().alpha(), 0);
+
+        view.open = false;
+        let mut pixmap = Pixmap::new(288, 312).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+        assert!(pixmap.pixels().iter().all(|pixel| pixel.alpha() == 0));
+    }
+}
@@ -0,0 +1,270 @@
+use super::glyph::Glyph;
+use crate::image_cache::decode;
+use fontdue::{Font, FontSettings, Metrics};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tiny_skia::{FilterQuality, Pixmap, PixmapPaint, Transform};
+use ttf_parser::{Face, RasterImageFormat};
+
+static FALLBACK: OnceLock<FontFallback> = OnceLock::new();
+
+/// Get the fonts used for characters that the bundled font doesn't have,
+/// the system fonts are used if the app didn't set any.
+pub(crate) fn font_fallback() -> &'static FontFallback {
+    FALLBACK.get_or_init(FontFallback::system)
+}
+
+/// Set the fonts used for missing characters, returns `false` if they were
+/// already set.
+pub(crate) fn set_font_fallback(fallback: FontFallback) -> bool {
+    FALLBACK.set(fallback).is_ok()
+}
+
+/// A font from the fallback chain.
+#[derive(Debug)]
+pub struct FallbackFont {
+    pub(crate) font: Font,
+    data: Vec<u8>,
+    index: u32,
+}
+
+impl FallbackFont {
+    /// Rasterize a glyph, using the color bitmap if the font has one, like
+    /// most emoji fonts.
+    pub(crate) fn rasterize(&self, character: char, font_size: f32) -> Glyph {
+        if let Some(glyph) = self.rasterize_color(character, font_size) {
+            return glyph;
+        }
+        let (metrics, coverage) = self.font.rasterize(character, font_size);
+        Glyph {
+            metrics,
+            coverage,
+            color: None,
+        }
+    }
+
+    fn rasterize_color(&self, character: char, font_size: f32) -> Option<Glyph> {
+        let face = Face::parse(&self.data, self.index).ok()?;
+        let id = face.glyph_index(character)?;
+        let image = face.glyph_raster_image(id, font_size.round() as u16)?;
+        if image.format != RasterImageFormat::PNG || image.pixels_per_em == 0 {
+            return None;
+        }
+        let bitmap = decode(image.data).ok()?;
+
+        // Scale the strike to the font size
+        let scale = font_size / image.pixels_per_em as f32;
+        let width = (bitmap.width() as f32 * scale).round().max(1.0) as u32;
+        let height = (bitmap.height() as f32 * scale).round().max(1.0) as u32;
+        let mut pixmap = Pixmap::new(width, height)?;
+        let paint = PixmapPaint {
+            quality: FilterQuality::Bilinear,
+            ..Default::default()
+        };
+        pixmap.draw_pixmap(
+            0,
+            0,
+            bitmap.as_ref(),
+            &paint,
+            Transform::from_scale(scale, scale),
+            None,
+        );
+
+        let metrics = Metrics {
+            xmin: (image.x as f32 * scale).round() as i32,
+            ymin: (image.y as f32 * scale).round() as i32,
+            width: width as usize,
+            height: height as usize,
+            advance_width: self.font.metrics(character, font_size).advance_width,
+            ..self.font.metrics(character, font_size)
+        };
+        Some(Glyph {
+            metrics,
+            coverage: vec![],
+            color: Some(pixmap),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct FallbackState {
+    /// The font files that haven't been loaded yet.
+    paths: Vec<PathBuf>,
+    fonts: Vec<Arc<FallbackFont>>,
+    /// The font that each character was found in.
+    characters: HashMap<char, Option<usize>>,
+}
+
+/// A chain of fonts that are searched, in order, for characters that the
+/// bundled font doesn't have, such as CJK, Arabic and emoji.
+///
+/// Fonts are only read from disk when a character is missing, and each
+/// character is only looked up once.
+#[derive(Debug, Default)]
+pub struct FontFallback {
+    state: Mutex<FallbackState>,
+}
+
+impl FontFallback {
+    /// Create a fallback chain from font files, `.ttc` collections are
+    /// supported.
+    pub fn new(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        let state = FallbackState {
+            paths: paths.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        };
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Create a fallback chain from the fonts installed on the system,
+    /// emoji fonts are searched first so that emoji are drawn in color.
+    pub fn system() -> Self {
+        let mut paths = vec![];
+        for directory in system_font_directories() {
+            find_fonts(&directory, &mut paths);
+        }
+        paths.sort_by_key(|path| {
+            let name = path.to_string_lossy().to_lowercase();
+            (!name.contains("emoji"), !name.contains("noto"), name)
+        });
+        log::info!("Found {} system fonts for fallback", paths.len());
+        Self::new(paths)
+    }
+
+    /// Get the first font in the chain that has a character.
+    pub fn font_for(&self, character: char) -> Option<Arc<FallbackFont>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(index) = state.characters.get(&character) {
+            return index.map(|index| Arc::clone(&state.fonts[index]));
+        }
+
+        let index = state
+            .fonts
+            .iter()
+            .position(|font| font.font.has_glyph(character))
+            .or_else(|| state.load_font_with(character));
+        state.characters.insert(character, index);
+        index.map(|index| Arc::clone(&state.fonts[index]))
+    }
+}
+
+impl FallbackState {
+    /// Load the next font file that has a character.
+    fn load_font_with(&mut self, character: char) -> Option<usize> {
+        for (position, path) in self.paths.iter().enumerate() {
+            let Ok(data) = std::fs::read(path) else {
+                continue;
+            };
+            let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+            let index = (0..count).find(|&index| {
+                Face::parse(&data, index).is_ok_and(|face| face.glyph_index(character).is_some())
+            });
+            let Some(index) = index else {
+                continue;
+            };
+
+            let settings = FontSettings {
+                collection_index: index,
+                ..Default::default()
+            };
+            match Font::from_bytes(data.as_slice(), settings) {
+                Ok(font) => {
+                    log::info!("Loaded fallback font {}", path.display());
+                    self.paths.remove(position);
+                    self.fonts
+                        .push(Arc::new(FallbackFont { font, data, index }));
+                    return Some(self.fonts.len() - 1);
+                }
+                Err(err) => log::warn!("Failed to load font {}: {err}", path.display()),
+            }
+        }
+        None
+    }
+}
+
+fn system_font_directories() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut directories = vec![];
+    if cfg!(target_os = "windows") {
+        let windows = std::env::var_os("WINDIR").unwrap_or("C:\\Windows".into());
+        directories.push(PathBuf::from(windows).join("Fonts"));
+    } else if cfg!(target_os = "macos") {
+        directories.push(PathBuf::from("/System/Library/Fonts"));
+        directories.push(PathBuf::from("/Library/Fonts"));
+        directories.extend(home.map(|home| home.join("Library/Fonts")));
+    } else {
+        directories.push(PathBuf::from("/usr/share/fonts"));
+        directories.push(PathBuf::from("/usr/local/share/fonts"));
+        if let Some(home) = home {
+            directories.push(home.join(".local/share/fonts"));
+            directories.push(home.join(".fonts"));
+        }
+    }
+    directories
+}
+
+/// Find the font files in a directory and its subdirectories.
+fn find_fonts(directory: &Path, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_fonts(&path, paths);
+            continue;
+        }
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        if matches!(extension.as_deref(), Some("ttf" | "otf" | "ttc")) {
+            paths.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bundled(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fonts/Inter/static")
+            .join(name)
+    }
+
+    #[test]
+    fn search_fonts_in_order() {
+        let fallback = FontFallback::new([
+            PathBuf::from("missing.ttf"),
+            bundled("Inter-Bold.ttf"),
+            bundled("Inter-Light.ttf"),
+        ]);
+        let font = fallback.font_for('A').unwrap();
+        let bold = std::fs::read(bundled("Inter-Bold.ttf")).unwrap();
+        let bold = Font::from_bytes(bold, FontSettings::default()).unwrap();
+        assert_eq!(font.font.file_hash(), bold.file_hash());
+
+        // The loaded font is reused
+        let other = fallback.font_for('B').unwrap();
+        assert!(Arc::ptr_eq(&font, &other));
+    }
+
+    #[test]
+    fn missing_character() {
+        let fallback = FontFallback::new([bundled("Inter-Bold.ttf")]);
+        assert!(fallback.font_for('\u{10FFFD}').is_none());
+    }
+
+    #[test]
+    fn outline_glyphs_have_no_color() {
+        let fallback = FontFallback::new([bundled("Inter-Bold.ttf")]);
+        let glyph = fallback.font_for('A').unwrap().rasterize('A', 16.0);
+        assert!(glyph.color.is_none());
+        assert!(!glyph.coverage.is_empty());
+    }
+}
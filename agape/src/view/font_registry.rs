@@ -0,0 +1,99 @@
+use crate::style::{FontStyle, FontWeight};
+use crate::{Error, Result};
+use fontdue::{Font, FontSettings};
+use std::sync::Mutex;
+use ttf_parser::{Face, name_id};
+
+/// A font loaded by the app.
+struct RegisteredFont {
+    family: String,
+    weight: FontWeight,
+    style: FontStyle,
+    font: &'static Font,
+}
+
+/// The fonts loaded by the app, they are kept for the rest of the program
+/// since text views borrow them.
+static REGISTRY: Mutex<Vec<RegisteredFont>> = Mutex::new(Vec::new());
+
+/// Load a font and return its family name.
+pub(crate) fn register_font(bytes: Vec<u8>) -> Result<String> {
+    let face = Face::parse(&bytes, 0).map_err(|_| Error::FontError("Invalid font data"))?;
+    let family = family_name(&face).ok_or(Error::FontError("The font has no family name"))?;
+    let weight = match face.weight().to_number() {
+        0..=349 => FontWeight::Light,
+        350..=449 => FontWeight::Regular,
+        450..=549 => FontWeight::Medium,
+        550..=649 => FontWeight::SemiBold,
+        _ => FontWeight::Bold,
+    };
+    let style = match face.is_italic() {
+        true => FontStyle::Italic,
+        false => FontStyle::Normal,
+    };
+
+    let font =
+        Font::from_bytes(bytes.as_slice(), FontSettings::default()).map_err(Error::FontError)?;
+    log::info!("Loaded font {family} {weight:?} {style:?}");
+    REGISTRY.lock().unwrap().push(RegisteredFont {
+        family: family.clone(),
+        weight,
+        style,
+        font: Box::leak(Box::new(font)),
+    });
+    Ok(family)
+}
+
+fn family_name(face: &Face) -> Option<String> {
+    // Prefer the typographic family, which groups all the weights together
+    [name_id::TYPOGRAPHIC_FAMILY, name_id::FAMILY]
+        .into_iter()
+        .find_map(|id| {
+            face.names()
+                .into_iter()
+                .filter(|name| name.name_id == id && name.is_unicode())
+                .find_map(|name| name.to_string())
+        })
+}
+
+/// Find the loaded font of a family that is closest to a weight and style.
+/// Returns the font and whether it has the style, otherwise the style
+/// has to be synthesized.
+pub(crate) fn find_font(
+    family: &str,
+    weight: FontWeight,
+    style: FontStyle,
+) -> Option<(&'static Font, bool)> {
+    let registry = REGISTRY.lock().unwrap();
+    registry
+        .iter()
+        .filter(|font| font.family.eq_ignore_ascii_case(family))
+        .min_by_key(|font| {
+            let weight_distance = (font.weight as i32 - weight as i32).abs();
+            (font.style != style, weight_distance)
+        })
+        .map(|font| (font.font, font.style == style))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn register_and_find() {
+        let bytes = include_bytes!("../../fonts/Inter/static/Inter-SemiBold.ttf");
+        let family = register_font(bytes.to_vec()).unwrap();
+        assert_eq!(family, "Inter");
+
+        let (font, has_style) = find_font("inter", FontWeight::Bold, FontStyle::Italic).unwrap();
+        let expected = Font::from_bytes(bytes.as_slice(), FontSettings::default()).unwrap();
+        assert_eq!(font.file_hash(), expected.file_hash());
+        assert!(!has_style);
+        assert!(find_font("Missing", FontWeight::Regular, FontStyle::Normal).is_none());
+    }
+
+    #[test]
+    fn invalid_font() {
+        assert!(register_font(vec![0, 1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use super::arc::arc_path;
+use super::new_paint;
+use super::{TextView, View, skia_color};
+use crate::Resources;
+use crate::widgets::GaugeRange;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, LineCap, PathBuilder, Pixmap, Stroke, Transform};
+
+/// The angle that the scale starts at, in degrees clockwise from the top.
+pub(crate) const START_ANGLE: f32 = -135.0;
+/// How far around the circle the scale goes.
+pub(crate) const SWEEP_ANGLE: f32 = 270.0;
+
+/// Get the angle of a value on the scale.
+pub(crate) fn value_angle(value: f32, min: f32, max: f32) -> f32 {
+    let fraction = if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    START_ANGLE + fraction * SWEEP_ANGLE
+}
+
+/// Get the point at an angle around the center.
+pub(super) fn point_at(center: Position, radius: f32, angle: f32) -> Position {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    Position::new(center.x + radius * sin, center.y - radius * cos)
+}
+
+pub(super) fn stroke_line(
+    pixmap: &mut Pixmap,
+    from: Position,
+    to: Position,
+    width: f32,
+    color: &Color<Rgba>,
+) {
+    let mut builder = PathBuilder::new();
+    builder.move_to(from.x, from.y);
+    builder.line_to(to.x, to.y);
+    let Some(path) = builder.finish() else {
+        return;
+    };
+
+    let mut paint = new_paint();
+    paint.set_color(skia_color(color));
+    let stroke = Stroke {
+        width,
+        line_cap: LineCap::Round,
+        ..Default::default()
+    };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+}
+
+pub(super) fn fill_circle(pixmap: &mut Pixmap, center: Position, radius: f32, color: &Color<Rgba>) {
+    let Some(path) = PathBuilder::from_circle(center.x, center.y, radius) else {
+        return;
+    };
+    let mut paint = new_paint();
+    paint.set_color(skia_color(color));
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+/// Draws a [`Gauge`](crate::widgets::Gauge).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaugeView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    pub ranges: Vec<GaugeRange>,
+    pub track_color: Color<Rgba>,
+    pub needle_color: Color<Rgba>,
+    pub stroke_width: f32,
+    pub label: Option<String>,
+}
+
+impl GaugeView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            min: 0.0,
+            max: 1.0,
+            value: 0.0,
+            ranges: vec![],
+            track_color: Color::rgb(230, 230, 230),
+            needle_color: Color::BLACK,
+            stroke_width: 8.0,
+            label: None,
+        }
+    }
+
+    fn center(&self) -> Position {
+        Position::new(
+            self.position.x + self.size.width / 2.0,
+            self.position.y + self.size.height / 2.0,
+        )
+    }
+
+    fn radius(&self) -> f32 {
+        (self.size.width.min(self.size.height) - self.stroke_width) / 2.0
+    }
+
+    fn draw_arc(&self, pixmap: &mut Pixmap, from: f32, to: f32, color: &Color<Rgba>) {
+        let start = value_angle(from, self.min, self.max);
+        let end = value_angle(to, self.min, self.max);
+        let Some(path) = arc_path(self.center(), self.radius(), start, end - start) else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        let stroke = Stroke {
+            width: self.stroke_width,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+impl View for GaugeView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.needle_color
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        if self.radius() <= 0.0 {
+            return;
+        }
+
+        self.draw_arc(pixmap, self.min, self.max, &self.track_color);
+        for range in &self.ranges {
+            self.draw_arc(pixmap, range.from, range.to, &range.color);
+        }
+
+        let center = self.center();
+        let angle = value_angle(self.value, self.min, self.max);
+        let tip = point_at(center, self.radius() * 0.8, angle);
+        stroke_line(pixmap, center, tip, 2.0, &self.needle_color);
+        fill_circle(pixmap, center, 4.0, &self.needle_color);
+
+        if let Some(label) = &self.label {
+            let mut text = TextView::new(label);
+            let size = text.text_size();
+            text.set_size(size);
+            text.set_position(Position::new(
+                center.x - size.width / 2.0,
+                center.y + self.radius() * 0.4,
+            ));
+            text.render(pixmap, resources);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn angle_of_values() {
+        assert_eq!(value_angle(0.0, 0.0, 100.0), START_ANGLE);
+        assert_eq!(value_angle(50.0, 0.0, 100.0), 0.0);
+        assert_eq!(value_angle(100.0, 0.0, 100.0), START_ANGLE + SWEEP_ANGLE);
+        assert_eq!(value_angle(200.0, 0.0, 100.0), START_ANGLE + SWEEP_ANGLE);
+    }
+
+    #[test]
+    fn needle_points_at_value() {
+        let mut view = GaugeView::new(GlobalId::new());
+        view.size = Size::unit(100.0);
+        view.min = 0.0;
+        view.max = 100.0;
+        view.value = 50.0;
+        view.track_color = Color::TRANSPARENT;
+
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+
+        // The middle value points straight up
+        assert_ne!(pixmap.pixel(50, 20).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(50, 80).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,230 @@
+use super::fallback::FallbackFont;
+use super::lru::LruCache;
+use agape_core::{Color, Rgba, map};
+use fontdue::{Font, Metrics};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tiny_skia::{Pixmap, PixmapPaint, PremultipliedColorU8, Transform};
+
+/// A rasterized glyph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    pub metrics: Metrics,
+    /// The coverage of each pixel, from `0` to `255`.
+    pub coverage: Vec<u8>,
+    /// The bitmap of a color glyph, such as an emoji, which is drawn
+    /// instead of the coverage.
+    pub color: Option<Pixmap>,
+}
+
+/// The maximum number of glyphs kept in a [`GlyphCache`].
+const GLYPH_CACHE_CAPACITY: usize = 1024;
+
+/// Caches rasterized glyphs so that each character is only rasterized once
+/// for every font and font size.
+///
+/// The cache is stored in the [`Resources`](crate::Resources) and shared by
+/// all the text views. It holds at most 1024 glyphs, the least recently
+/// used glyph is removed when it's full.
+#[derive(Debug)]
+pub struct GlyphCache {
+    glyphs: RefCell<LruCache<(usize, char, u32), Rc<Glyph>>>,
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlyphCache {
+    pub fn new() -> Self {
+        Self::with_capacity(GLYPH_CACHE_CAPACITY)
+    }
+
+    /// Create a cache that holds at most `capacity` glyphs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            glyphs: RefCell::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Get a glyph, rasterizing it if it's not in the cache.
+    pub fn get(&self, font: &Font, character: char, font_size: f32) -> Rc<Glyph> {
+        let key = (font.file_hash(), character, font_size.to_bits());
+        self.glyphs.borrow_mut().get_or_insert_with(key, || {
+            let (metrics, coverage) = font.rasterize(character, font_size);
+            Rc::new(Glyph {
+                metrics,
+                coverage,
+                color: None,
+            })
+        })
+    }
+
+    /// Get a glyph from a fallback font, which may be a color glyph.
+    pub(crate) fn get_fallback(
+        &self,
+        font: &FallbackFont,
+        character: char,
+        font_size: f32,
+    ) -> Rc<Glyph> {
+        let key = (font.font.file_hash(), character, font_size.to_bits());
+        self.glyphs
+            .borrow_mut()
+            .get_or_insert_with(key, || Rc::new(font.rasterize(character, font_size)))
+    }
+
+    /// Get the number of cached glyphs.
+    pub fn len(&self) -> usize {
+        self.glyphs.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all the glyphs.
+    pub fn clear(&self) {
+        self.glyphs.borrow_mut().clear();
+    }
+}
+
+/// Draw a glyph with its top left corner at `(x,y)`, blending it over the
+/// pixels that are already there.
+///
+/// Each row is shifted right by `skew` pixels for every pixel it is above
+/// the bottom of the glyph, which slants it for italic text.
+pub(crate) fn draw_glyph(
+    pixmap: &mut Pixmap,
+    glyph: &Glyph,
+    x: i32,
+    y: i32,
+    color: &Color<Rgba>,
+    skew: f32,
+) {
+    // Color glyphs keep their own colors
+    if let Some(bitmap) = &glyph.color {
+        pixmap.draw_pixmap(
+            x,
+            y,
+            bitmap.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+        return;
+    }
+
+    let (r, g, b, a) = color.inner();
+    let alpha = map(a as f32, [0.0, 100.0], [0.0, 1.0]);
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.pixels_mut();
+
+    for row in 0..glyph.metrics.height as i32 {
+        let py = y + row;
+        if py < 0 || py >= height {
+            continue;
+        }
+
+        let shift = ((glyph.metrics.height as i32 - row) as f32 * skew).round() as i32;
+        for column in 0..glyph.metrics.width as i32 {
+            let px = x + column + shift;
+            if px < 0 || px >= width {
+                continue;
+            }
+
+            let coverage = glyph.coverage[(row * glyph.metrics.width as i32 + column) as usize];
+            if coverage == 0 {
+                continue;
+            }
+
+            // Blend the premultiplied source over the destination
+            let source_alpha = coverage as f32 / 255.0 * alpha;
+            let inverse = 1.0 - source_alpha;
+            let pixel = &mut pixels[(py * width + px) as usize];
+            let blend = |source: u8, destination: u8| {
+                (source as f32 * source_alpha + destination as f32 * inverse).round() as u8
+            };
+
+            let blended = PremultipliedColorU8::from_rgba(
+                blend(r, pixel.red()),
+                blend(g, pixel.green()),
+                blend(b, pixel.blue()),
+                blend(255, pixel.alpha()),
+            );
+            if let Some(blended) = blended {
+                *pixel = blended;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::view::init_font;
+
+    #[test]
+    fn rasterize_once() {
+        let font = init_font();
+        let cache = GlyphCache::new();
+
+        let first = cache.get(&font, 'a', 16.0);
+        let second = cache.get(&font, 'a', 16.0);
+        assert!(Rc::ptr_eq(&first, &second));
+
+        cache.get(&font, 'a', 24.0);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn blend_glyph() {
+        let glyph = Glyph {
+            metrics: Metrics {
+                width: 2,
+                height: 1,
+                ..Default::default()
+            },
+            coverage: vec![255, 0],
+            color: None,
+        };
+        let mut pixmap = Pixmap::new(4, 4).unwrap();
+        draw_glyph(&mut pixmap, &glyph, 1, 1, &Color::RED, 0.0);
+
+        let pixel = pixmap.pixel(1, 1).unwrap();
+        assert_eq!((pixel.red(), pixel.alpha()), (255, 255));
+        assert_eq!(pixmap.pixel(2, 1).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn clip_glyph_to_pixmap() {
+        let glyph = Glyph {
+            metrics: Metrics {
+                width: 2,
+                height: 2,
+                ..Default::default()
+            },
+            coverage: vec![255; 4],
+            color: None,
+        };
+        let mut pixmap = Pixmap::new(2, 2).unwrap();
+        draw_glyph(&mut pixmap, &glyph, -1, 1, &Color::BLACK, 0.0);
+        assert_eq!(pixmap.pixel(0, 1).unwrap().alpha(), 255);
+    }
+
+    #[test]
+    fn bounded_cache() {
+        let font = init_font();
+        let cache = GlyphCache::with_capacity(2);
+
+        let first = cache.get(&font, 'a', 16.0);
+        cache.get(&font, 'b', 16.0);
+        cache.get(&font, 'c', 16.0);
+        assert_eq!(cache.len(), 2);
+
+        // The oldest glyph was removed, so it's rasterized again
+        let again = cache.get(&font, 'a', 16.0);
+        assert!(!Rc::ptr_eq(&first, &again));
+    }
+}
@@ -0,0 +1,290 @@
+use super::lru::LruCache;
+use super::skia_color;
+use crate::style::{Gradient, GradientKind, GradientStop};
+use agape_core::{Color, Rgba, map};
+use std::cell::RefCell;
+use std::f32::consts::TAU;
+use std::rc::Rc;
+use tiny_skia::{
+    FilterQuality, LinearGradient, Pattern, Pixmap, Point, PremultipliedColorU8, RadialGradient,
+    Shader, SpreadMode, Transform,
+};
+
+/// The maximum number of conic gradients kept in a [`GradientCache`].
+const GRADIENT_CACHE_CAPACITY: usize = 32;
+
+/// Everything that changes how a conic gradient looks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ConicKey {
+    stops: Vec<(u32, Color<Rgba>)>,
+    center: [u32; 2],
+    angle: u32,
+    size: [u32; 2],
+}
+
+/// Caches conic gradients, which are drawn on the cpu, so that they are only
+/// drawn again when the gradient or its size changes.
+#[derive(Debug)]
+pub struct GradientCache {
+    pixmaps: RefCell<LruCache<ConicKey, Option<Rc<Pixmap>>>>,
+}
+
+impl Default for GradientCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GradientCache {
+    pub fn new() -> Self {
+        Self {
+            pixmaps: RefCell::new(LruCache::new(GRADIENT_CACHE_CAPACITY)),
+        }
+    }
+
+    /// Get the number of cached gradients.
+    pub fn len(&self) -> usize {
+        self.pixmaps.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.pixmaps.borrow_mut().clear();
+    }
+}
+
+/// The shader for filling a shape with a [`Gradient`].
+///
+/// `tiny_skia` doesn't support conic gradients so they are drawn to a
+/// pixmap first, then used as a pattern.
+pub(crate) enum GradientFill {
+    Shader(Shader<'static>),
+    Pattern(Rc<Pixmap>, Transform),
+}
+
+impl GradientFill {
+    /// Create a fill that covers the `rect`, conic gradients are reused from
+    /// the cache if there is one.
+    pub(crate) fn new(
+        gradient: &Gradient,
+        rect: tiny_skia::Rect,
+        cache: Option<&GradientCache>,
+    ) -> Option<Self> {
+        let stops: Vec<tiny_skia::GradientStop> = gradient
+            .stops
+            .iter()
+            .map(|stop| tiny_skia::GradientStop::new(stop.offset, skia_color(&stop.color)))
+            .collect();
+
+        let (width, height) = (rect.width(), rect.height());
+        let center = Point::from_xy(rect.x() + width / 2.0, rect.y() + height / 2.0);
+
+        let shader = match gradient.kind {
+            GradientKind::Linear { angle } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                // Project the corners onto the gradient line so that the
+                // first and last stops touch the edges of the rect
+                let half_length = (width / 2.0 * sin).abs() + (height / 2.0 * cos).abs();
+                let (dx, dy) = (sin * half_length, -cos * half_length);
+
+                let start = Point::from_xy(center.x - dx, center.y - dy);
+                let end = Point::from_xy(center.x + dx, center.y + dy);
+                LinearGradient::new(start, end, stops, SpreadMode::Pad, Transform::identity())?
+            }
+            GradientKind::Radial {
+                center: relative_center,
+                radius,
+            } => {
+                let center = Point::from_xy(
+                    rect.x() + width * relative_center.x,
+                    rect.y() + height * relative_center.y,
+                );
+                let radius = radius * width.max(height);
+                RadialGradient::new(
+                    center,
+                    center,
+                    radius,
+                    stops,
+                    SpreadMode::Pad,
+                    Transform::identity(),
+                )?
+            }
+            GradientKind::Conic { center, angle } => {
+                let draw = || conic_pixmap(&gradient.stops, center, angle, width, height);
+                let pixmap = match cache {
+                    Some(cache) => {
+                        let key = ConicKey {
+                            stops: gradient
+                                .stops
+                                .iter()
+                                .map(|stop| (stop.offset.to_bits(), stop.color.clone()))
+                                .collect(),
+                            center: [center.x.to_bits(), center.y.to_bits()],
+                            angle: angle.to_bits(),
+                            size: [width.to_bits(), height.to_bits()],
+                        };
+                        cache
+                            .pixmaps
+                            .borrow_mut()
+                            .get_or_insert_with(key, || draw().map(Rc::new))?
+                    }
+                    None => Rc::new(draw()?),
+                };
+                let transform = Transform::from_translate(rect.x(), rect.y());
+                return Some(Self::Pattern(pixmap, transform));
+            }
+        };
+
+        Some(Self::Shader(shader))
+    }
+
+    pub(crate) fn shader(&self) -> Shader<'_> {
+        match self {
+            Self::Shader(shader) => shader.clone(),
+            Self::Pattern(pixmap, transform) => Pattern::new(
+                Pixmap::as_ref(pixmap),
+                SpreadMode::Pad,
+                FilterQuality::Bilinear,
+                1.0,
+                *transform,
+            ),
+        }
+    }
+}
+
+/// Draw a conic gradient onto a new pixmap.
+fn conic_pixmap(
+    stops: &[GradientStop],
+    center: agape_core::Position,
+    angle: f32,
+    width: f32,
+    height: f32,
+) -> Option<Pixmap> {
+    if stops.is_empty() {
+        return None;
+    }
+
+    let mut pixmap = Pixmap::new(width.ceil() as u32, height.ceil() as u32)?;
+    let pixmap_width = pixmap.width();
+    let (cx, cy) = (center.x * width, center.y * height);
+
+    for (index, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let x = (index as u32 % pixmap_width) as f32 + 0.5 - cx;
+        let y = (index as u32 / pixmap_width) as f32 + 0.5 - cy;
+
+        // Zero degrees points upwards and the angle increases clockwise
+        let sweep = x.atan2(-y) - angle.to_radians();
+        let offset = sweep.rem_euclid(TAU) / TAU;
+
+        let [r, g, b, a] = color_at(stops, offset);
+        let premultiply = |channel: f32| (channel * a).round() as u8;
+        *pixel = PremultipliedColorU8::from_rgba(
+            premultiply(r),
+            premultiply(g),
+            premultiply(b),
+            (a * 255.0).round() as u8,
+        )?;
+    }
+
+    Some(pixmap)
+}
+
+/// Get the color of the gradient at the `offset`, the alpha is
+/// normalized from `0.0` to `1.0`.
+fn color_at(stops: &[GradientStop], offset: f32) -> [f32; 4] {
+    let channels = |stop: &GradientStop| {
+        let (r, g, b, a) = stop.color.inner();
+        let a = map(a as f32, [0.0, 100.0], [0.0, 1.0]);
+        [r as f32, g as f32, b as f32, a]
+    };
+
+    let first = &stops[0];
+    if offset <= first.offset {
+        return channels(first);
+    }
+
+    for pair in stops.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        if offset > end.offset {
+            continue;
+        }
+
+        let range = end.offset - start.offset;
+        let t = if range > 0.0 {
+            (offset - start.offset) / range
+        } else {
+            1.0
+        };
+
+        let (start, end) = (channels(start), channels(end));
+        return std::array::from_fn(|i| start[i] + (end[i] - start[i]) * t);
+    }
+
+    channels(stops.last().unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_core::{Color, Position};
+
+    #[test]
+    fn interpolate_stops() {
+        let gradient = Gradient::linear(0.0)
+            .stop(0.0, Color::BLACK)
+            .stop(1.0, Color::WHITE);
+
+        assert_eq!(color_at(&gradient.stops, 0.0), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(color_at(&gradient.stops, 0.5), [127.5, 127.5, 127.5, 1.0]);
+        assert_eq!(color_at(&gradient.stops, 1.0), [255.0, 255.0, 255.0, 1.0]);
+    }
+
+    #[test]
+    fn conic_sweeps_clockwise() {
+        let stops = Gradient::conic(Position::unit(0.5), 0.0)
+            .stop(0.0, Color::BLACK)
+            .stop(1.0, Color::WHITE)
+            .stops;
+        let pixmap = conic_pixmap(&stops, Position::unit(0.5), 0.0, 100.0, 100.0).unwrap();
+
+        // A quarter of the way around is to the right of the center
+        let right = pixmap.pixel(99, 50).unwrap().red();
+        let bottom = pixmap.pixel(50, 99).unwrap().red();
+        let left = pixmap.pixel(0, 50).unwrap().red();
+        assert!(right < bottom && bottom < left);
+    }
+
+    #[test]
+    fn empty_gradient() {
+        let rect = tiny_skia::Rect::from_xywh(0.0, 0.0, 100.0, 100.0).unwrap();
+        let gradient = Gradient::conic(Position::unit(0.5), 0.0);
+        assert!(GradientFill::new(&gradient, rect, None).is_none());
+    }
+
+    #[test]
+    fn reuse_cached_conic_gradient() {
+        let cache = GradientCache::new();
+        let rect = tiny_skia::Rect::from_xywh(0.0, 0.0, 50.0, 50.0).unwrap();
+        let moved = tiny_skia::Rect::from_xywh(20.0, 20.0, 50.0, 50.0).unwrap();
+        let gradient = Gradient::conic(Position::unit(0.5), 0.0)
+            .stop(0.0, Color::BLACK)
+            .stop(1.0, Color::WHITE);
+
+        GradientFill::new(&gradient, rect, Some(&cache));
+        GradientFill::new(&gradient, moved, Some(&cache));
+        assert_eq!(cache.len(), 1);
+
+        let rotated = Gradient {
+            kind: GradientKind::Conic {
+                center: Position::unit(0.5),
+                angle: 90.0,
+            },
+            ..gradient
+        };
+        GradientFill::new(&rotated, rect, Some(&cache));
+        assert_eq!(cache.len(), 2);
+    }
+}
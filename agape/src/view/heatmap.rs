@@ -0,0 +1,169 @@
+use super::new_paint;
+use super::{View, skia_color};
+use crate::Resources;
+use crate::chart::draw_tooltip;
+use crate::resources::CursorPosition;
+use agape_core::{Bounds, Color, GlobalId, Position, Rgba, Size};
+use std::rc::Rc;
+use tiny_skia::{Pixmap, Rect, Transform};
+
+/// Draws a [`Heatmap`](crate::widgets::Heatmap), with a popover showing
+/// the value of the hovered cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub columns: usize,
+    pub values: Rc<[f32]>,
+    /// The color of the smallest value.
+    pub low: Color<Rgba>,
+    /// The color of the largest value.
+    pub high: Color<Rgba>,
+    /// The range of the values, values outside the range are clamped.
+    pub range: (f32, f32),
+    pub gap: f32,
+}
+
+impl HeatmapView {
+    pub fn new(id: GlobalId, columns: usize, values: Rc<[f32]>) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            columns: columns.max(1),
+            values,
+            low: Color::WHITE,
+            high: Color::BLACK,
+            range: (0.0, 1.0),
+            gap: 1.0,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.values.len().div_ceil(self.columns)
+    }
+
+    /// Get the size of each cell.
+    pub fn cell_size(&self) -> Size {
+        let columns = self.columns as f32;
+        let rows = self.rows().max(1) as f32;
+        Size::new(
+            (self.size.width - self.gap * (columns - 1.0)) / columns,
+            (self.size.height - self.gap * (rows - 1.0)) / rows,
+        )
+    }
+
+    /// Get the bounds of the cell at the `index`.
+    pub fn cell_bounds(&self, index: usize) -> Bounds {
+        let size = self.cell_size();
+        let column = (index % self.columns) as f32;
+        let row = (index / self.columns) as f32;
+        let position = Position::new(
+            self.position.x + column * (size.width + self.gap),
+            self.position.y + row * (size.height + self.gap),
+        );
+        Bounds::new(position, size)
+    }
+
+    /// Get the index of the cell at the `position`.
+    pub fn cell_at(&self, position: Position) -> Option<usize> {
+        (0..self.values.len()).find(|&index| self.cell_bounds(index).within(&position))
+    }
+
+    /// Get the color of a value.
+    pub fn value_color(&self, value: f32) -> Color<Rgba> {
+        let (min, max) = self.range;
+        let t = if max > min {
+            (value - min) / (max - min)
+        } else {
+            0.0
+        };
+        self.low.mix(&self.high, t)
+    }
+}
+
+impl View for HeatmapView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.low
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let size = self.cell_size();
+        let mut paint = new_paint();
+        for (index, &value) in self.values.iter().enumerate() {
+            let bounds = self.cell_bounds(index);
+            let Some(rect) = Rect::from_xywh(bounds.x[0], bounds.y[0], size.width, size.height)
+            else {
+                continue;
+            };
+            paint.set_color(skia_color(&self.value_color(value)));
+            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+
+        let Some(cursor) = resources.get::<CursorPosition>() else {
+            return;
+        };
+        if let Some(index) = self.cell_at(cursor.0) {
+            let cell = self.cell_bounds(index);
+            let anchor = Position::new((cell.x[0] + cell.x[1]) / 2.0, cell.y[0]);
+            let value = format!("{:.2}", self.values[index]);
+            draw_tooltip(pixmap, resources, anchor, &value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn heatmap() -> HeatmapView {
+        let mut view = HeatmapView::new(GlobalId::new(), 3, [0.0, 0.5, 1.0, 1.0].into());
+        view.size = Size::new(32.0, 21.0);
+        view
+    }
+
+    #[test]
+    fn grid_size() {
+        let view = heatmap();
+        assert_eq!(view.rows(), 2);
+        assert_eq!(view.cell_size(), Size::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn find_hovered_cell() {
+        let view = heatmap();
+        assert_eq!(view.cell_at(Position::new(5.0, 5.0)), Some(0));
+        assert_eq!(view.cell_at(Position::new(16.0, 5.0)), Some(1));
+        assert_eq!(view.cell_at(Position::new(5.0, 16.0)), Some(3));
+        // The gap between cells
+        assert_eq!(view.cell_at(Position::new(10.5, 5.0)), None);
+        // The empty cell on the last row
+        assert_eq!(view.cell_at(Position::new(16.0, 16.0)), None);
+    }
+
+    #[test]
+    fn interpolate_value_color() {
+        let view = heatmap();
+        assert_eq!(view.value_color(0.0), Color::WHITE);
+        assert_eq!(view.value_color(1.0), Color::BLACK);
+        assert_eq!(view.value_color(2.0), Color::BLACK);
+        assert_eq!(view.value_color(0.5), Color::rgb(128, 128, 128));
+    }
+}
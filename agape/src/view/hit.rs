@@ -0,0 +1,85 @@
+//! The shapes that views are clicked and hovered in, so the transparent
+//! corners of rounded and round widgets don't receive the cursor.
+use crate::style::CornerRadius;
+use agape_core::{Position, Size};
+
+/// The shape of a view that the cursor hits, filling the view's bounds.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum HitShape {
+    #[default]
+    Rect,
+    RoundedRect(CornerRadius),
+    Ellipse,
+}
+
+impl HitShape {
+    /// Returns `true` if a `point` is inside the shape, when it fills a
+    /// rect at `position` with a `size`.
+    pub fn contains(&self, position: Position, size: Size, point: Position) -> bool {
+        let Size { width, height } = size;
+        let x = point.x - position.x;
+        let y = point.y - position.y;
+        if x < 0.0 || y < 0.0 || x > width || y > height {
+            return false;
+        }
+
+        match self {
+            Self::Rect => true,
+            Self::RoundedRect(radius) => {
+                let r = radius.fit(width, height);
+                let inside_arc = |radius: f32, dx: f32, dy: f32| {
+                    let (dx, dy) = (dx.max(0.0), dy.max(0.0));
+                    dx * dx + dy * dy <= radius * radius
+                };
+                // Only points past the center of a corner's arc, towards the
+                // corner, can be outside of it
+                inside_arc(r.top_left, r.top_left - x, r.top_left - y)
+                    && inside_arc(r.top_right, x - (width - r.top_right), r.top_right - y)
+                    && inside_arc(
+                        r.bottom_right,
+                        x - (width - r.bottom_right),
+                        y - (height - r.bottom_right),
+                    )
+                    && inside_arc(
+                        r.bottom_left,
+                        r.bottom_left - x,
+                        y - (height - r.bottom_left),
+                    )
+            }
+            Self::Ellipse => {
+                let (rx, ry) = (width / 2.0, height / 2.0);
+                if rx <= 0.0 || ry <= 0.0 {
+                    return false;
+                }
+                let dx = (x - rx) / rx;
+                let dy = (y - ry) / ry;
+                dx * dx + dy * dy <= 1.0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rounded_corners_miss() {
+        let shape = HitShape::RoundedRect(CornerRadius::from(20.0));
+        let (position, size) = (Position::new(10.0, 10.0), Size::new(100.0, 50.0));
+        assert!(!shape.contains(position, size, Position::new(12.0, 12.0)));
+        assert!(!shape.contains(position, size, Position::new(108.0, 58.0)));
+        assert!(shape.contains(position, size, Position::new(30.0, 30.0)));
+        // The straight edges are still hit
+        assert!(shape.contains(position, size, Position::new(60.0, 11.0)));
+    }
+
+    #[test]
+    fn ellipse_corners_miss() {
+        let shape = HitShape::Ellipse;
+        let size = Size::unit(40.0);
+        assert!(shape.contains(Position::default(), size, Position::new(20.0, 20.0)));
+        assert!(shape.contains(Position::default(), size, Position::new(20.0, 1.0)));
+        assert!(!shape.contains(Position::default(), size, Position::new(3.0, 3.0)));
+    }
+}
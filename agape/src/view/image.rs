@@ -0,0 +1,308 @@
+use super::{View, new_paint};
+use crate::Resources;
+use crate::image_cache::{ImageCache, ImageHandle};
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use std::rc::Rc;
+use tiny_skia::{
+    FilterQuality, IntRect, Paint, Pattern, Pixmap, PixmapPaint, SpreadMode, Transform,
+};
+
+/// Splits an image into nine parts, so that it can be scaled without
+/// distorting its corners. The corners keep their size, while the edges and
+/// the center are stretched, or tiled, to fill the rest.
+///
+/// # Example
+/// ```
+/// use agape::view::NineSlice;
+///
+/// // The borders of the image are 8 pixels wide
+/// let slice = NineSlice::new(8.0).tile();
+/// assert_eq!(slice.left, 8.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NineSlice {
+    /// The insets of the corners, in image pixels.
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+    /// Repeat the edges and the center instead of stretching them.
+    pub tiled: bool,
+}
+
+impl NineSlice {
+    /// Create a [`NineSlice`] with the same inset on every side.
+    pub fn new(inset: f32) -> Self {
+        Self::insets(inset, inset, inset, inset)
+    }
+
+    pub fn insets(top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+            tiled: false,
+        }
+    }
+
+    /// Tile the edges and the center instead of stretching them.
+    pub fn tile(mut self) -> Self {
+        self.tiled = true;
+        self
+    }
+}
+
+/// Draws an image from the [`ImageCache`], scaled to fit the view's size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub handle: ImageHandle,
+    /// The encoded image, used to load the image if it's not in the cache.
+    pub bytes: Rc<[u8]>,
+    pub nine_slice: Option<NineSlice>,
+}
+
+impl ImageView {
+    pub fn new(id: GlobalId, handle: ImageHandle, bytes: Rc<[u8]>) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            handle,
+            bytes,
+            nine_slice: None,
+        }
+    }
+
+    /// Draw the nine parts of a sliced image, the corners are scaled down
+    /// if the view is smaller than them.
+    fn draw_nine_slice(&self, pixmap: &mut Pixmap, image: &Pixmap, slice: &NineSlice) {
+        let (width, height) = (image.width() as f32, image.height() as f32);
+        let left = slice.left.clamp(0.0, width);
+        let right = slice.right.clamp(0.0, width - left);
+        let top = slice.top.clamp(0.0, height);
+        let bottom = slice.bottom.clamp(0.0, height - top);
+
+        let scale = 1.0_f32
+            .min(self.size.width / (left + right).max(f32::EPSILON))
+            .min(self.size.height / (top + bottom).max(f32::EPSILON));
+        let Position { x, y } = self.position;
+
+        // The source and destination edges of the columns and rows
+        let columns = [
+            (0.0, x),
+            (left, x + left * scale),
+            (width - right, x + self.size.width - right * scale),
+            (width, x + self.size.width),
+        ];
+        let rows = [
+            (0.0, y),
+            (top, y + top * scale),
+            (height - bottom, y + self.size.height - bottom * scale),
+            (height, y + self.size.height),
+        ];
+
+        for row in 0..3 {
+            for column in 0..3 {
+                let (source_left, dest_left) = columns[column];
+                let (source_right, dest_right) = columns[column + 1];
+                let (source_top, dest_top) = rows[row];
+                let (source_bottom, dest_bottom) = rows[row + 1];
+                let source = (
+                    source_left,
+                    source_top,
+                    source_right - source_left,
+                    source_bottom - source_top,
+                );
+                let Some(dest) =
+                    tiny_skia::Rect::from_ltrb(dest_left, dest_top, dest_right, dest_bottom)
+                else {
+                    continue;
+                };
+                let corner = row != 1 && column != 1;
+                match slice.tiled && !corner {
+                    true => self.tile_part(pixmap, image, source, dest, scale),
+                    false => self.stretch_part(pixmap, image, source, dest),
+                }
+            }
+        }
+    }
+
+    /// Stretch part of the image, given as `(x, y, width, height)`, over a
+    /// rect.
+    fn stretch_part(
+        &self,
+        pixmap: &mut Pixmap,
+        image: &Pixmap,
+        (x, y, width, height): (f32, f32, f32, f32),
+        dest: tiny_skia::Rect,
+    ) {
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        let scale_x = dest.width() / width;
+        let scale_y = dest.height() / height;
+        let transform = Transform::from_scale(scale_x, scale_y)
+            .post_translate(dest.x() - x * scale_x, dest.y() - y * scale_y);
+        let paint = Paint {
+            shader: Pattern::new(
+                image.as_ref(),
+                SpreadMode::Pad,
+                FilterQuality::Bilinear,
+                1.0,
+                transform,
+            ),
+            ..new_paint()
+        };
+        pixmap.fill_rect(dest, &paint, Transform::identity(), None);
+    }
+
+    /// Repeat part of the image, given as `(x, y, width, height)`, over a
+    /// rect.
+    fn tile_part(
+        &self,
+        pixmap: &mut Pixmap,
+        image: &Pixmap,
+        (x, y, width, height): (f32, f32, f32, f32),
+        dest: tiny_skia::Rect,
+        scale: f32,
+    ) {
+        let rect = IntRect::from_xywh(x as i32, y as i32, width as u32, height as u32);
+        let Some(part) = rect.and_then(|rect| image.clone_rect(rect)) else {
+            return;
+        };
+        let transform = Transform::from_scale(scale, scale).post_translate(dest.x(), dest.y());
+        let paint = Paint {
+            shader: Pattern::new(
+                part.as_ref(),
+                SpreadMode::Repeat,
+                FilterQuality::Bilinear,
+                1.0,
+                transform,
+            ),
+            ..new_paint()
+        };
+        pixmap.fill_rect(dest, &paint, Transform::identity(), None);
+    }
+}
+
+impl View for ImageView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &Color::TRANSPARENT
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let Some(cache) = resources.get::<ImageCache>() else {
+            return;
+        };
+
+        let image = match cache.get(self.handle) {
+            Some(image) => image,
+            // Don't try to decode broken images every frame
+            None if cache.has_failed(self.handle) => return,
+            // Rendered images don't have bytes to load them from
+            None if self.bytes.is_empty() => return,
+            None => {
+                if let Err(err) = cache.load(&self.bytes) {
+                    log::error!("Failed to load image: {err}");
+                    return;
+                }
+                // Images bigger than the whole cache are kept until the
+                // next load
+                let Some(image) = cache.get(self.handle) else {
+                    return;
+                };
+                image
+            }
+        };
+
+        if let Some(slice) = &self.nine_slice {
+            self.draw_nine_slice(pixmap, &image, slice);
+            return;
+        }
+
+        let scale_x = self.size.width / image.width() as f32;
+        let scale_y = self.size.height / image.height() as f32;
+        let transform = Transform::from_scale(scale_x, scale_y)
+            .post_translate(self.position.x, self.position.y);
+        let paint = PixmapPaint {
+            quality: FilterQuality::Bilinear,
+            ..Default::default()
+        };
+
+        pixmap.draw_pixmap(0, 0, image.as_ref().as_ref(), &paint, transform, None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A 3x3 image with a different shade of red in each pixel.
+    fn grid(cache: &ImageCache) -> ImageHandle {
+        let mut image = Pixmap::new(3, 3).unwrap();
+        for (i, pixel) in image.pixels_mut().iter_mut().enumerate() {
+            let red = (i as u8 + 1) * 25;
+            *pixel = tiny_skia::PremultipliedColorU8::from_rgba(red, 0, 0, 255).unwrap();
+        }
+        cache.insert(image)
+    }
+
+    fn render(slice: NineSlice) -> Pixmap {
+        let mut resources = Resources::new();
+        resources.insert(ImageCache::default());
+        let handle = grid(resources.get().unwrap());
+
+        let mut view = ImageView::new(GlobalId::new(), handle, Rc::from([]));
+        view.nine_slice = Some(slice);
+        view.set_size(Size::unit(30.0));
+        let mut pixmap = Pixmap::new(30, 30).unwrap();
+        view.render(&mut pixmap, &resources);
+        pixmap
+    }
+
+    #[test]
+    fn keep_corners() {
+        let pixmap = render(NineSlice::new(1.0));
+        let red = |x, y| pixmap.pixel(x, y).unwrap().red();
+
+        assert_eq!(red(0, 0), 25);
+        assert_eq!(red(29, 0), 75);
+        assert_eq!(red(0, 29), 175);
+        assert_eq!(red(29, 29), 225);
+        // The edges and the center are stretched, with some filtering
+        assert!(red(15, 0).abs_diff(50) <= 2);
+        assert!(red(0, 15).abs_diff(100) <= 2);
+        assert!(red(15, 15).abs_diff(125) <= 2);
+    }
+
+    #[test]
+    fn tile_edges() {
+        let pixmap = render(NineSlice::new(1.0).tile());
+        let red = |x, y| pixmap.pixel(x, y).unwrap().red();
+
+        assert_eq!(red(0, 0), 25);
+        assert_eq!(red(12, 0), 50);
+        assert_eq!(red(15, 15), 125);
+        assert_eq!(red(29, 29), 225);
+    }
+}
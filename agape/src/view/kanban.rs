@@ -0,0 +1,302 @@
+use super::new_paint;
+use super::rect::rounded_rect_path;
+use super::{TextView, View, skia_color};
+use crate::Resources;
+use crate::widgets::KanbanColumn;
+use agape_core::{Bounds, Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, Pixmap, Rect, Stroke, Transform};
+
+/// The height of the title above each column's cards.
+pub(crate) const COLUMN_HEADER: f32 = 32.0;
+/// The width of a collapsed column.
+pub(crate) const COLLAPSED_WIDTH: f32 = 36.0;
+const COLUMN_GAP: f32 = 12.0;
+const CARD_GAP: f32 = 8.0;
+const PADDING: f32 = 8.0;
+const COLUMN_COLOR: Color<Rgba> = Color::rgb(240, 240, 240);
+const CARD_COLOR: Color<Rgba> = Color::WHITE;
+const CARD_BORDER: Color<Rgba> = Color::rgb(210, 210, 210);
+const INDICATOR_COLOR: Color<Rgba> = Color::rgb(59, 130, 246);
+const OVER_LIMIT_COLOR: Color<Rgba> = Color::rgb(220, 38, 38);
+
+/// The sizes used to place the columns and cards of a kanban board,
+/// relative to its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct KanbanMetrics {
+    pub column_width: f32,
+    pub card_height: f32,
+}
+
+impl KanbanMetrics {
+    fn width_of(&self, column: &KanbanColumn) -> f32 {
+        if column.collapsed {
+            COLLAPSED_WIDTH
+        } else {
+            self.column_width
+        }
+    }
+
+    /// Get the bounds of a column.
+    pub fn column_bounds(&self, columns: &[KanbanColumn], index: usize, height: f32) -> Bounds {
+        let x = columns[..index]
+            .iter()
+            .map(|column| self.width_of(column) + COLUMN_GAP)
+            .sum::<f32>();
+        Bounds {
+            x: [x, x + self.width_of(&columns[index])],
+            y: [0.0, height],
+        }
+    }
+
+    /// Get the bounds of a card in a column.
+    pub fn card_bounds(&self, columns: &[KanbanColumn], column: usize, index: usize) -> Bounds {
+        let column_bounds = self.column_bounds(columns, column, 0.0);
+        let top = COLUMN_HEADER + index as f32 * (self.card_height + CARD_GAP);
+        Bounds {
+            x: [column_bounds.x[0] + PADDING, column_bounds.x[1] - PADDING],
+            y: [top, top + self.card_height],
+        }
+    }
+
+    /// Find the column under an x coordinate.
+    pub fn column_at(&self, columns: &[KanbanColumn], x: f32) -> Option<usize> {
+        (0..columns.len()).find(|&index| {
+            let bounds = self.column_bounds(columns, index, 0.0);
+            x >= bounds.x[0] && x <= bounds.x[1]
+        })
+    }
+
+    /// Get the index a card dropped at `y` would be inserted at, in a column
+    /// with `count` cards.
+    pub fn slot_at(&self, y: f32, count: usize) -> usize {
+        // The card goes before the first card whose middle is below the cursor
+        let first_middle = COLUMN_HEADER + self.card_height / 2.0;
+        let slot = ((y - first_middle) / (self.card_height + CARD_GAP)).ceil();
+        slot.clamp(0.0, count as f32) as usize
+    }
+
+    pub fn width(&self, columns: &[KanbanColumn]) -> f32 {
+        let width = columns
+            .iter()
+            .map(|column| self.width_of(column) + COLUMN_GAP)
+            .sum::<f32>();
+        (width - COLUMN_GAP).max(0.0)
+    }
+
+    /// Get the height of the board, with room to drop one more card below the
+    /// longest column.
+    pub fn height(&self, columns: &[KanbanColumn]) -> f32 {
+        let cards = columns
+            .iter()
+            .map(|column| column.cards.len())
+            .max()
+            .unwrap_or(0);
+        COLUMN_HEADER + (cards + 1) as f32 * (self.card_height + CARD_GAP) + PADDING
+    }
+}
+
+/// A card being dragged across a kanban board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DraggedCard {
+    /// The column and index of the card.
+    pub card: (usize, usize),
+    /// The top left corner of the card, relative to the board.
+    pub position: Position,
+    /// Where the card would be dropped, if it can be dropped.
+    pub target: Option<(usize, usize)>,
+}
+
+/// Draws a [`KanbanBoard`](crate::widgets::KanbanBoard).
+#[derive(Debug, Clone)]
+pub struct KanbanView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub columns: Vec<KanbanColumn>,
+    pub column_width: f32,
+    pub card_height: f32,
+    pub dragged: Option<DraggedCard>,
+}
+
+impl KanbanView {
+    pub(crate) fn metrics(&self) -> KanbanMetrics {
+        KanbanMetrics {
+            column_width: self.column_width,
+            card_height: self.card_height,
+        }
+    }
+
+    /// Get the bounds on screen of bounds relative to the board.
+    fn screen_rect(&self, bounds: Bounds) -> Option<Rect> {
+        Rect::from_ltrb(
+            self.position.x + bounds.x[0],
+            self.position.y + bounds.y[0],
+            self.position.x + bounds.x[1],
+            self.position.y + bounds.y[1],
+        )
+    }
+
+    fn draw_column(&self, pixmap: &mut Pixmap, resources: &Resources, index: usize) {
+        let metrics = self.metrics();
+        let column = &self.columns[index];
+        let bounds = metrics.column_bounds(&self.columns, index, self.size.height);
+        fill_rounded_rect(pixmap, self.screen_rect(bounds), 6.0, &COLUMN_COLOR);
+
+        // Collapsed columns only show how many cards they have
+        let title = match (column.collapsed, column.wip_limit) {
+            (true, _) => column.cards.len().to_string(),
+            (false, Some(limit)) => format!("{} {}/{limit}", column.title, column.cards.len()),
+            (false, None) => format!("{} {}", column.title, column.cards.len()),
+        };
+        let mut text = TextView::new(&title);
+        if column.is_over_limit() {
+            text.set_foreground_color(OVER_LIMIT_COLOR);
+        }
+        let size = text.text_size();
+        text.set_size(size);
+        text.set_position(Position::new(
+            self.position.x + bounds.x[0] + PADDING,
+            self.position.y + COLUMN_HEADER / 2.0 - size.height / 2.0,
+        ));
+        text.render(pixmap, resources);
+
+        if column.collapsed {
+            return;
+        }
+
+        for (card_index, card) in column.cards.iter().enumerate() {
+            let bounds = metrics.card_bounds(&self.columns, index, card_index);
+            // The card being dragged is drawn faded in its old place
+            let faded = self
+                .dragged
+                .as_ref()
+                .is_some_and(|dragged| dragged.card == (index, card_index));
+            self.draw_card(pixmap, resources, bounds, &card.title, faded);
+        }
+    }
+
+    fn draw_card(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        bounds: Bounds,
+        title: &str,
+        faded: bool,
+    ) {
+        let Some(rect) = self.screen_rect(bounds) else {
+            return;
+        };
+        let Some(path) = rounded_rect_path(rect, 4.0) else {
+            return;
+        };
+        let opacity = if faded { 0.4 } else { 1.0 };
+        let mut paint = new_paint();
+        paint.set_color(faded_color(&CARD_COLOR, opacity));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+        paint.set_color(faded_color(&CARD_BORDER, opacity));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+
+        let mut text = TextView::new(title);
+        let size = text.text_size();
+        text.set_size(size);
+        text.set_position(Position::new(
+            rect.left() + PADDING,
+            rect.top() + rect.height() / 2.0 - size.height / 2.0,
+        ));
+        text.render(pixmap, resources);
+    }
+
+    fn draw_dragged(&self, pixmap: &mut Pixmap, resources: &Resources, dragged: &DraggedCard) {
+        let metrics = self.metrics();
+        let (column, index) = dragged.card;
+        let Some(card) = self.columns[column].cards.get(index) else {
+            return;
+        };
+
+        if let Some((target_column, slot)) = dragged.target {
+            let target = &self.columns[target_column];
+            if !target.collapsed {
+                // Show a line in the gap the card will be dropped into
+                let card_bounds = metrics.card_bounds(&self.columns, target_column, slot);
+                let y = card_bounds.y[0] - CARD_GAP / 2.0;
+                let bounds = Bounds {
+                    x: card_bounds.x,
+                    y: [y - 1.0, y + 1.0],
+                };
+                fill_rounded_rect(pixmap, self.screen_rect(bounds), 0.0, &INDICATOR_COLOR);
+            }
+        }
+
+        let home = metrics.card_bounds(&self.columns, column, index);
+        let width = home.x[1] - home.x[0];
+        let bounds = Bounds {
+            x: [dragged.position.x, dragged.position.x + width],
+            y: [dragged.position.y, dragged.position.y + metrics.card_height],
+        };
+        self.draw_card(pixmap, resources, bounds, &card.title, false);
+    }
+}
+
+fn faded_color(color: &Color<Rgba>, opacity: f32) -> tiny_skia::Color {
+    let mut color = skia_color(color);
+    color.apply_opacity(opacity);
+    color
+}
+
+fn fill_rounded_rect(pixmap: &mut Pixmap, rect: Option<Rect>, radius: f32, color: &Color<Rgba>) {
+    let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, radius)) else {
+        return;
+    };
+    let mut paint = new_paint();
+    paint.set_color(skia_color(color));
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+impl View for KanbanView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &COLUMN_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        for index in 0..self.columns.len() {
+            self.draw_column(pixmap, resources, index);
+        }
+        if let Some(dragged) = &self.dragged {
+            self.draw_dragged(pixmap, resources, dragged);
+        }
+    }
+}
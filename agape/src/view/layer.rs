@@ -0,0 +1,72 @@
+//! Widgets with a group opacity, blur or transform are drawn to a layer the
+//! size of the window, layers are pooled so they aren't allocated every frame.
+use std::cell::RefCell;
+use tiny_skia::Pixmap;
+
+/// The maximum number of unused layers kept in a [`LayerPool`].
+const LAYER_POOL_CAPACITY: usize = 4;
+
+/// Reuses the pixmaps of layers across frames.
+#[derive(Debug, Default)]
+pub struct LayerPool {
+    layers: RefCell<Vec<Pixmap>>,
+}
+
+impl LayerPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a transparent layer, layers of a different size, from before the
+    /// window was resized, are dropped.
+    pub fn take(&self, width: u32, height: u32) -> Option<Pixmap> {
+        let mut layers = self.layers.borrow_mut();
+        layers.retain(|layer| layer.width() == width && layer.height() == height);
+        match layers.pop() {
+            Some(mut layer) => {
+                layer.fill(tiny_skia::Color::TRANSPARENT);
+                Some(layer)
+            }
+            None => Pixmap::new(width, height),
+        }
+    }
+
+    /// Return a layer to the pool once it's drawn.
+    pub fn give_back(&self, layer: Pixmap) {
+        let mut layers = self.layers.borrow_mut();
+        if layers.len() < LAYER_POOL_CAPACITY {
+            layers.push(layer);
+        }
+    }
+
+    /// Get the number of unused layers.
+    pub fn len(&self) -> usize {
+        self.layers.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reuse_layers() {
+        let pool = LayerPool::new();
+        let mut layer = pool.take(20, 10).unwrap();
+        layer.fill(tiny_skia::Color::BLACK);
+        let data = layer.data().as_ptr();
+        pool.give_back(layer);
+
+        let layer = pool.take(20, 10).unwrap();
+        assert_eq!(layer.data().as_ptr(), data);
+        assert!(layer.pixels().iter().all(|pixel| pixel.alpha() == 0));
+        pool.give_back(layer);
+
+        pool.take(40, 10).unwrap();
+        assert!(pool.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use super::new_paint;
+use super::stroke::skia_stroke;
+use super::{View, skia_color};
+use crate::Resources;
+use crate::style::StrokeStyle;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{Path, PathBuilder, Pixmap, Transform};
+
+/// Draws a line through a list of points, the points are relative to the
+/// view's position.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LineView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub points: Vec<Position>,
+    pub stroke: StrokeStyle,
+    /// Connect the last point back to the first point.
+    pub closed: bool,
+}
+
+impl LineView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn path(&self) -> Option<Path> {
+        let mut builder = PathBuilder::new();
+        for (index, point) in self.points.iter().enumerate() {
+            let x = self.position.x + point.x;
+            let y = self.position.y + point.y;
+            if index == 0 {
+                builder.move_to(x, y);
+            } else {
+                builder.line_to(x, y);
+            }
+        }
+
+        if self.closed {
+            builder.close();
+        }
+        builder.finish()
+    }
+}
+
+impl View for LineView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.stroke.color
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        let Some(path) = self.path() else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.stroke.color));
+        let stroke = skia_stroke(&self.stroke);
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn render(view: &LineView) -> Pixmap {
+        let mut pixmap = Pixmap::new(100, 20).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+        pixmap
+    }
+
+    #[test]
+    fn draw_dashed_line() {
+        let mut view = LineView::new(GlobalId::new());
+        view.points = vec![Position::new(0.0, 10.0), Position::new(100.0, 10.0)];
+        view.stroke = StrokeStyle::new(4.0, Color::BLACK).dash(vec![10.0, 10.0], 0.0);
+
+        let pixmap = render(&view);
+        assert_ne!(pixmap.pixel(5, 10).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(15, 10).unwrap().alpha(), 0);
+        assert_ne!(pixmap.pixel(25, 10).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn draw_relative_to_position() {
+        let mut view = LineView::new(GlobalId::new());
+        view.position = Position::new(50.0, 0.0);
+        view.points = vec![Position::new(0.0, 10.0), Position::new(40.0, 10.0)];
+        view.stroke = StrokeStyle::new(4.0, Color::BLACK);
+
+        let pixmap = render(&view);
+        assert_eq!(pixmap.pixel(25, 10).unwrap().alpha(), 0);
+        assert_ne!(pixmap.pixel(75, 10).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn single_point() {
+        let mut view = LineView::new(GlobalId::new());
+        view.points = vec![Position::new(0.0, 10.0)];
+        assert!(view.path().is_none());
+    }
+}
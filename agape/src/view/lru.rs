@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cache that holds at most `capacity` entries, evicting the least
+/// recently used entry when it's full.
+#[derive(Debug)]
+pub(crate) struct LruCache<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    capacity: usize,
+    /// Increases every time an entry is used.
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            tick: 0,
+        }
+    }
+
+    /// Get an entry, or insert the value returned by `f` if there isn't one.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> V {
+        self.tick += 1;
+        if let Some((value, used)) = self.entries.get_mut(&key) {
+            *used = self.tick;
+            return value.clone();
+        }
+
+        if self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        let value = f();
+        self.entries.insert(key, (value.clone(), self.tick));
+        value
+    }
+
+    /// Remove the least recently used entry.
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (_, used))| *used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn evict_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.get_or_insert_with(1, || "one");
+        cache.get_or_insert_with(2, || "two");
+        // Use the first entry so that the second one is evicted
+        cache.get_or_insert_with(1, || "new one");
+        cache.get_or_insert_with(3, || "three");
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_or_insert_with(1, || "new one"), "one");
+        assert_eq!(cache.get_or_insert_with(2, || "new two"), "new two");
+    }
+}
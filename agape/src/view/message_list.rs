@@ -0,0 +1,161 @@
+use super::new_paint;
+use super::rect::rounded_rect_path;
+use super::{TextView, View, skia_color};
+use crate::Resources;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, PathBuilder, Pixmap, PixmapPaint, Stroke, Transform};
+
+const SEPARATOR_COLOR: Color<Rgba> = Color::rgb(160, 160, 160);
+const PILL_COLOR: Color<Rgba> = Color::rgb(59, 130, 246);
+
+/// A row in a [`MessageListView`], positioned relative to the top of the
+/// list.
+pub enum MessageRow {
+    /// A message, with the views of the widget built for it.
+    Message { y: f32, views: Vec<Box<dyn View>> },
+    /// A label between messages from different days.
+    Separator { y: f32, height: f32, label: String },
+}
+
+/// Draws a [`MessageList`](crate::widgets::MessageList).
+pub struct MessageListView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    /// The visible rows.
+    pub rows: Vec<MessageRow>,
+    /// The "new messages" pill and its bounds, relative to the list.
+    pub pill: Option<(String, tiny_skia::Rect)>,
+}
+
+impl MessageListView {
+    fn draw_separator(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        y: f32,
+        height: f32,
+        label: &str,
+    ) {
+        let mut text = TextView::new(label);
+        text.font_size = 12;
+        text.set_foreground_color(SEPARATOR_COLOR);
+        let size = text.text_size();
+        let center = self.size.width / 2.0;
+        let middle = y + height / 2.0;
+        text.set_size(size);
+        text.set_position(Position::new(
+            center - size.width / 2.0,
+            middle - size.height / 2.0,
+        ));
+        text.render(pixmap, resources);
+
+        // Lines on either side of the label
+        let gap = size.width / 2.0 + 8.0;
+        let mut builder = PathBuilder::new();
+        builder.move_to(16.0, middle);
+        builder.line_to(center - gap, middle);
+        builder.move_to(center + gap, middle);
+        builder.line_to(self.size.width - 16.0, middle);
+        let Some(path) = builder.finish() else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&SEPARATOR_COLOR));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn draw_pill(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        label: &str,
+        rect: tiny_skia::Rect,
+    ) {
+        let Some(path) = rounded_rect_path(rect, rect.height() / 2.0) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&PILL_COLOR));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+
+        let mut text = TextView::new(label);
+        text.font_size = 12;
+        text.set_foreground_color(Color::WHITE);
+        let size = text.text_size();
+        text.set_size(size);
+        text.set_position(Position::new(
+            rect.left() + rect.width() / 2.0 - size.width / 2.0,
+            rect.top() + rect.height() / 2.0 - size.height / 2.0,
+        ));
+        text.render(pixmap, resources);
+    }
+}
+
+impl View for MessageListView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &SEPARATOR_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        // Draw the rows onto their own pixmap so that they are clipped to
+        // the list
+        let Some(mut list) = Pixmap::new(self.size.width as u32, self.size.height as u32) else {
+            return;
+        };
+
+        for row in &self.rows {
+            match row {
+                MessageRow::Message { views, .. } => {
+                    for view in views {
+                        view.render(&mut list, resources);
+                    }
+                }
+                MessageRow::Separator { y, height, label } => {
+                    self.draw_separator(&mut list, resources, *y, *height, label);
+                }
+            }
+        }
+
+        if let Some((label, rect)) = &self.pill {
+            self.draw_pill(&mut list, resources, label, *rect);
+        }
+
+        pixmap.draw_pixmap(
+            self.position.x as i32,
+            self.position.y as i32,
+            list.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+}
@@ -3,14 +3,86 @@
 //! Rendering is done using `tiny_skia`.
 //!
 
-use agape_core::{Color, GlobalId, Position, Rgba, Size};
-use tiny_skia::Pixmap;
+use agape_core::{Color, GlobalId, Position, Rgba, Size, map};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tiny_skia::{Paint, Pixmap};
+mod arc;
+mod blur;
+mod dial;
+mod display_list;
+mod ellipse;
+mod emoji_picker;
+mod fallback;
+mod font_registry;
+mod gauge;
+mod glyph;
+mod gradient;
+mod heatmap;
+mod hit;
+mod image;
+mod kanban;
+mod layer;
+mod line;
+mod lru;
+mod message_list;
+mod path;
+mod pie;
+mod placed_text;
+mod print_preview;
 mod rect;
+mod rich_text;
+mod rich_text_editor;
+mod shadow;
+mod sparkline;
+mod stroke;
+mod svg;
 mod text;
+mod text_field;
+mod timeline;
 
 use crate::Resources;
+pub use arc::ArcView;
+pub(crate) use blur::blur_region;
+pub use dial::DialView;
+pub use display_list::{DisplayList, DrawCommand, Layer};
+pub use ellipse::EllipseView;
+pub use emoji_picker::EmojiPickerView;
+pub(crate) use emoji_picker::{EMOJI_CELL, EMOJI_COLUMNS, EMOJI_ROWS, PICKER_BAR_HEIGHT};
+pub(crate) use fallback::set_font_fallback;
+pub use fallback::{FallbackFont, FontFallback};
+pub(crate) use font_registry::register_font;
+pub use gauge::GaugeView;
+pub use glyph::{Glyph, GlyphCache};
+pub use gradient::GradientCache;
+pub use heatmap::HeatmapView;
+pub use hit::HitShape;
+pub use image::{ImageView, NineSlice};
+pub(crate) use kanban::{COLUMN_HEADER, KanbanMetrics};
+pub use kanban::{DraggedCard, KanbanView};
+pub use layer::LayerPool;
+pub use line::LineView;
+pub use message_list::{MessageListView, MessageRow};
+pub use path::{PathCommand, PathView};
+pub use pie::PieView;
+pub use placed_text::{CharIndex, PlacedText};
+pub(crate) use print_preview::{PREVIEW_TOOLBAR_HEIGHT, THUMBNAIL_GAP, THUMBNAIL_WIDTH};
+pub use print_preview::{PageThumbnail, PreviewToolbarItem, PrintPreviewView};
 pub use rect::RectView;
+pub(crate) use rect::rounded_rect_path;
+pub use rich_text::RichTextView;
+pub(crate) use rich_text_editor::{
+    EDITOR_BUTTON_WIDTH, EDITOR_PADDING, EDITOR_TOOLBAR_HEIGHT, LIST_INDENT, block_font_size,
+    block_height, char_advances, line_segments, object_size,
+};
+pub use rich_text_editor::{EditorLine, EditorSegment, RichTextEditorView};
+pub use shadow::ShadowCache;
+pub(crate) use shadow::draw_shadow;
+pub use sparkline::SparklineView;
+pub(crate) use stroke::skia_stroke;
 pub use text::*;
+pub use text_field::{CompletionPopup, TextFieldView};
+pub use timeline::TimelineView;
+pub(crate) use timeline::{HEADER_HEIGHT, TimelineMetrics};
 
 /// A [`View`] is a primitive object that performs the rendering to the screen.
 pub trait View {
@@ -19,10 +91,52 @@ pub trait View {
     /// Get the view's color
     fn color(&self) -> &Color<Rgba>;
 
+    /// Get the color of the view's content, such as text, if it's drawn
+    /// on top of another view's background.
+    fn foreground_color(&self) -> Option<&Color<Rgba>> {
+        None
+    }
+
     fn set_id(&mut self, id: GlobalId);
     fn set_size(&mut self, size: Size);
     fn set_position(&mut self, position: Position);
 
     /// Render the view to the screen.
     fn render(&self, pixmap: &mut Pixmap, resources: &Resources);
+
+    /// Describe the view as [`DrawCommand`]s, which backends other than
+    /// `tiny_skia` can draw. Views that return `None` are drawn with
+    /// [`View::render`].
+    fn commands(&self) -> Option<Vec<DrawCommand>> {
+        None
+    }
+
+    /// Get the shape that the cursor hits the view in.
+    fn hit_shape(&self) -> HitShape {
+        HitShape::Rect
+    }
+}
+
+static ANTI_ALIASING: AtomicBool = AtomicBool::new(true);
+
+/// Set whether shapes are drawn with smooth edges.
+pub(crate) fn set_anti_aliasing(enabled: bool) {
+    ANTI_ALIASING.store(enabled, Ordering::Relaxed);
+}
+
+/// Create a `tiny_skia` paint, which anti-aliases unless it was turned off
+/// for the app.
+pub(crate) fn new_paint() -> Paint<'static> {
+    Paint {
+        anti_alias: ANTI_ALIASING.load(Ordering::Relaxed),
+        ..Default::default()
+    }
+}
+
+/// Convert a [`Color`] into a `tiny_skia` color.
+pub(crate) fn skia_color(color: &Color<Rgba>) -> tiny_skia::Color {
+    let (r, g, b, a) = color.inner();
+    // Map the alpha since it's clipped to 100
+    let a = map(a as f32, [0.0, 100.0], [0.0, 255.0]) as u8;
+    tiny_skia::Color::from_rgba8(r, g, b, a)
 }
@@ -0,0 +1,154 @@
+use super::new_paint;
+use super::{View, skia_color, skia_stroke};
+use crate::Resources;
+use crate::style::{FillRule, StrokeStyle};
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{PathBuilder, Pixmap, Transform};
+
+/// A single drawing command in a path.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum PathCommand {
+    /// Start a new shape at the point.
+    MoveTo(Position),
+    /// Draw a straight line to the point.
+    LineTo(Position),
+    /// Draw a quadratic curve with one control point.
+    QuadTo(Position, Position),
+    /// Draw a cubic curve with two control points.
+    CubicTo(Position, Position, Position),
+    /// Draw a line back to the start of the shape.
+    Close,
+}
+
+/// Draws a shape made of [`PathCommand`]s, the points are relative to the
+/// view's position.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PathView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub commands: Vec<PathCommand>,
+    pub fill: Color<Rgba>,
+    pub fill_rule: FillRule,
+    pub stroke: Option<StrokeStyle>,
+}
+
+impl PathView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn path(&self) -> Option<tiny_skia::Path> {
+        let (x, y) = (self.position.x, self.position.y);
+        let mut builder = PathBuilder::new();
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(p) => builder.move_to(x + p.x, y + p.y),
+                PathCommand::LineTo(p) => builder.line_to(x + p.x, y + p.y),
+                PathCommand::QuadTo(c, p) => builder.quad_to(x + c.x, y + c.y, x + p.x, y + p.y),
+                PathCommand::CubicTo(c1, c2, p) => {
+                    builder.cubic_to(x + c1.x, y + c1.y, x + c2.x, y + c2.y, x + p.x, y + p.y)
+                }
+                PathCommand::Close => builder.close(),
+            }
+        }
+        builder.finish()
+    }
+}
+
+impl View for PathView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.fill
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        let Some(path) = self.path() else {
+            return;
+        };
+
+        let fill_rule = match self.fill_rule {
+            FillRule::NonZero => tiny_skia::FillRule::Winding,
+            FillRule::EvenOdd => tiny_skia::FillRule::EvenOdd,
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.fill));
+        pixmap.fill_path(&path, &paint, fill_rule, Transform::identity(), None);
+
+        if let Some(stroke) = &self.stroke {
+            paint.set_color(skia_color(&stroke.color));
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &skia_stroke(stroke),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A square with a square hole in the middle.
+    fn frame(fill_rule: FillRule) -> Pixmap {
+        let square = |x: f32, size: f32| {
+            [
+                PathCommand::MoveTo(Position::new(x, x)),
+                PathCommand::LineTo(Position::new(x + size, x)),
+                PathCommand::LineTo(Position::new(x + size, x + size)),
+                PathCommand::LineTo(Position::new(x, x + size)),
+                PathCommand::Close,
+            ]
+        };
+
+        let mut view = PathView::new(GlobalId::new());
+        view.commands = [square(0.0, 30.0), square(10.0, 10.0)].concat();
+        view.fill = Color::BLACK;
+        view.fill_rule = fill_rule;
+
+        let mut pixmap = Pixmap::new(30, 30).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+        pixmap
+    }
+
+    #[test]
+    fn even_odd_fill() {
+        let pixmap = frame(FillRule::EvenOdd);
+        assert_ne!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(15, 15).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn non_zero_fill() {
+        let pixmap = frame(FillRule::NonZero);
+        assert_ne!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+        assert_ne!(pixmap.pixel(15, 15).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn empty_path() {
+        let view = PathView::new(GlobalId::new());
+        assert!(view.path().is_none());
+    }
+}
@@ -0,0 +1,112 @@
+use super::arc::pie_path;
+use super::new_paint;
+use super::{View, skia_color, skia_stroke};
+use crate::Resources;
+use crate::style::StrokeStyle;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, Pixmap, Transform};
+
+/// Draws a slice of a circle, or a segment of a ring if the inner radius
+/// isn't zero.
+///
+/// Angles are in degrees, zero is at the top and angles increase clockwise.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PieView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub fill: Color<Rgba>,
+    pub stroke: Option<StrokeStyle>,
+    /// The angle the slice starts at.
+    pub start: f32,
+    /// How far the slice goes around the circle.
+    pub sweep: f32,
+    /// The radius of the hole in the middle, as a fraction of the radius.
+    pub inner_radius: f32,
+}
+
+impl PieView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
+}
+
+impl View for PieView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.fill
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        let center = Position::new(
+            self.position.x + self.size.width / 2.0,
+            self.position.y + self.size.height / 2.0,
+        );
+        let radius = self.size.width.min(self.size.height) / 2.0;
+        let inner_radius = radius * self.inner_radius.clamp(0.0, 1.0);
+        let Some(path) = pie_path(center, radius, inner_radius, self.start, self.sweep) else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.fill));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+
+        if let Some(stroke) = &self.stroke {
+            paint.set_color(skia_color(&stroke.color));
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &skia_stroke(stroke),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn draw_donut_segment() {
+        let mut view = PieView::new(GlobalId::new());
+        view.size = Size::unit(100.0);
+        view.fill = Color::BLACK;
+        view.sweep = 90.0;
+        view.inner_radius = 0.5;
+
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+
+        // The top right quarter of the ring, without the hole
+        assert_eq!(pixmap.pixel(80, 20).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(55, 45).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(20, 80).unwrap().alpha(), 0);
+    }
+}
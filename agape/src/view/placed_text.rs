@@ -0,0 +1,144 @@
+//! Mapping between points in drawn text and the characters under them.
+//!
+//! # Example
+//! ```no_run
+//! use agape::prelude::*;
+//!
+//! let text = Text::new("Hello world");
+//! let placed = text.placed_text(None);
+//!
+//! // Clicking before the middle of a character puts the caret before it
+//! let index = placed.hit_test(Position::new(1.0, 5.0));
+//! assert_eq!(index, 0);
+//! let caret = placed.caret_rect(text.text.len());
+//! assert_eq!(caret.x[0], placed.width());
+//! ```
+use agape_core::{Bounds, Position, Size};
+use std::ops::Range;
+
+/// The byte index of a character in a string of text, or of the end of
+/// the text.
+pub type CharIndex = usize;
+
+/// The byte index of a character, and the distances from the start of its
+/// line to its left and right edges.
+type CharExtent = (CharIndex, f32, f32);
+
+/// A line of [`PlacedText`].
+#[derive(Debug, Clone, PartialEq)]
+struct PlacedLine {
+    /// The byte range of the line in the text.
+    range: Range<usize>,
+    chars: Vec<CharExtent>,
+    y: f32,
+}
+
+impl PlacedLine {
+    fn width(&self) -> f32 {
+        self.chars.last().map_or(0.0, |(_, _, end)| *end)
+    }
+
+    /// Get the distance from the start of the line to the caret before
+    /// the character at `index`.
+    fn x_of(&self, index: CharIndex) -> f32 {
+        self.chars
+            .iter()
+            .find(|(i, _, _)| *i >= index)
+            .map_or(self.width(), |(_, x, _)| *x)
+    }
+}
+
+/// Text after it's broken into lines and its characters are placed, with
+/// positions relative to the top left of the text. Get it from
+/// [`Text::placed_text`](crate::widgets::Text::placed_text).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedText {
+    lines: Vec<PlacedLine>,
+    line_height: f32,
+}
+
+impl PlacedText {
+    /// Create the placed text from the byte range of each line and the
+    /// byte index and horizontal extent of each of its characters.
+    pub(crate) fn new(lines: Vec<(Range<usize>, Vec<CharExtent>)>, line_height: f32) -> Self {
+        let lines = lines
+            .into_iter()
+            .enumerate()
+            .map(|(row, (range, chars))| PlacedLine {
+                range,
+                chars,
+                y: row as f32 * line_height,
+            })
+            .collect();
+        Self { lines, line_height }
+    }
+
+    /// Get the number of lines.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Get the width of the widest line.
+    pub fn width(&self) -> f32 {
+        self.lines.iter().map(PlacedLine::width).fold(0.0, f32::max)
+    }
+
+    /// Get the size of the text.
+    pub fn size(&self) -> Size {
+        Size::new(self.width(), self.line_height * self.lines.len() as f32)
+    }
+
+    /// Get the index that a caret at `position` goes before, the closest
+    /// character boundary to the position. Points above or below the text
+    /// hit the first or last line.
+    pub fn hit_test(&self, position: Position) -> CharIndex {
+        let Some(line) = self
+            .lines
+            .iter()
+            .find(|line| position.y < line.y + self.line_height)
+            .or(self.lines.last())
+        else {
+            return 0;
+        };
+        line.chars
+            .iter()
+            .find(|(_, x, end)| position.x < (x + end) / 2.0)
+            .map_or(line.range.end, |(index, _, _)| *index)
+    }
+
+    /// Get the bounds of a caret before the character at `index`, which
+    /// are as tall as the line and have no width.
+    pub fn caret_rect(&self, index: CharIndex) -> Bounds {
+        let line = self.line_of(index);
+        let x = line.map_or(0.0, |line| line.x_of(index));
+        let y = line.map_or(0.0, |line| line.y);
+        Bounds::new(Position::new(x, y), Size::new(0.0, self.line_height))
+    }
+
+    /// Get the bounds that highlight a byte `range` of the text, one for
+    /// each line that it's on.
+    pub fn selection_rects(&self, range: Range<CharIndex>) -> Vec<Bounds> {
+        self.lines
+            .iter()
+            .filter(|line| range.start <= line.range.end && range.end >= line.range.start)
+            .map(|line| {
+                let left = line.x_of(range.start.max(line.range.start));
+                let right = line.x_of(range.end.min(line.range.end));
+                Bounds::new(
+                    Position::new(left, line.y),
+                    Size::new(right - left, self.line_height),
+                )
+            })
+            .filter(|bounds| bounds.x[1] > bounds.x[0])
+            .collect()
+    }
+
+    /// Get the line that a caret at `index` is on, the end of a line is on
+    /// that line rather than the start of the next one.
+    fn line_of(&self, index: CharIndex) -> Option<&PlacedLine> {
+        self.lines
+            .iter()
+            .find(|line| index <= line.range.end)
+            .or(self.lines.last())
+    }
+}
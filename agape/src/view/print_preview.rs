@@ -0,0 +1,185 @@
+use super::rect::rounded_rect_path;
+use super::{ImageView, TextView, View, new_paint, skia_color};
+use crate::Resources;
+use crate::image_cache::ImageHandle;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use std::rc::Rc;
+use tiny_skia::{FillRule, Pixmap, Rect, Stroke, Transform};
+
+/// The height of the navigation and zoom toolbar.
+pub(crate) const PREVIEW_TOOLBAR_HEIGHT: f32 = 40.0;
+/// The width of a page thumbnail at 100% zoom.
+pub(crate) const THUMBNAIL_WIDTH: f32 = 120.0;
+/// The space around each thumbnail, the page number is drawn below it.
+pub(crate) const THUMBNAIL_GAP: f32 = 24.0;
+const BACKGROUND_COLOR: Color<Rgba> = Color::rgb(243, 244, 246);
+const TOOLBAR_COLOR: Color<Rgba> = Color::WHITE;
+const BORDER_COLOR: Color<Rgba> = Color::rgb(210, 210, 210);
+const SELECTED_COLOR: Color<Rgba> = Color::rgb(37, 99, 235);
+const BUTTON_COLOR: Color<Rgba> = Color::rgb(229, 231, 235);
+const DISABLED_COLOR: Color<Rgba> = Color::rgb(160, 160, 160);
+
+/// An item in the toolbar of a [`PrintPreviewView`], positioned relative
+/// to the preview.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewToolbarItem {
+    pub label: String,
+    pub x: f32,
+    pub width: f32,
+    /// Whether the item is a button, labels aren't drawn with a background.
+    pub button: bool,
+    pub enabled: bool,
+}
+
+/// A page of a [`PrintPreviewView`], positioned relative to the preview.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageThumbnail {
+    pub number: usize,
+    pub position: Position,
+    pub size: Size,
+    pub handle: ImageHandle,
+    pub bytes: Rc<[u8]>,
+    pub selected: bool,
+}
+
+/// Draws a [`PrintPreview`](crate::widgets::PrintPreview).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintPreviewView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub toolbar: Vec<PreviewToolbarItem>,
+    pub pages: Vec<PageThumbnail>,
+}
+
+impl PrintPreviewView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            toolbar: vec![],
+            pages: vec![],
+        }
+    }
+
+    fn fill(&self, pixmap: &mut Pixmap, rect: Option<Rect>, radius: f32, color: &Color<Rgba>) {
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, radius)) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn stroke(&self, pixmap: &mut Pixmap, rect: Option<Rect>, width: f32, color: &Color<Rgba>) {
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, 0.0)) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        let stroke = Stroke {
+            width,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+
+    /// Draw text centered on a point.
+    fn draw_text(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        text: &str,
+        center: Position,
+        color: Color<Rgba>,
+    ) {
+        let mut view = TextView::new(text);
+        view.font_size = 14;
+        view.set_foreground_color(color);
+        let size = view.text_size();
+        view.set_size(size);
+        view.set_position(Position::new(
+            center.x - size.width / 2.0,
+            center.y - size.height / 2.0,
+        ));
+        view.render(pixmap, resources);
+    }
+}
+
+impl View for PrintPreviewView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &BACKGROUND_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let Position { x, y } = self.position;
+        let background = Rect::from_xywh(x, y, self.size.width, self.size.height);
+        self.fill(pixmap, background, 0.0, &BACKGROUND_COLOR);
+        let toolbar = Rect::from_xywh(x, y, self.size.width, PREVIEW_TOOLBAR_HEIGHT);
+        self.fill(pixmap, toolbar, 0.0, &TOOLBAR_COLOR);
+        self.stroke(pixmap, toolbar, 1.0, &BORDER_COLOR);
+
+        for item in &self.toolbar {
+            let left = x + item.x;
+            if item.button {
+                let rect = Rect::from_xywh(left + 4.0, y + 6.0, item.width - 8.0, 28.0);
+                self.fill(pixmap, rect, 4.0, &BUTTON_COLOR);
+            }
+            let color = match item.enabled {
+                true => Color::BLACK,
+                false => DISABLED_COLOR,
+            };
+            let center = Position::new(left + item.width / 2.0, y + PREVIEW_TOOLBAR_HEIGHT / 2.0);
+            self.draw_text(pixmap, resources, &item.label, center, color);
+        }
+
+        for page in &self.pages {
+            let bottom = page.position.y + page.size.height + THUMBNAIL_GAP;
+            if bottom > self.size.height {
+                break;
+            }
+            let position = Position::new(x + page.position.x, y + page.position.y);
+            let rect = Rect::from_xywh(position.x, position.y, page.size.width, page.size.height);
+            self.fill(pixmap, rect, 0.0, &Color::WHITE);
+            let mut view = ImageView::new(self.id, page.handle, page.bytes.clone());
+            view.set_position(position);
+            view.set_size(page.size);
+            view.render(pixmap, resources);
+
+            let (width, color) = match page.selected {
+                true => (3.0, &SELECTED_COLOR),
+                false => (1.0, &BORDER_COLOR),
+            };
+            self.stroke(pixmap, rect, width, color);
+            let center = Position::new(
+                position.x + page.size.width / 2.0,
+                position.y + page.size.height + THUMBNAIL_GAP / 2.0,
+            );
+            let label = page.number.to_string();
+            self.draw_text(pixmap, resources, &label, center, Color::BLACK);
+        }
+    }
+}
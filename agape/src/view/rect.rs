@@ -1,8 +1,15 @@
-use super::View;
+use super::blur::blur_region;
+use super::gradient::{GradientCache, GradientFill};
+use super::new_paint;
+use super::shadow::{ShadowCache, draw_shadow};
+use super::{DrawCommand, HitShape, View, skia_color};
 use crate::Resources;
-use crate::style::Border;
-use agape_core::{Color, GlobalId, Position, Rgba, Size, map};
-use tiny_skia::{Paint, PathBuilder, Pixmap, Stroke, Transform};
+use crate::style::{Border, BoxShadow, BoxStyle, CornerRadius, Gradient};
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{
+    BlendMode, FillRule, FilterQuality, IntRect, Paint, Path, PathBuilder, Pattern, Pixmap, Shader,
+    SpreadMode, Transform,
+};
 
 /// Responsible for drawing rectangular shapes to the screen.
 #[derive(Default, Debug, Clone, PartialEq)]
@@ -11,7 +18,12 @@ pub struct RectView {
     pub position: Position,
     pub size: Size,
     pub color: Color<Rgba>,
+    pub gradient: Option<Gradient>,
     pub border: Option<Border>,
+    pub corner_radius: CornerRadius,
+    pub shadow: Option<BoxShadow>,
+    /// The radius that the pixels behind the rect are blurred by.
+    pub backdrop_blur: Option<f32>,
 }
 
 impl RectView {
@@ -22,6 +34,195 @@ impl RectView {
             ..Default::default()
         }
     }
+
+    /// Create a rect view from a [`BoxStyle`].
+    pub fn with_style(id: GlobalId, style: &BoxStyle) -> Self {
+        Self {
+            id,
+            color: style.background_color.clone(),
+            gradient: style.gradient.clone(),
+            border: style.border.clone(),
+            corner_radius: style.corner_radius,
+            shadow: style.shadow.clone(),
+            backdrop_blur: style.backdrop_blur,
+            ..Default::default()
+        }
+    }
+}
+
+/// Create a rect path with rounded corners, the corners are scaled down
+/// when neighbouring corners would overlap.
+pub(crate) fn rounded_rect_path(
+    rect: tiny_skia::Rect,
+    radius: impl Into<CornerRadius>,
+) -> Option<Path> {
+    let radius = radius.into().fit(rect.width(), rect.height());
+    if radius.is_zero() {
+        return Some(PathBuilder::from_rect(rect));
+    }
+
+    // Control point offset for approximating a quarter circle with a cubic
+    const K: f32 = 0.552_284_8;
+    let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let CornerRadius {
+        top_left,
+        top_right,
+        bottom_right,
+        bottom_left,
+    } = radius;
+    let mut builder = PathBuilder::new();
+    builder.move_to(left + top_left, top);
+    builder.line_to(right - top_right, top);
+    builder.cubic_to(
+        right - top_right + top_right * K,
+        top,
+        right,
+        top + top_right - top_right * K,
+        right,
+        top + top_right,
+    );
+    builder.line_to(right, bottom - bottom_right);
+    builder.cubic_to(
+        right,
+        bottom - bottom_right + bottom_right * K,
+        right - bottom_right + bottom_right * K,
+        bottom,
+        right - bottom_right,
+        bottom,
+    );
+    builder.line_to(left + bottom_left, bottom);
+    builder.cubic_to(
+        left + bottom_left - bottom_left * K,
+        bottom,
+        left,
+        bottom - bottom_left + bottom_left * K,
+        left,
+        bottom - bottom_left,
+    );
+    builder.line_to(left, top + top_left);
+    builder.cubic_to(
+        left,
+        top + top_left - top_left * K,
+        left + top_left - top_left * K,
+        top,
+        left + top_left,
+        top,
+    );
+    builder.close();
+    builder.finish()
+}
+
+/// Blur the pixels behind a rect with rounded corners, the pixels outside
+/// of the corners are left as they are.
+fn blur_rounded_region(
+    pixmap: &mut Pixmap,
+    rect: tiny_skia::Rect,
+    corner_radius: CornerRadius,
+    radius: f32,
+) {
+    let bounds = IntRect::from_ltrb(
+        rect.left().floor().max(0.0) as i32,
+        rect.top().floor().max(0.0) as i32,
+        (rect.right().ceil() as i32).min(pixmap.width() as i32),
+        (rect.bottom().ceil() as i32).min(pixmap.height() as i32),
+    );
+    let Some(bounds) = bounds else {
+        return;
+    };
+    let Some(mut region) = pixmap.clone_rect(bounds) else {
+        return;
+    };
+    let Some(path) = rounded_rect_path(rect, corner_radius) else {
+        return;
+    };
+    let whole = tiny_skia::Rect::from_xywh(0.0, 0.0, bounds.width() as f32, bounds.height() as f32);
+    if let Some(whole) = whole {
+        blur_region(&mut region, whole, radius);
+    }
+
+    // The blurred pixels replace the pixels inside the rounded rect
+    let transform = Transform::from_translate(bounds.x() as f32, bounds.y() as f32);
+    let paint = Paint {
+        shader: Pattern::new(
+            region.as_ref(),
+            SpreadMode::Pad,
+            FilterQuality::Nearest,
+            1.0,
+            transform,
+        ),
+        blend_mode: BlendMode::Source,
+        ..new_paint()
+    };
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+/// Draw a [`Border`] centered on the edges of the `rect`.
+///
+/// The border is filled as the area between an outer and inner rounded rect,
+/// which allows each side to have a different width.
+fn draw_border(
+    pixmap: &mut Pixmap,
+    rect: tiny_skia::Rect,
+    corner_radius: CornerRadius,
+    border: &Border,
+    cache: Option<&GradientCache>,
+) {
+    let sides = border.side_widths();
+    let half = sides.max() / 2.0;
+
+    let outer = tiny_skia::Rect::from_ltrb(
+        rect.left() - sides.left / 2.0,
+        rect.top() - sides.top / 2.0,
+        rect.right() + sides.right / 2.0,
+        rect.bottom() + sides.bottom / 2.0,
+    );
+    let inner = tiny_skia::Rect::from_ltrb(
+        rect.left() + sides.left / 2.0,
+        rect.top() + sides.top / 2.0,
+        rect.right() - sides.right / 2.0,
+        rect.bottom() - sides.bottom / 2.0,
+    );
+
+    let mut builder = PathBuilder::new();
+    if let Some(path) = outer.and_then(|outer| rounded_rect_path(outer, corner_radius.offset(half)))
+    {
+        builder.push_path(&path);
+    }
+    // The inner rect won't exist if the border is wider than the rect
+    if let Some(path) =
+        inner.and_then(|inner| rounded_rect_path(inner, corner_radius.offset(-half)))
+    {
+        builder.push_path(&path);
+    }
+    let Some(path) = builder.finish() else {
+        return;
+    };
+
+    let fill = border
+        .gradient
+        .as_ref()
+        .and_then(|gradient| GradientFill::new(gradient, path.bounds(), cache));
+    let shader = match &fill {
+        Some(fill) => fill.shader(),
+        None => Shader::SolidColor(skia_color(&border.color)),
+    };
+    let paint = Paint {
+        shader,
+        ..new_paint()
+    };
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::EvenOdd,
+        Transform::identity(),
+        None,
+    );
 }
 
 impl View for RectView {
@@ -45,38 +246,87 @@ impl View for RectView {
         self.size = size
     }
 
-    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
-        let (r, g, b, a) = self.color.inner();
-
-        // Map the alpha since it's clipped to 100
-        let a = map(a as f32, [0.0, 100.0], [0.0, 255.0]) as u8;
-        let mut paint = Paint::default();
-        paint.set_color_rgba8(r, g, b, a);
-
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
         let Position { x, y } = self.position;
         let Size { width, height } = self.size;
 
-        let rect = tiny_skia::Rect::from_xywh(x, y, width, height).unwrap();
-        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        let Some(rect) = tiny_skia::Rect::from_xywh(x, y, width, height) else {
+            return;
+        };
+
+        if let Some(shadow) = &self.shadow {
+            let cache = resources.get::<ShadowCache>();
+            draw_shadow(pixmap, rect, self.corner_radius, shadow, cache);
+        }
 
-        if let Some(border) = &self.border {
-            // TODO turn this into a function
-            let (r, g, b, a) = border.color.inner();
-            let a = map(a as f32, [0.0, 100.0], [0.0, 255.0]) as u8;
-
-            let mut border_paint = Paint::default();
-            border_paint.set_color_rgba8(r, g, b, a);
-            let mut path_builder = PathBuilder::new();
-            path_builder.push_rect(rect);
-            let path = path_builder.finish().unwrap();
-
-            let stroke = Stroke {
-                width: border.width,
-                ..Default::default()
+        if let Some(radius) = self.backdrop_blur {
+            match self.corner_radius.is_zero() {
+                true => blur_region(pixmap, rect, radius),
+                false => blur_rounded_region(pixmap, rect, self.corner_radius, radius),
+            }
+        }
+
+        let fill = self
+            .gradient
+            .as_ref()
+            .and_then(|gradient| GradientFill::new(gradient, rect, resources.get()));
+        let shader = match &fill {
+            Some(fill) => fill.shader(),
+            None => Shader::SolidColor(skia_color(&self.color)),
+        };
+        // Transparent rects, like the ones behind layout only widgets, don't
+        // need to be filled
+        let transparent = matches!(shader, Shader::SolidColor(color) if color.alpha() == 0.0);
+        if !transparent {
+            let paint = Paint {
+                shader,
+                ..new_paint()
             };
+            if self.corner_radius.is_zero() {
+                // Filling the rect directly skips building a path for every rect
+                pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+            } else if let Some(path) = rounded_rect_path(rect, self.corner_radius) {
+                pixmap.fill_path(
+                    &path,
+                    &paint,
+                    FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+
+        if let Some(border) = &self.border {
+            draw_border(pixmap, rect, self.corner_radius, border, resources.get());
+        }
+    }
+
+    fn hit_shape(&self) -> HitShape {
+        match self.corner_radius.is_zero() {
+            true => HitShape::Rect,
+            false => HitShape::RoundedRect(self.corner_radius),
+        }
+    }
 
-            pixmap.stroke_path(&path, &border_paint, &stroke, Transform::identity(), None);
+    /// Plain rects are filled, rects with a gradient, border, shadow or
+    /// backdrop blur are drawn with [`View::render`].
+    fn commands(&self) -> Option<Vec<DrawCommand>> {
+        if self.gradient.is_some()
+            || self.border.is_some()
+            || self.shadow.is_some()
+            || self.backdrop_blur.is_some()
+        {
+            return None;
+        }
+        if self.color.a() == 0 {
+            return Some(vec![]);
         }
+        Some(vec![DrawCommand::FillRect {
+            position: self.position,
+            size: self.size,
+            corner_radius: self.corner_radius,
+            color: self.color.clone(),
+        }])
     }
 }
 
@@ -84,6 +334,7 @@ impl View for RectView {
 mod test {
     use super::*;
     use crate::Resources;
+    use crate::style::BorderSides;
     use agape_core::Color;
 
     #[test]
@@ -114,4 +365,114 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn border_sides() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let border = Border {
+            color: Color::BLACK,
+            sides: Some(BorderSides::new(0.0, 0.0, 10.0, 0.0)),
+            ..Default::default()
+        };
+        let view = RectView {
+            position: Position::unit(10.0),
+            size: Size::unit(80.0),
+            border: Some(border),
+            ..Default::default()
+        };
+        view.render(&mut pixmap, &Resources::new());
+
+        // Only the bottom side is drawn
+        assert_eq!(pixmap.pixel(50, 88).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(50, 10).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(10, 50).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(50, 50).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn gradient_border() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let gradient = Gradient::linear(90.0)
+            .stop(0.0, Color::BLACK)
+            .stop(1.0, Color::WHITE);
+        let border = Border {
+            width: 10.0,
+            gradient: Some(gradient),
+            ..Default::default()
+        };
+        let view = RectView {
+            position: Position::unit(10.0),
+            size: Size::unit(80.0),
+            color: Color::TRANSPARENT,
+            border: Some(border),
+            ..Default::default()
+        };
+        view.render(&mut pixmap, &Resources::new());
+
+        // The left side is darker than the right side
+        let left = pixmap.pixel(10, 50).unwrap();
+        let right = pixmap.pixel(90, 50).unwrap();
+        assert_eq!(left.alpha(), 255);
+        assert_eq!(right.alpha(), 255);
+        assert!(left.red() < 64 && right.red() > 192);
+        // Nothing is drawn inside the border
+        assert_eq!(pixmap.pixel(50, 50).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn backdrop_blur() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        pixmap.fill(tiny_skia::Color::WHITE);
+        let stripe = tiny_skia::Rect::from_xywh(0.0, 0.0, 50.0, 100.0).unwrap();
+        pixmap.fill_rect(stripe, &Paint::default(), Transform::identity(), None);
+
+        let view = RectView {
+            position: Position::unit(20.0),
+            size: Size::unit(60.0),
+            color: Color::rgba(255, 255, 255, 20),
+            backdrop_blur: Some(12.0),
+            ..Default::default()
+        };
+        view.render(&mut pixmap, &Resources::new());
+
+        // The edge of the stripe is blurred, but only behind the rect
+        let edge = pixmap.pixel(47, 50).unwrap().red();
+        assert!(edge > 64 && edge < 192);
+        assert_eq!(pixmap.pixel(47, 10).unwrap().red(), 0);
+    }
+
+    #[test]
+    fn with_style() {
+        let mut style = BoxStyle::new();
+        style.background_color = Color::RED;
+        style.gradient = Some(Gradient::linear(0.0));
+        style.border_width(2.0);
+
+        let id = GlobalId::new();
+        let view = RectView::with_style(id, &style);
+
+        assert_eq!(view.id, id);
+        assert_eq!(view.color, style.background_color);
+        assert_eq!(view.gradient, style.gradient);
+        assert_eq!(view.border, style.border);
+    }
+
+    #[test]
+    fn per_corner_radius() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let view = RectView {
+            size: Size::unit(100.0),
+            color: Color::BLACK,
+            corner_radius: CornerRadius::top(30.0),
+            ..Default::default()
+        };
+        view.render(&mut pixmap, &Resources::new());
+
+        // Only the top corners are cut off
+        assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(97, 2).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(2, 97).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(97, 97).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(50, 2).unwrap().alpha(), 255);
+    }
 }
@@ -0,0 +1,202 @@
+use super::glyph::{GlyphCache, draw_glyph};
+use super::new_paint;
+use super::text::{place_chars, resolve_font};
+use super::{View, skia_color};
+use crate::Resources;
+use crate::style::{FontStyle, FontWeight};
+use crate::widgets::TextSpan;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use fontdue::Font;
+use tiny_skia::{Pixmap, Transform};
+
+/// How far italic glyphs lean, in pixels per pixel of height.
+const ITALIC_SKEW: f32 = 0.2;
+
+/// Draws a [`RichText`](crate::widgets::RichText).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichTextView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub spans: Vec<TextSpan>,
+    pub font_size: u8,
+    pub font_family: Option<String>,
+    /// The color of spans that don't have their own color.
+    pub color: Color<Rgba>,
+}
+
+impl RichTextView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            spans: vec![],
+            font_size: 16,
+            font_family: None,
+            color: Color::BLACK,
+        }
+    }
+
+    /// Get the size of the paragraph, the height is the line height so
+    /// that spans with different weights line up.
+    pub fn text_size(&self) -> Size {
+        let font_size = self.font_size as f32;
+        let mut width = 0.0;
+        for span in &self.spans {
+            let (font, _) = self.span_font(span);
+            width += place_chars(&span.text, font, font_size).1;
+        }
+
+        let height = self
+            .line_font()
+            .horizontal_line_metrics(font_size)
+            .map(|metrics| (metrics.ascent - metrics.descent).ceil())
+            .unwrap_or_default();
+        Size::new(width, height)
+    }
+
+    /// Get the font used for the line metrics, which all the weights of a
+    /// family share.
+    fn line_font(&self) -> &'static Font {
+        let family = self.font_family.as_deref();
+        resolve_font(family, FontWeight::Regular, FontStyle::Normal).0
+    }
+
+    /// Get the font of a span, and whether it has to be slanted because the
+    /// font has no italic face.
+    fn span_font(&self, span: &TextSpan) -> (&'static Font, bool) {
+        resolve_font(self.font_family.as_deref(), span.weight, span.style)
+    }
+}
+
+/// Draw a horizontal line, used for underlines and strikethroughs.
+fn draw_line(pixmap: &mut Pixmap, x: f32, y: f32, width: f32, thickness: f32, color: &Color<Rgba>) {
+    let Some(rect) = tiny_skia::Rect::from_xywh(x, y, width, thickness) else {
+        return;
+    };
+    let mut paint = new_paint();
+    paint.set_color(skia_color(color));
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+}
+
+impl View for RichTextView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.color
+    }
+
+    fn foreground_color(&self) -> Option<&Color<Rgba>> {
+        Some(&self.color)
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let font_size = self.font_size as f32;
+        let local_cache;
+        let cache = match resources.get::<GlyphCache>() {
+            Some(cache) => cache,
+            None => {
+                local_cache = GlyphCache::new();
+                &local_cache
+            }
+        };
+
+        let Some(line_metrics) = self.line_font().horizontal_line_metrics(font_size) else {
+            return;
+        };
+        let baseline = self.position.y + line_metrics.ascent.round();
+        let thickness = (font_size / 14.0).round().max(1.0);
+
+        let mut x = self.position.x;
+        for span in &self.spans {
+            let (font, synthesize_style) = self.span_font(span);
+            let color = span.color.as_ref().unwrap_or(&self.color);
+            let skew = match synthesize_style {
+                true => ITALIC_SKEW,
+                false => 0.0,
+            };
+
+            let start = x;
+            let (chars, width) = place_chars(&span.text, font, font_size);
+            for placed in chars {
+                let glyph = placed.font.glyph(cache, placed.character, font_size);
+                let metrics = glyph.metrics;
+                let x = start + placed.x;
+                let glyph_x = (x + metrics.xmin as f32).round() as i32;
+                let glyph_y =
+                    (baseline - metrics.height as f32 - metrics.ymin as f32).round() as i32;
+                draw_glyph(pixmap, &glyph, glyph_x, glyph_y, color, skew);
+            }
+            x += width;
+
+            if span.underline {
+                let y = baseline + thickness;
+                draw_line(pixmap, start, y, x - start, thickness, color);
+            }
+            if span.strikethrough {
+                let y = baseline - font_size * 0.3;
+                draw_line(pixmap, start, y, x - start, thickness, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FONT;
+    use crate::view::init_font;
+    use crate::view::text::font;
+
+    fn view(span: TextSpan) -> RichTextView {
+        let _ = FONT.set(init_font());
+        let mut view = RichTextView::new(GlobalId::new());
+        view.spans = vec![span];
+        view
+    }
+
+    #[test]
+    fn bold_is_wider() {
+        let regular = view(TextSpan::new("Hello"));
+        let bold = view(TextSpan::new("Hello").bold());
+        assert!(bold.text_size().width > regular.text_size().width);
+        assert_eq!(bold.text_size().height, regular.text_size().height);
+    }
+
+    #[test]
+    fn draw_underline() {
+        let mut view = view(TextSpan::new("__").underline().color(Color::RED));
+        view.size = view.text_size();
+        let mut pixmap = Pixmap::new(50, 30).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+
+        let metrics = font(FontWeight::Regular)
+            .horizontal_line_metrics(16.0)
+            .unwrap();
+        let y = metrics.ascent.round() as u32 + 1;
+        assert_ne!(pixmap.pixel(5, y).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn multiple_spans() {
+        let mut view = view(TextSpan::new("Hello "));
+        let first = view.text_size().width;
+        view.spans.push(TextSpan::new("world").italic());
+        assert!(view.text_size().width > first);
+    }
+}
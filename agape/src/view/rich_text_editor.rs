@@ -0,0 +1,407 @@
+use super::new_paint;
+use super::rect::rounded_rect_path;
+use super::text::{place_chars, resolve_font};
+use super::{ImageView, RichTextView, TextView, View, skia_color};
+use crate::Resources;
+use crate::document::{Block, BlockKind, InlineObject};
+use crate::image_cache::ImageHandle;
+use crate::style::{FontStyle, FontWeight};
+use crate::widgets::TextSpan;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+use tiny_skia::{FillRule, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// The height of the formatting toolbar.
+pub(crate) const EDITOR_TOOLBAR_HEIGHT: f32 = 32.0;
+/// The width of each toolbar button.
+pub(crate) const EDITOR_BUTTON_WIDTH: f32 = 40.0;
+/// The space between the border and the text.
+pub(crate) const EDITOR_PADDING: f32 = 12.0;
+/// How far list items are indented, to make room for the bullet or number.
+pub(crate) const LIST_INDENT: f32 = 24.0;
+const BACKGROUND_COLOR: Color<Rgba> = Color::WHITE;
+const BORDER_COLOR: Color<Rgba> = Color::rgb(210, 210, 210);
+const ACTIVE_COLOR: Color<Rgba> = Color::rgb(229, 231, 235);
+const SELECTION_COLOR: Color<Rgba> = Color::rgb(191, 219, 254);
+const LINK_COLOR: Color<Rgba> = Color::rgb(37, 99, 235);
+const CHIP_COLOR: Color<Rgba> = Color::rgb(243, 244, 246);
+const PLACEHOLDER_COLOR: Color<Rgba> = Color::rgb(229, 231, 235);
+/// The font size of the name in a file chip.
+const CHIP_FONT_SIZE: u8 = 14;
+const CHIP_PADDING: f32 = 8.0;
+
+/// Get the font size of a block, headings are larger than the other text.
+pub(crate) fn block_font_size(kind: BlockKind) -> u8 {
+    match kind {
+        BlockKind::Heading(1) => 28,
+        BlockKind::Heading(2) => 22,
+        BlockKind::Heading(_) => 18,
+        _ => 16,
+    }
+}
+
+/// Get the height of the line a block is drawn on.
+pub(crate) fn block_height(kind: BlockKind) -> f32 {
+    line_height(block_font_size(kind))
+}
+
+fn line_height(font_size: u8) -> f32 {
+    font_size as f32 * 1.5
+}
+
+/// Get the size an object is drawn at.
+pub(crate) fn object_size(object: &InlineObject, kind: BlockKind) -> Size {
+    match object {
+        InlineObject::Image { width, height, .. } => Size::new(*width as f32, *height as f32),
+        InlineObject::File { name, .. } => {
+            let mut view = TextView::new(name);
+            view.font_size = CHIP_FONT_SIZE;
+            let width = view.text_size().width + CHIP_PADDING * 2.0;
+            Size::new(width, block_height(kind) - 4.0)
+        }
+    }
+}
+
+/// Get the width of each character of a block, objects take up the width
+/// they're drawn at.
+pub(crate) fn char_advances(block: &Block) -> Vec<f32> {
+    let font_size = block_font_size(block.kind) as f32;
+    let mut advances = vec![];
+    let mut buffer = [0; 4];
+    for (run, span) in block.runs.iter().zip(block_spans(block)) {
+        if let Some(object) = &run.object {
+            advances.push(object_size(object, block.kind).width);
+            continue;
+        }
+        let (font, _) = resolve_font(None, span.weight, span.style);
+        for c in run.text.chars() {
+            advances.push(place_chars(c.encode_utf8(&mut buffer), font, font_size).1);
+        }
+    }
+    advances
+}
+
+/// Split the characters of a line of a block into text and objects.
+pub(crate) fn line_segments(
+    block: &Block,
+    range: Range<usize>,
+    advances: &[f32],
+    images: &HashMap<String, Rc<[u8]>>,
+) -> Vec<EditorSegment> {
+    let mut segments = vec![];
+    let mut x = 0.0;
+    let mut start = 0;
+    for (run, span) in block.runs.iter().zip(block_spans(block)) {
+        let run_start = start;
+        let end = start + run.text.chars().count();
+        let from = start.max(range.start);
+        let to = end.min(range.end);
+        start = end;
+        if from >= to {
+            continue;
+        }
+
+        let width: f32 = advances[from..to].iter().sum();
+        if let Some(object) = &run.object {
+            let image = match object {
+                InlineObject::Image { source, .. } => images.get(source).cloned(),
+                InlineObject::File { .. } => None,
+            };
+            segments.push(EditorSegment::Object {
+                x,
+                size: object_size(object, block.kind),
+                object: object.clone(),
+                image,
+            });
+        } else {
+            let text: String = run
+                .text
+                .chars()
+                .skip(from - run_start)
+                .take(to - from)
+                .collect();
+            let span = TextSpan { text, ..span };
+            match segments.last_mut() {
+                Some(EditorSegment::Text { spans, .. }) => spans.push(span),
+                _ => segments.push(EditorSegment::Text {
+                    x,
+                    spans: vec![span],
+                }),
+            }
+        }
+        x += width;
+    }
+    segments
+}
+
+/// Part of an [`EditorLine`], positioned relative to the start of the line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditorSegment {
+    Text {
+        x: f32,
+        spans: Vec<TextSpan>,
+    },
+    Object {
+        x: f32,
+        size: Size,
+        object: InlineObject,
+        /// The encoded image, if the object is an image that was added to
+        /// the editor.
+        image: Option<Rc<[u8]>>,
+    },
+}
+
+/// Get the styled spans that a block is drawn with.
+pub(crate) fn block_spans(block: &Block) -> Vec<TextSpan> {
+    let heading = matches!(block.kind, BlockKind::Heading(_));
+    block
+        .runs
+        .iter()
+        .map(|run| {
+            let mut span = TextSpan::new(&run.text);
+            if run.marks.bold || heading {
+                span.weight = FontWeight::Bold;
+            }
+            if run.marks.italic {
+                span.style = FontStyle::Italic;
+            }
+            span.underline = run.marks.underline || run.marks.link.is_some();
+            if run.marks.link.is_some() {
+                span.color = Some(LINK_COLOR);
+            }
+            span
+        })
+        .collect()
+}
+
+/// A line of a [`RichTextEditorView`], positioned relative to the editor.
+/// Blocks that are wider than the editor are wrapped onto several lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorLine {
+    pub segments: Vec<EditorSegment>,
+    pub font_size: u8,
+    /// The bullet or number of a list item, on the first line of the item.
+    pub prefix: Option<String>,
+    pub x: f32,
+    pub y: f32,
+    pub height: f32,
+}
+
+/// Draws a [`RichTextEditor`](crate::widgets::RichTextEditor).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichTextEditorView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    /// The label of each toolbar button, and whether it's active.
+    pub toolbar: Vec<(String, bool)>,
+    pub lines: Vec<EditorLine>,
+    /// The selected areas, as `(x, y, width, height)` relative to the editor.
+    pub selection: Vec<(f32, f32, f32, f32)>,
+    /// The caret, as `(x, y, height)` relative to the editor.
+    pub caret: Option<(f32, f32, f32)>,
+}
+
+impl RichTextEditorView {
+    pub fn new(id: GlobalId) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            toolbar: vec![],
+            lines: vec![],
+            selection: vec![],
+            caret: None,
+        }
+    }
+
+    fn fill(&self, pixmap: &mut Pixmap, rect: Option<Rect>, radius: f32, color: &Color<Rgba>) {
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, radius)) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    /// Draw text vertically centered in a line.
+    fn draw_label(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        text: &str,
+        font_size: u8,
+        x: f32,
+        center_y: f32,
+    ) {
+        let mut view = TextView::new(text);
+        view.font_size = font_size;
+        let size = view.text_size();
+        view.set_size(size);
+        view.set_position(Position::new(x, center_y - size.height / 2.0));
+        view.render(pixmap, resources);
+    }
+
+    /// Draw an object with its top left corner at a position.
+    fn draw_object(
+        &self,
+        pixmap: &mut Pixmap,
+        resources: &Resources,
+        object: &InlineObject,
+        image: Option<&Rc<[u8]>>,
+        position: Position,
+        size: Size,
+    ) {
+        let rect = Rect::from_xywh(position.x, position.y, size.width, size.height);
+        match (object, image) {
+            (InlineObject::Image { .. }, Some(bytes)) => {
+                let handle = ImageHandle::from_bytes(bytes);
+                let mut view = ImageView::new(self.id, handle, bytes.clone());
+                view.set_position(position);
+                view.set_size(size);
+                view.render(pixmap, resources);
+            }
+            // Images that weren't added to the editor are drawn as a placeholder
+            (InlineObject::Image { .. }, None) => {
+                self.fill(pixmap, rect, 4.0, &PLACEHOLDER_COLOR);
+            }
+            (InlineObject::File { name, .. }, _) => {
+                self.fill(pixmap, rect, size.height / 2.0, &CHIP_COLOR);
+                let x = position.x + CHIP_PADDING;
+                let center = position.y + size.height / 2.0;
+                self.draw_label(pixmap, resources, name, CHIP_FONT_SIZE, x, center);
+            }
+        }
+    }
+
+    fn draw_border(&self, pixmap: &mut Pixmap) {
+        let Position { x, y } = self.position;
+        let rect = Rect::from_xywh(x, y, self.size.width, self.size.height);
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, 6.0)) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&BORDER_COLOR));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(x, y + EDITOR_TOOLBAR_HEIGHT);
+        builder.line_to(x + self.size.width, y + EDITOR_TOOLBAR_HEIGHT);
+        if let Some(path) = builder.finish() {
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &Stroke::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+impl View for RichTextEditorView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &BACKGROUND_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let Position { x, y } = self.position;
+        let background = Rect::from_xywh(x, y, self.size.width, self.size.height);
+        self.fill(pixmap, background, 6.0, &BACKGROUND_COLOR);
+        self.draw_border(pixmap);
+
+        for (index, (label, active)) in self.toolbar.iter().enumerate() {
+            let left = x + index as f32 * EDITOR_BUTTON_WIDTH;
+            if *active {
+                let rect = Rect::from_xywh(left + 4.0, y + 4.0, EDITOR_BUTTON_WIDTH - 8.0, 24.0);
+                self.fill(pixmap, rect, 4.0, &ACTIVE_COLOR);
+            }
+            let center = y + EDITOR_TOOLBAR_HEIGHT / 2.0;
+            let mut view = TextView::new(label);
+            view.font_size = 14;
+            let width = view.text_size().width;
+            let left = left + (EDITOR_BUTTON_WIDTH - width) / 2.0;
+            self.draw_label(pixmap, resources, label, 14, left, center);
+        }
+
+        for &(left, top, width, height) in &self.selection {
+            let rect = Rect::from_xywh(x + left, y + top, width, height);
+            self.fill(pixmap, rect, 0.0, &SELECTION_COLOR);
+        }
+
+        for line in &self.lines {
+            if line.y + line.height > self.size.height {
+                break;
+            }
+            // Text sits at the bottom of lines that are taller because of
+            // an image
+            let text_height = line_height(line.font_size);
+            let center = y + line.y + line.height - text_height / 2.0;
+            if let Some(prefix) = &line.prefix {
+                let left = x + line.x - LIST_INDENT;
+                self.draw_label(pixmap, resources, prefix, line.font_size, left, center);
+            }
+
+            for segment in &line.segments {
+                match segment {
+                    EditorSegment::Text { x: left, spans } => {
+                        let mut view = RichTextView::new(self.id);
+                        view.spans = spans.clone();
+                        view.font_size = line.font_size;
+                        let size = view.text_size();
+                        view.set_size(size);
+                        let left = x + line.x + left;
+                        view.set_position(Position::new(left, center - size.height / 2.0));
+                        view.render(pixmap, resources);
+                    }
+                    EditorSegment::Object {
+                        x: left,
+                        size,
+                        object,
+                        image,
+                    } => {
+                        let bottom = y + line.y + line.height - 2.0;
+                        let position = Position::new(x + line.x + left, bottom - size.height);
+                        let image = image.as_ref();
+                        self.draw_object(pixmap, resources, object, image, position, *size);
+                    }
+                }
+            }
+        }
+
+        if let Some((left, top, height)) = self.caret {
+            let rect = Rect::from_xywh(x + left, y + top, 1.5, height);
+            self.fill(pixmap, rect, 0.0, &Color::BLACK);
+        }
+    }
+}
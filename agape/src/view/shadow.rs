@@ -0,0 +1,279 @@
+//! Drop shadows are drawn on the cpu using the signed distance to a rounded
+//! rect, which is then blurred by the gaussian error function.
+use super::lru::LruCache;
+use crate::style::{BoxShadow, CornerRadius};
+use agape_core::{Color, Rgba, map};
+use std::cell::RefCell;
+use std::f32::consts::SQRT_2;
+use std::rc::Rc;
+use tiny_skia::{Pixmap, PixmapPaint, PremultipliedColorU8, Transform};
+
+/// The maximum number of shadow layers kept in a [`ShadowCache`].
+const SHADOW_CACHE_CAPACITY: usize = 64;
+
+/// Everything that changes how a shadow layer looks, the sub-pixel offset
+/// is included since the layer is drawn at whole pixels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ShadowKey {
+    width: u32,
+    height: u32,
+    radius: [u32; 4],
+    blur: u32,
+    fract: [u32; 2],
+    color: Color<Rgba>,
+}
+
+/// Caches blurred shadow layers, so that the blur is only computed when
+/// the size or style of a shadow changes.
+#[derive(Debug)]
+pub struct ShadowCache {
+    layers: RefCell<LruCache<ShadowKey, Option<Rc<Pixmap>>>>,
+}
+
+impl Default for ShadowCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShadowCache {
+    pub fn new() -> Self {
+        Self {
+            layers: RefCell::new(LruCache::new(SHADOW_CACHE_CAPACITY)),
+        }
+    }
+
+    /// Get the number of cached shadow layers.
+    pub fn len(&self) -> usize {
+        self.layers.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.layers.borrow_mut().clear();
+    }
+}
+
+/// Draw a [`BoxShadow`] for a `rect` with rounded corners, the blurred
+/// layer is reused from the cache if there is one.
+pub(crate) fn draw_shadow(
+    pixmap: &mut Pixmap,
+    rect: tiny_skia::Rect,
+    corner_radius: impl Into<CornerRadius>,
+    shadow: &BoxShadow,
+    cache: Option<&ShadowCache>,
+) {
+    let width = rect.width() + shadow.spread * 2.0;
+    let height = rect.height() + shadow.spread * 2.0;
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+
+    let x = rect.x() + shadow.offset.x - shadow.spread;
+    let y = rect.y() + shadow.offset.y - shadow.spread;
+    let radius = corner_radius
+        .into()
+        .offset(shadow.spread)
+        .fit(width, height);
+
+    // The gaussian is practically zero three standard deviations away
+    let margin = (shadow.blur / 2.0 * 3.0).ceil() + 1.0;
+    let left = (x - margin).floor();
+    let top = (y - margin).floor();
+    let layer = ShadowLayer {
+        width,
+        height,
+        radius,
+        blur: shadow.blur,
+        margin,
+        fract: [x - margin - left, y - margin - top],
+        color: &shadow.color,
+    };
+
+    let draw = |pixmap: &mut Pixmap, layer: &Pixmap| {
+        pixmap.draw_pixmap(
+            left as i32,
+            top as i32,
+            layer.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+    };
+
+    match cache {
+        Some(cache) => {
+            let key = layer.key();
+            let layer = cache
+                .layers
+                .borrow_mut()
+                .get_or_insert_with(key, || layer.render().map(Rc::new));
+            if let Some(layer) = layer {
+                draw(pixmap, &layer);
+            }
+        }
+        None => {
+            if let Some(layer) = layer.render() {
+                draw(pixmap, &layer);
+            }
+        }
+    }
+}
+
+/// A blurred shadow drawn onto its own pixmap.
+struct ShadowLayer<'a> {
+    width: f32,
+    height: f32,
+    radius: CornerRadius,
+    blur: f32,
+    margin: f32,
+    /// The offset of the shadow from the layer's whole pixel position.
+    fract: [f32; 2],
+    color: &'a Color<Rgba>,
+}
+
+impl ShadowLayer<'_> {
+    fn key(&self) -> ShadowKey {
+        ShadowKey {
+            width: self.width.to_bits(),
+            height: self.height.to_bits(),
+            radius: [
+                self.radius.top_left.to_bits(),
+                self.radius.top_right.to_bits(),
+                self.radius.bottom_right.to_bits(),
+                self.radius.bottom_left.to_bits(),
+            ],
+            blur: self.blur.to_bits(),
+            fract: [self.fract[0].to_bits(), self.fract[1].to_bits()],
+            color: self.color.clone(),
+        }
+    }
+
+    fn render(&self) -> Option<Pixmap> {
+        let (width, height) = (self.width, self.height);
+        let sigma = self.blur / 2.0;
+        let layer_width = (width + self.margin * 2.0).ceil() as u32 + 1;
+        let layer_height = (height + self.margin * 2.0).ceil() as u32 + 1;
+        let mut layer = Pixmap::new(layer_width, layer_height)?;
+
+        let (r, g, b, a) = self.color.inner();
+        let a = map(a as f32, [0.0, 100.0], [0.0, 1.0]);
+        let center_x = self.fract[0] + self.margin + width / 2.0;
+        let center_y = self.fract[1] + self.margin + height / 2.0;
+
+        for (index, pixel) in layer.pixels_mut().iter_mut().enumerate() {
+            let px = (index as u32 % layer_width) as f32 + 0.5 - center_x;
+            let py = (index as u32 / layer_width) as f32 + 0.5 - center_y;
+            // Each quarter of the shadow is rounded by its own corner
+            let radius = match (px < 0.0, py < 0.0) {
+                (true, true) => self.radius.top_left,
+                (false, true) => self.radius.top_right,
+                (false, false) => self.radius.bottom_right,
+                (true, false) => self.radius.bottom_left,
+            };
+            let distance = rounded_rect_distance(px, py, width / 2.0, height / 2.0, radius);
+
+            let coverage = if sigma > 0.0 {
+                0.5 * (1.0 - erf(distance / (sigma * SQRT_2)))
+            } else {
+                (0.5 - distance).clamp(0.0, 1.0)
+            };
+
+            let alpha = a * coverage;
+            let premultiply = |channel: u8| (channel as f32 * alpha).round() as u8;
+            *pixel = PremultipliedColorU8::from_rgba(
+                premultiply(r),
+                premultiply(g),
+                premultiply(b),
+                (alpha * 255.0).round() as u8,
+            )
+            .unwrap();
+        }
+
+        Some(layer)
+    }
+}
+
+/// The signed distance from a point to a rounded rect centered at the origin,
+/// negative values are inside the rect.
+fn rounded_rect_distance(x: f32, y: f32, half_width: f32, half_height: f32, radius: f32) -> f32 {
+    let qx = x.abs() - half_width + radius;
+    let qy = y.abs() - half_height + radius;
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    let inside = qx.max(qy).min(0.0);
+
+    outside + inside - radius
+}
+
+/// Approximation of the error function, from Abramowitz and Stegun 7.1.26.
+fn erf(x: f32) -> f32 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_6
+            + t * (-0.284_496_74 + t * (1.421_413_7 + t * (-1.453_152 + t * 1.061_405_4))));
+    let value = 1.0 - polynomial * (-x * x).exp();
+
+    if x < 0.0 { -value } else { value }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_core::Position;
+
+    #[test]
+    fn distance_to_rect() {
+        assert_eq!(rounded_rect_distance(0.0, 0.0, 10.0, 10.0, 0.0), -10.0);
+        assert_eq!(rounded_rect_distance(15.0, 0.0, 10.0, 10.0, 0.0), 5.0);
+        // The corner is cut off by the radius
+        let corner = rounded_rect_distance(10.0, 10.0, 10.0, 10.0, 5.0);
+        assert!(corner > 0.0);
+    }
+
+    #[test]
+    fn hard_shadow() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let rect = tiny_skia::Rect::from_xywh(10.0, 10.0, 40.0, 40.0).unwrap();
+        let shadow = BoxShadow::new(Position::new(20.0, 20.0), 0.0, 0.0, Color::BLACK);
+        draw_shadow(&mut pixmap, rect, 0.0, &shadow, None);
+
+        assert_eq!(pixmap.pixel(45, 45).unwrap().alpha(), 255);
+        assert_eq!(pixmap.pixel(20, 20).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(75, 75).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn blurred_shadow_fades_out() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let rect = tiny_skia::Rect::from_xywh(25.0, 25.0, 50.0, 50.0).unwrap();
+        let shadow = BoxShadow::new(Position::default(), 10.0, 0.0, Color::BLACK);
+        draw_shadow(&mut pixmap, rect, 0.0, &shadow, None);
+
+        let center = pixmap.pixel(50, 50).unwrap().alpha();
+        let edge = pixmap.pixel(75, 50).unwrap().alpha();
+        let outside = pixmap.pixel(85, 50).unwrap().alpha();
+        assert_eq!(center, 255);
+        assert!(edge > outside && edge < center);
+    }
+
+    #[test]
+    fn reuse_cached_layer() {
+        let cache = ShadowCache::new();
+        let shadow = BoxShadow::new(Position::default(), 4.0, 0.0, Color::BLACK);
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        let mut uncached = Pixmap::new(100, 100).unwrap();
+
+        for x in [10.0, 40.0] {
+            let rect = tiny_skia::Rect::from_xywh(x, 10.0, 20.0, 20.0).unwrap();
+            draw_shadow(&mut pixmap, rect, 0.0, &shadow, Some(&cache));
+            draw_shadow(&mut uncached, rect, 0.0, &shadow, None);
+        }
+
+        // Both rects are the same size, so they share a layer
+        assert_eq!(cache.len(), 1);
+        assert_eq!(pixmap, uncached);
+    }
+}
@@ -0,0 +1,276 @@
+use super::new_paint;
+use super::{View, skia_color};
+use crate::Resources;
+use crate::widgets::SparklineKind;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use std::rc::Rc;
+use tiny_skia::{LineCap, LineJoin, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// Draws a [`Sparkline`](crate::widgets::Sparkline).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparklineView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub values: Rc<[f32]>,
+    pub kind: SparklineKind,
+    pub color: Color<Rgba>,
+    pub stroke_width: f32,
+}
+
+impl SparklineView {
+    pub fn new(id: GlobalId, values: Rc<[f32]>) -> Self {
+        Self {
+            id,
+            position: Position::default(),
+            size: Size::default(),
+            values,
+            kind: SparklineKind::default(),
+            color: Color::BLACK,
+            stroke_width: 1.5,
+        }
+    }
+
+    /// Get the smallest and largest values.
+    fn range(&self) -> (f32, f32) {
+        self.values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f32::MAX, f32::MIN), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    }
+
+    fn draw_line(&self, pixmap: &mut Pixmap, min: f32, max: f32) {
+        // Inset the line so that the stroke isn't clipped
+        let inset = self.stroke_width / 2.0;
+        let height = self.size.height - self.stroke_width;
+        let scale = if max > min { height / (max - min) } else { 0.0 };
+        let step = if self.values.len() > 1 {
+            self.size.width / (self.values.len() - 1) as f32
+        } else {
+            0.0
+        };
+
+        let point = |index: usize, value: f32| {
+            let x = self.position.x + step * index as f32;
+            let y = self.position.y + inset + height - (value - min) * scale;
+            (x, y)
+        };
+
+        // Non-finite values can't be drawn, so they split the line into
+        // segments
+        let mut segments: Vec<Vec<(f32, f32)>> = vec![];
+        let mut segment = vec![];
+        for (index, &value) in self.values.iter().enumerate() {
+            if value.is_finite() {
+                segment.push(point(index, value));
+            } else if !segment.is_empty() {
+                segments.push(std::mem::take(&mut segment));
+            }
+        }
+        if !segment.is_empty() {
+            segments.push(segment);
+        }
+        // A single value is drawn as a flat line
+        if let [value] = *self.values {
+            if let Some(segment) = segments.first_mut() {
+                segment.push((self.position.x + self.size.width, point(0, value).1));
+            }
+        }
+
+        let mut paint = new_paint();
+        if self.kind == SparklineKind::Area {
+            let bottom = self.position.y + self.size.height;
+            let mut area = PathBuilder::new();
+            for segment in segments.iter().filter(|segment| segment.len() > 1) {
+                area.move_to(segment[0].0, bottom);
+                for &(x, y) in segment {
+                    area.line_to(x, y);
+                }
+                area.line_to(segment[segment.len() - 1].0, bottom);
+                area.close();
+            }
+
+            if let Some(area) = area.finish() {
+                let mut color = skia_color(&self.color);
+                color.apply_opacity(0.25);
+                paint.set_color(color);
+                pixmap.fill_path(
+                    &area,
+                    &paint,
+                    tiny_skia::FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+
+        let mut builder = PathBuilder::new();
+        let mut dots = PathBuilder::new();
+        for segment in &segments {
+            // Values between two gaps are drawn as dots
+            if let [(x, y)] = segment[..] {
+                dots.push_circle(x, y, self.stroke_width / 2.0);
+                continue;
+            }
+            builder.move_to(segment[0].0, segment[0].1);
+            for &(x, y) in &segment[1..] {
+                builder.line_to(x, y);
+            }
+        }
+        paint.set_color(skia_color(&self.color));
+        if let Some(dots) = dots.finish() {
+            pixmap.fill_path(
+                &dots,
+                &paint,
+                tiny_skia::FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+        let Some(line) = builder.finish() else {
+            return;
+        };
+
+        let stroke = Stroke {
+            width: self.stroke_width,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Default::default()
+        };
+        pixmap.stroke_path(&line, &paint, &stroke, Transform::identity(), None);
+    }
+
+    fn draw_bars(&self, pixmap: &mut Pixmap, min: f32, max: f32) {
+        // Bars grow from zero
+        let (min, max) = (min.min(0.0), max.max(0.0));
+        let scale = if max > min {
+            self.size.height / (max - min)
+        } else {
+            0.0
+        };
+        let baseline = self.position.y + max * scale;
+        let slot = self.size.width / self.values.len() as f32;
+        let gap = (slot * 0.2).min(1.0);
+
+        // Draw all the bars in one path
+        let mut builder = PathBuilder::new();
+        for (index, &value) in self.values.iter().enumerate() {
+            let x = self.position.x + slot * index as f32;
+            let top = baseline - value.max(0.0) * scale;
+            let bottom = baseline - value.min(0.0) * scale;
+            if let Some(rect) = Rect::from_ltrb(x, top, x + slot - gap, bottom) {
+                builder.push_rect(rect);
+            }
+        }
+        let Some(bars) = builder.finish() else {
+            return;
+        };
+
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&self.color));
+        pixmap.fill_path(
+            &bars,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+}
+
+impl View for SparklineView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &self.color
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, _: &Resources) {
+        if self.values.is_empty() {
+            return;
+        }
+
+        let (min, max) = self.range();
+        match self.kind {
+            SparklineKind::Line | SparklineKind::Area => self.draw_line(pixmap, min, max),
+            SparklineKind::Bar => self.draw_bars(pixmap, min, max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn render(kind: SparklineKind, values: &[f32]) -> Pixmap {
+        let mut view = SparklineView::new(GlobalId::new(), values.into());
+        view.kind = kind;
+        view.size = Size::new(100.0, 20.0);
+
+        let mut pixmap = Pixmap::new(100, 20).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+        pixmap
+    }
+
+    #[test]
+    fn range_skips_nan() {
+        let view = SparklineView::new(GlobalId::new(), [1.0, f32::NAN, 5.0].into());
+        assert_eq!(view.range(), (1.0, 5.0));
+    }
+
+    #[test]
+    fn draw_bars() {
+        let pixmap = render(SparklineKind::Bar, &[1.0, 0.0, 2.0, 0.0]);
+        // The third bar reaches the top, the second bar is empty
+        assert_ne!(pixmap.pixel(55, 1).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(35, 10).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn fill_area_under_line() {
+        let line = render(SparklineKind::Line, &[10.0, 10.0, 5.0]);
+        let area = render(SparklineKind::Area, &[10.0, 10.0, 5.0]);
+
+        assert_eq!(line.pixel(10, 10).unwrap().alpha(), 0);
+        assert_ne!(area.pixel(10, 10).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn split_line_at_non_finite_values() {
+        let pixmap = render(SparklineKind::Area, &[0.0, 0.0, f32::NAN, 0.0, 0.0]);
+        // Both halves are still drawn, with a gap where the NaN is
+        assert_ne!(pixmap.pixel(10, 19).unwrap().alpha(), 0);
+        assert_ne!(pixmap.pixel(90, 19).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(50, 19).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn isolated_value_is_a_dot() {
+        let pixmap = render(SparklineKind::Line, &[f32::INFINITY, 1.0, f32::NAN]);
+        // A flat range is drawn at the bottom
+        assert_ne!(pixmap.pixel(50, 19).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(60, 19).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn single_value() {
+        let pixmap = render(SparklineKind::Line, &[3.0]);
+        assert_ne!(pixmap.pixel(50, 19).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::style::{StrokeCap, StrokeJoin, StrokeStyle};
+use tiny_skia::{LineCap, LineJoin, Stroke, StrokeDash};
+
+pub(crate) fn line_cap(cap: StrokeCap) -> LineCap {
+    match cap {
+        StrokeCap::Butt => LineCap::Butt,
+        StrokeCap::Round => LineCap::Round,
+        StrokeCap::Square => LineCap::Square,
+    }
+}
+
+fn line_join(join: StrokeJoin) -> LineJoin {
+    match join {
+        StrokeJoin::Miter => LineJoin::Miter,
+        StrokeJoin::Round => LineJoin::Round,
+        StrokeJoin::Bevel => LineJoin::Bevel,
+    }
+}
+
+/// Convert a [`StrokeStyle`] into a `tiny_skia` stroke.
+pub(crate) fn skia_stroke(style: &StrokeStyle) -> Stroke {
+    let mut pattern = style.dash.clone();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_within(..);
+    }
+
+    // Invalid patterns, such as ones with negative lengths, are drawn
+    // as solid lines
+    let dash = if pattern.is_empty() {
+        None
+    } else {
+        StrokeDash::new(pattern, style.dash_offset)
+    };
+
+    Stroke {
+        width: style.width,
+        line_cap: line_cap(style.cap),
+        line_join: line_join(style.join),
+        dash,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_core::Color;
+
+    #[test]
+    fn repeat_odd_dash_pattern() {
+        let style = StrokeStyle::new(1.0, Color::BLACK).dash(vec![4.0], 0.0);
+        assert!(skia_stroke(&style).dash.is_some());
+    }
+
+    #[test]
+    fn invalid_dash_pattern() {
+        let style = StrokeStyle::new(1.0, Color::BLACK).dash(vec![-4.0, 2.0], 0.0);
+        assert!(skia_stroke(&style).dash.is_none());
+    }
+
+    #[test]
+    fn solid_line() {
+        let stroke = skia_stroke(&StrokeStyle::default());
+        assert!(stroke.dash.is_none());
+        assert_eq!(stroke.width, 1.0);
+    }
+}
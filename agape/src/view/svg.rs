@@ -0,0 +1,258 @@
+//! Exporting a [`DisplayList`] as an svg document.
+//!
+//! Rects and layers are written as svg elements, so they stay sharp when the
+//! document is scaled. Views that can't be described with draw commands,
+//! like text, are drawn with `tiny_skia` and embedded as png images.
+use super::display_list::blur_margin;
+use super::{DisplayList, DrawCommand, Layer, View, rounded_rect_path};
+use crate::Resources;
+use agape_core::{Color, Rgba, Size};
+use std::fmt::Write;
+use tiny_skia::{IntRect, Path, PathSegment, Pixmap};
+
+impl DisplayList {
+    /// Export the commands as an svg document with a `size`.
+    pub fn to_svg(&self, size: Size, resources: &Resources) -> String {
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            size.width, size.height
+        );
+        let mut filters = 0;
+        for command in self.commands() {
+            match command {
+                DrawCommand::FillRect {
+                    position,
+                    size,
+                    corner_radius,
+                    color,
+                } => {
+                    let Some(rect) =
+                        tiny_skia::Rect::from_xywh(position.x, position.y, size.width, size.height)
+                    else {
+                        continue;
+                    };
+                    if corner_radius.is_zero() {
+                        let _ = write!(
+                            svg,
+                            r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+                            rect.x(),
+                            rect.y(),
+                            rect.width(),
+                            rect.height(),
+                            fill(color)
+                        );
+                    } else if let Some(path) = rounded_rect_path(rect, *corner_radius) {
+                        let _ = write!(svg, r#"<path d="{}"{}/>"#, path_data(&path), fill(color));
+                    }
+                }
+                DrawCommand::View(view) => {
+                    write_view(&mut svg, view.as_ref(), size, resources);
+                }
+                DrawCommand::PushLayer(layer) => {
+                    write_layer(&mut svg, layer, filters);
+                    if layer.blur > 0.0 {
+                        filters += 1;
+                    }
+                }
+                DrawCommand::PopLayer => svg.push_str("</g>"),
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Get the fill attributes of a color.
+fn fill(color: &Color<Rgba>) -> String {
+    let (r, g, b, a) = color.inner();
+    let mut fill = format!(r#" fill="rgb({r},{g},{b})""#);
+    if a < 100 {
+        let _ = write!(fill, r#" fill-opacity="{}""#, a as f32 / 100.0);
+    }
+    fill
+}
+
+/// Convert a path into svg path data.
+fn path_data(path: &Path) -> String {
+    let mut data = String::new();
+    for segment in path.segments() {
+        let _ = match segment {
+            PathSegment::MoveTo(p) => write!(data, "M{} {}", p.x, p.y),
+            PathSegment::LineTo(p) => write!(data, "L{} {}", p.x, p.y),
+            PathSegment::QuadTo(p1, p) => write!(data, "Q{} {} {} {}", p1.x, p1.y, p.x, p.y),
+            PathSegment::CubicTo(p1, p2, p) => {
+                write!(data, "C{} {} {} {} {} {}", p1.x, p1.y, p2.x, p2.y, p.x, p.y)
+            }
+            PathSegment::Close => write!(data, "Z"),
+        };
+    }
+    data
+}
+
+/// Open a group for a layer, blurred layers get a filter with the id
+/// `blur{index}`.
+fn write_layer(svg: &mut String, layer: &Layer, index: usize) {
+    let mut attributes = String::new();
+    if layer.opacity < 1.0 {
+        let _ = write!(attributes, r#" opacity="{}""#, layer.opacity);
+    }
+    if !layer.transform.is_identity() {
+        let t = layer.transform;
+        let _ = write!(
+            attributes,
+            r#" transform="matrix({} {} {} {} {} {})""#,
+            t.sx, t.ky, t.kx, t.sy, t.tx, t.ty
+        );
+    }
+    if layer.blur > 0.0 {
+        let margin = blur_margin(layer.blur);
+        let region = layer
+            .bounds
+            .and_then(|bounds| bounds.outset(margin, margin));
+        let _ = write!(svg, r#"<defs><filter id="blur{index}""#);
+        if let Some(region) = region {
+            let _ = write!(
+                svg,
+                r#" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}""#,
+                region.x(),
+                region.y(),
+                region.width(),
+                region.height()
+            );
+        }
+        // Blur radii are two standard deviations
+        let _ = write!(
+            svg,
+            r#"><feGaussianBlur stdDeviation="{}"/></filter></defs>"#,
+            layer.blur / 2.0
+        );
+        let _ = write!(attributes, r#" filter="url(#blur{index})""#);
+    }
+    let _ = write!(svg, "<g{attributes}>");
+}
+
+/// Draw a view on its own and embed the pixels that it covers as an image.
+fn write_view(svg: &mut String, view: &dyn View, size: Size, resources: &Resources) {
+    let width = size.width.ceil() as u32;
+    let height = size.height.ceil() as u32;
+    let Some(mut pixmap) = Pixmap::new(width, height) else {
+        return;
+    };
+    view.render(&mut pixmap, resources);
+    let Some(bounds) = drawn_bounds(&pixmap) else {
+        return;
+    };
+    let Some(png) = pixmap
+        .clone_rect(bounds)
+        .and_then(|image| image.encode_png().ok())
+    else {
+        return;
+    };
+    let _ = write!(
+        svg,
+        r#"<image x="{}" y="{}" width="{}" height="{}" href="data:image/png;base64,{}"/>"#,
+        bounds.x(),
+        bounds.y(),
+        bounds.width(),
+        bounds.height(),
+        base64(&png)
+    );
+}
+
+/// Get the smallest rect that contains all the pixels that aren't
+/// transparent.
+fn drawn_bounds(pixmap: &Pixmap) -> Option<IntRect> {
+    let width = pixmap.width() as usize;
+    let (mut left, mut top, mut right, mut bottom) = (usize::MAX, usize::MAX, 0, 0);
+    for (i, pixel) in pixmap.pixels().iter().enumerate() {
+        if pixel.alpha() == 0 {
+            continue;
+        }
+        let (x, y) = (i % width, i / width);
+        left = left.min(x);
+        top = top.min(y);
+        right = right.max(x + 1);
+        bottom = bottom.max(y + 1);
+    }
+    IntRect::from_ltrb(left as i32, top as i32, right as i32, bottom as i32)
+}
+
+/// Encode bytes as standard base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - i * 8));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => encoded.push(ALPHABET[(n >> (18 - i * 6)) as usize & 63] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::style::CornerRadius;
+    use crate::view::RectView;
+    use agape_core::Position;
+
+    #[test]
+    fn encode_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn export_rects_and_layers() {
+        let mut list = DisplayList::new();
+        list.push(DrawCommand::PushLayer(Layer {
+            opacity: 0.5,
+            blur: 4.0,
+            ..Default::default()
+        }));
+        list.push(DrawCommand::FillRect {
+            position: Position::new(10.0, 20.0),
+            size: Size::new(30.0, 40.0),
+            corner_radius: CornerRadius::default(),
+            color: Color::rgba(255, 0, 0, 50),
+        });
+        list.push(DrawCommand::PopLayer);
+
+        let svg = list.to_svg(Size::new(100.0, 100.0), &Resources::new());
+        assert!(svg.contains(r#"<feGaussianBlur stdDeviation="2"/>"#));
+        assert!(svg.contains(r#"<g opacity="0.5" filter="url(#blur0)">"#));
+        assert!(svg.contains(
+            r#"<rect x="10" y="20" width="30" height="40" fill="rgb(255,0,0)" fill-opacity="0.5"/>"#
+        ));
+        assert!(svg.ends_with("</g></svg>"));
+    }
+
+    #[test]
+    fn embed_views_as_images() {
+        let view = RectView {
+            position: Position::new(10.0, 10.0),
+            size: Size::unit(20.0),
+            color: Color::BLACK,
+            ..Default::default()
+        };
+        let mut list = DisplayList::new();
+        list.push(DrawCommand::View(Box::new(view)));
+
+        let svg = list.to_svg(Size::new(100.0, 100.0), &Resources::new());
+        assert!(svg.contains(
+            r#"<image x="10" y="10" width="20" height="20" href="data:image/png;base64,"#
+        ));
+    }
+}
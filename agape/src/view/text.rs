@@ -1,10 +1,18 @@
 use super::View;
+use super::fallback::{FallbackFont, font_fallback};
+use super::font_registry::find_font;
+use super::glyph::{Glyph, GlyphCache, draw_glyph};
+use super::placed_text::PlacedText;
 use crate::FONT;
 use crate::Resources;
+use crate::style::{FontStyle, FontWeight, TextOverflow, TextWrap};
 use agape_core::{Color, GlobalId, Position, Rgba, Size};
-use fontdue::{Font, FontSettings};
+use fontdue::{Font, FontSettings, Metrics};
 use std::default::Default;
-use tiny_skia::{IntSize, Pixmap, PixmapPaint, Transform};
+use std::fmt;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock};
+use tiny_skia::Pixmap;
 
 pub struct TextView {
     id: GlobalId,
@@ -13,6 +21,15 @@ pub struct TextView {
     foreground_color: Color<Rgba>,
     text: String,
     pub font_size: u8,
+    /// The family of a font loaded with
+    /// [`Resources::load_font`](crate::Resources::load_font).
+    pub font_family: Option<String>,
+    pub wrap: TextWrap,
+    /// The maximum number of lines, the lines after it are cut off.
+    pub max_lines: Option<usize>,
+    pub overflow: TextOverflow,
+    /// Keeps the placed characters of single line text between frames.
+    pub(crate) line_cache: Option<LineCache>,
 }
 
 impl Default for TextView {
@@ -31,24 +48,492 @@ impl TextView {
             foreground_color: Color::BLACK,
             text: text.to_owned(),
             font_size: 16,
+            font_family: None,
+            wrap: TextWrap::None,
+            max_lines: None,
+            overflow: TextOverflow::Clip,
+            line_cache: None,
         }
     }
 
+    pub fn set_foreground_color(&mut self, color: Color<Rgba>) {
+        self.foreground_color = color;
+    }
+
+    fn font(&self) -> &'static Font {
+        let family = self.font_family.as_deref();
+        resolve_font(family, FontWeight::Regular, FontStyle::Normal).0
+    }
+
     /// Get the total size of a string of text
     pub fn text_size(&self) -> Size {
+        self.shaped_line().size()
+    }
+
+    /// Get the characters of the text placed on a single line, only the
+    /// characters that changed are placed if the text has a [`LineCache`].
+    pub(crate) fn shaped_line(&self) -> Arc<ShapedLine> {
+        let (font, font_size) = (self.font(), self.font_size as f32);
+        match &self.line_cache {
+            Some(cache) if self.is_single_line() => cache.get(&self.text, font, font_size),
+            _ => Arc::new(ShapedLine::new(&self.text, font, font_size)),
+        }
+    }
+
+    /// Returns `true` if the text is drawn on a single line, without being
+    /// wrapped or truncated.
+    fn is_single_line(&self) -> bool {
+        self.wrap == TextWrap::None
+            && self.max_lines.is_none()
+            && self.overflow == TextOverflow::Clip
+    }
+
+    /// Get the size of the text when it's given `max_width` pixels, or on
+    /// unwrapped lines if the width is `None`.
+    pub fn wrapped_size(&self, max_width: Option<f32>) -> Size {
+        if self.is_single_line() {
+            return self.text_size();
+        }
+
+        let font = self.font();
+        let font_size = self.font_size as f32;
+        let lines = self.lines(max_width);
+        let width = lines
+            .iter()
+            .map(|line| place_chars(line, font, font_size).1)
+            .fold(0.0, f32::max);
+        Size::new(width, self.line_height() * lines.len() as f32)
+    }
+
+    fn line_height(&self) -> f32 {
         let font_size = self.font_size as f32;
+        self.font()
+            .horizontal_line_metrics(font_size)
+            .map(|metrics| metrics.new_line_size.ceil())
+            .unwrap_or(font_size)
+    }
+
+    /// Break the text into the lines that are drawn when it's given
+    /// `max_width` pixels, applying the maximum number of lines and the
+    /// overflow.
+    pub fn lines(&self, max_width: Option<f32>) -> Vec<String> {
+        let font = self.font();
+        let font_size = self.font_size as f32;
+        let width_of = |text: &str| place_chars(text, font, font_size).1;
+
+        let mut lines = vec![];
+        for paragraph in self.text.split('\n') {
+            match max_width {
+                Some(max_width) if self.wrap != TextWrap::None => {
+                    wrap_paragraph(paragraph, max_width, self.wrap, &width_of, &mut lines);
+                }
+                _ => lines.push(paragraph.to_owned()),
+            }
+        }
+
+        let truncated = match self.max_lines {
+            Some(max_lines) if lines.len() > max_lines => {
+                lines.truncate(max_lines);
+                true
+            }
+            _ => false,
+        };
+        if self.overflow == TextOverflow::Ellipsis {
+            if let Some(last) = lines.last_mut() {
+                let overflows = max_width.is_some_and(|max_width| width_of(last) > max_width);
+                if truncated || overflows {
+                    *last = ellipsize(last, max_width, &width_of);
+                }
+            }
+        }
+        lines
+    }
+
+    /// Break the text into lines and place its characters the same way
+    /// they're drawn when the text is given `max_width` pixels.
+    pub fn placed_text(&self, max_width: Option<f32>) -> PlacedText {
+        let line_height = self.line_height();
+        let extents = |start: usize, text: &str, chars: &[PlacedChar]| {
+            visible_chars(text)
+                .zip(chars)
+                .map(|(index, placed)| (start + index, placed.x, placed.end()))
+                .collect::<Vec<_>>()
+        };
+        if self.is_single_line() {
+            let line = self.shaped_line();
+            let chars = extents(0, &self.text, &line.chars);
+            return PlacedText::new(vec![(0..self.text.len(), chars)], line_height);
+        }
+
+        let (font, font_size) = (self.font(), self.font_size as f32);
+        let mut start = 0;
+        let mut lines = vec![];
+        for (row, line) in self.lines(max_width).into_iter().enumerate() {
+            if row > 0 {
+                // Skip the spaces that wrapped lines are trimmed by, and the
+                // line break between paragraphs
+                let rest = &self.text[start..];
+                let trimmed = rest.trim_start_matches(|c: char| c != '\n' && c.is_whitespace());
+                start += rest.len() - trimmed.len();
+                if trimmed.starts_with('\n') {
+                    start += 1;
+                }
+            }
+            // Ellipsized lines end with text that isn't in the string
+            let rest = &self.text[start..];
+            let content = match rest.starts_with(&line) {
+                true => line.as_str(),
+                false => line.strip_suffix('…').unwrap_or(&line),
+            };
+            let (chars, _) = place_chars(&line, font, font_size);
+            lines.push((
+                start..start + content.len(),
+                extents(start, content, &chars),
+            ));
+            start += content.len();
+        }
+        PlacedText::new(lines, line_height)
+    }
+
+    /// Draw a line of placed characters, skipping the glyphs past `clip`
+    /// pixels.
+    fn draw_line(
+        &self,
+        pixmap: &mut Pixmap,
+        cache: &GlyphCache,
+        chars: &[PlacedChar],
+        baseline: f32,
+        clip: Option<f32>,
+    ) {
+        let font_size = self.font_size as f32;
+        for placed in chars {
+            let glyph = placed.font.glyph(cache, placed.character, font_size);
+            let metrics = glyph.metrics;
+            if clip.is_some_and(|clip| placed.x + metrics.advance_width > clip) {
+                break;
+            }
+            // Place each glyph on the baseline
+            let x = self.position.x + placed.x;
+            let glyph_x = (x + metrics.xmin as f32).round() as i32;
+            let glyph_y = (baseline - metrics.height as f32 - metrics.ymin as f32).round() as i32;
+            draw_glyph(
+                pixmap,
+                &glyph,
+                glyph_x,
+                glyph_y,
+                &self.foreground_color,
+                0.0,
+            );
+        }
+    }
+}
+
+/// Break a paragraph into lines that fit in `max_width`.
+fn wrap_paragraph(
+    text: &str,
+    max_width: f32,
+    wrap: TextWrap,
+    width_of: &impl Fn(&str) -> f32,
+    lines: &mut Vec<String>,
+) {
+    let tokens: Vec<&str> = match wrap {
+        TextWrap::Char => text.split_inclusive(|_| true).collect(),
+        _ => text.split_inclusive(' ').collect(),
+    };
+
+    let mut line = String::new();
+    for token in tokens {
+        let candidate = format!("{line}{token}");
+        if width_of(candidate.trim_end()) <= max_width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(line.trim_end().to_owned());
+            line.clear();
+        }
 
-        let mut width = 0.0;
-        let mut height = 0.0;
-        for c in self.text.chars() {
-            let metrics = FONT.get().unwrap().metrics(c, font_size);
-            width += metrics.advance_width;
-            if metrics.height as f32 > height {
-                height = metrics.height as f32;
+        // Words that don't fit on a line of their own are broken between
+        // characters
+        for c in token.chars() {
+            let candidate = format!("{line}{c}");
+            if !line.is_empty() && width_of(candidate.trim_end()) > max_width {
+                lines.push(std::mem::replace(&mut line, c.to_string()));
+            } else {
+                line = candidate;
             }
         }
+    }
+    lines.push(line.trim_end().to_owned());
+}
+
+/// Shorten a line until it fits in `max_width` with an ellipsis at the end.
+fn ellipsize(line: &str, max_width: Option<f32>, width_of: &impl Fn(&str) -> f32) -> String {
+    let mut text = line.trim_end().to_owned();
+    loop {
+        let candidate = format!("{text}…");
+        let fits = max_width.is_none_or(|max_width| width_of(&candidate) <= max_width);
+        if fits || text.is_empty() {
+            return candidate;
+        }
+        text.pop();
+        text.truncate(text.trim_end().len());
+    }
+}
+
+/// The font a character is drawn with.
+#[derive(Debug, Clone)]
+pub(crate) enum GlyphFont {
+    Bundled(&'static Font),
+    /// A font from the fallback chain, for characters the bundled font
+    /// doesn't have.
+    Fallback(Arc<FallbackFont>),
+}
+
+impl GlyphFont {
+    pub fn font(&self) -> &Font {
+        match self {
+            GlyphFont::Bundled(font) => font,
+            GlyphFont::Fallback(fallback) => &fallback.font,
+        }
+    }
 
-        Size::new(width, height)
+    pub fn glyph(&self, cache: &GlyphCache, character: char, font_size: f32) -> Rc<Glyph> {
+        match self {
+            GlyphFont::Bundled(font) => cache.get(font, character, font_size),
+            GlyphFont::Fallback(fallback) => cache.get_fallback(fallback, character, font_size),
+        }
+    }
+}
+
+/// A character placed along a line of text.
+#[derive(Debug, Clone)]
+pub(crate) struct PlacedChar {
+    pub character: char,
+    pub font: GlyphFont,
+    /// The distance from the start of the line.
+    pub x: f32,
+    pub metrics: Metrics,
+}
+
+impl PlacedChar {
+    /// Get the distance from the start of the line to the end of the
+    /// character.
+    fn end(&self) -> f32 {
+        self.x + self.metrics.advance_width
+    }
+}
+
+/// Returns `true` for characters that only change the characters around
+/// them, like the joiners and variation selectors in emoji sequences.
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200D}' | '\u{FE00}'..='\u{FE0F}')
+}
+
+/// Get the byte indices of the characters in `text` that are placed.
+fn visible_chars(text: &str) -> impl Iterator<Item = usize> + '_ {
+    text.char_indices()
+        .filter(|(_, c)| !is_invisible(*c))
+        .map(|(index, _)| index)
+}
+
+/// Place the characters of a line of text one after another, returning
+/// them with the width of the line. Characters that `font` doesn't have
+/// are taken from the [`FontFallback`](super::FontFallback).
+pub(crate) fn place_chars(
+    text: &str,
+    font: &'static Font,
+    font_size: f32,
+) -> (Vec<PlacedChar>, f32) {
+    let mut chars: Vec<PlacedChar> = vec![];
+    let mut x = 0.0;
+    for c in text.chars().filter(|c| !is_invisible(*c)) {
+        let placed = place_char(c, chars.last(), x, font, font_size);
+        x = placed.end();
+        chars.push(placed);
+    }
+    (chars, x)
+}
+
+/// Place a character at `x`, after the `previous` character.
+fn place_char(
+    c: char,
+    previous: Option<&PlacedChar>,
+    x: f32,
+    font: &'static Font,
+    font_size: f32,
+) -> PlacedChar {
+    let glyph_font = match font.has_glyph(c) || c.is_control() {
+        true => None,
+        false => font_fallback().font_for(c),
+    };
+    let glyph_font = match glyph_font {
+        Some(fallback) => GlyphFont::Fallback(fallback),
+        None => GlyphFont::Bundled(font),
+    };
+    let kern = previous.map_or(0.0, |previous| kerning(previous, c, &glyph_font, font_size));
+    let metrics = glyph_font.font().metrics(c, font_size);
+    PlacedChar {
+        character: c,
+        font: glyph_font,
+        x: x + kern,
+        metrics,
+    }
+}
+
+fn kerning(previous: &PlacedChar, c: char, font: &GlyphFont, font_size: f32) -> f32 {
+    // Kerning only applies between characters of the same font
+    if previous.font.font().file_hash() != font.font().file_hash() {
+        return 0.0;
+    }
+    font.font()
+        .horizontal_kern(previous.character, c, font_size)
+        .unwrap_or(0.0)
+}
+
+/// A line of text placed by [`place_chars`], which can be placed again
+/// after an edit without placing the characters that didn't change.
+#[derive(Debug, Clone)]
+pub(crate) struct ShapedLine {
+    text: String,
+    font: &'static Font,
+    font_size: f32,
+    pub chars: Vec<PlacedChar>,
+    pub width: f32,
+}
+
+impl ShapedLine {
+    pub fn new(text: &str, font: &'static Font, font_size: f32) -> Self {
+        let (chars, width) = place_chars(text, font, font_size);
+        Self {
+            text: text.to_owned(),
+            font,
+            font_size,
+            chars,
+            width,
+        }
+    }
+
+    /// Returns `true` if the line is the `text` placed with the font.
+    fn is_placed(&self, text: &str, font: &Font, font_size: f32) -> bool {
+        self.text == text
+            && self.font_size == font_size
+            && self.font.file_hash() == font.file_hash()
+    }
+
+    /// Place the line again for `text`. The characters before and after
+    /// the part that changed are kept, the ones after it are moved by the
+    /// change in width. Returns the number of characters that were placed.
+    pub fn update(&mut self, text: &str, font: &'static Font, font_size: f32) -> usize {
+        if self.is_placed(text, font, font_size) {
+            return 0;
+        }
+        if self.font_size != font_size || self.font.file_hash() != font.file_hash() {
+            *self = Self::new(text, font, font_size);
+            return self.chars.len();
+        }
+
+        let new: Vec<char> = text.chars().filter(|c| !is_invisible(*c)).collect();
+        let prefix = self
+            .chars
+            .iter()
+            .zip(&new)
+            .take_while(|(placed, c)| placed.character == **c)
+            .count();
+        let suffix = self
+            .chars
+            .iter()
+            .rev()
+            .zip(new.iter().rev())
+            .take(self.chars.len().min(new.len()) - prefix)
+            .take_while(|(placed, c)| placed.character == **c)
+            .count();
+
+        let mut x = self.chars[..prefix].last().map_or(0.0, PlacedChar::end);
+        let mut middle: Vec<PlacedChar> = vec![];
+        for &c in &new[prefix..new.len() - suffix] {
+            let previous = middle.last().or(self.chars[..prefix].last());
+            let placed = place_char(c, previous, x, font, font_size);
+            x = placed.end();
+            middle.push(placed);
+        }
+
+        let end = self.chars.len() - suffix;
+        match self.chars.get(end) {
+            Some(next) => {
+                let previous = middle.last().or(self.chars[..prefix].last());
+                let kern = previous.map_or(0.0, |previous| {
+                    kerning(previous, next.character, &next.font, font_size)
+                });
+                let shift = x + kern - next.x;
+                for placed in &mut self.chars[end..] {
+                    placed.x += shift;
+                }
+                self.width += shift;
+            }
+            None => self.width = x,
+        }
+
+        let count = middle.len();
+        self.chars.splice(prefix..end, middle);
+        self.text = text.to_owned();
+        count
+    }
+
+    /// Get the size of the line.
+    pub fn size(&self) -> Size {
+        let height = self
+            .chars
+            .iter()
+            .map(|placed| placed.metrics.height)
+            .max()
+            .unwrap_or(0);
+        Size::new(self.width, height as f32)
+    }
+
+    /// Get the width of the text up to a byte index.
+    pub fn width_to(&self, end: usize) -> f32 {
+        let count = self.text[..end]
+            .chars()
+            .filter(|c| !is_invisible(*c))
+            .count();
+        count
+            .checked_sub(1)
+            .map_or(0.0, |index| self.chars[index].end())
+    }
+}
+
+/// Keeps the [`ShapedLine`] of a text widget between frames, so that
+/// typing in long text doesn't place the whole line again. Clones share
+/// the same line, and the cache is ignored when comparing widgets.
+#[derive(Default, Clone)]
+pub(crate) struct LineCache(Arc<Mutex<Option<Arc<ShapedLine>>>>);
+
+impl LineCache {
+    /// Get the placed `text`, placing only the characters that changed
+    /// since the last call.
+    pub fn get(&self, text: &str, font: &'static Font, font_size: f32) -> Arc<ShapedLine> {
+        let mut cached = self.0.lock().unwrap();
+        match cached.as_mut() {
+            Some(line) if line.is_placed(text, font, font_size) => {}
+            Some(line) => {
+                Arc::make_mut(line).update(text, font, font_size);
+            }
+            None => *cached = Some(Arc::new(ShapedLine::new(text, font, font_size))),
+        }
+        cached.clone().unwrap()
+    }
+}
+
+impl PartialEq for LineCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for LineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LineCache")
     }
 }
 
@@ -61,6 +546,10 @@ impl View for TextView {
         &self.foreground_color
     }
 
+    fn foreground_color(&self) -> Option<&Color<Rgba>> {
+        Some(&self.foreground_color)
+    }
+
     fn set_position(&mut self, position: Position) {
         self.position = position;
     }
@@ -73,56 +562,40 @@ impl View for TextView {
         self.id = id
     }
 
-    fn render(&self, pixmap: &mut Pixmap, _resources: &Resources) {
-        let font = FONT.get().unwrap();
-        let font_size = 16.0;
-
-        let mut x_pos: i32 = self.position.x as i32;
-        let y_pos: i32 = self.position.y as i32;
-
-        let font_metrics = font.horizontal_line_metrics(font_size).unwrap();
-        let ascent = font_metrics.ascent.round() as i32;
-
-        // Draw each character onto a pixmap then
-        // draw that pixmap onto the root pixmap
-        for c in self.text.chars() {
-            let (metrics, bitmap) = font.rasterize(c, font_size);
-
-            // Skip spaces to avoid panicking
-            if metrics.width == 0 {
-                x_pos += metrics.advance_width as i32;
-                continue;
-            }
-
-            // This will make every character lie on the baseline
-            let y_pos: i32 = y_pos + ascent - metrics.height as i32;
-            x_pos += metrics.xmin;
-            let size = IntSize::from_wh(metrics.width as u32, metrics.height as u32).unwrap();
-
-            let mut colors = vec![];
-
-            for a in bitmap.iter() {
-                colors.push(0);
-                colors.push(0);
-                colors.push(0);
-                colors.push(*a);
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let font = self.font();
+        let font_size = self.font_size as f32;
+        // Fall back to a temporary cache if there isn't a shared one
+        let local_cache;
+        let cache = match resources.get::<GlyphCache>() {
+            Some(cache) => cache,
+            None => {
+                local_cache = GlyphCache::new();
+                &local_cache
             }
+        };
 
-            let glyph_pixmap = Pixmap::from_vec(colors, size).unwrap();
-            let paint = PixmapPaint::default();
+        let Some(line_metrics) = font.horizontal_line_metrics(font_size) else {
+            return;
+        };
+        let baseline = self.position.y + line_metrics.ascent.round();
 
-            pixmap.draw_pixmap(
-                x_pos,
-                y_pos,
-                glyph_pixmap.as_ref(),
-                &paint,
-                Transform::default(),
-                None,
-            );
+        if self.is_single_line() {
+            let line = self.shaped_line();
+            self.draw_line(pixmap, cache, &line.chars, baseline, None);
+            return;
+        }
 
-            // Move the cursor to the next character
-            // This is essentially letter spacing
-            x_pos += metrics.advance_width.round() as i32;
+        // Lines that aren't wrapped are cut off at the edge of the text
+        let clip = match self.wrap {
+            TextWrap::None => Some(self.size.width),
+            _ => None,
+        };
+        let line_height = self.line_height();
+        for (index, line) in self.lines(Some(self.size.width)).iter().enumerate() {
+            let baseline = baseline + index as f32 * line_height;
+            let (chars, _) = place_chars(line, font, font_size);
+            self.draw_line(pixmap, cache, &chars, baseline, clip);
         }
     }
 }
@@ -132,6 +605,44 @@ pub fn init_font() -> Font {
     Font::from_bytes(bytes, FontSettings::default()).unwrap()
 }
 
+/// Get the font for a family, weight and style, and whether the style has
+/// to be synthesized because the font doesn't have it. Text without a
+/// family, or with a family that wasn't loaded, uses the bundled font.
+pub(crate) fn resolve_font(
+    family: Option<&str>,
+    weight: FontWeight,
+    style: FontStyle,
+) -> (&'static Font, bool) {
+    if let Some(found) = family.and_then(|family| find_font(family, weight, style)) {
+        let (font, has_style) = found;
+        return (font, !has_style);
+    }
+    (font(weight), style != FontStyle::Normal)
+}
+
+/// Get the bundled font for a weight, the fonts other than the regular one
+/// are loaded the first time they're used.
+pub(crate) fn font(weight: FontWeight) -> &'static Font {
+    static FONTS: [OnceLock<Font>; 4] = [const { OnceLock::new() }; 4];
+    let (index, bytes): (usize, &[u8]) = match weight {
+        FontWeight::Regular => return FONT.get().unwrap(),
+        FontWeight::Light => (
+            0,
+            include_bytes!("../../fonts/Inter/static/Inter-Light.ttf"),
+        ),
+        FontWeight::Medium => (
+            1,
+            include_bytes!("../../fonts/Inter/static/Inter-Medium.ttf"),
+        ),
+        FontWeight::SemiBold => (
+            2,
+            include_bytes!("../../fonts/Inter/static/Inter-SemiBold.ttf"),
+        ),
+        FontWeight::Bold => (3, include_bytes!("../../fonts/Inter/static/Inter-Bold.ttf")),
+    };
+    FONTS[index].get_or_init(|| Font::from_bytes(bytes, FontSettings::default()).unwrap())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -144,4 +655,110 @@ mod test {
         let mut pixmap = Pixmap::new(500, 500).unwrap();
         view.render(&mut pixmap, &resources);
     }
+
+    #[test]
+    fn cache_glyphs() {
+        let _ = FONT.set(init_font());
+        let mut resources = Resources::new();
+        resources.insert(GlyphCache::new());
+
+        let view = TextView::new("Hello");
+        let mut pixmap = Pixmap::new(100, 50).unwrap();
+        view.render(&mut pixmap, &resources);
+
+        // The two l's share a glyph
+        assert_eq!(resources.get::<GlyphCache>().unwrap().len(), 4);
+        assert!(pixmap.pixels().iter().any(|pixel| pixel.alpha() != 0));
+    }
+
+    #[test]
+    fn skip_invisible_characters() {
+        let _ = FONT.set(init_font());
+        let font = FONT.get().unwrap();
+        let (chars, width) = place_chars("a\u{200D}b\u{FE0F}", font, 16.0);
+        assert_eq!(chars.len(), 2);
+        assert_eq!(width, place_chars("ab", font, 16.0).1);
+    }
+
+    fn assert_placed(line: &ShapedLine, text: &str) {
+        let (chars, width) = place_chars(text, FONT.get().unwrap(), 16.0);
+        assert!((line.width - width).abs() < 0.01);
+        assert_eq!(line.chars.len(), chars.len());
+        for (placed, expected) in line.chars.iter().zip(&chars) {
+            assert_eq!(placed.character, expected.character);
+            assert!((placed.x - expected.x).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn place_only_edited_chars() {
+        let _ = FONT.set(init_font());
+        let font = FONT.get().unwrap();
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(20);
+        let mut line = ShapedLine::new(&text, font, 16.0);
+
+        let mut edited = text.clone();
+        edited.insert(100, 'W');
+        assert_eq!(line.update(&edited, font, 16.0), 1);
+        assert_placed(&line, &edited);
+
+        edited.remove(10);
+        assert_eq!(line.update(&edited, font, 16.0), 0);
+        assert_placed(&line, &edited);
+
+        edited.push_str("AV");
+        assert_eq!(line.update(&edited, font, 16.0), 2);
+        assert_placed(&line, &edited);
+        assert_eq!(line.update(&edited, font, 16.0), 0);
+
+        assert_eq!(line.update("", font, 16.0), 0);
+        assert_placed(&line, "");
+    }
+
+    #[test]
+    fn width_to_byte_index() {
+        let _ = FONT.set(init_font());
+        let font = FONT.get().unwrap();
+        let line = ShapedLine::new("héllo wörld", font, 16.0);
+        let end = "héllo".len();
+        assert_eq!(line.width_to(end), place_chars("héllo", font, 16.0).1);
+        assert_eq!(line.width_to(0), 0.0);
+        assert_eq!(line.width_to("héllo wörld".len()), line.width);
+    }
+
+    #[test]
+    fn hit_test_single_line() {
+        let _ = FONT.set(init_font());
+        let view = TextView::new("héllo");
+        let placed = view.placed_text(None);
+        let width_of = |text| place_chars(text, FONT.get().unwrap(), 16.0).1;
+
+        assert_eq!(placed.hit_test(Position::new(-5.0, 0.0)), 0);
+        let after_e = width_of("hé") + 0.5;
+        assert_eq!(placed.hit_test(Position::new(after_e, 4.0)), "hé".len());
+        assert_eq!(placed.hit_test(Position::new(500.0, 4.0)), "héllo".len());
+        assert_eq!(placed.caret_rect("hé".len()).x[0], width_of("hé"));
+    }
+
+    #[test]
+    fn hit_test_wrapped_lines() {
+        let _ = FONT.set(init_font());
+        let mut view = TextView::new("one two\nthree");
+        view.wrap = TextWrap::Word;
+        let max_width = place_chars("one two", FONT.get().unwrap(), 16.0).1 - 1.0;
+        let placed = view.placed_text(Some(max_width));
+        assert_eq!(placed.line_count(), 3);
+        let line_height = placed.size().height / 3.0;
+
+        // The start of each line
+        let start_of = |row: f32| placed.hit_test(Position::new(0.0, row * line_height + 1.0));
+        assert_eq!(start_of(0.0), 0);
+        assert_eq!(start_of(1.0), "one ".len());
+        assert_eq!(start_of(2.0), "one two\n".len());
+
+        let caret = placed.caret_rect("one two\nth".len());
+        assert_eq!(caret.y[0], line_height * 2.0);
+        // Selections are split at line breaks
+        assert_eq!(placed.selection_rects(2..10).len(), 3);
+    }
 }
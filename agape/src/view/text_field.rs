@@ -0,0 +1,159 @@
+use super::new_paint;
+use super::rect::rounded_rect_path;
+use super::{RectView, TextView, View, skia_color};
+use crate::Resources;
+use agape_core::{Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, Pixmap, Rect, Stroke, Transform};
+
+const TOKEN_COLOR: Color<Rgba> = Color::rgb(219, 234, 254);
+const POPUP_COLOR: Color<Rgba> = Color::WHITE;
+const POPUP_BORDER: Color<Rgba> = Color::rgb(210, 210, 210);
+const SELECTED_COLOR: Color<Rgba> = Color::rgb(229, 231, 235);
+const ROW_HEIGHT: f32 = 28.0;
+const POPUP_WIDTH: f32 = 180.0;
+
+/// The suggestions shown below a [`TextField`](crate::widgets::TextField)
+/// while a completion is typed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionPopup {
+    /// Where the trigger character starts, relative to the text field.
+    pub x: f32,
+    pub items: Vec<String>,
+    pub selected: usize,
+}
+
+/// Draws a [`TextField`](crate::widgets::TextField), with the background of
+/// its tokens and the suggestion popup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextFieldView {
+    pub rect: RectView,
+    /// The start and width of each token, relative to the text field.
+    pub tokens: Vec<(f32, f32)>,
+    pub popup: Option<CompletionPopup>,
+}
+
+impl TextFieldView {
+    pub fn new(rect: RectView) -> Self {
+        Self {
+            rect,
+            tokens: vec![],
+            popup: None,
+        }
+    }
+
+    fn fill(&self, pixmap: &mut Pixmap, rect: Option<Rect>, color: &Color<Rgba>, border: bool) {
+        let Some(path) = rect.and_then(|rect| rounded_rect_path(rect, 4.0)) else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(color));
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+        if border {
+            paint.set_color(skia_color(&POPUP_BORDER));
+            pixmap.stroke_path(
+                &path,
+                &paint,
+                &Stroke::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn draw_popup(&self, pixmap: &mut Pixmap, resources: &Resources, popup: &CompletionPopup) {
+        if popup.items.is_empty() {
+            return;
+        }
+        let Position { x, y } = self.rect.position;
+        let left = x + popup.x;
+        let top = y + self.rect.size.height + 4.0;
+        let height = popup.items.len() as f32 * ROW_HEIGHT;
+        let background = Rect::from_xywh(left, top, POPUP_WIDTH, height);
+        self.fill(pixmap, background, &POPUP_COLOR, true);
+
+        for (index, item) in popup.items.iter().enumerate() {
+            let row_top = top + index as f32 * ROW_HEIGHT;
+            if index == popup.selected {
+                let rect = Rect::from_xywh(left + 2.0, row_top + 2.0, POPUP_WIDTH - 4.0, 24.0);
+                self.fill(pixmap, rect, &SELECTED_COLOR, false);
+            }
+            let mut text = TextView::new(item);
+            text.font_size = 14;
+            let size = text.text_size();
+            text.set_size(size);
+            text.set_position(Position::new(
+                left + 8.0,
+                row_top + ROW_HEIGHT / 2.0 - size.height / 2.0,
+            ));
+            text.render(pixmap, resources);
+        }
+    }
+}
+
+impl View for TextFieldView {
+    fn id(&self) -> GlobalId {
+        self.rect.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        self.rect.color()
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.rect.set_id(id)
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.rect.set_position(position)
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.rect.set_size(size)
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        self.rect.render(pixmap, resources);
+
+        let Position { x, y } = self.rect.position;
+        for (start, width) in &self.tokens {
+            let rect = Rect::from_xywh(x + start, y, *width, self.rect.size.height);
+            self.fill(pixmap, rect, &TOKEN_COLOR, false);
+        }
+
+        if let Some(popup) = &self.popup {
+            self.draw_popup(pixmap, resources, popup);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FONT;
+    use crate::view::init_font;
+
+    #[test]
+    fn draw_tokens_and_popup() {
+        let _ = FONT.set(init_font());
+        let mut view = TextFieldView::new(RectView::new(GlobalId::new()));
+        view.set_size(Size::new(200.0, 20.0));
+        view.tokens = vec![(10.0, 30.0)];
+        view.popup = Some(CompletionPopup {
+            x: 0.0,
+            items: vec![String::from("alice")],
+            selected: 0,
+        });
+
+        let mut pixmap = Pixmap::new(200, 60).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+        assert_ne!(pixmap.pixel(20, 10).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(5, 10).unwrap().alpha(), 0);
+        assert_ne!(pixmap.pixel(150, 30).unwrap().alpha(), 0);
+    }
+}
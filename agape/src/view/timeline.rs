@@ -0,0 +1,212 @@
+use super::new_paint;
+use super::rect::rounded_rect_path;
+use super::{TextView, View, skia_color};
+use crate::Resources;
+use crate::chart::Axis;
+use crate::widgets::TimelineBar;
+use agape_core::{Bounds, Color, GlobalId, Position, Rgba, Size};
+use tiny_skia::{FillRule, Mask, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// The height of the time axis above the rows.
+pub(crate) const HEADER_HEIGHT: f32 = 24.0;
+/// The space between a bar and the edges of its row.
+const BAR_INSET: f32 = 4.0;
+const GRID_COLOR: Color<Rgba> = Color::rgb(225, 225, 225);
+
+/// The sizes used to place the rows and bars of a timeline, relative to its
+/// top left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TimelineMetrics {
+    pub width: f32,
+    pub label_width: f32,
+    pub row_height: f32,
+    /// The visible range of time.
+    pub range: [f32; 2],
+}
+
+impl TimelineMetrics {
+    /// Get the number of pixels in one unit of time.
+    pub fn scale(&self) -> f32 {
+        let span = self.range[1] - self.range[0];
+        if span <= 0.0 {
+            return 0.0;
+        }
+        (self.width - self.label_width).max(0.0) / span
+    }
+
+    pub fn time_to_x(&self, time: f32) -> f32 {
+        self.label_width + (time - self.range[0]) * self.scale()
+    }
+
+    /// Get the bounds of a bar.
+    pub fn bar_bounds(&self, bar: &TimelineBar) -> Bounds {
+        let top = HEADER_HEIGHT + bar.row as f32 * self.row_height + BAR_INSET;
+        Bounds {
+            x: [self.time_to_x(bar.start), self.time_to_x(bar.end)],
+            y: [top, top + self.row_height - BAR_INSET * 2.0],
+        }
+    }
+}
+
+/// Draws a [`Timeline`](crate::widgets::Timeline).
+#[derive(Debug, Clone)]
+pub struct TimelineView {
+    pub id: GlobalId,
+    pub position: Position,
+    pub size: Size,
+    pub rows: Vec<String>,
+    pub bars: Vec<TimelineBar>,
+    pub axis: Axis,
+    pub label_width: f32,
+    pub row_height: f32,
+}
+
+impl TimelineView {
+    pub(crate) fn metrics(&self) -> TimelineMetrics {
+        TimelineMetrics {
+            width: self.size.width,
+            label_width: self.label_width,
+            row_height: self.row_height,
+            range: self.axis.range,
+        }
+    }
+
+    fn draw_grid(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let metrics = self.metrics();
+        let (x, y) = (self.position.x, self.position.y);
+        let bottom = y + self.size.height;
+
+        let mut builder = PathBuilder::new();
+        for row in 0..=self.rows.len() {
+            let row_y = y + HEADER_HEIGHT + row as f32 * self.row_height;
+            builder.move_to(x, row_y);
+            builder.line_to(x + self.size.width, row_y);
+        }
+
+        for tick in self.axis.ticks() {
+            let tick_x = x + metrics.time_to_x(tick);
+            builder.move_to(tick_x, y + HEADER_HEIGHT);
+            builder.line_to(tick_x, bottom);
+            draw_text(pixmap, resources, &self.axis.format(tick), |size| {
+                Position::new(
+                    tick_x - size.width / 2.0,
+                    y + HEADER_HEIGHT / 2.0 - size.height / 2.0,
+                )
+            });
+        }
+
+        for (row, label) in self.rows.iter().enumerate() {
+            let row_y = y + HEADER_HEIGHT + row as f32 * self.row_height;
+            draw_text(pixmap, resources, label, |size| {
+                Position::new(x + 8.0, row_y + self.row_height / 2.0 - size.height / 2.0)
+            });
+        }
+
+        let Some(path) = builder.finish() else {
+            return;
+        };
+        let mut paint = new_paint();
+        paint.set_color(skia_color(&GRID_COLOR));
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &Stroke::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn draw_bars(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        let metrics = self.metrics();
+        // Clip the bars to the area right of the labels
+        let mut mask = Mask::new(pixmap.width(), pixmap.height());
+        let clip = Rect::from_xywh(
+            self.position.x + self.label_width,
+            self.position.y,
+            self.size.width - self.label_width,
+            self.size.height,
+        );
+        if let (Some(mask), Some(clip)) = (&mut mask, clip) {
+            let path = PathBuilder::from_rect(clip);
+            mask.fill_path(&path, FillRule::Winding, false, Transform::identity());
+        }
+
+        let mut paint = new_paint();
+        for bar in &self.bars {
+            let bounds = metrics.bar_bounds(bar);
+            let Some(rect) = Rect::from_ltrb(
+                self.position.x + bounds.x[0],
+                self.position.y + bounds.y[0],
+                self.position.x + bounds.x[1],
+                self.position.y + bounds.y[1],
+            ) else {
+                continue;
+            };
+            let Some(path) = rounded_rect_path(rect, 4.0) else {
+                continue;
+            };
+
+            paint.set_color(skia_color(&bar.color));
+            pixmap.fill_path(
+                &path,
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                mask.as_ref(),
+            );
+
+            // Only show labels that fit inside the bar
+            let mut text = TextView::new(&bar.label);
+            let size = text.text_size();
+            if size.width + 8.0 > rect.width() || rect.left() < self.position.x + self.label_width {
+                continue;
+            }
+            text.set_size(size);
+            text.set_position(Position::new(
+                rect.left() + 4.0,
+                rect.top() + rect.height() / 2.0 - size.height / 2.0,
+            ));
+            text.render(pixmap, resources);
+        }
+    }
+}
+
+fn draw_text(
+    pixmap: &mut Pixmap,
+    resources: &Resources,
+    text: &str,
+    position: impl FnOnce(Size) -> Position,
+) {
+    let mut view = TextView::new(text);
+    let size = view.text_size();
+    view.set_size(size);
+    view.set_position(position(size));
+    view.render(pixmap, resources);
+}
+
+impl View for TimelineView {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn color(&self) -> &Color<Rgba> {
+        &GRID_COLOR
+    }
+
+    fn set_id(&mut self, id: GlobalId) {
+        self.id = id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position
+    }
+
+    fn set_size(&mut self, size: Size) {
+        self.size = size
+    }
+
+    fn render(&self, pixmap: &mut Pixmap, resources: &Resources) {
+        self.draw_grid(pixmap, resources);
+        self.draw_bars(pixmap, resources);
+    }
+}
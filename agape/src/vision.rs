@@ -0,0 +1,230 @@
+//! Simulate color blindness, so that developers can check that their colors
+//! can still be told apart.
+//!
+//! The [`VisionFilter`] resource filters the finished frame. Ctrl+Alt+V
+//! cycles through the simulations in debug builds.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//! use agape::vision::ColorBlindness;
+//!
+//! let app = App::new(hstack! {}).simulate_color_blindness(ColorBlindness::Deuteranopia);
+//! ```
+use std::sync::OnceLock;
+use tiny_skia::{ColorU8, Pixmap};
+use winit::keyboard::{KeyCode, ModifiersState};
+
+/// A type of color blindness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorBlindness {
+    /// No red cones, reds look dark and are confused with greens.
+    Protanopia,
+    /// No green cones, the most common type, greens are confused with reds.
+    Deuteranopia,
+    /// No blue cones, blues are confused with greens and yellows with pinks.
+    Tritanopia,
+}
+
+impl ColorBlindness {
+    /// Get the matrix that simulates the color blindness in linear RGB, from
+    /// Machado, Oliveira and Fernandes (2009), at full severity.
+    fn matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            Self::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Self::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Self::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+/// Filters the frame to simulate a [`ColorBlindness`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisionFilter {
+    simulation: Option<ColorBlindness>,
+    /// Whether the keyboard shortcut cycles through the simulations.
+    shortcut: bool,
+    modifiers: ModifiersState,
+}
+
+impl Default for VisionFilter {
+    fn default() -> Self {
+        Self {
+            simulation: None,
+            shortcut: cfg!(debug_assertions),
+            modifiers: ModifiersState::empty(),
+        }
+    }
+}
+
+impl VisionFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the color blindness that's being simulated.
+    pub fn get(&self) -> Option<ColorBlindness> {
+        self.simulation
+    }
+
+    /// Simulate a color blindness, or turn off the filter with `None`.
+    pub fn set(&mut self, simulation: Option<ColorBlindness>) {
+        self.simulation = simulation;
+    }
+
+    /// Move on to the next simulation, after tritanopia the filter is
+    /// turned off.
+    pub fn cycle(&mut self) {
+        self.simulation = match self.simulation {
+            None => Some(ColorBlindness::Protanopia),
+            Some(ColorBlindness::Protanopia) => Some(ColorBlindness::Deuteranopia),
+            Some(ColorBlindness::Deuteranopia) => Some(ColorBlindness::Tritanopia),
+            Some(ColorBlindness::Tritanopia) => None,
+        };
+        log::info!("Simulating color blindness: {:?}", self.simulation);
+    }
+
+    /// Turn the keyboard shortcut on or off, it's only on by default in
+    /// debug builds.
+    pub fn set_shortcut(&mut self, enabled: bool) {
+        self.shortcut = enabled;
+    }
+
+    pub(crate) fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    /// Handle the shortcut.
+    pub(crate) fn handle_key(&mut self, key: KeyCode) {
+        if !self.shortcut || !self.modifiers.control_key() || !self.modifiers.alt_key() {
+            return;
+        }
+        if key == KeyCode::KeyV {
+            self.cycle();
+        }
+    }
+
+    /// Filter the pixels that have been drawn to the `pixmap`.
+    pub(crate) fn apply(&self, pixmap: &mut Pixmap) {
+        let Some(simulation) = self.simulation else {
+            return;
+        };
+        let matrix = simulation.matrix();
+        let (to_linear, to_srgb) = tables();
+        for pixel in pixmap.pixels_mut() {
+            let color = pixel.demultiply();
+            if color.alpha() == 0 {
+                continue;
+            }
+            let rgb = [
+                to_linear[color.red() as usize],
+                to_linear[color.green() as usize],
+                to_linear[color.blue() as usize],
+            ];
+            let [red, green, blue] = matrix.map(|row| {
+                let value = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+                to_srgb[(value.clamp(0.0, 1.0) * LINEAR_STEPS) as usize]
+            });
+            *pixel = ColorU8::from_rgba(red, green, blue, color.alpha()).premultiply();
+        }
+    }
+}
+
+/// The number of steps in the linear to sRGB table.
+const LINEAR_STEPS: f32 = 4095.0;
+
+/// Get the tables that convert sRGB to linear values, and linear values back
+/// to sRGB.
+fn tables() -> &'static ([f32; 256], Vec<u8>) {
+    static TABLES: OnceLock<([f32; 256], Vec<u8>)> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let to_linear = std::array::from_fn(|i| {
+            let value = i as f32 / 255.0;
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        });
+        let to_srgb = (0..=LINEAR_STEPS as u32)
+            .map(|i| {
+                let value = i as f32 / LINEAR_STEPS;
+                let value = if value <= 0.0031308 {
+                    value * 12.92
+                } else {
+                    1.055 * value.powf(1.0 / 2.4) - 0.055
+                };
+                (value * 255.0).round() as u8
+            })
+            .collect();
+        (to_linear, to_srgb)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn filter(simulation: ColorBlindness, color: [u8; 3]) -> [u8; 3] {
+        let mut pixmap = Pixmap::new(1, 1).unwrap();
+        pixmap.fill(tiny_skia::Color::from_rgba8(
+            color[0], color[1], color[2], 255,
+        ));
+        let mut filter = VisionFilter::new();
+        filter.set(Some(simulation));
+        filter.apply(&mut pixmap);
+        let pixel = pixmap.pixel(0, 0).unwrap();
+        [pixel.red(), pixel.green(), pixel.blue()]
+    }
+
+    #[test]
+    fn keep_grays() {
+        for simulation in [
+            ColorBlindness::Protanopia,
+            ColorBlindness::Deuteranopia,
+            ColorBlindness::Tritanopia,
+        ] {
+            for gray in [0, 128, 255] {
+                let [red, green, blue] = filter(simulation, [gray; 3]);
+                for value in [red, green, blue] {
+                    assert!(value.abs_diff(gray) <= 1, "{simulation:?} changed {gray}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn confuse_red_and_green() {
+        let red = filter(ColorBlindness::Deuteranopia, [220, 40, 40]);
+        let green = filter(ColorBlindness::Deuteranopia, [80, 160, 40]);
+        // Both end up as a similar brownish yellow
+        assert!(red[0].abs_diff(red[1]) < 60);
+        assert!(green[0].abs_diff(green[1]) < 60);
+    }
+
+    #[test]
+    fn cycle_shortcut() {
+        let mut filter = VisionFilter::new();
+        filter.set_shortcut(true);
+        filter.modifiers_changed(ModifiersState::CONTROL | ModifiersState::ALT);
+        filter.handle_key(KeyCode::KeyV);
+        assert_eq!(filter.get(), Some(ColorBlindness::Protanopia));
+        filter.handle_key(KeyCode::KeyV);
+        filter.handle_key(KeyCode::KeyV);
+        assert_eq!(filter.get(), Some(ColorBlindness::Tritanopia));
+        filter.handle_key(KeyCode::KeyV);
+        assert_eq!(filter.get(), None);
+    }
+}
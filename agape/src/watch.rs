@@ -0,0 +1,249 @@
+//! Watch files and directories for changes made outside of the app, so that
+//! editors and file browsers can refresh their views.
+//!
+//! Watched paths are polled on the ui thread, and changes are only delivered
+//! once the files have settled, so a burst of writes is reported once.
+//!
+//! # Example
+//! ```
+//! use agape::{App, hstack};
+//! use agape::resources::Resources;
+//! use agape::watch::FileWatcher;
+//!
+//! let mut watching = false;
+//! let app = App::new(hstack! {}).add_system(move |resources: &mut Resources| {
+//!     if watching {
+//!         return;
+//!     }
+//!     watching = true;
+//!     let watcher = resources.get_mut::<FileWatcher>().unwrap();
+//!     watcher.watch("notes.md", |events| {
+//!         println!("{events:?}");
+//!     });
+//! });
+//! ```
+use crate::resources::Resources;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often watched paths are checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+type WatchFn = Box<dyn FnMut(&[FileEvent])>;
+
+/// A change to a watched file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FileEvent {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+}
+
+impl FileEvent {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Created(path) | Self::Modified(path) | Self::Removed(path) => path,
+        }
+    }
+}
+
+/// What a file looked like when it was last checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+type Snapshot = HashMap<PathBuf, Stamp>;
+
+struct Watch {
+    path: PathBuf,
+    /// The files when changes were last delivered.
+    delivered: Snapshot,
+    /// The files when they were last checked.
+    latest: Snapshot,
+    f: WatchFn,
+}
+
+/// Watches files and directories, directories are watched along with the
+/// files directly inside them.
+pub struct FileWatcher {
+    watches: Vec<Watch>,
+    last_poll: Instant,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self {
+            watches: vec![],
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Watch a path, `f` is called with the files that were created,
+    /// modified or removed. The path doesn't have to exist yet.
+    pub fn watch(&mut self, path: impl Into<PathBuf>, f: impl FnMut(&[FileEvent]) + 'static) {
+        let path = path.into();
+        let snapshot = scan(&path);
+        self.watches.push(Watch {
+            path,
+            delivered: snapshot.clone(),
+            latest: snapshot,
+            f: Box::new(f),
+        });
+    }
+
+    /// Stop watching a path.
+    pub fn unwatch(&mut self, path: impl AsRef<Path>) {
+        self.watches.retain(|watch| watch.path != path.as_ref());
+    }
+
+    pub fn is_watching(&self, path: impl AsRef<Path>) -> bool {
+        self.watches.iter().any(|watch| watch.path == path.as_ref())
+    }
+
+    /// Check the watched paths, changes are delivered once a check finds
+    /// that nothing changed since the last one.
+    fn poll(&mut self) {
+        for watch in &mut self.watches {
+            let current = scan(&watch.path);
+            if current != watch.latest {
+                watch.latest = current;
+                continue;
+            }
+            if watch.latest == watch.delivered {
+                continue;
+            }
+
+            let events = diff(&watch.delivered, &watch.latest);
+            watch.delivered = watch.latest.clone();
+            (watch.f)(&events);
+        }
+    }
+}
+
+/// Get the stamps of a file, or of the files directly inside a directory.
+fn scan(path: &Path) -> Snapshot {
+    let stamp = |path: &Path| {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Stamp {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    };
+
+    let mut snapshot = Snapshot::new();
+    if path.is_dir() {
+        let entries = fs::read_dir(path).into_iter().flatten().flatten();
+        for entry in entries {
+            let path = entry.path();
+            if let Some(stamp) = stamp(&path) {
+                snapshot.insert(path, stamp);
+            }
+        }
+    } else if let Some(stamp) = stamp(path) {
+        snapshot.insert(path.to_owned(), stamp);
+    }
+    snapshot
+}
+
+/// Get the changes between two snapshots, sorted by path.
+fn diff(old: &Snapshot, new: &Snapshot) -> Vec<FileEvent> {
+    let mut events: Vec<FileEvent> = new
+        .iter()
+        .filter_map(|(path, stamp)| match old.get(path) {
+            None => Some(FileEvent::Created(path.clone())),
+            Some(old) if old != stamp => Some(FileEvent::Modified(path.clone())),
+            Some(_) => None,
+        })
+        .chain(
+            old.keys()
+                .filter(|path| !new.contains_key(*path))
+                .map(|path| FileEvent::Removed(path.clone())),
+        )
+        .collect();
+    events.sort_by(|a, b| a.path().cmp(b.path()));
+    events
+}
+
+/// Polls the watched paths of the [`FileWatcher`].
+pub(crate) fn update_file_watcher(resources: &mut Resources) {
+    let Some(watcher) = resources.get_mut::<FileWatcher>() else {
+        return;
+    };
+    if watcher.watches.is_empty() || watcher.last_poll.elapsed() < POLL_INTERVAL {
+        return;
+    }
+    watcher.last_poll = Instant::now();
+    watcher.poll();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("agape-watch-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn deliver_settled_changes() {
+        let dir = temp_dir("settled");
+        let file = dir.join("notes.md");
+        fs::write(&file, "a").unwrap();
+
+        let events = Rc::new(RefCell::new(vec![]));
+        let received = Rc::clone(&events);
+        let mut watcher = FileWatcher::new();
+        watcher.watch(&dir, move |events| {
+            received.borrow_mut().extend_from_slice(events)
+        });
+
+        fs::write(&file, "abc").unwrap();
+        fs::write(dir.join("todo.md"), "").unwrap();
+        watcher.poll();
+        assert!(events.borrow().is_empty());
+
+        watcher.poll();
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                FileEvent::Modified(file.clone()),
+                FileEvent::Created(dir.join("todo.md"))
+            ]
+        );
+
+        events.borrow_mut().clear();
+        fs::remove_file(&file).unwrap();
+        watcher.poll();
+        watcher.poll();
+        assert_eq!(*events.borrow(), vec![FileEvent::Removed(file)]);
+    }
+
+    #[test]
+    fn unwatch() {
+        let dir = temp_dir("unwatch");
+        let mut watcher = FileWatcher::new();
+        watcher.watch(&dir, |_| panic!("The path isn't watched"));
+        assert!(watcher.is_watching(&dir));
+
+        watcher.unwatch(&dir);
+        fs::write(dir.join("file"), "").unwrap();
+        watcher.poll();
+        watcher.poll();
+        assert!(!watcher.is_watching(&dir));
+    }
+}
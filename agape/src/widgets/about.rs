@@ -0,0 +1,185 @@
+use crate::clipboard;
+use crate::diagnostics::Diagnostics;
+use crate::impl_style;
+use crate::style::BoxStyle;
+use crate::view::{RectView, View};
+use crate::widgets::{Button, Text, VStack, Widget};
+use agape_core::GlobalId;
+use agape_layout::{BlockLayout, Layout};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type CopyFn = Rc<RefCell<Option<Box<dyn FnMut(&str)>>>>;
+
+/// A dialog that shows information about the app and the environment it's
+/// running in, with a button for copying the [`Diagnostics`] report to the
+/// clipboard.
+///
+/// ```
+/// use agape::diagnostics::Diagnostics;
+/// use agape::widgets::About;
+///
+/// let diagnostics = Diagnostics::new().app("Notes","1.2.0");
+/// let about = About::new(diagnostics)
+///     .on_copy(|report| println!("{report}"));
+/// ```
+pub struct About {
+    id: GlobalId,
+    diagnostics: Diagnostics,
+    child: VStack,
+    copy_fn: CopyFn,
+    padding: u32,
+    style: BoxStyle,
+}
+
+impl About {
+    pub fn new(diagnostics: Diagnostics) -> Self {
+        let copy_fn: CopyFn = Rc::new(RefCell::new(None));
+        let report = diagnostics.report();
+
+        let callback = Rc::clone(&copy_fn);
+        let copy_button = Button::new(Text::new("Copy diagnostics")).on_click(move |_| {
+            copy_to_clipboard(&report);
+            if let Some(func) = callback.borrow_mut().as_mut() {
+                func(&report);
+            }
+        });
+
+        let mut child = VStack::new()
+            .spacing(8)
+            .add_child(Text::new(&format!(
+                "{} {}",
+                diagnostics.app_name, diagnostics.app_version
+            )))
+            .add_child(Text::new(&format!("agape {}", diagnostics.agape_version)))
+            .add_child(Text::new(&format!(
+                "Renderer: {} ({})",
+                diagnostics.renderer, diagnostics.renderer_kind
+            )))
+            .add_child(Text::new(&format!(
+                "OS: {} {}",
+                diagnostics.os, diagnostics.arch
+            )))
+            .add_child(Text::new(&format!(
+                "Scale factor: {}",
+                diagnostics.scale_factor
+            )));
+
+        if !diagnostics.errors.is_empty() {
+            child = child.add_child(Text::new("Recent errors:"));
+            for error in &diagnostics.errors {
+                child = child.add_child(Text::new(&format!("- {error}")));
+            }
+        }
+
+        Self {
+            id: GlobalId::new(),
+            child: child.add_child(copy_button),
+            diagnostics,
+            copy_fn,
+            padding: 16,
+            style: BoxStyle::new(),
+        }
+    }
+
+    /// Get the [`Diagnostics`] shown in the dialog.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Called with the diagnostics report when the copy button is clicked,
+    /// after it has been copied to the clipboard.
+    pub fn on_copy(self, callback: impl FnMut(&str) + 'static) -> Self {
+        *self.copy_fn.borrow_mut() = Some(Box::new(callback));
+        self
+    }
+
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+/// Copy text to the system clipboard, errors are logged since there's
+/// nothing else the dialog can do about them.
+fn copy_to_clipboard(text: &str) {
+    if let Err(err) = clipboard::set_text(text) {
+        log::error!("Failed to copy diagnostics to the clipboard: {err}");
+    }
+}
+
+impl_style!(About);
+
+impl Widget for About {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let view = RectView::with_style(self.id, &self.style);
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let child = self.child.layout();
+        let mut layout = BlockLayout::new(child);
+        layout.id = self.id;
+        layout.padding = self.padding;
+        layout.intrinsic_size = self.style.intrinsic_size;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![&self.child]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(&self.child);
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(&mut self.child);
+        self.child.traverse_mut(f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clipboard::MemoryClipboard;
+    use crate::event::dispatch_event;
+    use crate::widgets::WidgetEvent;
+
+    #[test]
+    fn copy_button_exports_report() {
+        clipboard::set_backend(MemoryClipboard::new());
+        let diagnostics = Diagnostics::new().app("Notes", "1.0.0");
+        let expected = diagnostics.report();
+
+        let copied = Rc::new(RefCell::new(String::new()));
+        let output = Rc::clone(&copied);
+        let mut about = About::new(diagnostics).on_copy(move |report| {
+            *output.borrow_mut() = report.to_owned();
+        });
+
+        // The copy button is the last child of the stack
+        let button_id = about.child.children().last().unwrap().id();
+        dispatch_event(&mut about, &WidgetEvent::Clicked(button_id), None);
+
+        assert_eq!(*copied.borrow(), expected);
+        assert_eq!(clipboard::get_text().unwrap(), expected);
+    }
+
+    #[test]
+    fn shows_recent_errors() {
+        let with_errors = About::new(Diagnostics::new().error("Oops").error("Surface lost"));
+        let without_errors = About::new(Diagnostics::new());
+
+        // A heading and a line for each error
+        assert_eq!(
+            with_errors.child.children().len(),
+            without_errors.child.children().len() + 3
+        );
+    }
+}
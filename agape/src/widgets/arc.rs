@@ -0,0 +1,88 @@
+use super::Widget;
+use crate::style::StrokeCap;
+use crate::view::{ArcView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// An outline of part of a circle.
+///
+/// Angles are in degrees, zero is at the top and angles increase clockwise.
+/// The stroke is kept inside the widget.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Arc;
+/// use agape::style::StrokeCap;
+/// use agape::Color;
+///
+/// // The bottom half of a circle
+/// let smile = Arc::new(48.0, 90.0, 180.0)
+///     .stroke_width(3.0)
+///     .cap(StrokeCap::Round)
+///     .color(Color::rgb(40, 40, 40));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arc {
+    id: GlobalId,
+    diameter: f32,
+    start: f32,
+    sweep: f32,
+    stroke_width: f32,
+    cap: StrokeCap,
+    color: Color<Rgba>,
+}
+
+impl Arc {
+    /// Create an arc that starts at the `start` angle and goes `sweep`
+    /// degrees around the circle.
+    pub fn new(diameter: f32, start: f32, sweep: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            diameter,
+            start,
+            sweep,
+            stroke_width: 4.0,
+            cap: StrokeCap::Butt,
+            color: Color::BLACK,
+        }
+    }
+
+    pub fn stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    /// Set the shape at the ends of the arc.
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = color.into_color();
+        self
+    }
+}
+
+impl Widget for Arc {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.diameter, self.diameter);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = ArcView::new(self.id);
+        view.color = self.color.clone();
+        view.stroke_width = self.stroke_width;
+        view.cap = self.cap;
+        view.start = self.start;
+        view.sweep = self.sweep;
+        Box::new(view)
+    }
+}
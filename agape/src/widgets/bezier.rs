@@ -0,0 +1,205 @@
+use super::Widget;
+use crate::style::{StrokeCap, StrokeStyle};
+use crate::view::{PathCommand, PathView, View};
+use agape_core::{Color, GlobalId, IntoColor, Position, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// The control points of a [`Bezier`] curve.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum BezierKind {
+    /// A curve with one control point.
+    Quadratic(Position),
+    /// A curve with two control points.
+    Cubic(Position, Position),
+}
+
+/// A curve from `start` to `end` that bends towards its control points.
+///
+/// The points are relative to the top left corner of the widget, which is
+/// sized to fit all the points.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Bezier;
+/// use agape::{Color,Position};
+///
+/// let connector = Bezier::cubic(
+///     Position::new(0.0,0.0),
+///     Position::new(80.0,0.0),
+///     Position::new(20.0,100.0),
+///     Position::new(100.0,100.0),
+/// )
+/// .stroke_width(2.0)
+/// .color(Color::BLUE);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bezier {
+    id: GlobalId,
+    start: Position,
+    end: Position,
+    kind: BezierKind,
+    stroke: StrokeStyle,
+}
+
+impl Bezier {
+    pub fn quadratic(start: Position, control: Position, end: Position) -> Self {
+        Self::new(start, BezierKind::Quadratic(control), end)
+    }
+
+    pub fn cubic(start: Position, c1: Position, c2: Position, end: Position) -> Self {
+        Self::new(start, BezierKind::Cubic(c1, c2), end)
+    }
+
+    fn new(start: Position, kind: BezierKind, end: Position) -> Self {
+        Self {
+            id: GlobalId::new(),
+            start,
+            end,
+            kind,
+            stroke: StrokeStyle::new(1.0, Color::BLACK).cap(StrokeCap::Round),
+        }
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    pub fn kind(&self) -> BezierKind {
+        self.kind
+    }
+
+    pub fn stroke_width(mut self, width: f32) -> Self {
+        self.stroke.width = width;
+        self
+    }
+
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.stroke.color = color.into_color();
+        self
+    }
+
+    /// Set the whole [`StrokeStyle`].
+    pub fn stroke(mut self, stroke: StrokeStyle) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// Get the point on the curve at `t`, from `0.0` at the start to `1.0`
+    /// at the end.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::widgets::Bezier;
+    /// use agape::Position;
+    ///
+    /// let curve = Bezier::quadratic(
+    ///     Position::new(0.0,0.0),
+    ///     Position::new(50.0,100.0),
+    ///     Position::new(100.0,0.0),
+    /// );
+    /// assert_eq!(curve.point_at(0.5),Position::new(50.0,50.0));
+    /// ```
+    pub fn point_at(&self, t: f32) -> Position {
+        let t = t.clamp(0.0, 1.0);
+        let u = 1.0 - t;
+        let (p0, p3) = (self.start, self.end);
+        let (x, y) = match self.kind {
+            BezierKind::Quadratic(p1) => (
+                u * u * p0.x + 2.0 * u * t * p1.x + t * t * p3.x,
+                u * u * p0.y + 2.0 * u * t * p1.y + t * t * p3.y,
+            ),
+            BezierKind::Cubic(p1, p2) => (
+                u * u * u * p0.x
+                    + 3.0 * u * u * t * p1.x
+                    + 3.0 * u * t * t * p2.x
+                    + t * t * t * p3.x,
+                u * u * u * p0.y
+                    + 3.0 * u * u * t * p1.y
+                    + 3.0 * u * t * t * p2.y
+                    + t * t * t * p3.y,
+            ),
+        };
+        Position::new(x, y)
+    }
+
+    fn commands(&self) -> [PathCommand; 2] {
+        let curve = match self.kind {
+            BezierKind::Quadratic(control) => PathCommand::QuadTo(control, self.end),
+            BezierKind::Cubic(c1, c2) => PathCommand::CubicTo(c1, c2, self.end),
+        };
+        [PathCommand::MoveTo(self.start), curve]
+    }
+}
+
+impl Widget for Bezier {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        // The curve is always inside the control points
+        let points = match self.kind {
+            BezierKind::Quadratic(control) => vec![self.start, control, self.end],
+            BezierKind::Cubic(c1, c2) => vec![self.start, c1, c2, self.end],
+        };
+        let (width, height) = points.iter().fold((0.0f32, 0.0f32), |(w, h), point| {
+            (w.max(point.x), h.max(point.y))
+        });
+
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(width, height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = PathView::new(self.id);
+        view.commands = self.commands().to_vec();
+        view.fill = Color::TRANSPARENT;
+        view.stroke = Some(self.stroke.clone());
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Resources;
+    use tiny_skia::Pixmap;
+
+    #[test]
+    fn cubic_end_points() {
+        let curve = Bezier::cubic(
+            Position::new(0.0, 10.0),
+            Position::new(5.0, 0.0),
+            Position::new(15.0, 0.0),
+            Position::new(20.0, 10.0),
+        );
+        assert_eq!(curve.point_at(0.0), Position::new(0.0, 10.0));
+        assert_eq!(curve.point_at(1.0), Position::new(20.0, 10.0));
+        assert_eq!(curve.point_at(0.5), Position::new(10.0, 2.5));
+    }
+
+    #[test]
+    fn draw_with_control_points() {
+        let curve = Bezier::quadratic(
+            Position::new(0.0, 0.0),
+            Position::new(50.0, 100.0),
+            Position::new(100.0, 0.0),
+        )
+        .stroke_width(4.0);
+
+        let mut view = curve.view();
+        view.set_position(Position::default());
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        view.render(&mut pixmap, &Resources::new());
+
+        // The curve passes through its midpoint but not the control point
+        assert_ne!(pixmap.pixel(50, 50).unwrap().alpha(), 0);
+        assert_eq!(pixmap.pixel(50, 95).unwrap().alpha(), 0);
+    }
+}
@@ -0,0 +1,82 @@
+use super::Widget;
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, Layout};
+
+/// Blurs a widget and its children, created with [`Widget::blur`].
+///
+/// The children are drawn on a separate layer which is blurred before it's
+/// blended with what's behind it. To blur what's behind a widget instead,
+/// use `backdrop_blur`.
+///
+/// # Example
+/// ```
+/// use agape::widgets::{Rect, Text, Widget};
+/// use agape::vstack;
+///
+/// let loading = vstack! {
+///     Text::new("Loading"),
+///     Rect::new(100.0, 20.0),
+/// }
+/// .blur(4.0);
+/// ```
+pub struct Blur {
+    id: GlobalId,
+    radius: f32,
+    child: Box<dyn Widget>,
+}
+
+impl Blur {
+    /// Wrap a widget, negative radii are treated as `0.0`.
+    pub fn new(child: impl Widget + 'static, radius: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            radius: radius.max(0.0),
+            child: Box::new(child),
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.max(0.0);
+    }
+}
+
+impl Widget for Blur {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn group_blur(&self) -> Option<f32> {
+        Some(self.radius)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+}
@@ -1,17 +1,47 @@
+use crate::event::EventCtx;
 use crate::impl_style;
-use crate::style::BoxStyle;
+use crate::shortcut::Shortcut;
+use crate::style::{BoxStyle, State, StateStyled, StateStyles};
 use crate::view::{RectView, View};
-use crate::widgets::{Text, Widget};
-use agape_core::GlobalId;
-use agape_layout::{BlockLayout, Layout};
+use crate::widgets::{
+    DropHandler, Focusable, GestureHandler, KeyboardInput, LayoutExt, PointerTarget, Role,
+    Semantics, Text, Widget,
+};
+use agape_core::{GlobalId, Position};
+use agape_layout::{AxisAlignment, BlockLayout, Layout};
+use std::path::PathBuf;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::CursorIcon;
+
+/// A callback for a click, which can stop it from bubbling up.
+type ClickFn = Box<dyn FnMut(&mut EventCtx)>;
 
 pub struct Button {
     id: GlobalId,
     child: Box<dyn Widget>,
     padding: u32,
-    click_fn: Option<Box<dyn FnMut()>>,
+    main_axis_alignment: AxisAlignment,
+    cross_axis_alignment: AxisAlignment,
+    click_fn: Option<ClickFn>,
+    press_fn: Option<ClickFn>,
+    release_fn: Option<ClickFn>,
+    double_click_fn: Option<ClickFn>,
+    right_click_fn: Option<ClickFn>,
+    middle_click_fn: Option<ClickFn>,
+    long_press_fn: Option<ClickFn>,
+    pan_fn: Option<Box<dyn FnMut(Position)>>,
+    pinch_fn: Option<Box<dyn FnMut(f32, Position)>>,
     hover_fn: Option<Box<dyn FnMut()>>,
+    leave_fn: Option<Box<dyn FnMut()>>,
+    move_fn: Option<Box<dyn FnMut(Position)>>,
+    file_hover_fn: Option<Box<dyn FnMut()>>,
+    file_leave_fn: Option<Box<dyn FnMut()>>,
+    file_drop_fn: Option<Box<dyn FnMut(Vec<PathBuf>)>>,
+    shortcut: Option<Shortcut>,
+    tab_index: Option<u32>,
     style: BoxStyle,
+    state_styles: StateStyles,
 }
 
 impl Default for Button {
@@ -19,10 +49,28 @@ impl Default for Button {
         Button {
             id: GlobalId::new(),
             padding: 0,
+            main_axis_alignment: AxisAlignment::default(),
+            cross_axis_alignment: AxisAlignment::default(),
             child: Box::new(Text::new("")),
             click_fn: None,
+            press_fn: None,
+            release_fn: None,
+            double_click_fn: None,
+            right_click_fn: None,
+            middle_click_fn: None,
+            long_press_fn: None,
+            pan_fn: None,
+            pinch_fn: None,
             hover_fn: None,
+            leave_fn: None,
+            move_fn: None,
+            file_hover_fn: None,
+            file_leave_fn: None,
+            file_drop_fn: None,
+            shortcut: None,
+            tab_index: None,
             style: BoxStyle::new(),
+            state_styles: StateStyles::new(),
         }
     }
 }
@@ -35,43 +83,320 @@ impl Button {
         }
     }
 
-    pub fn on_click(mut self, callback: impl FnMut() + 'static) -> Self {
+    /// Call a function when the button is pressed and released, the click
+    /// is cancelled if the cursor leaves the button before it's released.
+    pub fn on_click(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
         self.click_fn = Some(Box::new(callback));
         self
     }
 
-    pub fn on_hover(mut self, callback: impl FnMut() + 'static) -> Self {
+    /// Call a function when the primary mouse button is pressed over the
+    /// button.
+    pub fn on_press(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
+        self.press_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function when the primary mouse button is released after
+    /// pressing the button, whether or not the button is clicked.
+    pub fn on_release(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
+        self.release_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function when the button is double clicked, the first click
+    /// still calls [`on_click`](Self::on_click).
+    pub fn on_double_click(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
+        self.double_click_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function when the button is clicked with the right mouse
+    /// button, which doesn't call [`on_click`](Self::on_click).
+    pub fn on_right_click(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
+        self.right_click_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function when the button is clicked with the middle mouse
+    /// button, which doesn't call [`on_click`](Self::on_click).
+    pub fn on_middle_click(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
+        self.middle_click_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function when a touch, or the mouse, is held on the button
+    /// without moving, instead of the [`on_right_click`](Self::on_right_click)
+    /// callback. The button isn't clicked when it's released. Taps call
+    /// [`on_click`](Self::on_click).
+    pub fn on_long_press(mut self, callback: impl FnMut(&mut EventCtx) + 'static) -> Self {
+        self.long_press_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function with the number of pixels a touch is dragged over
+    /// the button.
+    pub fn on_pan(mut self, callback: impl FnMut(Position) + 'static) -> Self {
+        self.pan_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function with the scale and center of a pinch over the
+    /// button, see [`GestureHandler::pinch`].
+    pub fn on_pinch(mut self, callback: impl FnMut(f32, Position) + 'static) -> Self {
+        self.pinch_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Same as [`on_mouse_enter`](Self::on_mouse_enter).
+    pub fn on_hover(self, callback: impl FnMut() + 'static) -> Self {
+        self.on_mouse_enter(callback)
+    }
+
+    /// Call a function when the cursor enters the button.
+    pub fn on_mouse_enter(mut self, callback: impl FnMut() + 'static) -> Self {
         self.hover_fn = Some(Box::new(callback));
         self
     }
 
-    impl_style!();
+    /// Call a function when the cursor leaves the button.
+    pub fn on_mouse_leave(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.leave_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function with the position of the cursor, relative to the
+    /// button, whenever it moves within the button.
+    pub fn on_mouse_move(mut self, callback: impl FnMut(Position) + 'static) -> Self {
+        self.move_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Click the button when the keyboard [`Shortcut`] is pressed, even
+    /// when the button isn't focused. Unlike shortcuts registered with
+    /// [`App::shortcut`](crate::App::shortcut), these aren't checked for
+    /// conflicts.
+    pub fn shortcut(mut self, shortcut: Shortcut) -> Self {
+        self.shortcut = Some(shortcut);
+        self
+    }
+
+    /// Set the position of the button in the tab order, see
+    /// [`Focusable::tab_index`].
+    pub fn tab_index(mut self, index: u32) -> Self {
+        self.tab_index = Some(index);
+        self
+    }
+
+    /// Call a function when files from another app are dragged over the
+    /// button.
+    pub fn on_file_hover(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.file_hover_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function when files that were dragged over the button leave
+    /// it, are dropped or the drag is cancelled.
+    pub fn on_file_leave(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.file_leave_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Call a function with the paths of the files that are dropped on the
+    /// button, which makes it a drop zone that accepts all files.
+    pub fn on_file_drop(mut self, callback: impl FnMut(Vec<PathBuf>) + 'static) -> Self {
+        self.file_drop_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Focused buttons are clicked with Enter and Space.
+    fn handle_key(&mut self, key: &Key) {
+        if matches!(key, Key::Named(NamedKey::Enter | NamedKey::Space)) {
+            self.click(&mut EventCtx::new(self.id, None));
+        }
+    }
+}
+
+impl_style!(Button);
+
+impl StateStyled for Button {
+    fn state_styles_mut(&mut self) -> &mut StateStyles {
+        &mut self.state_styles
+    }
+}
+
+impl LayoutExt for Button {
+    fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    fn main_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+        self.main_axis_alignment = alignment;
+        self
+    }
+
+    fn cross_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+        self.cross_axis_alignment = alignment;
+        self
+    }
+}
+
+impl Focusable for Button {
+    fn tab_index(&self) -> Option<u32> {
+        self.tab_index
+    }
+
+    fn focus(&mut self) {
+        self.state_styles.update(State::Focused, true, &self.style);
+    }
+
+    fn unfocus(&mut self) {
+        self.state_styles.update(State::Focused, false, &self.style);
+    }
+}
+
+impl KeyboardInput for Button {
+    fn key_input(&mut self, event: &KeyEvent) {
+        if event.state == ElementState::Pressed && !event.repeat {
+            self.handle_key(&event.logical_key);
+        }
+    }
+
+    fn keyboard_shortcut(&self) -> Option<Shortcut> {
+        self.shortcut
+    }
+}
+
+impl Semantics for Button {
+    fn role(&self) -> Role {
+        Role::Button
+    }
+
+    /// Buttons are labelled by the text inside them.
+    fn label(&self) -> Option<String> {
+        self.child
+            .iter()
+            .find_map(|widget| widget.as_semantics()?.label())
+    }
+}
+
+impl GestureHandler for Button {
+    fn long_press(&mut self, ctx: &mut EventCtx) -> bool {
+        let Some(func) = &mut self.long_press_fn else {
+            return false;
+        };
+        func(ctx);
+        true
+    }
+
+    fn pan(&mut self, delta: Position) -> bool {
+        let Some(func) = &mut self.pan_fn else {
+            return false;
+        };
+        func(delta);
+        true
+    }
+
+    fn pinch(&mut self, scale: f32, center: Position) -> bool {
+        let Some(func) = &mut self.pinch_fn else {
+            return false;
+        };
+        func(scale, center);
+        true
+    }
 }
 
 impl Widget for Button {
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_keyboard_input(&self) -> Option<&dyn KeyboardInput> {
+        Some(self)
+    }
+
+    fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+        Some(self)
+    }
+
+    fn as_semantics(&self) -> Option<&dyn Semantics> {
+        Some(self)
+    }
+
+    fn as_gesture_handler_mut(&mut self) -> Option<&mut dyn GestureHandler> {
+        Some(self)
+    }
+
     fn id(&self) -> GlobalId {
         self.id
     }
 
-    fn click(&mut self) {
+    fn click(&mut self, ctx: &mut EventCtx) {
         if let Some(func) = &mut self.click_fn {
-            func();
+            func(ctx);
+        }
+    }
+
+    fn press(&mut self, ctx: &mut EventCtx) {
+        self.state_styles.update(State::Pressed, true, &self.style);
+        if let Some(func) = &mut self.press_fn {
+            func(ctx);
+        }
+    }
+
+    fn release(&mut self, ctx: &mut EventCtx) {
+        self.state_styles.update(State::Pressed, false, &self.style);
+        if let Some(func) = &mut self.release_fn {
+            func(ctx);
+        }
+    }
+
+    fn double_click(&mut self, ctx: &mut EventCtx) {
+        if let Some(func) = &mut self.double_click_fn {
+            func(ctx);
+        }
+    }
+
+    fn right_click(&mut self, ctx: &mut EventCtx) {
+        if let Some(func) = &mut self.right_click_fn {
+            func(ctx);
+        }
+    }
+
+    fn middle_click(&mut self, ctx: &mut EventCtx) {
+        if let Some(func) = &mut self.middle_click_fn {
+            func(ctx);
         }
     }
 
     fn hover(&mut self) {
+        self.state_styles.update(State::Hovered, true, &self.style);
         if let Some(func) = &mut self.hover_fn {
             func();
         }
     }
 
+    fn unhover(&mut self) {
+        self.state_styles.update(State::Hovered, false, &self.style);
+        if let Some(func) = &mut self.leave_fn {
+            func();
+        }
+    }
+
+    fn mouse_move(&mut self, position: Position) {
+        if let Some(func) = &mut self.move_fn {
+            func(position);
+        }
+    }
+
     fn view(&self) -> Box<dyn View> {
-        let view = RectView {
-            id: self.id,
-            color: self.style.background_color.clone(),
-            border: self.style.border.clone(),
-            ..Default::default()
-        };
+        let style = self.state_styles.resolve(&self.style);
+        let view = RectView::with_style(self.id, &style);
         Box::new(view)
     }
 
@@ -80,6 +405,8 @@ impl Widget for Button {
         let mut layout = BlockLayout::new(child);
         layout.id = self.id;
         layout.padding = self.padding;
+        layout.main_axis_alignment = self.main_axis_alignment;
+        layout.cross_axis_alignment = self.cross_axis_alignment;
         Box::new(layout)
     }
 
@@ -100,6 +427,46 @@ impl Widget for Button {
         f(self.child.as_mut());
         self.child.traverse_mut(f);
     }
+
+    fn as_pointer_target(&self) -> Option<&dyn PointerTarget> {
+        Some(self)
+    }
+
+    fn as_drop_handler(&self) -> Option<&dyn DropHandler> {
+        Some(self)
+    }
+
+    fn as_drop_handler_mut(&mut self) -> Option<&mut dyn DropHandler> {
+        Some(self)
+    }
+}
+
+impl DropHandler for Button {
+    fn file_hover(&mut self) {
+        if let Some(func) = &mut self.file_hover_fn {
+            func();
+        }
+    }
+
+    fn file_unhover(&mut self) {
+        if let Some(func) = &mut self.file_leave_fn {
+            func();
+        }
+    }
+
+    fn drop_files(&mut self, paths: &[PathBuf]) -> bool {
+        let Some(func) = &mut self.file_drop_fn else {
+            return false;
+        };
+        func(paths.to_vec());
+        true
+    }
+}
+
+impl PointerTarget for Button {
+    fn cursor_icon(&self) -> Option<CursorIcon> {
+        Some(CursorIcon::Pointer)
+    }
 }
 
 #[cfg(test)]
@@ -120,7 +487,7 @@ mod test {
 
     #[test]
     fn expose_children() {
-        FONT.set(init_font()).unwrap();
+        FONT.get_or_init(init_font);
         let text = Text::new("Hello");
         let id = text.id();
 
@@ -130,9 +497,95 @@ mod test {
 
     #[test]
     fn view_and_layout() {
-        FONT.set(init_font()).unwrap();
+        FONT.get_or_init(init_font);
         let button = Button::new(Text::new("Click me"));
         assert_eq!(button.layout().id(), button.id);
         assert_eq!(button.view().id(), button.id)
     }
+
+    #[test]
+    fn mouse_callbacks() {
+        use crate::widgets::WidgetEvent;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let calls = Rc::new(RefCell::new(vec![]));
+        let (enter, leave, moved) = (calls.clone(), calls.clone(), calls.clone());
+        let mut button = Button::new(hstack! {})
+            .on_mouse_enter(move || enter.borrow_mut().push("enter".to_owned()))
+            .on_mouse_leave(move || leave.borrow_mut().push("leave".to_owned()))
+            .on_mouse_move(move |position| moved.borrow_mut().push(format!("{}", position.x)));
+
+        let id = button.id();
+        button.handle_event(&WidgetEvent::Hovered(id));
+        button.handle_event(&WidgetEvent::MouseMoved(id, Position::new(4.0, 2.0)));
+        button.handle_event(&WidgetEvent::Unhovered(id));
+        assert_eq!(*calls.borrow(), ["enter", "4", "leave"]);
+    }
+
+    #[test]
+    fn right_click() {
+        use crate::event::dispatch_event;
+        use crate::widgets::WidgetEvent;
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use winit::event::MouseButton;
+
+        let clicks = Rc::new(Cell::new(0));
+        let right_clicks = Rc::new(Cell::new(0));
+        let (click, right_click) = (clicks.clone(), right_clicks.clone());
+        let mut button = Button::new(hstack! {})
+            .on_click(move |_| click.set(click.get() + 1))
+            .on_right_click(move |_| right_click.set(right_click.get() + 1));
+
+        let id = button.id();
+        dispatch_event(
+            &mut button,
+            &WidgetEvent::MousePressed(id, MouseButton::Right),
+            None,
+        );
+        assert_eq!((clicks.get(), right_clicks.get()), (0, 1));
+    }
+
+    #[test]
+    fn touch_gestures() {
+        use crate::event::dispatch_event;
+        use crate::widgets::WidgetEvent;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let clicks = Rc::new(Cell::new(0));
+        let right_clicks = Rc::new(Cell::new(0));
+        let (click, right_click) = (clicks.clone(), right_clicks.clone());
+        let mut button = Button::new(hstack! {})
+            .on_click(move |_| click.set(click.get() + 1))
+            .on_right_click(move |_| right_click.set(right_click.get() + 1));
+
+        // Taps click and long presses right click, unless they're handled
+        let id = button.id();
+        dispatch_event(&mut button, &WidgetEvent::Tapped(id), None);
+        dispatch_event(&mut button, &WidgetEvent::LongPressed(id), None);
+        assert_eq!((clicks.get(), right_clicks.get()), (1, 1));
+
+        let long_presses = Rc::new(Cell::new(0));
+        let long_press = long_presses.clone();
+        let mut button = button.on_long_press(move |_| long_press.set(long_press.get() + 1));
+        dispatch_event(&mut button, &WidgetEvent::LongPressed(id), None);
+        assert_eq!((right_clicks.get(), long_presses.get()), (1, 1));
+    }
+
+    #[test]
+    fn keyboard_clicks() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let clicks = Rc::new(Cell::new(0));
+        let click = clicks.clone();
+        let mut button = Button::new(hstack! {}).on_click(move |_| click.set(click.get() + 1));
+
+        button.handle_key(&Key::Named(NamedKey::Enter));
+        button.handle_key(&Key::Named(NamedKey::Space));
+        button.handle_key(&Key::Character("a".into()));
+        assert_eq!(clicks.get(), 2);
+    }
 }
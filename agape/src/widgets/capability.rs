@@ -0,0 +1,294 @@
+//! Optional capabilities of widgets.
+//!
+//! The [`Widget`] trait only requires what every widget has: an id, a
+//! layout and a view. Anything else a widget can do, like being focused or
+//! handling key input, is a separate trait that the widget opts into by
+//! returning itself from the matching `as_*` method on [`Widget`]. New
+//! capabilities can be added without changing the widgets that don't use
+//! them.
+//!
+//! # Example
+//! ```
+//! use agape::prelude::*;
+//! use agape::layout::{EmptyLayout, Layout};
+//! use agape::view::{RectView, View};
+//! use agape::GlobalId;
+//! use winit::event::KeyEvent;
+//!
+//! #[derive(Default)]
+//! struct Counter {
+//!     id: GlobalId,
+//!     count: u32,
+//! }
+//!
+//! impl Focusable for Counter {}
+//!
+//! impl KeyboardInput for Counter {
+//!     fn key_input(&mut self, event: &KeyEvent) {
+//!         if event.state.is_pressed() {
+//!             self.count += 1;
+//!         }
+//!     }
+//! }
+//!
+//! impl Widget for Counter {
+//!     fn id(&self) -> GlobalId {
+//!         self.id
+//!     }
+//!
+//!     fn view(&self) -> Box<dyn View> {
+//!         Box::new(RectView::new(self.id))
+//!     }
+//!
+//!     fn layout(&self) -> Box<dyn Layout> {
+//!         let mut layout = EmptyLayout::new();
+//!         layout.id = self.id;
+//!         Box::new(layout)
+//!     }
+//!
+//!     fn as_focusable(&self) -> Option<&dyn Focusable> {
+//!         Some(self)
+//!     }
+//!
+//!     fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+//!         Some(self)
+//!     }
+//!
+//!     fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+//!         Some(self)
+//!     }
+//! }
+//! ```
+//!
+//! [`Widget`]: super::Widget
+use super::Widget;
+use crate::event::EventCtx;
+use crate::shortcut::Shortcut;
+use crate::view::HitShape;
+use agape_core::Position;
+use std::any::Any;
+use std::path::PathBuf;
+use winit::event::KeyEvent;
+use winit::keyboard::ModifiersState;
+use winit::window::CursorIcon;
+
+/// A widget that can be focused, with a click or the Tab key.
+pub trait Focusable {
+    /// The position of the widget in the tab order, widgets with an index
+    /// are focused before the rest, from the lowest index.
+    fn tab_index(&self) -> Option<u32> {
+        None
+    }
+
+    /// Returns `true` if the widget uses the Tab key while it's focused,
+    /// instead of moving the focus to the next widget.
+    fn accepts_tab(&self) -> bool {
+        false
+    }
+
+    /// Called when the widget gains focus.
+    fn focus(&mut self) {}
+
+    /// Called when the widget loses focus.
+    fn unfocus(&mut self) {}
+}
+
+/// A widget that handles the keyboard.
+pub trait KeyboardInput {
+    /// Called when a key is pressed or released while the widget is
+    /// focused.
+    fn key_input(&mut self, event: &KeyEvent);
+
+    /// Called when a modifier key, such as shift or control, is pressed or
+    /// released, whether or not the widget is focused.
+    fn modifiers_changed(&mut self, _modifiers: ModifiersState) {}
+
+    /// A keyboard shortcut that clicks the widget, even when it isn't
+    /// focused.
+    fn keyboard_shortcut(&self) -> Option<Shortcut> {
+        None
+    }
+}
+
+/// What a widget is, for assistive technology and debug output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Button,
+    Text,
+    TextInput,
+    Image,
+}
+
+impl Role {
+    /// Get the name of the role, in lowercase.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Button => "button",
+            Self::Text => "text",
+            Self::TextInput => "text input",
+            Self::Image => "image",
+        }
+    }
+}
+
+/// Describes what a widget means, rather than how it looks.
+pub trait Semantics {
+    fn role(&self) -> Role;
+
+    /// Get the text that describes the widget.
+    fn label(&self) -> Option<String> {
+        None
+    }
+}
+
+/// A widget that needs to know when the app starts and stops using it.
+pub trait Lifecycle {
+    /// Called once the widget is part of a running app, before the first
+    /// frame, or when it's shown by a [`Show`](super::Show).
+    fn mount(&mut self) {}
+
+    /// Called when the app stops or a [`Show`](super::Show) hides the
+    /// widget, it isn't drawn again.
+    fn unmount(&mut self) {}
+}
+
+/// Call `f` on a widget and its descendants that have a [`Lifecycle`].
+pub(crate) fn for_each_lifecycle(widget: &mut dyn Widget, mut f: impl FnMut(&mut dyn Lifecycle)) {
+    if let Some(widget) = widget.as_lifecycle_mut() {
+        f(widget);
+    }
+    widget.traverse_mut(&mut |widget| {
+        if let Some(widget) = widget.as_lifecycle_mut() {
+            f(widget);
+        }
+    });
+}
+
+/// A widget that handles touch gestures, see the [`touch`](crate::touch)
+/// module.
+///
+/// Widgets don't need this to work on touchscreens: a tap clicks the
+/// widget, a long press right clicks it and a pan scrolls it. Each method
+/// returns `true` if the widget used the gesture, otherwise the widget gets
+/// the mouse event instead.
+pub trait GestureHandler {
+    /// Called when the widget is tapped on a touchscreen. Taps bubble up
+    /// like clicks.
+    fn tap(&mut self, _ctx: &mut EventCtx) -> bool {
+        false
+    }
+
+    /// Called when a touch, or the primary mouse button, is held on the
+    /// widget without moving, see [`LongPress`](crate::resources::LongPress).
+    /// Releasing the button after a long press doesn't click the widget.
+    fn long_press(&mut self, _ctx: &mut EventCtx) -> bool {
+        false
+    }
+
+    /// Called when a touch is dragged over the widget, by a number of
+    /// pixels.
+    ///
+    /// If neither the widget nor its scrolling uses the pan, it's passed on
+    /// to the widget's parent.
+    fn pan(&mut self, _delta: Position) -> bool {
+        false
+    }
+
+    /// Called when two touches are pinched over the widget. The `scale` is
+    /// the change in the distance between the touches, more than `1.0` when
+    /// they move apart, and the `center` is the position between them in
+    /// the window.
+    ///
+    /// If the widget doesn't use the pinch, it's passed on to the widget's
+    /// parent.
+    fn pinch(&mut self, _scale: f32, _center: Position) -> bool {
+        false
+    }
+}
+
+/// A widget that changes how the cursor interacts with it.
+pub trait PointerTarget {
+    /// Get the cursor icon shown while the cursor is over the widget, or
+    /// `None` to use the icon of the widget's parent.
+    fn cursor_icon(&self) -> Option<CursorIcon> {
+        None
+    }
+
+    /// Get the shape that the widget and its children are hit in, which
+    /// overrides the [`View::hit_shape`] of the widget's view. The cursor
+    /// misses the children outside of the shape.
+    ///
+    /// [`View::hit_shape`]: crate::view::View::hit_shape
+    fn hit_area(&self) -> Option<HitShape> {
+        None
+    }
+}
+
+/// A widget that changes how its children are recorded and drawn.
+pub trait Scope {
+    /// Call `f` with the [context](crate::context) that the widget provides
+    /// to its children.
+    fn with_context(&self, f: &mut dyn FnMut()) {
+        f()
+    }
+
+    /// Returns `true` if panics raised while the widget's children are
+    /// recorded and drawn are caught.
+    fn catches_panics(&self) -> bool {
+        false
+    }
+}
+
+/// A widget that reacts to changes from outside of its events.
+pub trait Reactive {
+    /// Called with each app event emitted with an
+    /// [`Event`](crate::event::Event), the widget can downcast the events
+    /// that it handles.
+    fn app_event(&mut self, _event: &dyn Any) {}
+
+    /// Called before the widget is drawn again when a
+    /// [signal](crate::signal) that it read while its view was built has
+    /// changed, widgets that are bound to a signal copy its value here.
+    fn signals_changed(&mut self) {}
+}
+
+/// A widget that can be dragged onto a [`DropHandler`], carrying a payload.
+/// See the [`drag_drop`](crate::drag_drop) module.
+pub trait DragSource {
+    /// Get the data that the widget carries, called when a drag starts.
+    fn drag_payload(&self) -> Box<dyn Any>;
+}
+
+/// A widget that dragged payloads, or files from other apps, can be
+/// dropped on.
+pub trait DropHandler {
+    /// Returns `true` if the widget accepts the `payload`, usually by
+    /// checking its type.
+    fn accepts(&self, _payload: &dyn Any) -> bool {
+        false
+    }
+
+    /// Called when an accepted payload is dropped on the widget.
+    fn drop_payload(&mut self, _payload: Box<dyn Any>) {}
+
+    /// Called when an accepted payload is dragged over the widget, and
+    /// again when it leaves.
+    fn drag_hover(&mut self, _hovered: bool) {}
+
+    /// Called when files from another app are dragged over the widget, drop
+    /// zones can use this to highlight themselves.
+    fn file_hover(&mut self) {}
+
+    /// Called when files that were dragged over the widget leave it, are
+    /// dropped or the drag is cancelled.
+    fn file_unhover(&mut self) {}
+
+    /// Called when files from another app are dropped on the widget.
+    ///
+    /// Like [`Widget::scroll_by`], the innermost widget gets the files
+    /// first, return `true` if the widget accepted them, otherwise they're
+    /// passed on to the widget's parent.
+    fn drop_files(&mut self, _paths: &[PathBuf]) -> bool {
+        false
+    }
+}
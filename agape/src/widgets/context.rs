@@ -0,0 +1,159 @@
+use super::{Scope, Widget};
+use crate::context;
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, Layout};
+use std::any::Any;
+use std::rc::Rc;
+
+/// Provides a value to the widgets below it, created with
+/// [`Widget::provide_context`]. See the [`context`](crate::context) module.
+pub struct ContextProvider {
+    id: GlobalId,
+    value: Rc<dyn Any>,
+    child: Box<dyn Widget>,
+}
+
+impl ContextProvider {
+    pub fn new<T: 'static>(child: impl Widget + 'static, value: T) -> Self {
+        Self {
+            id: GlobalId::new(),
+            value: Rc::new(value),
+            child: Box::new(child),
+        }
+    }
+}
+
+impl Widget for ContextProvider {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let child = context::provide(&self.value, || self.child.layout());
+        let mut layout = BlockLayout::new(child);
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        context::provide(&self.value, || {
+            f(self.child.as_ref());
+            self.child.traverse(f);
+        });
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        context::provide(&self.value, || {
+            f(self.child.as_mut());
+            self.child.traverse_mut(f);
+        });
+    }
+
+    fn as_scope(&self) -> Option<&dyn Scope> {
+        Some(self)
+    }
+}
+
+impl Scope for ContextProvider {
+    fn with_context(&self, f: &mut dyn FnMut()) {
+        context::provide(&self.value, f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::context::use_context;
+    use crate::event::{EventCtx, dispatch_event};
+    use crate::view::DisplayList;
+    use crate::widgets::{Button, Rect, WidgetEvent};
+    use crate::{hstack, vstack};
+    use agape_core::Size;
+    use agape_layout::{EmptyLayout, LayoutSolver};
+    use std::cell::RefCell;
+
+    #[derive(Debug, PartialEq)]
+    struct Theme(&'static str);
+
+    /// Records the theme it's built with.
+    struct Themed {
+        id: GlobalId,
+        seen: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Themed {
+        fn record(&self) {
+            let theme = use_context::<Theme>().map_or("none", |theme| theme.0);
+            self.seen.borrow_mut().push(theme);
+        }
+    }
+
+    impl Widget for Themed {
+        fn id(&self) -> GlobalId {
+            self.id
+        }
+
+        fn view(&self) -> Box<dyn View> {
+            self.record();
+            Box::new(RectView::new(self.id))
+        }
+
+        fn layout(&self) -> Box<dyn Layout> {
+            self.record();
+            let mut layout = EmptyLayout::new();
+            layout.id = self.id;
+            Box::new(layout)
+        }
+    }
+
+    #[test]
+    fn closest_provider_wins() {
+        let seen = Rc::new(RefCell::new(vec![]));
+        let themed = || Themed {
+            id: GlobalId::new(),
+            seen: seen.clone(),
+        };
+        let root = hstack! {
+            hstack! {themed()}.provide_context(Theme("dark")),
+            themed(),
+        }
+        .provide_context(Theme("light"));
+        let root = vstack! {root, themed()};
+
+        let mut layout = root.layout();
+        assert_eq!(*seen.borrow(), ["dark", "light", "none"]);
+        seen.borrow_mut().clear();
+
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+        DisplayList::record(&root, layout.as_ref());
+        let mut views = seen.borrow().clone();
+        views.sort();
+        assert_eq!(views, ["dark", "light", "none"]);
+        assert!(use_context::<Theme>().is_none());
+    }
+
+    #[test]
+    fn context_in_event_handlers() {
+        let user = Rc::new(RefCell::new(None));
+        let button = Button::new(Rect::new(10.0, 10.0)).on_click({
+            let user = user.clone();
+            move |_: &mut EventCtx| *user.borrow_mut() = use_context::<String>()
+        });
+        let id = button.id();
+        let mut root = hstack! {button}.provide_context(String::from("alice"));
+
+        dispatch_event(&mut root, &WidgetEvent::Clicked(id), None);
+        assert_eq!(user.borrow().as_deref().map(String::as_str), Some("alice"));
+    }
+}
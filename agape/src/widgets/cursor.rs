@@ -0,0 +1,80 @@
+use super::{PointerTarget, Widget};
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, Layout};
+use winit::window::CursorIcon;
+
+/// Shows a cursor icon while the cursor is over a widget, created with
+/// [`Widget::cursor`].
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+///
+/// let handle = Rect::new(4.0, 200.0).cursor(CursorIcon::EwResize);
+/// ```
+pub struct CursorArea {
+    id: GlobalId,
+    icon: CursorIcon,
+    child: Box<dyn Widget>,
+}
+
+impl CursorArea {
+    pub fn new(child: impl Widget + 'static, icon: CursorIcon) -> Self {
+        Self {
+            id: GlobalId::new(),
+            icon,
+            child: Box::new(child),
+        }
+    }
+
+    pub fn icon(&self) -> CursorIcon {
+        self.icon
+    }
+
+    pub fn set_icon(&mut self, icon: CursorIcon) {
+        self.icon = icon;
+    }
+}
+
+impl Widget for CursorArea {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+
+    fn as_pointer_target(&self) -> Option<&dyn PointerTarget> {
+        Some(self)
+    }
+}
+
+impl PointerTarget for CursorArea {
+    fn cursor_icon(&self) -> Option<CursorIcon> {
+        Some(self.icon)
+    }
+}
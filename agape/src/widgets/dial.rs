@@ -0,0 +1,282 @@
+use super::{Reactive, Widget};
+use crate::signal::Signal;
+use crate::view::{DialView, View};
+use agape_core::{Color, GlobalId, IntoColor, Position, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// How far, in pixels, the dial has to be dragged to go from the minimum
+/// to the maximum.
+const DRAG_DISTANCE: f32 = 200.0;
+
+/// A rotary knob for picking a value, dragging up turns the dial
+/// clockwise.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Dial;
+///
+/// let volume = Dial::new(0.0, 10.0)
+///     .value(5.0)
+///     .detents(11)
+///     .on_change(|value| println!("Volume: {value}"));
+/// ```
+pub struct Dial {
+    id: GlobalId,
+    min: f32,
+    max: f32,
+    /// The value before snapping.
+    raw_value: f32,
+    value: f32,
+    detents: usize,
+    diameter: f32,
+    color: Color<Rgba>,
+    indicator_color: Color<Rgba>,
+    change_fn: Option<Box<dyn FnMut(f32)>>,
+    binding: Option<Signal<f32>>,
+}
+
+impl Dial {
+    /// Create a dial between `min` and `max`, the bounds are swapped if
+    /// `min` is larger and a NaN bound falls back to the other one.
+    pub fn new(min: f32, max: f32) -> Self {
+        let (min, max) = match (min.is_nan(), max.is_nan()) {
+            (true, true) => (0.0, 0.0),
+            _ => (min.min(max), max.max(min)),
+        };
+        Self {
+            id: GlobalId::new(),
+            min,
+            max,
+            raw_value: min,
+            value: min,
+            detents: 0,
+            diameter: 48.0,
+            color: Color::rgb(60, 60, 60),
+            indicator_color: Color::WHITE,
+            change_fn: None,
+            binding: None,
+        }
+    }
+
+    pub fn value(mut self, value: f32) -> Self {
+        self.raw_value = value.clamp(self.min, self.max);
+        self.value = self.snap(self.raw_value);
+        self
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    /// Snap the value to a number of evenly spaced steps, including the
+    /// minimum and maximum.
+    pub fn detents(mut self, detents: usize) -> Self {
+        self.detents = detents;
+        self.value = self.snap(self.raw_value);
+        self
+    }
+
+    pub fn diameter(mut self, diameter: f32) -> Self {
+        self.diameter = diameter;
+        self
+    }
+
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = color.into_color();
+        self
+    }
+
+    pub fn indicator_color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.indicator_color = color.into_color();
+        self
+    }
+
+    /// Called with the new value when the dial is turned.
+    pub fn on_change(mut self, callback: impl FnMut(f32) + 'static) -> Self {
+        self.change_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Show the value of a signal, and set it when the dial is turned.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::signal::Signal;
+    /// use agape::widgets::Dial;
+    ///
+    /// let volume = Signal::new(5.0);
+    /// let dial = Dial::new(0.0, 10.0).bind(volume.clone());
+    /// assert_eq!(dial.get_value(), 5.0);
+    /// ```
+    pub fn bind(mut self, signal: Signal<f32>) -> Self {
+        self = self.value(signal.get());
+        self.binding = Some(signal);
+        self
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        if self.detents < 2 {
+            return value;
+        }
+
+        let step = (self.max - self.min) / (self.detents - 1) as f32;
+        if step == 0.0 {
+            return self.min;
+        }
+        self.min + ((value - self.min) / step).round() * step
+    }
+
+    /// Turn the dial by an amount of the value.
+    fn turn(&mut self, amount: f32) {
+        self.raw_value = (self.raw_value + amount).clamp(self.min, self.max);
+        let value = self.snap(self.raw_value);
+        if value == self.value {
+            return;
+        }
+
+        self.value = value;
+        if let Some(signal) = &self.binding {
+            signal.set(value);
+        }
+        if let Some(func) = &mut self.change_fn {
+            func(value);
+        }
+    }
+
+    /// Turn the dial by one detent for each line scrolled, or a hundredth
+    /// of its range if it doesn't have detents.
+    pub fn scroll(&mut self, delta: Position) {
+        let step = if self.detents > 1 {
+            (self.max - self.min) / (self.detents - 1) as f32
+        } else {
+            (self.max - self.min) / 100.0
+        };
+        // Snap the raw value first, so each line moves exactly one detent
+        self.raw_value = self.value;
+        self.turn(delta.y * step);
+    }
+}
+
+impl Widget for Dial {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.diameter, self.diameter);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        // Read the signal, so the dial is updated when it changes
+        if let Some(signal) = &self.binding {
+            signal.get();
+        }
+        let mut view = DialView::new(self.id);
+        view.min = self.min;
+        view.max = self.max;
+        view.value = self.value;
+        view.detents = self.detents;
+        view.color = self.color.clone();
+        view.indicator_color = self.indicator_color.clone();
+        Box::new(view)
+    }
+
+    fn drag(&mut self, delta: Position) {
+        // The y axis points down, so dragging up increases the value
+        self.turn(-delta.y * (self.max - self.min) / DRAG_DISTANCE);
+    }
+
+    fn scroll_by(&mut self, delta: Position) -> bool {
+        self.scroll(delta);
+        true
+    }
+
+    fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+        Some(self)
+    }
+}
+
+impl Reactive for Dial {
+    fn signals_changed(&mut self) {
+        let Some(value) = self.binding.as_ref().map(Signal::get) else {
+            return;
+        };
+        if value != self.value {
+            self.raw_value = value.clamp(self.min, self.max);
+            self.value = self.snap(self.raw_value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn drag_up_to_increase() {
+        let mut dial = Dial::new(0.0, 100.0);
+        dial.drag(Position::new(0.0, -50.0));
+        assert_eq!(dial.get_value(), 25.0);
+
+        dial.drag(Position::new(0.0, 1000.0));
+        assert_eq!(dial.get_value(), 0.0);
+    }
+
+    #[test]
+    fn snap_to_detents() {
+        let changes = Rc::new(Cell::new(0));
+        let count = Rc::clone(&changes);
+        let mut dial = Dial::new(0.0, 10.0)
+            .detents(3)
+            .on_change(move |_| count.set(count.get() + 1));
+
+        // Small drags accumulate until the next detent is reached
+        dial.drag(Position::new(0.0, -20.0));
+        assert_eq!(dial.get_value(), 0.0);
+        dial.drag(Position::new(0.0, -20.0));
+        assert_eq!(dial.get_value(), 0.0);
+        dial.drag(Position::new(0.0, -20.0));
+        assert_eq!(dial.get_value(), 5.0);
+        assert_eq!(changes.get(), 1);
+    }
+
+    #[test]
+    fn reversed_range() {
+        let dial = Dial::new(10.0, 0.0).value(20.0);
+        assert_eq!(dial.get_value(), 10.0);
+
+        let mut dial = Dial::new(f32::NAN, 5.0).value(2.0);
+        dial.drag(Position::new(0.0, -50.0));
+        assert_eq!(dial.get_value(), 5.0);
+    }
+
+    #[test]
+    fn empty_range_with_detents() {
+        let dial = Dial::new(3.0, 3.0).detents(5).value(3.0);
+        assert_eq!(dial.get_value(), 3.0);
+    }
+
+    #[test]
+    fn bound_to_signal() {
+        let volume = Signal::new(2.0);
+        let mut dial = Dial::new(0.0, 10.0).detents(11).bind(volume.clone());
+        dial.scroll(Position::new(0.0, 1.0));
+        assert_eq!(volume.get(), 3.0);
+
+        volume.set(7.0);
+        dial.signals_changed();
+        assert_eq!(dial.get_value(), 7.0);
+    }
+
+    #[test]
+    fn scroll_one_detent() {
+        let mut dial = Dial::new(0.0, 10.0).detents(11);
+        dial.scroll(Position::new(0.0, 2.0));
+        assert_eq!(dial.get_value(), 2.0);
+    }
+}
@@ -0,0 +1,156 @@
+use super::{DragSource, DropHandler, Widget};
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, Layout};
+use std::any::Any;
+
+/// A widget that can be dragged onto a [`DropTarget`], carrying a payload,
+/// created with [`Widget::draggable`].
+pub struct Draggable<T> {
+    id: GlobalId,
+    payload: T,
+    child: Box<dyn Widget>,
+}
+
+impl<T: Clone + 'static> Draggable<T> {
+    pub fn new(child: impl Widget + 'static, payload: T) -> Self {
+        Self {
+            id: GlobalId::new(),
+            payload,
+            child: Box::new(child),
+        }
+    }
+
+    /// Get the payload that is dropped.
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    pub fn set_payload(&mut self, payload: T) {
+        self.payload = payload;
+    }
+}
+
+impl<T: Clone + 'static> DragSource for Draggable<T> {
+    fn drag_payload(&self) -> Box<dyn Any> {
+        Box::new(self.payload.clone())
+    }
+}
+
+impl<T: Clone + 'static> Widget for Draggable<T> {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+
+    fn as_drag_source(&self) -> Option<&dyn DragSource> {
+        Some(self)
+    }
+}
+
+/// A widget that accepts payloads of type `T` that are dropped on it,
+/// created with [`Widget::drop_target`].
+pub struct DropTarget<T> {
+    id: GlobalId,
+    hovered: bool,
+    on_drop: Box<dyn FnMut(T)>,
+    child: Box<dyn Widget>,
+}
+
+impl<T: 'static> DropTarget<T> {
+    pub fn new(child: impl Widget + 'static, on_drop: impl FnMut(T) + 'static) -> Self {
+        Self {
+            id: GlobalId::new(),
+            hovered: false,
+            on_drop: Box::new(on_drop),
+            child: Box::new(child),
+        }
+    }
+
+    /// Returns `true` if a payload that the target accepts is being
+    /// dragged over it.
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+}
+
+impl<T: 'static> DropHandler for DropTarget<T> {
+    fn accepts(&self, payload: &dyn Any) -> bool {
+        payload.is::<T>()
+    }
+
+    fn drop_payload(&mut self, payload: Box<dyn Any>) {
+        if let Ok(payload) = payload.downcast::<T>() {
+            (self.on_drop)(*payload);
+        }
+    }
+
+    fn drag_hover(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+}
+
+impl<T: 'static> Widget for DropTarget<T> {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+
+    fn as_drop_handler(&self) -> Option<&dyn DropHandler> {
+        Some(self)
+    }
+
+    fn as_drop_handler_mut(&mut self) -> Option<&mut dyn DropHandler> {
+        Some(self)
+    }
+}
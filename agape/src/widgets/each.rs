@@ -0,0 +1,221 @@
+use crate::signal::Signal;
+use crate::style::{BoxStyle, Styled};
+use crate::view::{RectView, View};
+use crate::widgets::{LayoutExt, Reactive, Widget};
+use agape_core::GlobalId;
+use agape_layout::{AxisAlignment, Layout, VerticalLayout};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+type KeyFn<T, K> = Box<dyn Fn(&T) -> K>;
+type RowFn<T> = Box<dyn Fn(&T) -> Box<dyn Widget>>;
+
+/// A vertical list with a row for each item in a signal.
+///
+/// Each row is identified by the key of its item. When the items change,
+/// only the rows of new items are built and the rows of removed items are
+/// dropped. The other rows are moved into place, so they keep their state,
+/// like being focused or scrolled. A row isn't rebuilt when its item
+/// changes but its key doesn't, rows that show changing values can read
+/// them from signals.
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+///
+/// struct Todo {
+///     id: u32,
+///     title: String,
+/// }
+///
+/// let todos = Signal::new(vec![Todo { id: 1, title: String::from("Write docs") }]);
+/// let list = For::each(todos.clone(), |todo| todo.id, |todo| Text::new(&todo.title)).spacing(8);
+///
+/// todos.update(|todos| todos.push(Todo { id: 2, title: String::from("Ship it") }));
+/// ```
+pub struct For<T, K> {
+    id: GlobalId,
+    items: Signal<Vec<T>>,
+    key_fn: KeyFn<T, K>,
+    row_fn: RowFn<T>,
+    rows: Vec<(K, Box<dyn Widget>)>,
+    layout: VerticalLayout,
+    pub style: BoxStyle,
+}
+
+impl<T: 'static, K: Eq + Hash + 'static> For<T, K> {
+    /// Create a list with a row for each item, built by `row` and
+    /// identified by `key`.
+    pub fn each<W: Widget + 'static>(
+        items: Signal<Vec<T>>,
+        key: impl Fn(&T) -> K + 'static,
+        row: impl Fn(&T) -> W + 'static,
+    ) -> Self {
+        let mut list = Self {
+            id: GlobalId::new(),
+            items,
+            key_fn: Box::new(key),
+            row_fn: Box::new(move |item| Box::new(row(item))),
+            rows: vec![],
+            layout: VerticalLayout::new(),
+            style: BoxStyle::default(),
+        };
+        list.sync_rows();
+        list
+    }
+
+    pub fn spacing(mut self, spacing: u32) -> Self {
+        self.layout.spacing = spacing;
+        self
+    }
+
+    /// Get the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Match the rows to the items, reusing the rows whose keys are still
+    /// in the list.
+    fn sync_rows(&mut self) {
+        let mut old: HashMap<K, Box<dyn Widget>> = self.rows.drain(..).collect();
+        let items = self.items.clone();
+        self.rows = items.with(|items| {
+            items
+                .iter()
+                .map(|item| {
+                    let key = (self.key_fn)(item);
+                    // Duplicate keys only reuse the row once
+                    let row = old.remove(&key).unwrap_or_else(|| (self.row_fn)(item));
+                    (key, row)
+                })
+                .collect()
+        });
+    }
+
+    /// Read the items, so the list is updated when they change.
+    fn track_items(&self) {
+        self.items.with(|_| ());
+    }
+}
+
+impl<T, K> LayoutExt for For<T, K> {
+    fn padding(mut self, padding: u32) -> Self {
+        self.layout.padding = padding;
+        self
+    }
+
+    fn main_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+        self.layout.main_axis_alignment = alignment;
+        self
+    }
+
+    fn cross_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+        self.layout.cross_axis_alignment = alignment;
+        self
+    }
+}
+
+impl<T, K> Styled for For<T, K> {
+    fn style_mut(&mut self) -> &mut BoxStyle {
+        &mut self.style
+    }
+}
+
+impl<T: 'static, K: Eq + Hash + 'static> Widget for For<T, K> {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for (_, row) in &self.rows {
+            f(row.as_ref());
+            row.traverse(f);
+        }
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for (_, row) in &mut self.rows {
+            f(row.as_mut());
+            row.traverse_mut(f);
+        }
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        self.track_items();
+        Box::new(RectView::with_style(self.id, &self.style))
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        self.track_items();
+        let layout = VerticalLayout {
+            id: self.id,
+            spacing: self.layout.spacing,
+            padding: self.layout.padding,
+            intrinsic_size: self.style.intrinsic_size,
+            main_axis_alignment: self.layout.main_axis_alignment,
+            cross_axis_alignment: self.layout.cross_axis_alignment,
+            constraints: self.layout.constraints,
+            scroll_offset: self.layout.scroll_offset,
+            children: self.rows.iter().map(|(_, row)| row.layout()).collect(),
+            ..Default::default()
+        };
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        self.rows.iter().map(|(_, row)| row.as_ref()).collect()
+    }
+
+    fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+        Some(self)
+    }
+}
+
+impl<T: 'static, K: Eq + Hash + 'static> Reactive for For<T, K> {
+    fn signals_changed(&mut self) {
+        self.sync_rows();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Rect;
+
+    fn ids<T: 'static>(list: &For<T, u32>) -> Vec<GlobalId> {
+        list.children().iter().map(|row| row.id()).collect()
+    }
+
+    #[test]
+    fn keep_rows_of_unchanged_items() {
+        let items = Signal::new(vec![1, 2, 3]);
+        let mut list = For::each(items.clone(), |item| *item, |_| Rect::new(10.0, 10.0));
+        let [one, two, three] = ids(&list)[..] else {
+            panic!("expected three rows");
+        };
+
+        items.set(vec![3, 4, 1]);
+        list.signals_changed();
+        let rows = ids(&list);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], three);
+        assert_eq!(rows[2], one);
+        assert!(![one, two, three].contains(&rows[1]));
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let items = Signal::new(vec![(1, "a"), (1, "b")]);
+        let mut list = For::each(items.clone(), |item| item.0, |_| Rect::new(10.0, 10.0));
+        assert_eq!(list.len(), 2);
+
+        items.set(vec![]);
+        list.signals_changed();
+        assert!(list.is_empty());
+        assert_eq!(list.layout().children().len(), 0);
+    }
+}
@@ -0,0 +1,75 @@
+use super::Widget;
+use crate::style::StrokeStyle;
+use crate::view::{EllipseView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// An ellipse, or a circle if the width and height are the same.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Ellipse;
+/// use agape::style::StrokeStyle;
+/// use agape::Color;
+///
+/// let dot = Ellipse::circle(8.0).fill(Color::rgb(0, 200, 80));
+/// let outline = Ellipse::new(120.0, 60.0)
+///     .fill(Color::TRANSPARENT)
+///     .stroke(StrokeStyle::default());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ellipse {
+    id: GlobalId,
+    width: f32,
+    height: f32,
+    fill: Color<Rgba>,
+    stroke: Option<StrokeStyle>,
+}
+
+impl Ellipse {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            width,
+            height,
+            fill: Color::BLACK,
+            stroke: None,
+        }
+    }
+
+    pub fn circle(diameter: f32) -> Self {
+        Self::new(diameter, diameter)
+    }
+
+    /// Set the fill color.
+    pub fn fill(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.fill = color.into_color();
+        self
+    }
+
+    /// Draw an outline around the ellipse.
+    pub fn stroke(mut self, stroke: StrokeStyle) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+}
+
+impl Widget for Ellipse {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width, self.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = EllipseView::new(self.id);
+        view.fill = self.fill.clone();
+        view.stroke = self.stroke.clone();
+        Box::new(view)
+    }
+}
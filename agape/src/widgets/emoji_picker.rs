@@ -0,0 +1,565 @@
+use super::{Focusable, KeyboardInput, Widget};
+use crate::view::{
+    EMOJI_CELL, EMOJI_COLUMNS, EMOJI_ROWS, EmojiPickerView, PICKER_BAR_HEIGHT, View,
+};
+use agape_core::{Color, GlobalId, Position, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use std::path::PathBuf;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, NamedKey};
+
+type PickFn = Box<dyn FnMut(&str)>;
+
+/// The number of recently picked emoji that are remembered.
+const RECENT_LIMIT: usize = EMOJI_COLUMNS * EMOJI_ROWS;
+
+/// A group of emoji, shown as a tab in an [`EmojiPicker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmojiCategory {
+    Smileys,
+    People,
+    Animals,
+    Food,
+    Activities,
+    Travel,
+    Objects,
+    Symbols,
+}
+
+impl EmojiCategory {
+    pub const ALL: [EmojiCategory; 8] = [
+        EmojiCategory::Smileys,
+        EmojiCategory::People,
+        EmojiCategory::Animals,
+        EmojiCategory::Food,
+        EmojiCategory::Activities,
+        EmojiCategory::Travel,
+        EmojiCategory::Objects,
+        EmojiCategory::Symbols,
+    ];
+
+    /// Get the emoji shown on the category's tab.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            EmojiCategory::Smileys => "😀",
+            EmojiCategory::People => "👋",
+            EmojiCategory::Animals => "🐶",
+            EmojiCategory::Food => "🍎",
+            EmojiCategory::Activities => "⚽",
+            EmojiCategory::Travel => "🚗",
+            EmojiCategory::Objects => "💡",
+            EmojiCategory::Symbols => "❤",
+        }
+    }
+}
+
+/// A modifier that changes the skin tone of emoji of people and hands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SkinTone {
+    #[default]
+    Default,
+    Light,
+    MediumLight,
+    Medium,
+    MediumDark,
+    Dark,
+}
+
+impl SkinTone {
+    pub const ALL: [SkinTone; 6] = [
+        SkinTone::Default,
+        SkinTone::Light,
+        SkinTone::MediumLight,
+        SkinTone::Medium,
+        SkinTone::MediumDark,
+        SkinTone::Dark,
+    ];
+
+    /// Get the Fitzpatrick modifier that follows the emoji.
+    pub fn modifier(&self) -> Option<char> {
+        match self {
+            SkinTone::Default => None,
+            SkinTone::Light => Some('\u{1F3FB}'),
+            SkinTone::MediumLight => Some('\u{1F3FC}'),
+            SkinTone::Medium => Some('\u{1F3FD}'),
+            SkinTone::MediumDark => Some('\u{1F3FE}'),
+            SkinTone::Dark => Some('\u{1F3FF}'),
+        }
+    }
+
+    /// Get the color of the tone's swatch.
+    pub fn color(&self) -> Color<Rgba> {
+        match self {
+            SkinTone::Default => Color::rgb(255, 205, 70),
+            SkinTone::Light => Color::rgb(247, 222, 206),
+            SkinTone::MediumLight => Color::rgb(243, 210, 162),
+            SkinTone::Medium => Color::rgb(213, 171, 136),
+            SkinTone::MediumDark => Color::rgb(175, 126, 87),
+            SkinTone::Dark => Color::rgb(124, 83, 62),
+        }
+    }
+}
+
+/// An emoji in the picker.
+struct Emoji {
+    emoji: &'static str,
+    name: &'static str,
+    category: EmojiCategory,
+    /// Whether the emoji can have a skin tone.
+    toned: bool,
+}
+
+const fn emoji(emoji: &'static str, name: &'static str, category: EmojiCategory) -> Emoji {
+    Emoji {
+        emoji,
+        name,
+        category,
+        toned: false,
+    }
+}
+
+const fn toned(emoji: &'static str, name: &'static str) -> Emoji {
+    Emoji {
+        emoji,
+        name,
+        category: EmojiCategory::People,
+        toned: true,
+    }
+}
+
+const EMOJI: &[Emoji] = {
+    use EmojiCategory::*;
+    &[
+        emoji("😀", "grinning face", Smileys),
+        emoji("😂", "face with tears of joy", Smileys),
+        emoji("😊", "smiling face with smiling eyes", Smileys),
+        emoji("😍", "smiling face with heart eyes", Smileys),
+        emoji("😎", "smiling face with sunglasses", Smileys),
+        emoji("🤔", "thinking face", Smileys),
+        emoji("😢", "crying face", Smileys),
+        emoji("😡", "angry face", Smileys),
+        emoji("😴", "sleeping face", Smileys),
+        emoji("🥳", "partying face", Smileys),
+        toned("👋", "waving hand"),
+        toned("👍", "thumbs up"),
+        toned("👎", "thumbs down"),
+        toned("👏", "clapping hands"),
+        toned("🙏", "folded hands"),
+        toned("💪", "flexed biceps"),
+        toned("✌", "victory hand"),
+        toned("👌", "ok hand"),
+        emoji("🐶", "dog face", Animals),
+        emoji("🐱", "cat face", Animals),
+        emoji("🦊", "fox", Animals),
+        emoji("🐻", "bear", Animals),
+        emoji("🐼", "panda", Animals),
+        emoji("🐸", "frog", Animals),
+        emoji("🐝", "honeybee", Animals),
+        emoji("🦋", "butterfly", Animals),
+        emoji("🍎", "red apple", Food),
+        emoji("🍌", "banana", Food),
+        emoji("🍕", "pizza", Food),
+        emoji("🍔", "hamburger", Food),
+        emoji("🌮", "taco", Food),
+        emoji("🍣", "sushi", Food),
+        emoji("☕", "hot beverage coffee", Food),
+        emoji("🍰", "shortcake", Food),
+        emoji("⚽", "soccer ball", Activities),
+        emoji("🏀", "basketball", Activities),
+        emoji("🎾", "tennis", Activities),
+        emoji("🎮", "video game", Activities),
+        emoji("🎲", "game die", Activities),
+        emoji("🎸", "guitar", Activities),
+        emoji("🎉", "party popper", Activities),
+        emoji("🏆", "trophy", Activities),
+        emoji("🚗", "automobile car", Travel),
+        emoji("🚲", "bicycle", Travel),
+        emoji("✈", "airplane", Travel),
+        emoji("🚀", "rocket", Travel),
+        emoji("🚢", "ship", Travel),
+        emoji("🏠", "house", Travel),
+        emoji("🌋", "volcano", Travel),
+        emoji("🗽", "statue of liberty", Travel),
+        emoji("💡", "light bulb", Objects),
+        emoji("📱", "mobile phone", Objects),
+        emoji("💻", "laptop", Objects),
+        emoji("📷", "camera", Objects),
+        emoji("📚", "books", Objects),
+        emoji("🔑", "key", Objects),
+        emoji("🔒", "locked", Objects),
+        emoji("✏", "pencil", Objects),
+        emoji("❤", "red heart", Symbols),
+        emoji("⭐", "star", Symbols),
+        emoji("✅", "check mark button", Symbols),
+        emoji("❌", "cross mark", Symbols),
+        emoji("⚠", "warning", Symbols),
+        emoji("❓", "question mark", Symbols),
+        emoji("💯", "hundred points", Symbols),
+        emoji("🔥", "fire", Symbols),
+    ]
+};
+
+/// The tab shown in an [`EmojiPicker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Recent,
+    Category(EmojiCategory),
+}
+
+/// A popover for picking emoji, with category tabs, search, skin tones
+/// and a tab of recently picked emoji.
+///
+/// The picker is drawn in a fixed area, when it's closed the area is left
+/// empty. Typing while it's open searches the emoji by name.
+///
+/// The recently picked emoji can be saved to a file, so that they're kept
+/// between runs of the app.
+///
+/// # Example
+/// ```
+/// use agape::widgets::EmojiPicker;
+///
+/// let picker = EmojiPicker::new()
+///     .recent_file("recent-emoji.txt")
+///     .on_pick(|emoji| println!("Picked {emoji}"));
+/// ```
+pub struct EmojiPicker {
+    id: GlobalId,
+    open: bool,
+    tab: Tab,
+    query: String,
+    skin_tone: SkinTone,
+    /// The recently picked emoji, with the most recent first.
+    recent: Vec<String>,
+    recent_file: Option<PathBuf>,
+    pick_fn: Option<PickFn>,
+}
+
+impl Default for EmojiPicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmojiPicker {
+    pub fn new() -> Self {
+        Self {
+            id: GlobalId::new(),
+            open: true,
+            tab: Tab::Category(EmojiCategory::Smileys),
+            query: String::new(),
+            skin_tone: SkinTone::Default,
+            recent: vec![],
+            recent_file: None,
+            pick_fn: None,
+        }
+    }
+
+    /// Called with the picked emoji. Emoji with a skin tone are made of
+    /// two characters, so the emoji is passed as a string.
+    pub fn on_pick(mut self, callback: impl FnMut(&str) + 'static) -> Self {
+        self.pick_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Load the recently picked emoji from a file, and save them to it
+    /// whenever an emoji is picked.
+    pub fn recent_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        // The file won't exist until the first emoji is picked
+        if let Ok(contents) = std::fs::read_to_string(&path) {
+            self.recent = contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .take(RECENT_LIMIT)
+                .map(String::from)
+                .collect();
+        }
+        if !self.recent.is_empty() {
+            self.tab = Tab::Recent;
+        }
+        self.recent_file = Some(path);
+        self
+    }
+
+    /// Start closed, the picker can be opened with [`EmojiPicker::open`].
+    pub fn closed(mut self) -> Self {
+        self.open = false;
+        self
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.query.clear();
+    }
+
+    pub fn toggle(&mut self) {
+        if self.open {
+            self.close();
+        } else {
+            self.open();
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show the emoji whose names contain `query`, an empty query shows
+    /// the selected tab again.
+    pub fn search(&mut self, query: &str) {
+        self.query = query.to_owned();
+    }
+
+    pub fn select_category(&mut self, category: EmojiCategory) {
+        self.tab = Tab::Category(category);
+        self.query.clear();
+    }
+
+    pub fn set_skin_tone(&mut self, tone: SkinTone) {
+        self.skin_tone = tone;
+    }
+
+    pub fn get_skin_tone(&self) -> SkinTone {
+        self.skin_tone
+    }
+
+    /// Get the recently picked emoji, with the most recent first.
+    pub fn recent(&self) -> &[String] {
+        &self.recent
+    }
+
+    /// Get the emoji that are shown, in the current skin tone.
+    pub fn results(&self) -> Vec<String> {
+        if self.query.is_empty() && self.tab == Tab::Recent {
+            return self.recent.clone();
+        }
+
+        let query = self.query.to_lowercase();
+        EMOJI
+            .iter()
+            .filter(|emoji| match self.tab {
+                _ if !query.is_empty() => emoji.name.contains(&query),
+                Tab::Category(category) => emoji.category == category,
+                Tab::Recent => false,
+            })
+            .take(RECENT_LIMIT)
+            .map(|emoji| self.apply_tone(emoji))
+            .collect()
+    }
+
+    fn apply_tone(&self, emoji: &Emoji) -> String {
+        let mut text = emoji.emoji.to_owned();
+        if emoji.toned {
+            text.extend(self.skin_tone.modifier());
+        }
+        text
+    }
+
+    /// Pick an emoji, as if it was clicked.
+    pub fn pick(&mut self, emoji: &str) {
+        self.recent.retain(|recent| recent != emoji);
+        self.recent.insert(0, emoji.to_owned());
+        self.recent.truncate(RECENT_LIMIT);
+        self.save_recent();
+
+        if let Some(func) = &mut self.pick_fn {
+            func(emoji);
+        }
+    }
+
+    fn save_recent(&self) {
+        let Some(path) = &self.recent_file else {
+            return;
+        };
+        if let Err(err) = std::fs::write(path, self.recent.join("\n")) {
+            log::warn!("Failed to save recent emoji to {}: {err}", path.display());
+        }
+    }
+
+    fn tabs(&self) -> Vec<Tab> {
+        let categories = EmojiCategory::ALL.into_iter().map(Tab::Category);
+        std::iter::once(Tab::Recent).chain(categories).collect()
+    }
+
+    fn width(&self) -> f32 {
+        EMOJI_COLUMNS as f32 * EMOJI_CELL
+    }
+}
+
+impl Focusable for EmojiPicker {}
+
+impl KeyboardInput for EmojiPicker {
+    fn key_input(&mut self, event: &KeyEvent) {
+        if !self.open || event.state != ElementState::Pressed {
+            return;
+        }
+        if event.logical_key == Key::Named(NamedKey::Backspace) {
+            self.query.pop();
+        } else if event.logical_key == Key::Named(NamedKey::Escape) {
+            self.close();
+        } else if let Some(text) = &event.text {
+            self.query.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+}
+
+impl Widget for EmojiPicker {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let height = PICKER_BAR_HEIGHT * 3.0 + EMOJI_ROWS as f32 * EMOJI_CELL;
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width(), height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = EmojiPickerView::new(self.id);
+        view.open = self.open;
+        view.tabs = self
+            .tabs()
+            .into_iter()
+            .map(|tab| {
+                let icon = match tab {
+                    Tab::Recent => "🕘",
+                    Tab::Category(category) => category.icon(),
+                };
+                (icon.to_owned(), tab == self.tab && self.query.is_empty())
+            })
+            .collect();
+        view.query = self.query.clone();
+        view.cells = self.results();
+        view.skin_tones = SkinTone::ALL
+            .iter()
+            .map(|tone| (tone.color(), *tone == self.skin_tone))
+            .collect();
+        Box::new(view)
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+        Some(self)
+    }
+
+    fn drag_start(&mut self, position: Position) {
+        if !self.open {
+            return;
+        }
+
+        let tabs = self.tabs();
+        let grid_top = PICKER_BAR_HEIGHT * 2.0;
+        let grid_bottom = grid_top + EMOJI_ROWS as f32 * EMOJI_CELL;
+        if position.y < PICKER_BAR_HEIGHT {
+            let tab_width = self.width() / tabs.len() as f32;
+            let index = (position.x / tab_width) as usize;
+            if let Some(tab) = tabs.get(index) {
+                self.tab = *tab;
+                self.query.clear();
+            }
+        } else if position.y >= grid_top && position.y < grid_bottom {
+            let column = (position.x / EMOJI_CELL) as usize;
+            let row = ((position.y - grid_top) / EMOJI_CELL) as usize;
+            if column < EMOJI_COLUMNS {
+                if let Some(emoji) = self.results().get(row * EMOJI_COLUMNS + column) {
+                    self.pick(&emoji.clone());
+                }
+            }
+        } else if position.y >= grid_bottom {
+            let index = (position.x / EMOJI_CELL) as usize;
+            if let Some(tone) = SkinTone::ALL.get(index) {
+                self.skin_tone = *tone;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn search_by_name() {
+        let mut picker = EmojiPicker::new();
+        picker.search("Pizza");
+        assert_eq!(picker.results(), vec!["🍕"]);
+
+        picker.search("");
+        assert_eq!(picker.results().len(), 10);
+    }
+
+    #[test]
+    fn skin_tones() {
+        let mut picker = EmojiPicker::new();
+        picker.select_category(EmojiCategory::People);
+        picker.set_skin_tone(SkinTone::Dark);
+        assert_eq!(picker.results()[0], "👋\u{1F3FF}");
+
+        // Emoji without tones are unchanged
+        picker.select_category(EmojiCategory::Animals);
+        assert_eq!(picker.results()[0], "🐶");
+    }
+
+    #[test]
+    fn click_to_pick() {
+        let picked = Rc::new(RefCell::new(String::new()));
+        let value = Rc::clone(&picked);
+        let mut picker =
+            EmojiPicker::new().on_pick(move |emoji| *value.borrow_mut() = emoji.into());
+
+        // The second emoji in the grid
+        let y = PICKER_BAR_HEIGHT * 2.0 + 4.0;
+        picker.drag_start(Position::new(EMOJI_CELL + 4.0, y));
+        assert_eq!(picked.borrow().as_str(), "😂");
+        assert_eq!(picker.recent(), ["😂"]);
+    }
+
+    #[test]
+    fn recent_emoji_are_unique() {
+        let mut picker = EmojiPicker::new();
+        picker.pick("🍕");
+        picker.pick("🔥");
+        picker.pick("🍕");
+        assert_eq!(picker.recent(), ["🍕", "🔥"]);
+    }
+
+    #[test]
+    fn persist_recent_emoji() {
+        let path = std::env::temp_dir().join(format!(
+            "agape-recent-{}-{}",
+            std::process::id(),
+            GlobalId::new()
+        ));
+        let mut picker = EmojiPicker::new().recent_file(&path);
+        picker.pick("🎉");
+        picker.pick("🚀");
+
+        let picker = EmojiPicker::new().recent_file(&path);
+        assert_eq!(picker.recent(), ["🚀", "🎉"]);
+        assert_eq!(picker.results(), vec!["🚀", "🎉"]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn closed_picker_ignores_clicks() {
+        let mut picker = EmojiPicker::new().closed();
+        picker.drag_start(Position::new(4.0, PICKER_BAR_HEIGHT * 2.0 + 4.0));
+        assert!(picker.recent().is_empty());
+    }
+}
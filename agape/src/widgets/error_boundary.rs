@@ -0,0 +1,239 @@
+use super::{Reactive, Scope, Widget};
+use crate::error_boundary::{self, BoundaryError, RetryBoundary};
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, EmptyLayout, Layout};
+use std::any::Any;
+
+type FallbackFn = Box<dyn Fn(&BoundaryError) -> Box<dyn Widget>>;
+type ErrorFn = Box<dyn FnMut(&BoundaryError)>;
+
+/// Shows a fallback widget when its children panic while they're laid
+/// out or drawn, created with [`Widget::error_boundary`]. See the
+/// [`error_boundary`](crate::error_boundary) module.
+pub struct ErrorBoundary {
+    id: GlobalId,
+    child: Box<dyn Widget>,
+    fallback_fn: FallbackFn,
+    /// Shown in place of the child after it panicked.
+    fallback: Option<Box<dyn Widget>>,
+    on_error: Option<ErrorFn>,
+}
+
+impl ErrorBoundary {
+    pub fn new<W: Widget + 'static>(
+        child: impl Widget + 'static,
+        fallback: impl Fn(&BoundaryError) -> W + 'static,
+    ) -> Self {
+        Self {
+            id: GlobalId::new(),
+            child: Box::new(child),
+            fallback_fn: Box::new(move |error| Box::new(fallback(error))),
+            fallback: None,
+            on_error: None,
+        }
+    }
+
+    /// Call a function when a panic is caught.
+    pub fn on_error(mut self, f: impl FnMut(&BoundaryError) + 'static) -> Self {
+        self.on_error = Some(Box::new(f));
+        self
+    }
+
+    /// Returns `true` if the fallback is shown.
+    pub fn has_failed(&self) -> bool {
+        self.fallback.is_some()
+    }
+
+    /// Show the children again, in place of the fallback.
+    pub fn retry(&mut self) {
+        self.fallback = None;
+    }
+
+    fn current(&self) -> &dyn Widget {
+        self.fallback.as_deref().unwrap_or(self.child.as_ref())
+    }
+
+    fn current_mut(&mut self) -> &mut dyn Widget {
+        match &mut self.fallback {
+            Some(fallback) => fallback.as_mut(),
+            None => self.child.as_mut(),
+        }
+    }
+}
+
+impl Widget for ErrorBoundary {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let child = self.current();
+        let child_layout = match self.has_failed() {
+            true => child.layout(),
+            false => error_boundary::catch(self.id, || child.layout())
+                .unwrap_or_else(|| Box::new(EmptyLayout::new())),
+        };
+        let mut layout = BlockLayout::new(child_layout);
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.current()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        let child = self.current();
+        f(child);
+        child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        let child = self.current_mut();
+        f(child);
+        child.traverse_mut(f);
+    }
+
+    fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+        Some(self)
+    }
+
+    fn as_scope(&self) -> Option<&dyn Scope> {
+        Some(self)
+    }
+}
+
+impl Scope for ErrorBoundary {
+    fn catches_panics(&self) -> bool {
+        !self.has_failed()
+    }
+}
+
+impl Reactive for ErrorBoundary {
+    fn app_event(&mut self, event: &dyn Any) {
+        if let Some(RetryBoundary(id)) = event.downcast_ref() {
+            if *id == self.id {
+                self.retry();
+            }
+        }
+        let Some(error) = event.downcast_ref::<BoundaryError>() else {
+            return;
+        };
+        // Panics that were caught again before the fallback was shown are
+        // only handled once
+        if error.boundary != self.id || self.has_failed() {
+            return;
+        }
+        self.fallback = Some((self.fallback_fn)(error));
+        if let Some(on_error) = &mut self.on_error {
+            on_error(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::view::DisplayList;
+    use crate::widgets::{Rect, Text};
+    use crate::{Resources, hstack};
+    use agape_core::Size;
+    use agape_layout::LayoutSolver;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tiny_skia::Pixmap;
+
+    struct Faulty {
+        id: GlobalId,
+        panics_in_layout: bool,
+    }
+
+    impl Faulty {
+        fn new(panics_in_layout: bool) -> Self {
+            Self {
+                id: GlobalId::new(),
+                panics_in_layout,
+            }
+        }
+    }
+
+    impl Widget for Faulty {
+        fn id(&self) -> GlobalId {
+            self.id
+        }
+
+        fn view(&self) -> Box<dyn View> {
+            panic!("view failed");
+        }
+
+        fn layout(&self) -> Box<dyn Layout> {
+            if self.panics_in_layout {
+                panic!("layout failed");
+            }
+            let mut layout = EmptyLayout::new();
+            layout.id = self.id;
+            Box::new(layout)
+        }
+    }
+
+    fn fallback(_: &BoundaryError) -> Rect {
+        Rect::new(20.0, 20.0)
+    }
+
+    #[test]
+    fn catch_panics_in_view() {
+        let boundary = Faulty::new(false).error_boundary(fallback);
+        let root = hstack! {boundary, Rect::new(10.0, 10.0)};
+        let mut layout = root.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+
+        let list = DisplayList::record(&root, layout.as_ref());
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+        list.render(&mut pixmap, &Resources::new());
+    }
+
+    #[test]
+    fn catch_panics_in_layout() {
+        let boundary = Faulty::new(true).error_boundary(fallback);
+        let root = hstack! {boundary, Rect::new(10.0, 10.0)};
+        let mut layout = root.layout();
+        LayoutSolver::solve(&mut *layout, Size::unit(100.0));
+        DisplayList::record(&root, layout.as_ref());
+    }
+
+    #[test]
+    fn show_fallback_on_error() {
+        let errors = Rc::new(Cell::new(0));
+        let mut boundary = Text::new("Chart").error_boundary(fallback).on_error({
+            let errors = errors.clone();
+            move |_| errors.set(errors.get() + 1)
+        });
+        let error = BoundaryError {
+            boundary: boundary.id(),
+            message: String::from("view failed"),
+        };
+
+        boundary.app_event(&BoundaryError {
+            boundary: GlobalId::new(),
+            message: String::new(),
+        });
+        assert!(!boundary.has_failed());
+        boundary.app_event(&error);
+        boundary.app_event(&error);
+        assert!(boundary.has_failed());
+        assert!(!boundary.catches_panics());
+        assert_eq!(errors.get(), 1);
+        assert_ne!(boundary.children()[0].id(), boundary.child.id());
+
+        boundary.app_event(&RetryBoundary(boundary.id()));
+        assert!(!boundary.has_failed());
+        assert_eq!(boundary.children()[0].id(), boundary.child.id());
+    }
+}
@@ -0,0 +1,123 @@
+use super::Widget;
+use crate::format;
+use crate::locale::Locale;
+use crate::view::{TextView, View};
+use agape_core::GlobalId;
+use agape_layout::{Layout, TextLayout};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+type FormatFn = Arc<dyn Fn() -> String + Send + Sync>;
+
+/// Text that is formatted every frame, so values like relative times stay
+/// up to date on their own.
+///
+/// # Example
+/// ```
+/// use agape::widgets::FormattedText;
+/// use agape::locale::Locale;
+/// use std::time::SystemTime;
+///
+/// let locale = Locale::current();
+/// let price = FormattedText::currency(19.99, "$", &locale);
+/// let download = FormattedText::bytes(52_000_000, &locale);
+/// // Changes from "just now" to "1 min ago" after a minute
+/// let edited = FormattedText::relative_time(SystemTime::now()).font_size(12);
+/// ```
+pub struct FormattedText {
+    id: GlobalId,
+    format: FormatFn,
+    font_size: u8,
+}
+
+impl FormattedText {
+    /// Create text that is formatted by `f` every frame.
+    pub fn new(f: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        Self {
+            id: GlobalId::new(),
+            format: Arc::new(f),
+            font_size: 16,
+        }
+    }
+
+    /// Text that doesn't change.
+    fn fixed(text: String) -> Self {
+        Self::new(move || text.clone())
+    }
+
+    /// Format an amount of money, see [`format::currency`].
+    pub fn currency(amount: f64, symbol: &str, locale: &Locale) -> Self {
+        Self::fixed(format::currency(amount, symbol, locale))
+    }
+
+    /// Format a fraction as a percentage, see [`format::percent`].
+    pub fn percent(value: f64, decimals: usize, locale: &Locale) -> Self {
+        Self::fixed(format::percent(value, decimals, locale))
+    }
+
+    /// Format a number of bytes, see [`format::bytes`].
+    pub fn bytes(bytes: u64, locale: &Locale) -> Self {
+        Self::fixed(format::bytes(bytes, locale))
+    }
+
+    /// Format a duration, see [`format::duration`].
+    pub fn duration(duration: Duration) -> Self {
+        Self::fixed(format::duration(duration))
+    }
+
+    /// Show how long ago a `time` was, which is updated as time passes.
+    ///
+    /// The text is only formatted again when it changes, every minute at
+    /// first, then every hour and then every day.
+    pub fn relative_time(time: SystemTime) -> Self {
+        let cache = Mutex::new((String::new(), SystemTime::UNIX_EPOCH));
+        Self::new(move || {
+            let now = SystemTime::now();
+            let mut cache = cache.lock().unwrap();
+            let (text, refresh_at) = &mut *cache;
+            if now >= *refresh_at {
+                *text = format::relative_time(time, now);
+                *refresh_at = now + format::relative_time_refresh(time, now);
+            }
+            text.clone()
+        })
+    }
+
+    pub fn font_size(mut self, font_size: u8) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Get the text as it's formatted right now.
+    pub fn text(&self) -> String {
+        (self.format)()
+    }
+}
+
+/// Format the text and create its view.
+fn text_view(format: &FormatFn, id: GlobalId, font_size: u8) -> TextView {
+    let mut view = TextView::new(&format());
+    view.set_id(id);
+    view.font_size = font_size;
+    view
+}
+
+impl Widget for FormattedText {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        // The text is measured every time the layout is solved, so the size
+        // follows the text as it changes
+        let (format, id, font_size) = (Arc::clone(&self.format), self.id, self.font_size);
+        let measure = move |_| text_view(&format, id, font_size).text_size();
+        let mut layout = TextLayout::new(Arc::new(measure));
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        Box::new(text_view(&self.format, self.id, self.font_size))
+    }
+}
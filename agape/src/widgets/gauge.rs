@@ -0,0 +1,171 @@
+use super::Widget;
+use crate::view::{GaugeView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// A colored section of a [`Gauge`]'s scale, used to mark thresholds.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct GaugeRange {
+    pub from: f32,
+    pub to: f32,
+    pub color: Color<Rgba>,
+}
+
+/// Shows a value on a circular scale with a needle, like a speedometer.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Gauge;
+/// use agape::Color;
+///
+/// let cpu = Gauge::new(0.0, 100.0)
+///     .value(72.0)
+///     .range(0.0, 60.0, Color::GREEN)
+///     .range(60.0, 85.0, Color::AMBER)
+///     .range(85.0, 100.0, Color::RED)
+///     .unit("%");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    id: GlobalId,
+    min: f32,
+    max: f32,
+    value: f32,
+    ranges: Vec<GaugeRange>,
+    track_color: Color<Rgba>,
+    needle_color: Color<Rgba>,
+    stroke_width: f32,
+    diameter: f32,
+    precision: usize,
+    unit: String,
+    show_label: bool,
+}
+
+impl Gauge {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            min,
+            max,
+            value: min,
+            ranges: vec![],
+            track_color: Color::rgb(230, 230, 230),
+            needle_color: Color::BLACK,
+            stroke_width: 8.0,
+            diameter: 120.0,
+            precision: 0,
+            unit: String::new(),
+            show_label: true,
+        }
+    }
+
+    /// Set the value, it's clamped to the scale.
+    pub fn value(mut self, value: f32) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.clamp(self.min.min(self.max), self.max.max(self.min));
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    /// Color a section of the scale.
+    pub fn range(mut self, from: f32, to: f32, color: impl IntoColor<Rgba>) -> Self {
+        self.ranges.push(GaugeRange {
+            from,
+            to,
+            color: color.into_color(),
+        });
+        self
+    }
+
+    pub fn track_color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.track_color = color.into_color();
+        self
+    }
+
+    pub fn needle_color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.needle_color = color.into_color();
+        self
+    }
+
+    pub fn stroke_width(mut self, width: f32) -> Self {
+        self.stroke_width = width;
+        self
+    }
+
+    pub fn diameter(mut self, diameter: f32) -> Self {
+        self.diameter = diameter;
+        self
+    }
+
+    /// Set the number of decimal places shown in the label.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Set the unit shown after the value.
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = unit.to_owned();
+        self
+    }
+
+    /// Hide the value label.
+    pub fn hide_label(mut self) -> Self {
+        self.show_label = false;
+        self
+    }
+
+    /// Get the text of the value label.
+    pub fn label(&self) -> String {
+        format!("{:.*}{}", self.precision, self.value, self.unit)
+    }
+}
+
+impl Widget for Gauge {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.diameter, self.diameter);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = GaugeView::new(self.id);
+        view.min = self.min;
+        view.max = self.max;
+        view.value = self.value;
+        view.ranges = self.ranges.clone();
+        view.track_color = self.track_color.clone();
+        view.needle_color = self.needle_color.clone();
+        view.stroke_width = self.stroke_width;
+        view.label = self.show_label.then(|| self.label());
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clamp_value() {
+        assert_eq!(Gauge::new(0.0, 10.0).value(20.0).get_value(), 10.0);
+        assert_eq!(Gauge::new(0.0, 10.0).value(-5.0).get_value(), 0.0);
+    }
+
+    #[test]
+    fn format_label() {
+        let gauge = Gauge::new(0.0, 100.0).value(42.5).precision(1).unit("%");
+        assert_eq!(gauge.label(), "42.5%");
+    }
+}
@@ -0,0 +1,135 @@
+use super::Widget;
+use crate::view::{HeatmapView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use std::rc::Rc;
+
+/// A grid of cells colored by their value, hovering over a cell shows
+/// its value.
+///
+/// Values are laid out row by row, the colors are interpolated between
+/// the `low` and `high` colors.
+///
+/// ```
+/// use agape::widgets::Heatmap;
+/// use agape::Color;
+///
+/// let activity = Heatmap::new(7, vec![0.0, 2.0, 5.0, 1.0, 0.0, 3.0, 8.0])
+///     .colors(Color::WHITE, Color::rgb(22, 101, 52))
+///     .cell_size(12.0)
+///     .gap(2.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    id: GlobalId,
+    columns: usize,
+    values: Rc<[f32]>,
+    low: Color<Rgba>,
+    high: Color<Rgba>,
+    range: Option<(f32, f32)>,
+    cell_size: f32,
+    gap: f32,
+}
+
+impl Heatmap {
+    /// Create a heatmap with the number of `columns`.
+    pub fn new(columns: usize, values: impl Into<Rc<[f32]>>) -> Self {
+        Self {
+            id: GlobalId::new(),
+            columns: columns.max(1),
+            values: values.into(),
+            low: Color::WHITE,
+            high: Color::BLACK,
+            range: None,
+            cell_size: 12.0,
+            gap: 1.0,
+        }
+    }
+
+    /// Set the colors of the smallest and largest values.
+    pub fn colors(mut self, low: impl IntoColor<Rgba>, high: impl IntoColor<Rgba>) -> Self {
+        self.low = low.into_color();
+        self.high = high.into_color();
+        self
+    }
+
+    /// Set the range of the values, by default the smallest and largest
+    /// values are used.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    pub fn cell_size(mut self, size: f32) -> Self {
+        self.cell_size = size;
+        self
+    }
+
+    /// Set the space between cells.
+    pub fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    fn value_range(&self) -> (f32, f32) {
+        if let Some(range) = self.range {
+            return range;
+        }
+
+        self.values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f32::MAX, f32::MIN), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    }
+}
+
+impl Widget for Heatmap {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let rows = self.values.len().div_ceil(self.columns);
+        let size = |cells: usize| {
+            let cells = cells as f32;
+            (self.cell_size * cells + self.gap * (cells - 1.0)).max(0.0)
+        };
+
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(size(self.columns), size(rows));
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = HeatmapView::new(self.id, self.columns, Rc::clone(&self.values));
+        view.low = self.low.clone();
+        view.high = self.high.clone();
+        view.range = self.value_range();
+        view.gap = self.gap;
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_layout::BoxSizing;
+
+    #[test]
+    fn size_from_cells() {
+        let heatmap = Heatmap::new(3, vec![0.0; 5]).cell_size(10.0).gap(2.0);
+        let layout = heatmap.layout();
+        assert_eq!(layout.intrinsic_size().width, BoxSizing::Fixed(34.0));
+        assert_eq!(layout.intrinsic_size().height, BoxSizing::Fixed(22.0));
+    }
+
+    #[test]
+    fn range_from_values() {
+        let heatmap = Heatmap::new(2, vec![3.0, -1.0, 8.0]);
+        assert_eq!(heatmap.value_range(), (-1.0, 8.0));
+        assert_eq!(heatmap.range(0.0, 10.0).value_range(), (0.0, 10.0));
+    }
+}
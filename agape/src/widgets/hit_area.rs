@@ -0,0 +1,82 @@
+use super::{PointerTarget, Widget};
+use crate::view::{HitShape, RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, Layout};
+
+/// Overrides the shape that a widget and its children are hit in, created
+/// with [`Widget::hit_shape`].
+///
+/// The cursor misses the widget and all of its children outside of the
+/// shape, even where the children are drawn.
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+///
+/// let button = Button::new(Text::new("+")).hit_shape(HitShape::Ellipse);
+/// ```
+pub struct HitArea {
+    id: GlobalId,
+    shape: HitShape,
+    child: Box<dyn Widget>,
+}
+
+impl HitArea {
+    pub fn new(child: impl Widget + 'static, shape: HitShape) -> Self {
+        Self {
+            id: GlobalId::new(),
+            shape,
+            child: Box::new(child),
+        }
+    }
+
+    pub fn shape(&self) -> HitShape {
+        self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: HitShape) {
+        self.shape = shape;
+    }
+}
+
+impl Widget for HitArea {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+
+    fn as_pointer_target(&self) -> Option<&dyn PointerTarget> {
+        Some(self)
+    }
+}
+
+impl PointerTarget for HitArea {
+    fn hit_area(&self) -> Option<HitShape> {
+        Some(self.shape)
+    }
+}
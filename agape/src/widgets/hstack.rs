@@ -1,6 +1,7 @@
+use crate::impl_style;
 use crate::style::BoxStyle;
 use crate::view::{RectView, View};
-use crate::{impl_style, widgets::Widget};
+use crate::widgets::{LayoutExt, Widget};
 use agape_core::GlobalId;
 use agape_layout::{AxisAlignment, HorizontalLayout, Layout};
 
@@ -54,35 +55,31 @@ impl HStack {
         self
     }
 
-    pub fn padding(mut self, padding: u32) -> Self {
-        self.layout.padding = padding;
-        self
-    }
-
     pub fn spacing(mut self, spacing: u32) -> Self {
         self.layout.spacing = spacing;
         self
     }
+}
 
-    pub fn align_center(mut self) -> Self {
-        self.layout.main_axis_alignment = AxisAlignment::Center;
-        self.layout.cross_axis_alignment = AxisAlignment::Center;
+impl LayoutExt for HStack {
+    fn padding(mut self, padding: u32) -> Self {
+        self.layout.padding = padding;
         self
     }
 
-    pub fn main_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+    fn main_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
         self.layout.main_axis_alignment = alignment;
         self
     }
 
-    pub fn cross_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+    fn cross_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
         self.layout.cross_axis_alignment = alignment;
         self
     }
-
-    impl_style!();
 }
 
+impl_style!(HStack);
+
 // TODO test this
 impl Widget for HStack {
     fn id(&self) -> GlobalId {
@@ -104,12 +101,7 @@ impl Widget for HStack {
     }
 
     fn view(&self) -> Box<dyn View> {
-        let view = RectView {
-            id: self.id,
-            color: self.style.background_color.clone(),
-            border: self.style.border.clone(),
-            ..Default::default()
-        };
+        let view = RectView::with_style(self.id, &self.style);
 
         Box::new(view)
     }
@@ -157,7 +149,7 @@ impl Widget for HStack {
 ///
 /// - Create an [`Hstack`] from a list of widgets.
 /// ```
-/// use agape::{hstack,widgets::{Rect}};
+/// use agape::{hstack,widgets::{LayoutExt,Rect}};
 ///
 /// hstack!{
 ///     Rect::new(100.0,200.0),
@@ -203,6 +195,7 @@ macro_rules! hstack {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::style::Styled;
     use crate::widgets::{Rect, Text};
     use agape_layout::BoxSizing;
 
@@ -0,0 +1,157 @@
+use super::{Role, Semantics, Widget};
+use crate::image_cache::ImageHandle;
+use crate::impl_style;
+use crate::style::BoxStyle;
+use crate::view::{ImageView, NineSlice, View};
+use agape_core::GlobalId;
+use agape_layout::{BoxSizing, EmptyLayout, Layout};
+use std::io::Cursor;
+use std::rc::Rc;
+
+/// Displays an encoded image, such as a png or jpeg.
+///
+/// Images are decoded the first time they are drawn and stored in the
+/// [`ImageCache`](crate::image_cache::ImageCache), use
+/// [`App::preload_image`](crate::App::preload_image) to decode them
+/// ahead of time.
+///
+/// ```no_run
+/// use agape::style::Styled;
+/// use agape::widgets::Image;
+///
+/// let bytes = std::fs::read("logo.png").unwrap();
+/// let image = Image::new(bytes).fixed(64.0, 64.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    id: GlobalId,
+    bytes: Rc<[u8]>,
+    handle: ImageHandle,
+    nine_slice: Option<NineSlice>,
+    style: BoxStyle,
+}
+
+impl Image {
+    /// Create an image from its encoded bytes, the image is sized to its
+    /// dimensions by default.
+    pub fn new(bytes: impl Into<Rc<[u8]>>) -> Self {
+        let bytes = bytes.into();
+        let mut style = BoxStyle::new();
+        if let Some((width, height)) = dimensions(&bytes) {
+            style.intrinsic_size.width = BoxSizing::Fixed(width as f32);
+            style.intrinsic_size.height = BoxSizing::Fixed(height as f32);
+        }
+
+        Self {
+            id: GlobalId::new(),
+            handle: ImageHandle::from_bytes(&bytes),
+            bytes,
+            nine_slice: None,
+            style,
+        }
+    }
+
+    /// Display an image that was rendered into the image cache, such as a
+    /// [texture](crate::texture), at a size.
+    pub fn texture(handle: ImageHandle, width: f32, height: f32) -> Self {
+        let mut style = BoxStyle::new();
+        style.fixed(width, height);
+        Self {
+            id: GlobalId::new(),
+            handle,
+            bytes: Rc::from([]),
+            nine_slice: None,
+            style,
+        }
+    }
+
+    /// Scale the image with a [`NineSlice`], so that its corners aren't
+    /// distorted, for bitmap buttons and panels.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use agape::style::Styled;
+    /// use agape::view::NineSlice;
+    /// use agape::widgets::Image;
+    ///
+    /// let bytes = std::fs::read("panel.png").unwrap();
+    /// let panel = Image::new(bytes)
+    ///     .nine_slice(NineSlice::new(12.0))
+    ///     .fixed(300.0, 120.0);
+    /// ```
+    pub fn nine_slice(mut self, slice: NineSlice) -> Self {
+        self.nine_slice = Some(slice);
+        self
+    }
+
+    /// Get the key of the image in the image cache.
+    pub fn handle(&self) -> ImageHandle {
+        self.handle
+    }
+}
+
+/// Read the dimensions of the image without decoding it.
+pub(crate) fn dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+impl_style!(Image);
+
+impl Semantics for Image {
+    fn role(&self) -> Role {
+        Role::Image
+    }
+}
+
+impl Widget for Image {
+    fn as_semantics(&self) -> Option<&dyn Semantics> {
+        Some(self)
+    }
+
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = self.style.intrinsic_size;
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = ImageView::new(self.id, self.handle, Rc::clone(&self.bytes));
+        view.nine_slice = self.nine_slice;
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use image::{ImageFormat, RgbaImage};
+
+    #[test]
+    fn size_from_dimensions() {
+        let mut bytes = Cursor::new(vec![]);
+        RgbaImage::new(24, 12)
+            .write_to(&mut bytes, ImageFormat::Png)
+            .unwrap();
+
+        let image = Image::new(bytes.into_inner());
+        let layout = image.layout();
+        assert_eq!(layout.intrinsic_size().width, BoxSizing::Fixed(24.0));
+        assert_eq!(layout.intrinsic_size().height, BoxSizing::Fixed(12.0));
+    }
+
+    #[test]
+    fn same_handle_for_same_bytes() {
+        let first = Image::new(vec![1, 2, 3]);
+        let second = Image::new(vec![1, 2, 3]);
+        assert_eq!(first.handle(), second.handle());
+    }
+}
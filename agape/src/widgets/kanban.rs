@@ -0,0 +1,486 @@
+use super::Widget;
+use crate::view::{COLUMN_HEADER, DraggedCard, KanbanMetrics, KanbanView, View};
+use agape_core::{GlobalId, Position, Size};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+type MoveFn = Box<dyn FnMut(&KanbanCard, usize, usize, usize)>;
+
+/// A card on a [`KanbanBoard`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KanbanCard {
+    pub title: String,
+}
+
+impl KanbanCard {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+        }
+    }
+}
+
+/// A column of cards on a [`KanbanBoard`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KanbanColumn {
+    pub title: String,
+    pub cards: Vec<KanbanCard>,
+    /// The maximum number of cards allowed in the column.
+    pub wip_limit: Option<usize>,
+    pub collapsed: bool,
+}
+
+impl KanbanColumn {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            cards: vec![],
+            wip_limit: None,
+            collapsed: false,
+        }
+    }
+
+    /// Returns `true` if the column has more cards than its WIP limit.
+    pub fn is_over_limit(&self) -> bool {
+        self.wip_limit.is_some_and(|limit| self.cards.len() > limit)
+    }
+
+    /// Returns `true` if another card can be moved into the column.
+    pub fn is_full(&self) -> bool {
+        self.wip_limit
+            .is_some_and(|limit| self.cards.len() >= limit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    Card {
+        column: usize,
+        index: usize,
+        /// The cursor position, relative to the board.
+        cursor: Position,
+        /// The cursor position relative to the card's top left corner.
+        offset: Position,
+    },
+    Header(usize),
+}
+
+/// A kanban board, with columns of cards that can be dragged between
+/// columns.
+///
+/// Columns can have a work in progress (WIP) limit, cards can't be moved
+/// into a column that is already at its limit. Clicking a column's header
+/// collapses it, cards can still be dropped onto collapsed columns.
+///
+/// # Example
+/// ```
+/// use agape::widgets::KanbanBoard;
+///
+/// let board = KanbanBoard::new()
+///     .column("To do")
+///     .column("Doing")
+///     .column("Done")
+///     .wip_limit(1, 3)
+///     .card(0, "Write docs")
+///     .card(0, "Fix bugs")
+///     .on_card_move(|card, from, to, index| {
+///         println!("Moved {} from {from} to {to} at {index}", card.title);
+///     });
+/// ```
+pub struct KanbanBoard {
+    id: GlobalId,
+    columns: Vec<KanbanColumn>,
+    column_width: f32,
+    card_height: f32,
+    drag: Option<Drag>,
+    move_fn: Option<MoveFn>,
+}
+
+impl Default for KanbanBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KanbanBoard {
+    pub fn new() -> Self {
+        Self {
+            id: GlobalId::new(),
+            columns: vec![],
+            column_width: 220.0,
+            card_height: 48.0,
+            drag: None,
+            move_fn: None,
+        }
+    }
+
+    /// Add a column.
+    pub fn column(mut self, title: &str) -> Self {
+        self.columns.push(KanbanColumn::new(title));
+        self
+    }
+
+    /// Add a card to the end of a column.
+    ///
+    /// # Panics
+    /// Panics if the column doesn't exist.
+    pub fn card(mut self, column: usize, title: &str) -> Self {
+        self.columns[column].cards.push(KanbanCard::new(title));
+        self
+    }
+
+    /// Set the maximum number of cards in a column.
+    ///
+    /// # Panics
+    /// Panics if the column doesn't exist.
+    pub fn wip_limit(mut self, column: usize, limit: usize) -> Self {
+        self.columns[column].wip_limit = Some(limit);
+        self
+    }
+
+    /// Start with a column collapsed.
+    ///
+    /// # Panics
+    /// Panics if the column doesn't exist.
+    pub fn collapsed(mut self, column: usize) -> Self {
+        self.columns[column].collapsed = true;
+        self
+    }
+
+    pub fn column_width(mut self, width: f32) -> Self {
+        self.column_width = width;
+        self
+    }
+
+    pub fn card_height(mut self, height: f32) -> Self {
+        self.card_height = height;
+        self
+    }
+
+    /// Called with the card, the column it was moved from, the column it was
+    /// moved to and its new index when a card is dropped in a new place.
+    pub fn on_card_move(
+        mut self,
+        callback: impl FnMut(&KanbanCard, usize, usize, usize) + 'static,
+    ) -> Self {
+        self.move_fn = Some(Box::new(callback));
+        self
+    }
+
+    pub fn columns(&self) -> &[KanbanColumn] {
+        &self.columns
+    }
+
+    /// Collapse or expand a column.
+    pub fn toggle_column(&mut self, column: usize) {
+        if let Some(column) = self.columns.get_mut(column) {
+            column.collapsed = !column.collapsed;
+        }
+    }
+
+    /// Move a card to a new column and index, returns `false` if the target
+    /// column is at its WIP limit or the card doesn't exist.
+    ///
+    /// The index is clamped to the length of the target column.
+    pub fn move_card(&mut self, from: (usize, usize), to: usize, index: usize) -> bool {
+        let (column, card) = from;
+        if to >= self.columns.len()
+            || self
+                .columns
+                .get(column)
+                .is_none_or(|c| card >= c.cards.len())
+        {
+            return false;
+        }
+        if to != column && self.columns[to].is_full() {
+            return false;
+        }
+
+        let card = self.columns[column].cards.remove(card);
+        let index = index.min(self.columns[to].cards.len());
+        self.columns[to].cards.insert(index, card);
+        if let Some(func) = &mut self.move_fn {
+            func(&self.columns[to].cards[index], column, to, index);
+        }
+        true
+    }
+
+    fn metrics(&self) -> KanbanMetrics {
+        KanbanMetrics {
+            column_width: self.column_width,
+            card_height: self.card_height,
+        }
+    }
+
+    /// Find the card under the position.
+    fn card_at(&self, position: Position) -> Option<(usize, usize)> {
+        let metrics = self.metrics();
+        let column = metrics.column_at(&self.columns, position.x)?;
+        if self.columns[column].collapsed {
+            return None;
+        }
+        (0..self.columns[column].cards.len())
+            .find(|&index| {
+                let bounds = metrics.card_bounds(&self.columns, column, index);
+                position.y >= bounds.y[0] && position.y <= bounds.y[1]
+            })
+            .map(|index| (column, index))
+    }
+
+    /// Get where the dragged card would be dropped, with the cursor at
+    /// `position`. The index is in the target column after the card has been
+    /// removed from its old column.
+    fn drop_slot(&self, card: (usize, usize), position: Position) -> Option<(usize, usize)> {
+        let metrics = self.metrics();
+        let target = metrics.column_at(&self.columns, position.x)?;
+        let column = &self.columns[target];
+        if target != card.0 && column.is_full() {
+            return None;
+        }
+        if column.collapsed {
+            let len = column.cards.len() - usize::from(target == card.0);
+            return Some((target, len));
+        }
+
+        let slot = metrics.slot_at(position.y, column.cards.len());
+        // Removing the card shifts the cards below it up
+        let index = if target == card.0 && slot > card.1 {
+            slot - 1
+        } else {
+            slot
+        };
+        Some((target, index))
+    }
+}
+
+impl Widget for KanbanBoard {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let metrics = self.metrics();
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size =
+            IntrinsicSize::fixed(metrics.width(&self.columns), metrics.height(&self.columns));
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let dragged = match self.drag {
+            Some(Drag::Card {
+                column,
+                index,
+                cursor,
+                offset,
+            }) => Some(DraggedCard {
+                card: (column, index),
+                position: Position::new(cursor.x - offset.x, cursor.y - offset.y),
+                target: self.drop_slot((column, index), cursor),
+            }),
+            _ => None,
+        };
+
+        Box::new(KanbanView {
+            id: self.id,
+            position: Position::default(),
+            size: Size::default(),
+            columns: self.columns.clone(),
+            column_width: self.column_width,
+            card_height: self.card_height,
+            dragged,
+        })
+    }
+
+    fn drag_start(&mut self, position: Position) {
+        let metrics = self.metrics();
+        if position.y < COLUMN_HEADER {
+            self.drag = metrics
+                .column_at(&self.columns, position.x)
+                .map(Drag::Header);
+            return;
+        }
+
+        self.drag = self.card_at(position).map(|(column, index)| {
+            let bounds = metrics.card_bounds(&self.columns, column, index);
+            Drag::Card {
+                column,
+                index,
+                cursor: position,
+                offset: Position::new(position.x - bounds.x[0], position.y - bounds.y[0]),
+            }
+        });
+    }
+
+    fn drag(&mut self, delta: Position) {
+        if let Some(Drag::Card { cursor, .. }) = &mut self.drag {
+            *cursor += delta;
+        }
+    }
+
+    fn drag_end(&mut self) {
+        match self.drag.take() {
+            Some(Drag::Header(column)) => self.toggle_column(column),
+            Some(Drag::Card {
+                column,
+                index,
+                cursor,
+                ..
+            }) => {
+                let Some((to, new_index)) = self.drop_slot((column, index), cursor) else {
+                    return;
+                };
+                // Dropping a card where it was isn't a move
+                if (to, new_index) != (column, index) {
+                    self.move_card((column, index), to, new_index);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_layout::BoxSizing;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Columns are 100 pixels wide with a 12 pixel gap and cards are 40 pixels
+    /// tall with an 8 pixel gap.
+    fn board() -> KanbanBoard {
+        KanbanBoard::new()
+            .column_width(100.0)
+            .card_height(40.0)
+            .column("To do")
+            .column("Doing")
+            .card(0, "A")
+            .card(0, "B")
+            .card(0, "C")
+    }
+
+    /// Get the position in the middle of a card.
+    fn card_center(board: &KanbanBoard, column: usize, index: usize) -> Position {
+        let bounds = board.metrics().card_bounds(&board.columns, column, index);
+        Position::new(
+            (bounds.x[0] + bounds.x[1]) / 2.0,
+            (bounds.y[0] + bounds.y[1]) / 2.0,
+        )
+    }
+
+    /// Get the cursor position of the card being dragged.
+    fn board_cursor(board: &KanbanBoard) -> Position {
+        match board.drag {
+            Some(Drag::Card { cursor, .. }) => cursor,
+            _ => panic!("Not dragging a card"),
+        }
+    }
+
+    fn titles(column: &KanbanColumn) -> Vec<&str> {
+        column
+            .cards
+            .iter()
+            .map(|card| card.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn drag_card_to_another_column() {
+        let moves = Rc::new(RefCell::new(vec![]));
+        let output = Rc::clone(&moves);
+        let mut board = board().on_card_move(move |card, from, to, index| {
+            output
+                .borrow_mut()
+                .push((card.title.clone(), from, to, index));
+        });
+
+        let start = card_center(&board, 0, 1);
+        board.drag_start(start);
+        board.drag(Position::new(112.0, 0.0));
+        board.drag_end();
+
+        assert_eq!(titles(&board.columns()[0]), ["A", "C"]);
+        assert_eq!(titles(&board.columns()[1]), ["B"]);
+        assert_eq!(*moves.borrow(), vec![(String::from("B"), 0, 1, 0)]);
+    }
+
+    #[test]
+    fn reorder_within_column() {
+        let mut board = board();
+        let start = card_center(&board, 0, 0);
+        board.drag_start(start);
+        // Past the middle of the last card
+        board.drag(Position::new(0.0, 100.0));
+        board.drag_end();
+
+        assert_eq!(titles(&board.columns()[0]), ["B", "C", "A"]);
+    }
+
+    #[test]
+    fn drop_in_place_is_not_a_move() {
+        let moves = Rc::new(RefCell::new(0));
+        let output = Rc::clone(&moves);
+        let mut board = board().on_card_move(move |_, _, _, _| *output.borrow_mut() += 1);
+
+        board.drag_start(card_center(&board, 0, 1));
+        board.drag(Position::new(0.0, 5.0));
+        board.drag_end();
+
+        assert_eq!(titles(&board.columns()[0]), ["A", "B", "C"]);
+        assert_eq!(*moves.borrow(), 0);
+    }
+
+    #[test]
+    fn full_column_rejects_cards() {
+        let mut board = board().wip_limit(1, 1).card(1, "D");
+        board.drag_start(card_center(&board, 0, 0));
+        board.drag(Position::new(112.0, 0.0));
+        assert_eq!(board.drop_slot((0, 0), board_cursor(&board)), None);
+        board.drag_end();
+
+        assert_eq!(board.columns()[0].cards.len(), 3);
+        assert_eq!(titles(&board.columns()[1]), ["D"]);
+    }
+
+    #[test]
+    fn wip_limit() {
+        let column = KanbanColumn {
+            wip_limit: Some(2),
+            ..KanbanColumn::new("Doing")
+        };
+        assert!(!column.is_full());
+
+        let mut board = board().wip_limit(0, 2);
+        assert!(board.columns()[0].is_over_limit());
+        assert!(!board.move_card((0, 0), 5, 0));
+        assert!(board.move_card((0, 0), 1, 0));
+        assert!(!board.columns()[0].is_over_limit());
+    }
+
+    #[test]
+    fn click_header_to_collapse() {
+        let mut board = board();
+        board.drag_start(Position::new(150.0, 10.0));
+        board.drag_end();
+        assert!(board.columns()[1].collapsed);
+
+        // Collapsed columns are narrower
+        let width = board.layout().intrinsic_size().width;
+        assert_eq!(width, BoxSizing::Fixed(100.0 + 12.0 + 36.0));
+
+        board.drag_start(Position::new(130.0, 10.0));
+        board.drag_end();
+        assert!(!board.columns()[1].collapsed);
+    }
+
+    #[test]
+    fn drop_onto_collapsed_column() {
+        let mut board = board().collapsed(1).card(1, "D");
+        board.drag_start(card_center(&board, 0, 2));
+        board.drag(Position::new(80.0, -50.0));
+        board.drag_end();
+
+        assert_eq!(titles(&board.columns()[1]), ["D", "C"]);
+    }
+}
@@ -0,0 +1,140 @@
+use super::Widget;
+use crate::style::{StrokeCap, StrokeJoin, StrokeStyle};
+use crate::view::{LineView, View};
+use agape_core::{GlobalId, IntoColor, Position, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// A straight line, or a polyline through several points.
+///
+/// The points are relative to the top left corner of the widget, which is
+/// sized to fit all the points.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Line;
+/// use agape::style::StrokeJoin;
+/// use agape::{Color,Position};
+///
+/// let divider = Line::between(Position::new(0.0,0.0),Position::new(200.0,0.0))
+///     .color(Color::rgb(200,200,200))
+///     .dash(vec![4.0,4.0]);
+///
+/// let zigzag = Line::new(vec![
+///     Position::new(0.0,20.0),
+///     Position::new(10.0,0.0),
+///     Position::new(20.0,20.0),
+/// ])
+/// .stroke_width(3.0)
+/// .join(StrokeJoin::Round);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    id: GlobalId,
+    points: Vec<Position>,
+    stroke: StrokeStyle,
+    closed: bool,
+}
+
+impl Line {
+    pub fn new(points: Vec<Position>) -> Self {
+        Self {
+            id: GlobalId::new(),
+            points,
+            stroke: StrokeStyle::default(),
+            closed: false,
+        }
+    }
+
+    /// Create a line between two points.
+    pub fn between(start: Position, end: Position) -> Self {
+        Self::new(vec![start, end])
+    }
+
+    pub fn points(&self) -> &[Position] {
+        &self.points
+    }
+
+    pub fn stroke_width(mut self, width: f32) -> Self {
+        self.stroke.width = width;
+        self
+    }
+
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.stroke.color = color.into_color();
+        self
+    }
+
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.stroke.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: StrokeJoin) -> Self {
+        self.stroke.join = join;
+        self
+    }
+
+    /// Set the lengths of the dashes and gaps.
+    pub fn dash(mut self, pattern: Vec<f32>) -> Self {
+        self.stroke.dash = pattern;
+        self
+    }
+
+    /// Set the whole [`StrokeStyle`].
+    pub fn stroke(mut self, stroke: StrokeStyle) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// Connect the last point to the first point.
+    pub fn closed(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+}
+
+impl Widget for Line {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let (width, height) = self
+            .points
+            .iter()
+            .fold((0.0f32, 0.0f32), |(width, height), point| {
+                (width.max(point.x), height.max(point.y))
+            });
+
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(width, height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = LineView::new(self.id);
+        view.points = self.points.clone();
+        view.stroke = self.stroke.clone();
+        view.closed = self.closed;
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_layout::BoxSizing;
+
+    #[test]
+    fn size_fits_points() {
+        let line = Line::new(vec![
+            Position::new(0.0, 20.0),
+            Position::new(50.0, 0.0),
+            Position::new(30.0, 40.0),
+        ]);
+        let layout = line.layout();
+        assert_eq!(layout.intrinsic_size().width, BoxSizing::Fixed(50.0));
+        assert_eq!(layout.intrinsic_size().height, BoxSizing::Fixed(40.0));
+    }
+}
@@ -0,0 +1,640 @@
+use super::Widget;
+use crate::view::{MessageListView, MessageRow, View};
+use agape_core::{Bounds, GlobalId, Position, Size};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout, LayoutSolver};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+/// The height of the label between messages from different days.
+const SEPARATOR_HEIGHT: f32 = 28.0;
+/// The number of pixels scrolled per line.
+const SCROLL_SPEED: f32 = 20.0;
+const PILL_WIDTH: f32 = 140.0;
+const PILL_HEIGHT: f32 = 28.0;
+/// The space between the pill and the bottom of the list.
+const PILL_MARGIN: f32 = 12.0;
+
+type BuildFn<M> = Box<dyn Fn(&M) -> Box<dyn Any>>;
+/// Rebinds a spare widget to a message, returns `false` if the widget isn't
+/// the type that the function rebinds.
+type RebindFn<M> = Box<dyn Fn(&mut dyn Any, &M) -> bool>;
+type DayFn<M> = Box<dyn Fn(&M) -> String>;
+
+/// The widgets are type erased so that spare widgets can be rebound.
+fn as_widget<W: Widget + 'static>(widget: &dyn Any) -> &dyn Widget {
+    widget.downcast_ref::<W>().unwrap()
+}
+
+/// A message's widget, with its solved layout.
+struct BuiltRow {
+    widget: Box<dyn Any>,
+    layout: Box<dyn Layout>,
+}
+
+/// The widgets of the messages in view, which are kept while they stay in
+/// view, and the widgets of messages that scrolled out of view.
+#[derive(Default)]
+struct RowPool {
+    rows: HashMap<usize, BuiltRow>,
+    spare: Vec<Box<dyn Any>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    Separator(String),
+    Message(usize),
+}
+
+/// A scrolling list of chat messages, with the newest message at the
+/// bottom.
+///
+/// The list sticks to the bottom as new messages are added, unless it has
+/// been scrolled up, in which case a pill showing the number of new
+/// messages is shown. Clicking the pill jumps back to the bottom.
+///
+/// Each message is drawn by a widget created by the builder closure. A
+/// message is measured once when it's added, and only the visible messages
+/// are built when drawing, so long conversations stay fast. The widgets are
+/// only drawn, they don't receive any events, and are kept for as long as
+/// their message is in view.
+///
+/// With [`recycle`](MessageList::recycle), the widgets of messages that
+/// scroll out of view are rebound to the messages scrolling into view,
+/// instead of building new widgets while scrolling.
+///
+/// # Example
+/// ```no_run
+/// use agape::widgets::{MessageList, Text};
+///
+/// struct Message {
+///     day: &'static str,
+///     text: &'static str,
+/// }
+///
+/// let mut list = MessageList::new(|message: &Message| Text::new(message.text))
+///     .day_separators(|message| message.day.to_owned())
+///     .message(Message { day: "Monday", text: "Hi!" })
+///     .message(Message { day: "Tuesday", text: "Hello" });
+///
+/// list.push(Message { day: "Tuesday", text: "How are you?" });
+/// ```
+pub struct MessageList<M> {
+    id: GlobalId,
+    messages: Vec<M>,
+    /// The rows from top to bottom, with their heights.
+    rows: Vec<(Row, f32)>,
+    /// Where each row starts, relative to the top of the content.
+    tops: Vec<f32>,
+    /// The day of the newest message, if there are day separators.
+    last_day: Option<String>,
+    build_fn: BuildFn<M>,
+    rebind_fn: Option<RebindFn<M>>,
+    as_widget: fn(&dyn Any) -> &dyn Widget,
+    pool: RefCell<RowPool>,
+    day_fn: Option<DayFn<M>>,
+    width: f32,
+    height: f32,
+    spacing: f32,
+    /// How far the list has been scrolled up from the bottom.
+    offset: f32,
+    unread: usize,
+}
+
+impl<M> MessageList<M> {
+    /// Create a message list that draws each message with the widget returned
+    /// by `builder`.
+    pub fn new<W: Widget + 'static>(builder: impl Fn(&M) -> W + 'static) -> Self {
+        Self {
+            id: GlobalId::new(),
+            messages: vec![],
+            rows: vec![],
+            tops: vec![],
+            last_day: None,
+            build_fn: Box::new(move |message| Box::new(builder(message))),
+            rebind_fn: None,
+            as_widget: as_widget::<W>,
+            pool: RefCell::default(),
+            day_fn: None,
+            width: 400.0,
+            height: 500.0,
+            spacing: 8.0,
+            offset: 0.0,
+            unread: 0,
+        }
+    }
+
+    /// Add a message.
+    pub fn message(mut self, message: M) -> Self {
+        self.push(message);
+        self
+    }
+
+    /// Show a separator with the day of a message whenever it is different
+    /// from the day of the message before it.
+    pub fn day_separators(mut self, day: impl Fn(&M) -> String + 'static) -> Self {
+        self.day_fn = Some(Box::new(day));
+        self.rebuild_rows();
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        // Messages can wrap differently at different widths
+        self.rebuild_rows();
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self.clear_rows();
+        self
+    }
+
+    /// Reuse the widgets of messages that scroll out of view, `rebind` is
+    /// called to show another message with a widget that was built by the
+    /// builder. The widget has to be the type returned by the builder,
+    /// otherwise new widgets are built.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::widgets::{MessageList, Text};
+    ///
+    /// let list = MessageList::new(|message: &String| Text::new(message))
+    ///     .recycle(|text: &mut Text, message| text.text = message.clone());
+    /// ```
+    pub fn recycle<W: Widget + 'static>(mut self, rebind: impl Fn(&mut W, &M) + 'static) -> Self {
+        self.rebind_fn = Some(Box::new(move |widget, message| {
+            let Some(widget) = widget.downcast_mut::<W>() else {
+                return false;
+            };
+            rebind(widget, message);
+            true
+        }));
+        self
+    }
+
+    /// Set the space between messages.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self.tops.clear();
+        let rows = std::mem::take(&mut self.rows);
+        for (row, height) in rows {
+            self.add_row(row, height);
+        }
+        self
+    }
+
+    /// Add a message to the bottom of the list.
+    pub fn push(&mut self, message: M) {
+        let previous_height = self.content_height();
+        self.add_message_rows(self.messages.len(), &message);
+        self.messages.push(message);
+
+        // Keep the same messages in view if the list has been scrolled up
+        if !self.is_at_bottom() {
+            self.offset += self.content_height() - previous_height;
+            self.unread += 1;
+        }
+    }
+
+    pub fn messages(&self) -> &[M] {
+        &self.messages
+    }
+
+    /// Get the number of messages added since the list was scrolled up.
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    /// Returns `true` if the newest message is in view.
+    pub fn is_at_bottom(&self) -> bool {
+        self.offset <= 0.0
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.offset = 0.0;
+        self.unread = 0;
+    }
+
+    /// Scroll the list by a number of lines, positive values scroll up
+    /// towards older messages.
+    pub fn scroll(&mut self, delta: Position) {
+        self.offset = (self.offset + delta.y * SCROLL_SPEED).clamp(0.0, self.max_offset());
+        if self.is_at_bottom() {
+            self.unread = 0;
+        }
+    }
+
+    /// Get the height of a message's widget.
+    fn measure(&self, message: &M) -> f32 {
+        let mut pool = self.pool.borrow_mut();
+        let widget = self.build(message, &mut pool.spare);
+        let height = self.solve(widget.as_ref()).size().height;
+        if self.rebind_fn.is_some() {
+            pool.spare.push(widget);
+        }
+        height
+    }
+
+    /// Build the widget for a message, rebinding a spare widget if the list
+    /// recycles them.
+    fn build(&self, message: &M, spare: &mut Vec<Box<dyn Any>>) -> Box<dyn Any> {
+        if let (Some(rebind), Some(mut widget)) = (&self.rebind_fn, spare.pop()) {
+            if rebind(widget.as_mut(), message) {
+                return widget;
+            }
+        }
+        (self.build_fn)(message)
+    }
+
+    fn solve(&self, widget: &dyn Any) -> Box<dyn Layout> {
+        let mut layout = (self.as_widget)(widget).layout();
+        LayoutSolver::solve(&mut *layout, Size::new(self.width, self.height));
+        layout
+    }
+
+    /// Drop the built rows, after the size of the list changes.
+    fn clear_rows(&mut self) {
+        let pool = self.pool.get_mut();
+        let rows = std::mem::take(&mut pool.rows);
+        if self.rebind_fn.is_some() {
+            pool.spare.extend(rows.into_values().map(|row| row.widget));
+        }
+    }
+
+    /// Add a row to the bottom of the content.
+    fn add_row(&mut self, row: Row, height: f32) {
+        let top = match self.rows.is_empty() {
+            true => 0.0,
+            false => self.content_height() + self.spacing,
+        };
+        self.tops.push(top);
+        self.rows.push((row, height));
+    }
+
+    /// Add the rows for a message, with a separator before it if it's
+    /// from a new day.
+    fn add_message_rows(&mut self, index: usize, message: &M) {
+        if let Some(day_fn) = &self.day_fn {
+            let day = day_fn(message);
+            if self.last_day.as_ref() != Some(&day) {
+                self.add_row(Row::Separator(day.clone()), SEPARATOR_HEIGHT);
+                self.last_day = Some(day);
+            }
+        }
+        let height = self.measure(message);
+        self.add_row(Row::Message(index), height);
+    }
+
+    /// Measure all the messages again and rebuild the rows.
+    fn rebuild_rows(&mut self) {
+        self.clear_rows();
+        self.rows.clear();
+        self.tops.clear();
+        self.last_day = None;
+        let messages = std::mem::take(&mut self.messages);
+        for (index, message) in messages.iter().enumerate() {
+            self.add_message_rows(index, message);
+        }
+        self.messages = messages;
+    }
+
+    fn content_height(&self) -> f32 {
+        match (self.tops.last(), self.rows.last()) {
+            (Some(top), Some((_, height))) => top + height,
+            _ => 0.0,
+        }
+    }
+
+    fn max_offset(&self) -> f32 {
+        (self.content_height() - self.height).max(0.0)
+    }
+
+    /// Get the visible rows and where they start, relative to the top of the
+    /// list.
+    fn visible_rows(&self) -> Vec<(Row, f32, f32)> {
+        // The content is anchored to the bottom of the list
+        let origin = self.height - self.content_height() + self.offset;
+        // The rows are sorted, so the visible ones can be found with a
+        // binary search
+        let first = self
+            .tops
+            .partition_point(|top| origin + top <= 0.0)
+            .saturating_sub(1);
+        let last = self.tops.partition_point(|top| origin + top < self.height);
+
+        (first..last)
+            .filter_map(|index| {
+                let (row, height) = &self.rows[index];
+                let y = origin + self.tops[index];
+                (y + height > 0.0).then(|| (row.clone(), y, *height))
+            })
+            .collect()
+    }
+
+    /// Get the bounds of the "new messages" pill, relative to the list.
+    fn pill_rect(&self) -> Option<tiny_skia::Rect> {
+        if self.unread == 0 {
+            return None;
+        }
+        tiny_skia::Rect::from_xywh(
+            (self.width - PILL_WIDTH) / 2.0,
+            self.height - PILL_MARGIN - PILL_HEIGHT,
+            PILL_WIDTH,
+            PILL_HEIGHT,
+        )
+    }
+}
+
+impl<M: 'static> Widget for MessageList<M> {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width, self.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let visible = self.visible_rows();
+        let in_view: HashSet<usize> = visible
+            .iter()
+            .filter_map(|(row, _, _)| match row {
+                Row::Message(index) => Some(*index),
+                Row::Separator(_) => None,
+            })
+            .collect();
+
+        let mut pool = self.pool.borrow_mut();
+        let RowPool { rows: built, spare } = &mut *pool;
+        // Rows that scrolled out of view give their widgets back to the pool
+        let hidden: Vec<usize> = built
+            .keys()
+            .filter(|index| !in_view.contains(index))
+            .copied()
+            .collect();
+        for index in hidden {
+            let row = built.remove(&index).unwrap();
+            if self.rebind_fn.is_some() {
+                spare.push(row.widget);
+            }
+        }
+
+        let rows = visible
+            .into_iter()
+            .map(|(row, y, height)| match row {
+                Row::Separator(label) => MessageRow::Separator { y, height, label },
+                Row::Message(index) => {
+                    let row = built.entry(index).or_insert_with(|| {
+                        let widget = self.build(&self.messages[index], spare);
+                        let layout = self.solve(widget.as_ref());
+                        BuiltRow { widget, layout }
+                    });
+                    let views = (self.as_widget)(row.widget.as_ref())
+                        .iter()
+                        .filter_map(|widget| {
+                            let mut view = widget.view();
+                            let layout = row.layout.get(view.id())?;
+                            view.set_size(layout.size());
+                            let mut position = layout.position();
+                            position.translate(0.0, y);
+                            view.set_position(position);
+                            Some(view)
+                        })
+                        .collect();
+                    MessageRow::Message { y, views }
+                }
+            })
+            .collect();
+
+        let pill = self.pill_rect().map(|rect| {
+            let label = match self.unread {
+                1 => String::from("1 new message"),
+                unread => format!("{unread} new messages"),
+            };
+            (label, rect)
+        });
+
+        Box::new(MessageListView {
+            id: self.id,
+            position: Position::default(),
+            size: Size::default(),
+            rows,
+            pill,
+        })
+    }
+
+    fn drag_start(&mut self, position: Position) {
+        let Some(rect) = self.pill_rect() else {
+            return;
+        };
+        let bounds = Bounds::new(
+            Position::new(rect.x(), rect.y()),
+            Size::new(rect.width(), rect.height()),
+        );
+        if bounds.within(&position) {
+            self.scroll_to_bottom();
+        }
+    }
+
+    fn scroll_by(&mut self, delta: Position) -> bool {
+        self.scroll(delta);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Rect;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// A list 100 pixels tall, where each message is a rect `message`
+    /// pixels tall.
+    fn list() -> MessageList<f32> {
+        MessageList::new(|height: &f32| Rect::new(50.0, *height))
+            .height(100.0)
+            .spacing(0.0)
+    }
+
+    fn message_rows(list: &MessageList<f32>) -> Vec<(usize, f32)> {
+        list.visible_rows()
+            .into_iter()
+            .filter_map(|(row, y, _)| match row {
+                Row::Message(index) => Some((index, y)),
+                Row::Separator(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn anchored_to_bottom() {
+        let list = list().message(20.0).message(30.0);
+        assert_eq!(message_rows(&list), vec![(0, 50.0), (1, 70.0)]);
+    }
+
+    #[test]
+    fn sticks_to_bottom() {
+        let mut list = list();
+        for _ in 0..10 {
+            list.push(40.0);
+        }
+        assert!(list.is_at_bottom());
+        assert_eq!(list.unread(), 0);
+        // The newest message is at the bottom
+        assert_eq!(message_rows(&list).last(), Some(&(9, 60.0)));
+    }
+
+    #[test]
+    fn scrolled_up_keeps_position() {
+        let mut list = list();
+        for _ in 0..10 {
+            list.push(40.0);
+        }
+        list.scroll(Position::new(0.0, 2.0));
+        let rows = message_rows(&list);
+
+        list.push(40.0);
+        list.push(40.0);
+        assert_eq!(message_rows(&list), rows);
+        assert_eq!(list.unread(), 2);
+
+        // Scrolling back down clears the new messages
+        list.scroll(Position::new(0.0, -100.0));
+        assert!(list.is_at_bottom());
+        assert_eq!(list.unread(), 0);
+    }
+
+    #[test]
+    fn click_pill_to_jump_to_bottom() {
+        let mut list = list().width(200.0);
+        for _ in 0..10 {
+            list.push(40.0);
+        }
+        list.scroll(Position::new(0.0, 2.0));
+        list.push(40.0);
+
+        // Clicking outside the pill does nothing
+        list.drag_start(Position::new(10.0, 10.0));
+        assert!(!list.is_at_bottom());
+
+        list.drag_start(Position::new(100.0, 75.0));
+        assert!(list.is_at_bottom());
+        assert_eq!(list.pill_rect(), None);
+    }
+
+    #[test]
+    fn scroll_is_clamped() {
+        let mut list = list().message(40.0).message(40.0).message(40.0);
+        list.scroll(Position::new(0.0, 100.0));
+        assert_eq!(list.offset, 20.0);
+        assert_eq!(message_rows(&list)[0], (0, 0.0));
+    }
+
+    #[test]
+    fn day_separators() {
+        let list = MessageList::new(|_: &(u32, f32)| Rect::new(10.0, 10.0))
+            .day_separators(|(day, _)| format!("Day {day}"))
+            .message((1, 0.0))
+            .message((1, 0.0))
+            .message((2, 0.0));
+
+        let rows: Vec<Row> = list.rows.into_iter().map(|(row, _)| row).collect();
+        assert_eq!(
+            rows,
+            vec![
+                Row::Separator(String::from("Day 1")),
+                Row::Message(0),
+                Row::Message(1),
+                Row::Separator(String::from("Day 2")),
+                Row::Message(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_visible_messages_are_built() {
+        let built = Rc::new(Cell::new(0));
+        let count = Rc::clone(&built);
+        let mut list = MessageList::new(move |_: &()| {
+            count.set(count.get() + 1);
+            Rect::new(10.0, 20.0)
+        })
+        .height(100.0)
+        .spacing(0.0);
+        for _ in 0..1000 {
+            list.push(());
+        }
+
+        built.set(0);
+        let _ = list.view();
+        assert_eq!(built.get(), 5);
+
+        // The rows in view are kept
+        let _ = list.view();
+        assert_eq!(built.get(), 5);
+    }
+
+    #[test]
+    fn recycle_hidden_rows() {
+        let built = Rc::new(Cell::new(0));
+        let rebound = Rc::new(Cell::new(0));
+        let (build_count, rebind_count) = (Rc::clone(&built), Rc::clone(&rebound));
+        let mut list = MessageList::new(move |_: &()| {
+            build_count.set(build_count.get() + 1);
+            Rect::new(10.0, 20.0)
+        })
+        .recycle(move |_: &mut Rect, _| rebind_count.set(rebind_count.get() + 1))
+        .height(100.0)
+        .spacing(0.0);
+        for _ in 0..1000 {
+            list.push(());
+        }
+        // Measuring reuses the same widget
+        assert_eq!(built.get(), 1);
+
+        let _ = list.view();
+        assert_eq!(built.get(), 5);
+        rebound.set(0);
+
+        // Scroll up by a whole page
+        list.scroll(Position::new(0.0, 5.0));
+        let _ = list.view();
+        assert_eq!(built.get(), 5);
+        assert_eq!(rebound.get(), 5);
+    }
+
+    #[test]
+    fn push_only_measures_new_message() {
+        let built = Rc::new(Cell::new(0));
+        let days = Rc::new(Cell::new(0));
+        let (build_count, day_count) = (Rc::clone(&built), Rc::clone(&days));
+        let mut list = MessageList::new(move |_: &u32| {
+            build_count.set(build_count.get() + 1);
+            Rect::new(10.0, 20.0)
+        })
+        .day_separators(move |day| {
+            day_count.set(day_count.get() + 1);
+            day.to_string()
+        });
+        for day in 0..100 {
+            list.push(day / 10);
+        }
+
+        assert_eq!(built.get(), 100);
+        assert_eq!(days.get(), 100);
+        assert_eq!(list.rows.len(), 110);
+    }
+
+    #[test]
+    fn row_offsets_include_spacing() {
+        let list = list().message(20.0).message(30.0).spacing(5.0);
+        assert_eq!(list.tops, vec![0.0, 25.0]);
+        assert_eq!(list.content_height(), 55.0);
+    }
+}
@@ -11,24 +11,135 @@
 //!
 //! Additionally, if your widget has any children you will need to implement the `children`
 //! method.
+//!
+//! Optional capabilities, such as [`Focusable`] and [`KeyboardInput`], are separate traits
+//! that a widget opts into with the matching `as_*` method, see the [`capability`] module.
+//!
+//! A view can look up the [`InteractionState`] of its widget when it's rendered, with
+//! [`Resources::interaction_state`](crate::Resources::interaction_state), to draw its
+//! hovered, pressed and focused states.
+mod about;
+mod arc;
+mod bezier;
+mod blur;
 mod button;
+pub mod capability;
+mod context;
+mod cursor;
+mod dial;
+mod drag_drop;
+mod each;
+mod ellipse;
+mod emoji_picker;
+mod error_boundary;
+mod formatted_text;
+mod gauge;
+mod heatmap;
+mod hit_area;
 mod hstack;
+mod image;
+mod kanban;
+mod line;
+mod message_list;
+mod opacity;
+mod path;
+mod pie;
+mod print_preview;
+mod progress_ring;
 mod rect;
+mod rich_text;
+mod rich_text_editor;
+mod show;
+mod sparkline;
 mod text;
 mod text_field;
+mod timeline;
+mod transform;
 mod vstack;
 
-use crate::view::View;
-use agape_core::GlobalId;
-use agape_layout::Layout;
+use crate::error_boundary::BoundaryError;
+use crate::event::EventCtx;
+use crate::style::State;
+use crate::view::{HitShape, View};
+pub use about::About;
+use agape_core::{GlobalId, Position};
+use agape_layout::{AxisAlignment, Layout};
+pub use arc::Arc;
+pub use bezier::{Bezier, BezierKind};
+pub use blur::Blur;
 pub use button::Button;
+pub(crate) use capability::for_each_lifecycle;
+pub use capability::{
+    DragSource, DropHandler, Focusable, GestureHandler, KeyboardInput, Lifecycle, PointerTarget,
+    Reactive, Role, Scope, Semantics,
+};
+pub use context::ContextProvider;
+pub use cursor::CursorArea;
+pub use dial::Dial;
+pub use drag_drop::{Draggable, DropTarget};
+pub use each::For;
+pub use ellipse::Ellipse;
+pub use emoji_picker::{EmojiCategory, EmojiPicker, SkinTone};
+pub use error_boundary::ErrorBoundary;
+pub use formatted_text::FormattedText;
+pub use gauge::{Gauge, GaugeRange};
+pub use heatmap::Heatmap;
+pub use hit_area::HitArea;
 pub use hstack::*;
+pub use image::Image;
+pub use kanban::{KanbanBoard, KanbanCard, KanbanColumn};
+pub use line::Line;
+pub use message_list::MessageList;
+pub use opacity::Opacity;
+pub use path::Path;
+pub use pie::Pie;
+pub use print_preview::PrintPreview;
+pub use progress_ring::ProgressRing;
 pub use rect::*;
-use std::collections::HashMap;
+pub use rich_text::{RichText, TextSpan};
+pub use rich_text_editor::RichTextEditor;
+pub use show::Show;
+pub use sparkline::{Sparkline, SparklineKind};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 pub use text::Text;
-pub use text_field::TextField;
+pub use text_field::{TextField, Token};
+pub use timeline::{Timeline, TimelineBar};
+pub use transform::{Transformed, WidgetTransform};
 pub use vstack::*;
-use winit::event::KeyEvent;
+use winit::event::{KeyEvent, MouseButton};
+use winit::keyboard::ModifiersState;
+use winit::window::CursorIcon;
+
+/// The builders shared by widgets that lay out their children.
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+///
+/// let toolbar = hstack! {
+///     Text::new("Save"),
+///     Text::new("Share"),
+/// }
+/// .padding(8)
+/// .align_center();
+/// ```
+pub trait LayoutExt: Sized {
+    /// Set the space between the widget's edges and its children.
+    fn padding(self, padding: u32) -> Self;
+
+    /// Align the children along the direction that they flow in.
+    fn main_axis_alignment(self, alignment: AxisAlignment) -> Self;
+
+    /// Align the children across the direction that they flow in.
+    fn cross_axis_alignment(self, alignment: AxisAlignment) -> Self;
+
+    /// Center the children on both axes.
+    fn align_center(self) -> Self {
+        self.main_axis_alignment(AxisAlignment::Center)
+            .cross_axis_alignment(AxisAlignment::Center)
+    }
+}
 
 pub trait Widget: WidgetIterator {
     fn view(&self) -> Box<dyn View>;
@@ -39,11 +150,26 @@ pub trait Widget: WidgetIterator {
     /// Get the `id` of the [`Widget`]
     fn id(&self) -> GlobalId;
 
+    /// Get the name of the widget's type, used in debug output.
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        // Strip the module path and any generics
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
     /// Get a [`Widget`] from the widget tree by it's `id`
     fn get(&self, id: GlobalId) -> Option<&dyn Widget> {
         self.iter().find(|&widget| widget.id() == id)
     }
 
+    /// Get the widget whose id has the [debug name](GlobalId::debug_name),
+    /// names are only kept in debug builds so this is meant for tests.
+    fn get_by_name(&self, name: &str) -> Option<&dyn Widget> {
+        self.iter()
+            .find(|&widget| widget.id().name().as_deref() == Some(name))
+    }
+
     fn traverse(&self, _f: &mut dyn FnMut(&dyn Widget)) {}
     fn traverse_mut(&mut self, _f: &mut dyn FnMut(&mut dyn Widget)) {}
 
@@ -56,6 +182,113 @@ pub trait Widget: WidgetIterator {
         &mut []
     }
 
+    /// Get the opacity that the widget and its children are drawn with as
+    /// a group, or `None` if they're drawn directly.
+    fn group_opacity(&self) -> Option<f32> {
+        None
+    }
+
+    /// Draw the widget and its children with an opacity, as a group.
+    fn opacity(self, opacity: f32) -> Opacity
+    where
+        Self: Sized + 'static,
+    {
+        Opacity::new(self, opacity)
+    }
+
+    /// Show a cursor icon while the cursor is over the widget, this takes
+    /// priority over the widget's own icon.
+    fn cursor(self, icon: CursorIcon) -> CursorArea
+    where
+        Self: Sized + 'static,
+    {
+        CursorArea::new(self, icon)
+    }
+
+    /// Set the shape that the widget and its children are hit in.
+    fn hit_shape(self, shape: HitShape) -> HitArea
+    where
+        Self: Sized + 'static,
+    {
+        HitArea::new(self, shape)
+    }
+
+    /// Let the widget be dragged onto a drop target that accepts a `T`, see
+    /// the [`drag_drop`](crate::drag_drop) module.
+    fn draggable<T: Clone + 'static>(self, payload: T) -> Draggable<T>
+    where
+        Self: Sized + 'static,
+    {
+        Draggable::new(self, payload)
+    }
+
+    /// Accept payloads of type `T` that are dropped on the widget, see the
+    /// [`drag_drop`](crate::drag_drop) module.
+    fn drop_target<T: 'static>(self, on_drop: impl FnMut(T) + 'static) -> DropTarget<T>
+    where
+        Self: Sized + 'static,
+    {
+        DropTarget::new(self, on_drop)
+    }
+
+    /// Get the radius that the widget and its children are blurred by as a
+    /// group, or `None` if they aren't blurred.
+    fn group_blur(&self) -> Option<f32> {
+        None
+    }
+
+    /// Blur the widget and its children.
+    fn blur(self, radius: f32) -> Blur
+    where
+        Self: Sized + 'static,
+    {
+        Blur::new(self, radius)
+    }
+
+    /// Get the transform that the widget and its children are drawn with,
+    /// or `None` if they aren't transformed.
+    fn group_transform(&self) -> Option<WidgetTransform> {
+        None
+    }
+
+    /// Rotate the widget and its children clockwise around their center.
+    fn rotate(self, degrees: f32) -> Transformed
+    where
+        Self: Sized + 'static,
+    {
+        Transformed::new(self, WidgetTransform::default()).rotate(degrees)
+    }
+
+    /// Scale the widget and its children around their center.
+    fn scale(self, factor: f32) -> Transformed
+    where
+        Self: Sized + 'static,
+    {
+        Transformed::new(self, WidgetTransform::default()).scale(factor)
+    }
+
+    /// Provide a value to the widget's descendants, which they get with
+    /// [`use_context`](crate::context::use_context).
+    fn provide_context<T: 'static>(self, value: T) -> ContextProvider
+    where
+        Self: Sized + 'static,
+    {
+        ContextProvider::new(self, value)
+    }
+
+    /// Show a fallback widget in place of the widget if it panics while
+    /// it's laid out or drawn, see the
+    /// [`error_boundary`](crate::error_boundary) module.
+    fn error_boundary<W: Widget + 'static>(
+        self,
+        fallback: impl Fn(&BoundaryError) -> W + 'static,
+    ) -> ErrorBoundary
+    where
+        Self: Sized + 'static,
+    {
+        ErrorBoundary::new(self, fallback)
+    }
+
     fn handle_event(&mut self, event: &WidgetEvent) {
         match event {
             WidgetEvent::Hovered(id) => {
@@ -63,30 +296,297 @@ pub trait Widget: WidgetIterator {
                     self.hover();
                 }
             }
-            WidgetEvent::Clicked(id) => {
+            WidgetEvent::Unhovered(id) => {
+                if id == &self.id() {
+                    self.unhover();
+                }
+            }
+            WidgetEvent::MouseMoved(id, position) => {
                 if id == &self.id() {
-                    self.click();
+                    self.mouse_move(*position);
                 }
             }
-            WidgetEvent::KeyInput(event) => {
-                self.key_input(event);
+            WidgetEvent::KeyInput(id, event) => {
+                if id == &self.id() {
+                    if let Some(widget) = self.as_keyboard_input_mut() {
+                        widget.key_input(event);
+                    }
+                }
+            }
+            WidgetEvent::Focused(id) => {
+                if id == &self.id() {
+                    if let Some(widget) = self.as_focusable_mut() {
+                        widget.focus();
+                    }
+                }
             }
+            WidgetEvent::Unfocused(id) => {
+                if id == &self.id() {
+                    if let Some(widget) = self.as_focusable_mut() {
+                        widget.unfocus();
+                    }
+                }
+            }
+            WidgetEvent::ModifiersChanged(modifiers) => {
+                if let Some(widget) = self.as_keyboard_input_mut() {
+                    widget.modifiers_changed(*modifiers);
+                }
+            }
+            WidgetEvent::DragStarted(id, position) => {
+                if id == &self.id() {
+                    self.drag_start(*position);
+                }
+            }
+            WidgetEvent::Dragged(id, delta) => {
+                if id == &self.id() {
+                    self.drag(*delta);
+                }
+            }
+            WidgetEvent::DragEnded(id) => {
+                if id == &self.id() {
+                    self.drag_end();
+                }
+            }
+            WidgetEvent::FileHovered(id) => {
+                if id == &self.id() {
+                    if let Some(widget) = self.as_drop_handler_mut() {
+                        widget.file_hover();
+                    }
+                }
+            }
+            WidgetEvent::FileUnhovered(id) => {
+                if id == &self.id() {
+                    if let Some(widget) = self.as_drop_handler_mut() {
+                        widget.file_unhover();
+                    }
+                }
+            }
+            // Clicks bubble up from the innermost widget, and scrolls and
+            // drops stop at the first widget that uses them, so they're
+            // delivered by `dispatch_event` instead
+            WidgetEvent::Clicked(..)
+            | WidgetEvent::Pressed(..)
+            | WidgetEvent::Released(..)
+            | WidgetEvent::MousePressed(..)
+            | WidgetEvent::DoubleClicked(..)
+            | WidgetEvent::TripleClicked(..)
+            | WidgetEvent::Scrolled(..)
+            | WidgetEvent::FilesDropped(..)
+            | WidgetEvent::Tapped(..)
+            | WidgetEvent::LongPressed(..)
+            | WidgetEvent::Panned(..)
+            | WidgetEvent::Pinched(..) => {}
         }
 
         self.traverse_mut(&mut |child| child.handle_event(event));
     }
 
-    fn click(&mut self) {}
+    /// Called when the primary mouse button is pressed and then released
+    /// over the widget or one of its children. The click is cancelled if
+    /// the cursor leaves the widget before the button is released.
+    ///
+    /// The click starts at the innermost widget under the cursor and
+    /// bubbles up through its ancestors, until one of them calls
+    /// [`EventCtx::stop_propagation`]. The other click hooks bubble the
+    /// same way.
+    fn click(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called when the primary mouse button is pressed over the widget or
+    /// one of its children, before it's clicked.
+    fn press(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called when the primary mouse button is released after it was
+    /// pressed over the widget, even if the cursor has left the widget.
+    fn release(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called when any mouse button is pressed over the widget, by default
+    /// this calls [`right_click`](Self::right_click) and
+    /// [`middle_click`](Self::middle_click).
+    fn mouse_press(&mut self, button: MouseButton, ctx: &mut EventCtx) {
+        match button {
+            MouseButton::Right => self.right_click(ctx),
+            MouseButton::Middle => self.middle_click(ctx),
+            _ => {}
+        }
+    }
+
+    /// Called when the right mouse button is pressed over the widget.
+    fn right_click(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called when the middle mouse button is pressed over the widget.
+    fn middle_click(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called on the second click of a double click, after [`click`](Self::click).
+    fn double_click(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called on the third click of a triple click, after [`click`](Self::click).
+    fn triple_click(&mut self, _ctx: &mut EventCtx) {}
+
+    /// Called when the cursor enters the widget.
     fn hover(&mut self) {}
 
-    fn key_input(&mut self, _: &KeyEvent) {}
+    /// Called when the cursor leaves the widget.
+    fn unhover(&mut self) {}
+
+    /// Called when the cursor moves within the widget, the `position` is
+    /// relative to the widget's top left corner.
+    fn mouse_move(&mut self, _position: Position) {}
+
+    /// Called when the left mouse button is pressed over the widget, the
+    /// `position` is relative to the widget's top left corner.
+    fn drag_start(&mut self, _position: Position) {}
+
+    /// Called when the cursor moves while the left mouse button is held
+    /// down after being pressed over the widget.
+    fn drag(&mut self, _delta: Position) {}
+
+    /// Called when the left mouse button is released after dragging the
+    /// widget.
+    fn drag_end(&mut self) {}
+
+    /// Called when the mouse wheel or trackpad scrolls over the widget, by
+    /// a number of lines. Positive values scroll up and to the left.
+    ///
+    /// The innermost widget under the cursor gets the scroll first, return
+    /// `true` if the widget used it, otherwise it's passed on to the
+    /// widget's parent.
+    fn scroll_by(&mut self, _delta: Position) -> bool {
+        false
+    }
+
+    /// Get the widget as [`Focusable`], if it can be focused.
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        None
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        None
+    }
+
+    /// Get the widget as [`KeyboardInput`], if it handles the keyboard.
+    fn as_keyboard_input(&self) -> Option<&dyn KeyboardInput> {
+        None
+    }
+
+    fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+        None
+    }
+
+    /// Get the widget's [`Semantics`], if it describes what it means.
+    fn as_semantics(&self) -> Option<&dyn Semantics> {
+        None
+    }
+
+    /// Get the widget as a [`PointerTarget`], if it changes the cursor
+    /// icon or the shape it's hit in.
+    fn as_pointer_target(&self) -> Option<&dyn PointerTarget> {
+        None
+    }
+
+    /// Get the widget as a [`Scope`], if it changes how its children are
+    /// recorded.
+    fn as_scope(&self) -> Option<&dyn Scope> {
+        None
+    }
+
+    /// Get the widget as [`Lifecycle`], if it needs to know when the app
+    /// starts and stops.
+    fn as_lifecycle_mut(&mut self) -> Option<&mut dyn Lifecycle> {
+        None
+    }
+
+    /// Get the widget as [`Reactive`], if it reacts to changes from outside
+    /// its events.
+    fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+        None
+    }
+
+    /// Get the widget as a [`GestureHandler`], if it handles touch
+    /// gestures itself.
+    fn as_gesture_handler_mut(&mut self) -> Option<&mut dyn GestureHandler> {
+        None
+    }
+
+    /// Get the widget as a [`DragSource`], if it can be dragged.
+    fn as_drag_source(&self) -> Option<&dyn DragSource> {
+        None
+    }
+
+    /// Get the widget as a [`DropHandler`], if payloads or files can be
+    /// dropped on it.
+    fn as_drop_handler(&self) -> Option<&dyn DropHandler> {
+        None
+    }
+
+    fn as_drop_handler_mut(&mut self) -> Option<&mut dyn DropHandler> {
+        None
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum WidgetEvent {
+    /// The cursor entered a widget.
     Hovered(GlobalId),
+    /// The cursor left a widget.
+    Unhovered(GlobalId),
+    /// The cursor moved within a widget, to a position relative to the
+    /// widget.
+    MouseMoved(GlobalId, Position),
+    /// The primary mouse button was pressed and released over a widget,
+    /// the innermost one that the cursor was over both times. The event
+    /// bubbles up to its ancestors.
     Clicked(GlobalId),
-    KeyInput(KeyEvent),
+    /// The primary mouse button was pressed over a widget, the innermost
+    /// one under the cursor. The event bubbles up to its ancestors.
+    Pressed(GlobalId),
+    /// The primary mouse button was released after being pressed over a
+    /// widget, wherever the cursor is. The event bubbles up to its
+    /// ancestors.
+    Released(GlobalId),
+    /// A mouse button was pressed over a widget, this is sent for every
+    /// button, alongside [`WidgetEvent::Pressed`] for the primary button.
+    MousePressed(GlobalId, MouseButton),
+    /// A widget was clicked twice in quick succession.
+    DoubleClicked(GlobalId),
+    /// A widget was clicked three times in quick succession.
+    TripleClicked(GlobalId),
+    /// A key was pressed or released while a widget was focused.
+    KeyInput(GlobalId, KeyEvent),
+    /// A widget gained focus.
+    Focused(GlobalId),
+    /// A widget lost focus.
+    Unfocused(GlobalId),
+    /// The modifier keys that are held down changed.
+    ModifiersChanged(ModifiersState),
+    /// The left mouse button was pressed over a widget, at a position
+    /// relative to the widget.
+    DragStarted(GlobalId, Position),
+    /// A widget was dragged, by a number of pixels.
+    Dragged(GlobalId, Position),
+    /// The left mouse button was released after dragging a widget.
+    DragEnded(GlobalId),
+    /// The mouse wheel or trackpad scrolled, by a number of lines, over
+    /// widgets ordered from the innermost to the outermost.
+    Scrolled(Vec<GlobalId>, Position),
+    /// Files from another app were dragged over a widget.
+    FileHovered(GlobalId),
+    /// Files that were dragged over a widget left it.
+    FileUnhovered(GlobalId),
+    /// Files were dropped on widgets, ordered from the innermost to the
+    /// outermost.
+    FilesDropped(Vec<GlobalId>, Vec<PathBuf>),
+    /// A widget was tapped on a touchscreen, the innermost one under the
+    /// touch. The event bubbles up to its ancestors.
+    Tapped(GlobalId),
+    /// A touch, or the primary mouse button, was held on a widget without
+    /// moving. The event bubbles up to its ancestors.
+    LongPressed(GlobalId),
+    /// A touch was dragged, by a number of pixels, over widgets ordered
+    /// from the innermost to the outermost.
+    Panned(Vec<GlobalId>, Position),
+    /// Two touches were pinched over widgets, ordered from the innermost to
+    /// the outermost, with the scale and the center of the pinch.
+    Pinched(Vec<GlobalId>, f32, Position),
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -95,7 +595,6 @@ pub struct StateTracker {
     current_state: HashMap<GlobalId, WidgetState>,
 }
 
-// TODO test these
 impl StateTracker {
     pub fn new(widget: &dyn Widget) -> Self {
         let mut previous_state = HashMap::new();
@@ -121,11 +620,54 @@ impl StateTracker {
         self.previous_state.get(&id)
     }
 
+    /// Set the state of a widget, widgets that aren't tracked yet start
+    /// out resting.
     pub fn update_state(&mut self, id: GlobalId, state: WidgetState) {
-        let previous_state = self.current_state.get(&id).unwrap();
-        self.previous_state.insert(id, *previous_state);
+        let previous_state = self
+            .current_state
+            .get(&id)
+            .copied()
+            .unwrap_or(WidgetState::Resting);
+        self.previous_state.insert(id, previous_state);
         self.current_state.insert(id, state);
     }
+
+    /// Track the widgets that were added to the tree since the last sync
+    /// and forget the ones that were removed, returns the removed ids.
+    pub fn sync(&mut self, widget: &dyn Widget) -> Vec<GlobalId> {
+        let ids: HashSet<GlobalId> = widget.iter().map(|w| w.id()).collect();
+        let removed: Vec<GlobalId> = self
+            .current_state
+            .keys()
+            .filter(|id| !ids.contains(id))
+            .copied()
+            .collect();
+        for id in &removed {
+            self.previous_state.remove(id);
+            self.current_state.remove(id);
+        }
+        for id in ids {
+            self.previous_state
+                .entry(id)
+                .or_insert(WidgetState::Resting);
+            self.current_state.entry(id).or_insert(WidgetState::Resting);
+        }
+        removed
+    }
+
+    /// Returns `true` if the widget is tracked.
+    pub fn contains(&self, id: GlobalId) -> bool {
+        self.current_state.contains_key(&id)
+    }
+
+    /// Get the number of tracked widgets.
+    pub fn len(&self) -> usize {
+        self.current_state.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current_state.is_empty()
+    }
 }
 
 /// The current state of the widget
@@ -136,6 +678,40 @@ pub enum WidgetState {
     Clicked,
 }
 
+/// The interaction states of a widget, looked up by id with
+/// [`Resources::interaction_state`](crate::Resources::interaction_state).
+///
+/// Views get the [`Resources`](crate::Resources) when they're rendered, so
+/// custom widgets can draw their states without keeping their own flags
+/// up to date from event callbacks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InteractionState {
+    /// The cursor is over the widget.
+    pub hovered: bool,
+    /// The primary mouse button was pressed on the widget and hasn't been
+    /// released yet.
+    pub pressed: bool,
+    pub focused: bool,
+    /// The widget is focused and its focus ring is shown, because the
+    /// focus was moved with the keyboard.
+    pub focus_visible: bool,
+    /// The widget doesn't receive input, because it's outside of an open
+    /// modal.
+    pub disabled: bool,
+}
+
+impl InteractionState {
+    /// Returns `true` if the widget is in a [`State`] that can be styled
+    /// with [`StateStyled::style_when`](crate::style::StateStyled::style_when).
+    pub fn matches(&self, state: State) -> bool {
+        match state {
+            State::Hovered => self.hovered,
+            State::Focused => self.focused,
+            State::Pressed => self.pressed,
+        }
+    }
+}
+
 /// An iterator over the [`Widget`] tree.
 pub struct WidgetIter<'a> {
     stack: Vec<&'a dyn Widget>,
@@ -0,0 +1,83 @@
+use super::Widget;
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, Layout};
+
+/// Draws a widget and its children with an opacity, created with
+/// [`Widget::opacity`].
+///
+/// The children are drawn together on a separate layer, which is then
+/// blended with what's behind it. So when a panel is faded, the children
+/// that overlap its background don't show through each other.
+///
+/// # Example
+/// ```
+/// use agape::widgets::{Rect, Text, Widget};
+/// use agape::vstack;
+///
+/// let panel = vstack! {
+///     Text::new("Disabled"),
+///     Rect::new(100.0, 20.0),
+/// }
+/// .opacity(0.5);
+/// ```
+pub struct Opacity {
+    id: GlobalId,
+    opacity: f32,
+    child: Box<dyn Widget>,
+}
+
+impl Opacity {
+    /// Wrap a widget, the opacity is clamped between `0.0` and `1.0`.
+    pub fn new(child: impl Widget + 'static, opacity: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            opacity: opacity.clamp(0.0, 1.0),
+            child: Box::new(child),
+        }
+    }
+
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+}
+
+impl Widget for Opacity {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn group_opacity(&self) -> Option<f32> {
+        Some(self.opacity)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+}
@@ -0,0 +1,171 @@
+use super::Widget;
+use crate::style::{FillRule, StrokeStyle};
+use crate::view::{PathCommand, PathView, View};
+use agape_core::{Color, GlobalId, IntoColor, Position, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// A custom filled shape, built from lines and curves.
+///
+/// The points are relative to the top left corner of the widget, which is
+/// sized to fit all the points.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Path;
+/// use agape::Color;
+///
+/// // An arrow pointing right
+/// let arrow = Path::new()
+///     .move_to(0.0, 8.0)
+///     .line_to(16.0, 8.0)
+///     .line_to(16.0, 0.0)
+///     .line_to(32.0, 16.0)
+///     .line_to(16.0, 32.0)
+///     .line_to(16.0, 24.0)
+///     .line_to(0.0, 24.0)
+///     .close()
+///     .fill(Color::BLACK);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Path {
+    id: GlobalId,
+    commands: Vec<PathCommand>,
+    fill: Color<Rgba>,
+    fill_rule: FillRule,
+    stroke: Option<StrokeStyle>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self {
+            fill: Color::BLACK,
+            ..Default::default()
+        }
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    /// Start a new shape at the point.
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::MoveTo(Position::new(x, y)));
+        self
+    }
+
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::LineTo(Position::new(x, y)));
+        self
+    }
+
+    /// Draw a quadratic curve to `(x,y)`, bending towards the control point.
+    pub fn quad_to(mut self, cx: f32, cy: f32, x: f32, y: f32) -> Self {
+        let command = PathCommand::QuadTo(Position::new(cx, cy), Position::new(x, y));
+        self.commands.push(command);
+        self
+    }
+
+    /// Draw a cubic curve to `(x,y)` with two control points.
+    pub fn cubic_to(mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Self {
+        let command = PathCommand::CubicTo(
+            Position::new(c1x, c1y),
+            Position::new(c2x, c2y),
+            Position::new(x, y),
+        );
+        self.commands.push(command);
+        self
+    }
+
+    /// Close the current shape with a line to its start.
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Set the fill color.
+    pub fn fill(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.fill = color.into_color();
+        self
+    }
+
+    pub fn fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Draw an outline around the shape.
+    pub fn stroke(mut self, stroke: StrokeStyle) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+}
+
+impl Widget for Path {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut size = Position::default();
+        let mut extend = |point: &Position| {
+            size.x = size.x.max(point.x);
+            size.y = size.y.max(point.y);
+        };
+        for command in &self.commands {
+            match command {
+                PathCommand::MoveTo(point) | PathCommand::LineTo(point) => extend(point),
+                PathCommand::QuadTo(control, point) => {
+                    extend(control);
+                    extend(point);
+                }
+                PathCommand::CubicTo(c1, c2, point) => {
+                    extend(c1);
+                    extend(c2);
+                    extend(point);
+                }
+                PathCommand::Close => {}
+            }
+        }
+
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(size.x, size.y);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = PathView::new(self.id);
+        view.commands = self.commands.clone();
+        view.fill = self.fill.clone();
+        view.fill_rule = self.fill_rule;
+        view.stroke = self.stroke.clone();
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use agape_layout::BoxSizing;
+
+    #[test]
+    fn size_includes_control_points() {
+        let path = Path::new().move_to(0.0, 0.0).quad_to(40.0, 60.0, 20.0, 0.0);
+        let layout = path.layout();
+        assert_eq!(layout.intrinsic_size().width, BoxSizing::Fixed(40.0));
+        assert_eq!(layout.intrinsic_size().height, BoxSizing::Fixed(60.0));
+    }
+
+    #[test]
+    fn build_commands() {
+        let path = Path::new().move_to(0.0, 0.0).line_to(10.0, 0.0).close();
+        assert_eq!(
+            path.commands(),
+            &[
+                PathCommand::MoveTo(Position::new(0.0, 0.0)),
+                PathCommand::LineTo(Position::new(10.0, 0.0)),
+                PathCommand::Close
+            ]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use super::Widget;
+use crate::style::StrokeStyle;
+use crate::view::{PieView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// A slice of a pie chart, or a segment of a donut chart when it has an
+/// inner radius.
+///
+/// Angles are in degrees, zero is at the top and angles increase clockwise.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Pie;
+/// use agape::Color;
+///
+/// // A quarter of a donut chart
+/// let segment = Pie::new(120.0, 0.0, 90.0)
+///     .inner_radius(0.6)
+///     .fill(Color::rgb(66, 133, 244));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pie {
+    id: GlobalId,
+    diameter: f32,
+    start: f32,
+    sweep: f32,
+    inner_radius: f32,
+    fill: Color<Rgba>,
+    stroke: Option<StrokeStyle>,
+}
+
+impl Pie {
+    /// Create a slice that starts at the `start` angle and goes `sweep`
+    /// degrees around the circle.
+    pub fn new(diameter: f32, start: f32, sweep: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            diameter,
+            start,
+            sweep,
+            inner_radius: 0.0,
+            fill: Color::BLACK,
+            stroke: None,
+        }
+    }
+
+    /// Cut a hole in the middle, the radius is a fraction of the outer
+    /// radius between `0.0` and `1.0`.
+    pub fn inner_radius(mut self, inner_radius: f32) -> Self {
+        self.inner_radius = inner_radius.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the fill color.
+    pub fn fill(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.fill = color.into_color();
+        self
+    }
+
+    /// Draw an outline around the slice.
+    pub fn stroke(mut self, stroke: StrokeStyle) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+}
+
+impl Widget for Pie {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.diameter, self.diameter);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = PieView::new(self.id);
+        view.fill = self.fill.clone();
+        view.stroke = self.stroke.clone();
+        view.start = self.start;
+        view.sweep = self.sweep;
+        view.inner_radius = self.inner_radius;
+        Box::new(view)
+    }
+}
@@ -0,0 +1,454 @@
+use super::image::dimensions;
+use super::{Focusable, KeyboardInput, Widget};
+use crate::image_cache::ImageHandle;
+use crate::view::{
+    PREVIEW_TOOLBAR_HEIGHT, PageThumbnail, PreviewToolbarItem, PrintPreviewView, THUMBNAIL_GAP,
+    THUMBNAIL_WIDTH, View,
+};
+use agape_core::{GlobalId, Position, Size};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use std::rc::Rc;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+type PrintFn = Box<dyn FnMut()>;
+
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 4.0;
+const ZOOM_STEP: f32 = 1.25;
+/// The height of a page relative to its width, used for pages whose size
+/// can't be read. This is the ratio of A4 paper.
+const DEFAULT_ASPECT: f32 = 1.414;
+
+/// An item in the toolbar of a [`PrintPreview`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolbarItem {
+    Previous,
+    PageNumber,
+    Next,
+    ZoomOut,
+    Zoom,
+    ZoomIn,
+    Print,
+}
+
+/// A page of a [`PrintPreview`].
+#[derive(Debug, Clone, PartialEq)]
+struct Page {
+    bytes: Rc<[u8]>,
+    handle: ImageHandle,
+    /// The height of the page relative to its width.
+    aspect: f32,
+}
+
+/// Shows the pages that will be printed or exported as thumbnails, which
+/// can be zoomed, with buttons to move between pages and to print.
+///
+/// Each page is an encoded image, such as a png of the rendered page. The
+/// pages can also be navigated with the arrow keys, and zoomed with `Ctrl`
+/// or `Cmd` and `+`, `-` or `0`. `Ctrl` + `P` prints.
+///
+/// # Example
+/// ```no_run
+/// use agape::widgets::PrintPreview;
+///
+/// let pages = ["page-1.png", "page-2.png"].map(|path| std::fs::read(path).unwrap());
+/// let preview = PrintPreview::new(pages)
+///     .on_print(|| println!("Printing..."));
+/// ```
+pub struct PrintPreview {
+    id: GlobalId,
+    pages: Vec<Page>,
+    /// The index of the selected page.
+    page: usize,
+    zoom: f32,
+    width: f32,
+    height: f32,
+    modifiers: ModifiersState,
+    print_fn: Option<PrintFn>,
+}
+
+impl PrintPreview {
+    /// Create a preview from the encoded image of each page.
+    pub fn new<I>(pages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Rc<[u8]>>,
+    {
+        let pages = pages
+            .into_iter()
+            .map(|bytes| {
+                let bytes = bytes.into();
+                let aspect = dimensions(&bytes)
+                    .filter(|(width, _)| *width > 0)
+                    .map(|(width, height)| height as f32 / width as f32)
+                    .unwrap_or(DEFAULT_ASPECT);
+                Page {
+                    handle: ImageHandle::from_bytes(&bytes),
+                    bytes,
+                    aspect,
+                }
+            })
+            .collect();
+
+        Self {
+            id: GlobalId::new(),
+            pages,
+            page: 0,
+            zoom: 1.0,
+            width: 640.0,
+            height: 480.0,
+            modifiers: ModifiersState::empty(),
+            print_fn: None,
+        }
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Called when the print button is pressed.
+    pub fn on_print(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.print_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Get the index of the selected page.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Select a page, the index is clamped to the last page.
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page.min(self.pages.len().saturating_sub(1));
+    }
+
+    pub fn next_page(&mut self) {
+        self.set_page(self.page + 1);
+    }
+
+    pub fn previous_page(&mut self) {
+        self.set_page(self.page.saturating_sub(1));
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Set the size of the thumbnails, where `1.0` is 100%. The zoom is
+    /// clamped between 50% and 400%.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.set_zoom(self.zoom * ZOOM_STEP);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.set_zoom(self.zoom / ZOOM_STEP);
+    }
+
+    fn print(&mut self) {
+        if let Some(func) = &mut self.print_fn {
+            func();
+        }
+    }
+
+    /// Get the items in the toolbar, with their position and width.
+    fn toolbar(&self) -> Vec<(ToolbarItem, f32, f32)> {
+        let items = [
+            (ToolbarItem::Previous, 40.0),
+            (ToolbarItem::PageNumber, 112.0),
+            (ToolbarItem::Next, 40.0),
+            (ToolbarItem::ZoomOut, 40.0),
+            (ToolbarItem::Zoom, 64.0),
+            (ToolbarItem::ZoomIn, 40.0),
+        ];
+        let mut x = 8.0;
+        let mut toolbar = vec![];
+        for (item, width) in items {
+            // Leave a gap between the page and zoom controls
+            if item == ToolbarItem::ZoomOut {
+                x += 16.0;
+            }
+            toolbar.push((item, x, width));
+            x += width;
+        }
+        toolbar.push((ToolbarItem::Print, self.width - 80.0, 72.0));
+        toolbar
+    }
+
+    fn press(&mut self, item: ToolbarItem) {
+        match item {
+            ToolbarItem::Previous => self.previous_page(),
+            ToolbarItem::Next => self.next_page(),
+            ToolbarItem::ZoomOut => self.zoom_out(),
+            ToolbarItem::ZoomIn => self.zoom_in(),
+            ToolbarItem::Print => self.print(),
+            ToolbarItem::PageNumber | ToolbarItem::Zoom => {}
+        }
+    }
+
+    /// Get the index, position and size of each visible page, relative to
+    /// the preview. The rows of pages scroll so that the selected page is
+    /// visible.
+    fn thumbnails(&self) -> Vec<(usize, Position, Size)> {
+        let width = THUMBNAIL_WIDTH * self.zoom;
+        let columns = ((self.width - THUMBNAIL_GAP) / (width + THUMBNAIL_GAP)).max(1.0) as usize;
+        let rows: Vec<&[Page]> = self.pages.chunks(columns).collect();
+        let row_height = |row: &[Page]| {
+            let tallest = row.iter().map(|page| page.aspect).fold(0.0, f32::max);
+            tallest * width + THUMBNAIL_GAP
+        };
+
+        let visible = self.height - PREVIEW_TOOLBAR_HEIGHT - THUMBNAIL_GAP;
+        let selected = self.page / columns;
+        let mut first = 0;
+        while first < selected
+            && rows[first..=selected]
+                .iter()
+                .map(|row| row_height(row))
+                .sum::<f32>()
+                > visible
+        {
+            first += 1;
+        }
+
+        let used = columns as f32 * (width + THUMBNAIL_GAP) - THUMBNAIL_GAP;
+        let left = ((self.width - used) / 2.0).max(0.0);
+        let mut y = PREVIEW_TOOLBAR_HEIGHT + THUMBNAIL_GAP;
+        let mut thumbnails = vec![];
+        for (index, row) in rows.iter().enumerate().skip(first) {
+            for (column, page) in row.iter().enumerate() {
+                let x = left + column as f32 * (width + THUMBNAIL_GAP);
+                let size = Size::new(width, width * page.aspect);
+                thumbnails.push((index * columns + column, Position::new(x, y), size));
+            }
+            y += row_height(row);
+        }
+        thumbnails
+    }
+
+    fn handle_key(&mut self, key: &Key) {
+        if self.modifiers.control_key() || self.modifiers.super_key() {
+            if let Key::Character(key) = key {
+                match key.to_lowercase().as_str() {
+                    "=" | "+" => self.zoom_in(),
+                    "-" => self.zoom_out(),
+                    "0" => self.set_zoom(1.0),
+                    "p" => self.print(),
+                    _ => {}
+                }
+            }
+            return;
+        }
+
+        match key {
+            Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowUp | NamedKey::PageUp) => {
+                self.previous_page()
+            }
+            Key::Named(NamedKey::ArrowRight | NamedKey::ArrowDown | NamedKey::PageDown) => {
+                self.next_page()
+            }
+            Key::Named(NamedKey::Home) => self.set_page(0),
+            Key::Named(NamedKey::End) => self.set_page(usize::MAX),
+            _ => {}
+        }
+    }
+}
+
+impl Focusable for PrintPreview {}
+
+impl KeyboardInput for PrintPreview {
+    fn key_input(&mut self, event: &KeyEvent) {
+        if event.state == ElementState::Pressed {
+            self.handle_key(&event.logical_key);
+        }
+    }
+
+    fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+}
+
+impl Widget for PrintPreview {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width, self.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = PrintPreviewView::new(self.id);
+        view.toolbar = self
+            .toolbar()
+            .into_iter()
+            .map(|(item, x, width)| {
+                let (label, enabled) = match item {
+                    ToolbarItem::Previous => ("‹".to_owned(), self.page > 0),
+                    ToolbarItem::Next => ("›".to_owned(), self.page + 1 < self.pages.len()),
+                    ToolbarItem::ZoomOut => ("−".to_owned(), self.zoom > MIN_ZOOM),
+                    ToolbarItem::ZoomIn => ("+".to_owned(), self.zoom < MAX_ZOOM),
+                    ToolbarItem::Print => ("Print".to_owned(), self.print_fn.is_some()),
+                    ToolbarItem::PageNumber => {
+                        let page = (self.page + 1).min(self.pages.len());
+                        (format!("Page {page} of {}", self.pages.len()), true)
+                    }
+                    ToolbarItem::Zoom => (format!("{:.0}%", self.zoom * 100.0), true),
+                };
+                let button = !matches!(item, ToolbarItem::PageNumber | ToolbarItem::Zoom);
+                PreviewToolbarItem {
+                    label,
+                    x,
+                    width,
+                    button,
+                    enabled,
+                }
+            })
+            .collect();
+        view.pages = self
+            .thumbnails()
+            .into_iter()
+            .map(|(index, position, size)| {
+                let page = &self.pages[index];
+                PageThumbnail {
+                    number: index + 1,
+                    position,
+                    size,
+                    handle: page.handle,
+                    bytes: page.bytes.clone(),
+                    selected: index == self.page,
+                }
+            })
+            .collect();
+        Box::new(view)
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+        Some(self)
+    }
+
+    fn drag_start(&mut self, position: Position) {
+        if position.y < PREVIEW_TOOLBAR_HEIGHT {
+            let item = self
+                .toolbar()
+                .into_iter()
+                .find(|(_, x, width)| position.x >= *x && position.x < x + width);
+            if let Some((item, _, _)) = item {
+                self.press(item);
+            }
+            return;
+        }
+
+        let page = self.thumbnails().into_iter().find(|(_, page, size)| {
+            let inside_x = position.x >= page.x && position.x < page.x + size.width;
+            let inside_y = position.y >= page.y && position.y < page.y + size.height;
+            inside_x && inside_y
+        });
+        if let Some((index, _, _)) = page {
+            self.page = index;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use image::{ImageFormat, RgbaImage};
+    use std::cell::Cell;
+    use std::io::Cursor;
+
+    /// Encode a blank page.
+    fn page(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Cursor::new(vec![]);
+        RgbaImage::new(width, height)
+            .write_to(&mut bytes, ImageFormat::Png)
+            .unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn navigate_pages() {
+        let mut preview = PrintPreview::new([page(10, 20), page(10, 20), page(10, 20)]);
+        preview.handle_key(&Key::Named(NamedKey::ArrowRight));
+        preview.handle_key(&Key::Named(NamedKey::ArrowRight));
+        preview.handle_key(&Key::Named(NamedKey::ArrowRight));
+        assert_eq!(preview.page(), 2);
+        preview.handle_key(&Key::Named(NamedKey::Home));
+        assert_eq!(preview.page(), 0);
+        preview.previous_page();
+        assert_eq!(preview.page(), 0);
+    }
+
+    #[test]
+    fn zoom_thumbnails() {
+        let mut preview = PrintPreview::new([page(10, 20), page(10, 20)]).width(500.0);
+        let (_, first, size) = preview.thumbnails()[0];
+        let (_, second, _) = preview.thumbnails()[1];
+        assert_eq!(size, Size::new(120.0, 240.0));
+        assert_eq!(second.y, first.y);
+
+        // Both pages don't fit on one row anymore
+        preview.modifiers_changed(ModifiersState::CONTROL);
+        for _ in 0..3 {
+            preview.handle_key(&Key::Character("=".into()));
+        }
+        let (_, first, _) = preview.thumbnails()[0];
+        let (_, second, _) = preview.thumbnails()[1];
+        assert!(second.y > first.y);
+
+        preview.set_zoom(10.0);
+        assert_eq!(preview.zoom(), MAX_ZOOM);
+    }
+
+    #[test]
+    fn scroll_to_selected_page() {
+        let pages = (0..6).map(|_| page(10, 10));
+        let mut preview = PrintPreview::new(pages).width(200.0).height(300.0);
+        preview.set_page(5);
+        let thumbnails = preview.thumbnails();
+        assert!(thumbnails.iter().any(|(index, ..)| *index == 5));
+        assert!(thumbnails.iter().all(|(index, ..)| *index != 0));
+    }
+
+    #[test]
+    fn click_to_select_and_print() {
+        let printed = Rc::new(Cell::new(0));
+        let count = printed.clone();
+        let mut preview = PrintPreview::new([page(10, 10), page(10, 10)])
+            .width(400.0)
+            .on_print(move || count.set(count.get() + 1));
+
+        let (_, mut position, _) = preview.thumbnails()[1];
+        position.translate(4.0, 4.0);
+        preview.drag_start(position);
+        assert_eq!(preview.page(), 1);
+
+        preview.drag_start(Position::new(400.0 - 40.0, 20.0));
+        assert_eq!(printed.get(), 1);
+    }
+}
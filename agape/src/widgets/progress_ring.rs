@@ -0,0 +1,174 @@
+use super::Widget;
+use crate::style::StrokeCap;
+use crate::view::{ArcView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{BoxSizing, EmptyLayout, IntrinsicSize, Layout};
+use std::time::Instant;
+
+/// How many degrees an indeterminate ring rotates in a second.
+const SPIN_SPEED: f32 = 360.0;
+/// The length of the arc of an indeterminate ring, in degrees.
+const SPIN_SWEEP: f32 = 270.0;
+
+/// A circular progress indicator.
+///
+/// A determinate ring sweeps an arc around a track based on the progress,
+/// an indeterminate ring spins continuously.
+///
+/// ```
+/// use agape::widgets::ProgressRing;
+/// use agape::style::StrokeCap;
+/// use agape::Color;
+///
+/// let download = ProgressRing::new(0.25)
+///     .stroke_width(6.0)
+///     .cap(StrokeCap::Round)
+///     .track_color(Color::rgb(230,230,230));
+///
+/// let loading = ProgressRing::indeterminate();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressRing {
+    id: GlobalId,
+    progress: Option<f32>,
+    diameter: f32,
+    stroke_width: f32,
+    cap: StrokeCap,
+    color: Color<Rgba>,
+    track_color: Color<Rgba>,
+    started: Instant,
+}
+
+impl ProgressRing {
+    /// Create a determinate progress ring, the `progress` is clamped between
+    /// `0.0` and `1.0`.
+    pub fn new(progress: f32) -> Self {
+        Self {
+            progress: Some(progress.clamp(0.0, 1.0)),
+            ..Self::indeterminate()
+        }
+    }
+
+    /// Create a progress ring that spins until the progress is known.
+    pub fn indeterminate() -> Self {
+        Self {
+            id: GlobalId::new(),
+            progress: None,
+            diameter: 48.0,
+            stroke_width: 4.0,
+            cap: StrokeCap::Butt,
+            color: Color::BLACK,
+            track_color: Color::TRANSPARENT,
+            started: Instant::now(),
+        }
+    }
+
+    /// Get the progress, or `None` if the ring is indeterminate.
+    pub fn progress(&self) -> Option<f32> {
+        self.progress
+    }
+
+    /// Set the width and height of the ring.
+    pub fn diameter(mut self, diameter: f32) -> Self {
+        self.diameter = diameter;
+        self
+    }
+
+    pub fn stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    /// Set the shape at the ends of the arc.
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Set the color of the arc.
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = color.into_color();
+        self
+    }
+
+    /// Set the color of the circle behind the arc.
+    pub fn track_color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.track_color = color.into_color();
+        self
+    }
+
+    /// Get the start angle and sweep of the arc, in degrees.
+    fn arc(&self) -> (f32, f32) {
+        match self.progress {
+            Some(progress) => (0.0, progress * 360.0),
+            None => {
+                let elapsed = self.started.elapsed().as_secs_f32();
+                ((elapsed * SPIN_SPEED) % 360.0, SPIN_SWEEP)
+            }
+        }
+    }
+}
+
+impl Widget for ProgressRing {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize {
+            width: BoxSizing::Fixed(self.diameter),
+            height: BoxSizing::Fixed(self.diameter),
+        };
+
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let (start, sweep) = self.arc();
+        let mut view = ArcView::new(self.id);
+        view.color = self.color.clone();
+        view.track_color = self.track_color.clone();
+        view.stroke_width = self.stroke_width;
+        view.cap = self.cap;
+        view.start = start;
+        view.sweep = sweep;
+
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clamp_progress() {
+        assert_eq!(ProgressRing::new(1.5).progress(), Some(1.0));
+        assert_eq!(ProgressRing::new(-1.0).progress(), Some(0.0));
+        assert_eq!(ProgressRing::indeterminate().progress(), None);
+    }
+
+    #[test]
+    fn determinate_sweep() {
+        let ring = ProgressRing::new(0.5);
+        assert_eq!(ring.arc(), (0.0, 180.0));
+    }
+
+    #[test]
+    fn indeterminate_sweep() {
+        let ring = ProgressRing::indeterminate();
+        let (start, sweep) = ring.arc();
+        assert!((0.0..360.0).contains(&start));
+        assert_eq!(sweep, SPIN_SWEEP);
+    }
+
+    #[test]
+    fn fixed_size() {
+        let ring = ProgressRing::new(0.0).diameter(32.0);
+        let layout = ring.layout();
+        assert_eq!(layout.intrinsic_size().width, BoxSizing::Fixed(32.0));
+        assert_eq!(layout.intrinsic_size().height, BoxSizing::Fixed(32.0));
+    }
+}
@@ -24,10 +24,10 @@ impl Rect {
             ..Default::default()
         }
     }
-
-    impl_style!();
 }
 
+impl_style!(Rect);
+
 impl Widget for Rect {
     fn id(&self) -> GlobalId {
         self.id
@@ -42,12 +42,7 @@ impl Widget for Rect {
     }
 
     fn view(&self) -> Box<dyn View> {
-        let view = RectView {
-            id: self.id,
-            color: self.style.background_color.clone(),
-            border: self.style.border.clone(),
-            ..Default::default()
-        };
+        let view = RectView::with_style(self.id, &self.style);
 
         Box::new(view)
     }
@@ -57,6 +52,7 @@ impl Widget for Rect {
 mod test {
     use super::*;
     use crate::Color;
+    use crate::style::Styled;
 
     #[test]
     fn correct_ids() {
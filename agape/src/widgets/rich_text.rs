@@ -0,0 +1,175 @@
+use super::Widget;
+use crate::style::{FontStyle, FontWeight};
+use crate::view::{RichTextView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// A run of text in a [`RichText`] with its own style.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextSpan {
+    pub text: String,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+    /// The color of the text, or the paragraph's color if `None`.
+    pub color: Option<Color<Rgba>>,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+impl TextSpan {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::Bold)
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.style = FontStyle::Italic;
+        self
+    }
+
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = Some(color.into_color());
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+}
+
+impl From<&str> for TextSpan {
+    fn from(text: &str) -> Self {
+        TextSpan::new(text)
+    }
+}
+
+/// A paragraph made of differently styled runs of text.
+///
+/// # Example
+/// ```
+/// use agape::widgets::{RichText, TextSpan};
+/// use agape::Color;
+///
+/// let text = RichText::new()
+///     .span("Build ")
+///     .span(TextSpan::new("failed").bold().color(Color::RED))
+///     .span(" after ")
+///     .span(TextSpan::new("3 minutes").italic());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichText {
+    id: GlobalId,
+    spans: Vec<TextSpan>,
+    font_size: u8,
+    font_family: Option<String>,
+    color: Color<Rgba>,
+}
+
+impl Default for RichText {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self {
+            id: GlobalId::new(),
+            spans: vec![],
+            font_size: 16,
+            font_family: None,
+            color: Color::BLACK,
+        }
+    }
+
+    /// Add a span to the end of the paragraph.
+    pub fn span(mut self, span: impl Into<TextSpan>) -> Self {
+        self.spans.push(span.into());
+        self
+    }
+
+    pub fn font_size(mut self, font_size: u8) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Use a font loaded with [`Resources::load_font`](crate::Resources::load_font)
+    /// for all the spans.
+    pub fn font_family(mut self, family: &str) -> Self {
+        self.font_family = Some(family.to_owned());
+        self
+    }
+
+    /// Set the color of the spans that don't have their own color.
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = color.into_color();
+        self
+    }
+
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    fn rich_text_view(&self) -> RichTextView {
+        let mut view = RichTextView::new(self.id);
+        view.spans = self.spans.clone();
+        view.font_size = self.font_size;
+        view.font_family = self.font_family.clone();
+        view.color = self.color.clone();
+        view
+    }
+
+    /// Get the plain text of all the spans.
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+impl Widget for RichText {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let size = self.rich_text_view().text_size();
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(size.width, size.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        Box::new(self.rich_text_view())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        let text = RichText::new()
+            .span("Hello ")
+            .span(TextSpan::new("world").bold());
+        assert_eq!(text.text(), "Hello world");
+        assert_eq!(text.spans()[1].weight, FontWeight::Bold);
+    }
+}
@@ -0,0 +1,1059 @@
+use super::{Focusable, KeyboardInput, PointerTarget, Widget};
+use crate::Result;
+use crate::document::{
+    Block, BlockKind, Document, InlineObject, MarkChange, Marks, OBJECT_REPLACEMENT, Operation,
+    TextPosition,
+};
+use crate::image_cache::decode;
+use crate::view::{
+    EDITOR_BUTTON_WIDTH, EDITOR_PADDING, EDITOR_TOOLBAR_HEIGHT, EditorLine, LIST_INDENT,
+    RichTextEditorView, View, block_font_size, block_height, char_advances, line_segments,
+    object_size,
+};
+use agape_core::{GlobalId, Position};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+use winit::window::CursorIcon;
+
+type LinkFn = Box<dyn FnMut(&str) -> Option<String>>;
+type OperationFn = Box<dyn FnMut(&Operation)>;
+
+/// A button in the toolbar of a [`RichTextEditor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolbarButton {
+    Bold,
+    Italic,
+    Underline,
+    Heading1,
+    Heading2,
+    BulletList,
+    NumberedList,
+    Link,
+}
+
+impl ToolbarButton {
+    const ALL: [ToolbarButton; 8] = [
+        ToolbarButton::Bold,
+        ToolbarButton::Italic,
+        ToolbarButton::Underline,
+        ToolbarButton::Heading1,
+        ToolbarButton::Heading2,
+        ToolbarButton::BulletList,
+        ToolbarButton::NumberedList,
+        ToolbarButton::Link,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ToolbarButton::Bold => "B",
+            ToolbarButton::Italic => "I",
+            ToolbarButton::Underline => "U",
+            ToolbarButton::Heading1 => "H1",
+            ToolbarButton::Heading2 => "H2",
+            ToolbarButton::BulletList => "•",
+            ToolbarButton::NumberedList => "1.",
+            ToolbarButton::Link => "Link",
+        }
+    }
+}
+
+/// A line of a block, after the block is wrapped to the width of the
+/// editor.
+#[derive(Debug, Clone, PartialEq)]
+struct VisualLine {
+    block: usize,
+    /// The characters of the block that are on the line.
+    range: Range<usize>,
+    x: f32,
+    y: f32,
+    height: f32,
+}
+
+/// The wrapped lines of a [`RichTextEditor`].
+struct EditorLayout {
+    lines: Vec<VisualLine>,
+    /// The width of each character of each block.
+    advances: Vec<Vec<f32>>,
+}
+
+impl EditorLayout {
+    /// Get the distance from the left of the editor to an offset in a line.
+    fn x_of(&self, line: &VisualLine, offset: usize) -> f32 {
+        let advances = &self.advances[line.block][line.range.start..offset];
+        line.x + advances.iter().sum::<f32>()
+    }
+
+    /// Get the index of the line that a position is on.
+    fn line_of(&self, position: TextPosition) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.block == position.block && line.range.start <= position.offset)
+            .unwrap_or(0)
+    }
+
+    /// Get the offset in a line that is closest to `x`.
+    fn offset_in(&self, line: &VisualLine, x: f32) -> usize {
+        let mut left = line.x;
+        for offset in line.range.clone() {
+            let advance = self.advances[line.block][offset];
+            if x < left + advance / 2.0 {
+                return offset;
+            }
+            left += advance;
+        }
+        // The end of a wrapped line is the start of the next one, so stay
+        // before the space the line was broken at
+        let wrapped = line.range.end < self.advances[line.block].len();
+        match wrapped {
+            true => (line.range.end - 1).max(line.range.start),
+            false => line.range.end,
+        }
+    }
+}
+
+/// Break a block into lines that fit in `max_width`, between words or
+/// around objects.
+fn wrap_block(block: &Block, advances: &[f32], max_width: f32) -> Vec<Range<usize>> {
+    let chars: Vec<char> = block.text().chars().collect();
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut width = 0.0;
+    let mut last_break = None;
+    for (offset, advance) in advances.iter().enumerate() {
+        // Spaces are allowed to hang past the edge
+        if width + advance > max_width && offset > start && chars[offset] != ' ' {
+            let end = last_break.filter(|end| *end > start).unwrap_or(offset);
+            ranges.push(start..end);
+            width = advances[end..offset].iter().sum();
+            start = end;
+            last_break = None;
+        }
+        width += advance;
+
+        let before_object = chars.get(offset + 1) == Some(&OBJECT_REPLACEMENT);
+        if chars[offset] == ' ' || chars[offset] == OBJECT_REPLACEMENT || before_object {
+            last_break = Some(offset + 1);
+        }
+    }
+    ranges.push(start..chars.len());
+    ranges
+}
+
+/// An editor for formatted text, with a toolbar for bold, italic,
+/// underline, headings, lists and links.
+///
+/// The formatting can also be changed with keyboard shortcuts, using
+/// `Ctrl` or `Cmd`:
+///
+/// | Shortcut | Format |
+/// | --- | --- |
+/// | `B`, `I`, `U` | Bold, italic and underline |
+/// | `K` | Link |
+/// | `1`, `2`, `3` | Headings |
+/// | `0` | Paragraph |
+/// | `Shift` + `8` | Bulleted list |
+/// | `Shift` + `7` | Numbered list |
+///
+/// The text is stored as a [`Document`], which can be exported to Markdown
+/// or HTML. Images and files can be added to the text, they wrap with the
+/// paragraph and are selected and deleted like characters.
+///
+/// Every edit is made as an [`Operation`], which can be sent to other
+/// editors with [`on_operation`](RichTextEditor::on_operation) and applied
+/// with [`apply_operation`](RichTextEditor::apply_operation), to edit a
+/// document together.
+///
+/// # Example
+/// ```
+/// use agape::widgets::RichTextEditor;
+///
+/// let editor = RichTextEditor::new()
+///     .markdown("# Notes\n- Buy **milk**")
+///     .on_link(|text| Some(format!("https://example.com/{text}")));
+///
+/// assert_eq!(editor.document().to_markdown(), "# Notes\n- Buy **milk**");
+/// ```
+pub struct RichTextEditor {
+    id: GlobalId,
+    document: Document,
+    cursor: TextPosition,
+    /// The other end of the selection, the same as the cursor when nothing
+    /// is selected.
+    anchor: TextPosition,
+    /// Marks toggled without a selection, used for the next typed text.
+    pending: Option<Marks>,
+    modifiers: ModifiersState,
+    width: f32,
+    height: f32,
+    /// Whether the text is being selected with the mouse.
+    selecting: bool,
+    drag_position: Position,
+    link_fn: Option<LinkFn>,
+    operation_fn: Option<OperationFn>,
+    /// The encoded images, by their source.
+    images: HashMap<String, Rc<[u8]>>,
+}
+
+impl Default for RichTextEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RichTextEditor {
+    pub fn new() -> Self {
+        Self {
+            id: GlobalId::new(),
+            document: Document::new(),
+            cursor: TextPosition::default(),
+            anchor: TextPosition::default(),
+            pending: None,
+            modifiers: ModifiersState::empty(),
+            width: 480.0,
+            height: 320.0,
+            selecting: false,
+            drag_position: Position::default(),
+            link_fn: None,
+            operation_fn: None,
+            images: HashMap::new(),
+        }
+    }
+
+    /// Start with the contents of a Markdown document.
+    pub fn markdown(mut self, markdown: &str) -> Self {
+        self.set_document(Document::from_markdown(markdown));
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Called with the selected text when a link is added, and returns the
+    /// url to link to, or `None` to cancel. Without a callback the selected
+    /// text is used as the url.
+    pub fn on_link(mut self, callback: impl FnMut(&str) -> Option<String> + 'static) -> Self {
+        self.link_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Called with each edit made in the editor, after it's applied to the
+    /// document.
+    pub fn on_operation(mut self, callback: impl FnMut(&Operation) + 'static) -> Self {
+        self.operation_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Add the encoded bytes of an image, which are drawn for the images
+    /// in the document with the same `source`. Images without bytes are
+    /// drawn as placeholders.
+    pub fn image(mut self, source: &str, bytes: impl Into<Rc<[u8]>>) -> Self {
+        self.images.insert(source.to_owned(), bytes.into());
+        self
+    }
+
+    /// Insert an image at the cursor, scaled down to fit in the editor.
+    ///
+    /// # Errors
+    /// Returns an error if the image could not be decoded.
+    pub fn insert_image(&mut self, source: &str, bytes: impl Into<Rc<[u8]>>) -> Result<()> {
+        let bytes = bytes.into();
+        let pixmap = decode(&bytes)?;
+        let max_width = self.width - EDITOR_PADDING * 2.0 - LIST_INDENT;
+        let scale = (max_width / pixmap.width() as f32).min(1.0);
+        let object = InlineObject::Image {
+            source: source.to_owned(),
+            width: (pixmap.width() as f32 * scale).round() as u32,
+            height: (pixmap.height() as f32 * scale).round() as u32,
+        };
+        self.images.insert(source.to_owned(), bytes);
+        self.insert_object(object);
+        Ok(())
+    }
+
+    /// Insert a file at the cursor, drawn as a chip with its name.
+    pub fn insert_file(&mut self, name: &str, source: &str) {
+        self.insert_object(InlineObject::File {
+            name: name.to_owned(),
+            source: source.to_owned(),
+        });
+    }
+
+    /// Replace the selection with an object.
+    pub fn insert_object(&mut self, object: InlineObject) {
+        self.delete_selection();
+        let offset = self.document.offset_of(self.cursor);
+        self.edit(Operation::InsertObject { offset, object });
+        self.cursor = self.document.position_at(offset + 1);
+        self.anchor = self.cursor;
+        self.pending = None;
+    }
+
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Apply an operation made by someone else, the cursor and selection
+    /// are moved to stay on the same text.
+    ///
+    /// The operation should already be transformed against the edits made
+    /// in this editor since the version it was made on, see
+    /// [`Operation::transform`].
+    pub fn apply_operation(&mut self, operation: &Operation) {
+        let cursor = operation.transform_offset(self.document.offset_of(self.cursor));
+        let anchor = operation.transform_offset(self.document.offset_of(self.anchor));
+        self.document.apply_operation(operation);
+        self.cursor = self.document.position_at(cursor);
+        self.anchor = self.document.position_at(anchor);
+    }
+
+    /// Apply an edit made in the editor.
+    fn edit(&mut self, operation: Operation) {
+        self.document.apply_operation(&operation);
+        if let Some(func) = &mut self.operation_fn {
+            func(&operation);
+        }
+    }
+
+    /// Change the marks of the text between two positions.
+    fn format(&mut self, start: TextPosition, end: TextPosition, change: MarkChange) {
+        let offset = self.document.offset_of(start);
+        let len = self.document.offset_of(end) - offset;
+        self.edit(Operation::Format {
+            offset,
+            len,
+            change,
+        });
+    }
+
+    /// Delete the text between two positions.
+    fn delete(&mut self, start: TextPosition, end: TextPosition) {
+        let offset = self.document.offset_of(start);
+        let len = self.document.offset_of(end) - offset;
+        if len > 0 {
+            self.edit(Operation::Delete { offset, len });
+        }
+    }
+
+    fn set_kind(&mut self, block: usize, kind: BlockKind) {
+        let offset = self.document.offset_of(TextPosition::new(block, 0));
+        self.edit(Operation::SetBlockKind { offset, kind });
+    }
+
+    /// Replace the document, moving the cursor to the start.
+    pub fn set_document(&mut self, document: Document) {
+        self.document = document;
+        self.cursor = TextPosition::default();
+        self.anchor = TextPosition::default();
+        self.pending = None;
+    }
+
+    /// Get the position of the text cursor.
+    pub fn caret(&self) -> TextPosition {
+        self.cursor
+    }
+
+    /// Get the start and end of the selection.
+    pub fn selection(&self) -> (TextPosition, TextPosition) {
+        (self.cursor.min(self.anchor), self.cursor.max(self.anchor))
+    }
+
+    /// Select the text between two positions, the cursor is placed at `end`.
+    pub fn select(&mut self, start: TextPosition, end: TextPosition) {
+        self.anchor = self.clamp(start);
+        self.cursor = self.clamp(end);
+        self.pending = None;
+    }
+
+    fn clamp(&self, position: TextPosition) -> TextPosition {
+        let block = position.block.min(self.document.blocks.len() - 1);
+        let offset = position.offset.min(self.document.blocks[block].len());
+        TextPosition::new(block, offset)
+    }
+
+    /// Get the selected text, with the blocks on separate lines.
+    pub fn selected_text(&self) -> String {
+        let (start, end) = self.selection();
+        let lines: Vec<String> = (start.block..=end.block)
+            .map(|index| {
+                let text = self.document.blocks[index].text();
+                let from = if index == start.block {
+                    start.offset
+                } else {
+                    0
+                };
+                let to = if index == end.block {
+                    end.offset
+                } else {
+                    usize::MAX
+                };
+                text.chars()
+                    .skip(from)
+                    .take(to.saturating_sub(from))
+                    .collect()
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    /// Replace the selection with text.
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        let marks = self.typing_marks();
+        let offset = self.document.offset_of(self.cursor);
+        self.edit(Operation::Insert {
+            offset,
+            text: text.to_owned(),
+            marks,
+        });
+        self.cursor = self.document.position_at(offset + text.chars().count());
+        self.anchor = self.cursor;
+        self.pending = None;
+    }
+
+    pub fn toggle_bold(&mut self) {
+        self.toggle_mark(|marks| marks.bold, MarkChange::Bold);
+    }
+
+    pub fn toggle_italic(&mut self) {
+        self.toggle_mark(|marks| marks.italic, MarkChange::Italic);
+    }
+
+    pub fn toggle_underline(&mut self) {
+        self.toggle_mark(|marks| marks.underline, MarkChange::Underline);
+    }
+
+    /// Link the selected text to a url, or remove the link if `url` is
+    /// `None`.
+    pub fn set_link(&mut self, url: Option<String>) {
+        let (start, end) = self.selection();
+        self.format(start, end, MarkChange::Link(url));
+    }
+
+    /// Change the kind of the blocks in the selection.
+    pub fn set_block_kind(&mut self, kind: BlockKind) {
+        let (start, end) = self.selection();
+        for block in start.block..=end.block {
+            self.set_kind(block, kind);
+        }
+    }
+
+    /// Change the kind of the blocks in the selection, or turn them back
+    /// into paragraphs if they're already that kind.
+    fn toggle_block_kind(&mut self, kind: BlockKind) {
+        let (start, end) = self.selection();
+        let blocks = &self.document.blocks[start.block..=end.block];
+        if blocks.iter().all(|block| block.kind == kind) {
+            self.set_block_kind(BlockKind::Paragraph);
+        } else {
+            self.set_block_kind(kind);
+        }
+    }
+
+    fn toggle_link(&mut self) {
+        let (start, end) = self.selection();
+        if start == end {
+            return;
+        }
+        if self.document.all(start, end, |marks| marks.link.is_some()) {
+            self.set_link(None);
+            return;
+        }
+
+        let text = self.selected_text();
+        let url = match &mut self.link_fn {
+            Some(func) => func(&text),
+            None => Some(text),
+        };
+        if url.is_some() {
+            self.set_link(url);
+        }
+    }
+
+    /// Get the marks that typed text will have.
+    fn typing_marks(&self) -> Marks {
+        match &self.pending {
+            Some(marks) => marks.clone(),
+            None => self.document.blocks[self.cursor.block].marks_at(self.cursor.offset),
+        }
+    }
+
+    fn toggle_mark(&mut self, get: fn(&Marks) -> bool, change: fn(bool) -> MarkChange) {
+        let (start, end) = self.selection();
+        if start == end {
+            let mut marks = self.typing_marks();
+            change(!get(&marks)).apply(&mut marks);
+            self.pending = Some(marks);
+            return;
+        }
+
+        let all = self.document.all(start, end, get);
+        self.format(start, end, change(!all));
+    }
+
+    /// Returns `true` if a toolbar button's format applies to the selection.
+    fn is_active(&self, button: ToolbarButton) -> bool {
+        let (start, end) = self.selection();
+        let has_mark = |get: fn(&Marks) -> bool| {
+            if start == end {
+                get(&self.typing_marks())
+            } else {
+                self.document.all(start, end, get)
+            }
+        };
+        let kind = self.document.blocks[self.cursor.block].kind;
+        match button {
+            ToolbarButton::Bold => has_mark(|marks| marks.bold),
+            ToolbarButton::Italic => has_mark(|marks| marks.italic),
+            ToolbarButton::Underline => has_mark(|marks| marks.underline),
+            ToolbarButton::Link => has_mark(|marks| marks.link.is_some()),
+            ToolbarButton::Heading1 => kind == BlockKind::Heading(1),
+            ToolbarButton::Heading2 => kind == BlockKind::Heading(2),
+            ToolbarButton::BulletList => kind == BlockKind::BulletItem,
+            ToolbarButton::NumberedList => kind == BlockKind::NumberedItem,
+        }
+    }
+
+    fn press(&mut self, button: ToolbarButton) {
+        match button {
+            ToolbarButton::Bold => self.toggle_bold(),
+            ToolbarButton::Italic => self.toggle_italic(),
+            ToolbarButton::Underline => self.toggle_underline(),
+            ToolbarButton::Link => self.toggle_link(),
+            ToolbarButton::Heading1 => self.toggle_block_kind(BlockKind::Heading(1)),
+            ToolbarButton::Heading2 => self.toggle_block_kind(BlockKind::Heading(2)),
+            ToolbarButton::BulletList => self.toggle_block_kind(BlockKind::BulletItem),
+            ToolbarButton::NumberedList => self.toggle_block_kind(BlockKind::NumberedItem),
+        }
+    }
+
+    /// Delete the selected text, returns `false` if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = self.selection();
+        if start == end {
+            return false;
+        }
+        self.delete(start, end);
+        self.cursor = start;
+        self.anchor = start;
+        true
+    }
+
+    fn backspace(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let TextPosition { block, offset } = self.cursor;
+        if offset > 0 {
+            let start = TextPosition::new(block, offset - 1);
+            self.delete(start, self.cursor);
+            self.move_to(start, false);
+        } else if self.document.blocks[block].kind != BlockKind::Paragraph {
+            // Backspace at the start of a heading or list item removes the
+            // formatting before joining lines
+            self.set_kind(block, BlockKind::Paragraph);
+        } else if block > 0 {
+            let start = TextPosition::new(block - 1, self.document.blocks[block - 1].len());
+            self.delete(start, self.cursor);
+            self.move_to(start, false);
+        }
+    }
+
+    fn delete_forward(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let end = self.next_position(self.cursor);
+        self.delete(self.cursor, end);
+    }
+
+    fn enter(&mut self) {
+        self.delete_selection();
+        let block = &self.document.blocks[self.cursor.block];
+        // Enter on an empty list item ends the list
+        if block.is_empty() && matches!(block.kind, BlockKind::BulletItem | BlockKind::NumberedItem)
+        {
+            self.set_kind(self.cursor.block, BlockKind::Paragraph);
+            return;
+        }
+        let offset = self.document.offset_of(self.cursor);
+        self.edit(Operation::Insert {
+            offset,
+            text: "\n".to_owned(),
+            marks: Marks::default(),
+        });
+        self.move_to(TextPosition::new(self.cursor.block + 1, 0), false);
+    }
+
+    fn previous_position(&self, position: TextPosition) -> TextPosition {
+        match position {
+            TextPosition {
+                block: 0,
+                offset: 0,
+            } => position,
+            TextPosition { block, offset: 0 } => {
+                TextPosition::new(block - 1, self.document.blocks[block - 1].len())
+            }
+            TextPosition { block, offset } => TextPosition::new(block, offset - 1),
+        }
+    }
+
+    fn next_position(&self, position: TextPosition) -> TextPosition {
+        let TextPosition { block, offset } = position;
+        if offset < self.document.blocks[block].len() {
+            TextPosition::new(block, offset + 1)
+        } else if block + 1 < self.document.blocks.len() {
+            TextPosition::new(block + 1, 0)
+        } else {
+            position
+        }
+    }
+
+    /// Move the cursor, extending the selection if `extend` is `true`.
+    fn move_to(&mut self, position: TextPosition, extend: bool) {
+        self.cursor = self.clamp(position);
+        if !extend {
+            self.anchor = self.cursor;
+        }
+        self.pending = None;
+    }
+
+    fn shortcut(&mut self, key: &str) {
+        let shift = self.modifiers.shift_key();
+        match key.to_lowercase().as_str() {
+            "b" => self.toggle_bold(),
+            "i" => self.toggle_italic(),
+            "u" => self.toggle_underline(),
+            "k" => self.toggle_link(),
+            "8" | "*" if shift => self.toggle_block_kind(BlockKind::BulletItem),
+            "7" | "&" if shift => self.toggle_block_kind(BlockKind::NumberedItem),
+            "1" => self.toggle_block_kind(BlockKind::Heading(1)),
+            "2" => self.toggle_block_kind(BlockKind::Heading(2)),
+            "3" => self.toggle_block_kind(BlockKind::Heading(3)),
+            "0" => self.set_block_kind(BlockKind::Paragraph),
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, key: &Key, text: Option<&str>) {
+        if self.modifiers.control_key() || self.modifiers.super_key() {
+            if let Key::Character(key) = key {
+                self.shortcut(key);
+            }
+            return;
+        }
+
+        let extend = self.modifiers.shift_key();
+        let block = self.cursor.block;
+        match key {
+            Key::Named(NamedKey::ArrowLeft) => {
+                self.move_to(self.previous_position(self.cursor), extend)
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                self.move_to(self.next_position(self.cursor), extend)
+            }
+            Key::Named(NamedKey::ArrowUp) => self.move_vertically(false, extend),
+            Key::Named(NamedKey::ArrowDown) => self.move_vertically(true, extend),
+            Key::Named(NamedKey::Home) => self.move_to(TextPosition::new(block, 0), extend),
+            Key::Named(NamedKey::End) => self.move_to(TextPosition::new(block, usize::MAX), extend),
+            Key::Named(NamedKey::Backspace) => self.backspace(),
+            Key::Named(NamedKey::Delete) => self.delete_forward(),
+            Key::Named(NamedKey::Enter) => self.enter(),
+            _ => {
+                let text: String = text
+                    .unwrap_or_default()
+                    .chars()
+                    .filter(|c| !c.is_control())
+                    .collect();
+                if !text.is_empty() {
+                    self.insert(&text);
+                }
+            }
+        }
+    }
+
+    /// Move the cursor to the line above or below, keeping it at the same
+    /// distance from the left.
+    fn move_vertically(&mut self, down: bool, extend: bool) {
+        let layout = self.editor_layout();
+        let index = layout.line_of(self.cursor);
+        let target = match down {
+            true => index + 1,
+            false => index.wrapping_sub(1),
+        };
+        let Some(line) = layout.lines.get(target) else {
+            return;
+        };
+        let x = layout.x_of(&layout.lines[index], self.cursor.offset);
+        let offset = layout.offset_in(line, x);
+        self.move_to(TextPosition::new(line.block, offset), extend);
+    }
+
+    /// Wrap the blocks to the width of the editor.
+    fn editor_layout(&self) -> EditorLayout {
+        let advances: Vec<Vec<f32>> = self.document.blocks.iter().map(char_advances).collect();
+        let mut lines = vec![];
+        let mut y = EDITOR_TOOLBAR_HEIGHT + EDITOR_PADDING;
+        for (index, block) in self.document.blocks.iter().enumerate() {
+            let x = match block.kind {
+                BlockKind::BulletItem | BlockKind::NumberedItem => EDITOR_PADDING + LIST_INDENT,
+                _ => EDITOR_PADDING,
+            };
+            let max_width = self.width - x - EDITOR_PADDING;
+            for range in wrap_block(block, &advances[index], max_width) {
+                // Lines grow to fit their objects
+                let height = range
+                    .clone()
+                    .filter_map(|offset| block.object_at(offset))
+                    .map(|object| object_size(object, block.kind).height + 4.0)
+                    .fold(block_height(block.kind), f32::max);
+                lines.push(VisualLine {
+                    block: index,
+                    range,
+                    x,
+                    y,
+                    height,
+                });
+                y += height;
+            }
+        }
+        EditorLayout { lines, advances }
+    }
+
+    /// Get the text position closest to a point in the editor.
+    fn position_at(&self, point: Position) -> TextPosition {
+        let layout = self.editor_layout();
+        let line = layout
+            .lines
+            .iter()
+            .find(|line| point.y < line.y + line.height)
+            .or(layout.lines.last())
+            .unwrap();
+        TextPosition::new(line.block, layout.offset_in(line, point.x))
+    }
+}
+
+impl Focusable for RichTextEditor {}
+
+impl KeyboardInput for RichTextEditor {
+    fn key_input(&mut self, event: &KeyEvent) {
+        if event.state == ElementState::Pressed {
+            self.handle_key(&event.logical_key, event.text.as_deref());
+        }
+    }
+
+    fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+}
+
+impl Widget for RichTextEditor {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width, self.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RichTextEditorView::new(self.id);
+        view.toolbar = ToolbarButton::ALL
+            .iter()
+            .map(|button| (button.label().to_owned(), self.is_active(*button)))
+            .collect();
+
+        let layout = self.editor_layout();
+        let mut number = 0;
+        for line in &layout.lines {
+            let block = &self.document.blocks[line.block];
+            let first = line.range.start == 0;
+            if first {
+                number = match block.kind {
+                    BlockKind::NumberedItem => number + 1,
+                    _ => 0,
+                };
+            }
+            let prefix = match block.kind {
+                _ if !first => None,
+                BlockKind::BulletItem => Some(String::from("•")),
+                BlockKind::NumberedItem => Some(format!("{number}.")),
+                _ => None,
+            };
+            let advances = &layout.advances[line.block];
+            view.lines.push(EditorLine {
+                segments: line_segments(block, line.range.clone(), advances, &self.images),
+                font_size: block_font_size(block.kind),
+                prefix,
+                x: line.x,
+                y: line.y,
+                height: line.height,
+            });
+        }
+
+        let (start, end) = self.selection();
+        if start == end {
+            let line = &layout.lines[layout.line_of(self.cursor)];
+            let x = layout.x_of(line, self.cursor.offset);
+            // The caret is as tall as the text, not the objects on the line
+            let height = block_height(self.document.blocks[line.block].kind);
+            let top = line.y + line.height - height;
+            view.caret = Some((x, top + height * 0.15, height * 0.7));
+        } else {
+            for line in &layout.lines {
+                let position = |offset| TextPosition::new(line.block, offset);
+                let from = position(line.range.start).max(start);
+                let to = position(line.range.end).min(end);
+                if from >= to {
+                    continue;
+                }
+                let left = layout.x_of(line, from.offset);
+                let right = layout.x_of(line, to.offset);
+                view.selection
+                    .push((left, line.y, right - left, line.height));
+            }
+        }
+
+        Box::new(view)
+    }
+
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+        Some(self)
+    }
+
+    fn drag_start(&mut self, position: Position) {
+        if position.y < EDITOR_TOOLBAR_HEIGHT {
+            let index = (position.x / EDITOR_BUTTON_WIDTH) as usize;
+            if let Some(button) = ToolbarButton::ALL.get(index) {
+                self.press(*button);
+            }
+            return;
+        }
+
+        self.selecting = true;
+        self.drag_position = position;
+        let extend = self.modifiers.shift_key();
+        self.move_to(self.position_at(position), extend);
+    }
+
+    fn drag(&mut self, delta: Position) {
+        if !self.selecting {
+            return;
+        }
+        self.drag_position += delta;
+        self.move_to(self.position_at(self.drag_position), true);
+    }
+
+    fn drag_end(&mut self) {
+        self.selecting = false;
+    }
+
+    fn as_pointer_target(&self) -> Option<&dyn PointerTarget> {
+        Some(self)
+    }
+}
+
+impl PointerTarget for RichTextEditor {
+    fn cursor_icon(&self) -> Option<CursorIcon> {
+        Some(CursorIcon::Text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn key(c: &str) -> Key {
+        Key::Character(c.into())
+    }
+
+    #[test]
+    fn toggle_bold_selection() {
+        let mut editor = RichTextEditor::new().markdown("hello world");
+        editor.select(TextPosition::new(0, 0), TextPosition::new(0, 5));
+        editor.toggle_bold();
+        assert_eq!(editor.document().to_markdown(), "**hello** world");
+        assert!(editor.is_active(ToolbarButton::Bold));
+
+        editor.toggle_bold();
+        assert_eq!(editor.document().to_markdown(), "hello world");
+    }
+
+    #[test]
+    fn share_edits_as_operations() {
+        let operations = Rc::new(RefCell::new(vec![]));
+        let sent = operations.clone();
+        let mut editor = RichTextEditor::new()
+            .on_operation(move |operation| sent.borrow_mut().push(operation.clone()));
+        let mut other = RichTextEditor::new();
+
+        editor.insert("hello");
+        editor.handle_key(&Key::Named(NamedKey::Enter), None);
+        editor.insert("world");
+        editor.select(TextPosition::new(0, 0), TextPosition::new(0, 5));
+        editor.toggle_bold();
+        editor.modifiers_changed(ModifiersState::CONTROL);
+        editor.handle_key(&key("1"), None);
+        for operation in operations.borrow().iter() {
+            other.apply_operation(operation);
+        }
+        assert_eq!(other.document(), editor.document());
+
+        // Text typed before the cursor moves it along
+        other.select(TextPosition::new(1, 2), TextPosition::new(1, 2));
+        other.apply_operation(&Operation::Insert {
+            offset: 6,
+            text: "big ".to_owned(),
+            marks: Marks::default(),
+        });
+        assert_eq!(other.caret(), TextPosition::new(1, 6));
+    }
+
+    #[test]
+    fn pending_marks_apply_to_typed_text() {
+        let mut editor = RichTextEditor::new();
+        editor.insert("a");
+        editor.toggle_italic();
+        editor.insert("b");
+        editor.toggle_italic();
+        editor.insert("c");
+        assert_eq!(editor.document().to_markdown(), "a_b_c");
+    }
+
+    #[test]
+    fn keyboard_shortcuts() {
+        let mut editor = RichTextEditor::new().markdown("Title\nitem");
+        editor.modifiers_changed(ModifiersState::CONTROL);
+        editor.handle_key(&key("2"), None);
+        editor.select(TextPosition::new(1, 0), TextPosition::new(1, 0));
+        editor.modifiers_changed(ModifiersState::CONTROL | ModifiersState::SHIFT);
+        editor.handle_key(&key("*"), Some("*"));
+        assert_eq!(editor.document().to_markdown(), "## Title\n- item");
+    }
+
+    #[test]
+    fn typing_and_editing_keys() {
+        let mut editor = RichTextEditor::new();
+        editor.handle_key(&key("a"), Some("a"));
+        editor.handle_key(&Key::Named(NamedKey::Enter), Some("\r"));
+        editor.handle_key(&key("b"), Some("b"));
+        assert_eq!(editor.document().text(), "a\nb");
+
+        editor.handle_key(&Key::Named(NamedKey::Backspace), None);
+        editor.handle_key(&Key::Named(NamedKey::Backspace), None);
+        assert_eq!(editor.document().text(), "a");
+        assert_eq!(editor.caret(), TextPosition::new(0, 1));
+    }
+
+    #[test]
+    fn enter_on_empty_item_ends_list() {
+        let mut editor = RichTextEditor::new().markdown("- one");
+        editor.select(TextPosition::new(0, 3), TextPosition::new(0, 3));
+        editor.enter();
+        assert_eq!(editor.document().blocks[1].kind, BlockKind::BulletItem);
+        editor.enter();
+        assert_eq!(editor.document().blocks[1].kind, BlockKind::Paragraph);
+    }
+
+    /// A block with one character per item, where `#` is an object.
+    fn block(text: &str) -> Block {
+        let mut block = Block::default();
+        for c in text.chars() {
+            match c {
+                '#' => block.insert_object(
+                    block.len(),
+                    InlineObject::File {
+                        name: String::from("file"),
+                        source: String::from("file"),
+                    },
+                ),
+                _ => block.insert(block.len(), &c.to_string(), &Marks::default()),
+            }
+        }
+        block
+    }
+
+    #[test]
+    fn wrap_between_words() {
+        let block = block("one two three");
+        let advances = vec![10.0; block.len()];
+        assert_eq!(wrap_block(&block, &advances, 75.0), [0..8, 8..13]);
+        // Words longer than the line are broken anywhere
+        assert_eq!(
+            wrap_block(&block, &advances, 35.0),
+            [0..4, 4..8, 8..11, 11..13]
+        );
+    }
+
+    #[test]
+    fn wrap_around_objects() {
+        let block = block("ab#cd");
+        let mut advances = vec![10.0; block.len()];
+        advances[2] = 50.0;
+        assert_eq!(wrap_block(&block, &advances, 60.0), [0..2, 2..3, 3..5]);
+    }
+
+    #[test]
+    fn insert_image_as_character() {
+        let mut bytes = Vec::new();
+        image::RgbaImage::new(1000, 500)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+
+        let mut editor = RichTextEditor::new().width(400.0).markdown("ab");
+        editor.select(TextPosition::new(0, 1), TextPosition::new(0, 1));
+        editor.insert_image("photo.png", bytes).unwrap();
+        assert!(editor.insert_image("broken.png", vec![0, 1, 2]).is_err());
+
+        let image = editor.document().blocks[0].object_at(1).unwrap();
+        let InlineObject::Image { width, height, .. } = image else {
+            panic!("Expected an image, found {image:?}");
+        };
+        assert!(*width <= 400);
+        assert_eq!(*width, *height * 2);
+
+        editor.handle_key(&Key::Named(NamedKey::Backspace), None);
+        assert_eq!(editor.document().text(), "ab");
+    }
+
+    #[test]
+    fn link_selection() {
+        let mut editor = RichTextEditor::new()
+            .markdown("see docs")
+            .on_link(|text| Some(format!("https://example.com/{text}")));
+        editor.select(TextPosition::new(0, 4), TextPosition::new(0, 8));
+        editor.press(ToolbarButton::Link);
+        assert_eq!(
+            editor.document().to_markdown(),
+            "see [docs](https://example.com/docs)"
+        );
+
+        editor.press(ToolbarButton::Link);
+        assert_eq!(editor.document().to_markdown(), "see docs");
+    }
+}
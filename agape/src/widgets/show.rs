@@ -0,0 +1,225 @@
+use super::{Reactive, Widget, for_each_lifecycle};
+use crate::signal::Signal;
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId};
+use agape_layout::{BlockLayout, EmptyLayout, Layout};
+
+type BuildFn = Box<dyn Fn() -> Box<dyn Widget>>;
+
+/// Shows a widget only while a condition is `true`.
+///
+/// The widget is built when the condition becomes `true` and dropped when
+/// it becomes `false`, so a hidden widget isn't laid out, drawn or sent
+/// events. Conditions over other signals are derived from them, and a
+/// fallback can be shown in its place with [`otherwise`](Show::otherwise).
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+///
+/// let unread = Signal::new(0);
+/// let badge = Show::when(unread.map(|unread| *unread > 0), || Text::new("New"))
+///     .otherwise(|| Text::new("Up to date"));
+///
+/// unread.set(3);
+/// ```
+pub struct Show {
+    id: GlobalId,
+    condition: Signal<bool>,
+    shown: bool,
+    child_fn: BuildFn,
+    fallback_fn: Option<BuildFn>,
+    /// The widget or fallback that's built for the condition.
+    child: Option<Box<dyn Widget>>,
+}
+
+impl Show {
+    pub fn when<W: Widget + 'static>(
+        condition: Signal<bool>,
+        child: impl Fn() -> W + 'static,
+    ) -> Self {
+        let mut show = Self {
+            id: GlobalId::new(),
+            condition,
+            shown: false,
+            child_fn: Box::new(move || Box::new(child())),
+            fallback_fn: None,
+            child: None,
+        };
+        show.build();
+        show
+    }
+
+    /// Show another widget while the condition is `false`.
+    pub fn otherwise<W: Widget + 'static>(mut self, fallback: impl Fn() -> W + 'static) -> Self {
+        self.fallback_fn = Some(Box::new(move || Box::new(fallback())));
+        if !self.shown {
+            self.build();
+        }
+        self
+    }
+
+    /// Returns `true` if the widget is shown, rather than the fallback.
+    pub fn is_shown(&self) -> bool {
+        self.shown
+    }
+
+    /// Build the widget for the current condition.
+    fn build(&mut self) {
+        self.shown = self.condition.with(|shown| *shown);
+        self.child = match self.shown {
+            true => Some((self.child_fn)()),
+            false => self.fallback_fn.as_ref().map(|fallback| fallback()),
+        };
+    }
+
+    /// Read the condition, so the widget is updated when it changes.
+    fn track_condition(&self) {
+        self.condition.with(|_| ());
+    }
+}
+
+impl Widget for Show {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        self.track_condition();
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        self.track_condition();
+        let Some(child) = &self.child else {
+            let mut layout = EmptyLayout::new();
+            layout.id = self.id;
+            return Box::new(layout);
+        };
+        let mut layout = BlockLayout::new(child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        self.child.iter().map(|child| child.as_ref()).collect()
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        if let Some(child) = &self.child {
+            f(child.as_ref());
+            child.traverse(f);
+        }
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        if let Some(child) = &mut self.child {
+            f(child.as_mut());
+            child.traverse_mut(f);
+        }
+    }
+
+    fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+        Some(self)
+    }
+}
+
+impl Reactive for Show {
+    fn signals_changed(&mut self) {
+        if self.condition.with(|shown| *shown) == self.shown {
+            return;
+        }
+        if let Some(child) = &mut self.child {
+            for_each_lifecycle(child.as_mut(), |widget| widget.unmount());
+        }
+        self.build();
+        if let Some(child) = &mut self.child {
+            for_each_lifecycle(child.as_mut(), |widget| widget.mount());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::{Lifecycle, Rect};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Records when it's mounted and unmounted.
+    struct Tracked {
+        id: GlobalId,
+        events: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Lifecycle for Tracked {
+        fn mount(&mut self) {
+            self.events.borrow_mut().push("mount");
+        }
+
+        fn unmount(&mut self) {
+            self.events.borrow_mut().push("unmount");
+        }
+    }
+
+    impl Widget for Tracked {
+        fn id(&self) -> GlobalId {
+            self.id
+        }
+
+        fn view(&self) -> Box<dyn View> {
+            Box::new(RectView::new(self.id))
+        }
+
+        fn layout(&self) -> Box<dyn Layout> {
+            let mut layout = EmptyLayout::new();
+            layout.id = self.id;
+            Box::new(layout)
+        }
+
+        fn as_lifecycle_mut(&mut self) -> Option<&mut dyn Lifecycle> {
+            Some(self)
+        }
+    }
+
+    #[test]
+    fn build_child_while_true() {
+        let events = Rc::new(RefCell::new(vec![]));
+        let visible = Signal::new(false);
+        let mut show = Show::when(visible.clone(), {
+            let events = events.clone();
+            move || Tracked {
+                id: GlobalId::new(),
+                events: events.clone(),
+            }
+        });
+        assert!(show.children().is_empty());
+        assert!(show.layout().children().is_empty());
+
+        visible.set(true);
+        show.signals_changed();
+        assert!(show.is_shown());
+        let id = show.children()[0].id();
+
+        visible.set(false);
+        show.signals_changed();
+        assert!(show.children().is_empty());
+        assert!(show.get(id).is_none());
+        assert_eq!(*events.borrow(), ["mount", "unmount"]);
+    }
+
+    #[test]
+    fn show_fallback_while_false() {
+        let visible = Signal::new(true);
+        let mut show = Show::when(visible.clone(), || Rect::new(10.0, 10.0))
+            .otherwise(|| Rect::new(20.0, 20.0));
+        let child = show.children()[0].id();
+
+        visible.set(false);
+        show.signals_changed();
+        assert!(!show.is_shown());
+        assert_ne!(show.children()[0].id(), child);
+    }
+}
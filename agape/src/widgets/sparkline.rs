@@ -0,0 +1,128 @@
+use super::Widget;
+use crate::view::{SparklineView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use std::rc::Rc;
+
+/// How the values of a [`Sparkline`] are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub enum SparklineKind {
+    /// A line through each value.
+    #[default]
+    Line,
+    /// A line with the area under it filled.
+    Area,
+    /// A bar for each value.
+    Bar,
+}
+
+/// A tiny chart with no axes or labels, meant to show the trend of some
+/// values at a glance.
+///
+/// Sparklines are cheap to draw so they can be used hundreds of times,
+/// for example in each row of a table.
+///
+/// ```
+/// use agape::widgets::{Sparkline,SparklineKind};
+/// use agape::Color;
+///
+/// let requests = Sparkline::new([12.0, 18.0, 9.0, 24.0, 30.0])
+///     .kind(SparklineKind::Area)
+///     .color(Color::BLUE)
+///     .size(120.0, 24.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sparkline {
+    id: GlobalId,
+    values: Rc<[f32]>,
+    kind: SparklineKind,
+    color: Color<Rgba>,
+    stroke_width: f32,
+    width: f32,
+    height: f32,
+}
+
+impl Sparkline {
+    pub fn new(values: impl Into<Rc<[f32]>>) -> Self {
+        Self {
+            id: GlobalId::new(),
+            values: values.into(),
+            kind: SparklineKind::default(),
+            color: Color::BLACK,
+            stroke_width: 1.5,
+            width: 80.0,
+            height: 24.0,
+        }
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn kind(mut self, kind: SparklineKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = color.into_color();
+        self
+    }
+
+    /// Set the width of the line, this has no effect on bar sparklines.
+    pub fn stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+}
+
+impl Widget for Sparkline {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width, self.height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = SparklineView::new(self.id, Rc::clone(&self.values));
+        view.kind = self.kind;
+        view.color = self.color.clone();
+        view.stroke_width = self.stroke_width;
+        Box::new(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn view_uses_sparkline_style() {
+        let sparkline = Sparkline::new([1.0, 2.0, 3.0])
+            .kind(SparklineKind::Bar)
+            .color(Color::BLUE)
+            .stroke_width(3.0);
+        let view = sparkline.view();
+
+        assert_eq!(view.id(), sparkline.id);
+        assert_eq!(view.color(), &Color::BLUE);
+    }
+
+    #[test]
+    fn fixed_size() {
+        let sparkline = Sparkline::new([1.0]).size(120.0, 30.0);
+        let size = sparkline.layout().intrinsic_size();
+        assert_eq!(size, IntrinsicSize::fixed(120.0, 30.0));
+    }
+}
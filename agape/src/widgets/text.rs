@@ -1,13 +1,22 @@
-use super::Widget;
-use crate::view::{TextView, View};
-use agape_core::GlobalId;
-use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+use super::{FormattedText, Role, Semantics, Widget};
+use crate::style::{TextOverflow, TextWrap};
+use crate::view::{LineCache, PlacedText, ShapedLine, TextView, View};
+use agape_core::{Color, GlobalId, IntoColor, Rgba};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout, TextLayout};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Text {
     id: GlobalId,
     pub text: String,
     pub font_size: u8,
+    pub font_family: Option<String>,
+    pub color: Color<Rgba>,
+    pub wrap: TextWrap,
+    pub max_lines: Option<usize>,
+    pub overflow: TextOverflow,
+    line_cache: LineCache,
 }
 
 impl Default for Text {
@@ -16,6 +25,12 @@ impl Default for Text {
             id: GlobalId::new(),
             text: String::new(),
             font_size: 16,
+            font_family: None,
+            color: Color::BLACK,
+            wrap: TextWrap::None,
+            max_lines: None,
+            overflow: TextOverflow::Clip,
+            line_cache: LineCache::default(),
         }
     }
 }
@@ -28,6 +43,21 @@ impl Text {
         }
     }
 
+    /// Show how long ago a `time` was, like `just now`, `5 min ago` or
+    /// `yesterday`, which stays up to date on its own. See
+    /// [`FormattedText::relative_time`].
+    ///
+    /// ```
+    /// use agape::widgets::Text;
+    /// use std::time::SystemTime;
+    ///
+    /// let posted = Text::relative_time(SystemTime::now());
+    /// assert_eq!(posted.text(), "just now");
+    /// ```
+    pub fn relative_time(time: SystemTime) -> FormattedText {
+        FormattedText::relative_time(time)
+    }
+
     /// Set the font size of the `Text` widget.
     ///
     /// ```
@@ -40,21 +70,142 @@ impl Text {
         self.font_size = font_size;
         self
     }
-}
 
-impl Widget for Text {
-    fn view(&self) -> Box<dyn View> {
+    /// Use a font loaded with [`Resources::load_font`](crate::Resources::load_font),
+    /// the bundled font is used if the family wasn't loaded.
+    ///
+    /// ```
+    /// use agape::widgets::Text;
+    /// let title = Text::new("Foo").font_family("Inter");
+    ///
+    /// assert_eq!(title.font_family.as_deref(),Some("Inter"));
+    /// ```
+    pub fn font_family(mut self, family: &str) -> Self {
+        self.font_family = Some(family.to_owned());
+        self
+    }
+
+    /// Set the color of the text.
+    ///
+    /// ```
+    /// use agape::widgets::Text;
+    /// use agape::Color;
+    /// let caption = Text::new("Foo").color(Color::rgb(100, 100, 100));
+    ///
+    /// assert_eq!(caption.color,Color::rgb(100, 100, 100));
+    /// ```
+    pub fn color(mut self, color: impl IntoColor<Rgba>) -> Self {
+        self.color = color.into_color();
+        self
+    }
+
+    /// Break the text into lines that fit in the width it's given by its
+    /// parent, the height of the text grows with the number of lines.
+    ///
+    /// ```
+    /// use agape::widgets::Text;
+    /// use agape::style::TextWrap;
+    /// let label = Text::new("A long description").wrap(TextWrap::Word);
+    ///
+    /// assert_eq!(label.wrap,TextWrap::Word);
+    /// ```
+    pub fn wrap(mut self, wrap: TextWrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Set the maximum number of lines, the lines after it are cut off.
+    ///
+    /// ```
+    /// use agape::widgets::Text;
+    /// let preview = Text::new("A long description").max_lines(2);
+    ///
+    /// assert_eq!(preview.max_lines,Some(2));
+    /// ```
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Set what happens to text that doesn't fit, either clipping it or
+    /// ending it with an ellipsis.
+    ///
+    /// ```
+    /// use agape::widgets::Text;
+    /// use agape::style::TextOverflow;
+    /// let title = Text::new("A long title").overflow(TextOverflow::Ellipsis);
+    ///
+    /// assert_eq!(title.overflow,TextOverflow::Ellipsis);
+    /// ```
+    pub fn overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    fn text_view(&self) -> TextView {
         let mut view = TextView::new(&self.text);
         view.set_id(self.id);
         view.font_size = self.font_size;
-        Box::new(view)
+        view.font_family = self.font_family.clone();
+        view.set_foreground_color(self.color.clone());
+        view.wrap = self.wrap;
+        view.max_lines = self.max_lines;
+        view.overflow = self.overflow;
+        view.line_cache = Some(self.line_cache.clone());
+        view
+    }
+
+    /// Get the lines and characters of the text as they're drawn when it's
+    /// given `max_width` pixels, to find the characters under the cursor or
+    /// draw carets and highlights.
+    ///
+    /// The width is ignored for text that isn't wrapped or truncated.
+    pub fn placed_text(&self, max_width: Option<f32>) -> PlacedText {
+        self.text_view().placed_text(max_width)
+    }
+
+    /// Get the characters of the text placed on a single line.
+    pub(crate) fn shaped_line(&self) -> Arc<ShapedLine> {
+        self.text_view().shaped_line()
+    }
+
+    /// Returns `true` if the size of the text depends on the width it's
+    /// given.
+    fn fits_width(&self) -> bool {
+        self.wrap != TextWrap::None
+            || self.max_lines.is_some()
+            || self.overflow == TextOverflow::Ellipsis
+    }
+}
+
+impl Semantics for Text {
+    fn role(&self) -> Role {
+        Role::Text
+    }
+
+    fn label(&self) -> Option<String> {
+        Some(self.text.clone())
+    }
+}
+
+impl Widget for Text {
+    fn as_semantics(&self) -> Option<&dyn Semantics> {
+        Some(self)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        Box::new(self.text_view())
     }
 
     fn layout(&self) -> Box<dyn Layout> {
-        let mut view = TextView::new(&self.text);
-        view.font_size = self.font_size;
-        let size = view.text_size();
+        let view = self.text_view();
+        if self.fits_width() {
+            let mut layout = TextLayout::new(Arc::new(move |width| view.wrapped_size(width)));
+            layout.id = self.id;
+            return Box::new(layout);
+        }
 
+        let size = view.text_size();
         let mut layout = EmptyLayout::new();
         layout.intrinsic_size = IntrinsicSize::fixed(size.width, size.height);
         layout.id = self.id;
@@ -72,6 +223,7 @@ mod test {
     use super::*;
     use crate::FONT;
     use crate::view::init_font;
+    use agape_layout::{BoxSizing, LayoutSolver, Size};
 
     #[test]
     fn layout_has_correct_id() {
@@ -81,6 +233,41 @@ mod test {
         assert_eq!(text.id, layout.id());
     }
 
+    #[test]
+    fn wrap_to_parent_width() {
+        let _ = FONT.set(init_font());
+        let text = Text::new("The quick brown fox jumps over the lazy dog").wrap(TextWrap::Word);
+        let single_line = Text::new(&text.text).layout();
+        let mut layout = text.layout();
+        let width = single_line.intrinsic_size().width;
+        let BoxSizing::Fixed(width) = width else {
+            panic!("Expected a fixed width, found {width:?}");
+        };
+
+        LayoutSolver::solve(layout.as_mut(), Size::new(width / 2.0, 500.0));
+        assert!(layout.size().width <= width / 2.0);
+        let lines = text.text_view().lines(Some(layout.size().width));
+        assert!(lines.len() >= 2);
+        assert!(lines.iter().all(|line| !line.starts_with(' ')));
+    }
+
+    #[test]
+    fn ellipsis_after_max_lines() {
+        let _ = FONT.set(init_font());
+        let text = Text::new("one two three four five six")
+            .wrap(TextWrap::Word)
+            .max_lines(2)
+            .overflow(TextOverflow::Ellipsis);
+        let view = text.text_view();
+        let max_width = TextView::new("one two").text_size().width;
+        let lines = view.lines(Some(max_width));
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "one two");
+        assert!(lines[1].ends_with('…'));
+        assert!(view.wrapped_size(Some(max_width)).width <= max_width);
+    }
+
     #[test]
     fn view_has_correct_id() {
         let text = Text::new("Hello");
@@ -1,16 +1,76 @@
+use crate::event::EventCtx;
 use crate::impl_style;
-use crate::style::BoxStyle;
-use crate::view::{RectView, View};
-use crate::widgets::{Text, Widget};
-use agape_core::GlobalId;
-use agape_layout::{BlockLayout, Layout};
-use winit::event::KeyEvent;
-
-#[derive(Default, Clone)]
+use crate::signal::Signal;
+use crate::style::{BoxStyle, State, StateStyled, StateStyles};
+use crate::view::{CompletionPopup, RectView, TextFieldView, View};
+use crate::widgets::{
+    Button, Focusable, KeyboardInput, PointerTarget, Reactive, Role, Semantics, Text, Widget,
+};
+use agape_core::{Color, GlobalId, Rgba, Size};
+use agape_layout::{AxisAlignment, HorizontalLayout, Layout, LayoutSolver};
+use std::ops::Range;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::CursorIcon;
+
+/// The maximum number of suggestions shown at once.
+const MAX_SUGGESTIONS: usize = 6;
+/// The space between the text and the widgets next to it.
+const SPACING: u32 = 8;
+const PLACEHOLDER_COLOR: Color<Rgba> = Color::rgb(150, 150, 150);
+
+/// Suggestions shown when a trigger character is typed.
+#[derive(Debug, Clone, PartialEq)]
+struct Completion {
+    trigger: char,
+    items: Vec<String>,
+}
+
+/// A completion that is being typed.
+#[derive(Debug, Clone, PartialEq)]
+struct ActiveCompletion {
+    trigger: char,
+    /// The byte index of the trigger character.
+    start: usize,
+    selected: usize,
+}
+
+/// A completed item in a [`TextField`], such as a mention.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub trigger: char,
+    /// The byte range of the token in the text, including the trigger.
+    pub range: Range<usize>,
+}
+
+/// A single line text input.
+///
+/// # Example
+/// ```
+/// use agape::widgets::{Text, TextField};
+///
+/// let price = TextField::new()
+///     .placeholder("Price")
+///     .leading(Text::new("$"))
+///     .trailing(Text::new("USD"))
+///     .clearable();
+/// ```
+#[derive(Default)]
 pub struct TextField {
     id: GlobalId,
     pub text: Text,
+    /// Shown in place of the text while it's empty.
+    placeholder: Option<Text>,
+    leading: Option<Box<dyn Widget>>,
+    trailing: Option<Box<dyn Widget>>,
+    clear_button: Option<Button>,
     pub style: BoxStyle,
+    state_styles: StateStyles,
+    completions: Vec<Completion>,
+    active: Option<ActiveCompletion>,
+    tokens: Vec<Token>,
+    tab_index: Option<u32>,
+    binding: Option<Signal<String>>,
 }
 
 impl TextField {
@@ -18,48 +78,583 @@ impl TextField {
         Self::default()
     }
 
-    impl_style!();
+    /// Show muted text while the field is empty.
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        let text = Text::new(placeholder)
+            .font_size(self.text.font_size)
+            .color(PLACEHOLDER_COLOR);
+        self.placeholder = Some(text);
+        self
+    }
+
+    /// Show a widget before the text, such as an icon.
+    pub fn leading(mut self, widget: impl Widget + 'static) -> Self {
+        self.leading = Some(Box::new(widget));
+        self
+    }
+
+    /// Show a widget after the text, such as a unit.
+    pub fn trailing(mut self, widget: impl Widget + 'static) -> Self {
+        self.trailing = Some(Box::new(widget));
+        self
+    }
+
+    /// Show a button that clears the text while the field isn't empty.
+    pub fn clearable(mut self) -> Self {
+        self.clear_button = Some(Button::new(Text::new("×").color(PLACEHOLDER_COLOR)));
+        self
+    }
+
+    /// Show the text of a signal, and set it when the text is edited.
+    ///
+    /// # Example
+    /// ```
+    /// use agape::signal::Signal;
+    /// use agape::widgets::TextField;
+    ///
+    /// let name = Signal::new(String::from("Alice"));
+    /// let field = TextField::new().bind(name.clone());
+    /// assert_eq!(field.text.text, "Alice");
+    /// ```
+    pub fn bind(mut self, signal: Signal<String>) -> Self {
+        self.text.text = signal.get();
+        self.binding = Some(signal);
+        self
+    }
+
+    /// Remove the text and the tokens in it.
+    pub fn clear(&mut self) {
+        self.text.text.clear();
+        self.tokens.clear();
+        self.active = None;
+        self.write_binding();
+    }
+
+    /// Set the bound signal to the text, if it's different.
+    fn write_binding(&self) {
+        let Some(signal) = &self.binding else {
+            return;
+        };
+        if signal.with(|text| *text != self.text.text) {
+            signal.set(self.text.text.clone());
+        }
+    }
+
+    /// Read the bound signal, so the field is updated when it changes.
+    fn track_binding(&self) {
+        if let Some(signal) = &self.binding {
+            signal.with(|_| ());
+        }
+    }
+
+    /// Show suggestions when `trigger` is typed at the start of a word,
+    /// filtered by the text typed after it. Choosing a suggestion inserts
+    /// it as a [`Token`].
+    ///
+    /// # Example
+    /// ```
+    /// use agape::widgets::TextField;
+    ///
+    /// let field = TextField::new()
+    ///     .completion('@', ["alice", "bob"])
+    ///     .completion('/', ["giphy", "shrug"]);
+    /// ```
+    pub fn completion(
+        mut self,
+        trigger: char,
+        items: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.completions.push(Completion {
+            trigger,
+            items: items.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Set the position of the field in the tab order, see
+    /// [`Focusable::tab_index`].
+    pub fn tab_index(mut self, index: u32) -> Self {
+        self.tab_index = Some(index);
+        self
+    }
+
+    /// Get the completed tokens, in the order they appear.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Get the suggestions for the completion being typed.
+    pub fn suggestions(&self) -> Vec<&str> {
+        let Some(active) = &self.active else {
+            return vec![];
+        };
+        let Some(completion) = self
+            .completions
+            .iter()
+            .find(|completion| completion.trigger == active.trigger)
+        else {
+            return vec![];
+        };
+
+        // The text can be changed from outside of the field
+        let Some(query) = self
+            .text
+            .text
+            .get(active.start + active.trigger.len_utf8()..)
+        else {
+            return vec![];
+        };
+        let query = query.to_lowercase();
+        completion
+            .items
+            .iter()
+            .filter(|item| item.to_lowercase().contains(&query))
+            .take(MAX_SUGGESTIONS)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Insert a suggestion, replacing the text typed after the trigger.
+    pub fn complete(&mut self, index: usize) {
+        let Some(item) = self.suggestions().get(index).map(|item| item.to_string()) else {
+            return;
+        };
+        let Some(active) = self.active.take() else {
+            return;
+        };
+
+        let text = &mut self.text.text;
+        text.truncate(active.start);
+        text.push(active.trigger);
+        text.push_str(&item);
+        self.tokens.push(Token {
+            trigger: active.trigger,
+            range: active.start..text.len(),
+        });
+        text.push(' ');
+        self.write_binding();
+    }
+
+    fn insert_text(&mut self, input: &str) {
+        for c in input.chars().filter(|c| !c.is_control()) {
+            let at_word_start = self.text.text.is_empty() || self.text.text.ends_with(' ');
+            if c.is_whitespace() {
+                self.active = None;
+            } else if at_word_start && self.completions.iter().any(|item| item.trigger == c) {
+                self.active = Some(ActiveCompletion {
+                    trigger: c,
+                    start: self.text.text.len(),
+                    selected: 0,
+                });
+            }
+            self.text.text.push(c);
+        }
+
+        if let Some(active) = &mut self.active {
+            active.selected = 0;
+        }
+    }
+
+    fn backspace(&mut self) {
+        let text = &mut self.text.text;
+        // Tokens are deleted as a whole, like a single character
+        if let Some(token) = self.tokens.last() {
+            if token.range.end == text.len() {
+                text.truncate(token.range.start);
+                self.tokens.pop();
+                return;
+            }
+        }
+
+        text.pop();
+        if let Some(active) = &self.active {
+            if text.len() <= active.start {
+                self.active = None;
+            }
+        }
+    }
+
+    /// Handle keys that navigate the suggestions, returns `true` if the key
+    /// was used.
+    fn navigate(&mut self, key: &Key) -> bool {
+        let count = self.suggestions().len();
+        let Some(active) = &mut self.active else {
+            return false;
+        };
+        match key {
+            Key::Named(NamedKey::ArrowDown) if count > 0 => {
+                active.selected = (active.selected + 1) % count;
+            }
+            Key::Named(NamedKey::ArrowUp) if count > 0 => {
+                active.selected = (active.selected + count - 1) % count;
+            }
+            Key::Named(NamedKey::Enter | NamedKey::Tab) if count > 0 => {
+                let selected = active.selected;
+                self.complete(selected);
+            }
+            Key::Named(NamedKey::Escape) => self.active = None,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Get the width of the text up to a byte index.
+    fn text_width(&self, end: usize) -> f32 {
+        self.text.shaped_line().width_to(end)
+    }
+
+    /// Get the distance from the left of the field to the text.
+    fn text_offset(&self) -> f32 {
+        let Some(leading) = &self.leading else {
+            return 0.0;
+        };
+        let mut layout = leading.layout();
+        LayoutSolver::solve(&mut *layout, Size::default());
+        layout.size().width + SPACING as f32
+    }
+
+    /// Get the text, or the placeholder while the text is empty.
+    fn content(&self) -> &Text {
+        match &self.placeholder {
+            Some(placeholder) if self.text.text.is_empty() => placeholder,
+            _ => &self.text,
+        }
+    }
+
+    fn slots(&self) -> Vec<&dyn Widget> {
+        let mut slots: Vec<&dyn Widget> = vec![];
+        slots.extend(self.leading.as_deref());
+        slots.push(self.content());
+        if !self.text.text.is_empty() {
+            slots.extend(
+                self.clear_button
+                    .as_ref()
+                    .map(|button| button as &dyn Widget),
+            );
+        }
+        slots.extend(self.trailing.as_deref());
+        slots
+    }
 }
 
-impl Widget for TextField {
+impl_style!(TextField);
+
+impl StateStyled for TextField {
+    fn state_styles_mut(&mut self) -> &mut StateStyles {
+        &mut self.state_styles
+    }
+}
+
+impl Focusable for TextField {
+    fn tab_index(&self) -> Option<u32> {
+        self.tab_index
+    }
+
+    /// Tab picks the selected suggestion.
+    fn accepts_tab(&self) -> bool {
+        self.active.is_some() && !self.suggestions().is_empty()
+    }
+
+    fn focus(&mut self) {
+        self.state_styles.update(State::Focused, true, &self.style);
+    }
+
+    fn unfocus(&mut self) {
+        self.state_styles.update(State::Focused, false, &self.style);
+    }
+}
+
+impl KeyboardInput for TextField {
     fn key_input(&mut self, event: &KeyEvent) {
-        if let Some(text) = &event.text {
-            self.text.text.push_str(text);
+        if event.state != ElementState::Pressed || self.navigate(&event.logical_key) {
+            return;
         }
+        if event.logical_key == Key::Named(NamedKey::Backspace) {
+            self.backspace();
+        } else if let Some(text) = &event.text {
+            self.insert_text(text);
+        }
+        self.write_binding();
+    }
+}
+
+impl Semantics for TextField {
+    fn role(&self) -> Role {
+        Role::TextInput
+    }
+}
+
+impl Widget for TextField {
+    fn as_focusable(&self) -> Option<&dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_focusable_mut(&mut self) -> Option<&mut dyn Focusable> {
+        Some(self)
+    }
+
+    fn as_keyboard_input_mut(&mut self) -> Option<&mut dyn KeyboardInput> {
+        Some(self)
+    }
+
+    fn as_semantics(&self) -> Option<&dyn Semantics> {
+        Some(self)
     }
 
     fn id(&self) -> GlobalId {
         self.id
     }
 
+    fn click(&mut self, ctx: &mut EventCtx) {
+        let on_clear = self
+            .clear_button
+            .as_ref()
+            .is_some_and(|button| button.get(ctx.target()).is_some());
+        if on_clear {
+            self.clear();
+            ctx.stop_propagation();
+        }
+    }
+
+    fn press(&mut self, _ctx: &mut EventCtx) {
+        self.state_styles.update(State::Pressed, true, &self.style);
+    }
+
+    fn release(&mut self, _ctx: &mut EventCtx) {
+        self.state_styles.update(State::Pressed, false, &self.style);
+    }
+
+    fn hover(&mut self) {
+        self.state_styles.update(State::Hovered, true, &self.style);
+    }
+
+    fn unhover(&mut self) {
+        self.state_styles.update(State::Hovered, false, &self.style);
+    }
+
     fn view(&self) -> Box<dyn View> {
-        let view = RectView {
-            id: self.id,
-            color: self.style.background_color.clone(),
-            border: self.style.border.clone(),
-            ..Default::default()
-        };
+        self.track_binding();
+        let style = self.state_styles.resolve(&self.style);
+        let mut view = TextFieldView::new(RectView::with_style(self.id, &style));
+        let offset = self.text_offset();
+        let len = self.text.text.len();
+        view.tokens = self
+            .tokens
+            .iter()
+            .filter(|token| token.range.end <= len)
+            .map(|token| {
+                let start = self.text_width(token.range.start);
+                (offset + start, self.text_width(token.range.end) - start)
+            })
+            .collect();
+        view.popup = self
+            .active
+            .as_ref()
+            .filter(|active| active.start < len)
+            .map(|active| CompletionPopup {
+                x: offset + self.text_width(active.start),
+                items: self.suggestions().into_iter().map(String::from).collect(),
+                selected: active.selected,
+            });
         Box::new(view)
     }
 
     fn layout(&self) -> Box<dyn Layout> {
-        let child_layout = self.text.layout();
-        let mut layout = BlockLayout::new(child_layout);
-        layout.id = self.id;
+        self.track_binding();
+        let mut layout = HorizontalLayout {
+            id: self.id,
+            spacing: SPACING,
+            cross_axis_alignment: AxisAlignment::Center,
+            ..Default::default()
+        };
+        layout.children = self.slots().iter().map(|slot| slot.layout()).collect();
         Box::new(layout)
     }
 
     fn children(&self) -> Vec<&dyn Widget> {
-        vec![&self.text]
+        self.slots()
     }
 
     fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
-        f(&self.text);
-        self.text.traverse(f);
+        for slot in self.slots() {
+            f(slot);
+            slot.traverse(f);
+        }
     }
 
     fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
-        f(&mut self.text);
-        self.text.traverse_mut(f);
+        let empty = self.text.text.is_empty();
+        if let Some(leading) = &mut self.leading {
+            f(leading.as_mut());
+            leading.traverse_mut(f);
+        }
+        let content = match &mut self.placeholder {
+            Some(placeholder) if empty => placeholder,
+            _ => &mut self.text,
+        };
+        f(content);
+        content.traverse_mut(f);
+        if let Some(button) = self.clear_button.as_mut().filter(|_| !empty) {
+            f(button);
+            button.traverse_mut(f);
+        }
+        if let Some(trailing) = &mut self.trailing {
+            f(trailing.as_mut());
+            trailing.traverse_mut(f);
+        }
+    }
+
+    fn as_reactive_mut(&mut self) -> Option<&mut dyn Reactive> {
+        Some(self)
+    }
+
+    fn as_pointer_target(&self) -> Option<&dyn PointerTarget> {
+        Some(self)
+    }
+}
+
+impl PointerTarget for TextField {
+    fn cursor_icon(&self) -> Option<CursorIcon> {
+        Some(CursorIcon::Text)
+    }
+}
+
+impl Reactive for TextField {
+    fn signals_changed(&mut self) {
+        let Some(text) = self.binding.as_ref().map(Signal::get) else {
+            return;
+        };
+        // The tokens don't point into text that was set from outside
+        if text != self.text.text {
+            self.text.text = text;
+            self.tokens.clear();
+            self.active = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FONT;
+    use crate::view::init_font;
+
+    fn field() -> TextField {
+        TextField::new().completion('@', ["alice", "bob", "Alicia"])
+    }
+
+    #[test]
+    fn filter_suggestions() {
+        let mut field = field();
+        field.insert_text("hi @al");
+        assert_eq!(field.suggestions(), vec!["alice", "Alicia"]);
+
+        // Triggers only start completions at the start of a word
+        let mut field = self::field();
+        field.insert_text("me@al");
+        assert!(field.suggestions().is_empty());
+    }
+
+    #[test]
+    fn complete_inserts_token() {
+        let mut field = field();
+        field.insert_text("hi @b");
+        assert!(field.navigate(&Key::Named(NamedKey::Enter)));
+
+        assert_eq!(field.text.text, "hi @bob ");
+        assert_eq!(
+            field.tokens(),
+            [Token {
+                trigger: '@',
+                range: 3..7
+            }]
+        );
+        assert!(field.suggestions().is_empty());
+    }
+
+    #[test]
+    fn select_with_arrows() {
+        let mut field = field();
+        field.insert_text("@a");
+        field.navigate(&Key::Named(NamedKey::ArrowDown));
+        field.navigate(&Key::Named(NamedKey::Tab));
+        assert_eq!(field.text.text, "@Alicia ");
+    }
+
+    #[test]
+    fn delete_token_at_once() {
+        let mut field = field();
+        field.insert_text("@b");
+        field.complete(0);
+        field.backspace();
+        field.backspace();
+        assert_eq!(field.text.text, "");
+        assert!(field.tokens().is_empty());
+    }
+
+    #[test]
+    fn space_cancels_completion() {
+        let mut field = field();
+        field.insert_text("@bo");
+        assert_eq!(field.suggestions(), vec!["bob"]);
+        field.insert_text(" ");
+        assert!(field.suggestions().is_empty());
+        assert!(!field.navigate(&Key::Named(NamedKey::Enter)));
+    }
+
+    #[test]
+    fn placeholder_while_empty() {
+        let _ = FONT.set(init_font());
+        let mut field = TextField::new()
+            .placeholder("Search")
+            .leading(Text::new("?"));
+        let placeholder = field.placeholder.as_ref().unwrap().id();
+        assert_eq!(field.children()[1].id(), placeholder);
+        assert_eq!(field.layout().children().len(), 2);
+
+        field.insert_text("a");
+        assert_eq!(field.children()[1].id(), field.text.id());
+    }
+
+    #[test]
+    fn clear_button_clears_text() {
+        let mut field = field().clearable().trailing(Text::new("kg"));
+        assert_eq!(field.children().len(), 2);
+        field.insert_text("@b");
+        field.complete(0);
+        assert_eq!(field.children().len(), 3);
+
+        // Clicks on the button's label bubble up to the field
+        let label = field.children()[1].children()[0].id();
+        let mut ctx = EventCtx::new(label, None);
+        field.click(&mut ctx);
+        assert!(ctx.is_propagation_stopped());
+        assert_eq!(field.text.text, "");
+        assert!(field.tokens().is_empty());
+        assert_eq!(field.children().len(), 2);
+    }
+
+    #[test]
+    fn bound_to_signal() {
+        let message = Signal::new(String::from("hi "));
+        let mut field = field().bind(message.clone());
+        field.insert_text("@b");
+        field.complete(0);
+        assert_eq!(message.get(), "hi @bob ");
+
+        message.set(String::from("bye"));
+        field.signals_changed();
+        assert_eq!(field.text.text, "bye");
+        assert!(field.tokens().is_empty());
+    }
+
+    #[test]
+    fn text_emptied_outside_of_field() {
+        let _ = FONT.set(init_font());
+        let mut field = field();
+        field.insert_text("@b");
+        field.text.text.clear();
+        assert!(field.suggestions().is_empty());
+        field.view();
     }
 }
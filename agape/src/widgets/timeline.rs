@@ -0,0 +1,420 @@
+use super::Widget;
+use crate::chart::Axis;
+use crate::view::{HEADER_HEIGHT, TimelineMetrics, TimelineView, View};
+use agape_core::{Color, GlobalId, IntoColor, Position, Rgba, Size};
+use agape_layout::{EmptyLayout, IntrinsicSize, Layout};
+
+/// How close, in pixels, to the edge of a bar the cursor has to be to
+/// resize it instead of moving it.
+const RESIZE_HANDLE: f32 = 6.0;
+
+type ChangeFn = Box<dyn FnMut(usize, &TimelineBar)>;
+
+/// A bar on a [`Timeline`], from `start` to `end`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct TimelineBar {
+    pub label: String,
+    pub row: usize,
+    pub start: f32,
+    pub end: f32,
+    pub color: Color<Rgba>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DragMode {
+    Move,
+    ResizeStart,
+    ResizeEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    /// Dragging a bar, the start and end are kept before snapping so that
+    /// small movements add up.
+    Bar {
+        index: usize,
+        mode: DragMode,
+        start: f32,
+        end: f32,
+    },
+    Pan,
+}
+
+/// A Gantt chart of bars grouped into rows, along a time axis.
+///
+/// Bars can be moved by dragging them, or resized by dragging their edges.
+/// Dragging the background pans the time axis and [`Timeline::scroll`] zooms
+/// it.
+///
+/// The units of time are up to the app, for example days since the
+/// start of a project.
+///
+/// # Example
+/// ```
+/// use agape::widgets::Timeline;
+/// use agape::Color;
+///
+/// let project = Timeline::new(0.0, 30.0)
+///     .row("Design")
+///     .row("Build")
+///     .bar(0, "Wireframes", 0.0, 7.0, Color::BLUE)
+///     .bar(1, "Backend", 5.0, 20.0, Color::AMBER)
+///     .snap(1.0)
+///     .formatter(|day| format!("Day {day}"))
+///     .on_change(|index, bar| println!("Bar {index} moved to {}..{}", bar.start, bar.end));
+/// ```
+pub struct Timeline {
+    id: GlobalId,
+    rows: Vec<String>,
+    bars: Vec<TimelineBar>,
+    axis: Axis,
+    range: [f32; 2],
+    width: f32,
+    label_width: f32,
+    row_height: f32,
+    snap: Option<f32>,
+    drag: Option<Drag>,
+    change_fn: Option<ChangeFn>,
+}
+
+impl Timeline {
+    /// Create a timeline showing the time from `start` to `end`.
+    pub fn new(start: f32, end: f32) -> Self {
+        Self {
+            id: GlobalId::new(),
+            rows: vec![],
+            bars: vec![],
+            axis: Axis::new(start, end).tick_count(8),
+            range: [start, end],
+            width: 600.0,
+            label_width: 100.0,
+            row_height: 28.0,
+            snap: None,
+            drag: None,
+            change_fn: None,
+        }
+    }
+
+    /// Add a row.
+    pub fn row(mut self, label: &str) -> Self {
+        self.rows.push(label.to_owned());
+        self
+    }
+
+    /// Add a bar to a row.
+    pub fn bar(
+        mut self,
+        row: usize,
+        label: &str,
+        start: f32,
+        end: f32,
+        color: impl IntoColor<Rgba>,
+    ) -> Self {
+        self.bars.push(TimelineBar {
+            label: label.to_owned(),
+            row,
+            start: start.min(end),
+            end: start.max(end),
+            color: color.into_color(),
+        });
+        self
+    }
+
+    /// Snap the bars to multiples of a unit of time when they are dragged.
+    pub fn snap(mut self, unit: f32) -> Self {
+        self.snap = (unit > 0.0).then_some(unit);
+        self
+    }
+
+    /// Set how times are shown on the axis.
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'static) -> Self {
+        self.axis = self.axis.formatter(formatter);
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the width of the column with the row labels.
+    pub fn label_width(mut self, width: f32) -> Self {
+        self.label_width = width;
+        self
+    }
+
+    pub fn row_height(mut self, height: f32) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    /// Called with the index of a bar when it has been moved or resized.
+    pub fn on_change(mut self, callback: impl FnMut(usize, &TimelineBar) + 'static) -> Self {
+        self.change_fn = Some(Box::new(callback));
+        self
+    }
+
+    pub fn bars(&self) -> &[TimelineBar] {
+        &self.bars
+    }
+
+    /// Get the visible range of time.
+    pub fn visible_range(&self) -> [f32; 2] {
+        self.range
+    }
+
+    fn metrics(&self) -> TimelineMetrics {
+        TimelineMetrics {
+            width: self.width,
+            label_width: self.label_width,
+            row_height: self.row_height,
+            range: self.range,
+        }
+    }
+
+    fn snap_time(&self, time: f32) -> f32 {
+        match self.snap {
+            Some(unit) => (time / unit).round() * unit,
+            None => time,
+        }
+    }
+
+    /// Find the bar under the position and what dragging it would do.
+    fn hit_test(&self, position: Position) -> Option<(usize, DragMode)> {
+        let metrics = self.metrics();
+        // Check the bars drawn last first, since they are on top
+        let (index, bounds) = self
+            .bars
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, bar)| (index, metrics.bar_bounds(bar)))
+            .find(|(_, bounds)| {
+                position.x >= bounds.x[0] - RESIZE_HANDLE / 2.0
+                    && position.x <= bounds.x[1] + RESIZE_HANDLE / 2.0
+                    && position.y >= bounds.y[0]
+                    && position.y <= bounds.y[1]
+            })?;
+
+        let mode = if (position.x - bounds.x[0]).abs() <= RESIZE_HANDLE / 2.0 {
+            DragMode::ResizeStart
+        } else if (position.x - bounds.x[1]).abs() <= RESIZE_HANDLE / 2.0 {
+            DragMode::ResizeEnd
+        } else {
+            DragMode::Move
+        };
+        Some((index, mode))
+    }
+
+    /// Vertical scrolling zooms and horizontal scrolling pans the time axis.
+    pub fn scroll(&mut self, delta: Position) {
+        let [start, end] = self.range;
+        let factor = 1.1f32.powf(delta.y);
+        let center = (start + end) / 2.0;
+        let half = (end - start) / 2.0 / factor;
+        let pan = -delta.x * (end - start) * 0.1;
+        self.range = [center - half + pan, center + half + pan];
+    }
+}
+
+impl Widget for Timeline {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let height = HEADER_HEIGHT + self.rows.len() as f32 * self.row_height;
+        let mut layout = EmptyLayout::new();
+        layout.id = self.id;
+        layout.intrinsic_size = IntrinsicSize::fixed(self.width, height);
+        Box::new(layout)
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut axis = self.axis.clone();
+        axis.range = self.range;
+        Box::new(TimelineView {
+            id: self.id,
+            position: Position::default(),
+            size: Size::default(),
+            rows: self.rows.clone(),
+            bars: self.bars.clone(),
+            axis,
+            label_width: self.label_width,
+            row_height: self.row_height,
+        })
+    }
+
+    fn drag_start(&mut self, position: Position) {
+        self.drag = match self.hit_test(position) {
+            Some((index, mode)) => Some(Drag::Bar {
+                index,
+                mode,
+                start: self.bars[index].start,
+                end: self.bars[index].end,
+            }),
+            None if position.x > self.label_width => Some(Drag::Pan),
+            None => None,
+        };
+    }
+
+    fn drag(&mut self, delta: Position) {
+        let scale = self.metrics().scale();
+        if scale == 0.0 {
+            return;
+        }
+        let dt = delta.x / scale;
+
+        let Some(Drag::Bar {
+            index,
+            mode,
+            start,
+            end,
+        }) = self.drag
+        else {
+            if self.drag == Some(Drag::Pan) {
+                self.range = [self.range[0] - dt, self.range[1] - dt];
+            }
+            return;
+        };
+
+        let (start, end) = match mode {
+            DragMode::Move => (start + dt, end + dt),
+            DragMode::ResizeStart => (start + dt, end),
+            DragMode::ResizeEnd => (start, end + dt),
+        };
+        self.drag = Some(Drag::Bar {
+            index,
+            mode,
+            start,
+            end,
+        });
+
+        let duration = self.bars[index].end - self.bars[index].start;
+        let (new_start, new_end) = match mode {
+            // Keep the duration when moving
+            DragMode::Move => {
+                let start = self.snap_time(start);
+                (start, start + duration)
+            }
+            DragMode::ResizeStart => (self.snap_time(start), self.bars[index].end),
+            DragMode::ResizeEnd => (self.bars[index].start, self.snap_time(end)),
+        };
+        // Bars can't be shorter than one unit, or flip around
+        let min_duration = self.snap.unwrap_or(0.0);
+        if new_end - new_start < min_duration || new_end < new_start {
+            return;
+        }
+
+        let bar = &mut self.bars[index];
+        if bar.start == new_start && bar.end == new_end {
+            return;
+        }
+        bar.start = new_start;
+        bar.end = new_end;
+        if let Some(func) = &mut self.change_fn {
+            func(index, &self.bars[index]);
+        }
+    }
+
+    fn scroll_by(&mut self, delta: Position) -> bool {
+        self.scroll(delta);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A timeline where each unit of time is 10 pixels wide.
+    fn timeline() -> Timeline {
+        Timeline::new(0.0, 50.0)
+            .width(600.0)
+            .label_width(100.0)
+            .row_height(20.0)
+            .row("Design")
+            .bar(0, "Mockups", 10.0, 20.0, Color::BLUE)
+    }
+
+    fn bar_y() -> f32 {
+        HEADER_HEIGHT + 10.0
+    }
+
+    #[test]
+    fn find_bar_under_cursor() {
+        let timeline = timeline();
+        // The bar goes from x=200 to x=300
+        assert_eq!(
+            timeline.hit_test(Position::new(250.0, bar_y())),
+            Some((0, DragMode::Move))
+        );
+        assert_eq!(
+            timeline.hit_test(Position::new(201.0, bar_y())),
+            Some((0, DragMode::ResizeStart))
+        );
+        assert_eq!(
+            timeline.hit_test(Position::new(299.0, bar_y())),
+            Some((0, DragMode::ResizeEnd))
+        );
+        assert_eq!(timeline.hit_test(Position::new(350.0, bar_y())), None);
+    }
+
+    #[test]
+    fn move_bar() {
+        let changes = Rc::new(RefCell::new(vec![]));
+        let output = Rc::clone(&changes);
+        let mut timeline = timeline().on_change(move |index, bar| {
+            output.borrow_mut().push((index, bar.start, bar.end));
+        });
+
+        timeline.drag_start(Position::new(250.0, bar_y()));
+        timeline.drag(Position::new(25.0, 0.0));
+        assert_eq!(*changes.borrow(), vec![(0, 12.5, 22.5)]);
+    }
+
+    #[test]
+    fn snap_when_moving() {
+        let mut timeline = timeline().snap(5.0);
+        timeline.drag_start(Position::new(250.0, bar_y()));
+
+        timeline.drag(Position::new(20.0, 0.0));
+        assert_eq!(timeline.bars()[0].start, 10.0);
+        timeline.drag(Position::new(20.0, 0.0));
+        assert_eq!(timeline.bars()[0].start, 15.0);
+        assert_eq!(timeline.bars()[0].end, 25.0);
+    }
+
+    #[test]
+    fn resize_bar() {
+        let mut timeline = timeline().snap(1.0);
+        timeline.drag_start(Position::new(299.0, bar_y()));
+        timeline.drag(Position::new(50.0, 0.0));
+        assert_eq!(timeline.bars()[0].start, 10.0);
+        assert_eq!(timeline.bars()[0].end, 25.0);
+
+        // Bars can't be shrunk past their start
+        timeline.drag(Position::new(-500.0, 0.0));
+        assert_eq!(timeline.bars()[0].end, 25.0);
+    }
+
+    #[test]
+    fn drag_background_to_pan() {
+        let mut timeline = timeline();
+        timeline.drag_start(Position::new(500.0, bar_y()));
+        timeline.drag(Position::new(-100.0, 0.0));
+        assert_eq!(timeline.visible_range(), [10.0, 60.0]);
+        assert_eq!(timeline.bars()[0].start, 10.0);
+    }
+
+    #[test]
+    fn scroll_to_zoom() {
+        let mut timeline = timeline();
+        timeline.scroll(Position::new(0.0, 1.0));
+        let [start, end] = timeline.visible_range();
+        assert!(end - start < 50.0);
+        assert_eq!((start + end) / 2.0, 25.0);
+    }
+}
@@ -0,0 +1,186 @@
+use super::Widget;
+use crate::view::{RectView, View};
+use agape_core::{Color, GlobalId, Position, Size};
+use agape_layout::{BlockLayout, Layout};
+
+/// A rotation, scale and translation applied to a widget around an origin.
+///
+/// Transforms only change how a widget is drawn and where it's clicked, the
+/// widget still takes up its untransformed space in the layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetTransform {
+    /// The clockwise rotation in degrees.
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub translation: Position,
+    /// The point that the widget is rotated and scaled around, as a fraction
+    /// of its size, the center is `(0.5, 0.5)`.
+    pub origin: Position,
+}
+
+impl Default for WidgetTransform {
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            translation: Position::default(),
+            origin: Position::unit(0.5),
+        }
+    }
+}
+
+impl WidgetTransform {
+    /// Get the matrix that transforms a widget at a `position` with a `size`.
+    pub(crate) fn matrix(&self, position: Position, size: Size) -> tiny_skia::Transform {
+        let origin_x = position.x + size.width * self.origin.x;
+        let origin_y = position.y + size.height * self.origin.y;
+        tiny_skia::Transform::from_translate(-origin_x, -origin_y)
+            .post_scale(self.scale_x, self.scale_y)
+            .post_rotate(self.rotation)
+            .post_translate(origin_x + self.translation.x, origin_y + self.translation.y)
+    }
+}
+
+/// Rotates, scales and moves a widget and its children, created with
+/// [`Widget::rotate`] and [`Widget::scale`].
+///
+/// # Example
+/// ```
+/// use agape::widgets::{Rect, Widget};
+///
+/// // Spin around the top left corner, at half the size
+/// let rect = Rect::new(100.0, 20.0)
+///     .rotate(45.0)
+///     .scale(0.5)
+///     .origin(0.0, 0.0);
+/// ```
+pub struct Transformed {
+    id: GlobalId,
+    transform: WidgetTransform,
+    child: Box<dyn Widget>,
+}
+
+impl Transformed {
+    pub fn new(child: impl Widget + 'static, transform: WidgetTransform) -> Self {
+        Self {
+            id: GlobalId::new(),
+            transform,
+            child: Box::new(child),
+        }
+    }
+
+    /// Rotate clockwise by `degrees`, on top of the current rotation.
+    pub fn rotate(mut self, degrees: f32) -> Self {
+        self.transform.rotation += degrees;
+        self
+    }
+
+    /// Scale by `factor`, on top of the current scale.
+    pub fn scale(mut self, factor: f32) -> Self {
+        self.transform.scale_x *= factor;
+        self.transform.scale_y *= factor;
+        self
+    }
+
+    /// Scale the width and height separately.
+    pub fn scale_xy(mut self, x: f32, y: f32) -> Self {
+        self.transform.scale_x *= x;
+        self.transform.scale_y *= y;
+        self
+    }
+
+    pub fn translate(mut self, x: f32, y: f32) -> Self {
+        self.transform.translation.translate(x, y);
+        self
+    }
+
+    /// Set the point that the widget is rotated and scaled around, as a
+    /// fraction of its size.
+    pub fn origin(mut self, x: f32, y: f32) -> Self {
+        self.transform.origin = Position::new(x, y);
+        self
+    }
+
+    pub fn get_transform(&self) -> WidgetTransform {
+        self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: WidgetTransform) {
+        self.transform = transform;
+    }
+}
+
+impl Widget for Transformed {
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn view(&self) -> Box<dyn View> {
+        let mut view = RectView::new(self.id);
+        view.color = Color::TRANSPARENT;
+        Box::new(view)
+    }
+
+    fn layout(&self) -> Box<dyn Layout> {
+        let mut layout = BlockLayout::new(self.child.layout());
+        layout.id = self.id;
+        Box::new(layout)
+    }
+
+    fn group_transform(&self) -> Option<WidgetTransform> {
+        Some(self.transform)
+    }
+
+    fn children(&self) -> Vec<&dyn Widget> {
+        vec![self.child.as_ref()]
+    }
+
+    fn traverse(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        f(self.child.as_ref());
+        self.child.traverse(f);
+    }
+
+    fn traverse_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(self.child.as_mut());
+        self.child.traverse_mut(f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Rect;
+    use tiny_skia::Point;
+
+    #[test]
+    fn transform_around_origin() {
+        let transform = WidgetTransform {
+            rotation: 90.0,
+            ..Default::default()
+        };
+        let matrix = transform.matrix(Position::new(10.0, 10.0), Size::new(20.0, 20.0));
+        let mut point = Point::from_xy(10.0, 10.0);
+        matrix.map_point(&mut point);
+        assert!((point.x - 30.0).abs() < 0.001 && (point.y - 10.0).abs() < 0.001);
+
+        // The origin doesn't move
+        let mut center = Point::from_xy(20.0, 20.0);
+        matrix.map_point(&mut center);
+        assert!((center.x - 20.0).abs() < 0.001 && (center.y - 20.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn combine_modifiers() {
+        let rect = Rect::new(10.0, 10.0)
+            .rotate(30.0)
+            .rotate(15.0)
+            .scale(2.0)
+            .translate(5.0, 0.0);
+        let transform = rect.group_transform().unwrap();
+        assert_eq!(transform.rotation, 45.0);
+        assert_eq!(transform.scale_x, 2.0);
+        assert_eq!(transform.translation, Position::new(5.0, 0.0));
+    }
+}
@@ -1,6 +1,7 @@
+use crate::impl_style;
 use crate::style::BoxStyle;
 use crate::view::{RectView, View};
-use crate::{impl_style, widgets::Widget};
+use crate::widgets::{LayoutExt, Widget};
 use agape_core::GlobalId;
 use agape_layout::{AxisAlignment, Layout, VerticalLayout};
 
@@ -47,35 +48,31 @@ impl VStack {
         self
     }
 
-    pub fn padding(mut self, padding: u32) -> Self {
-        self.layout.padding = padding;
-        self
-    }
-
     pub fn spacing(mut self, spacing: u32) -> Self {
         self.layout.spacing = spacing;
         self
     }
+}
 
-    pub fn align_center(mut self) -> Self {
-        self.layout.main_axis_alignment = AxisAlignment::Center;
-        self.layout.cross_axis_alignment = AxisAlignment::Center;
+impl LayoutExt for VStack {
+    fn padding(mut self, padding: u32) -> Self {
+        self.layout.padding = padding;
         self
     }
 
-    pub fn main_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+    fn main_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
         self.layout.main_axis_alignment = alignment;
         self
     }
 
-    pub fn cross_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
+    fn cross_axis_alignment(mut self, alignment: AxisAlignment) -> Self {
         self.layout.cross_axis_alignment = alignment;
         self
     }
-
-    impl_style!();
 }
 
+impl_style!(VStack);
+
 impl Widget for VStack {
     fn id(&self) -> GlobalId {
         self.id
@@ -96,12 +93,7 @@ impl Widget for VStack {
     }
 
     fn view(&self) -> Box<dyn View> {
-        let view = RectView {
-            id: self.id,
-            color: self.style.background_color.clone(),
-            border: self.style.border.clone(),
-            ..Default::default()
-        };
+        let view = RectView::with_style(self.id, &self.style);
         Box::new(view)
     }
 
@@ -148,7 +140,8 @@ impl Widget for VStack {
 /// - Create a [`Vstack`] from a given list of widgets.
 /// ```
 /// use agape::vstack;
-/// use agape::widgets::Text;
+/// use agape::style::Styled;
+/// use agape::widgets::{LayoutExt,Text};
 ///
 /// let vstack = vstack!{
 ///     Text::new("Hello"),
@@ -196,6 +189,7 @@ macro_rules! vstack {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::style::Styled;
     use crate::widgets::{Rect, Text};
     use agape_layout::BoxSizing;
 
@@ -0,0 +1,501 @@
+//! Settings for the app's window, and controlling it while the app is
+//! running with [`WindowControls`].
+use crate::style::BoxShadow;
+use crate::view::{ShadowCache, draw_shadow, new_paint};
+use agape_core::{Color, Position, Size};
+use std::cell::RefCell;
+use std::sync::Arc;
+use tiny_skia::Pixmap;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+use winit::window::{ResizeDirection, Window, WindowAttributes};
+
+/// The default width of the resize border of undecorated windows.
+pub const DEFAULT_RESIZE_BORDER: f32 = 6.0;
+
+/// A translucent material drawn by the system behind the window, so that
+/// the desktop shows through the app's transparent areas.
+///
+/// Platforms that don't have the exact material fall back to blurring what's
+/// behind the window, which is only supported on macOS and KDE on Wayland.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Vibrancy {
+    /// An opaque window.
+    #[default]
+    None,
+    /// Blur what's behind the window.
+    Blur,
+    /// The Mica material on Windows 11, which is tinted by the desktop
+    /// wallpaper.
+    Mica,
+    /// The Acrylic material on Windows 11.
+    Acrylic,
+}
+
+/// The settings of the window, used when it's created.
+///
+/// # Example
+/// ```
+/// use agape::{App, hstack};
+/// use agape::window::{Vibrancy, WindowConfig};
+///
+/// let app = App::new(hstack! {})
+///     .window(WindowConfig::new().vibrancy(Vibrancy::Mica));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowConfig {
+    vibrancy: Vibrancy,
+    size: Option<Size>,
+    decorations: bool,
+    shadow: Option<BoxShadow>,
+    resize_border: f32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            vibrancy: Vibrancy::None,
+            size: None,
+            decorations: true,
+            shadow: Some(BoxShadow::new(
+                Position::new(0.0, 4.0),
+                16.0,
+                0.0,
+                Color::rgba(0, 0, 0, 30),
+            )),
+            resize_border: DEFAULT_RESIZE_BORDER,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the initial size of the window's content, in physical pixels.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = Some(Size::new(width, height));
+        self
+    }
+
+    pub fn get_size(&self) -> Option<Size> {
+        self.size
+    }
+
+    /// Show or hide the title bar and borders drawn by the system, for apps
+    /// that draw their own title bar.
+    ///
+    /// Undecorated windows draw their own drop shadow and can still be
+    /// resized from their edges and corners.
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Set the drop shadow drawn around undecorated windows, or remove it
+    /// with `None`.
+    pub fn shadow(mut self, shadow: Option<BoxShadow>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// Set the width of the edges that resize undecorated windows, a width
+    /// of `0.0` makes the window only resizable by the system.
+    pub fn resize_border(mut self, width: f32) -> Self {
+        self.resize_border = width.max(0.0);
+        self
+    }
+
+    /// Draw a translucent material behind the window. The window is
+    /// cleared to transparent instead of white, so only the widgets with a
+    /// background are opaque.
+    pub fn vibrancy(mut self, vibrancy: Vibrancy) -> Self {
+        self.vibrancy = vibrancy;
+        self
+    }
+
+    pub fn get_vibrancy(&self) -> Vibrancy {
+        self.vibrancy
+    }
+
+    pub fn has_decorations(&self) -> bool {
+        self.decorations
+    }
+
+    /// Returns `true` if the window is cleared to transparent, which
+    /// is the case for windows with vibrancy or a synthesized shadow.
+    pub fn is_transparent(&self) -> bool {
+        self.vibrancy != Vibrancy::None || self.frame().shadow.is_some()
+    }
+
+    /// Get the [`WindowFrame`] around the content of the window.
+    pub(crate) fn frame(&self) -> WindowFrame {
+        if self.decorations {
+            return WindowFrame::default();
+        }
+
+        let shadow = self.shadow.clone();
+        let margin = shadow.as_ref().map_or(0.0, |shadow| {
+            let offset = shadow.offset.x.abs().max(shadow.offset.y.abs());
+            (shadow.blur * 1.5 + shadow.spread + offset).ceil().max(0.0)
+        });
+        WindowFrame {
+            margin,
+            resize_border: self.resize_border,
+            shadow,
+            hovered: None,
+            opaque: self.vibrancy == Vibrancy::None,
+        }
+    }
+
+    pub(crate) fn attributes(&self) -> WindowAttributes {
+        let mut attributes = WindowAttributes::default()
+            .with_decorations(self.decorations)
+            .with_transparent(self.is_transparent())
+            .with_blur(self.vibrancy != Vibrancy::None);
+        if let Some(size) = self.size {
+            attributes = attributes.with_inner_size(PhysicalSize::new(size.width, size.height));
+        }
+
+        #[cfg(target_os = "windows")]
+        let attributes = {
+            use winit::platform::windows::{BackdropType, WindowAttributesExtWindows};
+            let backdrop = match self.vibrancy {
+                Vibrancy::None => BackdropType::Auto,
+                Vibrancy::Mica => BackdropType::MainWindow,
+                Vibrancy::Blur | Vibrancy::Acrylic => BackdropType::TransientWindow,
+            };
+            attributes.with_system_backdrop(backdrop)
+        };
+
+        attributes
+    }
+}
+
+thread_local! {
+    /// The app's window, once it's been opened.
+    static WINDOW: RefCell<Option<Arc<Window>>> = const { RefCell::new(None) };
+}
+
+/// Make the window available to [`WindowControls::current`].
+pub(crate) fn set_current(window: Arc<Window>) {
+    WINDOW.with_borrow_mut(|current| *current = Some(window));
+}
+
+/// How the window fills the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fullscreen {
+    /// Cover the monitor with a borderless window, which is quick to switch
+    /// to and from.
+    Borderless,
+    /// Take over the monitor, at its native resolution and the highest
+    /// refresh rate. Falls back to borderless on platforms that don't
+    /// support it.
+    Exclusive,
+}
+
+/// A monitor connected to the computer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: Option<String>,
+    /// The position of the monitor on the desktop, in physical pixels.
+    pub position: Position,
+    /// The size of the monitor, in physical pixels.
+    pub size: Size,
+    pub scale_factor: f64,
+    /// The refresh rate in hertz, if it's known.
+    pub refresh_rate: Option<f32>,
+}
+
+impl From<MonitorHandle> for Monitor {
+    fn from(monitor: MonitorHandle) -> Self {
+        let position = monitor.position();
+        Self {
+            name: monitor.name(),
+            position: Position::new(position.x as f32, position.y as f32),
+            size: Size::from(monitor.size()),
+            scale_factor: monitor.scale_factor(),
+            refresh_rate: monitor
+                .refresh_rate_millihertz()
+                .map(|rate| rate as f32 / 1000.0),
+        }
+    }
+}
+
+/// Controls the app's window while it's running, from event handlers,
+/// systems or anywhere else on the ui thread.
+///
+/// Positions and sizes are in physical pixels, like in [`WindowConfig`].
+///
+/// # Example
+/// ```
+/// use agape::prelude::*;
+/// use agape::window::{Fullscreen, WindowControls};
+///
+/// let fullscreen = Button::new(Text::new("Fullscreen")).on_click(|_| {
+///     if let Some(window) = WindowControls::current() {
+///         window.toggle_fullscreen(Fullscreen::Borderless);
+///     }
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowControls {
+    window: Arc<Window>,
+}
+
+impl WindowControls {
+    /// Get the controls of the app's window, returns `None` if the window
+    /// hasn't been opened yet.
+    pub fn current() -> Option<Self> {
+        WINDOW.with_borrow(|window| {
+            window.as_ref().map(|window| Self {
+                window: Arc::clone(window),
+            })
+        })
+    }
+
+    /// Make the window fill the monitor it's on, or leave fullscreen with
+    /// `None`.
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let monitor = self.window.current_monitor();
+        let fullscreen = fullscreen.map(|fullscreen| match fullscreen {
+            Fullscreen::Borderless => winit::window::Fullscreen::Borderless(monitor),
+            Fullscreen::Exclusive => {
+                let mode = monitor.as_ref().and_then(|monitor| {
+                    let size = monitor.size();
+                    monitor
+                        .video_modes()
+                        .filter(|mode| mode.size() == size)
+                        .max_by_key(|mode| mode.refresh_rate_millihertz())
+                });
+                match mode {
+                    Some(mode) => winit::window::Fullscreen::Exclusive(mode),
+                    None => winit::window::Fullscreen::Borderless(monitor),
+                }
+            }
+        });
+        self.window.set_fullscreen(fullscreen);
+    }
+
+    /// Enter `fullscreen` if the window isn't fullscreen, otherwise leave
+    /// fullscreen.
+    pub fn toggle_fullscreen(&self, fullscreen: Fullscreen) {
+        match self.is_fullscreen() {
+            true => self.set_fullscreen(None),
+            false => self.set_fullscreen(Some(fullscreen)),
+        }
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.fullscreen().is_some()
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        self.window.set_maximized(maximized);
+    }
+
+    pub fn toggle_maximized(&self) {
+        self.set_maximized(!self.is_maximized());
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.window.is_maximized()
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        self.window.set_minimized(minimized);
+    }
+
+    /// Returns `true` if the window is minimized, or `None` if the platform
+    /// doesn't say.
+    pub fn is_minimized(&self) -> Option<bool> {
+        self.window.is_minimized()
+    }
+
+    /// Show or hide the window, hidden windows don't receive events.
+    pub fn set_visible(&self, visible: bool) {
+        self.window.set_visible(visible);
+    }
+
+    /// Bring the window to the front and give it the keyboard focus.
+    pub fn focus(&self) {
+        self.window.focus_window();
+    }
+
+    /// Move the top left corner of the window to a position on the desktop.
+    pub fn set_position(&self, position: Position) {
+        self.window
+            .set_outer_position(PhysicalPosition::new(position.x, position.y));
+    }
+
+    /// Get the position of the top left corner of the window, or `None` on
+    /// platforms where windows can't tell, like Wayland.
+    pub fn position(&self) -> Option<Position> {
+        let position = self.window.outer_position().ok()?;
+        Some(Position::new(position.x as f32, position.y as f32))
+    }
+
+    /// Resize the content of the window, the system may pick another size,
+    /// like when the window is maximized.
+    pub fn set_size(&self, width: f32, height: f32) {
+        let _ = self
+            .window
+            .request_inner_size(PhysicalSize::new(width, height));
+    }
+
+    /// Get the size of the content of the window.
+    pub fn size(&self) -> Size {
+        Size::from(self.window.inner_size())
+    }
+
+    /// Get the monitor that the window is on, if it can be detected.
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        self.window.current_monitor().map(Monitor::from)
+    }
+
+    /// Get all the monitors connected to the computer.
+    pub fn monitors(&self) -> Vec<Monitor> {
+        self.window
+            .available_monitors()
+            .map(Monitor::from)
+            .collect()
+    }
+
+    /// Set the text in the title bar.
+    pub fn set_title(&self, title: &str) {
+        self.window.set_title(title);
+    }
+}
+
+/// The area around the content of an undecorated window, where its shadow
+/// is drawn and where it can be resized from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct WindowFrame {
+    /// The space between the edges of the window and its content.
+    pub margin: f32,
+    pub resize_border: f32,
+    pub shadow: Option<BoxShadow>,
+    /// The edge that the cursor is over.
+    pub hovered: Option<ResizeDirection>,
+    /// Whether the content is filled, windows with vibrancy only draw the
+    /// shadow.
+    pub opaque: bool,
+}
+
+impl WindowFrame {
+    /// Get the position and size of the content of the window.
+    pub fn content(&self, window_size: Size) -> (Position, Size) {
+        let size = Size::new(
+            (window_size.width - self.margin * 2.0).max(0.0),
+            (window_size.height - self.margin * 2.0).max(0.0),
+        );
+        (Position::unit(self.margin), size)
+    }
+
+    /// Get the edge or corner of the window at a `position`, the whole
+    /// margin is part of the edges since the shadow can't be clicked
+    /// through.
+    pub fn resize_direction(
+        &self,
+        position: Position,
+        window_size: Size,
+    ) -> Option<ResizeDirection> {
+        if self.resize_border <= 0.0 {
+            return None;
+        }
+
+        let edge = self.margin + self.resize_border;
+        let left = position.x < edge;
+        let right = position.x > window_size.width - edge;
+        let top = position.y < edge;
+        let bottom = position.y > window_size.height - edge;
+
+        match (left, right, top, bottom) {
+            (true, _, true, _) => Some(ResizeDirection::NorthWest),
+            (_, true, true, _) => Some(ResizeDirection::NorthEast),
+            (true, _, _, true) => Some(ResizeDirection::SouthWest),
+            (_, true, _, true) => Some(ResizeDirection::SouthEast),
+            (true, ..) => Some(ResizeDirection::West),
+            (_, true, ..) => Some(ResizeDirection::East),
+            (_, _, true, _) => Some(ResizeDirection::North),
+            (_, _, _, true) => Some(ResizeDirection::South),
+            _ => None,
+        }
+    }
+
+    /// Draw the shadow around the content, and fill the content for
+    /// opaque windows.
+    pub fn render(&self, pixmap: &mut Pixmap, cache: Option<&ShadowCache>) {
+        let Some(shadow) = &self.shadow else {
+            return;
+        };
+        let window_size = Size::new(pixmap.width() as f32, pixmap.height() as f32);
+        let (position, size) = self.content(window_size);
+        let Some(rect) =
+            tiny_skia::Rect::from_xywh(position.x, position.y, size.width, size.height)
+        else {
+            return;
+        };
+
+        draw_shadow(pixmap, rect, 0.0, shadow, cache);
+        if self.opaque {
+            let mut paint = new_paint();
+            paint.set_color(tiny_skia::Color::WHITE);
+            pixmap.fill_rect(rect, &paint, tiny_skia::Transform::identity(), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vibrancy_makes_window_transparent() {
+        let attributes = WindowConfig::new().attributes();
+        assert!(!attributes.transparent);
+
+        let config = WindowConfig::new().vibrancy(Vibrancy::Acrylic);
+        let attributes = config.attributes();
+        assert!(attributes.transparent && attributes.blur);
+    }
+
+    #[test]
+    fn undecorated_window_frame() {
+        let config = WindowConfig::new().decorations(false);
+        let attributes = config.attributes();
+        assert!(!attributes.decorations && attributes.transparent);
+
+        let frame = config.frame();
+        assert_eq!(frame.margin, 28.0);
+        let (position, size) = frame.content(Size::unit(256.0));
+        assert_eq!(position, Position::unit(28.0));
+        assert_eq!(size, Size::unit(200.0));
+        assert_eq!(WindowConfig::new().frame(), WindowFrame::default());
+    }
+
+    #[test]
+    fn resize_from_edges() {
+        let frame = WindowConfig::new().decorations(false).shadow(None).frame();
+        let size = Size::new(400.0, 300.0);
+        let direction = |x, y| frame.resize_direction(Position::new(x, y), size);
+
+        assert_eq!(direction(2.0, 2.0), Some(ResizeDirection::NorthWest));
+        assert_eq!(direction(398.0, 298.0), Some(ResizeDirection::SouthEast));
+        assert_eq!(direction(200.0, 2.0), Some(ResizeDirection::North));
+        assert_eq!(direction(2.0, 150.0), Some(ResizeDirection::West));
+        assert_eq!(direction(200.0, 150.0), None);
+
+        let frame = WindowFrame {
+            resize_border: 0.0,
+            ..frame
+        };
+        assert_eq!(frame.resize_direction(Position::unit(2.0), size), None);
+    }
+
+    #[test]
+    fn no_controls_before_window_opens() {
+        assert!(WindowControls::current().is_none());
+    }
+}
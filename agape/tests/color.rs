@@ -0,0 +1,9 @@
+use agape::{Color, Rgba, hex};
+
+static ACCENT: Color<Rgba> = hex!("#f808");
+
+#[test]
+fn hex_macro_is_const() {
+    assert_eq!(ACCENT, Color::rgba(255, 136, 0, 53));
+    assert_eq!(hex!("#ffabbc"), Color::rgb(255, 171, 188));
+}
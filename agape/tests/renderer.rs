@@ -1,7 +1,7 @@
 use agape::Resources;
-use agape::style::Border;
+use agape::style::{Border, Gradient};
 use agape::view::{RectView, View};
-use agape_core::{Color, Size};
+use agape_core::{Color, Position, Size};
 use tiny_skia::Pixmap;
 
 #[test]
@@ -44,6 +44,7 @@ fn draw_border() {
         border: Some(Border {
             color: border_color.clone(),
             width: 10.0,
+            ..Default::default()
         }),
         color: background_color,
         ..Default::default()
@@ -65,3 +66,45 @@ fn draw_border() {
         }
     }
 }
+
+#[test]
+fn draw_linear_gradient() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let view = RectView {
+        size: Size::unit(100.0),
+        gradient: Some(
+            Gradient::linear(90.0)
+                .stop(0.0, Color::RED)
+                .stop(1.0, Color::BLUE),
+        ),
+        ..Default::default()
+    };
+
+    view.render(&mut pixmap, &Resources::new());
+
+    let left = pixmap.pixel(0, 50).unwrap();
+    let right = pixmap.pixel(99, 50).unwrap();
+    assert!(left.red() > 250 && left.blue() < 5);
+    assert!(right.blue() > 250 && right.red() < 5);
+}
+
+#[test]
+fn draw_radial_gradient() {
+    let mut pixmap = Pixmap::new(100, 100).unwrap();
+    let view = RectView {
+        size: Size::unit(100.0),
+        gradient: Some(
+            Gradient::radial(Position::unit(0.5), 0.5)
+                .stop(0.0, Color::WHITE)
+                .stop(1.0, Color::BLACK),
+        ),
+        ..Default::default()
+    };
+
+    view.render(&mut pixmap, &Resources::new());
+
+    let center = pixmap.pixel(50, 50).unwrap();
+    let corner = pixmap.pixel(0, 0).unwrap();
+    assert!(center.red() > 245);
+    assert_eq!(corner.red(), 0);
+}
@@ -11,12 +11,40 @@ pub trait IntoColor<Container> {
 pub enum ColorError {
     #[error("Missing # at the start of hex code")]
     MissingHex,
-    #[error("Hex code is {0} characters long but must have a length of 6 or 8")]
+    #[error("Hex code is {0} characters long but must have a length of 3, 4, 6 or 8")]
     InvalidLength(usize),
+    #[error("Hex code contains {0:?}, which is not a hexadecimal digit")]
+    InvalidDigit(char),
     #[error(transparent)]
     ParseIntError(#[from] ParseIntError),
 }
 
+/// The minimum contrast ratios from the Web Content Accessibility
+/// Guidelines (WCAG).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ContrastLevel {
+    /// Level AA for normal text.
+    AA,
+    /// Level AA for large text (18pt or 14pt bold).
+    AALarge,
+    /// Level AAA for normal text.
+    AAA,
+    /// Level AAA for large text (18pt or 14pt bold).
+    AAALarge,
+}
+
+impl ContrastLevel {
+    /// The minimum contrast ratio required to meet this level.
+    pub fn min_ratio(&self) -> f32 {
+        match self {
+            ContrastLevel::AA => 4.5,
+            ContrastLevel::AALarge => 3.0,
+            ContrastLevel::AAA => 7.0,
+            ContrastLevel::AAALarge => 4.5,
+        }
+    }
+}
+
 // TODO change this to static string and make color copy
 /// Container for Hex [`Color`]s
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -103,31 +131,148 @@ impl Color<Rgba> {
         Color(color)
     }
 
+    /// Create a new rgba [`Color`] with an alpha channel from 0-255, like
+    /// the other channels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use agape_core::{Color,Rgba};
+    ///
+    /// static OVERLAY: Color<Rgba> = Color::rgba8(0,0,0,128);
+    ///
+    /// assert_eq!(OVERLAY.a(),50);
+    /// ```
+    pub const fn rgba8(r: u8, g: u8, b: u8, a: u8) -> Color<Rgba> {
+        // Round to the nearest percent
+        let a = (a as u16 * 100 + 127) / 255;
+        Color::rgba(r, g, b, a as u8)
+    }
+
+    /// Create a new rgba [`Color`] from channels between `0.0` and `1.0`,
+    /// values outside of that range are clipped and `NaN` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use agape_core::{Color,Rgba};
+    ///
+    /// const ACCENT: Color<Rgba> = Color::rgba_f32(1.0,0.5,0.0,0.75);
+    ///
+    /// assert_eq!(ACCENT.inner(),(255,128,0,75));
+    /// ```
+    pub const fn rgba_f32(r: f32, g: f32, b: f32, a: f32) -> Color<Rgba> {
+        // Float to int casts saturate, which does the clipping
+        Color::rgba(
+            (r * 255.0 + 0.5) as u8,
+            (g * 255.0 + 0.5) as u8,
+            (b * 255.0 + 0.5) as u8,
+            (a * 100.0 + 0.5) as u8,
+        )
+    }
+
     /// Get the red component of the [`Color`]
-    pub fn r(&self) -> u8 {
+    pub const fn r(&self) -> u8 {
         self.0.r
     }
 
     /// Get the green component of the [`Color`]
-    pub fn g(&self) -> u8 {
+    pub const fn g(&self) -> u8 {
         self.0.g
     }
 
     /// Get the blue component of the [`Color`]
-    pub fn b(&self) -> u8 {
+    pub const fn b(&self) -> u8 {
         self.0.b
     }
 
     /// Get the alpha component of the [`Color`]
-    pub fn a(&self) -> u8 {
+    pub const fn a(&self) -> u8 {
         self.0.a
     }
 
     /// Get all the inner color components
-    pub fn inner(&self) -> (u8, u8, u8, u8) {
+    pub const fn inner(&self) -> (u8, u8, u8, u8) {
         (self.r(), self.g(), self.b(), self.a())
     }
 
+    /// Get the relative luminance of the [`Color`], from `0.0` for black to
+    /// `1.0` for white. The alpha channel is ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use agape_core::Color;
+    ///
+    /// assert_eq!(Color::BLACK.luminance(),0.0);
+    /// assert_eq!(Color::WHITE.luminance(),1.0);
+    /// ```
+    pub fn luminance(&self) -> f32 {
+        let linear = |channel: u8| {
+            let channel = channel as f32 / 255.0;
+            if channel <= 0.03928 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linear(self.r()) + 0.7152 * linear(self.g()) + 0.0722 * linear(self.b())
+    }
+
+    /// Get the contrast ratio between two colors, from `1.0` (no contrast)
+    /// to `21.0` (black on white).
+    ///
+    /// # Example
+    /// ```
+    /// use agape_core::Color;
+    ///
+    /// let ratio = Color::BLACK.contrast_ratio(&Color::WHITE);
+    /// assert_eq!(ratio.round(),21.0);
+    /// ```
+    pub fn contrast_ratio(&self, other: &Color<Rgba>) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    /// Returns `true` if the contrast between the two colors meets the
+    /// [`ContrastLevel`].
+    ///
+    /// # Example
+    /// ```
+    /// use agape_core::{Color,ContrastLevel};
+    ///
+    /// let gray = Color::rgb(150,150,150);
+    /// assert!(Color::BLACK.meets_contrast(&gray,ContrastLevel::AA));
+    /// assert!(!Color::WHITE.meets_contrast(&gray,ContrastLevel::AA));
+    /// ```
+    pub fn meets_contrast(&self, other: &Color<Rgba>, level: ContrastLevel) -> bool {
+        self.contrast_ratio(other) >= level.min_ratio()
+    }
+
+    /// Mix two colors, a `t` of `0.0` is this color and `1.0` is the `other`
+    /// color.
+    ///
+    /// # Example
+    /// ```
+    /// use agape_core::Color;
+    ///
+    /// let gray = Color::BLACK.mix(&Color::WHITE,0.5);
+    /// assert_eq!(gray,Color::rgb(128,128,128));
+    /// ```
+    pub fn mix(&self, other: &Color<Rgba>, t: f32) -> Color<Rgba> {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        Color::rgba(
+            lerp(self.r(), other.r()),
+            lerp(self.g(), other.g()),
+            lerp(self.b(), other.b()),
+            lerp(self.a(), other.a()),
+        )
+    }
+
     /// Normalize the colors and convert them from `srgb` to linear `rgb`.
     pub fn normalize(&self) -> [f32; 4] {
         // TODO test the values
@@ -144,10 +289,13 @@ impl Color<Hex> {
     /// Create a new hex [`Color`].
     ///
     /// This method is fallible, as not every string is a valid hex code. Hex codes
-    /// must start with `#` and must contain a valid hexadecimal string with 6  or 8
-    /// characters.
+    /// must start with `#` and must contain a valid hexadecimal string with 3, 4,
+    /// 6 or 8 characters. The short forms repeat each digit, so `#f80` is
+    /// `#ff8800`, and the last digits of the 4 and 8 character forms are the
+    /// alpha channel.
     ///
-    /// For an easier way to create hex colors use the `hex!` macro.
+    /// For an easier way to create hex colors use the `hex!` macro, which checks
+    /// the hex code at compile time.
     ///
     /// # Example
     ///
@@ -156,7 +304,9 @@ impl Color<Hex> {
     ///
     /// fn main() -> Result<(),ColorError>{
     ///     let color = Color::hex("#FFFFFF")?;
+    ///     let translucent = Color::hex("#f808")?;
     ///
+    ///     assert_eq!(translucent.as_str(),"#ff880088");
     ///     Ok(())
     /// }
     ///
@@ -164,36 +314,21 @@ impl Color<Hex> {
     pub fn hex(value: &str) -> Result<Color<Hex>, ColorError> {
         let hex_code = value.strip_prefix("#").ok_or(ColorError::MissingHex)?;
 
-        match hex_code.len() {
-            6 => {
-                let (red, green, blue) = (&hex_code[0..2], &hex_code[2..4], &hex_code[4..6]);
-
-                let _ = u8::from_str_radix(red, 16)?;
-                let _ = u8::from_str_radix(green, 16)?;
-                let _ = u8::from_str_radix(blue, 16)?;
-
-                let mut hex = value.to_string();
-                hex.push_str("FF");
+        if let Some(digit) = hex_code.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ColorError::InvalidDigit(digit));
+        }
 
-                Ok(Color(Hex(hex)))
-            }
-            8 => {
-                let (red, green, blue, alpha) = (
-                    &hex_code[0..2],
-                    &hex_code[2..4],
-                    &hex_code[4..6],
-                    &hex_code[6..8],
-                );
-
-                let _ = u8::from_str_radix(red, 16)?;
-                let _ = u8::from_str_radix(green, 16)?;
-                let _ = u8::from_str_radix(blue, 16)?;
-                let _ = u8::from_str_radix(alpha, 16)?;
-
-                Ok(Color(Hex(String::from(value))))
-            }
-            _ => Err(ColorError::InvalidLength(hex_code.len())),
+        let mut hex = String::from("#");
+        match hex_code.len() {
+            3 | 4 => hex.extend(hex_code.chars().flat_map(|c| [c, c])),
+            6 | 8 => hex.push_str(hex_code),
+            length => return Err(ColorError::InvalidLength(length)),
+        }
+        if hex.len() == 7 {
+            hex.push_str("ff");
         }
+
+        Ok(Color(Hex(hex)))
     }
 
     /// Convert the hex color into rgba format
@@ -230,7 +365,7 @@ impl Color<Hex> {
         let b = u8::from_str_radix(blue, 16).unwrap();
         let a = u8::from_str_radix(alpha, 16).unwrap();
 
-        Color::rgba(r, g, b, a)
+        Color::rgba8(r, g, b, a)
     }
 
     pub fn as_str(&self) -> &str {
@@ -320,6 +455,32 @@ mod test {
     fn invalid_hex_color() {
         assert_eq!(Color::hex("ffffff"), Err(ColorError::MissingHex));
         assert_eq!(Color::hex("#"), Err(ColorError::InvalidLength(0)));
-        assert_eq!(Color::hex("#fff"), Err(ColorError::InvalidLength(3)));
+        assert_eq!(Color::hex("#fffff"), Err(ColorError::InvalidLength(5)));
+        assert_eq!(Color::hex("#ggg"), Err(ColorError::InvalidDigit('g')));
+        assert_eq!(Color::hex("#aéé"), Err(ColorError::InvalidDigit('é')));
+    }
+
+    #[test]
+    fn short_hex_colors() {
+        let color = Color::hex("#f80").unwrap();
+        assert_eq!(color.as_str(), "#ff8800ff");
+        assert_eq!(color.to_rgba(), Color::rgb(255, 136, 0));
+
+        let color = Color::hex("#0008").unwrap();
+        assert_eq!(color.to_rgba(), Color::rgba(0, 0, 0, 53));
+    }
+
+    #[test]
+    fn hex_alpha() {
+        let color = Color::hex("#00000080").unwrap().to_rgba();
+        assert_eq!(color.a(), 50);
+        assert_eq!(Color::rgba8(0, 0, 0, 255).a(), 100);
+        assert_eq!(Color::rgba8(0, 0, 0, 0).a(), 0);
+    }
+
+    #[test]
+    fn rgba_f32_clipped() {
+        let color = Color::rgba_f32(-1.0, 2.0, f32::NAN, 0.5);
+        assert_eq!(color.inner(), (0, 255, 0, 50));
     }
 }
@@ -5,6 +5,11 @@ mod size;
 
 pub use {color::*, position::*, size::*};
 
+/// The debug names of ids, kept out of [`GlobalId`] so that ids stay small.
+#[cfg(debug_assertions)]
+static DEBUG_NAMES: std::sync::Mutex<std::collections::BTreeMap<GlobalId, String>> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+
 /// A global unique identifier
 #[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Debug, Ord, Hash)]
 pub struct GlobalId(u32);
@@ -14,6 +19,46 @@ impl GlobalId {
         let id = rand::random();
         Self(id)
     }
+
+    /// Give the id a name that is shown next to it in logs, errors and
+    /// layout dumps. Names are only kept in debug builds, in release builds
+    /// this does nothing.
+    ///
+    /// # Example
+    /// ```
+    /// use agape_core::GlobalId;
+    ///
+    /// let id = GlobalId::new().debug_name("save-button");
+    /// # #[cfg(debug_assertions)]
+    /// assert_eq!(id.name().as_deref(), Some("save-button"));
+    /// ```
+    #[allow(unused_variables)]
+    pub fn debug_name(self, name: impl Into<String>) -> Self {
+        #[cfg(debug_assertions)]
+        DEBUG_NAMES
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(self, name.into());
+        self
+    }
+
+    /// Get the debug name of the id, this is always `None` in release
+    /// builds.
+    pub fn name(&self) -> Option<String> {
+        #[cfg(debug_assertions)]
+        return DEBUG_NAMES
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(self)
+            .cloned();
+        #[cfg(not(debug_assertions))]
+        None
+    }
+
+    /// Get the number of the id, without its name.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
 }
 
 impl Default for GlobalId {
@@ -24,7 +69,10 @@ impl Default for GlobalId {
 
 impl std::fmt::Display for GlobalId {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self.name() {
+            Some(name) => write!(f, "{name}#{}", self.0),
+            None => write!(f, "{}", self.0),
+        }
     }
 }
 
@@ -64,4 +112,13 @@ mod test {
             assert!(ids.insert(GlobalId::new()));
         }
     }
+
+    #[test]
+    fn display_debug_name() {
+        let id = GlobalId::new();
+        assert_eq!(id.to_string(), id.as_u32().to_string());
+
+        let id = id.debug_name("save-button");
+        assert_eq!(id.to_string(), format!("save-button#{}", id.as_u32()));
+    }
 }
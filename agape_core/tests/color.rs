@@ -1,7 +1,29 @@
-use agape_core::IntoColor;
+use agape_core::{Color, ContrastLevel, IntoColor};
 
 #[test]
 fn tuple_into_color() {
     let color = (100, 100, 255, 100).into_color();
     assert_eq!(color.inner(), (100, 100, 255, 100));
 }
+
+#[test]
+fn contrast_ratio_is_symmetric() {
+    let a = Color::rgb(20, 90, 200);
+    let b = Color::rgb(240, 240, 200);
+    assert_eq!(a.contrast_ratio(&b), b.contrast_ratio(&a));
+}
+
+#[test]
+fn same_color_has_no_contrast() {
+    let color = Color::rgb(120, 30, 200);
+    assert_eq!(color.contrast_ratio(&color), 1.0);
+    assert!(!color.meets_contrast(&color, ContrastLevel::AALarge));
+}
+
+#[test]
+fn contrast_levels() {
+    // #767676 is the lightest gray that passes AA on white
+    let gray = Color::rgb(118, 118, 118);
+    assert!(gray.meets_contrast(&Color::WHITE, ContrastLevel::AA));
+    assert!(!gray.meets_contrast(&Color::WHITE, ContrastLevel::AAA));
+}
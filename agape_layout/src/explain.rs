@@ -0,0 +1,172 @@
+use crate::{BoxSizing, Layout};
+use agape_core::GlobalId;
+use std::fmt::Write;
+
+impl dyn Layout {
+    /// Explain how a solved [`Layout`] got its size and position, starting
+    /// from the root and going down to the layout with the `id`. Returns
+    /// `None` if the layout isn't in the tree.
+    ///
+    /// # Example
+    /// ```
+    /// use agape_layout::{BlockLayout, EmptyLayout, IntrinsicSize, Layout, LayoutSolver, Size};
+    ///
+    /// let mut child = EmptyLayout::new();
+    /// child.intrinsic_size = IntrinsicSize::fixed(50.0, 20.0);
+    /// let id = child.id;
+    /// let mut root: Box<dyn Layout> = Box::new(BlockLayout::new(Box::new(child)));
+    /// LayoutSolver::solve(&mut *root, Size::unit(200.0));
+    ///
+    /// let explanation = root.explain(id).unwrap();
+    /// assert!(explanation.contains("width 50: fixed"));
+    /// ```
+    pub fn explain(&self, id: GlobalId) -> Option<String> {
+        let mut path = vec![];
+        if !find_path(self, id, &mut path) {
+            return None;
+        }
+
+        let mut output = String::new();
+        for (depth, layout) in path.iter().enumerate() {
+            let parent = depth.checked_sub(1).map(|i| path[i]);
+            explain_node(*layout, parent, depth, &mut output);
+        }
+        Some(output)
+    }
+}
+
+/// Collect the layouts from `layout` down to the layout with the `id`.
+fn find_path<'a>(layout: &'a dyn Layout, id: GlobalId, path: &mut Vec<&'a dyn Layout>) -> bool {
+    path.push(layout);
+    if layout.id() == id {
+        return true;
+    }
+    for child in layout.children() {
+        if find_path(child.as_ref(), id, path) {
+            return true;
+        }
+    }
+    path.pop();
+    false
+}
+
+fn explain_node(
+    layout: &dyn Layout,
+    parent: Option<&dyn Layout>,
+    depth: usize,
+    output: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+    let constraints = layout.constraints();
+    let intrinsic_size = layout.intrinsic_size();
+    let (size, position) = (layout.size(), layout.position());
+    let given_by = match parent {
+        Some(parent) => format!("its parent {}", parent.name()),
+        None => "the window".to_owned(),
+    };
+
+    // Writing to a string never fails
+    let _ = writeln!(output, "{indent}{} {}", layout.name(), layout.id());
+    let _ = writeln!(
+        output,
+        "{indent}  constraints: width {}..{}, height {}..{}, from {given_by}",
+        constraints.min_width,
+        constraints.max_width,
+        constraints.min_height,
+        constraints.max_height
+    );
+    let _ = writeln!(
+        output,
+        "{indent}  {}",
+        explain_axis(
+            "width",
+            size.width,
+            intrinsic_size.width,
+            constraints.min_width,
+            constraints.max_width,
+            &given_by
+        )
+    );
+    let _ = writeln!(
+        output,
+        "{indent}  {}",
+        explain_axis(
+            "height",
+            size.height,
+            intrinsic_size.height,
+            constraints.min_height,
+            constraints.max_height,
+            &given_by
+        )
+    );
+    let _ = writeln!(
+        output,
+        "{indent}  position: ({}, {})",
+        position.x, position.y
+    );
+}
+
+/// Explain how the size along one axis was picked.
+fn explain_axis(
+    axis: &str,
+    size: f32,
+    sizing: BoxSizing,
+    min: f32,
+    max: f32,
+    given_by: &str,
+) -> String {
+    let reason = match sizing {
+        BoxSizing::Fixed(_) if size > max => {
+            format!("fixed, which overflows the max {axis} of {max}")
+        }
+        BoxSizing::Fixed(_) => "fixed".to_owned(),
+        BoxSizing::Shrink if min > max => {
+            format!("shrinks to fit its content, which overflows the max {axis} of {max}")
+        }
+        BoxSizing::Shrink => "shrinks to fit its content and padding".to_owned(),
+        BoxSizing::Flex(factor) if max <= min => {
+            format!("flex {factor}, but there's no space left over so it's clamped to its content")
+        }
+        BoxSizing::Flex(factor) => {
+            format!("flex {factor}, fills its share of the space left over by {given_by}")
+        }
+    };
+    format!("{axis} {size}: {reason}")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BoxSizing, EmptyLayout, HorizontalLayout, IntrinsicSize, Layout, LayoutSolver};
+    use agape_core::{GlobalId, Size};
+
+    #[test]
+    fn explain_flex_child() {
+        let fixed = EmptyLayout {
+            intrinsic_size: IntrinsicSize::fixed(100.0, 20.0),
+            ..Default::default()
+        };
+        let flex = EmptyLayout {
+            intrinsic_size: IntrinsicSize {
+                width: BoxSizing::Flex(1),
+                height: BoxSizing::Shrink,
+            },
+            ..Default::default()
+        };
+        let id = flex.id;
+        let mut root = HorizontalLayout::new();
+        root.intrinsic_size.width = BoxSizing::Flex(1);
+        root.add_child(fixed);
+        root.add_child(flex);
+        let mut root: Box<dyn Layout> = Box::new(root);
+        LayoutSolver::solve(&mut *root, Size::new(300.0, 100.0));
+
+        let explanation = root.explain(id).unwrap();
+        let lines: Vec<&str> = explanation.lines().collect();
+        assert!(lines[0].starts_with("HorizontalLayout"));
+        assert!(lines[1].ends_with("from the window"));
+        assert!(lines[5].starts_with("  EmptyLayout"));
+        assert!(explanation.contains("width 200: flex 1, fills its share"));
+        assert!(explanation.contains("height 0: shrinks"));
+        assert!(root.explain(GlobalId::new()).is_none());
+    }
+}
@@ -5,7 +5,9 @@
 mod block;
 mod empty;
 mod error;
+mod explain;
 mod horizontal;
+mod text;
 mod vertical;
 use agape_core::{Bounds, GlobalId};
 pub use agape_core::{Position, Size};
@@ -14,6 +16,7 @@ pub use empty::EmptyLayout;
 pub use error::LayoutError;
 pub use horizontal::HorizontalLayout;
 use std::fmt::Debug;
+pub use text::{MeasureFn, TextLayout};
 pub use vertical::VerticalLayout;
 
 pub struct LayoutSolver;
@@ -29,6 +32,14 @@ impl LayoutSolver {
         let _ = root.solve_min_constraints();
         root.solve_max_contraints(window_size);
         root.update_size();
+
+        // Layouts whose height depends on their width, like wrapped text,
+        // only know their size now, so solve again with it
+        if root.iter().any(|layout| layout.needs_relayout()) {
+            let _ = root.solve_min_constraints();
+            root.solve_max_contraints(window_size);
+            root.update_size();
+        }
         root.position_children();
 
         // TODO add a push error function that checks for equality so that we don't have duplicate errors
@@ -56,6 +67,12 @@ pub trait Layout: Debug + Send + Sync {
     /// Get the `id` of the [`Layout`]
     fn id(&self) -> GlobalId;
 
+    /// Get the name of the layout's type, used in explanations.
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
     /// Get the [`BoxConstraints`] of the [`Layout`]
     fn constraints(&self) -> BoxConstraints;
 
@@ -85,6 +102,12 @@ pub trait Layout: Debug + Send + Sync {
 
     fn iter(&self) -> LayoutIter;
 
+    /// Returns `true` if the size of the [`Layout`] changed after its width
+    /// was solved, so the tree has to be solved again.
+    fn needs_relayout(&self) -> bool {
+        false
+    }
+
     /// Get a [`Layout`] by it's `id`.
     fn get(&self, id: GlobalId) -> Option<&dyn Layout> {
         self.iter().find(|&layout| layout.id() == id)
@@ -0,0 +1,188 @@
+use crate::{BoxConstraints, BoxSizing, IntrinsicSize, Layout, LayoutIter};
+use agape_core::{GlobalId, Position, Size};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Measures text, wrapped to a maximum width or on a single line if the
+/// width is `None`.
+pub type MeasureFn = Arc<dyn Fn(Option<f32>) -> Size + Send + Sync>;
+
+/// A [`Layout`] for text that wraps, its height depends on the width it's
+/// given.
+///
+/// The text takes up as much of the available width as it needs, up to its
+/// width on a single line. Since the width is only known after the parent's
+/// constraints are solved, the wrapped height is fed back by solving the
+/// tree again.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use agape_layout::{LayoutSolver, Layout, Size, TextLayout};
+///
+/// // Each character is 10 pixels wide, and lines are 20 pixels tall
+/// let mut layout = TextLayout::new(Arc::new(|width: Option<f32>| {
+///     let lines = width.map(|width| (100.0 / width).ceil()).unwrap_or(1.0);
+///     Size::new(width.unwrap_or(100.0).min(100.0), lines * 20.0)
+/// }));
+///
+/// LayoutSolver::solve(&mut layout, Size::new(50.0, 500.0));
+/// assert_eq!(layout.size(), Size::new(50.0, 40.0));
+/// ```
+#[derive(Clone)]
+pub struct TextLayout {
+    pub id: GlobalId,
+    pub size: Size,
+    pub position: Position,
+    pub constraints: BoxConstraints,
+    measure: MeasureFn,
+    /// The width the text was last wrapped to.
+    wrap_width: Option<f32>,
+}
+
+impl Debug for TextLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextLayout")
+            .field("id", &self.id)
+            .field("size", &self.size)
+            .field("position", &self.position)
+            .field("constraints", &self.constraints)
+            .field("wrap_width", &self.wrap_width)
+            .finish()
+    }
+}
+
+impl TextLayout {
+    pub fn new(measure: MeasureFn) -> Self {
+        Self {
+            id: GlobalId::new(),
+            size: Size::default(),
+            position: Position::default(),
+            constraints: BoxConstraints::default(),
+            measure,
+            wrap_width: None,
+        }
+    }
+}
+
+impl Layout for TextLayout {
+    fn size(&self) -> Size {
+        self.size
+    }
+
+    fn id(&self) -> GlobalId {
+        self.id
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn set_x(&mut self, x: f32) {
+        self.position.x = x;
+    }
+
+    fn set_y(&mut self, y: f32) {
+        self.position.y = y;
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn children(&self) -> &[Box<dyn Layout>] {
+        &[]
+    }
+
+    fn constraints(&self) -> BoxConstraints {
+        self.constraints
+    }
+
+    fn intrinsic_size(&self) -> IntrinsicSize {
+        IntrinsicSize {
+            width: BoxSizing::Flex(1),
+            height: BoxSizing::Shrink,
+        }
+    }
+
+    fn set_max_height(&mut self, height: f32) {
+        self.constraints.max_height = height;
+    }
+
+    fn set_max_width(&mut self, width: f32) {
+        self.constraints.max_width = width;
+    }
+
+    fn set_min_height(&mut self, height: f32) {
+        self.constraints.min_height = height;
+    }
+
+    fn set_min_width(&mut self, width: f32) {
+        self.constraints.min_width = width;
+    }
+
+    fn collect_errors(&mut self) -> Vec<crate::LayoutError> {
+        vec![]
+    }
+
+    fn iter(&self) -> LayoutIter {
+        LayoutIter { stack: vec![self] }
+    }
+
+    fn solve_min_constraints(&mut self) -> (f32, f32) {
+        // Parents that shrink give the text its unwrapped width
+        self.constraints.min_width = (self.measure)(None).width;
+        self.constraints.min_height = (self.measure)(self.wrap_width).height;
+        (self.constraints.min_width, self.constraints.min_height)
+    }
+
+    fn solve_max_contraints(&mut self, _space: Size) { /* No children to solve for */
+    }
+
+    fn update_size(&mut self) {
+        let width = self.constraints.max_width.min(self.constraints.min_width);
+        self.size = (self.measure)(Some(width));
+        self.wrap_width = Some(width);
+    }
+
+    fn needs_relayout(&self) -> bool {
+        self.size.height != self.constraints.min_height
+    }
+
+    fn position_children(&mut self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{LayoutSolver, VerticalLayout};
+
+    /// Text that is 100 pixels wide on one line.
+    fn text() -> TextLayout {
+        TextLayout::new(Arc::new(|width: Option<f32>| {
+            let lines = width.map(|width| (100.0 / width).ceil()).unwrap_or(1.0);
+            Size::new(width.unwrap_or(100.0).min(100.0), lines * 20.0)
+        }))
+    }
+
+    #[test]
+    fn wrapped_height_is_fed_back() {
+        let mut root = VerticalLayout::new();
+        root.intrinsic_size.width = BoxSizing::Flex(1);
+        root.add_children([text(), text()]);
+
+        LayoutSolver::solve(&mut root, Size::new(25.0, 500.0));
+
+        assert_eq!(root.children[0].size(), Size::new(25.0, 80.0));
+        assert_eq!(root.children[1].position().y, 80.0);
+        assert_eq!(root.size().height, 160.0);
+    }
+
+    #[test]
+    fn no_wrap_in_shrinking_parent() {
+        let mut root = VerticalLayout::new();
+        root.add_child(text());
+        LayoutSolver::solve(&mut root, Size::new(500.0, 500.0));
+        assert_eq!(root.children[0].size(), Size::new(100.0, 20.0));
+    }
+}
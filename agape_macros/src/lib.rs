@@ -2,21 +2,26 @@ use proc_macro::TokenStream;
 use proc_macro2::{Literal, Span};
 use quote::quote;
 use std::{fs, path::Path};
+use syn::LitStr;
 
 /// A macro for creating compile time verified hex colors.
+///
+/// The hex code is parsed when compiling, like [`Color::hex`], and expands to
+/// a constant rgba color, so it can be used in `const` and `static` items.
+///
+/// [`Color::hex`]: agape_core::Color::hex
 #[proc_macro]
 pub fn hex(item: TokenStream) -> TokenStream {
-    let s = item.to_string().replace("\"", "");
+    let literal = syn::parse_macro_input!(item as LitStr);
 
-    match agape_core::Color::hex(&s) {
-        Ok(_) => quote! {agape::Color::hex(#s).unwrap()}.into(),
-        Err(err) => {
-            let message = format!("{err}");
-            quote! {
-                compile_error!(#message)
-            }
-            .into()
+    match agape_core::Color::hex(&literal.value()) {
+        Ok(color) => {
+            let (r, g, b, a) = color.to_rgba().inner();
+            quote! {agape::Color::rgba(#r, #g, #b, #a)}.into()
         }
+        Err(err) => syn::Error::new(literal.span(), err)
+            .to_compile_error()
+            .into(),
     }
 }
 
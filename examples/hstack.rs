@@ -1,7 +1,4 @@
-use agape::{
-    App, Color, hstack,
-    widgets::{Rect, Text},
-};
+use agape::prelude::*;
 
 fn main() {
     let hstack = hstack! {
@@ -1,9 +1,13 @@
-use agape::{App, hex, hstack, widgets::*};
+use agape::hex;
+use agape::prelude::*;
 
 fn main() {
     let widget = hstack! {
         Text::new("Email"),
-        TextField::new().background_color(hex!("#ffabbc"))
+        TextField::new()
+            .placeholder("you@example.com")
+            .clearable()
+            .background_color(hex!("#ffabbc"))
     }
     .fill()
     .spacing(12)
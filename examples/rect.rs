@@ -1,4 +1,4 @@
-use agape::{App, Color, widgets::Rect};
+use agape::prelude::*;
 
 fn main() -> Result<(), agape::Error> {
     let rect = Rect::new(200.0, 200.0).background_color(Color::BLUE);
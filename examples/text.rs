@@ -1,5 +1,4 @@
-use agape::App;
-use agape::widgets::Text;
+use agape::prelude::*;
 
 fn main() -> Result<(), agape::Error> {
     let text = Text::new("Hello, world!");
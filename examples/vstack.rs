@@ -1,4 +1,4 @@
-use agape::{App, Color, vstack, widgets::Rect};
+use agape::prelude::*;
 
 fn main() -> Result<(), agape::Error> {
     let vstack = vstack! {